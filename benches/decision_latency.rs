@@ -5,9 +5,36 @@ use std::sync::Arc;
 
 use riskr::domain::event::{Asset, Chain, Direction, EventId, TxEvent, SCHEMA_VERSION};
 use riskr::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
-use riskr::domain::Decision;
+use riskr::domain::{Decision, Policy};
+use riskr::loadtest::{self, RuleMix};
 use riskr::rules::inline::{JurisdictionRule, KycCapRule, OfacRule};
-use riskr::rules::InlineRule;
+use riskr::rules::{DailyVolumeRule, InlineRule, RuleSet, StreamingRule, StructuringRule};
+use riskr::storage::{DecisionRecord, MockStorage, Storage, StorageWrite, TransactionRecord};
+
+/// Policy with inline and streaming rules, for the full pipeline.
+const PIPELINE_POLICY: &str = r#"
+policy_version: "bench"
+params:
+  kyc_tier_caps_usd: { L0: 1000, L1: 10000, L2: 100000 }
+  daily_volume_limit_usd: 50000
+  structuring_small_usd: 1000
+  structuring_small_count: 5
+  weekly_volume_limit_usd: 200000
+rules:
+  - { id: R1_OFAC, type: ofac_addr, action: REJECT_FATAL }
+  - { id: R2_JURISDICTION, type: jurisdiction_block, action: REJECT_FATAL, blocked_countries: [IR, KP] }
+  - { id: R3_KYC_CAP, type: kyc_tier_tx_cap, action: HOLD_AUTO }
+  - { id: R4_DAILY_USD, type: daily_usd_volume, action: HOLD_AUTO }
+  - { id: R5_STRUCTURING, type: structuring_small_tx, action: REVIEW }
+  - { id: R6_WEEKLY_USD, type: weekly_usd_volume, action: REVIEW }
+"#;
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
 
 fn create_test_event(user_id: &str, usd_value: Decimal) -> TxEvent {
     let now = chrono::Utc::now();
@@ -26,11 +53,19 @@ fn create_test_event(user_id: &str, usd_value: Decimal) -> TxEvent {
         chain: Chain::inline(),
         tx_hash: "0xabc123".to_string(),
         direction: Direction::Outbound,
+        tx_type: String::new(),
         asset: Asset::new("USDC"),
         amount: "1000000".to_string(),
         usd_value,
+        usd_rate: None,
         confirmations: 6,
         max_finality_depth: 12,
+        counterparty_geo: None,
+        destination: None,
+        features: Vec::new(),
+        subject_is_new: None,
+        kyc_tier_missing: false,
+        client_ip: None,
     }
 }
 
@@ -72,9 +107,9 @@ fn bench_jurisdiction_rule(c: &mut Criterion) {
 
 fn bench_kyc_cap_rule(c: &mut Criterion) {
     let mut caps = std::collections::HashMap::new();
-    caps.insert("L0".to_string(), Decimal::new(100, 0));
-    caps.insert("L1".to_string(), Decimal::new(1000, 0));
-    caps.insert("L2".to_string(), Decimal::new(10000, 0));
+    caps.insert(KycTier::L0, Decimal::new(100, 0));
+    caps.insert(KycTier::L1, Decimal::new(1000, 0));
+    caps.insert(KycTier::L2, Decimal::new(10000, 0));
 
     let rule = KycCapRule::new("R3_KYC".to_string(), Decision::HoldAuto, caps);
 
@@ -94,7 +129,7 @@ fn bench_full_inline_pipeline(c: &mut Criterion) {
     blocked_countries.insert("IR".to_string());
 
     let mut caps = std::collections::HashMap::new();
-    caps.insert("L2".to_string(), Decimal::new(10000, 0));
+    caps.insert(KycTier::L2, Decimal::new(10000, 0));
 
     let rules: Vec<Arc<dyn InlineRule>> = vec![
         Arc::new(OfacRule::new(
@@ -130,12 +165,104 @@ fn bench_full_inline_pipeline(c: &mut Criterion) {
     });
 }
 
+fn bench_streaming_rules(c: &mut Criterion) {
+    let rt = runtime();
+    let storage = MockStorage::new();
+    let event = create_test_event("user1", Decimal::new(500, 0));
+    let subject_id = storage.add_subject(event.subject.clone());
+    storage.set_rolling_volume(subject_id, Decimal::new(20000, 0));
+    storage.set_small_tx_count(subject_id, 3);
+
+    let daily = DailyVolumeRule::new(
+        "R4_DAILY_USD".to_string(),
+        Decision::HoldAuto,
+        Decimal::new(50000, 0),
+    );
+    let structuring = StructuringRule::new(
+        "R5_STRUCTURING".to_string(),
+        Decision::Review,
+        Decimal::new(1000, 0),
+        5,
+    );
+
+    c.bench_function("daily_volume_rule_evaluate", |b| {
+        b.to_async(&rt)
+            .iter(|| daily.evaluate(black_box(&event), subject_id, &storage))
+    });
+    c.bench_function("structuring_rule_evaluate", |b| {
+        b.to_async(&rt)
+            .iter(|| structuring.evaluate(black_box(&event), subject_id, &storage))
+    });
+}
+
+fn bench_record_outcome(c: &mut Criterion) {
+    let rt = runtime();
+    let storage = MockStorage::new();
+    let event = create_test_event("user1", Decimal::new(500, 0));
+    let subject_id = storage.add_subject(event.subject.clone());
+
+    let tx = TransactionRecord {
+        subject_id,
+        tx_type: "Outbound".to_string(),
+        asset: "USDC".to_string(),
+        amount: Decimal::new(500, 0),
+        usd_value: Decimal::new(500, 0),
+        dest_address: None,
+        counterparty_geo: None,
+        account_id: None,
+    };
+    let decision = DecisionRecord {
+        subject_id: Some(subject_id),
+        request_id: None,
+        request: serde_json::Value::Null,
+        decision: Decision::Allow,
+        decision_code: "OK".to_string(),
+        policy_version: "bench".to_string(),
+        evidence: Vec::new(),
+        latency_ms: 0,
+    };
+
+    c.bench_function("record_outcome", |b| {
+        b.to_async(&rt)
+            .iter(|| storage.record_outcome(black_box(&tx), black_box(&decision)))
+    });
+}
+
+fn bench_decision_pipeline(c: &mut Criterion) {
+    let rt = runtime();
+    let policy: Policy = serde_yaml::from_str(PIPELINE_POLICY).unwrap();
+    let ruleset = RuleSet::from_policy(&policy, HashSet::from(["0xdead".to_string()]));
+    let storage: Arc<dyn Storage> = Arc::new(MockStorage::new());
+
+    // Spread over many users so windows stay small, as in production
+    let events: Vec<TxEvent> = (0..1000)
+        .map(|i| create_test_event(&format!("user{i}"), Decimal::new(500, 0)))
+        .collect();
+
+    for (name, mix) in [
+        ("decision_pipeline_inline", RuleMix::Inline),
+        ("decision_pipeline_streaming", RuleMix::Streaming),
+        ("decision_pipeline_all", RuleMix::All),
+    ] {
+        let mut i = 0;
+        c.bench_function(name, |b| {
+            b.to_async(&rt).iter(|| {
+                i = (i + 1) % events.len();
+                loadtest::decide(&ruleset, &storage, black_box(&events[i]), mix)
+            })
+        });
+    }
+}
+
 criterion_group!(
     benches,
     bench_ofac_rule,
     bench_jurisdiction_rule,
     bench_kyc_cap_rule,
     bench_full_inline_pipeline,
+    bench_streaming_rules,
+    bench_record_outcome,
+    bench_decision_pipeline,
 );
 
 criterion_main!(benches);
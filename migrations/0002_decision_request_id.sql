@@ -0,0 +1,5 @@
+-- migrations/0002_decision_request_id.sql
+
+-- Correlation ID of the HTTP request that produced the decision
+ALTER TABLE decisions ADD COLUMN request_id TEXT;
+CREATE INDEX idx_decisions_request_id ON decisions(request_id);
@@ -0,0 +1,5 @@
+-- migrations/0003_subject_kyc_verified.sql
+
+-- Set when the KYC tier was updated out-of-band; request-supplied tiers
+-- no longer overwrite it
+ALTER TABLE subjects ADD COLUMN kyc_verified_at TIMESTAMPTZ;
@@ -0,0 +1,14 @@
+-- migrations/0004_outbox.sql
+
+-- Side effects of decisions, written in the same transaction as the
+-- decision and delivered by the outbox relay
+CREATE TABLE outbox (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    delivered_at TIMESTAMPTZ
+);
+CREATE INDEX idx_outbox_pending ON outbox(created_at) WHERE delivered_at IS NULL;
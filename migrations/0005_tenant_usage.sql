@@ -0,0 +1,18 @@
+-- migrations/0005_tenant_usage.sql
+
+-- Decision volume per tenant and UTC day, for quotas and billing
+CREATE TABLE tenant_usage (
+    tenant TEXT NOT NULL,
+    day DATE NOT NULL,
+    decisions BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (tenant, day)
+);
+
+-- Rule triggers per tenant and UTC day
+CREATE TABLE tenant_rule_hits (
+    tenant TEXT NOT NULL,
+    day DATE NOT NULL,
+    rule_id TEXT NOT NULL,
+    hits BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (tenant, day, rule_id)
+);
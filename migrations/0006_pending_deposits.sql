@@ -0,0 +1,10 @@
+-- migrations/0006_pending_deposits.sql
+
+-- Deposits held until they have enough confirmations for finality
+CREATE TABLE pending_deposits (
+    event_id TEXT PRIMARY KEY,
+    subject_id UUID NOT NULL REFERENCES subjects(id),
+    deposit JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
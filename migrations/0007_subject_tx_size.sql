@@ -0,0 +1,6 @@
+-- migrations/0007_subject_tx_size.sql
+
+-- Typical transaction size per subject (log-space EWMA of USD values),
+-- used by adaptive structuring detection
+ALTER TABLE subjects ADD COLUMN typical_tx_usd NUMERIC;
+ALTER TABLE subjects ADD COLUMN tx_size_samples INTEGER NOT NULL DEFAULT 0;
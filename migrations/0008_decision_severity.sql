@@ -0,0 +1,19 @@
+-- migrations/0008_decision_severity.sql
+
+-- Store decisions as their severity rank instead of Rust variant names:
+-- 0 = ALLOW, 1 = SOFT_DENY_RETRY, 2 = HOLD_AUTO, 3 = REVIEW, 4 = REJECT_FATAL.
+-- Ranks compare by severity and index compactly. Unknown names fail the
+-- migration rather than being dropped.
+ALTER TABLE decisions
+    ALTER COLUMN decision TYPE SMALLINT USING CASE decision
+        WHEN 'Allow' THEN 0
+        WHEN 'SoftDenyRetry' THEN 1
+        WHEN 'HoldAuto' THEN 2
+        WHEN 'Review' THEN 3
+        WHEN 'RejectFatal' THEN 4
+    END,
+    ADD CONSTRAINT decisions_decision_severity CHECK (decision BETWEEN 0 AND 4);
+
+-- Severity filters over time, e.g. holds and reviews in the last day.
+-- (subject_id, created_at) is covered by idx_decisions_subject_time.
+CREATE INDEX idx_decisions_decision_time ON decisions(decision, created_at DESC);
@@ -0,0 +1,20 @@
+-- migrations/0009_rule_switches.sql
+
+-- Rules currently disabled through the admin kill-switch
+CREATE TABLE rule_switches (
+    rule_id TEXT PRIMARY KEY,
+    disabled_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    actor TEXT NOT NULL,
+    reason TEXT
+);
+
+-- Every disable and enable, for audit
+CREATE TABLE rule_switch_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    rule_id TEXT NOT NULL,
+    disabled BOOLEAN NOT NULL,
+    actor TEXT NOT NULL,
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_rule_switch_log_rule_time ON rule_switch_log(rule_id, created_at DESC);
@@ -0,0 +1,8 @@
+-- migrations/0010_counterparty_geo.sql
+
+-- Counterparty country of a transaction, when the caller knows it, for
+-- per-country transaction count caps
+ALTER TABLE transactions ADD COLUMN counterparty_geo TEXT;
+CREATE INDEX idx_transactions_subject_counterparty_geo
+    ON transactions(subject_id, counterparty_geo, created_at DESC)
+    WHERE counterparty_geo IS NOT NULL;
@@ -0,0 +1,21 @@
+-- migrations/0011_cases.sql
+
+-- Investigations opened by REVIEW decisions or by hand
+CREATE TABLE cases (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    subject_id UUID NOT NULL REFERENCES subjects(id),
+    status TEXT NOT NULL DEFAULT 'open' CHECK (
+        status IN ('open', 'investigating', 'closed_approved', 'closed_rejected', 'sar_filed')
+    ),
+    opened_by_decision UUID,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+-- At most one active case per subject; new decisions link to it
+CREATE UNIQUE INDEX idx_cases_subject_active ON cases(subject_id)
+    WHERE status IN ('open', 'investigating');
+CREATE INDEX idx_cases_status_updated ON cases(status, updated_at DESC);
+
+ALTER TABLE decisions ADD COLUMN case_id UUID REFERENCES cases(id);
+CREATE INDEX idx_decisions_case ON decisions(case_id, created_at) WHERE case_id IS NOT NULL;
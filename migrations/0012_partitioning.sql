@@ -0,0 +1,122 @@
+-- migrations/0012_partitioning.sql
+
+-- Monthly range partitioning of transactions and decisions by created_at.
+-- Nothing is partitioned here: riskr calls these functions when
+-- --db-partitioning is set (new installs) or from `riskr db partition`
+-- (existing installs). Partitions are named <table>_pYYYYMM with UTC month
+-- bounds, plus a <table>_default catching anything outside them.
+
+-- Create the default partition and monthly partitions from `from_month`
+-- through `months_ahead` months after the current one. Rows that landed in
+-- the default partition are moved into the new month they belong to.
+CREATE OR REPLACE FUNCTION riskr_ensure_month_partitions(
+    parent TEXT,
+    from_month DATE,
+    months_ahead INTEGER
+) RETURNS INTEGER
+LANGUAGE plpgsql AS $$
+DECLARE
+    month DATE := date_trunc('month', from_month)::date;
+    last_month DATE := (date_trunc('month', now() AT TIME ZONE 'UTC')
+        + make_interval(months => months_ahead))::date;
+    part TEXT;
+    lo TIMESTAMPTZ;
+    hi TIMESTAMPTZ;
+    created INTEGER := 0;
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtext('riskr_partitions'));
+
+    IF to_regclass(parent || '_default') IS NULL THEN
+        EXECUTE format('CREATE TABLE %I PARTITION OF %I DEFAULT', parent || '_default', parent);
+    END IF;
+
+    WHILE month <= last_month LOOP
+        part := parent || '_p' || to_char(month, 'YYYYMM');
+        IF to_regclass(part) IS NULL THEN
+            lo := month::timestamp AT TIME ZONE 'UTC';
+            hi := (month + INTERVAL '1 month')::timestamp AT TIME ZONE 'UTC';
+            EXECUTE format(
+                'CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS INCLUDING CONSTRAINTS)',
+                part, parent
+            );
+            EXECUTE format(
+                'WITH moved AS (DELETE FROM %I WHERE created_at >= %L AND created_at < %L RETURNING *)
+                 INSERT INTO %I SELECT * FROM moved',
+                parent || '_default', lo, hi, part
+            );
+            EXECUTE format(
+                'ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (%L) TO (%L)',
+                parent, part, lo, hi
+            );
+            created := created + 1;
+        END IF;
+        month := (month + INTERVAL '1 month')::date;
+    END LOOP;
+
+    RETURN created;
+END;
+$$;
+
+-- Convert a plain table to a partitioned one, copying its rows. The table
+-- is locked for the duration of the copy. Secondary indexes and foreign
+-- keys are recreated; the primary key becomes (id, created_at) since
+-- unique keys must include the partition column.
+CREATE OR REPLACE FUNCTION riskr_partition_table(parent TEXT, months_ahead INTEGER)
+RETURNS BOOLEAN
+LANGUAGE plpgsql AS $$
+DECLARE
+    legacy TEXT := parent || '_unpartitioned';
+    first_month DATE;
+    index_defs TEXT[];
+    foreign_keys TEXT[];
+    def TEXT;
+BEGIN
+    PERFORM pg_advisory_xact_lock(hashtext('riskr_partitions'));
+
+    IF EXISTS (SELECT 1 FROM pg_partitioned_table WHERE partrelid = parent::regclass) THEN
+        RETURN false;
+    END IF;
+
+    EXECUTE format('LOCK TABLE %I IN ACCESS EXCLUSIVE MODE', parent);
+
+    SELECT array_agg(indexdef) INTO index_defs
+    FROM pg_indexes
+    WHERE schemaname = current_schema() AND tablename = parent AND indexname <> parent || '_pkey';
+
+    SELECT array_agg(format('ALTER TABLE %I ADD CONSTRAINT %I %s',
+                            parent, conname, pg_get_constraintdef(oid)))
+    INTO foreign_keys
+    FROM pg_constraint
+    WHERE conrelid = parent::regclass AND contype = 'f';
+
+    EXECUTE format('ALTER TABLE %I RENAME TO %I', parent, legacy);
+    EXECUTE format('ALTER INDEX %I RENAME TO %I', parent || '_pkey', legacy || '_pkey');
+    EXECUTE format(
+        'CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS INCLUDING CONSTRAINTS,
+                          PRIMARY KEY (id, created_at))
+         PARTITION BY RANGE (created_at)',
+        parent, legacy
+    );
+
+    EXECUTE format('SELECT min(created_at) AT TIME ZONE ''UTC'' FROM %I', legacy)
+    INTO first_month;
+    PERFORM riskr_ensure_month_partitions(
+        parent,
+        COALESCE(first_month, (now() AT TIME ZONE 'UTC')::date),
+        months_ahead
+    );
+
+    EXECUTE format('INSERT INTO %I SELECT * FROM %I', parent, legacy);
+    EXECUTE format('DROP TABLE %I', legacy);
+
+    -- Index names are free again once the old table is gone
+    FOREACH def IN ARRAY COALESCE(index_defs, '{}') LOOP
+        EXECUTE def;
+    END LOOP;
+    FOREACH def IN ARRAY COALESCE(foreign_keys, '{}') LOOP
+        EXECUTE def;
+    END LOOP;
+
+    RETURN true;
+END;
+$$;
@@ -0,0 +1,9 @@
+-- migrations/0013_internal_blocklist.sql
+
+-- Addresses blocked by compliance in-house, screened alongside sanctions
+CREATE TABLE internal_blocklist (
+    address TEXT PRIMARY KEY,
+    reason TEXT,
+    actor TEXT NOT NULL,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,10 @@
+-- migrations/0014_request_nonces.sql
+
+-- Nonces of signed requests, kept until their timestamp is stale so a
+-- captured request can't be replayed against any replica
+CREATE TABLE request_nonces (
+    nonce TEXT PRIMARY KEY,
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_request_nonces_expires_at ON request_nonces(expires_at);
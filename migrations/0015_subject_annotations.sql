@@ -0,0 +1,13 @@
+-- migrations/0015_subject_annotations.sql
+
+-- Reviewer notes and risk ratings attached to subjects; append-only
+CREATE TABLE subject_annotations (
+    id UUID PRIMARY KEY,
+    subject_id UUID NOT NULL REFERENCES subjects(id),
+    note TEXT NOT NULL,
+    risk_rating TEXT CHECK (risk_rating IN ('low', 'medium', 'high')),
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    actor TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+CREATE INDEX idx_subject_annotations_subject ON subject_annotations(subject_id, created_at DESC);
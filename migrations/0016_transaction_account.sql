@@ -0,0 +1,8 @@
+-- migrations/0016_transaction_account.sql
+
+-- Account a transaction was made from, for rules whose windows aggregate
+-- per account rather than per user
+ALTER TABLE transactions ADD COLUMN account_id TEXT;
+CREATE INDEX idx_transactions_subject_account
+    ON transactions(subject_id, account_id, created_at DESC)
+    WHERE account_id IS NOT NULL;
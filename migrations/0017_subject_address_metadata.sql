@@ -0,0 +1,6 @@
+-- migrations/0017_subject_address_metadata.sql
+
+-- Whether a subject's ownership of an address is verified, and the
+-- caller's name for it; created_at is when it was first seen
+ALTER TABLE subject_addresses ADD COLUMN verified BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE subject_addresses ADD COLUMN label TEXT;
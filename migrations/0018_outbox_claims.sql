@@ -0,0 +1,10 @@
+-- migrations/0018_outbox_claims.sql
+
+-- Relays claim events with a lease so replicas deliver disjoint events,
+-- and events that keep failing are dead-lettered instead of retried forever
+ALTER TABLE outbox ADD COLUMN claimed_until TIMESTAMPTZ;
+ALTER TABLE outbox ADD COLUMN dead_lettered_at TIMESTAMPTZ;
+
+DROP INDEX idx_outbox_pending;
+CREATE INDEX idx_outbox_pending ON outbox(created_at)
+    WHERE delivered_at IS NULL AND dead_lettered_at IS NULL;
@@ -0,0 +1,540 @@
+//! API keys and per-operation scopes for admin endpoints.
+//!
+//! Keys are listed in a YAML file by SHA-256 hash, each with the actor it
+//! identifies and the scopes it grants:
+//!
+//! ```yaml
+//! - actor: alice
+//!   key_sha256: 2c26b46b68ffc68ff99b453c1d30413413422d706483bfa0f98a5e886266e7ae
+//!   scopes: [policy:write, decisions:read]
+//! ```
+//!
+//! Callers send the key as `Authorization: Bearer <key>` or `X-Api-Key`.
+//! People can instead send a JWT from the OIDC provider (see
+//! [`super::oidc`]). Each admin route needs one scope; decision checks,
+//! screening and health endpoints need none, and unlisted routes need
+//! `policy:write`. Every admin call is logged on
+//! the `riskr::audit` target with the actor and the scope it used.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::{header, HeaderMap, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Deserializer};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
+
+use super::oidc::{is_jwt, OidcVerifier};
+use super::request_id::RequestId;
+use super::response::{ErrorCode, ErrorResponse};
+use super::tenant::TenantId;
+
+/// Header carrying an API key, as an alternative to a bearer token.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Permission to perform one kind of admin operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scope {
+    /// Read policy history and rejected candidates
+    PolicyRead,
+    /// Toggle rules through the kill-switch
+    PolicyWrite,
+    /// Change the internal blocklist
+    SanctionsWrite,
+    /// Set subjects' KYC tiers and manage their addresses out of band
+    OverridesWrite,
+    /// Open and move cases, and annotate subjects
+    CasesWrite,
+    /// Read decisions, cases, subject profiles and history, and usage
+    DecisionsRead,
+    /// Re-evaluate stored decisions and report deposit confirmations
+    DecisionsWrite,
+    /// Download decisions and lists in bulk
+    ExportsRead,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::PolicyRead => "policy:read",
+            Scope::PolicyWrite => "policy:write",
+            Scope::SanctionsWrite => "sanctions:write",
+            Scope::OverridesWrite => "overrides:write",
+            Scope::CasesWrite => "cases:write",
+            Scope::DecisionsRead => "decisions:read",
+            Scope::DecisionsWrite => "decisions:write",
+            Scope::ExportsRead => "exports:read",
+        }
+    }
+
+    /// Resource part of the scope, e.g. `policy` for `policy:write`.
+    pub fn group(&self) -> &'static str {
+        let name = self.as_str();
+        name.split_once(':').map_or(name, |(group, _)| group)
+    }
+
+    /// Scope needed to call a route, or None for routes open to any caller.
+    ///
+    /// Routes not listed here need `policy:write`, so a new route is
+    /// closed until it is given its own scope.
+    pub fn required(method: &Method, route: &str) -> Option<Scope> {
+        // HEAD is answered by the GET handler
+        let method = if method == Method::HEAD {
+            "GET"
+        } else {
+            method.as_str()
+        };
+        let scope = match (method, route) {
+            ("POST", "/v1/decision/check" | "/v1/screening/addresses")
+            | (
+                "GET",
+                "/v1/decisions/:request_id"
+                | "/health"
+                | "/ready"
+                | "/metrics"
+                | "/openapi.json"
+                | "/docs",
+            ) => return None,
+            (
+                "POST",
+                "/v1/decisions/:request_id/recheck" | "/v1/events/:event_id/confirmations",
+            ) => Scope::DecisionsWrite,
+            ("POST", "/v1/subjects/:user_id/kyc") => Scope::OverridesWrite,
+            ("GET", "/v1/cases" | "/v1/cases/:case_id") => Scope::DecisionsRead,
+            ("POST", "/v1/cases" | "/v1/cases/:case_id/status") => Scope::CasesWrite,
+            (
+                "GET",
+                "/v1/subjects/:user_id"
+                | "/v1/subjects/:user_id/annotations"
+                | "/v1/subjects/:user_id/addresses",
+            ) => Scope::DecisionsRead,
+            ("POST", "/v1/subjects/:user_id/addresses")
+            | ("DELETE", "/v1/subjects/:user_id/addresses/:address") => Scope::OverridesWrite,
+            ("POST", "/v1/subjects/:user_id/annotations") => Scope::CasesWrite,
+            (
+                "GET",
+                "/v1/admin/subjects/:user_id/as-of" | "/v1/admin/usage" | "/v1/stats/decisions",
+            ) => Scope::DecisionsRead,
+            (
+                "GET",
+                "/v1/rules"
+                | "/v1/admin/policies/diff"
+                | "/v1/admin/policies/failed"
+                | "/v1/admin/sanctions/check/:address",
+            ) => Scope::PolicyRead,
+            ("POST", "/v1/admin/rules/:rule_id/disable" | "/v1/admin/rules/:rule_id/enable") => {
+                Scope::PolicyWrite
+            }
+            ("POST", "/v1/admin/import/overrides") => Scope::OverridesWrite,
+            ("POST", "/v1/admin/import/blocklist") => Scope::SanctionsWrite,
+            ("GET", route) if route.starts_with("/v1/admin/export/") => Scope::ExportsRead,
+            _ => Scope::PolicyWrite,
+        };
+        Some(scope)
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Scope {
+    type Err = AuthError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "policy:read" => Ok(Scope::PolicyRead),
+            "policy:write" => Ok(Scope::PolicyWrite),
+            "sanctions:write" => Ok(Scope::SanctionsWrite),
+            "overrides:write" => Ok(Scope::OverridesWrite),
+            "cases:write" => Ok(Scope::CasesWrite),
+            "decisions:read" => Ok(Scope::DecisionsRead),
+            "decisions:write" => Ok(Scope::DecisionsWrite),
+            "exports:read" => Ok(Scope::ExportsRead),
+            _ => Err(AuthError::UnknownScope(s.to_string())),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Scope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Errors loading API keys.
+#[derive(Debug, thiserror::Error)]
+pub enum AuthError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Parse error: {0}")]
+    Parse(#[from] serde_yaml::Error),
+
+    #[error("Unknown scope: {0}")]
+    UnknownScope(String),
+
+    #[error("Invalid key hash for {0}: expected 64 hex characters")]
+    InvalidHash(String),
+
+    #[error("Duplicate key for {0}")]
+    DuplicateKey(String),
+}
+
+/// Caller identified by an API key or OIDC token, attached to admin
+/// requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Principal {
+    pub actor: String,
+    pub scopes: Vec<Scope>,
+}
+
+impl Principal {
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyEntry {
+    actor: String,
+    key_sha256: String,
+    scopes: Vec<Scope>,
+}
+
+/// Known API keys, by SHA-256 hash.
+#[derive(Debug, Default)]
+pub struct ApiKeys {
+    by_hash: HashMap<String, Principal>,
+}
+
+impl ApiKeys {
+    /// Load keys from a YAML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AuthError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_yaml(&content)
+    }
+
+    /// Parse keys from YAML.
+    pub fn from_yaml(content: &str) -> Result<Self, AuthError> {
+        let entries: Vec<KeyEntry> = serde_yaml::from_str(content)?;
+        let mut keys = ApiKeys::default();
+        for entry in entries {
+            let hash = entry.key_sha256.to_lowercase();
+            if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(AuthError::InvalidHash(entry.actor));
+            }
+            let principal = Principal {
+                actor: entry.actor,
+                scopes: entry.scopes,
+            };
+            if let Some(existing) = keys.by_hash.insert(hash, principal) {
+                return Err(AuthError::DuplicateKey(existing.actor));
+            }
+        }
+        Ok(keys)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_hash.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_hash.is_empty()
+    }
+
+    /// Caller holding `key`, if it is known.
+    pub fn authenticate(&self, key: &str) -> Option<&Principal> {
+        self.by_hash.get(&hash_key(key))
+    }
+}
+
+/// How admin callers authenticate. Admin routes are open when neither API
+/// keys nor OIDC are configured.
+#[derive(Debug, Clone, Default)]
+pub struct AdminAuth {
+    pub api_keys: Option<Arc<ApiKeys>>,
+    pub oidc: Option<Arc<OidcVerifier>>,
+    /// Scope groups whose routes accept API keys; all when empty
+    pub api_key_groups: Vec<String>,
+    /// Scope groups whose routes accept OIDC tokens; all when empty
+    pub oidc_groups: Vec<String>,
+}
+
+impl AdminAuth {
+    pub fn is_open(&self) -> bool {
+        self.api_keys.is_none() && self.oidc.is_none()
+    }
+
+    /// Caller presenting `token` for a route needing `scope`, if the token
+    /// is valid for that route. Scopes are checked by the caller.
+    async fn authenticate(&self, token: &str, scope: Scope) -> Option<Principal> {
+        let accepts =
+            |groups: &[String]| groups.is_empty() || groups.iter().any(|g| g == scope.group());
+
+        if let Some(oidc) = self.oidc.as_ref().filter(|_| is_jwt(token)) {
+            if !accepts(&self.oidc_groups) {
+                return None;
+            }
+            return match oidc.verify(token).await {
+                Ok(principal) => Some(principal),
+                Err(e) => {
+                    debug!(error = %e, "Rejected OIDC token");
+                    None
+                }
+            };
+        }
+
+        if !accepts(&self.api_key_groups) {
+            return None;
+        }
+        self.api_keys.as_ref()?.authenticate(token).cloned()
+    }
+}
+
+/// Hex SHA-256 of a key, as listed in the keys file.
+pub fn hash_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Key presented as a bearer token or in `X-Api-Key`.
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    bearer
+        .or_else(|| headers.get(API_KEY_HEADER).and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+}
+
+/// Middleware enforcing the scope each admin route needs.
+///
+/// Without API keys or OIDC configured admin routes stay open and the
+/// tenant is recorded as the actor. The caller is attached as a
+/// [`Principal`]. Must be added with `route_layer`, after the tenant and
+/// request ID layers, so the matched route is known.
+pub async fn authorize(State(auth): State<AdminAuth>, mut req: Request, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let Some(scope) = Scope::required(req.method(), &route) else {
+        return next.run(req).await;
+    };
+
+    let principal = if auth.is_open() {
+        Principal {
+            actor: req
+                .extensions()
+                .get::<TenantId>()
+                .map(|t| t.0.clone())
+                .unwrap_or_default(),
+            scopes: vec![scope],
+        }
+    } else {
+        let token = presented_key(req.headers()).map(str::to_string);
+        let principal = match token {
+            Some(token) => auth.authenticate(&token, scope).await,
+            None => None,
+        };
+        let Some(principal) = principal else {
+            return denied(
+                StatusCode::UNAUTHORIZED,
+                "Valid credentials are required",
+                ErrorCode::Unauthorized,
+            );
+        };
+        if !principal.allows(scope) {
+            info!(
+                target: "riskr::audit",
+                actor = %principal.actor,
+                scope = %scope,
+                route = %route,
+                "Admin call denied"
+            );
+            return denied(
+                StatusCode::FORBIDDEN,
+                &format!("Requires scope {}", scope),
+                ErrorCode::Forbidden,
+            );
+        }
+        principal
+    };
+
+    let method = req.method().clone();
+    let request_id = req
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_default();
+    let actor = principal.actor.clone();
+    req.extensions_mut().insert(principal);
+
+    let response = next.run(req).await;
+    info!(
+        target: "riskr::audit",
+        actor = %actor,
+        scope = %scope,
+        method = %method,
+        route = %route,
+        request_id = %request_id,
+        status = response.status().as_u16(),
+        "Admin call"
+    );
+    response
+}
+
+fn denied(status: StatusCode, message: &str, code: ErrorCode) -> Response {
+    (status, Json(ErrorResponse::new(message, code))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> ApiKeys {
+        ApiKeys::from_yaml(&format!(
+            r#"
+- actor: alice
+  key_sha256: {}
+  scopes: [policy:write, decisions:read]
+- actor: bob
+  key_sha256: {}
+  scopes: [exports:read]
+"#,
+            hash_key("alice-key"),
+            hash_key("bob-key").to_uppercase()
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let keys = keys();
+        assert_eq!(keys.len(), 2);
+
+        let alice = keys.authenticate("alice-key").unwrap();
+        assert_eq!(alice.actor, "alice");
+        assert!(alice.allows(Scope::PolicyWrite));
+        assert!(!alice.allows(Scope::ExportsRead));
+        assert_eq!(keys.authenticate("bob-key").unwrap().actor, "bob");
+        assert!(keys.authenticate("mallory-key").is_none());
+    }
+
+    #[test]
+    fn test_invalid_keys_file() {
+        let unknown_scope = format!(
+            "- actor: a\n  key_sha256: {}\n  scopes: [root]\n",
+            hash_key("k")
+        );
+        assert!(ApiKeys::from_yaml(&unknown_scope).is_err());
+
+        let bad_hash = "- actor: a\n  key_sha256: abc\n  scopes: []\n";
+        assert!(matches!(
+            ApiKeys::from_yaml(bad_hash),
+            Err(AuthError::InvalidHash(_))
+        ));
+
+        let duplicate = format!(
+            "- actor: a\n  key_sha256: {h}\n  scopes: []\n- actor: b\n  key_sha256: {h}\n  scopes: []\n",
+            h = hash_key("k")
+        );
+        assert!(matches!(
+            ApiKeys::from_yaml(&duplicate),
+            Err(AuthError::DuplicateKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_required_scopes() {
+        let post = Method::POST;
+        let get = Method::GET;
+        assert_eq!(Scope::required(&post, "/v1/decision/check"), None);
+        assert_eq!(Scope::required(&get, "/v1/decisions/:request_id"), None);
+        assert_eq!(Scope::required(&get, "/health"), None);
+        assert_eq!(Scope::required(&Method::HEAD, "/health"), None);
+        assert_eq!(
+            Scope::required(&post, "/v1/decisions/:request_id/recheck"),
+            Some(Scope::DecisionsWrite)
+        );
+        assert_eq!(
+            Scope::required(&post, "/v1/events/:event_id/confirmations"),
+            Some(Scope::DecisionsWrite)
+        );
+        // Unlisted routes fail closed
+        assert_eq!(
+            Scope::required(&post, "/v1/not-yet-scoped"),
+            Some(Scope::PolicyWrite)
+        );
+        assert_eq!(
+            Scope::required(&Method::DELETE, "/v1/decisions/:request_id"),
+            Some(Scope::PolicyWrite)
+        );
+        assert_eq!(
+            Scope::required(&post, "/v1/admin/rules/:rule_id/disable"),
+            Some(Scope::PolicyWrite)
+        );
+        assert_eq!(
+            Scope::required(&post, "/v1/admin/import/blocklist"),
+            Some(Scope::SanctionsWrite)
+        );
+        assert_eq!(
+            Scope::required(&post, "/v1/subjects/:user_id/kyc"),
+            Some(Scope::OverridesWrite)
+        );
+        assert_eq!(
+            Scope::required(&get, "/v1/subjects/:user_id"),
+            Some(Scope::DecisionsRead)
+        );
+        assert_eq!(
+            Scope::required(&post, "/v1/subjects/:user_id/addresses"),
+            Some(Scope::OverridesWrite)
+        );
+        assert_eq!(
+            Scope::required(&Method::DELETE, "/v1/subjects/:user_id/addresses/:address"),
+            Some(Scope::OverridesWrite)
+        );
+        assert_eq!(
+            Scope::required(&post, "/v1/subjects/:user_id/annotations"),
+            Some(Scope::CasesWrite)
+        );
+        assert_eq!(
+            Scope::required(&get, "/v1/stats/decisions"),
+            Some(Scope::DecisionsRead)
+        );
+        assert_eq!(
+            Scope::required(&get, "/v1/admin/export/decisions"),
+            Some(Scope::ExportsRead)
+        );
+        assert_eq!(
+            Scope::required(&get, "/v1/admin/usage"),
+            Some(Scope::DecisionsRead)
+        );
+    }
+
+    #[test]
+    fn test_presented_key() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(presented_key(&headers), None);
+        headers.insert(API_KEY_HEADER, "k1".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("k1"));
+        headers.insert(header::AUTHORIZATION, "Bearer k2".parse().unwrap());
+        assert_eq!(presented_key(&headers), Some("k2"));
+    }
+}
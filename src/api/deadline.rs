@@ -0,0 +1,267 @@
+//! Overall time limits for requests, by kind of route.
+//!
+//! A stalled dependency such as a hung Postgres connection would otherwise
+//! hold a request open until the client gives up. Once a route's limit is
+//! reached the handler is dropped, cancelling its work, and the caller gets
+//! a 504: a `SOFT_DENY_RETRY` decision for decision routes, an error
+//! response elsewhere. A handler that has started persisting its outcome
+//! is left to finish, so a retry never repeats a half-made write. Streamed
+//! export bodies are cut off at the same limit.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::StreamExt;
+use tracing::{warn, Span};
+
+use super::response::{DecisionResponse, ErrorCode, ErrorResponse};
+use super::routes::{record_completed, AppState};
+use super::tenant::TenantId;
+use crate::domain::{Decision, Evidence};
+use crate::observability::PhaseTimings;
+
+/// Evidence rule ID of a decision cut short by its time limit.
+pub const TIMEOUT_RULE_ID: &str = "TIMEOUT";
+
+/// Kind of route, for picking its time limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteClass {
+    /// Routes that answer with a decision
+    Decision,
+    /// Bulk exports and imports
+    Export,
+    /// Everything else: admin, case and subject routes, health and docs
+    Admin,
+}
+
+impl RouteClass {
+    /// Class of a matched route.
+    pub fn of(route: &str) -> RouteClass {
+        match route {
+            "/v1/decision/check"
+            | "/v1/decisions/:request_id/recheck"
+            | "/v1/events/:event_id/confirmations" => RouteClass::Decision,
+            r if r.starts_with("/v1/admin/export/") || r.starts_with("/v1/admin/import/") => {
+                RouteClass::Export
+            }
+            _ => RouteClass::Admin,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteClass::Decision => "decision",
+            RouteClass::Export => "export",
+            RouteClass::Admin => "admin",
+        }
+    }
+}
+
+/// Time limit of each route class; None leaves the class unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteTimeouts {
+    pub decision: Option<Duration>,
+    pub export: Option<Duration>,
+    pub admin: Option<Duration>,
+}
+
+impl RouteTimeouts {
+    /// Time limit of `class`.
+    pub fn get(&self, class: RouteClass) -> Option<Duration> {
+        match class {
+            RouteClass::Decision => self.decision,
+            RouteClass::Export => self.export,
+            RouteClass::Admin => self.admin,
+        }
+    }
+}
+
+tokio::task_local! {
+    /// Set once the current request's handler starts persisting its outcome
+    static PERSISTING: Arc<AtomicBool>;
+}
+
+/// Mark the current request as persisting its outcome. Past this point the
+/// request is no longer cut off at its time limit.
+pub fn persistence_started() {
+    let _ = PERSISTING.try_with(|p| p.store(true, Ordering::Relaxed));
+}
+
+/// Middleware cutting requests off at their route's time limit.
+///
+/// The limit and route class are recorded on the request span. Must be
+/// added with `route_layer`, so the matched route is known.
+pub async fn enforce_timeout(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+    let class = RouteClass::of(&route);
+    let Some(limit) = state.route_timeouts.get(class) else {
+        return next.run(req).await;
+    };
+    let tenant = req.extensions().get::<TenantId>().cloned();
+
+    let span = Span::current();
+    span.record("route_class", class.as_str());
+    span.record("timeout_ms", limit.as_millis() as u64);
+
+    let start = Instant::now();
+    let deadline = tokio::time::Instant::now() + limit;
+    let persisting = Arc::new(AtomicBool::new(false));
+    let run = PERSISTING.scope(persisting.clone(), next.run(req));
+    tokio::pin!(run);
+
+    let response = match tokio::time::timeout_at(deadline, &mut run).await {
+        Ok(response) => response,
+        Err(_) if persisting.load(Ordering::Relaxed) => {
+            warn!(
+                route = %route,
+                route_class = class.as_str(),
+                timeout_ms = limit.as_millis() as u64,
+                "Request exceeded its time limit while persisting, letting it finish"
+            );
+            run.await
+        }
+        Err(_) => {
+            let elapsed_ms = start.elapsed().as_millis() as u64;
+            warn!(
+                route = %route,
+                route_class = class.as_str(),
+                timeout_ms = limit.as_millis() as u64,
+                "Request exceeded its time limit"
+            );
+            return timed_out(&state, tenant.as_ref(), class, limit, start, elapsed_ms).await;
+        }
+    };
+
+    if class == RouteClass::Export {
+        limit_body(response, deadline, limit)
+    } else {
+        response
+    }
+}
+
+/// Cut a streamed response body off at `deadline`. The connection is
+/// aborted rather than the body ended, so clients can't mistake a cut-off
+/// export for a complete one.
+fn limit_body(response: Response, deadline: tokio::time::Instant, limit: Duration) -> Response {
+    let (parts, body) = response.into_parts();
+    let chunks = futures::stream::unfold(Some(body.into_data_stream()), move |chunks| async move {
+        let mut chunks = chunks?;
+        match tokio::time::timeout_at(deadline, chunks.next()).await {
+            Ok(Some(chunk)) => Some((chunk, Some(chunks))),
+            Ok(None) => None,
+            Err(_) => {
+                warn!(
+                    timeout_ms = limit.as_millis() as u64,
+                    "Response body exceeded its time limit"
+                );
+                let error = io::Error::new(io::ErrorKind::TimedOut, "response time limit exceeded");
+                Some((Err(axum::Error::new(error)), None))
+            }
+        }
+    });
+    Response::from_parts(parts, Body::from_stream(chunks))
+}
+
+/// Response for a request cut off at its time limit. Decisions are
+/// counted in the metrics, SLO and tenant usage like completed ones.
+async fn timed_out(
+    state: &AppState,
+    tenant: Option<&TenantId>,
+    class: RouteClass,
+    limit: Duration,
+    start: Instant,
+    elapsed_ms: u64,
+) -> Response {
+    if class != RouteClass::Decision {
+        return (
+            StatusCode::GATEWAY_TIMEOUT,
+            Json(ErrorResponse::new(
+                format!("Request exceeded its {} ms time limit", limit.as_millis()),
+                ErrorCode::Timeout,
+            )),
+        )
+            .into_response();
+    }
+
+    let ruleset = state.ruleset_rx.borrow().clone();
+    let response = if state.monitor_only || ruleset.monitor_only {
+        DecisionResponse::monitor_only(ruleset.policy_version.clone())
+    } else {
+        let evidence = Evidence::with_limit(
+            TIMEOUT_RULE_ID,
+            "elapsed_ms",
+            elapsed_ms.to_string(),
+            limit.as_millis().to_string(),
+        );
+        DecisionResponse::new(
+            Decision::SoftDenyRetry,
+            ruleset.policy_version.clone(),
+            vec![evidence],
+        )
+        .with_actions(&ruleset)
+    };
+    let status = StatusCode::GATEWAY_TIMEOUT;
+    if let Some(tenant) = tenant {
+        record_completed(
+            state,
+            tenant,
+            start,
+            &PhaseTimings::default(),
+            status,
+            &response,
+        )
+        .await;
+    }
+    (status, Json(response)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_classes() {
+        assert_eq!(RouteClass::of("/v1/decision/check"), RouteClass::Decision);
+        assert_eq!(
+            RouteClass::of("/v1/events/:event_id/confirmations"),
+            RouteClass::Decision
+        );
+        assert_eq!(
+            RouteClass::of("/v1/admin/export/decisions"),
+            RouteClass::Export
+        );
+        assert_eq!(
+            RouteClass::of("/v1/admin/import/blocklist"),
+            RouteClass::Export
+        );
+        assert_eq!(RouteClass::of("/v1/cases"), RouteClass::Admin);
+
+        let timeouts = RouteTimeouts {
+            decision: Some(Duration::from_millis(500)),
+            ..RouteTimeouts::default()
+        };
+        assert_eq!(
+            timeouts.get(RouteClass::Decision),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(timeouts.get(RouteClass::Admin), None);
+    }
+}
@@ -0,0 +1,153 @@
+use crate::domain::subject::KycTier;
+use crate::domain::Subject;
+
+use super::request::SubjectRequest;
+
+/// Merges the stored subject into the subject sent with a decision request.
+///
+/// Missing addresses, KYC tier and country are filled from storage. The
+/// KYC tier and country are security sensitive and callers often send
+/// stale values, so each can be configured to always come from storage
+/// for known subjects.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubjectEnrichment {
+    /// Use the stored KYC tier even when the request has one
+    pub prefer_stored_kyc: bool,
+    /// Use the stored country even when the request has one
+    pub prefer_stored_geo: bool,
+}
+
+impl SubjectEnrichment {
+    /// Enrichment trusting or overriding the caller's tier and country.
+    pub fn new(trust_request_kyc: bool, trust_request_geo: bool) -> Self {
+        SubjectEnrichment {
+            prefer_stored_kyc: !trust_request_kyc,
+            prefer_stored_geo: !trust_request_geo,
+        }
+    }
+
+    /// Whether the stored subject is needed for this request.
+    pub fn needs_lookup(&self, req: &SubjectRequest) -> bool {
+        self.prefer_stored_kyc
+            || self.prefer_stored_geo
+            || req.addresses.is_empty()
+            || kyc_missing(req)
+            || req.geo_iso.is_empty()
+    }
+
+    /// Merge `stored` into `subject`, returning the fields taken from
+    /// storage.
+    pub fn apply(
+        &self,
+        req: &SubjectRequest,
+        subject: &mut Subject,
+        stored: &Subject,
+    ) -> Vec<&'static str> {
+        let mut enriched = Vec::new();
+
+        if req.addresses.is_empty() && !stored.addresses.is_empty() {
+            subject.addresses = stored.addresses.clone();
+            enriched.push("addresses");
+        }
+
+        if (self.prefer_stored_kyc || kyc_missing(req)) && subject.kyc_tier != stored.kyc_tier {
+            subject.kyc_tier = stored.kyc_tier.clone();
+            enriched.push("kyc_level");
+        }
+
+        if (self.prefer_stored_geo || req.geo_iso.is_empty())
+            && !stored.geo_iso.as_str().is_empty()
+            && subject.geo_iso != stored.geo_iso
+        {
+            subject.geo_iso = stored.geo_iso.clone();
+            enriched.push("geo_iso");
+        }
+
+        enriched
+    }
+}
+
+/// Absent or unparseable tiers count as missing.
+fn kyc_missing(req: &SubjectRequest) -> bool {
+    KycTier::from_str(&req.kyc_tier).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::subject::{AccountId, Address, CountryCode, UserId};
+    use smallvec::smallvec;
+
+    fn stored() -> Subject {
+        Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![Address::new("0xabc")],
+            geo_iso: CountryCode::new("GB"),
+            kyc_tier: KycTier::L2,
+        }
+    }
+
+    fn request(addresses: &[&str], geo_iso: &str, kyc_level: &str) -> SubjectRequest {
+        SubjectRequest {
+            user_id: "U1".to_string(),
+            account_id: "A1".to_string(),
+            addresses: addresses.iter().map(|a| a.to_string()).collect(),
+            geo_iso: geo_iso.to_string(),
+            kyc_tier: kyc_level.to_string(),
+        }
+    }
+
+    fn subject_for(req: &SubjectRequest) -> Subject {
+        Subject {
+            user_id: UserId::new(&req.user_id),
+            account_id: AccountId::new(&req.account_id),
+            addresses: req.addresses.iter().map(Address::new).collect(),
+            geo_iso: CountryCode::new(&req.geo_iso),
+            kyc_tier: KycTier::from_str(&req.kyc_tier).unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn test_fills_missing_fields() {
+        let enrichment = SubjectEnrichment::new(true, true);
+        let req = request(&[], "", "");
+        assert!(enrichment.needs_lookup(&req));
+
+        let mut subject = subject_for(&req);
+        let enriched = enrichment.apply(&req, &mut subject, &stored());
+
+        assert_eq!(enriched, vec!["addresses", "kyc_level", "geo_iso"]);
+        assert_eq!(subject.addresses[0].as_str(), "0xabc");
+        assert_eq!(subject.kyc_tier, KycTier::L2);
+        assert_eq!(subject.geo_iso.as_str(), "GB");
+    }
+
+    #[test]
+    fn test_trusted_request_fields_kept() {
+        let enrichment = SubjectEnrichment::new(true, true);
+        let req = request(&["0xdef"], "US", "L1");
+        assert!(!enrichment.needs_lookup(&req));
+
+        let mut subject = subject_for(&req);
+        assert!(enrichment.apply(&req, &mut subject, &stored()).is_empty());
+        assert_eq!(subject.kyc_tier, KycTier::L1);
+        assert_eq!(subject.geo_iso.as_str(), "US");
+    }
+
+    #[test]
+    fn test_prefers_stored_per_field() {
+        let enrichment = SubjectEnrichment::new(false, true);
+        let req = request(&["0xdef"], "US", "L1");
+        assert!(enrichment.needs_lookup(&req));
+
+        let mut subject = subject_for(&req);
+        let enriched = enrichment.apply(&req, &mut subject, &stored());
+
+        // Only the tier is overridden; the caller's addresses and country stay
+        assert_eq!(enriched, vec!["kyc_level"]);
+        assert_eq!(subject.kyc_tier, KycTier::L2);
+        assert_eq!(subject.geo_iso.as_str(), "US");
+        assert_eq!(subject.addresses[0].as_str(), "0xdef");
+    }
+}
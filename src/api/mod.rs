@@ -1,5 +1,15 @@
+pub mod auth;
+pub mod deadline;
+pub mod enrich;
+pub mod oidc;
+pub mod openapi;
 pub mod request;
+pub mod request_id;
 pub mod response;
 pub mod routes;
+pub mod server;
+pub mod signing;
+pub mod tenant;
+pub mod timed;
 
 pub use routes::create_router;
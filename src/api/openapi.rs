@@ -0,0 +1,150 @@
+//! OpenAPI description of the HTTP API, generated from the handler
+//! annotations and the request and response types.
+//!
+//! Served at `/openapi.json`, with Swagger UI at `/docs`.
+
+use axum::response::{Html, IntoResponse};
+use axum::Json;
+use utoipa::OpenApi;
+
+use super::request::{
+    ConfirmationUpdate, DecisionRequest, KycUpdateRequest, ResponseDetail, RuleSwitchRequest,
+    ScreeningRequest, SubjectRequest, TxRequest,
+};
+use super::response::{
+    AddressScreening, ConfirmationResponse, DecisionResponse, DepositStatus, ErrorResponse,
+    HealthResponse, RecheckResponse, RuleActions, RuleInfo, RuleKind, RuleOutcome,
+    RuleSwitchResponse, RuleTrace, RulesResponse, ScreeningResponse, TenantUsage, UsageResponse,
+};
+use super::routes;
+use crate::domain::event::{DecisionStage, EventId};
+use crate::domain::{Decision, DecisionEvent, Evidence, PlannedAction, RuleType, UsdRate};
+use crate::observability::stats::{LatencyPercentiles, RuleHits};
+use crate::observability::{DecisionSummary, LivenessCheck, PhaseTimings, StatsWindow};
+use crate::rules::RuleDescription;
+use crate::storage::UsageRecord;
+
+/// Swagger UI version loaded by `/docs`.
+const SWAGGER_UI_VERSION: &str = "5.17.14";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "riskr", description = "Compliance-aware risk engine for crypto transactions"),
+    paths(
+        routes::handle_decision,
+        routes::handle_get_decision,
+        routes::handle_recheck_decision,
+        routes::handle_confirmations,
+        routes::handle_screening,
+        routes::handle_kyc_update,
+        routes::handle_list_rules,
+        routes::handle_disable_rule,
+        routes::handle_enable_rule,
+        routes::handle_decision_stats,
+        routes::handle_usage,
+        routes::handle_health,
+    ),
+    components(schemas(
+        DecisionRequest,
+        SubjectRequest,
+        TxRequest,
+        UsdRate,
+        ResponseDetail,
+        DecisionResponse,
+        RecheckResponse,
+        Decision,
+        Evidence,
+        RuleActions,
+        PlannedAction,
+        RuleTrace,
+        RuleOutcome,
+        PhaseTimings,
+        DecisionStage,
+        DecisionEvent,
+        EventId,
+        ConfirmationUpdate,
+        ConfirmationResponse,
+        DepositStatus,
+        ScreeningRequest,
+        ScreeningResponse,
+        AddressScreening,
+        KycUpdateRequest,
+        RulesResponse,
+        RuleInfo,
+        RuleKind,
+        RuleDescription,
+        RuleType,
+        RuleSwitchRequest,
+        RuleSwitchResponse,
+        DecisionSummary,
+        StatsWindow,
+        RuleHits,
+        LatencyPercentiles,
+        UsageResponse,
+        TenantUsage,
+        UsageRecord,
+        HealthResponse,
+        LivenessCheck,
+        ErrorResponse,
+    )),
+    tags(
+        (name = "decisions", description = "Transaction decisions"),
+        (name = "screening", description = "Sanctions screening"),
+        (name = "subjects", description = "Subject updates"),
+        (name = "admin", description = "Operator endpoints"),
+        (name = "health", description = "Liveness"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Serve the OpenAPI document.
+pub async fn handle_openapi() -> impl IntoResponse {
+    let mut doc = ApiDoc::openapi();
+    doc.info.version = env!("CARGO_PKG_VERSION").to_string();
+    Json(doc)
+}
+
+/// Serve Swagger UI for the OpenAPI document.
+pub async fn handle_docs() -> Html<String> {
+    Html(format!(
+        r##"<!DOCTYPE html>
+<html>
+<head>
+  <title>riskr API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@{v}/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@{v}/swagger-ui-bundle.js"></script>
+  <script>SwaggerUIBundle({{ url: "/openapi.json", dom_id: "#swagger-ui" }});</script>
+</body>
+</html>
+"##,
+        v = SWAGGER_UI_VERSION
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_covers_routes_and_types() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let paths = spec["paths"].as_object().unwrap();
+        assert!(paths["/v1/decision/check"]["post"].is_object());
+        assert!(paths["/v1/decisions/{request_id}"]["get"].is_object());
+        assert!(paths["/v1/admin/rules/{rule_id}/disable"]["post"].is_object());
+
+        // Schemas follow the serde representation
+        let schemas = &spec["components"]["schemas"];
+        let evidence = &schemas["Evidence"]["properties"];
+        assert!(evidence["rule_id"].is_object());
+        assert!(evidence["details"].is_object());
+        assert!(schemas["TxRequest"]["properties"]["type"].is_object());
+        assert!(schemas["DecisionResponse"]["properties"]["evidence"].is_object());
+        let decisions = schemas["Decision"]["enum"].as_array().unwrap();
+        assert!(decisions.contains(&serde_json::json!("REJECT_FATAL")));
+    }
+}
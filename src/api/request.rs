@@ -1,13 +1,20 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
+use utoipa::{IntoParams, ToSchema};
 
-use crate::domain::event::{Asset, Chain, Direction, EventId, TxEvent, SCHEMA_VERSION};
+use crate::domain::event::{
+    Asset, Chain, Destination, Direction, EventId, TxEvent, SCHEMA_VERSION,
+};
 use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
-use chrono::Utc;
+use crate::domain::{CaseStatus, RiskRating, UsdRate};
+use crate::export::ExportFormat;
+use crate::lists::ListFormat;
+use crate::observability::StatsWindow;
+use chrono::{DateTime, NaiveDate, Utc};
 
 /// Request for a decision check.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DecisionRequest {
     /// Subject information
     pub subject: SubjectRequest,
@@ -15,25 +22,33 @@ pub struct DecisionRequest {
     /// Transaction details
     pub tx: TxRequest,
 
-    /// Additional context (optional)
+    /// Additional context (optional). An `ip` is the client's address,
+    /// screened by `ip_jurisdiction` rules
     #[serde(default)]
     pub context: serde_json::Value,
+
+    /// Experimental features to opt in to, or out of with a leading `-`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
 }
 
 /// Subject portion of the request.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubjectRequest {
     pub user_id: String,
     pub account_id: String,
     #[serde(default)]
     pub addresses: Vec<String>,
+    /// Filled from the stored subject when empty
+    #[serde(default)]
     pub geo_iso: String,
-    #[serde(rename = "kyc_level")]
+    /// Filled from the stored subject when empty
+    #[serde(rename = "kyc_level", default)]
     pub kyc_tier: String,
 }
 
 /// Transaction portion of the request.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TxRequest {
     /// Transaction type (withdraw, deposit, etc.)
     #[serde(rename = "type")]
@@ -46,12 +61,246 @@ pub struct TxRequest {
     #[serde(default)]
     pub amount: String,
 
-    /// USD value of the transaction
-    pub usd_value: f64,
+    /// USD value of the transaction, as a decimal string or a number.
+    /// Echoed back as a decimal string.
+    pub usd_value: Decimal,
+
+    /// Exchange rate `usd_value` was computed at, recorded with decisions
+    /// on USD limits; implied from `amount` when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_rate: Option<UsdRate>,
 
     /// Destination address (for withdrawals)
     #[serde(default)]
     pub dest_address: Option<String>,
+
+    /// Destination tag or memo, for assets whose accounts share an address
+    /// (XRP, XLM, EOS)
+    #[serde(default)]
+    pub dest_tag: Option<String>,
+
+    /// Caller's ID for the transfer, used to report confirmations for a
+    /// held deposit (generated if absent)
+    #[serde(default)]
+    pub event_id: Option<String>,
+
+    /// Confirmations observed so far (for deposits)
+    #[serde(default)]
+    pub confirmations: u32,
+
+    /// Confirmations the chain needs for finality (for deposits)
+    #[serde(default)]
+    pub finality_depth: u32,
+
+    /// Country of the counterparty, when known (e.g. from travel rule data)
+    #[serde(default)]
+    pub counterparty_geo_iso: Option<String>,
+}
+
+/// Maximum number of addresses accepted by a single screening request.
+pub const MAX_SCREENING_ADDRESSES: usize = 1000;
+
+/// Request to screen a batch of addresses against the sanctions list.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScreeningRequest {
+    pub addresses: Vec<String>,
+}
+
+/// Out-of-band KYC tier update for a subject.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct KycUpdateRequest {
+    #[schema(value_type = String, example = "L2")]
+    pub kyc_level: KycTier,
+}
+
+/// Confirmation count reported for a held deposit.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConfirmationUpdate {
+    pub confirmations: u32,
+}
+
+/// Default number of cases listed.
+pub const DEFAULT_CASE_LIMIT: u32 = 100;
+
+/// Maximum number of cases listed by one request.
+pub const MAX_CASE_LIMIT: u32 = 1000;
+
+/// Query parameters for listing cases.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CaseQuery {
+    #[serde(default)]
+    pub status: Option<CaseStatus>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Request to open a case by hand.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateCaseRequest {
+    pub user_id: String,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Request to move a case through its lifecycle.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CaseStatusUpdate {
+    pub status: CaseStatus,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Default number of annotations listed.
+pub const DEFAULT_ANNOTATION_LIMIT: u32 = 50;
+
+/// Maximum number of annotations listed by one request.
+pub const MAX_ANNOTATION_LIMIT: u32 = 500;
+
+/// Longest accepted annotation note, in bytes.
+pub const MAX_ANNOTATION_NOTE_BYTES: usize = 4096;
+
+/// Most tags accepted on one annotation.
+pub const MAX_ANNOTATION_TAGS: usize = 16;
+
+/// Query parameters for listing a subject's annotations.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AnnotationQuery {
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Request to annotate a subject.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnnotationRequest {
+    pub note: String,
+    #[serde(default)]
+    pub risk_rating: Option<RiskRating>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Longest accepted subject address label, in bytes.
+pub const MAX_ADDRESS_LABEL_BYTES: usize = 256;
+
+/// Request to link an address to a subject, or update one already linked.
+///
+/// Omitted fields leave the stored value unchanged; an empty label clears
+/// it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SubjectAddressRequest {
+    pub address: String,
+    #[serde(default)]
+    pub verified: Option<bool>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// Optional body of an admin kill-switch toggle.
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct RuleSwitchRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Query parameters for the usage report, as inclusive UTC days.
+#[derive(Debug, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UsageQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+/// Query parameters for the decision statistics.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StatsQuery {
+    /// `1h` or `24h`; defaults to `1h`
+    #[serde(default)]
+    #[param(value_type = Option<String>)]
+    pub window: StatsWindow,
+}
+
+/// Query parameters for the decision export, as inclusive UTC days.
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Query parameters for a bulk list export.
+#[derive(Debug, Deserialize)]
+pub struct ListExportQuery {
+    #[serde(default)]
+    pub format: ListFormat,
+}
+
+/// Query parameters for a bulk list import; the file is the request body.
+#[derive(Debug, Deserialize)]
+pub struct ListImportQuery {
+    #[serde(default)]
+    pub format: ListFormat,
+    /// Validate only
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Blocklist only: the file becomes the whole list
+    #[serde(default)]
+    pub replace: bool,
+}
+
+/// How much of a decision is returned to the caller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseDetail {
+    /// Only `decision` and `decision_code`
+    Minimal,
+    /// Decision with evidence and actions
+    #[default]
+    Standard,
+    /// Standard plus per-phase timings and a trace of every rule evaluated
+    Full,
+}
+
+/// Query parameters for the decision endpoint.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DecisionQuery {
+    /// Include per-phase timings in the response
+    #[serde(default)]
+    pub debug: bool,
+    #[serde(default)]
+    pub response_detail: ResponseDetail,
+    /// Answer at once from the inline rules and finish the decision in
+    /// the background
+    #[serde(default)]
+    pub provisional: bool,
+}
+
+/// Default number of decisions in a subject's as-of view.
+pub const DEFAULT_AS_OF_DECISIONS: u32 = 20;
+
+/// Maximum number of decisions in a subject's as-of view.
+pub const MAX_AS_OF_DECISIONS: u32 = 200;
+
+/// Query parameters for a subject's state as of an instant.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AsOfQuery {
+    pub at: DateTime<Utc>,
+    /// Policy whose window and limit to apply; defaults to the one behind
+    /// the subject's last decision before `at`
+    #[serde(default)]
+    pub policy_version: Option<String>,
+    /// Decisions to include
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Query parameters for the policy diff endpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolicyDiffQuery {
+    pub from: String,
+    pub to: String,
 }
 
 impl DecisionRequest {
@@ -60,7 +309,9 @@ impl DecisionRequest {
         let now = Utc::now();
 
         // Parse KYC tier
-        let kyc_tier = KycTier::from_str(&self.subject.kyc_tier).unwrap_or_default();
+        let parsed_tier = KycTier::from_str(&self.subject.kyc_tier);
+        let kyc_tier_missing = parsed_tier.is_none();
+        let kyc_tier = parsed_tier.unwrap_or_default();
 
         // Convert addresses
         let addresses: SmallVec<[Address; 4]> = self
@@ -79,7 +330,12 @@ impl DecisionRequest {
 
         TxEvent {
             schema_version: SCHEMA_VERSION.to_string(),
-            event_id: EventId::new(),
+            event_id: self
+                .tx
+                .event_id
+                .as_ref()
+                .map(EventId::from_string)
+                .unwrap_or_default(),
             occurred_at: now,
             observed_at: now,
             subject: Subject {
@@ -92,11 +348,33 @@ impl DecisionRequest {
             chain: Chain::inline(),
             tx_hash: String::new(),
             direction,
+            tx_type: self.tx.tx_type.to_lowercase(),
             asset: Asset::new(&self.tx.asset),
             amount: self.tx.amount.clone(),
-            usd_value: Decimal::from_f64_retain(self.tx.usd_value).unwrap_or(Decimal::ZERO),
-            confirmations: 0,
-            max_finality_depth: 0,
+            usd_value: self.tx.usd_value,
+            usd_rate: self.tx.usd_rate.clone(),
+            confirmations: self.tx.confirmations,
+            max_finality_depth: self.tx.finality_depth,
+            counterparty_geo: self
+                .tx
+                .counterparty_geo_iso
+                .as_deref()
+                .filter(|c| !c.is_empty())
+                .map(CountryCode::new),
+            destination: self
+                .tx
+                .dest_address
+                .as_deref()
+                .filter(|a| !a.is_empty())
+                .map(|a| Destination::new(a, self.tx.dest_tag.clone())),
+            features: self.features.clone(),
+            subject_is_new: None,
+            kyc_tier_missing,
+            client_ip: self
+                .context
+                .get("ip")
+                .and_then(|ip| ip.as_str())
+                .and_then(|ip| ip.trim().parse().ok()),
         }
     }
 }
@@ -128,7 +406,7 @@ mod tests {
         let req: DecisionRequest = serde_json::from_str(json).unwrap();
 
         assert_eq!(req.subject.user_id, "U123");
-        assert_eq!(req.tx.usd_value, 1000.0);
+        assert_eq!(req.tx.usd_value, Decimal::new(1000, 0));
         assert_eq!(req.subject.addresses.len(), 2);
     }
 
@@ -156,7 +434,122 @@ mod tests {
         assert_eq!(event.subject.geo_iso.as_str(), "US");
         assert_eq!(event.subject.kyc_tier, KycTier::L2);
         assert_eq!(event.direction, Direction::Outbound);
+        assert_eq!(event.usd_value, Decimal::new(500050, 2));
         // Address should be normalized to lowercase
         assert_eq!(event.subject.addresses[0].as_str(), "0xabc");
+        assert_eq!(event.destination, None);
+        assert_eq!(event.client_ip, None);
+        assert!(!event.kyc_tier_missing);
+    }
+
+    #[test]
+    fn test_to_tx_event_client_ip() {
+        let mut req: DecisionRequest = serde_json::from_value(serde_json::json!({
+            "subject": { "user_id": "U1", "account_id": "A1" },
+            "tx": { "type": "withdraw", "asset": "USDC", "usd_value": 100 },
+            "context": { "ip": "203.0.113.7" }
+        }))
+        .unwrap();
+        assert_eq!(
+            req.to_tx_event().client_ip,
+            Some("203.0.113.7".parse().unwrap())
+        );
+
+        req.context = serde_json::json!({ "ip": "not an address" });
+        assert_eq!(req.to_tx_event().client_ip, None);
+    }
+
+    #[test]
+    fn test_to_tx_event_missing_tier() {
+        let mut req: DecisionRequest = serde_json::from_value(serde_json::json!({
+            "subject": { "user_id": "U1", "account_id": "A1" },
+            "tx": { "type": "withdraw", "asset": "USDC", "usd_value": 100 }
+        }))
+        .unwrap();
+        assert!(req.to_tx_event().kyc_tier_missing);
+
+        req.subject.kyc_tier = "  ".to_string();
+        assert!(req.to_tx_event().kyc_tier_missing);
+
+        // Unknown tiers are sent, just not ranked; the subject field check
+        // handles them
+        req.subject.kyc_tier = "bogus".to_string();
+        let event = req.to_tx_event();
+        assert!(!event.kyc_tier_missing);
+        assert_eq!(event.subject.kyc_tier.as_str(), "BOGUS");
+    }
+
+    #[test]
+    fn test_to_tx_event_usd_rate() {
+        let req: DecisionRequest = serde_json::from_value(serde_json::json!({
+            "subject": { "user_id": "U1", "account_id": "A1" },
+            "tx": {
+                "type": "withdraw",
+                "asset": "BTC",
+                "amount": "0.5",
+                "usd_value": "30000",
+                "usd_rate": {
+                    "source": "coinbase",
+                    "rate": "60000",
+                    "as_of": "2025-01-15T12:00:00Z"
+                }
+            }
+        }))
+        .unwrap();
+        let rate = req.to_tx_event().usd_rate.unwrap();
+        assert_eq!(rate.source, "coinbase");
+        assert_eq!(rate.rate, Decimal::new(60000, 0));
+        assert_eq!(rate.as_of.to_rfc3339(), "2025-01-15T12:00:00+00:00");
+    }
+
+    #[test]
+    fn test_to_tx_event_destination_tag() {
+        let json = r#"{
+            "subject": {
+                "user_id": "U123",
+                "account_id": "A456",
+                "addresses": [],
+                "geo_iso": "US"
+            },
+            "tx": {
+                "type": "withdraw",
+                "asset": "XRP",
+                "usd_value": 100.0,
+                "dest_address": "rHost",
+                "dest_tag": " 12345 "
+            }
+        }"#;
+
+        let req: DecisionRequest = serde_json::from_str(json).unwrap();
+        let destination = req.to_tx_event().destination.unwrap();
+
+        assert_eq!(destination.address.as_str(), "rhost");
+        assert_eq!(destination.tag.as_deref(), Some("12345"));
+    }
+
+    #[test]
+    fn test_usd_value_decimal_string() {
+        let json = r#"{
+            "subject": {
+                "user_id": "U123",
+                "account_id": "A456",
+                "addresses": [],
+                "geo_iso": "US"
+            },
+            "tx": {
+                "type": "withdraw",
+                "asset": "USDC",
+                "usd_value": "0.30000000000000000001"
+            }
+        }"#;
+
+        let req: DecisionRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            req.to_tx_event().usd_value.to_string(),
+            "0.30000000000000000001"
+        );
+
+        let echoed = serde_json::to_value(&req).unwrap();
+        assert_eq!(echoed["tx"]["usd_value"], "0.30000000000000000001");
     }
 }
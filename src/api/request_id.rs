@@ -0,0 +1,145 @@
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{info_span, Instrument};
+use uuid::Uuid;
+
+/// Header carrying the correlation ID.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// W3C trace context header, used when no request ID is supplied.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Maximum accepted length of a caller-supplied request ID.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Correlation ID for a single request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+impl RequestId {
+    /// Generate a new random request ID.
+    pub fn generate() -> Self {
+        RequestId(Uuid::new_v4().to_string())
+    }
+
+    /// Take the request ID from `X-Request-Id`, falling back to the trace ID
+    /// of a `traceparent` header, and generating one if neither is usable.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        if let Some(id) = headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|id| is_valid_request_id(id))
+        {
+            return RequestId(id.to_string());
+        }
+
+        if let Some(trace_id) = headers
+            .get(TRACEPARENT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_trace_id)
+        {
+            return RequestId(trace_id.to_string());
+        }
+
+        RequestId::generate()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Accept only short, printable ASCII IDs so they are safe to log and echo.
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Extract the trace ID from a `traceparent` header
+/// (`version-traceid-parentid-flags`).
+fn parse_trace_id(traceparent: &str) -> Option<&str> {
+    let mut parts = traceparent.trim().split('-');
+    let _version = parts.next()?;
+    let trace_id = parts.next()?;
+
+    let valid = trace_id.len() == 32
+        && trace_id.bytes().all(|b| b.is_ascii_hexdigit())
+        && trace_id.bytes().any(|b| b != b'0');
+
+    valid.then_some(trace_id)
+}
+
+/// Middleware attaching a request ID to the request, its tracing span,
+/// and the response headers.
+pub async fn propagate_request_id(mut req: Request, next: Next) -> Response {
+    let request_id = RequestId::from_headers(req.headers());
+
+    let span = info_span!(
+        "request",
+        request_id = %request_id.as_str(),
+        method = %req.method(),
+        path = %req.uri().path(),
+        route_class = tracing::field::Empty,
+        timeout_ms = tracing::field::Empty,
+    );
+
+    req.extensions_mut().insert(request_id.clone());
+    let mut response = next.run(req).instrument(span).await;
+
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_id_header_preferred() {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("req-123"));
+        headers.insert(
+            TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+
+        assert_eq!(RequestId::from_headers(&headers).as_str(), "req-123");
+    }
+
+    #[test]
+    fn test_traceparent_fallback() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+
+        assert_eq!(
+            RequestId::from_headers(&headers).as_str(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+    }
+
+    #[test]
+    fn test_generated_when_absent_or_invalid() {
+        let mut headers = HeaderMap::new();
+        headers.insert(REQUEST_ID_HEADER, HeaderValue::from_static("has space"));
+        headers.insert(
+            TRACEPARENT_HEADER,
+            HeaderValue::from_static("00-00000000000000000000000000000000-00f067aa0ba902b7-01"),
+        );
+
+        let id = RequestId::from_headers(&headers);
+        assert!(Uuid::parse_str(id.as_str()).is_ok());
+
+        let id = RequestId::from_headers(&HeaderMap::new());
+        assert!(Uuid::parse_str(id.as_str()).is_ok());
+    }
+}
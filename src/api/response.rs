@@ -1,10 +1,56 @@
-use chrono::{DateTime, Utc};
-use serde::Serialize;
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-use crate::domain::{Decision, Evidence};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::domain::event::DecisionStage;
+use crate::domain::evidence::RuleResult;
+use crate::domain::{
+    ActionAnnotations, Annotation, Case, Decision, DecisionEvent, Evidence, PlannedAction,
+    RiskRating, Subject, SubjectAddress,
+};
+use crate::observability::{LivenessCheck, PhaseTimings};
+use crate::policy::FailedPolicy;
+use crate::rules::{MembershipTrace, RuleDescription, RuleSet, SanctionsStats, ShadowedRule};
+use crate::storage::{RuleSwitch, UsageRecord};
+
+/// Serde helpers for monetary fields.
+///
+/// Monetary values in responses and outbox payloads are [`Money`]: a
+/// decimal string with an explicit currency, never a float. Annotate
+/// `Decimal` USD fields with `#[serde(serialize_with = "usd::serialize")]`
+/// (or `usd::option::serialize`), and build JSON payloads with
+/// [`Money::usd`].
+///
+/// [`Money`]: crate::domain::Money
+/// [`Money::usd`]: crate::domain::Money::usd
+pub mod usd {
+    use rust_decimal::Decimal;
+    use serde::{Serialize, Serializer};
+
+    use crate::domain::Money;
+
+    pub fn serialize<S: Serializer>(value: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        Money::usd(*value).serialize(serializer)
+    }
+
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            value: &Option<Decimal>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            value.map(Money::usd).serialize(serializer)
+        }
+    }
+}
 
 /// Response from a decision check.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DecisionResponse {
     /// The decision outcome
     pub decision: Decision,
@@ -21,6 +67,141 @@ pub struct DecisionResponse {
     /// When this decision expires (if applicable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub expires_at: Option<DateTime<Utc>>,
+
+    /// Whether the decision is enforced (false in monitor-only mode)
+    pub enforced: bool,
+
+    /// Event ID to report confirmations against, when the transfer is held
+    /// pending finality
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<String>,
+
+    /// Action annotations of the triggered rules, in evidence order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<RuleActions>,
+
+    /// What the caller should do for this decision, from the policy's
+    /// `decision_actions`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub action_plan: Vec<PlannedAction>,
+
+    /// Time spent in each pipeline phase, when requested with `debug=true`
+    /// or `response_detail=full`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timings: Option<PhaseTimings>,
+
+    /// Outcome of every rule evaluated, in evaluation order, when requested
+    /// with `response_detail=full`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<RuleTrace>>,
+
+    /// `provisional` for an answer from the inline rules alone, which the
+    /// final decision may revoke
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stage: Option<DecisionStage>,
+
+    /// Where the final decision can be fetched once recorded, for a
+    /// provisional answer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub final_decision_url: Option<String>,
+}
+
+/// Outcome of re-checking a recorded decision against the current policy
+/// and state.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RecheckResponse {
+    /// Whether the current decision is no more severe than the prior one;
+    /// always true in monitor-only mode
+    pub still_holds: bool,
+
+    /// Seconds since the prior decision was recorded
+    pub age_secs: i64,
+
+    /// The decision as recorded
+    pub prior: DecisionEvent,
+
+    /// The decision the request gets now
+    pub current: DecisionResponse,
+}
+
+/// Decision reduced to its outcome, for `response_detail=minimal`.
+#[derive(Debug, Serialize)]
+pub struct MinimalDecisionResponse<'a> {
+    pub decision: Decision,
+    pub decision_code: &'a str,
+}
+
+impl<'a> From<&'a DecisionResponse> for MinimalDecisionResponse<'a> {
+    fn from(response: &'a DecisionResponse) -> Self {
+        MinimalDecisionResponse {
+            decision: response.decision,
+            decision_code: &response.decision_code,
+        }
+    }
+}
+
+/// How a rule ended for one decision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOutcome {
+    Hit,
+    Pass,
+    /// Switched off by the admin kill-switch
+    Disabled,
+    /// Experimental rule whose feature the request did not enable
+    Experimental,
+    /// Failed to evaluate and was skipped
+    Error,
+    /// Cancelled because another rule had already reached a fatal
+    /// decision
+    Cancelled,
+    /// Evaluated in shadow mode after breaching its SLA; did not affect
+    /// the decision
+    Shadow,
+}
+
+/// Outcome of one rule evaluated for a decision.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RuleTrace {
+    pub rule_id: String,
+    pub outcome: RuleOutcome,
+    /// Decision of a hit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decision: Option<Decision>,
+}
+
+impl RuleTrace {
+    /// Trace a rule that was evaluated.
+    pub fn evaluated(rule_id: &str, result: &RuleResult) -> Self {
+        RuleTrace {
+            rule_id: rule_id.to_string(),
+            outcome: if result.hit {
+                RuleOutcome::Hit
+            } else {
+                RuleOutcome::Pass
+            },
+            decision: result.hit.then_some(result.decision),
+        }
+    }
+
+    /// Trace a rule evaluated in shadow mode, with the decision it would
+    /// have contributed.
+    pub fn shadowed(rule_id: &str, result: &RuleResult) -> Self {
+        RuleTrace {
+            rule_id: rule_id.to_string(),
+            outcome: RuleOutcome::Shadow,
+            decision: result.hit.then_some(result.decision),
+        }
+    }
+
+    /// Trace a rule that was not evaluated.
+    pub fn skipped(rule_id: &str, outcome: RuleOutcome) -> Self {
+        RuleTrace {
+            rule_id: rule_id.to_string(),
+            outcome,
+            decision: None,
+        }
+    }
 }
 
 impl DecisionResponse {
@@ -38,6 +219,14 @@ impl DecisionResponse {
             policy_version,
             evidence,
             expires_at: None,
+            enforced: true,
+            event_id: None,
+            actions: Vec::new(),
+            action_plan: Vec::new(),
+            timings: None,
+            trace: None,
+            stage: None,
+            final_decision_url: None,
         }
     }
 
@@ -49,17 +238,276 @@ impl DecisionResponse {
             policy_version,
             evidence: Vec::new(),
             expires_at: None,
+            enforced: true,
+            event_id: None,
+            actions: Vec::new(),
+            action_plan: Vec::new(),
+            timings: None,
+            trace: None,
+            stage: None,
+            final_decision_url: None,
+        }
+    }
+
+    /// Attach the annotations of rules that produced evidence, and the
+    /// rule set's action plan for the decision.
+    pub fn with_actions(mut self, ruleset: &RuleSet) -> Self {
+        for evidence in &self.evidence {
+            let Some(rule_annotations) = ruleset.annotations.get(&evidence.rule_id) else {
+                continue;
+            };
+            if self.actions.iter().any(|a| a.rule_id == evidence.rule_id) {
+                continue;
+            }
+            self.actions.push(RuleActions {
+                rule_id: evidence.rule_id.clone(),
+                annotations: rule_annotations.clone(),
+            });
+        }
+        self.action_plan = ruleset
+            .action_plans
+            .get(&self.decision)
+            .cloned()
+            .unwrap_or_default();
+        self
+    }
+
+    /// Mark as a provisional answer whose final decision will be served at
+    /// `final_decision_url`.
+    pub fn provisional(mut self, final_decision_url: String) -> Self {
+        self.stage = Some(DecisionStage::Provisional);
+        self.final_decision_url = Some(final_decision_url);
+        self
+    }
+
+    /// Create an unenforced allow response for monitor-only mode.
+    ///
+    /// The actual decision is recorded but never returned to the caller.
+    pub fn monitor_only(policy_version: String) -> Self {
+        DecisionResponse {
+            enforced: false,
+            ..DecisionResponse::allow(policy_version)
         }
     }
 }
 
-/// Health check response.
+/// Actions requested by a triggered rule.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RuleActions {
+    pub rule_id: String,
+    pub annotations: ActionAnnotations,
+}
+
+/// State of a deposit held pending finality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositStatus {
+    /// Still short of the required confirmations
+    Pending,
+    /// Final and allowed
+    Released,
+    /// Final, but other rules require more than an allow
+    Escalated,
+}
+
+/// Response to a confirmation update.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ConfirmationResponse {
+    pub event_id: String,
+    pub status: DepositStatus,
+    pub confirmations: u32,
+    /// Decision issued when the deposit stopped being held
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decision: Option<DecisionEvent>,
+}
+
+/// Screening result for a single address.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AddressScreening {
+    pub address: String,
+    pub sanctioned: bool,
+    /// Sanctions list the address was checked against
+    pub list: String,
+    pub list_version: String,
+}
+
+/// Bulk address screening response.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ScreeningResponse {
+    pub results: Vec<AddressScreening>,
+}
+
+/// How screening resolves one address, for operators debugging a miss.
+#[derive(Debug, Serialize)]
+pub struct SanctionsCheckResponse {
+    pub address: String,
+    #[serde(flatten)]
+    pub trace: MembershipTrace,
+    /// Whether the address is on the internal blocklist
+    pub blocklisted: bool,
+    /// List the address matched, if any
+    pub matched_list: Option<String>,
+    /// Sanctions list the address was checked against
+    pub list: String,
+    pub list_version: String,
+    /// Whether the list is served from a compiled file
+    pub compiled: bool,
+    pub stats: SanctionsStats,
+    pub observed_fp_rate: f64,
+}
+
+/// Recently rejected policy candidates, newest first.
 #[derive(Debug, Serialize)]
+pub struct FailedPoliciesResponse {
+    pub failed: Vec<FailedPolicy>,
+}
+
+/// A subject's daily volume window as it stood at an instant.
+#[derive(Debug, Serialize)]
+pub struct VolumeWindow {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    #[serde(serialize_with = "usd::serialize")]
+    pub volume: Decimal,
+    /// Daily volume limit of the applied policy
+    #[serde(serialize_with = "usd::option::serialize")]
+    pub limit: Option<Decimal>,
+    /// Share of the limit used, as a decimal string
+    pub utilization: Option<Decimal>,
+}
+
+impl VolumeWindow {
+    pub fn new(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        volume: Decimal,
+        limit: Option<Decimal>,
+    ) -> Self {
+        VolumeWindow {
+            from,
+            to,
+            volume,
+            limit,
+            utilization: limit
+                .filter(|l| !l.is_zero())
+                .map(|l| (volume / l).round_dp(4).normalize()),
+        }
+    }
+}
+
+/// A subject's window and decisions as of an instant, for disputes.
+#[derive(Debug, Serialize)]
+pub struct SubjectAsOfResponse {
+    pub user_id: String,
+    pub at: DateTime<Utc>,
+    /// Policy whose window and limit were applied
+    pub policy_version: String,
+    pub window: VolumeWindow,
+    /// Decisions at or before `at`, newest first
+    pub decisions: Vec<DecisionEvent>,
+}
+
+/// Cases, most recently updated first.
+#[derive(Debug, Serialize)]
+pub struct CasesResponse {
+    pub cases: Vec<Case>,
+}
+
+/// A case with what reviewers know about its subject.
+#[derive(Debug, Serialize)]
+pub struct CaseResponse {
+    #[serde(flatten)]
+    pub case: Case,
+    /// The subject's annotations, newest first
+    pub annotations: Vec<Annotation>,
+}
+
+/// A subject's annotations, newest first, and its current rating.
+#[derive(Debug, Serialize)]
+pub struct AnnotationsResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub risk_rating: Option<RiskRating>,
+    pub annotations: Vec<Annotation>,
+}
+
+impl AnnotationsResponse {
+    pub fn new(annotations: Vec<Annotation>) -> Self {
+        AnnotationsResponse {
+            risk_rating: crate::domain::annotation::current_rating(&annotations),
+            annotations,
+        }
+    }
+}
+
+/// Addresses linked to a subject, oldest first.
+#[derive(Debug, Serialize)]
+pub struct SubjectAddressesResponse {
+    pub addresses: Vec<SubjectAddress>,
+}
+
+/// A subject's stored details and reviewer annotations.
+#[derive(Debug, Serialize)]
+pub struct SubjectProfileResponse {
+    #[serde(flatten)]
+    pub subject: Subject,
+    #[serde(flatten)]
+    pub annotations: AnnotationsResponse,
+}
+
+/// Usage totals for one tenant over the reported range.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TenantUsage {
+    pub tenant: String,
+    pub decisions: u64,
+    pub rule_hits: BTreeMap<String, u64>,
+}
+
+/// Usage report response.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UsageResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Totals per tenant, ordered by tenant
+    pub tenants: Vec<TenantUsage>,
+    /// Per-day breakdown, ordered by day then tenant
+    pub daily: Vec<UsageRecord>,
+}
+
+impl UsageResponse {
+    /// Build a report from per-day records.
+    pub fn new(from: NaiveDate, to: NaiveDate, daily: Vec<UsageRecord>) -> Self {
+        let mut totals: BTreeMap<&str, TenantUsage> = BTreeMap::new();
+        for record in &daily {
+            let total = totals.entry(&record.tenant).or_insert_with(|| TenantUsage {
+                tenant: record.tenant.clone(),
+                decisions: 0,
+                rule_hits: BTreeMap::new(),
+            });
+            total.decisions += record.decisions;
+            for (rule_id, hits) in &record.rule_hits {
+                *total.rule_hits.entry(rule_id.clone()).or_default() += hits;
+            }
+        }
+
+        UsageResponse {
+            from,
+            to,
+            tenants: totals.into_values().collect(),
+            daily,
+        }
+    }
+}
+
+/// Health check response.
+#[derive(Debug, Serialize, ToSchema)]
 pub struct HealthResponse {
     pub status: String,
     pub version: String,
     pub policy_version: String,
     pub uptime_secs: u64,
+    /// Latest liveness self-check, once one has run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub liveness: Option<LivenessCheck>,
 }
 
 /// Readiness check response.
@@ -69,29 +517,171 @@ pub struct ReadyResponse {
     pub policy_version: String,
     pub inline_rules: usize,
     pub streaming_rules: usize,
+    /// Rules turned off through the admin kill-switch
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub disabled_rules: Vec<RuleSwitch>,
+    /// Rules demoted to shadow mode for breaching their SLA
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shadowed_rules: Vec<ShadowedRule>,
+}
+
+/// Result of an admin kill-switch toggle.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RuleSwitchResponse {
+    pub rule_id: String,
+    pub disabled: bool,
+    /// False if the toggle applies to this replica only because storage
+    /// could not be updated
+    pub persisted: bool,
+}
+
+/// Where a rule runs: on the event alone, or against stored history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleKind {
+    Inline,
+    Streaming,
+}
+
+/// A rule of the active policy.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RuleInfo {
+    pub kind: RuleKind,
+    #[serde(flatten)]
+    pub description: RuleDescription,
+    /// Switched off by the kill-switch
+    pub disabled: bool,
+}
+
+/// Rules of the active policy, in evaluation order.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RulesResponse {
+    pub policy_version: String,
+    pub rules: Vec<RuleInfo>,
+}
+
+/// Machine-readable error code, serialized as a stable string.
+///
+/// Clients should branch on the code, not the message, which may change.
+/// Codes are never renamed or reused; a code added by a newer server
+/// deserializes as `Unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The request is malformed or fails validation
+    ValidationFailed,
+    /// Credentials are missing or invalid
+    Unauthorized,
+    /// The credentials lack the scope the route needs
+    Forbidden,
+    /// Signature headers are missing, stale, replayed or do not verify
+    InvalidSignature,
+    /// The addressed resource does not exist
+    NotFound,
+    /// The resource already exists or was changed concurrently
+    Conflict,
+    /// The case cannot move to the requested status
+    InvalidTransition,
+    /// The recorded request cannot be evaluated again
+    NotRecheckable,
+    /// The request body is too large
+    PayloadTooLarge,
+    /// The tenant's daily decision quota is used up
+    QuotaExceeded,
+    /// Storage cannot be reached
+    StorageUnavailable,
+    /// No rules are loaded yet
+    NotReady,
+    /// The request exceeded its route's time limit
+    Timeout,
+    /// Unexpected server failure
+    InternalError,
+    /// A code this build does not know
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    /// Every code the server sends.
+    pub const ALL: [ErrorCode; 14] = [
+        ErrorCode::ValidationFailed,
+        ErrorCode::Unauthorized,
+        ErrorCode::Forbidden,
+        ErrorCode::InvalidSignature,
+        ErrorCode::NotFound,
+        ErrorCode::Conflict,
+        ErrorCode::InvalidTransition,
+        ErrorCode::NotRecheckable,
+        ErrorCode::PayloadTooLarge,
+        ErrorCode::QuotaExceeded,
+        ErrorCode::StorageUnavailable,
+        ErrorCode::NotReady,
+        ErrorCode::Timeout,
+        ErrorCode::InternalError,
+    ];
+
+    /// The serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::ValidationFailed => "VALIDATION_FAILED",
+            ErrorCode::Unauthorized => "UNAUTHORIZED",
+            ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::InvalidSignature => "INVALID_SIGNATURE",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::InvalidTransition => "INVALID_TRANSITION",
+            ErrorCode::NotRecheckable => "NOT_RECHECKABLE",
+            ErrorCode::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            ErrorCode::QuotaExceeded => "QUOTA_EXCEEDED",
+            ErrorCode::StorageUnavailable => "STORAGE_UNAVAILABLE",
+            ErrorCode::NotReady => "NOT_READY",
+            ErrorCode::Timeout => "TIMEOUT",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::Unknown => "UNKNOWN",
+        }
+    }
+
+    /// Returns true if the same request may succeed when retried later.
+    /// Other errors need a changed request or operator action.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorCode::StorageUnavailable
+                | ErrorCode::NotReady
+                | ErrorCode::Timeout
+                | ErrorCode::InternalError
+        )
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Error response.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
+    /// Human-readable message; may change between releases
     pub error: String,
-    pub code: String,
+    pub code: ErrorCode,
 }
 
 impl ErrorResponse {
-    pub fn new(error: impl Into<String>, code: impl Into<String>) -> Self {
+    pub fn new(error: impl Into<String>, code: ErrorCode) -> Self {
         ErrorResponse {
             error: error.into(),
-            code: code.into(),
+            code,
         }
     }
 
     pub fn bad_request(message: impl Into<String>) -> Self {
-        ErrorResponse::new(message, "BAD_REQUEST")
+        ErrorResponse::new(message, ErrorCode::ValidationFailed)
     }
 
     pub fn internal_error(message: impl Into<String>) -> Self {
-        ErrorResponse::new(message, "INTERNAL_ERROR")
+        ErrorResponse::new(message, ErrorCode::InternalError)
     }
 }
 
@@ -99,6 +689,21 @@ impl ErrorResponse {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_error_codes_are_stable_strings() {
+        for code in ErrorCode::ALL {
+            let json = serde_json::to_value(code).unwrap();
+            assert_eq!(json, serde_json::json!(code.as_str()));
+            assert_eq!(serde_json::from_value::<ErrorCode>(json).unwrap(), code);
+        }
+
+        let body: ErrorResponse =
+            serde_json::from_str(r#"{"error":"slow down","code":"SOMETHING_NEW"}"#).unwrap();
+        assert_eq!(body.code, ErrorCode::Unknown);
+        assert!(ErrorCode::StorageUnavailable.is_retryable());
+        assert!(!ErrorCode::ValidationFailed.is_retryable());
+    }
+
     #[test]
     fn test_decision_response_serialization() {
         let resp = DecisionResponse::new(
@@ -114,6 +719,30 @@ mod tests {
         assert!(json.contains("v1.0"));
     }
 
+    #[test]
+    fn test_usd_fields_serialize_as_decimal_strings() {
+        #[derive(Serialize)]
+        struct Totals {
+            #[serde(serialize_with = "usd::serialize")]
+            total: rust_decimal::Decimal,
+            #[serde(serialize_with = "usd::option::serialize")]
+            limit: Option<rust_decimal::Decimal>,
+        }
+
+        let json = serde_json::to_value(Totals {
+            total: rust_decimal::Decimal::new(100005, 2),
+            limit: None,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "total": { "amount": "1000.05", "currency": "USD" },
+                "limit": null,
+            })
+        );
+    }
+
     #[test]
     fn test_allow_response() {
         let resp = DecisionResponse::allow("v1.0".to_string());
@@ -121,5 +750,17 @@ mod tests {
         assert_eq!(resp.decision, Decision::Allow);
         assert_eq!(resp.decision_code, "OK");
         assert!(resp.evidence.is_empty());
+        assert!(resp.enforced);
+    }
+
+    #[test]
+    fn test_monitor_only_response() {
+        let resp = DecisionResponse::monitor_only("v1.0".to_string());
+
+        assert_eq!(resp.decision, Decision::Allow);
+        assert!(!resp.enforced);
+
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"enforced\":false"));
     }
 }
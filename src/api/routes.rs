@@ -1,21 +1,65 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
     response::IntoResponse,
-    routing::{get, post},
-    Json, Router,
+    routing::{delete, get, post},
+    Extension, Json, Router,
 };
+use chrono::Utc;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::watch;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
 
-use crate::domain::Decision;
-use crate::rules::RuleSet;
-use crate::storage::{DecisionRecord, Storage, TransactionRecord};
+use crate::config::DegradedMode;
+use crate::domain::event::{Asset, Chain, DecisionStage, EventId, SCHEMA_VERSION};
+use crate::domain::{
+    Annotation, AssetRegistry, CaseStatus, Decision, DecisionEvent, Evidence, Subject, TxEvent,
+    UsdRate,
+};
+use crate::export::{self, ExportFormat, Redactor};
+use crate::inline_engine;
+use crate::lists::{self, ImportError, ImportReport, ListFormat};
+use crate::observability::{DecisionSummary, MetricsRegistry, Phase, PhaseTimings, WatchdogStatus};
+use crate::policy::{FailedPolicyLog, PolicyDiff};
+use crate::rules::{
+    evaluate_streaming, Blocklist, DayWindow, FixedClock, RuleDescription, RuleSet, RuleSlaMonitor,
+    RuleSwitches, StreamingRule, BLOCKLIST_RULE_ID, FINALITY_EVIDENCE_KEY,
+};
+use crate::storage::{
+    DecisionRecord, PendingDeposit, RuleSwitch, Storage, StorageRead, StoredDecision,
+    TransactionRecord, WindowCache, WindowSpec,
+};
 
-use super::request::DecisionRequest;
-use super::response::{DecisionResponse, ErrorResponse, HealthResponse, ReadyResponse};
+use super::auth::{authorize, AdminAuth, Principal};
+use super::deadline::{enforce_timeout, persistence_started, RouteTimeouts};
+use super::enrich::SubjectEnrichment;
+use super::openapi;
+use super::request::{
+    AnnotationQuery, AnnotationRequest, AsOfQuery, CaseQuery, CaseStatusUpdate, ConfirmationUpdate,
+    CreateCaseRequest, DecisionQuery, DecisionRequest, ExportQuery, KycUpdateRequest,
+    ListExportQuery, ListImportQuery, PolicyDiffQuery, ResponseDetail, RuleSwitchRequest,
+    ScreeningRequest, StatsQuery, SubjectAddressRequest, UsageQuery, DEFAULT_ANNOTATION_LIMIT,
+    DEFAULT_AS_OF_DECISIONS, DEFAULT_CASE_LIMIT, MAX_ADDRESS_LABEL_BYTES, MAX_ANNOTATION_LIMIT,
+    MAX_ANNOTATION_NOTE_BYTES, MAX_ANNOTATION_TAGS, MAX_AS_OF_DECISIONS, MAX_CASE_LIMIT,
+    MAX_SCREENING_ADDRESSES,
+};
+use super::request_id::{propagate_request_id, RequestId};
+use super::response::{
+    AddressScreening, AnnotationsResponse, CaseResponse, CasesResponse, ConfirmationResponse,
+    DecisionResponse, DepositStatus, ErrorCode, ErrorResponse, FailedPoliciesResponse,
+    HealthResponse, MinimalDecisionResponse, ReadyResponse, RecheckResponse, RuleInfo, RuleKind,
+    RuleOutcome, RuleSwitchResponse, RuleTrace, RulesResponse, SanctionsCheckResponse,
+    ScreeningResponse, SubjectAddressesResponse, SubjectAsOfResponse, SubjectProfileResponse,
+    UsageResponse, VolumeWindow,
+};
+use super::signing::{verify_signature, RequestSigning};
+use super::tenant::{identify_tenant, TenantId};
+use super::timed::TimedJson;
 
 /// Shared application state.
 pub struct AppState {
@@ -33,50 +77,656 @@ pub struct AppState {
 
     /// Latency budget in milliseconds
     pub latency_budget_ms: u64,
+
+    /// Record decisions without enforcing them (always return Allow)
+    pub monitor_only: bool,
+
+    /// Decision handling while storage is unavailable
+    pub degraded_mode: DegradedMode,
+
+    /// Stored subject fields filled in or preferred over the caller's
+    pub subject_enrichment: SubjectEnrichment,
+
+    /// Decision counters, latency histogram and SLO
+    pub metrics: Arc<MetricsRegistry>,
+
+    /// Asset alias normalization; unknown assets are rejected when set
+    pub assets: Option<Arc<AssetRegistry>>,
+
+    /// Daily decision quotas keyed by tenant
+    pub tenant_quotas: HashMap<String, u64>,
+
+    /// Result of the liveness self-check
+    pub watchdog: Arc<WatchdogStatus>,
+
+    /// Request fields removed from decision exports
+    pub export_redactor: Redactor,
+
+    /// Policy candidates rejected by recent loads
+    pub failed_policies: Arc<FailedPolicyLog>,
+
+    /// Rules turned off through the admin kill-switch
+    pub rule_switches: Arc<RuleSwitches>,
+
+    /// Internal address blocklist, screened with the inline rules
+    pub blocklist: Arc<Blocklist>,
+
+    /// Streaming rule SLA tracking; slow rules are shadowed when set
+    pub rule_sla: Option<Arc<RuleSlaMonitor>>,
+
+    /// How admin callers authenticate
+    pub admin_auth: AdminAuth,
+
+    /// POST requests must be signed when set
+    pub request_signing: Option<Arc<RequestSigning>>,
+
+    /// Overall time limit of each route class
+    pub route_timeouts: RouteTimeouts,
 }
 
 /// Create the application router.
 pub fn create_router(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/v1/decision/check", post(handle_decision))
+        .route("/v1/decisions/:request_id", get(handle_get_decision))
+        .route(
+            "/v1/decisions/:request_id/recheck",
+            post(handle_recheck_decision),
+        )
+        .route("/v1/screening/addresses", post(handle_screening))
+        .route("/v1/subjects/:user_id", get(handle_get_subject))
+        .route("/v1/subjects/:user_id/kyc", post(handle_kyc_update))
+        .route(
+            "/v1/subjects/:user_id/annotations",
+            get(handle_list_annotations).post(handle_add_annotation),
+        )
+        .route(
+            "/v1/subjects/:user_id/addresses",
+            get(handle_list_addresses).post(handle_save_address),
+        )
+        .route(
+            "/v1/subjects/:user_id/addresses/:address",
+            delete(handle_remove_address),
+        )
+        .route(
+            "/v1/events/:event_id/confirmations",
+            post(handle_confirmations),
+        )
+        .route("/v1/cases", get(handle_list_cases).post(handle_create_case))
+        .route("/v1/cases/:case_id", get(handle_get_case))
+        .route("/v1/cases/:case_id/status", post(handle_update_case_status))
+        .route(
+            "/v1/admin/subjects/:user_id/as-of",
+            get(handle_subject_as_of),
+        )
+        .route("/v1/admin/policies/diff", get(handle_policy_diff))
+        .route("/v1/admin/policies/failed", get(handle_failed_policies))
+        .route(
+            "/v1/admin/sanctions/check/:address",
+            get(handle_sanctions_check),
+        )
+        .route("/v1/rules", get(handle_list_rules))
+        .route(
+            "/v1/admin/rules/:rule_id/disable",
+            post(handle_disable_rule),
+        )
+        .route("/v1/admin/rules/:rule_id/enable", post(handle_enable_rule))
+        .route("/v1/stats/decisions", get(handle_decision_stats))
+        .route("/v1/admin/usage", get(handle_usage))
+        .route("/v1/admin/export/decisions", get(handle_export_decisions))
+        .route("/v1/admin/export/overrides", get(handle_export_overrides))
+        .route("/v1/admin/export/blocklist", get(handle_export_blocklist))
+        .route("/v1/admin/import/overrides", post(handle_import_overrides))
+        .route("/v1/admin/import/blocklist", post(handle_import_blocklist))
         .route("/health", get(handle_health))
         .route("/ready", get(handle_ready))
         .route("/metrics", get(handle_metrics))
+        .route("/openapi.json", get(openapi::handle_openapi))
+        .route("/docs", get(openapi::handle_docs))
+        .route_layer(middleware::from_fn_with_state(
+            state.admin_auth.clone(),
+            authorize,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            enforce_timeout,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            verify_signature,
+        ))
+        .layer(middleware::from_fn(identify_tenant))
+        .layer(middleware::from_fn(propagate_request_id))
         .with_state(state)
 }
 
 /// Handle decision check requests.
+#[utoipa::path(
+    post,
+    path = "/v1/decision/check",
+    tag = "decisions",
+    request_body = DecisionRequest,
+    params(
+        DecisionQuery,
+        ("x-request-id" = Option<String>, Header, description = "Correlation ID; generated if absent"),
+        ("x-tenant-id" = Option<String>, Header, description = "Tenant for quotas and usage reporting"),
+    ),
+    responses(
+        (status = 200, description = "Decision", body = DecisionResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 429, description = "Tenant quota exceeded", body = ErrorResponse),
+        (status = 500, description = "Degraded decision while storage is unavailable", body = DecisionResponse),
+    )
+)]
 async fn handle_decision(
     State(state): State<Arc<AppState>>,
-    Json(req): Json<DecisionRequest>,
-) -> impl IntoResponse {
+    Extension(request_id): Extension<RequestId>,
+    Extension(tenant): Extension<TenantId>,
+    Query(query): Query<DecisionQuery>,
+    body: TimedJson<DecisionRequest>,
+) -> axum::response::Response {
     let start = Instant::now();
+    let req = body.value;
+    let mut timings = PhaseTimings::default();
+    timings.add(Phase::Deserialize, body.elapsed);
 
-    // Convert request to TxEvent
-    let event = req.to_tx_event();
-    let user_id = event.subject.user_id.as_str();
+    // Reject assets the registry cannot normalize before any evaluation
+    if let Some(assets) = &state.assets {
+        if let Err(e) = assets.validate(&req.tx.asset, &req.tx.amount) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::bad_request(e.to_string())),
+            )
+                .into_response();
+        }
+    }
 
-    // Get current ruleset
+    if let Some(&quota) = state.tenant_quotas.get(tenant.as_str()) {
+        if quota_exceeded(&state, &tenant, quota).await {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(ErrorResponse::new(
+                    format!("Daily decision quota of {} exceeded", quota),
+                    ErrorCode::QuotaExceeded,
+                )),
+            )
+                .into_response();
+        }
+    }
+
+    let event = prepare_event(&state, &req, &mut timings).await;
+
+    if query.provisional {
+        return provisional_decision(state, request_id, tenant, req, event, start, timings).await;
+    }
+
+    let detail = query.response_detail;
+    let mut trace = (detail == ResponseDetail::Full).then(Vec::new);
+    let (status, mut response) = decide(
+        &state,
+        &request_id,
+        &req,
+        event,
+        start,
+        &mut timings,
+        &mut trace,
+    )
+    .await;
+    record_completed(&state, &tenant, start, &timings, status, &response).await;
+
+    match detail {
+        ResponseDetail::Minimal => {
+            (status, Json(MinimalDecisionResponse::from(&response.0))).into_response()
+        }
+        ResponseDetail::Standard | ResponseDetail::Full => {
+            if query.debug || detail == ResponseDetail::Full {
+                response.timings = Some(timings);
+            }
+            response.trace = trace;
+            (status, response).into_response()
+        }
+    }
+}
+
+/// Count a completed decision in the metrics, SLO and tenant usage.
+pub(crate) async fn record_completed(
+    state: &AppState,
+    tenant: &TenantId,
+    start: Instant,
+    timings: &PhaseTimings,
+    status: StatusCode,
+    response: &DecisionResponse,
+) {
+    // Failed requests spend the error budget like slow ones
+    let over_budget = start.elapsed().as_millis() > state.latency_budget_ms as u128;
+    state.metrics.record_decision(&response.decision);
+    state.metrics.record_latency(start);
+    state.metrics.phases.record(timings);
+    state
+        .metrics
+        .slo
+        .record(over_budget || status.is_server_error());
+
+    let degraded = state.storage.is_degraded();
+    let rules_hit: Vec<String> = response
+        .evidence
+        .iter()
+        .map(|e| e.rule_id.clone())
+        .collect();
+    state
+        .metrics
+        .stats
+        .record(response.decision, &rules_hit, start.elapsed(), degraded);
+
+    if status.is_success() && !degraded {
+        if let Err(e) = state
+            .storage
+            .record_usage(tenant.as_str(), &rules_hit)
+            .await
+        {
+            warn!(tenant = tenant.as_str(), error = %e, "Failed to record tenant usage");
+        }
+    }
+}
+
+/// Answer from the inline rules at once and finish the decision in the
+/// background.
+///
+/// The final decision is recorded, with its `decision` outbox event, like
+/// any other and can be fetched from the returned `final_decision_url`. A
+/// fatal inline outcome is already final and is returned as such.
+async fn provisional_decision(
+    state: Arc<AppState>,
+    request_id: RequestId,
+    tenant: TenantId,
+    req: DecisionRequest,
+    event: TxEvent,
+    start: Instant,
+    mut timings: PhaseTimings,
+) -> axum::response::Response {
     let ruleset = state.ruleset_rx.borrow().clone();
+    let monitor_only = state.monitor_only || ruleset.monitor_only;
+    // Subject field handling may rewrite the subject, so it is applied to
+    // a copy; `decide` applies it again to the event itself
+    let mut checked = event.clone();
+    let tier_missing = checked.kyc_tier_missing;
+    let (anomaly_decision, anomalies) = ruleset.subject_fields.apply(&mut checked, tier_missing);
+    let (mut decision, mut evidence) = evaluate_inline(&ruleset, &state, &checked, None);
+    decision = decision.max(anomaly_decision);
+    evidence.splice(0..0, anomalies);
+    ruleset
+        .mitigations
+        .apply(&checked, &mut decision, &mut evidence);
 
-    // Phase 1: Evaluate inline rules (stateless)
-    let mut final_decision = Decision::Allow;
-    let mut evidence = Vec::new();
+    if decision.is_fatal() && !monitor_only {
+        let (status, response) = decide(
+            &state,
+            &request_id,
+            &req,
+            event,
+            start,
+            &mut timings,
+            &mut None,
+        )
+        .await;
+        record_completed(&state, &tenant, start, &timings, status, &response).await;
+        return (status, response).into_response();
+    }
 
-    for rule in &ruleset.inline {
-        let result = rule.evaluate(&event);
-        if result.hit {
-            if result.decision > final_decision {
-                final_decision = result.decision;
+    let response = if monitor_only {
+        DecisionResponse::monitor_only(ruleset.policy_version.clone())
+    } else {
+        DecisionResponse::new(decision, ruleset.policy_version.clone(), evidence)
+            .with_actions(&ruleset)
+    }
+    .provisional(format!("/v1/decisions/{}", request_id.0));
+
+    tokio::spawn(async move {
+        let (status, response) = decide(
+            &state,
+            &request_id,
+            &req,
+            event,
+            start,
+            &mut timings,
+            &mut None,
+        )
+        .await;
+        record_completed(&state, &tenant, start, &timings, status, &response).await;
+        if response.decision != decision {
+            info!(
+                request_id = %request_id.0,
+                provisional = %decision,
+                decision = %response.decision,
+                "Final decision differs from provisional"
+            );
+        }
+    });
+
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// Fetch the decision recorded for a request, such as the final decision
+/// following a provisional answer. Not found until it is recorded.
+#[utoipa::path(
+    get,
+    path = "/v1/decisions/{request_id}",
+    tag = "decisions",
+    params(("request_id" = String, Path, description = "Request ID of the check")),
+    responses(
+        (status = 200, description = "Final decision", body = DecisionEvent),
+        (status = 404, description = "Not recorded yet", body = ErrorResponse),
+    )
+)]
+async fn handle_get_decision(
+    State(state): State<Arc<AppState>>,
+    Path(request_id): Path<String>,
+) -> axum::response::Response {
+    match state.storage.get_decision_by_request_id(&request_id).await {
+        Ok(Some(stored)) => (StatusCode::OK, Json(final_decision_event(stored))).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                format!("No decision recorded for request: {}", request_id),
+                ErrorCode::NotFound,
+            )),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!(request_id = %request_id, error = %e, "Failed to load decision");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to load decision")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Re-run a recorded request against the current policy and state, without
+/// recording anything, and report whether its decision still holds.
+///
+/// For callers acting on a decision some time after it was made, such as
+/// executing a withdrawal approved minutes earlier. The transaction is
+/// already part of the subject's history, so stateful rules see it with a
+/// zero amount, as in sweeps; stateless rules see it as sent.
+#[utoipa::path(
+    post,
+    path = "/v1/decisions/{request_id}/recheck",
+    tag = "decisions",
+    params(("request_id" = String, Path, description = "Request ID of the original check")),
+    responses(
+        (status = 200, description = "Re-check outcome", body = RecheckResponse),
+        (status = 404, description = "No decision recorded for the request", body = ErrorResponse),
+        (status = 422, description = "The recorded request cannot be re-run", body = ErrorResponse),
+        (status = 503, description = "Storage unavailable", body = ErrorResponse),
+    )
+)]
+async fn handle_recheck_decision(
+    State(state): State<Arc<AppState>>,
+    Path(request_id): Path<String>,
+) -> axum::response::Response {
+    let unavailable = || {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(
+                "Decisions cannot be re-checked while storage is unavailable",
+                ErrorCode::StorageUnavailable,
+            )),
+        )
+            .into_response()
+    };
+    if state.storage.is_degraded() {
+        return unavailable();
+    }
+
+    let stored = match state.storage.get_decision_by_request_id(&request_id).await {
+        Ok(Some(stored)) => stored,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(
+                    format!("No decision recorded for request: {}", request_id),
+                    ErrorCode::NotFound,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            warn!(request_id = %request_id, error = %e, "Failed to load decision");
+            return unavailable();
+        }
+    };
+    let Ok(req) = serde_json::from_value::<DecisionRequest>(stored.record.request.clone()) else {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ErrorResponse::new(
+                format!("Recorded request cannot be re-run: {}", request_id),
+                ErrorCode::NotRecheckable,
+            )),
+        )
+            .into_response();
+    };
+
+    let event = prepare_event(&state, &req, &mut PhaseTimings::default()).await;
+    let Some((decision, current)) = recheck(&state, event).await else {
+        return unavailable();
+    };
+
+    let prior = final_decision_event(stored);
+    let still_holds = !current.enforced || decision <= prior.decision;
+    if !still_holds {
+        info!(
+            request_id = %request_id,
+            prior = %prior.decision,
+            decision = %decision,
+            "Re-checked decision no longer holds"
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(RecheckResponse {
+            still_holds,
+            age_secs: (Utc::now() - prior.issued_at).num_seconds(),
+            prior,
+            current,
+        }),
+    )
+        .into_response()
+}
+
+/// Evaluate an already recorded request again, returning the decision
+/// reached and the response the caller would get now, or None if the
+/// subject cannot be read.
+async fn recheck(state: &AppState, mut event: TxEvent) -> Option<(Decision, DecisionResponse)> {
+    let user_id = event.subject.user_id.as_str().to_string();
+    let ruleset = state.ruleset_rx.borrow().clone();
+    let monitor_only = state.monitor_only || ruleset.monitor_only;
+
+    let (mut decision, mut evidence) = evaluate_inline(&ruleset, state, &event, None);
+
+    let subject_id = if decision.is_fatal() {
+        None
+    } else {
+        match state.storage.get_subject_by_user_id(&user_id).await {
+            Ok(found) => found.map(|(subject_id, _)| subject_id),
+            Err(e) => {
+                warn!(user_id = %user_id, error = %e, "Failed to look up subject");
+                return None;
             }
-            if let Some(ev) = result.evidence {
-                evidence.push(ev);
+        }
+    };
+
+    if let Some(subject_id) = subject_id {
+        // The transaction is already in the subject's history
+        event.usd_value = rust_decimal::Decimal::ZERO;
+        event.amount = "0".to_string();
+
+        let rules: Vec<Arc<dyn StreamingRule>> = ruleset
+            .streaming_for(&event)
+            .iter()
+            .filter(|rule| {
+                !state.rule_switches.is_disabled(rule.id())
+                    && ruleset.features.allows(rule.id(), &event.features)
+                    && !state
+                        .rule_sla
+                        .as_ref()
+                        .is_some_and(|sla| sla.is_shadowed(rule.id()))
+            })
+            .cloned()
+            .collect();
+        let windows: Arc<dyn StorageRead> = Arc::new(WindowCache::new(state.storage.clone()));
+        let evaluations = evaluate_streaming(
+            &rules,
+            &Arc::new(event.clone()),
+            subject_id,
+            &windows,
+            |_| true,
+        )
+        .await;
+        for evaluation in evaluations {
+            match evaluation.result {
+                Ok(result) if result.hit => {
+                    decision = decision.max(result.decision);
+                    evidence.extend(result.evidence);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(user_id = %user_id, rule_id = rules[evaluation.index].id(), error = %e, "Failed to re-check streaming rule");
+                }
+            }
+        }
+    }
+    ruleset
+        .mitigations
+        .apply(&event, &mut decision, &mut evidence);
+
+    let response = if monitor_only {
+        DecisionResponse::monitor_only(ruleset.policy_version.clone())
+    } else {
+        DecisionResponse::new(decision, ruleset.policy_version.clone(), evidence)
+            .with_actions(&ruleset)
+    };
+    Some((decision, response))
+}
+
+/// Final decision event for a decision read back from the audit log. The
+/// event ID is the request ID, or the decision ID if there was none.
+fn final_decision_event(stored: StoredDecision) -> DecisionEvent {
+    let record = stored.record;
+    let decision_id = stored.id.to_string();
+    DecisionEvent {
+        schema_version: SCHEMA_VERSION.to_string(),
+        event_id: EventId::from_string(record.request_id.unwrap_or_else(|| decision_id.clone())),
+        decision_id: EventId::from_string(decision_id),
+        issued_at: stored.created_at,
+        stage: DecisionStage::Final,
+        decision: record.decision,
+        decision_code: record.decision_code,
+        policy_version: record.policy_version,
+        evidence: record.evidence,
+    }
+}
+
+/// Returns true if the tenant has used its daily quota. Requests are
+/// admitted while usage cannot be read, so quota checks never fail closed.
+async fn quota_exceeded(state: &AppState, tenant: &TenantId, quota: u64) -> bool {
+    if state.storage.is_degraded() {
+        return false;
+    }
+
+    match state.storage.get_daily_usage(tenant.as_str()).await {
+        Ok(used) => used >= quota,
+        Err(e) => {
+            warn!(tenant = tenant.as_str(), error = %e, "Failed to read tenant usage");
+            false
+        }
+    }
+}
+
+/// Convert a request to the event rules evaluate, normalizing the asset
+/// and filling the subject from storage.
+async fn prepare_event(
+    state: &AppState,
+    req: &DecisionRequest,
+    timings: &mut PhaseTimings,
+) -> TxEvent {
+    let mut event = req.to_tx_event();
+
+    // Aggregate aliases under the canonical symbol
+    if let Some(info) = state.assets.as_ref().and_then(|a| a.resolve(&req.tx.asset)) {
+        event.asset = Asset::new(&info.symbol);
+        if let Some(chain) = &info.chain {
+            event.chain = Chain::new(chain);
+        }
+    }
+
+    // Fill sparse subjects from storage and apply trusted stored fields, and
+    // note whether the subject is new for rules that ask
+    let enrichment = state.subject_enrichment;
+    let enrich = enrichment.needs_lookup(&req.subject);
+    let needs_newness = state.ruleset_rx.borrow().needs_subject_lookup;
+    if (enrich || needs_newness) && !state.storage.is_degraded() {
+        let lookup_start = Instant::now();
+        match state
+            .storage
+            .get_subject_by_user_id(event.subject.user_id.as_str())
+            .await
+        {
+            Ok(Some((_, known))) => {
+                event.subject_is_new = Some(false);
+                if enrich {
+                    // A known subject always has a stored tier to fill in
+                    event.kyc_tier_missing = false;
+                    let enriched = enrichment.apply(&req.subject, &mut event.subject, &known);
+                    if !enriched.is_empty() {
+                        debug!(fields = ?enriched, "Enriched subject from storage");
+                    }
+                }
             }
+            Ok(None) => event.subject_is_new = Some(true),
+            Err(e) => warn!(error = %e, "Failed to look up stored subject"),
         }
+        timings.record(Phase::SubjectUpsert, lookup_start);
     }
 
-    // Short-circuit if fatal decision from inline rules
-    if final_decision.is_fatal() {
+    event
+}
+
+/// Evaluate a decision request, tracing each rule's outcome into `trace`
+/// when it is set.
+async fn decide(
+    state: &AppState,
+    request_id: &RequestId,
+    req: &DecisionRequest,
+    mut event: TxEvent,
+    start: Instant,
+    timings: &mut PhaseTimings,
+    trace: &mut Option<Vec<RuleTrace>>,
+) -> (StatusCode, Json<DecisionResponse>) {
+    // Get current ruleset
+    let ruleset = state.ruleset_rx.borrow().clone();
+    let monitor_only = state.monitor_only || ruleset.monitor_only;
+
+    // Apply the policy's handling of subject fields that are missing or
+    // unknown
+    let tier_missing = event.kyc_tier_missing;
+    let (anomaly_decision, anomalies) = ruleset.subject_fields.apply(&mut event, tier_missing);
+    let user_id = event.subject.user_id.as_str();
+
+    // Phase 1: Evaluate inline rules (stateless)
+    let phase_start = Instant::now();
+    let (mut final_decision, mut evidence) =
+        evaluate_inline(&ruleset, state, &event, trace.as_mut());
+    final_decision = final_decision.max(anomaly_decision);
+    evidence.splice(0..0, anomalies);
+    timings.record(Phase::InlineRules, phase_start);
+
+    // Short-circuit if fatal decision from inline rules; in monitor-only mode
+    // evaluate everything so the full outcome is recorded
+    if final_decision.is_fatal() && !monitor_only {
+        UsdRate::attach(&event, &mut evidence);
         let elapsed = start.elapsed();
         if elapsed.as_millis() > state.latency_budget_ms as u128 {
             warn!(
@@ -88,44 +738,148 @@ async fn handle_decision(
 
         return (
             StatusCode::OK,
-            Json(DecisionResponse::new(
-                final_decision,
-                ruleset.policy_version.clone(),
-                evidence,
-            )),
+            Json(
+                DecisionResponse::new(final_decision, ruleset.policy_version.clone(), evidence)
+                    .with_actions(&ruleset),
+            ),
         );
     }
 
-    // Phase 2: Get subject_id for stateful rules
-    let subject_id = match state.storage.upsert_subject(&event.subject).await {
-        Ok(id) => id,
-        Err(e) => {
-            warn!(user_id = user_id, error = %e, "Failed to upsert subject");
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(DecisionResponse::new(
-                    Decision::Allow, // Fail open on storage errors
-                    ruleset.policy_version.clone(),
-                    evidence,
-                )),
-            );
+    // Phase 2: Get subject_id for stateful rules, skipping storage entirely
+    // while it is known to be unavailable
+    let subject_id = if state.storage.is_degraded() {
+        warn!(
+            user_id = user_id,
+            "Storage degraded, skipping stateful rules"
+        );
+        None
+    } else {
+        let phase_start = Instant::now();
+        let upserted = state.storage.upsert_subject(&event.subject).await;
+        timings.record(Phase::SubjectUpsert, phase_start);
+        match upserted {
+            Ok(id) => Some(id),
+            Err(e) => {
+                warn!(user_id = user_id, error = %e, "Failed to upsert subject");
+                None
+            }
         }
     };
 
-    // Phase 3: Evaluate streaming rules (stateful)
-    for rule in &ruleset.streaming {
-        let result = match rule
-            .evaluate(&event, subject_id, state.storage.as_ref())
-            .await
-        {
+    let Some(subject_id) = subject_id else {
+        ruleset
+            .mitigations
+            .apply(&event, &mut final_decision, &mut evidence);
+        UsdRate::attach(&event, &mut evidence);
+        return degraded_response(
+            state.degraded_mode,
+            final_decision,
+            evidence,
+            &ruleset,
+            monitor_only,
+        );
+    };
+
+    // Phase 3: Evaluate streaming rules (stateful), fetching each window
+    // aggregate once for the request and those rules declare up front in
+    // a single query
+    let phase_start = Instant::now();
+    let windows = WindowCache::new(state.storage.clone());
+    let streaming = ruleset.streaming_for(&event);
+    let specs: Vec<WindowSpec> = streaming
+        .iter()
+        .filter(|rule| {
+            !state.rule_switches.is_disabled(rule.id())
+                && ruleset.features.allows(rule.id(), &event.features)
+        })
+        .flat_map(|rule| rule.windows(&event))
+        .collect();
+    if !specs.is_empty() {
+        // Rules fetch their own windows if this fails
+        if let Err(e) = windows.prefetch(subject_id, &specs).await {
+            warn!(user_id = user_id, error = %e, "Failed to prefetch window aggregates");
+        }
+    }
+    let windows: Arc<dyn StorageRead> = Arc::new(windows);
+
+    // Rules run concurrently; a fatal hit cancels those still running
+    let mut skipped = Vec::new();
+    let mut runnable: Vec<Arc<dyn StreamingRule>> = Vec::new();
+    for rule in streaming.iter() {
+        if state.rule_switches.is_disabled(rule.id()) {
+            skipped.push(Some(RuleOutcome::Disabled));
+        } else if !ruleset.features.allows(rule.id(), &event.features) {
+            skipped.push(Some(RuleOutcome::Experimental));
+        } else {
+            skipped.push(None);
+            runnable.push(rule.clone());
+        }
+    }
+    let shadowed: Vec<bool> = runnable
+        .iter()
+        .map(|rule| {
+            state
+                .rule_sla
+                .as_ref()
+                .is_some_and(|sla| sla.is_shadowed(rule.id()))
+        })
+        .collect();
+    // Monitor-only decisions record every rule's outcome, so no hit cancels
+    // the rest
+    let shared_event = Arc::new(event.clone());
+    let mut evaluations = evaluate_streaming(&runnable, &shared_event, subject_id, &windows, |i| {
+        !monitor_only && !shadowed[i]
+    })
+    .await
+    .into_iter()
+    .peekable();
+
+    let streaming_from = evidence.len();
+    let mut streaming_decision = Decision::Allow;
+    let mut runnable_index = 0;
+    for (rule, skipped) in streaming.iter().zip(skipped) {
+        if let Some(outcome) = skipped {
+            if let Some(trace) = trace.as_mut() {
+                trace.push(RuleTrace::skipped(rule.id(), outcome));
+            }
+            continue;
+        }
+        let index = runnable_index;
+        runnable_index += 1;
+        let Some(evaluation) = evaluations.next_if(|e| e.index == index) else {
+            if let Some(trace) = trace.as_mut() {
+                trace.push(RuleTrace::skipped(rule.id(), RuleOutcome::Cancelled));
+            }
+            continue;
+        };
+        if let Some(sla) = &state.rule_sla {
+            sla.record(rule.id(), evaluation.elapsed, evaluation.result.is_err());
+        }
+        let result = match evaluation.result {
             Ok(r) => r,
             Err(e) => {
                 warn!(user_id = user_id, rule_id = rule.id(), error = %e, "Failed to evaluate streaming rule");
+                if let Some(trace) = trace.as_mut() {
+                    trace.push(RuleTrace::skipped(rule.id(), RuleOutcome::Error));
+                }
                 continue; // Skip this rule on error
             }
         };
+        if shadowed[index] {
+            if result.hit {
+                debug!(user_id = user_id, rule_id = rule.id(), decision = ?result.decision, "Shadowed rule hit");
+            }
+            if let Some(trace) = trace.as_mut() {
+                trace.push(RuleTrace::shadowed(rule.id(), &result));
+            }
+            continue;
+        }
+        if let Some(trace) = trace.as_mut() {
+            trace.push(RuleTrace::evaluated(rule.id(), &result));
+        }
 
         if result.hit {
+            streaming_decision = streaming_decision.max(result.decision);
             if result.decision > final_decision {
                 final_decision = result.decision;
             }
@@ -135,24 +889,35 @@ async fn handle_decision(
         }
     }
 
-    // Phase 4: Record transaction
+    timings.record(Phase::StreamingRules, phase_start);
+
+    ruleset
+        .mitigations
+        .apply(&event, &mut final_decision, &mut evidence);
+    UsdRate::attach(&event, &mut evidence);
+
+    // Phase 4: Record transaction, decision and outbox event atomically
     let tx_record = TransactionRecord {
         subject_id,
         tx_type: format!("{:?}", event.direction),
         asset: event.asset.0.clone(),
         amount: event.amount.parse().unwrap_or_default(),
         usd_value: event.usd_value,
-        dest_address: None, // Could extract from event if needed
+        dest_address: event
+            .destination
+            .as_ref()
+            .map(|d| d.address.as_str().to_string()),
+        counterparty_geo: event
+            .counterparty_geo
+            .as_ref()
+            .map(|c| c.as_str().to_string()),
+        account_id: Some(event.subject.account_id.as_str().to_string()),
     };
 
-    if let Err(e) = state.storage.record_transaction(&tx_record).await {
-        warn!(user_id = user_id, error = %e, "Failed to record transaction");
-    }
-
-    // Phase 5: Record decision
     let decision_record = DecisionRecord {
         subject_id: Some(subject_id),
-        request: serde_json::to_value(&req).unwrap_or(serde_json::Value::Null),
+        request_id: Some(request_id.0.clone()),
+        request: serde_json::to_value(req).unwrap_or(serde_json::Value::Null),
         decision: final_decision,
         decision_code: evidence
             .first()
@@ -163,10 +928,34 @@ async fn handle_decision(
         latency_ms: start.elapsed().as_millis() as u32,
     };
 
-    if let Err(e) = state.storage.record_decision(&decision_record).await {
+    let phase_start = Instant::now();
+    persistence_started();
+    if let Err(e) = state
+        .storage
+        .record_outcome(&tx_record, &decision_record)
+        .await
+    {
         warn!(user_id = user_id, error = %e, "Failed to record decision");
     }
 
+    // Keep deposits short of finality so confirmation updates can release them
+    let held = !monitor_only && evidence.iter().any(|e| e.key == FINALITY_EVIDENCE_KEY);
+    if held {
+        let deposit = PendingDeposit {
+            subject_id,
+            event: event.clone(),
+            request_id: decision_record.request_id.clone(),
+            request: decision_record.request.clone(),
+            streaming_decision,
+            streaming_evidence: evidence[streaming_from..].to_vec(),
+            held_at: Utc::now(),
+        };
+        if let Err(e) = state.storage.save_pending_deposit(&deposit).await {
+            warn!(user_id = user_id, error = %e, "Failed to save pending deposit");
+        }
+    }
+    timings.record(Phase::Persistence, phase_start);
+
     // Check latency budget
     let elapsed = start.elapsed();
     if elapsed.as_millis() > state.latency_budget_ms as u128 {
@@ -181,144 +970,3196 @@ async fn handle_decision(
     info!(
         user_id = user_id,
         decision = %final_decision,
+        enforced = !monitor_only,
         latency_ms = elapsed.as_millis(),
         "Decision completed"
     );
 
-    (
-        StatusCode::OK,
-        Json(DecisionResponse::new(
-            final_decision,
-            ruleset.policy_version.clone(),
-            evidence,
-        )),
-    )
+    if monitor_only {
+        return (
+            StatusCode::OK,
+            Json(DecisionResponse::monitor_only(
+                ruleset.policy_version.clone(),
+            )),
+        );
+    }
+
+    let mut response =
+        DecisionResponse::new(final_decision, ruleset.policy_version.clone(), evidence)
+            .with_actions(&ruleset);
+    if held {
+        response.event_id = Some(event.event_id.0.clone());
+    }
+
+    (StatusCode::OK, Json(response))
 }
 
-/// Health check endpoint.
-async fn handle_health(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let ruleset = state.ruleset_rx.borrow();
+/// Evaluate the stateless rules that are not switched off or gated by a
+/// feature the request did not enable, and screen the internal blocklist,
+/// returning the most severe decision and the evidence of every hit.
+///
+/// Shares `inline_engine::evaluate` with embedded evaluation, so both
+/// decide alike on the policy's rules.
+fn evaluate_inline(
+    ruleset: &RuleSet,
+    state: &AppState,
+    event: &TxEvent,
+    mut trace: Option<&mut Vec<RuleTrace>>,
+) -> (Decision, Vec<Evidence>) {
+    let switches = &state.rule_switches;
+    let mut verdict = inline_engine::evaluate(
+        &ruleset.inline_for(event),
+        event,
+        |id| switches.is_disabled(id) || !ruleset.features.allows(id, &event.features),
+        |id, result| {
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.push(match result {
+                    Some(result) => RuleTrace::evaluated(id, result),
+                    None if switches.is_disabled(id) => {
+                        RuleTrace::skipped(id, RuleOutcome::Disabled)
+                    }
+                    None => RuleTrace::skipped(id, RuleOutcome::Experimental),
+                });
+            }
+        },
+    );
 
-    Json(HealthResponse {
-        status: "healthy".to_string(),
-        version: state.version.clone(),
-        policy_version: ruleset.policy_version.clone(),
-        uptime_secs: state.start_time.elapsed().as_secs(),
-    })
+    if !state.blocklist.is_empty() {
+        let result = state.blocklist.evaluate(event);
+        if let Some(trace) = trace {
+            trace.push(RuleTrace::evaluated(BLOCKLIST_RULE_ID, &result));
+        }
+        if result.hit {
+            verdict.decision = verdict.decision.max(result.decision);
+            verdict.evidence.extend(result.evidence);
+        }
+    }
+
+    (verdict.decision, verdict.evidence)
 }
 
-/// Readiness check endpoint.
-async fn handle_ready(State(state): State<Arc<AppState>>) -> axum::response::Response {
-    let ruleset = state.ruleset_rx.borrow();
+/// Response when stateful rules cannot run because storage is unavailable.
+fn degraded_response(
+    mode: DegradedMode,
+    inline_decision: Decision,
+    evidence: Vec<Evidence>,
+    ruleset: &RuleSet,
+    monitor_only: bool,
+) -> (StatusCode, Json<DecisionResponse>) {
+    let policy_version = ruleset.policy_version.clone();
+    let response = if monitor_only {
+        DecisionResponse::monitor_only(policy_version)
+    } else {
+        let decision = match mode {
+            DegradedMode::FailOpen => Decision::Allow,
+            DegradedMode::FailClosed => inline_decision.max(Decision::SoftDenyRetry),
+            DegradedMode::InlineOnly => inline_decision,
+        };
+        DecisionResponse::new(decision, policy_version, evidence).with_actions(ruleset)
+    };
 
-    // Check if we have rules loaded
-    if ruleset.inline.is_empty() && ruleset.streaming.is_empty() {
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(response))
+}
+
+/// Screen a batch of addresses against the current sanctions list.
+#[utoipa::path(
+    post,
+    path = "/v1/screening/addresses",
+    tag = "screening",
+    request_body = ScreeningRequest,
+    responses(
+        (status = 200, description = "Screening results in request order", body = ScreeningResponse),
+        (status = 400, description = "Too many addresses", body = ErrorResponse),
+    )
+)]
+async fn handle_screening(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<ScreeningRequest>,
+) -> axum::response::Response {
+    if req.addresses.len() > MAX_SCREENING_ADDRESSES {
         return (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse::new("No rules loaded", "NOT_READY")),
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(format!(
+                "At most {} addresses per request",
+                MAX_SCREENING_ADDRESSES
+            ))),
         )
             .into_response();
     }
 
-    (
-        StatusCode::OK,
-        Json(ReadyResponse {
-            ready: true,
-            policy_version: ruleset.policy_version.clone(),
-            inline_rules: ruleset.inline.len(),
-            streaming_rules: ruleset.streaming.len(),
-        }),
-    )
-        .into_response()
-}
+    let sanctions = state.ruleset_rx.borrow().sanctions.clone();
 
-/// Metrics endpoint (Prometheus format).
-async fn handle_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
-    let ruleset = state.ruleset_rx.borrow();
+    let results = req
+        .addresses
+        .into_iter()
+        .map(|address| AddressScreening {
+            sanctioned: sanctions.contains(&address),
+            address,
+            list: sanctions.name().to_string(),
+            list_version: sanctions.version().to_string(),
+        })
+        .collect();
+
+    (StatusCode::OK, Json(ScreeningResponse { results })).into_response()
+}
+
+/// Update a subject's KYC tier out-of-band.
+#[utoipa::path(
+    post,
+    path = "/v1/subjects/{user_id}/kyc",
+    tag = "subjects",
+    request_body = KycUpdateRequest,
+    params(("user_id" = String, Path, description = "Subject's user ID")),
+    responses(
+        (status = 204, description = "Tier updated"),
+        (status = 404, description = "Unknown subject", body = ErrorResponse),
+    )
+)]
+async fn handle_kyc_update(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Json(req): Json<KycUpdateRequest>,
+) -> axum::response::Response {
+    match state.storage.set_kyc_tier(&user_id, &req.kyc_level).await {
+        Ok(true) => {
+            info!(user_id = %user_id, kyc_level = %req.kyc_level, "KYC tier updated");
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                format!("Unknown subject: {}", user_id),
+                ErrorCode::NotFound,
+            )),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!(user_id = %user_id, error = %e, "Failed to update KYC tier");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to update KYC tier")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Report confirmations for a deposit held pending finality.
+///
+/// Inline rules are rerun against the current policy with the new count.
+/// Once the deposit is final its decision combines them with the streaming
+/// outcome recorded when it was held, and is emitted as a decision event.
+#[utoipa::path(
+    post,
+    path = "/v1/events/{event_id}/confirmations",
+    tag = "decisions",
+    request_body = ConfirmationUpdate,
+    params(("event_id" = String, Path, description = "Event ID returned for the held deposit")),
+    responses(
+        (status = 200, description = "Deposit resolved", body = ConfirmationResponse),
+        (status = 202, description = "Deposit still pending", body = ConfirmationResponse),
+        (status = 404, description = "No deposit held for the event", body = ErrorResponse),
+    )
+)]
+async fn handle_confirmations(
+    State(state): State<Arc<AppState>>,
+    Path(event_id): Path<String>,
+    Json(update): Json<ConfirmationUpdate>,
+) -> axum::response::Response {
+    let start = Instant::now();
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                format!("No deposit held for event: {}", event_id),
+                ErrorCode::NotFound,
+            )),
+        )
+            .into_response()
+    };
+    let storage_error = |e: anyhow::Error| {
+        warn!(event_id = %event_id, error = %e, "Failed to update held deposit");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::internal_error(
+                "Failed to update held deposit",
+            )),
+        )
+            .into_response()
+    };
+
+    let mut deposit = match state.storage.get_pending_deposit(&event_id).await {
+        Ok(Some(deposit)) => deposit,
+        Ok(None) => return not_found(),
+        Err(e) => return storage_error(e),
+    };
+
+    // Confirmations only grow; a stale report must not undo a newer one
+    deposit.event.confirmations = deposit.event.confirmations.max(update.confirmations);
+    let confirmations = deposit.event.confirmations;
+
+    let ruleset = state.ruleset_rx.borrow().clone();
+    let (inline_decision, mut evidence) = evaluate_inline(&ruleset, &state, &deposit.event, None);
+
+    if evidence.iter().any(|e| e.key == FINALITY_EVIDENCE_KEY) {
+        if let Err(e) = state.storage.save_pending_deposit(&deposit).await {
+            return storage_error(e);
+        }
+        return (
+            StatusCode::ACCEPTED,
+            Json(ConfirmationResponse {
+                event_id,
+                status: DepositStatus::Pending,
+                confirmations,
+                decision: None,
+            }),
+        )
+            .into_response();
+    }
+
+    let mut final_decision = inline_decision.max(deposit.streaming_decision);
+    evidence.extend(deposit.streaming_evidence);
+    ruleset
+        .mitigations
+        .apply(&deposit.event, &mut final_decision, &mut evidence);
+
+    let decision_record = DecisionRecord {
+        subject_id: Some(deposit.subject_id),
+        request_id: deposit.request_id,
+        request: deposit.request,
+        decision: final_decision,
+        decision_code: evidence
+            .first()
+            .map(|e| e.rule_id.clone())
+            .unwrap_or_else(|| "OK".to_string()),
+        policy_version: ruleset.policy_version.clone(),
+        evidence: evidence.clone(),
+        latency_ms: start.elapsed().as_millis() as u32,
+    };
+    let decision_event = DecisionEvent::new(
+        EventId::from_string(event_id.as_str()),
+        final_decision,
+        ruleset.policy_version.clone(),
+        evidence,
+    );
+
+    persistence_started();
+    match state
+        .storage
+        .resolve_pending_deposit(&event_id, &decision_record, &decision_event)
+        .await
+    {
+        Ok(Some(_)) => {}
+        // Resolved concurrently by another update
+        Ok(None) => return not_found(),
+        Err(e) => return storage_error(e),
+    }
+
+    let status = if final_decision == Decision::Allow {
+        DepositStatus::Released
+    } else {
+        DepositStatus::Escalated
+    };
+    info!(
+        event_id = %event_id,
+        decision = %final_decision,
+        confirmations = confirmations,
+        "Held deposit resolved"
+    );
+
+    (
+        StatusCode::OK,
+        Json(ConfirmationResponse {
+            event_id,
+            status,
+            confirmations,
+            decision: Some(decision_event),
+        }),
+    )
+        .into_response()
+}
+
+/// Diff two recorded policy versions.
+async fn handle_policy_diff(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PolicyDiffQuery>,
+) -> axum::response::Response {
+    let (from, to) = match tokio::try_join!(
+        state.storage.get_policy(&query.from),
+        state.storage.get_policy(&query.to),
+    ) {
+        Ok(policies) => policies,
+        Err(e) => {
+            warn!(error = %e, "Failed to load policies for diff");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to load policies")),
+            )
+                .into_response();
+        }
+    };
+
+    match (from, to) {
+        (Some(from), Some(to)) => {
+            (StatusCode::OK, Json(PolicyDiff::between(&from, &to))).into_response()
+        }
+        (from, _) => {
+            let missing = if from.is_none() {
+                &query.from
+            } else {
+                &query.to
+            };
+            (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(
+                    format!("Unknown policy version: {}", missing),
+                    ErrorCode::NotFound,
+                )),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Show a subject's daily volume window, its limit utilization and its
+/// decisions as they stood at `at`, rebuilt from stored transactions.
+///
+/// The window and limit come from the policy behind the subject's last
+/// decision before `at` unless a version is given, so a dispute is judged
+/// against the rules that were live at the time.
+async fn handle_subject_as_of(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(query): Query<AsOfQuery>,
+) -> axum::response::Response {
+    let subject_id = match find_subject(&state, &user_id).await {
+        Ok((subject_id, _)) => subject_id,
+        Err(response) => return response,
+    };
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AS_OF_DECISIONS)
+        .clamp(1, MAX_AS_OF_DECISIONS);
+    let decisions = match state
+        .storage
+        .get_subject_decisions(subject_id, query.at, limit)
+        .await
+    {
+        Ok(decisions) => decisions,
+        Err(e) => {
+            warn!(user_id = %user_id, error = %e, "Failed to load subject decisions");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to load decisions")),
+            )
+                .into_response();
+        }
+    };
+
+    let policy_version = query
+        .policy_version
+        .or_else(|| decisions.first().map(|d| d.record.policy_version.clone()))
+        .unwrap_or_else(|| state.ruleset_rx.borrow().policy_version.clone());
+    let policy = match state.storage.get_policy(&policy_version).await {
+        Ok(Some(policy)) => policy,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(
+                    format!("Unknown policy version: {}", policy_version),
+                    ErrorCode::NotFound,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            warn!(version = %policy_version, error = %e, "Failed to load policy");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to load policy")),
+            )
+                .into_response();
+        }
+    };
+
+    let lookback = DayWindow::from_params(&policy.params)
+        .with_clock(Arc::new(FixedClock(query.at)))
+        .lookback();
+    let volume = match state
+        .storage
+        .get_rolling_volume_at(subject_id, lookback, query.at)
+        .await
+    {
+        Ok(volume) => volume,
+        Err(e) => {
+            warn!(user_id = %user_id, error = %e, "Failed to rebuild rolling volume");
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error(
+                    "Failed to rebuild rolling volume",
+                )),
+            )
+                .into_response();
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(SubjectAsOfResponse {
+            user_id,
+            at: query.at,
+            policy_version,
+            window: VolumeWindow::new(
+                query.at - lookback,
+                query.at,
+                volume,
+                policy.params.daily_volume_limit_usd,
+            ),
+            decisions: decisions.into_iter().map(final_decision_event).collect(),
+        }),
+    )
+        .into_response()
+}
+
+/// List policy candidates rejected by recent loads, newest first.
+async fn handle_failed_policies(
+    State(state): State<Arc<AppState>>,
+) -> Json<FailedPoliciesResponse> {
+    Json(FailedPoliciesResponse {
+        failed: state.failed_policies.list(),
+    })
+}
+
+/// Show how screening resolves an address: its normalized form, the bloom
+/// filter and set results, and the list it matched. The check is not
+/// counted in the sanctions statistics.
+async fn handle_sanctions_check(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+) -> Json<SanctionsCheckResponse> {
+    let sanctions = state.ruleset_rx.borrow().sanctions.clone();
+    let trace = sanctions.trace(&address);
+    let blocklisted = state.blocklist.contains(&address);
+
+    let matched_list = if trace.sanctioned {
+        Some(sanctions.name().to_string())
+    } else if blocklisted {
+        Some(BLOCKLIST_RULE_ID.to_string())
+    } else {
+        None
+    };
+    let stats = sanctions.stats();
+
+    Json(SanctionsCheckResponse {
+        address,
+        trace,
+        blocklisted,
+        matched_list,
+        list: sanctions.name().to_string(),
+        list_version: sanctions.version().to_string(),
+        compiled: sanctions.is_compiled(),
+        observed_fp_rate: stats.observed_fp_rate(),
+        stats,
+    })
+}
+
+/// List cases, most recently updated first.
+async fn handle_list_cases(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CaseQuery>,
+) -> axum::response::Response {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_CASE_LIMIT)
+        .min(MAX_CASE_LIMIT);
+
+    match state.storage.list_cases(query.status, limit).await {
+        Ok(cases) => (StatusCode::OK, Json(CasesResponse { cases })).into_response(),
+        Err(e) => {
+            warn!(error = %e, "Failed to list cases");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to list cases")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Open a case for a known subject by hand.
+async fn handle_create_case(
+    State(state): State<Arc<AppState>>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<CreateCaseRequest>,
+) -> axum::response::Response {
+    let created = match state.storage.get_subject_by_user_id(&req.user_id).await {
+        Ok(Some((subject_id, _))) => {
+            state
+                .storage
+                .create_case(subject_id, req.note.as_deref())
+                .await
+        }
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(
+                    format!("Unknown subject: {}", req.user_id),
+                    ErrorCode::NotFound,
+                )),
+            )
+                .into_response()
+        }
+        Err(e) => Err(e),
+    };
+
+    match created {
+        Ok(Some(case)) => {
+            info!(case_id = %case.id, user_id = %req.user_id, actor = %principal.actor, "Case opened");
+            (StatusCode::CREATED, Json(case)).into_response()
+        }
+        Ok(None) => (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new(
+                format!("Subject already has an active case: {}", req.user_id),
+                ErrorCode::Conflict,
+            )),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!(user_id = %req.user_id, error = %e, "Failed to open case");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to open case")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Get a case with its linked decisions and its subject's annotations.
+async fn handle_get_case(
+    State(state): State<Arc<AppState>>,
+    Path(case_id): Path<Uuid>,
+) -> axum::response::Response {
+    let loaded = match state.storage.get_case(case_id).await {
+        Ok(Some(case)) => state
+            .storage
+            .get_annotations(case.subject_id, DEFAULT_ANNOTATION_LIMIT)
+            .await
+            .map(|annotations| CaseResponse { case, annotations }),
+        Ok(None) => return case_not_found(case_id),
+        Err(e) => Err(e),
+    };
+
+    match loaded {
+        Ok(case) => (StatusCode::OK, Json(case)).into_response(),
+        Err(e) => {
+            warn!(case_id = %case_id, error = %e, "Failed to load case");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to load case")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Move a case through its lifecycle. Closed cases cannot be reopened.
+async fn handle_update_case_status(
+    State(state): State<Arc<AppState>>,
+    Path(case_id): Path<Uuid>,
+    Extension(principal): Extension<Principal>,
+    Json(update): Json<CaseStatusUpdate>,
+) -> axum::response::Response {
+    let storage_error = |e: anyhow::Error| {
+        warn!(case_id = %case_id, error = %e, "Failed to update case");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::internal_error("Failed to update case")),
+        )
+            .into_response()
+    };
+
+    let case = match state.storage.get_case(case_id).await {
+        Ok(Some(case)) => case,
+        Ok(None) => return case_not_found(case_id),
+        Err(e) => return storage_error(e),
+    };
+
+    let conflict = |from: CaseStatus| {
+        (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new(
+                format!("Case cannot move from {} to {}", from, update.status),
+                ErrorCode::InvalidTransition,
+            )),
+        )
+            .into_response()
+    };
+    if !case.status.can_transition_to(update.status) {
+        return conflict(case.status);
+    }
+
+    match state
+        .storage
+        .update_case_status(case_id, case.status, update.status, update.note.as_deref())
+        .await
+    {
+        Ok(Some(updated)) => {
+            info!(
+                case_id = %case_id,
+                from = %case.status,
+                to = %updated.status,
+                actor = %principal.actor,
+                "Case status changed"
+            );
+            (StatusCode::OK, Json(updated)).into_response()
+        }
+        // Changed by someone else since it was read
+        Ok(None) => conflict(case.status),
+        Err(e) => storage_error(e),
+    }
+}
+
+fn case_not_found(case_id: Uuid) -> axum::response::Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(ErrorResponse::new(
+            format!("Unknown case: {}", case_id),
+            ErrorCode::NotFound,
+        )),
+    )
+        .into_response()
+}
+
+/// Look up a subject by user ID, or the response to return when it is
+/// unknown or the lookup fails.
+async fn find_subject(
+    state: &AppState,
+    user_id: &str,
+) -> Result<(Uuid, Subject), axum::response::Response> {
+    match state.storage.get_subject_by_user_id(user_id).await {
+        Ok(Some(found)) => Ok(found),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                format!("Unknown subject: {}", user_id),
+                ErrorCode::NotFound,
+            )),
+        )
+            .into_response()),
+        Err(e) => {
+            warn!(user_id = %user_id, error = %e, "Failed to look up subject");
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to look up subject")),
+            )
+                .into_response())
+        }
+    }
+}
+
+fn annotations_error(user_id: &str, e: anyhow::Error) -> axum::response::Response {
+    warn!(user_id = %user_id, error = %e, "Failed to load annotations");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse::internal_error("Failed to load annotations")),
+    )
+        .into_response()
+}
+
+/// Get a subject's stored details with its most recent annotations.
+async fn handle_get_subject(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+) -> axum::response::Response {
+    let (subject_id, subject) = match find_subject(&state, &user_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    match state
+        .storage
+        .get_annotations(subject_id, DEFAULT_ANNOTATION_LIMIT)
+        .await
+    {
+        Ok(annotations) => (
+            StatusCode::OK,
+            Json(SubjectProfileResponse {
+                subject,
+                annotations: AnnotationsResponse::new(annotations),
+            }),
+        )
+            .into_response(),
+        Err(e) => annotations_error(&user_id, e),
+    }
+}
+
+/// List a subject's annotations, newest first.
+async fn handle_list_annotations(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(query): Query<AnnotationQuery>,
+) -> axum::response::Response {
+    let (subject_id, _) = match find_subject(&state, &user_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ANNOTATION_LIMIT)
+        .clamp(1, MAX_ANNOTATION_LIMIT);
+
+    match state.storage.get_annotations(subject_id, limit).await {
+        Ok(annotations) => {
+            (StatusCode::OK, Json(AnnotationsResponse::new(annotations))).into_response()
+        }
+        Err(e) => annotations_error(&user_id, e),
+    }
+}
+
+/// Attach a note, and optionally a risk rating and tags, to a subject.
+async fn handle_add_annotation(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<AnnotationRequest>,
+) -> axum::response::Response {
+    let note = req.note.trim();
+    let invalid = if note.is_empty() {
+        Some("note must not be empty".to_string())
+    } else if note.len() > MAX_ANNOTATION_NOTE_BYTES {
+        Some(format!(
+            "note must be at most {} bytes",
+            MAX_ANNOTATION_NOTE_BYTES
+        ))
+    } else if req.tags.len() > MAX_ANNOTATION_TAGS {
+        Some(format!(
+            "At most {} tags per annotation",
+            MAX_ANNOTATION_TAGS
+        ))
+    } else if req.tags.iter().any(|t| t.trim().is_empty()) {
+        Some("tags must not be empty".to_string())
+    } else {
+        None
+    };
+    if let Some(message) = invalid {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(message)),
+        )
+            .into_response();
+    }
+
+    let (subject_id, _) = match find_subject(&state, &user_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+    let annotation = Annotation {
+        id: Uuid::new_v4(),
+        subject_id,
+        note: note.to_string(),
+        risk_rating: req.risk_rating,
+        tags: req.tags.iter().map(|t| t.trim().to_string()).collect(),
+        actor: principal.actor.clone(),
+        created_at: Utc::now(),
+    };
+
+    match state.storage.add_annotation(&annotation).await {
+        Ok(()) => {
+            info!(
+                user_id = %user_id,
+                annotation_id = %annotation.id,
+                risk_rating = ?annotation.risk_rating,
+                actor = %principal.actor,
+                "Subject annotated"
+            );
+            (StatusCode::CREATED, Json(annotation)).into_response()
+        }
+        Err(e) => {
+            warn!(user_id = %user_id, error = %e, "Failed to add annotation");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to add annotation")),
+            )
+                .into_response()
+        }
+    }
+}
+
+fn addresses_error(user_id: &str, e: anyhow::Error) -> axum::response::Response {
+    warn!(user_id = %user_id, error = %e, "Failed to update addresses");
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse::internal_error("Failed to update addresses")),
+    )
+        .into_response()
+}
+
+/// List the addresses linked to a subject, oldest first.
+async fn handle_list_addresses(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+) -> axum::response::Response {
+    let (subject_id, _) = match find_subject(&state, &user_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    match state.storage.get_subject_addresses(subject_id).await {
+        Ok(addresses) => {
+            (StatusCode::OK, Json(SubjectAddressesResponse { addresses })).into_response()
+        }
+        Err(e) => {
+            warn!(user_id = %user_id, error = %e, "Failed to load addresses");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to load addresses")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Link an address to a subject, or update the verified flag or label of
+/// one already linked.
+async fn handle_save_address(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Extension(principal): Extension<Principal>,
+    Json(req): Json<SubjectAddressRequest>,
+) -> axum::response::Response {
+    let address = req.address.trim();
+    let label = req.label.as_deref().map(str::trim);
+    let invalid = if address.is_empty() {
+        Some("address must not be empty".to_string())
+    } else if label.is_some_and(|l| l.len() > MAX_ADDRESS_LABEL_BYTES) {
+        Some(format!(
+            "label must be at most {} bytes",
+            MAX_ADDRESS_LABEL_BYTES
+        ))
+    } else {
+        None
+    };
+    if let Some(message) = invalid {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(message)),
+        )
+            .into_response();
+    }
+
+    let (subject_id, _) = match find_subject(&state, &user_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    match state
+        .storage
+        .save_subject_address(subject_id, address, req.verified, label)
+        .await
+    {
+        Ok(saved) => {
+            info!(
+                user_id = %user_id,
+                address = %saved.address,
+                verified = saved.verified,
+                actor = %principal.actor,
+                "Subject address saved"
+            );
+            (StatusCode::OK, Json(saved)).into_response()
+        }
+        Err(e) => addresses_error(&user_id, e),
+    }
+}
+
+/// Unlink an address from a subject.
+async fn handle_remove_address(
+    State(state): State<Arc<AppState>>,
+    Path((user_id, address)): Path<(String, String)>,
+    Extension(principal): Extension<Principal>,
+) -> axum::response::Response {
+    let (subject_id, _) = match find_subject(&state, &user_id).await {
+        Ok(found) => found,
+        Err(response) => return response,
+    };
+
+    match state
+        .storage
+        .remove_subject_address(subject_id, &address)
+        .await
+    {
+        Ok(true) => {
+            info!(
+                user_id = %user_id,
+                address = %address,
+                actor = %principal.actor,
+                "Subject address removed"
+            );
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                format!("Address {} is not linked to {}", address, user_id),
+                ErrorCode::NotFound,
+            )),
+        )
+            .into_response(),
+        Err(e) => addresses_error(&user_id, e),
+    }
+}
+
+/// Describe the rules of the active policy: their type, action and
+/// parameters. Sanctions entries and provider credentials are left out.
+#[utoipa::path(
+    get,
+    path = "/v1/rules",
+    tag = "admin",
+    responses((status = 200, description = "Active rules", body = RulesResponse))
+)]
+async fn handle_list_rules(State(state): State<Arc<AppState>>) -> Json<RulesResponse> {
+    let ruleset = state.ruleset_rx.borrow().clone();
+    let info = |kind: RuleKind, description: RuleDescription| RuleInfo {
+        disabled: state.rule_switches.is_disabled(&description.id),
+        kind,
+        description,
+    };
+
+    let rules = ruleset
+        .inline
+        .iter()
+        .map(|rule| info(RuleKind::Inline, rule.describe()))
+        .chain(
+            ruleset
+                .streaming
+                .iter()
+                .map(|rule| info(RuleKind::Streaming, rule.describe())),
+        )
+        .collect();
+
+    Json(RulesResponse {
+        policy_version: ruleset.policy_version.clone(),
+        rules,
+    })
+}
+
+/// Turn a rule off until it is enabled again, without a policy publish.
+///
+/// The switch applies to this replica at once. It is then persisted, which
+/// notifies the other replicas; if that fails it stays local.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/rules/{rule_id}/disable",
+    tag = "admin",
+    request_body(content = RuleSwitchRequest, description = "Optional reason; the body may be omitted"),
+    params(("rule_id" = String, Path, description = "Rule ID in the active policy")),
+    responses(
+        (status = 200, description = "Rule disabled", body = RuleSwitchResponse),
+        (status = 404, description = "Unknown rule", body = ErrorResponse),
+    )
+)]
+async fn handle_disable_rule(
+    State(state): State<Arc<AppState>>,
+    Path(rule_id): Path<String>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(principal): Extension<Principal>,
+    body: Option<Json<RuleSwitchRequest>>,
+) -> axum::response::Response {
+    let known = {
+        let ruleset = state.ruleset_rx.borrow();
+        ruleset.inline.iter().any(|r| r.id() == rule_id)
+            || ruleset.streaming.iter().any(|r| r.id() == rule_id)
+    };
+    if !known {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(
+                format!("Unknown rule: {}", rule_id),
+                ErrorCode::NotFound,
+            )),
+        )
+            .into_response();
+    }
+
+    let switch = RuleSwitch {
+        rule_id: rule_id.clone(),
+        disabled_at: Utc::now(),
+        actor: principal.actor.clone(),
+        reason: body.and_then(|Json(b)| b.reason),
+    };
+    state.rule_switches.disable(switch.clone());
+    warn!(
+        rule_id = %rule_id,
+        actor = %switch.actor,
+        request_id = %request_id.0,
+        reason = switch.reason.as_deref().unwrap_or(""),
+        "Rule disabled by kill-switch"
+    );
+
+    let persisted = match state.storage.disable_rule(&switch).await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!(rule_id = %rule_id, error = %e, "Failed to persist rule switch; applied locally only");
+            false
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(RuleSwitchResponse {
+            rule_id,
+            disabled: true,
+            persisted,
+        }),
+    )
+        .into_response()
+}
+
+/// Turn a rule switched off by [`handle_disable_rule`] back on.
+///
+/// Rules no longer in the policy can still be enabled, so stale switches
+/// can be cleared.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/rules/{rule_id}/enable",
+    tag = "admin",
+    params(("rule_id" = String, Path, description = "Rule ID")),
+    responses((status = 200, description = "Rule enabled", body = RuleSwitchResponse))
+)]
+async fn handle_enable_rule(
+    State(state): State<Arc<AppState>>,
+    Path(rule_id): Path<String>,
+    Extension(request_id): Extension<RequestId>,
+    Extension(principal): Extension<Principal>,
+) -> axum::response::Response {
+    if state.rule_switches.enable(&rule_id) {
+        info!(
+            rule_id = %rule_id,
+            actor = %principal.actor,
+            request_id = %request_id.0,
+            "Rule re-enabled by kill-switch"
+        );
+    }
+
+    let persisted = match state.storage.enable_rule(&rule_id, &principal.actor).await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!(rule_id = %rule_id, error = %e, "Failed to persist rule switch; applied locally only");
+            false
+        }
+    };
+
+    (
+        StatusCode::OK,
+        Json(RuleSwitchResponse {
+            rule_id,
+            disabled: false,
+            persisted,
+        }),
+    )
+        .into_response()
+}
+
+/// Report decision and rule-trigger counts per tenant.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/usage",
+    tag = "admin",
+    params(UsageQuery),
+    responses(
+        (status = 200, description = "Usage per tenant", body = UsageResponse),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+    )
+)]
+async fn handle_usage(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<UsageQuery>,
+) -> axum::response::Response {
+    if query.from > query.to {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request("from must not be after to")),
+        )
+            .into_response();
+    }
+
+    match state.storage.get_usage(query.from, query.to).await {
+        Ok(daily) => (
+            StatusCode::OK,
+            Json(UsageResponse::new(query.from, query.to, daily)),
+        )
+            .into_response(),
+        Err(e) => {
+            warn!(error = %e, "Failed to load tenant usage");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to load usage")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Summarize this instance's recent decisions: counts by outcome, the most
+/// frequently triggered rules, latency percentiles and time spent degraded.
+#[utoipa::path(
+    get,
+    path = "/v1/stats/decisions",
+    tag = "decisions",
+    params(StatsQuery),
+    responses(
+        (status = 200, description = "Decisions in the window", body = DecisionSummary),
+    )
+)]
+async fn handle_decision_stats(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StatsQuery>,
+) -> Json<DecisionSummary> {
+    Json(state.metrics.stats.summary(query.window))
+}
+
+/// Download subjects' out-of-band KYC tiers.
+async fn handle_export_overrides(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListExportQuery>,
+) -> axum::response::Response {
+    let result = lists::export_overrides(state.storage.as_ref(), query.format).await;
+    list_download("overrides", query.format, result)
+}
+
+/// Download the internal blocklist.
+async fn handle_export_blocklist(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListExportQuery>,
+) -> axum::response::Response {
+    let result = lists::export_blocklist(state.storage.as_ref(), query.format).await;
+    list_download("blocklist", query.format, result)
+}
+
+fn list_download(
+    name: &str,
+    format: ListFormat,
+    result: anyhow::Result<String>,
+) -> axum::response::Response {
+    match result {
+        Ok(body) => {
+            let disposition = format!("attachment; filename=\"{}.{}\"", name, format.extension());
+            let headers = [
+                (header::CONTENT_TYPE, format.content_type().to_string()),
+                (header::CONTENT_DISPOSITION, disposition),
+            ];
+            (headers, body).into_response()
+        }
+        Err(e) => {
+            warn!(list = name, error = %e, "Failed to export list");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to export list")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Set subjects' KYC tiers from an uploaded file.
+///
+/// Tiers are checked against the active policy's ranking when it can be
+/// read. Nothing is applied unless every row is valid.
+async fn handle_import_overrides(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListImportQuery>,
+    body: String,
+) -> axum::response::Response {
+    let policy = match state.storage.get_active_policy().await {
+        Ok(policy) => policy,
+        Err(e) => {
+            warn!(error = %e, "Failed to load active policy; KYC tiers not checked");
+            None
+        }
+    };
+    let tiers = policy.as_ref().map(|p| &p.params.kyc_tiers);
+
+    let result = lists::import_overrides(
+        state.storage.as_ref(),
+        query.format,
+        &body,
+        tiers,
+        query.dry_run,
+    )
+    .await;
+    if let Ok(report) = &result {
+        if report.applied > 0 {
+            info!(applied = report.applied, "Imported KYC overrides");
+        }
+    }
+    import_response("overrides", result)
+}
+
+/// Add addresses to the internal blocklist from an uploaded file, or with
+/// `replace` make the file the whole list.
+///
+/// The blocklist applies to this replica once stored; other replicas
+/// reload it when notified.
+async fn handle_import_blocklist(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListImportQuery>,
+    Extension(principal): Extension<Principal>,
+    body: String,
+) -> axum::response::Response {
+    let result = lists::import_blocklist(
+        state.storage.as_ref(),
+        query.format,
+        &body,
+        &principal.actor,
+        query.replace,
+        query.dry_run,
+    )
+    .await;
+    if let Ok(report) = &result {
+        if report.applied > 0 {
+            warn!(
+                applied = report.applied,
+                replace = query.replace,
+                actor = %principal.actor,
+                "Imported internal blocklist"
+            );
+            if let Err(e) = state.blocklist.load(state.storage.as_ref()).await {
+                warn!(error = %e, "Failed to reload internal blocklist");
+            }
+        }
+    }
+    import_response("blocklist", result)
+}
+
+/// Report for an import: `422` if any row was rejected.
+fn import_response(
+    name: &str,
+    result: Result<ImportReport, ImportError>,
+) -> axum::response::Response {
+    match result {
+        Ok(report) if report.is_rejected() => {
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(report)).into_response()
+        }
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(ImportError::Malformed(e)) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(format!("Malformed file: {}", e))),
+        )
+            .into_response(),
+        Err(ImportError::Storage(e)) => {
+            warn!(list = name, error = %e, "Failed to import list");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::internal_error("Failed to import list")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Download decisions for a range of UTC days.
+///
+/// CSV is streamed page by page; Parquet is built in memory because the
+/// footer is written last.
+async fn handle_export_decisions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ExportQuery>,
+) -> axum::response::Response {
+    if query.from > query.to {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request("from must not be after to")),
+        )
+            .into_response();
+    }
+
+    let disposition = format!(
+        "attachment; filename=\"decisions-{}-{}.{}\"",
+        query.from,
+        query.to,
+        query.format.extension()
+    );
+    let headers = [
+        (
+            header::CONTENT_TYPE,
+            query.format.content_type().to_string(),
+        ),
+        (header::CONTENT_DISPOSITION, disposition),
+    ];
+
+    match query.format {
+        ExportFormat::Csv => {
+            let stream = export::csv_stream(
+                state.storage.clone(),
+                query.from,
+                query.to,
+                state.export_redactor.clone(),
+            );
+            (headers, Body::from_stream(stream)).into_response()
+        }
+        ExportFormat::Parquet => {
+            let mut buf = Vec::new();
+            match export::export_decisions(
+                state.storage.clone(),
+                query.from,
+                query.to,
+                query.format,
+                &state.export_redactor,
+                &mut buf,
+            )
+            .await
+            {
+                Ok(_) => (headers, buf).into_response(),
+                Err(e) => {
+                    warn!(error = %e, "Failed to export decisions");
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ErrorResponse::internal_error("Failed to export decisions")),
+                    )
+                        .into_response()
+                }
+            }
+        }
+    }
+}
+
+/// Health check endpoint.
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Healthy", body = HealthResponse),
+        (status = 503, description = "Liveness check failed", body = HealthResponse),
+    )
+)]
+async fn handle_health(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let policy_version = state.ruleset_rx.borrow().policy_version.clone();
+    let (status, label) = if state.watchdog.is_healthy() {
+        (StatusCode::OK, "healthy")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "unhealthy")
+    };
+
+    (
+        status,
+        Json(HealthResponse {
+            status: label.to_string(),
+            version: state.version.clone(),
+            policy_version,
+            uptime_secs: state.start_time.elapsed().as_secs(),
+            liveness: state.watchdog.last(),
+        }),
+    )
+}
+
+/// Readiness check endpoint.
+async fn handle_ready(State(state): State<Arc<AppState>>) -> axum::response::Response {
+    let ruleset = state.ruleset_rx.borrow();
+
+    // Check if we have rules loaded
+    if ruleset.inline.is_empty() && ruleset.streaming.is_empty() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new("No rules loaded", ErrorCode::NotReady)),
+        )
+            .into_response();
+    }
+
+    (
+        StatusCode::OK,
+        Json(ReadyResponse {
+            ready: true,
+            policy_version: ruleset.policy_version.clone(),
+            inline_rules: ruleset.inline.len(),
+            streaming_rules: ruleset.streaming.len(),
+            disabled_rules: state.rule_switches.list(),
+            shadowed_rules: state
+                .rule_sla
+                .as_ref()
+                .map(|sla| sla.shadowed())
+                .unwrap_or_default(),
+        }),
+    )
+        .into_response()
+}
+
+/// Gauge of rules in shadow mode, one series per rule.
+fn shadowed_rules_prometheus(state: &AppState) -> String {
+    let Some(sla) = &state.rule_sla else {
+        return String::new();
+    };
+    let shadowed = sla.shadowed();
+    let mut output = format!(
+        "\n# HELP riskr_rules_shadowed Rules demoted to shadow mode for breaching their SLA\n\
+         # TYPE riskr_rules_shadowed gauge\n\
+         riskr_rules_shadowed {}\n\
+         # HELP riskr_rule_shadowed Set for each rule in shadow mode\n\
+         # TYPE riskr_rule_shadowed gauge\n",
+        shadowed.len()
+    );
+    for rule in shadowed {
+        output.push_str(&format!(
+            "riskr_rule_shadowed{{rule_id=\"{}\"}} 1\n",
+            rule.rule_id
+        ));
+    }
+    output
+}
+
+/// Metrics endpoint (Prometheus format).
+async fn handle_metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let ruleset = state.ruleset_rx.borrow();
+    let sanctions = ruleset.sanctions.stats();
 
     let metrics = format!(
         r#"# HELP riskr_uptime_seconds Application uptime in seconds
 # TYPE riskr_uptime_seconds counter
 riskr_uptime_seconds {}
 
-# HELP riskr_inline_rules Number of inline rules loaded
-# TYPE riskr_inline_rules gauge
-riskr_inline_rules {}
+# HELP riskr_inline_rules Number of inline rules loaded
+# TYPE riskr_inline_rules gauge
+riskr_inline_rules {}
+
+# HELP riskr_streaming_rules Number of streaming rules loaded
+# TYPE riskr_streaming_rules gauge
+riskr_streaming_rules {}
+
+# HELP riskr_sanctions_entries Number of sanctioned addresses loaded
+# TYPE riskr_sanctions_entries gauge
+riskr_sanctions_entries {}
+
+# HELP riskr_sanctions_bloom_bytes Sanctions bloom filter size in bytes
+# TYPE riskr_sanctions_bloom_bytes gauge
+riskr_sanctions_bloom_bytes {}
+
+# HELP riskr_sanctions_bloom_target_fp_rate Configured bloom false positive rate
+# TYPE riskr_sanctions_bloom_target_fp_rate gauge
+riskr_sanctions_bloom_target_fp_rate {}
+
+# HELP riskr_sanctions_checks_total Sanctions membership checks since load
+# TYPE riskr_sanctions_checks_total counter
+riskr_sanctions_checks_total {}
+
+# HELP riskr_sanctions_bloom_hits_total Checks that passed the bloom filter
+# TYPE riskr_sanctions_bloom_hits_total counter
+riskr_sanctions_bloom_hits_total {}
+
+# HELP riskr_sanctions_bloom_false_positives_total Bloom hits not in the sanctions set
+# TYPE riskr_sanctions_bloom_false_positives_total counter
+riskr_sanctions_bloom_false_positives_total {}
+
+# HELP riskr_sanctions_bloom_observed_fp_rate Observed bloom false positive rate
+# TYPE riskr_sanctions_bloom_observed_fp_rate gauge
+riskr_sanctions_bloom_observed_fp_rate {}
+"#,
+        state.start_time.elapsed().as_secs(),
+        ruleset.inline.len(),
+        ruleset.streaming.len(),
+        sanctions.entries,
+        sanctions.bloom_bytes,
+        sanctions.target_fp_rate,
+        sanctions.checks,
+        sanctions.bloom_hits,
+        sanctions.false_positives,
+        sanctions.observed_fp_rate(),
+    ) + &state.metrics.to_prometheus()
+        + &state.watchdog.to_prometheus()
+        + &shadowed_rules_prometheus(&state);
+
+    (
+        StatusCode::OK,
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/plain; charset=utf-8",
+        )],
+        metrics,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::subject::KycTier;
+    use crate::domain::Policy;
+    use crate::rules::{
+        BloomOptions, DailyVolumeRule, FeatureGates, FinalityRule, OfacRule, SanctionsList,
+    };
+    use crate::storage::{MockStorage, StorageRead, StorageWrite};
+    use chrono::{DateTime, Duration};
+    use rust_decimal::Decimal;
+    use std::collections::HashSet;
+    use std::sync::atomic::Ordering;
+
+    fn test_app_state() -> Arc<AppState> {
+        test_app_state_with(Arc::new(MockStorage::new()), false)
+    }
+
+    fn test_app_state_with(storage: Arc<MockStorage>, monitor_only: bool) -> Arc<AppState> {
+        let mut sanctions = HashSet::new();
+        sanctions.insert("0xdead".to_string());
+        let sanctions = Arc::new(SanctionsList::new(sanctions, BloomOptions::default()));
+
+        let inline_rules: Vec<Arc<dyn crate::rules::InlineRule>> = vec![
+            Arc::new(OfacRule::with_list(
+                "R1_OFAC".to_string(),
+                Decision::RejectFatal,
+                sanctions.clone(),
+            )),
+            Arc::new(FinalityRule::new(
+                "R_FINALITY".to_string(),
+                Decision::HoldAuto,
+                HashMap::new(),
+            )),
+        ];
+
+        let streaming_rules: Vec<Arc<dyn crate::rules::StreamingRule>> =
+            vec![Arc::new(DailyVolumeRule::new(
+                "R4_DAILY".to_string(),
+                Decision::HoldAuto,
+                Decimal::new(50000, 0),
+            ))];
+
+        let ruleset = Arc::new(RuleSet {
+            inline: inline_rules,
+            streaming: streaming_rules.clone(),
+            sanctions,
+            policy_version: "test-v1".to_string(),
+            monitor_only: false,
+            annotations: HashMap::from([(
+                "R1_OFAC".to_string(),
+                crate::domain::ActionAnnotations::from([(
+                    "notify_team".to_string(),
+                    serde_json::json!("sanctions-ops"),
+                )]),
+            )]),
+            action_plans: crate::domain::ActionPlans::from([(
+                Decision::RejectFatal,
+                vec![crate::domain::PlannedAction {
+                    action: "freeze_withdrawals".to_string(),
+                    duration_secs: Some(86400),
+                    target: None,
+                }],
+            )]),
+            features: FeatureGates::default(),
+            needs_subject_lookup: false,
+            sweep: HashSet::new(),
+            mitigations: crate::rules::Mitigations::default(),
+            dispatch: crate::rules::RuleDispatch::default(),
+            subject_fields: crate::rules::SubjectFieldCheck::default(),
+        });
+
+        let (_tx, rx) = watch::channel(ruleset);
+        let storage = storage as Arc<dyn Storage>;
+
+        Arc::new(AppState {
+            storage,
+            ruleset_rx: rx,
+            start_time: Instant::now(),
+            version: "0.1.0-test".to_string(),
+            latency_budget_ms: 100,
+            monitor_only,
+            degraded_mode: DegradedMode::FailOpen,
+            subject_enrichment: SubjectEnrichment::new(true, true),
+            metrics: Arc::new(MetricsRegistry::new()),
+            assets: None,
+            tenant_quotas: HashMap::new(),
+            watchdog: Arc::new(WatchdogStatus::new()),
+            export_redactor: Redactor::default(),
+            failed_policies: Arc::new(FailedPolicyLog::default()),
+            rule_switches: Arc::new(RuleSwitches::default()),
+            blocklist: Arc::new(Blocklist::default()),
+            rule_sla: None,
+            admin_auth: AdminAuth::default(),
+            request_signing: None,
+            route_timeouts: RouteTimeouts::default(),
+        })
+    }
+
+    fn decision_request(address: &str) -> axum::http::Request<axum::body::Body> {
+        let body = serde_json::json!({
+            "subject": {
+                "user_id": "U1",
+                "account_id": "A1",
+                "addresses": [address],
+                "geo_iso": "US",
+                "kyc_level": "L1"
+            },
+            "tx": {
+                "type": "withdraw",
+                "asset": "USDC",
+                "usd_value": 100.0
+            }
+        });
+
+        axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/decision/check")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn response_json(response: axum::response::Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint() {
+        let state = test_app_state();
+        let app = create_router(state);
+
+        let response = axum::http::Request::builder()
+            .uri("/health")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        let response = tower::ServiceExt::oneshot(app, response).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_decision_enforced() {
+        let state = test_app_state();
+        let app = create_router(state);
+
+        let response = tower::ServiceExt::oneshot(app, decision_request("0xdead"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["decision"], "REJECT_FATAL");
+        assert_eq!(body["enforced"], true);
+        assert_eq!(body["actions"][0]["rule_id"], "R1_OFAC");
+        assert_eq!(
+            body["actions"][0]["annotations"]["notify_team"],
+            "sanctions-ops"
+        );
+        assert_eq!(
+            body["action_plan"],
+            serde_json::json!([{"action": "freeze_withdrawals", "duration_secs": 86400}])
+        );
+
+        // Decisions without a plan carry none
+        let response =
+            tower::ServiceExt::oneshot(create_router(test_app_state()), decision_request("0xabc"))
+                .await
+                .unwrap();
+        assert!(response_json(response).await.get("action_plan").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_decision_phase_timings() {
+        let state = test_app_state();
+        let app = create_router(state.clone());
+
+        // Timings are only returned when asked for
+        let response = tower::ServiceExt::oneshot(app.clone(), decision_request("0xabc"))
+            .await
+            .unwrap();
+        assert!(response_json(response).await.get("timings").is_none());
+
+        let mut request = decision_request("0xabc");
+        *request.uri_mut() = "/v1/decision/check?debug=true".parse().unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        let body = response_json(response).await;
+
+        let timings = &body["timings"];
+        for phase in [
+            "deserialize_us",
+            "inline_rules_us",
+            "subject_upsert_us",
+            "streaming_rules_us",
+            "persistence_us",
+        ] {
+            assert!(timings[phase].is_u64(), "missing {}", phase);
+        }
+        assert_eq!(state.metrics.phases.count(Phase::StreamingRules), 2);
+    }
+
+    #[tokio::test]
+    async fn test_decision_response_detail() {
+        let app = create_router(test_app_state());
+
+        let mut request = decision_request("0xdead");
+        *request.uri_mut() = "/v1/decision/check?response_detail=minimal"
+            .parse()
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app.clone(), request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response_json(response).await,
+            serde_json::json!({"decision": "REJECT_FATAL", "decision_code": "R1_OFAC"})
+        );
+
+        let mut request = decision_request("0xabc");
+        *request.uri_mut() = "/v1/decision/check?response_detail=full".parse().unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        let body = response_json(response).await;
+        assert!(body["timings"].is_object());
+        assert_eq!(body["trace"][0]["rule_id"], "R1_OFAC");
+        assert_eq!(body["trace"][0]["outcome"], "pass");
+        assert!(body["trace"][0].get("decision").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_decisions_are_counted() {
+        let state = test_app_state();
+        let app = create_router(state.clone());
+
+        tower::ServiceExt::oneshot(app, decision_request("0xdead"))
+            .await
+            .unwrap();
+
+        assert_eq!(state.metrics.decisions_reject.load(Ordering::Relaxed), 1);
+        assert_eq!(state.metrics.slo.window(300).total, 1);
+    }
+
+    #[tokio::test]
+    async fn test_decision_stats() {
+        let state = test_app_state();
+        for address in ["0xdead", "0xabc"] {
+            tower::ServiceExt::oneshot(create_router(state.clone()), decision_request(address))
+                .await
+                .unwrap();
+        }
+
+        let stats = |uri: &str| {
+            tower::ServiceExt::oneshot(
+                create_router(state.clone()),
+                axum::http::Request::builder()
+                    .uri(uri)
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+        };
+
+        let response = stats("/v1/stats/decisions?window=24h").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["window"], "24h");
+        assert_eq!(body["decisions"], 2);
+        assert_eq!(body["outcomes"]["REJECT_FATAL"], 1);
+        assert_eq!(body["outcomes"]["ALLOW"], 1);
+        assert_eq!(body["top_rules"][0]["rule_id"], "R1_OFAC");
+        assert!(body["latency"]["p99_ms"].as_f64().unwrap() > 0.0);
+        assert_eq!(body["degraded_secs"], 0);
+
+        let response = stats("/v1/stats/decisions").await.unwrap();
+        assert_eq!(response_json(response).await["window"], "1h");
+
+        let response = stats("/v1/stats/decisions?window=7d").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_decision_monitor_only() {
+        let storage = Arc::new(MockStorage::new());
+        let state = test_app_state_with(storage.clone(), true);
+        let app = create_router(state);
+
+        let response = tower::ServiceExt::oneshot(app, decision_request("0xdead"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["decision"], "ALLOW");
+        assert_eq!(body["enforced"], false);
+        assert!(body.get("actions").is_none());
+
+        // The real outcome is still recorded for observation
+        let recorded = storage.get_recorded_decisions();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].decision, Decision::RejectFatal);
+        assert_eq!(recorded[0].evidence[0].rule_id, "R1_OFAC");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_propagation() {
+        let storage = Arc::new(MockStorage::new());
+        let state = test_app_state_with(storage.clone(), true);
+        let app = create_router(state);
+
+        let mut request = decision_request("0xabc");
+        request
+            .headers_mut()
+            .insert("x-request-id", "req-42".parse().unwrap());
+
+        let response = tower::ServiceExt::oneshot(app.clone(), request)
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["x-request-id"], "req-42");
+
+        let recorded = storage.get_recorded_decisions();
+        assert_eq!(recorded[0].request_id.as_deref(), Some("req-42"));
+
+        // Generated when the caller does not send one
+        let request = axum::http::Request::builder()
+            .uri("/health")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        assert!(response.headers().contains_key("x-request-id"));
+    }
+
+    fn screening_request(addresses: Vec<String>) -> axum::http::Request<axum::body::Body> {
+        let body = serde_json::json!({ "addresses": addresses });
+
+        axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/screening/addresses")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_screening_addresses() {
+        let app = create_router(test_app_state());
+
+        let request = screening_request(vec!["0xDEAD".to_string(), "0xbeef".to_string()]);
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["address"], "0xDEAD");
+        assert_eq!(results[0]["sanctioned"], true);
+        assert_eq!(results[0]["list"], "OFAC");
+        assert_eq!(results[1]["sanctioned"], false);
+    }
+
+    #[tokio::test]
+    async fn test_screening_too_many_addresses() {
+        let app = create_router(test_app_state());
+
+        let addresses = (0..=MAX_SCREENING_ADDRESSES)
+            .map(|i| format!("0x{:x}", i))
+            .collect();
+        let response = tower::ServiceExt::oneshot(app, screening_request(addresses))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_policy_diff_endpoint() {
+        let storage = Arc::new(MockStorage::new());
+        let mut v1 = Policy::empty();
+        v1.version = "v1".to_string();
+        let mut v2 = Policy::empty();
+        v2.version = "v2".to_string();
+        v2.monitor_only = true;
+        storage.set_active_policy(&v1).await.unwrap();
+        storage.set_active_policy(&v2).await.unwrap();
+        let app = create_router(test_app_state_with(storage, false));
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/admin/policies/diff?from=v1&to=v2")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app.clone(), request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["from_version"], "v1");
+        assert_eq!(body["settings_changed"][0]["name"], "monitor_only");
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/admin/policies/diff?from=v1&to=v9")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_subject_as_of() {
+        let storage = Arc::new(MockStorage::new());
+        let subject_id = storage.add_subject(crate::domain::Subject {
+            user_id: crate::domain::subject::UserId::new("U1"),
+            account_id: crate::domain::subject::AccountId::new("A1"),
+            addresses: smallvec::smallvec![],
+            geo_iso: crate::domain::subject::CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        });
+        let at: DateTime<Utc> = "2026-01-02T14:32:00Z".parse().unwrap();
+        for (offset, usd) in [(-30, 500), (-20 * 60, 700), (-30 * 60, 900), (30, 300)] {
+            storage.add_transaction_point(
+                subject_id,
+                crate::storage::TransactionPoint {
+                    at: at + Duration::minutes(offset),
+                    usd_value: Decimal::new(usd, 0),
+                },
+            );
+        }
+        let mut v1 = Policy::empty();
+        v1.version = "v1".to_string();
+        v1.params.daily_volume_limit_usd = Some(Decimal::new(2400, 0));
+        storage.set_active_policy(&v1).await.unwrap();
+        let app = create_router(test_app_state_with(storage, false));
+
+        // Only the two transactions in the 24 hours up to `at` count
+        let request = axum::http::Request::builder()
+            .uri("/v1/admin/subjects/U1/as-of?at=2026-01-02T14:32:00Z&policy_version=v1")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app.clone(), request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["policy_version"], "v1");
+        assert_eq!(body["window"]["volume"]["amount"], "1200");
+        assert_eq!(body["window"]["limit"]["amount"], "2400");
+        assert_eq!(body["window"]["utilization"], "0.5");
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/admin/subjects/U9/as-of?at=2026-01-02T14:32:00Z")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_blocklist_import_blocks_decisions() {
+        let app = create_router(test_app_state());
+
+        let import = |query: &str, body: &str| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/admin/import/blocklist?{}", query))
+                .header("content-type", "text/csv")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // A bad row rejects the whole file
+        let response = tower::ServiceExt::oneshot(
+            app.clone(),
+            import("", "address,reason\n0xBAD,fraud ring\n,missing\n"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = response_json(response).await;
+        assert_eq!(body["errors"][0]["row"], 2);
+
+        let response = tower::ServiceExt::oneshot(
+            app.clone(),
+            import("dry_run=true", "address,reason\n0xBAD,fraud ring\n"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_json(response).await["applied"], 0);
+
+        let response = tower::ServiceExt::oneshot(app.clone(), decision_request("0xbad"))
+            .await
+            .unwrap();
+        assert_eq!(response_json(response).await["decision"], "ALLOW");
+
+        let response = tower::ServiceExt::oneshot(
+            app.clone(),
+            import("", "address,reason\n0xBAD,fraud ring\n"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_json(response).await["applied"], 1);
+
+        let response = tower::ServiceExt::oneshot(app.clone(), decision_request("0xbad"))
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["decision"], "REJECT_FATAL");
+        assert_eq!(body["evidence"][0]["rule_id"], BLOCKLIST_RULE_ID);
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/admin/export/blocklist")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&bytes)
+            .starts_with("address,reason,added_at,actor\n0xbad,fraud ring,"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_policies_endpoint() {
+        let state = test_app_state();
+        state.failed_policies.record(
+            Some("v2".to_string()),
+            Some("test-v1".to_string()),
+            vec!["Duplicate rule ID: R1".to_string()],
+            None,
+        );
+        let app = create_router(state);
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/admin/policies/failed")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["failed"][0]["version"], "v2");
+        assert_eq!(body["failed"][0]["errors"][0], "Duplicate rule ID: R1");
+        assert_eq!(body["failed"][0]["attempts"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_sanctions_check_endpoint() {
+        let state = test_app_state();
+        state.blocklist.extend(vec![crate::storage::BlocklistEntry {
+            address: "0xbad".to_string(),
+            reason: None,
+            added_at: Utc::now(),
+            actor: "compliance".to_string(),
+        }]);
+        let app = create_router(state.clone());
+        let check = |address: &str| {
+            axum::http::Request::builder()
+                .uri(format!("/v1/admin/sanctions/check/{}", address))
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let response = tower::ServiceExt::oneshot(app.clone(), check("0xDEAD"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["normalized"], "0xdead");
+        assert_eq!(body["bloom"], true);
+        assert_eq!(body["sanctioned"], true);
+        assert_eq!(body["matched_list"], "OFAC");
+        assert_eq!(body["stats"]["entries"], 1);
+
+        let response = tower::ServiceExt::oneshot(app.clone(), check("0xbad"))
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["sanctioned"], false);
+        assert_eq!(body["blocklisted"], true);
+        assert_eq!(body["matched_list"], BLOCKLIST_RULE_ID);
+
+        let response = tower::ServiceExt::oneshot(app, check("0xabc"))
+            .await
+            .unwrap();
+        assert!(response_json(response).await["matched_list"].is_null());
+
+        // Debug checks don't skew the screening statistics
+        let sanctions = state.ruleset_rx.borrow().sanctions.clone();
+        assert_eq!(sanctions.stats().checks, 0);
+    }
+
+    #[tokio::test]
+    async fn test_list_rules_endpoint() {
+        let state = test_app_state();
+        state.rule_switches.disable(RuleSwitch {
+            rule_id: "R4_DAILY".to_string(),
+            disabled_at: Utc::now(),
+            actor: "ops".to_string(),
+            reason: None,
+        });
+        let app = create_router(state);
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/rules")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+
+        let rules = body["rules"].as_array().unwrap();
+        let ids: Vec<&str> = rules.iter().map(|r| r["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec!["R1_OFAC", "R_FINALITY", "R4_DAILY"]);
+
+        let ofac = &rules[0];
+        assert_eq!(ofac["kind"], "inline");
+        assert_eq!(ofac["rule_type"], "ofac_addr");
+        assert_eq!(ofac["action"], "REJECT_FATAL");
+        assert_eq!(ofac["params"]["entries"], 1);
+        // List entries are never listed
+        assert!(!ofac.to_string().contains("0xdead"));
+
+        let daily = &rules[2];
+        assert_eq!(daily["kind"], "streaming");
+        assert_eq!(daily["params"]["limit_usd"]["amount"], "50000");
+        assert_eq!(daily["disabled"], true);
+        assert_eq!(rules[0]["disabled"], false);
+    }
+
+    #[tokio::test]
+    async fn test_provisional_decision_then_final() {
+        let storage = Arc::new(MockStorage::new());
+        let state = test_app_state_with(storage, false);
+        let provisional = |address: &str, request_id: &str| {
+            let mut request = decision_request(address);
+            *request.uri_mut() = "/v1/decision/check?provisional=true".parse().unwrap();
+            request
+                .headers_mut()
+                .insert("x-request-id", request_id.parse().unwrap());
+            request
+        };
+
+        let response =
+            tower::ServiceExt::oneshot(create_router(state.clone()), provisional("0xabc", "req-1"))
+                .await
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["decision"], "ALLOW");
+        assert_eq!(body["stage"], "provisional");
+        assert_eq!(body["final_decision_url"], "/v1/decisions/req-1");
+
+        // The final decision is recorded in the background
+        let mut final_decision = None;
+        for _ in 0..100 {
+            let request = axum::http::Request::builder()
+                .uri("/v1/decisions/req-1")
+                .body(axum::body::Body::empty())
+                .unwrap();
+            let response = tower::ServiceExt::oneshot(create_router(state.clone()), request)
+                .await
+                .unwrap();
+            if response.status() == StatusCode::OK {
+                final_decision = Some(response_json(response).await);
+                break;
+            }
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        let final_decision = final_decision.expect("final decision recorded");
+        assert_eq!(final_decision["stage"], "final");
+        assert_eq!(final_decision["decision"], "ALLOW");
+        assert_eq!(final_decision["event_id"], "req-1");
+
+        // A fatal inline outcome is final at once
+        let response =
+            tower::ServiceExt::oneshot(create_router(state), provisional("0xdead", "req-2"))
+                .await
+                .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["decision"], "REJECT_FATAL");
+        assert!(body.get("stage").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_review_opens_case() {
+        let storage = Arc::new(MockStorage::new());
+        let state = test_app_state_with(storage.clone(), false);
+        let subject_id = Uuid::new_v4();
+
+        let record = |decision: Decision| {
+            let storage = storage.clone();
+            async move {
+                storage
+                    .record_outcome(
+                        &TransactionRecord {
+                            subject_id,
+                            tx_type: "withdraw".to_string(),
+                            asset: "USDC".to_string(),
+                            amount: rust_decimal::Decimal::new(100, 0),
+                            usd_value: rust_decimal::Decimal::new(100, 0),
+                            dest_address: None,
+                            counterparty_geo: None,
+                            account_id: None,
+                        },
+                        &DecisionRecord {
+                            subject_id: Some(subject_id),
+                            request_id: None,
+                            request: serde_json::Value::Null,
+                            decision,
+                            decision_code: "R".to_string(),
+                            policy_version: "v1".to_string(),
+                            evidence: vec![],
+                            latency_ms: 1,
+                        },
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+        let status_update = |case_id: &str, body: serde_json::Value| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/cases/{}/status", case_id))
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        // Allows alone open nothing; a review opens a case that later
+        // decisions join
+        record(Decision::Allow).await;
+        let review_id = record(Decision::Review).await;
+        let later_id = record(Decision::Allow).await;
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/cases?status=open")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), request)
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        let case = &body["cases"][0];
+        assert_eq!(case["opened_by_decision"], review_id.to_string());
+        assert_eq!(
+            case["decision_ids"],
+            serde_json::json!([review_id.to_string(), later_id.to_string()])
+        );
+        let case_id = case["id"].as_str().unwrap().to_string();
+
+        let response = tower::ServiceExt::oneshot(
+            create_router(state.clone()),
+            status_update(&case_id, serde_json::json!({"status": "investigating"})),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = tower::ServiceExt::oneshot(
+            create_router(state.clone()),
+            status_update(
+                &case_id,
+                serde_json::json!({"status": "sar_filed", "note": "SAR 2024-118"}),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response_json(response).await["note"], "SAR 2024-118");
+
+        // Closed cases are final and no longer collect decisions
+        let response = tower::ServiceExt::oneshot(
+            create_router(state.clone()),
+            status_update(&case_id, serde_json::json!({"status": "open"})),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        record(Decision::Allow).await;
+        let case = storage
+            .get_case(case_id.parse().unwrap())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(case.status, CaseStatus::SarFiled);
+        assert_eq!(case.decision_ids.len(), 2);
+
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/cases")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(r#"{"user_id":"nobody"}"#))
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(create_router(state), request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_recheck_decision() {
+        let storage = Arc::new(MockStorage::new());
+        let state = test_app_state_with(storage.clone(), false);
+        let recheck = |request_id: &str| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/decisions/{}/recheck", request_id))
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let mut request = decision_request("0xabc");
+        request
+            .headers_mut()
+            .insert("x-request-id", "req-1".parse().unwrap());
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), request)
+            .await
+            .unwrap();
+        assert_eq!(response_json(response).await["decision"], "ALLOW");
+
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), recheck("req-1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["still_holds"], true);
+        assert_eq!(body["prior"]["event_id"], "req-1");
+        assert_eq!(body["current"]["decision"], "ALLOW");
+
+        // Later activity fills the window; the transaction itself is not
+        // counted again
+        let (subject_id, _) = storage.get_subject_by_user_id("U1").await.unwrap().unwrap();
+        storage.set_rolling_volume(subject_id, Decimal::new(50000, 0));
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), recheck("req-1"))
+            .await
+            .unwrap();
+        assert_eq!(response_json(response).await["still_holds"], true);
+
+        storage.set_rolling_volume(subject_id, Decimal::new(50001, 0));
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), recheck("req-1"))
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["still_holds"], false);
+        assert_eq!(body["prior"]["decision"], "ALLOW");
+        assert_eq!(body["current"]["decision"], "HOLD_AUTO");
+        assert_eq!(body["current"]["evidence"][0]["rule_id"], "R4_DAILY");
+
+        // Nothing is recorded by a re-check
+        assert_eq!(
+            storage
+                .get_subject_decisions(subject_id, Utc::now(), 10)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let response = tower::ServiceExt::oneshot(create_router(state), recheck("unknown"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_subject_annotations() {
+        let storage = Arc::new(MockStorage::new());
+        let subject_id = storage.add_subject(crate::domain::Subject {
+            user_id: crate::domain::subject::UserId::new("U1"),
+            account_id: crate::domain::subject::AccountId::new("A1"),
+            addresses: smallvec::smallvec![],
+            geo_iso: crate::domain::subject::CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        });
+        let state = test_app_state_with(storage.clone(), false);
+        let annotate = |user_id: &str, body: serde_json::Value| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/subjects/{}/annotations", user_id))
+                .header("content-type", "application/json")
+                .header("x-tenant-id", "alice")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = tower::ServiceExt::oneshot(
+            create_router(state.clone()),
+            annotate(
+                "U1",
+                serde_json::json!({"note": "Confirmed corporate treasury flow", "risk_rating": "low", "tags": ["treasury"]}),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response_json(response).await["actor"], "alice");
+
+        let response = tower::ServiceExt::oneshot(
+            create_router(state.clone()),
+            annotate("U1", serde_json::json!({"note": "Called the CFO"})),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        for (user_id, body, status) in [
+            (
+                "U1",
+                serde_json::json!({"note": "  "}),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                "nobody",
+                serde_json::json!({"note": "x"}),
+                StatusCode::NOT_FOUND,
+            ),
+        ] {
+            let response =
+                tower::ServiceExt::oneshot(create_router(state.clone()), annotate(user_id, body))
+                    .await
+                    .unwrap();
+            assert_eq!(response.status(), status);
+        }
+
+        // The profile shows the newest first, with the latest rating given
+        let request = axum::http::Request::builder()
+            .uri("/v1/subjects/U1")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["user_id"], "U1");
+        assert_eq!(body["risk_rating"], "low");
+        assert_eq!(body["annotations"][0]["note"], "Called the CFO");
+        assert_eq!(
+            body["annotations"][1]["tags"],
+            serde_json::json!(["treasury"])
+        );
+
+        let request = axum::http::Request::builder()
+            .uri("/v1/subjects/U1/annotations?limit=1")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), request)
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["annotations"].as_array().unwrap().len(), 1);
+        assert!(body.get("risk_rating").is_none());
 
-# HELP riskr_streaming_rules Number of streaming rules loaded
-# TYPE riskr_streaming_rules gauge
-riskr_streaming_rules {}
-"#,
-        state.start_time.elapsed().as_secs(),
-        ruleset.inline.len(),
-        ruleset.streaming.len(),
-    );
+        // Reviewers see them alongside the subject's case
+        let case = storage
+            .create_case(subject_id, None)
+            .await
+            .unwrap()
+            .unwrap();
+        let request = axum::http::Request::builder()
+            .uri(format!("/v1/cases/{}", case.id))
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(create_router(state), request)
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["status"], "open");
+        assert_eq!(body["annotations"].as_array().unwrap().len(), 2);
+    }
 
-    (
-        StatusCode::OK,
-        [(
-            axum::http::header::CONTENT_TYPE,
-            "text/plain; charset=utf-8",
-        )],
-        metrics,
-    )
-}
+    #[tokio::test]
+    async fn test_subject_addresses() {
+        let storage = Arc::new(MockStorage::new());
+        storage.add_subject(crate::domain::Subject {
+            user_id: crate::domain::subject::UserId::new("U1"),
+            account_id: crate::domain::subject::AccountId::new("A1"),
+            addresses: smallvec::smallvec![crate::domain::subject::Address::new("0xaaa")],
+            geo_iso: crate::domain::subject::CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        });
+        let state = test_app_state_with(storage.clone(), false);
+        let save = |user_id: &str, body: serde_json::Value| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/subjects/{}/addresses", user_id))
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
+        let remove = |address: &str| {
+            axum::http::Request::builder()
+                .method("DELETE")
+                .uri(format!("/v1/subjects/U1/addresses/{}", address))
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::rules::{DailyVolumeRule, OfacRule};
-    use crate::storage::MockStorage;
-    use rust_decimal::Decimal;
-    use std::collections::HashSet;
+        let response = tower::ServiceExt::oneshot(
+            create_router(state.clone()),
+            save(
+                "U1",
+                serde_json::json!({"address": "0xBBB", "label": "cold wallet"}),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["address"], "0xbbb");
+        assert_eq!(body["verified"], false);
+        assert_eq!(body["label"], "cold wallet");
 
-    fn test_app_state() -> Arc<AppState> {
-        let mut sanctions = HashSet::new();
-        sanctions.insert("0xdead".to_string());
+        // Verifying keeps the label and when the address was first seen
+        let response = tower::ServiceExt::oneshot(
+            create_router(state.clone()),
+            save(
+                "U1",
+                serde_json::json!({"address": "0xbbb", "verified": true}),
+            ),
+        )
+        .await
+        .unwrap();
+        let verified = response_json(response).await;
+        assert_eq!(verified["verified"], true);
+        assert_eq!(verified["label"], "cold wallet");
+        assert_eq!(verified["first_seen"], body["first_seen"]);
 
-        let inline_rules: Vec<Arc<dyn crate::rules::InlineRule>> = vec![Arc::new(OfacRule::new(
-            "R1_OFAC".to_string(),
-            Decision::RejectFatal,
-            sanctions,
-        ))];
+        for (user_id, body, status) in [
+            (
+                "U1",
+                serde_json::json!({"address": " "}),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                "U1",
+                serde_json::json!({"address": "0xccc", "label": "x".repeat(MAX_ADDRESS_LABEL_BYTES + 1)}),
+                StatusCode::BAD_REQUEST,
+            ),
+            (
+                "nobody",
+                serde_json::json!({"address": "0xccc"}),
+                StatusCode::NOT_FOUND,
+            ),
+        ] {
+            let response =
+                tower::ServiceExt::oneshot(create_router(state.clone()), save(user_id, body))
+                    .await
+                    .unwrap();
+            assert_eq!(response.status(), status);
+        }
 
-        let streaming_rules: Vec<Arc<dyn crate::rules::StreamingRule>> =
-            vec![Arc::new(DailyVolumeRule::new(
-                "R4_DAILY".to_string(),
-                Decision::HoldAuto,
-                Decimal::new(50000, 0),
-            ))];
+        let list = || {
+            axum::http::Request::builder()
+                .uri("/v1/subjects/U1/addresses")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), list())
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["addresses"][0]["address"], "0xaaa");
+        assert_eq!(body["addresses"][1]["address"], "0xbbb");
 
-        let ruleset = Arc::new(RuleSet {
-            inline: inline_rules,
-            streaming: streaming_rules.clone(),
-            policy_version: "test-v1".to_string(),
-        });
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), remove("0xAAA"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), remove("0xaaa"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-        let (_tx, rx) = watch::channel(ruleset);
-        let storage = Arc::new(MockStorage::new()) as Arc<dyn Storage>;
+        // The stored subject carries only the addresses still linked
+        let (_, subject) = storage.get_subject_by_user_id("U1").await.unwrap().unwrap();
+        assert_eq!(subject.addresses.len(), 1);
+        assert_eq!(subject.addresses[0].as_str(), "0xbbb");
+        let response = tower::ServiceExt::oneshot(create_router(state), list())
+            .await
+            .unwrap();
+        let body = response_json(response).await;
+        assert_eq!(body["addresses"].as_array().unwrap().len(), 1);
+    }
 
-        Arc::new(AppState {
-            storage,
-            ruleset_rx: rx,
-            start_time: Instant::now(),
-            version: "0.1.0-test".to_string(),
-            latency_budget_ms: 100,
-        })
+    #[tokio::test]
+    async fn test_rule_kill_switch() {
+        let storage = Arc::new(MockStorage::new());
+        let state = test_app_state_with(storage.clone(), false);
+
+        let toggle = |rule_id: &str, action: &str| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/admin/rules/{}/{}", rule_id, action))
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(r#"{"reason":"false positives"}"#))
+                .unwrap()
+        };
+
+        let response =
+            tower::ServiceExt::oneshot(create_router(state.clone()), toggle("R9", "disable"))
+                .await
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response =
+            tower::ServiceExt::oneshot(create_router(state.clone()), toggle("R1_OFAC", "disable"))
+                .await
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response_json(response).await;
+        assert_eq!(body["disabled"], true);
+        assert_eq!(body["persisted"], true);
+        let persisted = storage.get_disabled_rules().await.unwrap();
+        assert_eq!(persisted[0].reason.as_deref(), Some("false positives"));
+
+        // Sanctioned address passes while the rule is off
+        let response =
+            tower::ServiceExt::oneshot(create_router(state.clone()), decision_request("0xdead"))
+                .await
+                .unwrap();
+        assert_eq!(response_json(response).await["decision"], "ALLOW");
+
+        let request = axum::http::Request::builder()
+            .uri("/ready")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(create_router(state.clone()), request)
+            .await
+            .unwrap();
+        assert_eq!(
+            response_json(response).await["disabled_rules"][0]["rule_id"],
+            "R1_OFAC"
+        );
+
+        let response =
+            tower::ServiceExt::oneshot(create_router(state.clone()), toggle("R1_OFAC", "enable"))
+                .await
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(storage.get_disabled_rules().await.unwrap().is_empty());
+
+        let response = tower::ServiceExt::oneshot(create_router(state), decision_request("0xdead"))
+            .await
+            .unwrap();
+        assert_eq!(response_json(response).await["decision"], "REJECT_FATAL");
     }
 
     #[tokio::test]
-    async fn test_health_endpoint() {
-        let state = test_app_state();
-        let app = create_router(state);
+    async fn test_admin_scopes() {
+        let storage = Arc::new(MockStorage::new());
+        let mut state = Arc::try_unwrap(test_app_state_with(storage.clone(), false))
+            .ok()
+            .unwrap();
+        let keys = format!(
+            "- actor: alice\n  key_sha256: {}\n  scopes: [policy:write]\n\
+             - actor: bob\n  key_sha256: {}\n  scopes: [exports:read]\n",
+            crate::api::auth::hash_key("alice-key"),
+            crate::api::auth::hash_key("bob-key"),
+        );
+        state.admin_auth.api_keys = Some(Arc::new(
+            crate::api::auth::ApiKeys::from_yaml(&keys).unwrap(),
+        ));
+        let state = Arc::new(state);
 
-        let response = axum::http::Request::builder()
+        let disable = |key: Option<&str>| {
+            let mut request = axum::http::Request::builder()
+                .method("POST")
+                .uri("/v1/admin/rules/R1_OFAC/disable");
+            if let Some(key) = key {
+                request = request.header("authorization", format!("Bearer {}", key));
+            }
+            request.body(axum::body::Body::empty()).unwrap()
+        };
+
+        for (key, status) in [
+            (None, StatusCode::UNAUTHORIZED),
+            (Some("mallory-key"), StatusCode::UNAUTHORIZED),
+            (Some("bob-key"), StatusCode::FORBIDDEN),
+            (Some("alice-key"), StatusCode::OK),
+        ] {
+            let response = tower::ServiceExt::oneshot(create_router(state.clone()), disable(key))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status);
+        }
+        assert_eq!(
+            storage.get_disabled_rules().await.unwrap()[0].actor,
+            "alice"
+        );
+
+        // Decision checks need no key
+        let response = tower::ServiceExt::oneshot(create_router(state), decision_request("0xabc"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_request_signing() {
+        use crate::api::signing::{sign, NONCE_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+
+        let mut state = Arc::try_unwrap(test_app_state()).ok().unwrap();
+        state.request_signing = Some(Arc::new(RequestSigning::new(
+            ["secret"],
+            chrono::Duration::minutes(5),
+        )));
+        let state = Arc::new(state);
+
+        let body = serde_json::json!({
+            "subject": {"user_id": "U1", "account_id": "A1", "addresses": ["0xabc"],
+                        "geo_iso": "US", "kyc_level": "L1"},
+            "tx": {"type": "withdraw", "asset": "USDC", "usd_value": 100.0}
+        })
+        .to_string();
+        let signed = |secret: &str, timestamp: i64, nonce: &str| {
+            let signature = sign(
+                secret.as_bytes(),
+                timestamp,
+                nonce,
+                &axum::http::Method::POST,
+                "/v1/decision/check",
+                body.as_bytes(),
+            );
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/v1/decision/check")
+                .header("content-type", "application/json")
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(NONCE_HEADER, nonce)
+                .header(SIGNATURE_HEADER, signature)
+                .body(axum::body::Body::from(body.clone()))
+                .unwrap()
+        };
+        let now = Utc::now().timestamp();
+
+        for (request, status) in [
+            (decision_request("0xabc"), StatusCode::UNAUTHORIZED),
+            (signed("wrong", now, "n1"), StatusCode::UNAUTHORIZED),
+            (signed("secret", now - 600, "n2"), StatusCode::UNAUTHORIZED),
+            (signed("secret", now, "n3"), StatusCode::OK),
+            // Replayed
+            (signed("secret", now, "n3"), StatusCode::UNAUTHORIZED),
+        ] {
+            let response = tower::ServiceExt::oneshot(create_router(state.clone()), request)
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status);
+        }
+
+        // Verification honours the configured body limit
+        let mut limited = Arc::try_unwrap(test_app_state()).ok().unwrap();
+        limited.request_signing = Some(Arc::new(
+            RequestSigning::new(["secret"], chrono::Duration::minutes(5)).with_max_body_bytes(16),
+        ));
+        let response = tower::ServiceExt::oneshot(
+            create_router(Arc::new(limited)),
+            signed("secret", now, "n4"),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Reads need no signature
+        let request = axum::http::Request::builder()
             .uri("/health")
             .body(axum::body::Body::empty())
             .unwrap();
+        let response = tower::ServiceExt::oneshot(create_router(state), request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_degraded_storage() {
+        let storage = Arc::new(MockStorage::new());
+        storage.set_degraded(true);
+
+        for (mode, expected) in [
+            (DegradedMode::FailOpen, "ALLOW"),
+            (DegradedMode::FailClosed, "SOFT_DENY_RETRY"),
+            (DegradedMode::InlineOnly, "ALLOW"),
+        ] {
+            let mut state = Arc::try_unwrap(test_app_state_with(storage.clone(), false))
+                .ok()
+                .unwrap();
+            state.degraded_mode = mode;
+            let app = create_router(Arc::new(state));
+
+            let response = tower::ServiceExt::oneshot(app, decision_request("0xabc"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+            let body = response_json(response).await;
+            assert_eq!(body["decision"], expected, "{:?}", mode);
+        }
+
+        // Storage was never touched
+        assert!(storage.get_recorded_decisions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_kyc_update() {
+        let storage = Arc::new(MockStorage::new());
+        let app = create_router(test_app_state_with(storage.clone(), false));
+
+        let kyc_request = |user_id: &str| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/subjects/{}/kyc", user_id))
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(r#"{"kyc_level": "L2"}"#))
+                .unwrap()
+        };
+
+        let response = tower::ServiceExt::oneshot(app.clone(), kyc_request("U1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-        let response = tower::ServiceExt::oneshot(app, response).await.unwrap();
+        // Subject becomes known through a decision request (sent as L1)
+        tower::ServiceExt::oneshot(app.clone(), decision_request("0xabc"))
+            .await
+            .unwrap();
+
+        let response = tower::ServiceExt::oneshot(app.clone(), kyc_request("U1"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        // A later request claiming L1 does not overwrite the updated tier
+        tower::ServiceExt::oneshot(app, decision_request("0xabc"))
+            .await
+            .unwrap();
+        let (_, subject) = storage.get_subject_by_user_id("U1").await.unwrap().unwrap();
+        assert_eq!(subject.kyc_tier, KycTier::L2);
+    }
+
+    #[tokio::test]
+    async fn test_held_deposit_released_on_confirmations() {
+        let storage = Arc::new(MockStorage::new());
+        let app = create_router(test_app_state_with(storage.clone(), false));
+
+        let body = serde_json::json!({
+            "subject": {
+                "user_id": "U1",
+                "account_id": "A1",
+                "addresses": ["0xabc"],
+                "geo_iso": "US",
+                "kyc_level": "L1"
+            },
+            "tx": {
+                "type": "deposit",
+                "asset": "ETH",
+                "usd_value": 100.0,
+                "event_id": "dep-1",
+                "confirmations": 2,
+                "finality_depth": 12
+            }
+        });
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/decision/check")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app.clone(), request)
+            .await
+            .unwrap();
+        let json = response_json(response).await;
+        assert_eq!(json["decision"], "HOLD_AUTO");
+        assert_eq!(json["event_id"], "dep-1");
+
+        let confirmations = |event_id: &str, count: u32| {
+            axum::http::Request::builder()
+                .method("POST")
+                .uri(format!("/v1/events/{}/confirmations", event_id))
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(format!(
+                    r#"{{"confirmations": {}}}"#,
+                    count
+                )))
+                .unwrap()
+        };
+
+        let response = tower::ServiceExt::oneshot(app.clone(), confirmations("dep-1", 6))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(response_json(response).await["status"], "pending");
+
+        // A stale count does not lower the confirmations already reported
+        let response = tower::ServiceExt::oneshot(app.clone(), confirmations("dep-1", 3))
+            .await
+            .unwrap();
+        assert_eq!(response_json(response).await["confirmations"], 6);
+        let outbox_before = storage.pending_outbox(10).await.unwrap().len();
+
+        let response = tower::ServiceExt::oneshot(app.clone(), confirmations("dep-1", 12))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = response_json(response).await;
+        assert_eq!(json["status"], "released");
+        assert_eq!(json["decision"]["decision"], "ALLOW");
+        assert_eq!(json["decision"]["event_id"], "dep-1");
+
+        let outbox = storage.pending_outbox(10).await.unwrap();
+        assert_eq!(outbox.len(), outbox_before + 1);
+        assert_eq!(outbox.last().unwrap().kind, "decision_event");
+
+        // Resolved deposits are no longer held
+        let response = tower::ServiceExt::oneshot(app, confirmations("dep-1", 20))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_sparse_subject_enriched_from_storage() {
+        let storage = Arc::new(MockStorage::new());
+        storage
+            .upsert_subject(&crate::domain::Subject {
+                user_id: crate::domain::subject::UserId::new("U1"),
+                account_id: crate::domain::subject::AccountId::new("A1"),
+                addresses: smallvec::smallvec![crate::domain::subject::Address::new("0xdead")],
+                geo_iso: crate::domain::subject::CountryCode::new("US"),
+                kyc_tier: KycTier::L1,
+            })
+            .await
+            .unwrap();
+        let app = create_router(test_app_state_with(storage, false));
+
+        // No addresses, tier or country; the stored sanctioned address is screened
+        let body = serde_json::json!({
+            "subject": { "user_id": "U1", "account_id": "A1" },
+            "tx": { "type": "withdraw", "asset": "USDC", "usd_value": 100.0 }
+        });
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/decision/check")
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        let response = tower::ServiceExt::oneshot(app, request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = response_json(response).await;
+        assert_eq!(json["decision"], "REJECT_FATAL");
+    }
+
+    #[tokio::test]
+    async fn test_asset_registry() {
+        let storage = Arc::new(MockStorage::new());
+        let Ok(mut state) = Arc::try_unwrap(test_app_state_with(storage.clone(), false)) else {
+            unreachable!()
+        };
+        let registry = AssetRegistry::from_yaml(
+            "assets:\n  USDC:\n    decimals: 6\n    chains: { usdc-polygon: polygon }\n",
+        )
+        .unwrap();
+        state.assets = Some(Arc::new(registry));
+        let app = create_router(Arc::new(state));
 
+        let request = |asset: &str| {
+            let body = serde_json::json!({
+                "subject": {
+                    "user_id": "U1",
+                    "account_id": "A1",
+                    "geo_iso": "US",
+                    "kyc_level": "L1"
+                },
+                "tx": { "type": "withdraw", "asset": asset, "usd_value": 100.0 }
+            });
+            axum::http::Request::builder()
+                .method("POST")
+                .uri("/v1/decision/check")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = tower::ServiceExt::oneshot(app.clone(), request("usdc-polygon"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let recorded = storage.get_recorded_transactions();
+        assert_eq!(recorded[0].asset, "USDC");
+
+        let response = tower::ServiceExt::oneshot(app, request("DOGE"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(storage.get_recorded_transactions().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_quota_and_usage() {
+        let storage = Arc::new(MockStorage::new());
+        let Ok(mut state) = Arc::try_unwrap(test_app_state_with(storage.clone(), false)) else {
+            unreachable!()
+        };
+        state.tenant_quotas = HashMap::from([("payments".to_string(), 1)]);
+        let app = create_router(Arc::new(state));
+
+        let tenant_request = |tenant: &str| {
+            let mut request = decision_request("0xdead");
+            request
+                .headers_mut()
+                .insert("x-tenant-id", tenant.parse().unwrap());
+            request
+        };
+
+        let response = tower::ServiceExt::oneshot(app.clone(), tenant_request("payments"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = tower::ServiceExt::oneshot(app.clone(), tenant_request("payments"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response_json(response).await["code"], "QUOTA_EXCEEDED");
+
+        // Tenants without a quota are unlimited
+        for _ in 0..2 {
+            let response = tower::ServiceExt::oneshot(app.clone(), decision_request("0xdead"))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        let today = chrono::Utc::now().date_naive();
+        let response = tower::ServiceExt::oneshot(
+            app,
+            axum::http::Request::builder()
+                .uri(format!("/v1/admin/usage?from={}&to={}", today, today))
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response_json(response).await;
+        assert_eq!(body["tenants"][0]["tenant"], "default");
+        assert_eq!(body["tenants"][0]["decisions"], 2);
+        assert_eq!(body["tenants"][1]["tenant"], "payments");
+        assert_eq!(body["tenants"][1]["decisions"], 1);
+        assert_eq!(body["tenants"][1]["rule_hits"]["R1_OFAC"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_export_decisions_csv() {
+        let storage = Arc::new(MockStorage::new());
+        let Ok(mut state) = Arc::try_unwrap(test_app_state_with(storage.clone(), false)) else {
+            unreachable!()
+        };
+        state.export_redactor = Redactor::new(&["subject.addresses".to_string()]);
+        let app = create_router(Arc::new(state));
+
+        // Inline fatals short-circuit before the decision is recorded, so
+        // export an allowed one
+        let response = tower::ServiceExt::oneshot(app.clone(), decision_request("0xabc"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let today = chrono::Utc::now().date_naive();
+        let response = tower::ServiceExt::oneshot(
+            app,
+            axum::http::Request::builder()
+                .uri(format!(
+                    "/v1/admin/export/decisions?from={}&to={}&format=csv",
+                    today, today
+                ))
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::CONTENT_DISPOSITION]
+            .to_str()
+            .unwrap()
+            .starts_with("attachment;"));
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let csv = String::from_utf8(bytes.to_vec()).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.contains("ALLOW"));
+        assert!(csv.contains(r#"""addresses"":""[REDACTED]"""#));
+    }
+
+    #[tokio::test]
+    async fn test_route_timeouts() {
+        let mut state = Arc::try_unwrap(test_app_state()).ok().unwrap();
+        state.route_timeouts = RouteTimeouts {
+            decision: Some(std::time::Duration::from_millis(20)),
+            admin: Some(std::time::Duration::from_millis(20)),
+            export: Some(std::time::Duration::from_millis(20)),
+        };
+        let state = Arc::new(state);
+
+        // Handlers stalled like a hung storage call
+        let stall = || async {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            StatusCode::OK
+        };
+        // A handler stalled while persisting, and an export whose body
+        // stalls after its first chunk
+        let persist = || async {
+            persistence_started();
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            StatusCode::CREATED
+        };
+        let export = || async {
+            let chunks = futures::StreamExt::chain(
+                futures::stream::once(async { Ok::<_, std::io::Error>("a,b\n") }),
+                futures::stream::once(async {
+                    tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                    Ok("c,d\n")
+                }),
+            );
+            axum::body::Body::from_stream(chunks)
+        };
+        let app = Router::new()
+            .route("/v1/decision/check", post(stall))
+            .route("/v1/cases", get(stall))
+            .route("/v1/admin/rules", get(persist))
+            .route("/v1/admin/export/decisions", get(export))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                enforce_timeout,
+            ))
+            .layer(middleware::from_fn(identify_tenant))
+            .with_state(state.clone());
+
+        let response = tower::ServiceExt::oneshot(app.clone(), decision_request("0xabc"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        let body = response_json(response).await;
+        assert_eq!(body["decision"], "SOFT_DENY_RETRY");
+        assert_eq!(body["evidence"][0]["rule_id"], "TIMEOUT");
+        assert_eq!(body["evidence"][0]["limit"], "20");
+        // Counted as a failed decision
+        assert_eq!(state.metrics.decisions_soft_deny.load(Ordering::Relaxed), 1);
+        assert_eq!(state.metrics.slo.window(300).bad, 1);
+
+        let get = |uri: &str| {
+            axum::http::Request::builder()
+                .uri(uri)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        let response = tower::ServiceExt::oneshot(app.clone(), get("/v1/cases"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(response_json(response).await["code"], "TIMEOUT");
+
+        let response = tower::ServiceExt::oneshot(app.clone(), get("/v1/admin/rules"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let response = tower::ServiceExt::oneshot(app, get("/v1/admin/export/decisions"))
+            .await
+            .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
+        assert!(axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .is_err());
     }
 }
@@ -0,0 +1,293 @@
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::DefaultBodyLimit;
+use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto::Builder;
+use hyper_util::server::graceful::GracefulShutdown;
+use hyper_util::service::TowerToHyperService;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{watch, Semaphore};
+use tower_http::compression::predicate::{DefaultPredicate, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::timeout::RequestBodyTimeoutLayer;
+use tracing::{debug, warn};
+
+use crate::config::Config;
+
+/// HTTP connection handling options.
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    /// Accept HTTP/2 (prior knowledge / h2c) in addition to HTTP/1.1
+    pub http2: bool,
+    /// Keep HTTP/1.1 connections open between requests
+    pub keep_alive: bool,
+    /// Interval between HTTP/2 keep-alive pings
+    pub http2_keep_alive_interval: Option<Duration>,
+    /// Maximum concurrent streams per HTTP/2 connection
+    pub http2_max_concurrent_streams: Option<u32>,
+    /// Maximum open connections per listener
+    pub max_connections: Option<usize>,
+    /// How long to wait for open connections on shutdown
+    pub shutdown_timeout: Duration,
+    /// Largest request body accepted
+    pub max_request_body_bytes: usize,
+    /// Smallest response body compressed, or None to never compress
+    pub compression_min_bytes: Option<u16>,
+    /// How long a client may take to send request headers
+    pub header_read_timeout: Duration,
+    /// How long a client may take to send a request body
+    pub body_read_timeout: Duration,
+}
+
+impl From<&Config> for ServerOptions {
+    fn from(config: &Config) -> Self {
+        ServerOptions {
+            http2: config.http2,
+            keep_alive: config.http_keep_alive,
+            http2_keep_alive_interval: config.http2_keep_alive_interval(),
+            http2_max_concurrent_streams: config.http2_max_concurrent_streams,
+            max_connections: config.max_connections,
+            shutdown_timeout: config.shutdown_timeout(),
+            max_request_body_bytes: config.max_request_body_bytes,
+            compression_min_bytes: config
+                .response_compression
+                .then_some(config.compression_min_bytes),
+            header_read_timeout: config.header_read_timeout(),
+            body_read_timeout: config.body_read_timeout(),
+        }
+    }
+}
+
+impl ServerOptions {
+    /// Build a hyper connection builder with these options applied.
+    fn builder(&self) -> Builder<TokioExecutor> {
+        let mut builder = Builder::new(TokioExecutor::new());
+        // Close connections that trickle in headers (slow loris)
+        builder
+            .http1()
+            .keep_alive(self.keep_alive)
+            .timer(TokioTimer::new())
+            .header_read_timeout(self.header_read_timeout);
+        builder
+            .http2()
+            .timer(TokioTimer::new())
+            .keep_alive_interval(self.http2_keep_alive_interval)
+            .max_concurrent_streams(self.http2_max_concurrent_streams);
+
+        if !self.http2 {
+            builder = builder.http1_only();
+        }
+
+        builder
+    }
+
+    /// Apply the body size limit, body read timeout and response
+    /// compression to every route of `app`.
+    pub fn layer(&self, app: Router) -> Router {
+        let app = app
+            .layer(DefaultBodyLimit::max(self.max_request_body_bytes))
+            .layer(RequestBodyTimeoutLayer::new(self.body_read_timeout));
+        match self.compression_min_bytes {
+            Some(min_bytes) => app.layer(
+                CompressionLayer::new()
+                    .compress_when(DefaultPredicate::new().and(SizeAbove::new(min_bytes))),
+            ),
+            None => app,
+        }
+    }
+}
+
+/// A listener the server can accept connections from.
+pub trait Listener {
+    type Stream: AsyncRead + AsyncWrite + Unpin + Send + 'static;
+
+    /// Accept the next connection.
+    fn next_conn(&self) -> impl std::future::Future<Output = io::Result<Self::Stream>> + Send;
+}
+
+impl Listener for TcpListener {
+    type Stream = TcpStream;
+
+    async fn next_conn(&self) -> io::Result<TcpStream> {
+        let (stream, _) = self.accept().await?;
+        stream.set_nodelay(true)?;
+        Ok(stream)
+    }
+}
+
+#[cfg(unix)]
+impl Listener for tokio::net::UnixListener {
+    type Stream = tokio::net::UnixStream;
+
+    async fn next_conn(&self) -> io::Result<tokio::net::UnixStream> {
+        let (stream, _) = self.accept().await?;
+        Ok(stream)
+    }
+}
+
+/// Bind a Unix domain socket, replacing a stale socket file if present.
+#[cfg(unix)]
+pub fn bind_unix(path: &std::path::Path) -> io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::FileTypeExt;
+
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if meta.file_type().is_socket() {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    tokio::net::UnixListener::bind(path)
+}
+
+/// Serve the router on a listener until shutdown is signalled.
+///
+/// Shutdown is requested by sending `true` on the watch channel. Open
+/// connections are then drained for up to `shutdown_timeout`.
+pub async fn serve<L: Listener>(
+    listener: L,
+    app: Router,
+    options: ServerOptions,
+    mut shutdown: watch::Receiver<bool>,
+) -> io::Result<()> {
+    let builder = options.builder();
+    let app = options.layer(app);
+    let graceful = GracefulShutdown::new();
+    let limit = options.max_connections.map(|n| Arc::new(Semaphore::new(n)));
+
+    loop {
+        // Wait for a free connection slot before accepting more
+        let permit = match &limit {
+            Some(semaphore) => tokio::select! {
+                permit = semaphore.clone().acquire_owned() => permit.ok(),
+                _ = shutdown_requested(&mut shutdown) => break,
+            },
+            None => None,
+        };
+
+        let stream = tokio::select! {
+            res = listener.next_conn() => match res {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!(error = %e, "Failed to accept connection");
+                    continue;
+                }
+            },
+            _ = shutdown_requested(&mut shutdown) => break,
+        };
+
+        let service = TowerToHyperService::new(app.clone());
+        let conn = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+        let conn = graceful.watch(conn.into_owned());
+
+        tokio::spawn(async move {
+            if let Err(e) = conn.await {
+                debug!(error = %e, "Connection closed with error");
+            }
+            drop(permit);
+        });
+    }
+
+    tokio::select! {
+        _ = graceful.shutdown() => {}
+        _ = tokio::time::sleep(options.shutdown_timeout) => {
+            warn!("Timed out waiting for open connections to close");
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve once shutdown is requested.
+///
+/// A dropped sender means shutdown will never be requested.
+async fn shutdown_requested(shutdown: &mut watch::Receiver<bool>) {
+    if shutdown.wait_for(|stop| *stop).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_from_config() {
+        let config = Config {
+            http2: false,
+            http2_keep_alive_secs: Some(20),
+            max_connections: Some(128),
+            shutdown_timeout_secs: 5,
+            ..Default::default()
+        };
+
+        let options = ServerOptions::from(&config);
+
+        assert!(!options.http2);
+        assert!(options.keep_alive);
+        assert_eq!(
+            options.http2_keep_alive_interval,
+            Some(Duration::from_secs(20))
+        );
+        assert_eq!(options.max_connections, Some(128));
+        assert_eq!(options.shutdown_timeout, Duration::from_secs(5));
+        assert_eq!(options.compression_min_bytes, Some(1024));
+
+        let config = Config {
+            response_compression: false,
+            ..Default::default()
+        };
+        assert_eq!(ServerOptions::from(&config).compression_min_bytes, None);
+    }
+
+    #[tokio::test]
+    async fn test_body_limit_and_compression() {
+        use axum::body::{Body, Bytes};
+        use axum::http::{header, Request, StatusCode};
+        use axum::routing::post;
+        use tower::ServiceExt;
+
+        let options = ServerOptions {
+            max_request_body_bytes: 1024,
+            ..ServerOptions::from(&Config::default())
+        };
+        let app = options
+            .layer(Router::new().route("/echo", post(|body: Bytes| async move { body.repeat(4) })));
+        let request = |size: usize| {
+            Request::post("/echo")
+                .header(header::ACCEPT_ENCODING, "gzip")
+                .body(Body::from(vec![b'a'; size]))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request(2048)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let response = app.clone().oneshot(request(512)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+
+        // Responses under the minimum size are sent as is
+        let response = app.oneshot(request(16)).await.unwrap();
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_serve_stops_on_shutdown() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let options = ServerOptions::from(&Config::default());
+        let (tx, rx) = watch::channel(false);
+
+        let handle = tokio::spawn(serve(listener, Router::new(), options, rx));
+        tx.send(true).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), handle)
+            .await
+            .expect("server did not stop")
+            .unwrap()
+            .unwrap();
+    }
+}
@@ -0,0 +1,312 @@
+//! Request signing with replay protection.
+//!
+//! For externally exposed deployments, POST requests can be required to
+//! carry an HMAC-SHA256 signature over a timestamp, a nonce, the method,
+//! the path and the body:
+//!
+//! ```text
+//! X-Riskr-Timestamp: 1767225600
+//! X-Riskr-Nonce: 5f0c6e2a-...
+//! X-Riskr-Signature: hex(HMAC-SHA256(secret, "{timestamp}\n{nonce}\n{METHOD}\n{path}\n" + body))
+//! ```
+//!
+//! Requests outside the allowed clock skew are rejected as stale, and each
+//! nonce is claimed in storage so a captured request can't be replayed,
+//! even against another replica.
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::warn;
+
+use super::response::{ErrorCode, ErrorResponse};
+use super::routes::AppState;
+
+/// Header carrying the Unix time the request was signed at, in seconds.
+pub const TIMESTAMP_HEADER: &str = "x-riskr-timestamp";
+
+/// Header carrying a value unique to the request.
+pub const NONCE_HEADER: &str = "x-riskr-nonce";
+
+/// Header carrying the hex encoded signature.
+pub const SIGNATURE_HEADER: &str = "x-riskr-signature";
+
+/// Longest accepted nonce.
+const MAX_NONCE_LEN: usize = 128;
+
+/// Largest body read for verification, unless set with
+/// `RequestSigning::with_max_body_bytes`.
+pub const DEFAULT_MAX_SIGNED_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Shared secrets and clock skew for signed requests.
+///
+/// Any of the secrets is accepted, so a new one can be added before
+/// callers switch to it and the old one removed after.
+#[derive(Clone)]
+pub struct RequestSigning {
+    secrets: Vec<Vec<u8>>,
+    max_skew: Duration,
+    max_body_bytes: usize,
+}
+
+impl std::fmt::Debug for RequestSigning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RequestSigning")
+            .field("secrets", &self.secrets.len())
+            .field("max_skew", &self.max_skew)
+            .field("max_body_bytes", &self.max_body_bytes)
+            .finish()
+    }
+}
+
+impl RequestSigning {
+    /// Accept signatures by any of `secrets` within `max_skew` of now.
+    pub fn new<S: AsRef<[u8]>>(secrets: impl IntoIterator<Item = S>, max_skew: Duration) -> Self {
+        RequestSigning {
+            secrets: secrets.into_iter().map(|s| s.as_ref().to_vec()).collect(),
+            max_skew,
+            max_body_bytes: DEFAULT_MAX_SIGNED_BODY_BYTES,
+        }
+    }
+
+    /// Read at most `max_body_bytes` of a body to verify it, matching the
+    /// server's request body limit.
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Whether the request's signature is valid for any secret.
+    fn verifies(&self, signed: &SignedParts<'_>, body: &[u8], signature: &[u8]) -> bool {
+        self.secrets
+            .iter()
+            .any(|secret| mac(secret, signed, body).verify_slice(signature).is_ok())
+    }
+
+    /// Time the nonce must be remembered for: until its timestamp is stale.
+    fn nonce_expiry(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        timestamp + self.max_skew
+    }
+}
+
+/// Request fields covered by the signature besides the body.
+struct SignedParts<'a> {
+    timestamp: &'a str,
+    nonce: &'a str,
+    method: &'a Method,
+    path: &'a str,
+}
+
+fn mac(secret: &[u8], signed: &SignedParts<'_>, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(
+        format!(
+            "{}\n{}\n{}\n{}\n",
+            signed.timestamp, signed.nonce, signed.method, signed.path
+        )
+        .as_bytes(),
+    );
+    mac.update(body);
+    mac
+}
+
+/// Hex signature of a request, for callers and tests.
+pub fn sign(
+    secret: &[u8],
+    timestamp: i64,
+    nonce: &str,
+    method: &Method,
+    path: &str,
+    body: &[u8],
+) -> String {
+    let timestamp = timestamp.to_string();
+    let signed = SignedParts {
+        timestamp: &timestamp,
+        nonce,
+        method,
+        path,
+    };
+    mac(secret, &signed, body)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+}
+
+/// Middleware rejecting unsigned, stale and replayed POST requests when
+/// request signing is configured.
+pub async fn verify_signature(
+    State(state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(signing) = state.request_signing.as_ref() else {
+        return next.run(req).await;
+    };
+    if req.method() != Method::POST {
+        return next.run(req).await;
+    }
+
+    let headers = req.headers();
+    let (Some(timestamp), Some(nonce), Some(signature)) = (
+        header(headers, TIMESTAMP_HEADER),
+        header(headers, NONCE_HEADER),
+        header(headers, SIGNATURE_HEADER).and_then(decode_hex),
+    ) else {
+        return rejected("Request signature headers are missing or malformed");
+    };
+    let (timestamp, nonce) = (timestamp.to_string(), nonce.to_string());
+
+    let Some(signed_at) = timestamp
+        .parse::<i64>()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+    else {
+        return rejected("Request timestamp is malformed");
+    };
+    if (Utc::now() - signed_at).abs() > signing.max_skew {
+        return rejected("Request timestamp is outside the allowed clock skew");
+    }
+    if nonce.is_empty()
+        || nonce.len() > MAX_NONCE_LEN
+        || !nonce.bytes().all(|b| b.is_ascii_graphic())
+    {
+        return rejected("Request nonce is malformed");
+    }
+
+    let (parts, body) = req.into_parts();
+    let Ok(body) = to_bytes(body, signing.max_body_bytes).await else {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(ErrorResponse::new(
+                "Request body is too large",
+                ErrorCode::PayloadTooLarge,
+            )),
+        )
+            .into_response();
+    };
+    let path = parts
+        .uri
+        .path_and_query()
+        .map(|p| p.as_str())
+        .unwrap_or("/");
+    let signed = SignedParts {
+        timestamp: &timestamp,
+        nonce: &nonce,
+        method: &parts.method,
+        path,
+    };
+    if !signing.verifies(&signed, &body, &signature) {
+        return rejected("Request signature is invalid");
+    }
+
+    // Claimed only after the signature checks out, so forged requests
+    // can't burn nonces
+    match state
+        .storage
+        .claim_nonce(&nonce, signing.nonce_expiry(signed_at))
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => {
+            warn!(nonce = %nonce, path = %path, "Replayed request rejected");
+            return rejected("Request nonce has already been used");
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to claim request nonce");
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse::new(
+                    "Replay protection is unavailable",
+                    ErrorCode::StorageUnavailable,
+                )),
+            )
+                .into_response();
+        }
+    }
+
+    next.run(Request::from_parts(parts, Body::from(body))).await
+}
+
+fn rejected(message: &str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        Json(ErrorResponse::new(message, ErrorCode::InvalidSignature)),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signing = RequestSigning::new(["old", "new"], Duration::minutes(5));
+        let body = br#"{"tx":{}}"#;
+        let signature = sign(
+            b"new",
+            1767225600,
+            "n1",
+            &Method::POST,
+            "/v1/decision/check",
+            body,
+        );
+        assert_eq!(signature.len(), 64);
+
+        let signed = SignedParts {
+            timestamp: "1767225600",
+            nonce: "n1",
+            method: &Method::POST,
+            path: "/v1/decision/check",
+        };
+        let bytes = decode_hex(&signature).unwrap();
+        assert!(signing.verifies(&signed, body, &bytes));
+
+        // Any change to the signed fields invalidates it
+        assert!(!signing.verifies(&signed, br#"{"tx":{"a":1}}"#, &bytes));
+        let other_path = SignedParts {
+            path: "/v1/screening/addresses",
+            ..signed
+        };
+        assert!(!signing.verifies(&other_path, body, &bytes));
+
+        let retired = RequestSigning::new(["old"], Duration::minutes(5));
+        assert!(!retired.verifies(&signed, body, &bytes));
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("00ff10"), Some(vec![0, 255, 16]));
+        assert_eq!(decode_hex("abc"), None);
+        assert_eq!(decode_hex("zz"), None);
+    }
+}
@@ -0,0 +1,66 @@
+use axum::{extract::Request, http::HeaderMap, middleware::Next, response::Response};
+
+/// Header identifying the calling tenant.
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Tenant used for requests without a usable tenant header.
+pub const DEFAULT_TENANT: &str = "default";
+
+/// Maximum accepted length of a tenant ID.
+const MAX_TENANT_LEN: usize = 64;
+
+/// Calling tenant, used for usage reporting and quotas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantId(pub String);
+
+impl TenantId {
+    /// Take the tenant from `X-Tenant-Id`, falling back to the default tenant.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let tenant = headers
+            .get(TENANT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|t| is_valid_tenant(t))
+            .unwrap_or(DEFAULT_TENANT);
+
+        TenantId(tenant.to_string())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Accept short identifiers only, since tenants become storage keys.
+fn is_valid_tenant(tenant: &str) -> bool {
+    !tenant.is_empty()
+        && tenant.len() <= MAX_TENANT_LEN
+        && tenant
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// Middleware attaching the calling tenant to the request.
+pub async fn identify_tenant(mut req: Request, next: Next) -> Response {
+    let tenant = TenantId::from_headers(req.headers());
+    req.extensions_mut().insert(tenant);
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_tenant_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(TenantId::from_headers(&headers).as_str(), DEFAULT_TENANT);
+
+        headers.insert(TENANT_HEADER, HeaderValue::from_static(" payments "));
+        assert_eq!(TenantId::from_headers(&headers).as_str(), "payments");
+
+        headers.insert(TENANT_HEADER, HeaderValue::from_static("drop table"));
+        assert_eq!(TenantId::from_headers(&headers).as_str(), DEFAULT_TENANT);
+    }
+}
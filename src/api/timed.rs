@@ -0,0 +1,32 @@
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+    Json,
+};
+use std::time::{Duration, Instant};
+
+/// JSON body extractor that also reports how long reading and parsing
+/// the body took. Rejections are the same as [`Json`]'s.
+#[derive(Debug)]
+pub struct TimedJson<T> {
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+#[async_trait]
+impl<S, T> FromRequest<S> for TimedJson<T>
+where
+    Json<T>: FromRequest<S>,
+    S: Send + Sync,
+{
+    type Rejection = <Json<T> as FromRequest<S>>::Rejection;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let start = Instant::now();
+        let Json(value) = Json::<T>::from_request(req, state).await?;
+        Ok(TimedJson {
+            value,
+            elapsed: start.elapsed(),
+        })
+    }
+}
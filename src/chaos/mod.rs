@@ -0,0 +1,287 @@
+//! Failure injection for staging.
+//!
+//! Adds storage latency and errors, slow rules and policy load failures so
+//! degraded mode, the circuit breaker and the latency budget can be
+//! exercised on purpose. Settings come from `--chaos-*` flags and can be
+//! changed at runtime through `GET`/`PUT /v1/admin/chaos`. Only built with
+//! the `chaos` feature.
+
+mod storage;
+
+pub use storage::ChaosStorage;
+
+use async_trait::async_trait;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::api::response::ErrorResponse;
+use crate::domain::evidence::RuleResult;
+use crate::domain::TxEvent;
+use crate::rules::{RuleDescription, RuleSet, StreamingRule};
+
+/// ID of the rule that injects rule latency.
+pub const SLOW_RULE_ID: &str = "CHAOS_SLOW_RULE";
+
+/// Faults to inject.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosSettings {
+    /// Delay added to every storage call
+    pub storage_latency_ms: u64,
+    /// Fraction of storage calls that fail, from 0 to 1
+    pub storage_error_rate: f64,
+    /// Delay added to streaming rule evaluation for each decision
+    pub rule_delay_ms: u64,
+    /// Fail every policy reload
+    pub fail_policy_load: bool,
+}
+
+impl ChaosSettings {
+    fn validate(&self) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&self.storage_error_rate) {
+            return Err(format!(
+                "storage_error_rate must be between 0 and 1, got {}",
+                self.storage_error_rate
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Shared fault injection state.
+#[derive(Debug, Default)]
+pub struct Chaos {
+    settings: RwLock<ChaosSettings>,
+    /// Storage errors and policy load failures injected so far
+    pub faults_injected: AtomicU64,
+}
+
+impl Chaos {
+    pub fn new(settings: ChaosSettings) -> Self {
+        Chaos {
+            settings: RwLock::new(settings),
+            faults_injected: AtomicU64::new(0),
+        }
+    }
+
+    /// Current settings.
+    pub fn settings(&self) -> ChaosSettings {
+        self.settings.read().clone()
+    }
+
+    /// Replace the settings.
+    pub fn set(&self, settings: ChaosSettings) -> Result<(), String> {
+        settings.validate()?;
+        warn!(settings = ?settings, "Chaos settings changed");
+        *self.settings.write() = settings;
+        Ok(())
+    }
+
+    /// Delay and possibly fail a storage call.
+    pub async fn storage_fault(&self) -> anyhow::Result<()> {
+        let settings = self.settings();
+        if settings.storage_latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(settings.storage_latency_ms)).await;
+        }
+        if settings.storage_error_rate > 0.0 && random_unit() < settings.storage_error_rate {
+            self.faults_injected.fetch_add(1, Ordering::Relaxed);
+            anyhow::bail!("chaos: injected storage error");
+        }
+        Ok(())
+    }
+
+    /// Returns true if this policy load should fail.
+    pub fn policy_load_fault(&self) -> bool {
+        let fail = self.settings.read().fail_policy_load;
+        if fail {
+            self.faults_injected.fetch_add(1, Ordering::Relaxed);
+        }
+        fail
+    }
+
+    /// Forward rule sets from `rx`, appending a rule that sleeps for
+    /// `rule_delay_ms` to each one.
+    pub fn inject_slow_rule(
+        self: &Arc<Self>,
+        mut rx: watch::Receiver<Arc<RuleSet>>,
+    ) -> (watch::Receiver<Arc<RuleSet>>, tokio::task::JoinHandle<()>) {
+        let with_slow_rule = |ruleset: &RuleSet, chaos: &Arc<Chaos>| {
+            let mut streaming = ruleset.streaming.clone();
+            streaming.push(Arc::new(SlowRule {
+                chaos: chaos.clone(),
+            }));
+            let dispatch = ruleset.dispatch.indexed(&ruleset.inline, &streaming);
+            Arc::new(RuleSet {
+                inline: ruleset.inline.clone(),
+                streaming,
+                sanctions: ruleset.sanctions.clone(),
+                policy_version: ruleset.policy_version.clone(),
+                monitor_only: ruleset.monitor_only,
+                annotations: ruleset.annotations.clone(),
+                action_plans: ruleset.action_plans.clone(),
+                features: ruleset.features.clone(),
+                needs_subject_lookup: ruleset.needs_subject_lookup,
+                sweep: ruleset.sweep.clone(),
+                mitigations: ruleset.mitigations.clone(),
+                dispatch,
+                subject_fields: ruleset.subject_fields.clone(),
+            })
+        };
+
+        let (tx, forwarded) = watch::channel(with_slow_rule(&rx.borrow_and_update(), self));
+        let chaos = self.clone();
+        let handle = tokio::spawn(async move {
+            while rx.changed().await.is_ok() {
+                let ruleset = with_slow_rule(&rx.borrow_and_update(), &chaos);
+                let _ = tx.send(ruleset);
+            }
+        });
+
+        (forwarded, handle)
+    }
+}
+
+/// Uniform random number in [0, 1).
+fn random_unit() -> f64 {
+    let bits = std::collections::hash_map::RandomState::new().hash_one(Instant::now());
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Streaming rule that only adds latency.
+#[derive(Debug)]
+struct SlowRule {
+    chaos: Arc<Chaos>,
+}
+
+#[async_trait]
+impl StreamingRule for SlowRule {
+    fn id(&self) -> &str {
+        SLOW_RULE_ID
+    }
+
+    fn describe(&self) -> RuleDescription {
+        RuleDescription {
+            id: SLOW_RULE_ID.to_string(),
+            rule_type: None,
+            action: None,
+            params: serde_json::json!({ "delay_ms": self.chaos.settings.read().rule_delay_ms }),
+        }
+    }
+
+    async fn evaluate(
+        &self,
+        _event: &TxEvent,
+        _subject_id: Uuid,
+        _storage: &dyn crate::storage::StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        let delay = self.chaos.settings.read().rule_delay_ms;
+        if delay > 0 {
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+        }
+        Ok(RuleResult::allow())
+    }
+}
+
+/// Admin routes for reading and changing chaos settings.
+pub fn router(chaos: Arc<Chaos>) -> Router {
+    Router::new()
+        .route("/v1/admin/chaos", get(get_settings).put(put_settings))
+        .with_state(chaos)
+}
+
+async fn get_settings(State(chaos): State<Arc<Chaos>>) -> Json<ChaosSettings> {
+    Json(chaos.settings())
+}
+
+async fn put_settings(
+    State(chaos): State<Arc<Chaos>>,
+    Json(settings): Json<ChaosSettings>,
+) -> axum::response::Response {
+    match chaos.set(settings) {
+        Ok(()) => {
+            info!("Chaos settings updated via admin endpoint");
+            Json(chaos.settings()).into_response()
+        }
+        Err(message) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::bad_request(message)),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{MockStorage, StorageRead};
+
+    #[tokio::test]
+    async fn test_storage_faults() {
+        let chaos = Arc::new(Chaos::default());
+        let storage = ChaosStorage::new(Arc::new(MockStorage::new()), chaos.clone());
+
+        assert!(storage.get_all_sanctions().await.is_ok());
+
+        chaos
+            .set(ChaosSettings {
+                storage_error_rate: 1.0,
+                ..Default::default()
+            })
+            .unwrap();
+        let err = storage.get_all_sanctions().await.unwrap_err();
+        assert!(err.to_string().contains("chaos"));
+        assert_eq!(chaos.faults_injected.load(Ordering::Relaxed), 1);
+
+        chaos
+            .set(ChaosSettings {
+                storage_latency_ms: 50,
+                ..Default::default()
+            })
+            .unwrap();
+        let start = Instant::now();
+        storage.get_all_sanctions().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn test_slow_rule_follows_ruleset_updates() {
+        let chaos = Arc::new(Chaos::new(ChaosSettings {
+            rule_delay_ms: 20,
+            ..Default::default()
+        }));
+        let (tx, rx) = watch::channel(Arc::new(RuleSet::empty()));
+        let (mut forwarded, handle) = chaos.inject_slow_rule(rx);
+
+        let ruleset = forwarded.borrow_and_update().clone();
+        assert_eq!(ruleset.streaming.len(), 1);
+        assert_eq!(ruleset.streaming[0].id(), SLOW_RULE_ID);
+
+        let mut next = RuleSet::empty();
+        next.policy_version = "v2".to_string();
+        tx.send(Arc::new(next)).unwrap();
+        forwarded.changed().await.unwrap();
+        assert_eq!(forwarded.borrow().policy_version, "v2");
+        assert_eq!(forwarded.borrow().streaming.len(), 1);
+
+        handle.abort();
+    }
+
+    #[test]
+    fn test_rejects_invalid_error_rate() {
+        let chaos = Chaos::default();
+        let result = chaos.set(ChaosSettings {
+            storage_error_rate: 1.5,
+            ..Default::default()
+        });
+        assert!(result.unwrap_err().contains("storage_error_rate"));
+        assert_eq!(chaos.settings(), ChaosSettings::default());
+    }
+}
@@ -0,0 +1,433 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::subject::{KycTier, SubjectAddress};
+use crate::domain::{Annotation, Case, CaseStatus, Decision, DecisionEvent, Policy, Subject};
+use crate::storage::{
+    AssetFlow, BlocklistEntry, DecisionRecord, KycOverride, OutboxEvent, PendingDeposit,
+    RuleSwitch, Storage, StorageRead, StorageWrite, StoredDecision, TransactionPoint,
+    TransactionRecord, TxSizeProfile, UsageRecord, WindowSpec,
+};
+
+use super::Chaos;
+
+/// Storage wrapper that delays or fails calls before they reach `inner`.
+///
+/// Wrap the base storage, beneath `ResilientStorage`, so injected errors
+/// count toward the circuit breaker.
+pub struct ChaosStorage {
+    inner: Arc<dyn Storage>,
+    chaos: Arc<Chaos>,
+}
+
+impl ChaosStorage {
+    pub fn new(inner: Arc<dyn Storage>, chaos: Arc<Chaos>) -> Self {
+        ChaosStorage { inner, chaos }
+    }
+}
+
+#[async_trait]
+impl StorageRead for ChaosStorage {
+    async fn get_subject_by_user_id(
+        &self,
+        user_id: &str,
+    ) -> anyhow::Result<Option<(Uuid, Subject)>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_subject_by_user_id(user_id).await
+    }
+
+    async fn get_subject_addresses(&self, subject_id: Uuid) -> anyhow::Result<Vec<SubjectAddress>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_subject_addresses(subject_id).await
+    }
+
+    async fn get_kyc_overrides(&self) -> anyhow::Result<Vec<KycOverride>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_kyc_overrides().await
+    }
+
+    async fn get_rolling_volume(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_rolling_volume(subject_id, window).await
+    }
+
+    async fn get_rolling_amount(
+        &self,
+        subject_id: Uuid,
+        asset: &str,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        self.chaos.storage_fault().await?;
+        self.inner
+            .get_rolling_amount(subject_id, asset, window)
+            .await
+    }
+
+    async fn get_small_tx_count(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+        threshold: Decimal,
+    ) -> anyhow::Result<u32> {
+        self.chaos.storage_fault().await?;
+        self.inner
+            .get_small_tx_count(subject_id, window, threshold)
+            .await
+    }
+
+    async fn get_counterparty_tx_count(
+        &self,
+        subject_id: Uuid,
+        country: &str,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        self.chaos.storage_fault().await?;
+        self.inner
+            .get_counterparty_tx_count(subject_id, country, window)
+            .await
+    }
+
+    async fn get_asset_flows(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<AssetFlow>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_asset_flows(subject_id, window).await
+    }
+
+    async fn get_recent_transactions(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<TransactionPoint>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_recent_transactions(subject_id, window).await
+    }
+
+    async fn get_hourly_activity(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<[u32; 24]> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_hourly_activity(subject_id, window).await
+    }
+
+    async fn get_window_aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_window_aggregates(subject_id, specs).await
+    }
+
+    async fn get_window_aggregates_at(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.chaos.storage_fault().await?;
+        self.inner
+            .get_window_aggregates_at(subject_id, specs, at)
+            .await
+    }
+
+    async fn get_tx_size_profile(&self, subject_id: Uuid) -> anyhow::Result<Option<TxSizeProfile>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_tx_size_profile(subject_id).await
+    }
+
+    async fn get_all_sanctions(&self) -> anyhow::Result<Vec<String>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_all_sanctions().await
+    }
+
+    async fn is_sanctioned(&self, address: &str) -> anyhow::Result<bool> {
+        self.chaos.storage_fault().await?;
+        self.inner.is_sanctioned(address).await
+    }
+
+    async fn get_active_policy(&self) -> anyhow::Result<Option<Policy>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_active_policy().await
+    }
+
+    async fn get_policy(&self, version: &str) -> anyhow::Result<Option<Policy>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_policy(version).await
+    }
+
+    async fn count_recent_decisions(
+        &self,
+        subject_id: Uuid,
+        min_decision: Decision,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        self.chaos.storage_fault().await?;
+        self.inner
+            .count_recent_decisions(subject_id, min_decision, window)
+            .await
+    }
+
+    async fn get_decisions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_decisions(from, to, after, limit).await
+    }
+
+    async fn get_decision_by_request_id(
+        &self,
+        request_id: &str,
+    ) -> anyhow::Result<Option<StoredDecision>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_decision_by_request_id(request_id).await
+    }
+
+    async fn get_subject_decisions(
+        &self,
+        subject_id: Uuid,
+        at: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        self.chaos.storage_fault().await?;
+        self.inner
+            .get_subject_decisions(subject_id, at, limit)
+            .await
+    }
+
+    async fn get_recent_decisions(&self, limit: u32) -> anyhow::Result<Vec<StoredDecision>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_recent_decisions(limit).await
+    }
+
+    async fn pending_outbox(&self, limit: u32) -> anyhow::Result<Vec<OutboxEvent>> {
+        self.chaos.storage_fault().await?;
+        self.inner.pending_outbox(limit).await
+    }
+
+    async fn get_pending_deposit(&self, event_id: &str) -> anyhow::Result<Option<PendingDeposit>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_pending_deposit(event_id).await
+    }
+
+    async fn get_case(&self, case_id: Uuid) -> anyhow::Result<Option<Case>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_case(case_id).await
+    }
+
+    async fn list_cases(
+        &self,
+        status: Option<CaseStatus>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Case>> {
+        self.chaos.storage_fault().await?;
+        self.inner.list_cases(status, limit).await
+    }
+
+    async fn get_annotations(
+        &self,
+        subject_id: Uuid,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Annotation>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_annotations(subject_id, limit).await
+    }
+
+    async fn get_disabled_rules(&self) -> anyhow::Result<Vec<RuleSwitch>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_disabled_rules().await
+    }
+
+    async fn get_blocklist(&self) -> anyhow::Result<Vec<BlocklistEntry>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_blocklist().await
+    }
+
+    async fn get_daily_usage(&self, tenant: &str) -> anyhow::Result<u64> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_daily_usage(tenant).await
+    }
+
+    async fn get_usage(&self, from: NaiveDate, to: NaiveDate) -> anyhow::Result<Vec<UsageRecord>> {
+        self.chaos.storage_fault().await?;
+        self.inner.get_usage(from, to).await
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.inner.is_degraded()
+    }
+}
+
+#[async_trait]
+impl StorageWrite for ChaosStorage {
+    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid> {
+        self.chaos.storage_fault().await?;
+        self.inner.upsert_subject(subject).await
+    }
+
+    async fn set_kyc_tier(&self, user_id: &str, tier: &KycTier) -> anyhow::Result<bool> {
+        self.chaos.storage_fault().await?;
+        self.inner.set_kyc_tier(user_id, tier).await
+    }
+
+    async fn save_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+        verified: Option<bool>,
+        label: Option<&str>,
+    ) -> anyhow::Result<SubjectAddress> {
+        self.chaos.storage_fault().await?;
+        self.inner
+            .save_subject_address(subject_id, address, verified, label)
+            .await
+    }
+
+    async fn remove_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+    ) -> anyhow::Result<bool> {
+        self.chaos.storage_fault().await?;
+        self.inner.remove_subject_address(subject_id, address).await
+    }
+
+    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid> {
+        self.chaos.storage_fault().await?;
+        self.inner.record_transaction(tx).await
+    }
+
+    async fn set_active_policy(&self, policy: &Policy) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.set_active_policy(policy).await
+    }
+
+    async fn record_decision(&self, decision: &DecisionRecord) -> anyhow::Result<Uuid> {
+        self.chaos.storage_fault().await?;
+        self.inner.record_decision(decision).await
+    }
+
+    async fn record_outcome(
+        &self,
+        tx: &TransactionRecord,
+        decision: &DecisionRecord,
+    ) -> anyhow::Result<Uuid> {
+        self.chaos.storage_fault().await?;
+        self.inner.record_outcome(tx, decision).await
+    }
+
+    async fn record_decision_outcome(
+        &self,
+        decision: &DecisionRecord,
+        note: Option<&str>,
+    ) -> anyhow::Result<(Uuid, bool)> {
+        self.chaos.storage_fault().await?;
+        self.inner.record_decision_outcome(decision, note).await
+    }
+
+    async fn claim_outbox(&self, limit: u32, lease: Duration) -> anyhow::Result<Vec<OutboxEvent>> {
+        self.chaos.storage_fault().await?;
+        self.inner.claim_outbox(limit, lease).await
+    }
+
+    async fn mark_outbox_delivered(&self, id: Uuid) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.mark_outbox_delivered(id).await
+    }
+
+    async fn mark_outbox_failed(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.mark_outbox_failed(id, error).await
+    }
+
+    async fn mark_outbox_dead(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.mark_outbox_dead(id, error).await
+    }
+
+    async fn save_pending_deposit(&self, deposit: &PendingDeposit) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.save_pending_deposit(deposit).await
+    }
+
+    async fn resolve_pending_deposit(
+        &self,
+        event_id: &str,
+        decision: &DecisionRecord,
+        event: &DecisionEvent,
+    ) -> anyhow::Result<Option<Uuid>> {
+        self.chaos.storage_fault().await?;
+        self.inner
+            .resolve_pending_deposit(event_id, decision, event)
+            .await
+    }
+
+    async fn create_case(
+        &self,
+        subject_id: Uuid,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        self.chaos.storage_fault().await?;
+        self.inner.create_case(subject_id, note).await
+    }
+
+    async fn update_case_status(
+        &self,
+        case_id: Uuid,
+        from: CaseStatus,
+        to: CaseStatus,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        self.chaos.storage_fault().await?;
+        self.inner.update_case_status(case_id, from, to, note).await
+    }
+
+    async fn add_annotation(&self, annotation: &Annotation) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.add_annotation(annotation).await
+    }
+
+    async fn disable_rule(&self, switch: &RuleSwitch) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.disable_rule(switch).await
+    }
+
+    async fn enable_rule(&self, rule_id: &str, actor: &str) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.enable_rule(rule_id, actor).await
+    }
+
+    async fn save_blocklist(
+        &self,
+        entries: &[BlocklistEntry],
+        replace: bool,
+    ) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.save_blocklist(entries, replace).await
+    }
+
+    async fn record_usage(&self, tenant: &str, rules_hit: &[String]) -> anyhow::Result<()> {
+        self.chaos.storage_fault().await?;
+        self.inner.record_usage(tenant, rules_hit).await
+    }
+
+    async fn claim_nonce(&self, nonce: &str, expires_at: DateTime<Utc>) -> anyhow::Result<bool> {
+        self.chaos.storage_fault().await?;
+        self.inner.claim_nonce(nonce, expires_at).await
+    }
+}
@@ -0,0 +1,508 @@
+//! Typed async client for the riskr HTTP API.
+//!
+//! Requests and responses use the server's own types from [`crate::api`],
+//! so the client cannot drift from the routes it calls. Enabled with the
+//! `client` feature.
+//!
+//! ```no_run
+//! # async fn run(request: riskr::api::request::DecisionRequest) -> Result<(), riskr::client::ClientError> {
+//! use riskr::client::RiskrClient;
+//!
+//! let client = RiskrClient::new("http://riskr:8080").with_tenant("payments");
+//! let response = client.check(&request).await?;
+//! println!("{} ({})", response.decision, response.decision_code);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use chrono::{NaiveDate, Utc};
+use futures::stream::{self, StreamExt};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::api::request::{
+    ConfirmationUpdate, DecisionQuery, DecisionRequest, KycUpdateRequest, ResponseDetail,
+    RuleSwitchRequest, ScreeningRequest, UsageQuery,
+};
+use crate::api::request_id::REQUEST_ID_HEADER;
+use crate::api::response::{
+    ConfirmationResponse, DecisionResponse, ErrorCode, ErrorResponse, RuleSwitchResponse,
+    ScreeningResponse, UsageResponse,
+};
+use crate::api::signing;
+use crate::api::tenant::TENANT_HEADER;
+use crate::domain::{DecisionEvent, KycTier};
+use crate::identity::encode_path_segment;
+
+/// Default timeout for one API call.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Checks sent at once by [`RiskrClient::check_batch`].
+pub const BATCH_CONCURRENCY: usize = 16;
+
+/// Largest response body read.
+const MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Error from an API call.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("invalid request: {0}")]
+    Request(String),
+
+    #[error("HTTP error: {0}")]
+    Http(String),
+
+    #[error("request timed out after {0:?}")]
+    Timeout(Duration),
+
+    /// The server answered with a non-success status. `error` is the
+    /// server's error body, when it sent one.
+    #[error("server returned {status}")]
+    Status {
+        status: StatusCode,
+        error: Option<ErrorResponse>,
+    },
+
+    #[error("failed to decode response: {0}")]
+    Decode(#[from] serde_json::Error),
+}
+
+impl ClientError {
+    /// HTTP status of a rejected call.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClientError::Status { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// The server's error code, when it sent an error body.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            ClientError::Status {
+                error: Some(error), ..
+            } => Some(error.code),
+            _ => None,
+        }
+    }
+
+    /// Returns true if retrying the same call later may succeed: timeouts,
+    /// connection failures and server errors whose code says so.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ClientError::Http(_) | ClientError::Timeout(_) => true,
+            ClientError::Status { error, status } => match error {
+                Some(error) => error.code.is_retryable(),
+                None => status.is_server_error(),
+            },
+            ClientError::Request(_) | ClientError::Decode(_) => false,
+        }
+    }
+}
+
+/// Async client for a riskr server.
+#[derive(Clone)]
+pub struct RiskrClient {
+    client: Client<HttpConnector, Body>,
+    base_url: String,
+    timeout: Duration,
+    tenant: Option<String>,
+    signing_secret: Option<Arc<[u8]>>,
+}
+
+impl RiskrClient {
+    /// Client for the server at `base_url`, e.g. `http://riskr:8080`.
+    pub fn new(base_url: &str) -> Self {
+        RiskrClient {
+            client: Client::builder(TokioExecutor::new()).build_http(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            tenant: None,
+            signing_secret: None,
+        }
+    }
+
+    /// Set the timeout for each call.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send calls on behalf of a tenant, for quotas and usage reporting.
+    pub fn with_tenant(mut self, tenant: impl Into<String>) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    /// Sign POST requests with `secret`, for servers that require request
+    /// signing.
+    pub fn with_signing_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.signing_secret = Some(Arc::from(secret.as_ref()));
+        self
+    }
+
+    /// Decide a transaction.
+    pub async fn check(&self, request: &DecisionRequest) -> Result<DecisionResponse, ClientError> {
+        self.check_with(request, &DecisionQuery::default(), None)
+            .await
+    }
+
+    /// Decide a transaction with query options, correlated by `request_id`
+    /// so the decision can later be fetched with [`get_decision`].
+    ///
+    /// `response_detail=minimal` is not supported, since its body is not a
+    /// full [`DecisionResponse`].
+    ///
+    /// [`get_decision`]: RiskrClient::get_decision
+    pub async fn check_with(
+        &self,
+        request: &DecisionRequest,
+        query: &DecisionQuery,
+        request_id: Option<&str>,
+    ) -> Result<DecisionResponse, ClientError> {
+        if query.response_detail == ResponseDetail::Minimal {
+            return Err(ClientError::Request(
+                "response_detail=minimal is not supported".to_string(),
+            ));
+        }
+        let path = format!("/v1/decision/check?{}", query_string(query)?);
+        self.send(Method::POST, &path, Some(request), request_id)
+            .await
+    }
+
+    /// Decide several transactions, returning their results in order.
+    ///
+    /// The server has no batch endpoint, so each request is its own check;
+    /// up to [`BATCH_CONCURRENCY`] run at once and one failing does not
+    /// stop the others.
+    pub async fn check_batch(
+        &self,
+        requests: &[DecisionRequest],
+    ) -> Vec<Result<DecisionResponse, ClientError>> {
+        stream::iter(requests)
+            .map(|request| self.check(request))
+            .buffered(BATCH_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Final decision recorded for a request ID, e.g. after a provisional
+    /// answer.
+    pub async fn get_decision(&self, request_id: &str) -> Result<DecisionEvent, ClientError> {
+        let path = format!("/v1/decisions/{}", encode_path_segment(request_id));
+        self.send(Method::GET, &path, None::<&()>, None).await
+    }
+
+    /// Screen addresses against the sanctions list.
+    pub async fn screen_addresses(
+        &self,
+        addresses: Vec<String>,
+    ) -> Result<ScreeningResponse, ClientError> {
+        let request = ScreeningRequest { addresses };
+        self.send(
+            Method::POST,
+            "/v1/screening/addresses",
+            Some(&request),
+            None,
+        )
+        .await
+    }
+
+    /// Record a subject's KYC tier out of band.
+    pub async fn update_kyc(&self, user_id: &str, kyc_level: KycTier) -> Result<(), ClientError> {
+        let path = format!("/v1/subjects/{}/kyc", encode_path_segment(user_id));
+        let request = KycUpdateRequest { kyc_level };
+        self.send_empty(Method::POST, &path, Some(&request)).await
+    }
+
+    /// Report confirmations for a deposit held pending finality.
+    pub async fn report_confirmations(
+        &self,
+        event_id: &str,
+        confirmations: u32,
+    ) -> Result<ConfirmationResponse, ClientError> {
+        let path = format!("/v1/events/{}/confirmations", encode_path_segment(event_id));
+        let update = ConfirmationUpdate { confirmations };
+        self.send(Method::POST, &path, Some(&update), None).await
+    }
+
+    /// Turn a rule off through the admin kill-switch.
+    pub async fn disable_rule(
+        &self,
+        rule_id: &str,
+        reason: Option<String>,
+    ) -> Result<RuleSwitchResponse, ClientError> {
+        let path = format!("/v1/admin/rules/{}/disable", encode_path_segment(rule_id));
+        let request = RuleSwitchRequest { reason };
+        self.send(Method::POST, &path, Some(&request), None).await
+    }
+
+    /// Turn a rule switched off by the admin kill-switch back on.
+    pub async fn enable_rule(&self, rule_id: &str) -> Result<RuleSwitchResponse, ClientError> {
+        let path = format!("/v1/admin/rules/{}/enable", encode_path_segment(rule_id));
+        self.send(Method::POST, &path, None::<&()>, None).await
+    }
+
+    /// Usage per tenant over inclusive UTC days.
+    pub async fn usage(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<UsageResponse, ClientError> {
+        let path = format!(
+            "/v1/admin/usage?{}",
+            query_string(&UsageQuery { from, to })?
+        );
+        self.send(Method::GET, &path, None::<&()>, None).await
+    }
+
+    async fn send<B: Serialize, T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+        request_id: Option<&str>,
+    ) -> Result<T, ClientError> {
+        let body = self.call(method, path, body, request_id).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    async fn send_empty<B: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<(), ClientError> {
+        self.call(method, path, body, None).await.map(|_| ())
+    }
+
+    /// Make a call, returning the body of a success response.
+    async fn call<B: Serialize>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+        request_id: Option<&str>,
+    ) -> Result<Vec<u8>, ClientError> {
+        let bytes = body.map(serde_json::to_vec).transpose()?;
+        let mut request = Request::builder()
+            .method(method.clone())
+            .uri(format!("{}{}", self.base_url, path))
+            .header(header::ACCEPT, "application/json");
+        if let Some(tenant) = &self.tenant {
+            request = request.header(TENANT_HEADER, tenant);
+        }
+        if let Some(request_id) = request_id {
+            request = request.header(REQUEST_ID_HEADER, request_id);
+        }
+        if let (Some(secret), &Method::POST) = (&self.signing_secret, &method) {
+            let timestamp = Utc::now().timestamp();
+            let nonce = Uuid::new_v4().to_string();
+            let signature = signing::sign(
+                secret,
+                timestamp,
+                &nonce,
+                &method,
+                path,
+                bytes.as_deref().unwrap_or_default(),
+            );
+            request = request
+                .header(signing::TIMESTAMP_HEADER, timestamp.to_string())
+                .header(signing::NONCE_HEADER, nonce)
+                .header(signing::SIGNATURE_HEADER, signature);
+        }
+        let request = match bytes {
+            Some(bytes) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(bytes)),
+            None => request.body(Body::empty()),
+        }
+        .map_err(|e| ClientError::Request(e.to_string()))?;
+
+        let call = async {
+            let response = self
+                .client
+                .request(request)
+                .await
+                .map_err(|e| ClientError::Http(e.to_string()))?;
+            let status = response.status();
+            let body = axum::body::to_bytes(Body::new(response.into_body()), MAX_RESPONSE_BYTES)
+                .await
+                .map_err(|e| ClientError::Http(e.to_string()))?;
+            if !status.is_success() {
+                return Err(ClientError::Status {
+                    status,
+                    error: serde_json::from_slice(&body).ok(),
+                });
+            }
+            Ok(body.to_vec())
+        };
+
+        tokio::time::timeout(self.timeout, call)
+            .await
+            .map_err(|_| ClientError::Timeout(self.timeout))?
+    }
+}
+
+impl fmt::Debug for RiskrClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RiskrClient")
+            .field("base_url", &self.base_url)
+            .field("timeout", &self.timeout)
+            .field("tenant", &self.tenant)
+            .field("signed", &self.signing_secret.is_some())
+            .finish()
+    }
+}
+
+/// Encode query parameters the way the server's `Query` extractor reads
+/// them.
+fn query_string<Q: Serialize>(query: &Q) -> Result<String, ClientError> {
+    let serde_json::Value::Object(fields) = serde_json::to_value(query)? else {
+        return Err(ClientError::Request("query must be a struct".to_string()));
+    };
+    let pairs: Vec<String> = fields
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::Null => return None,
+                serde_json::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            Some(format!("{}={}", key, encode_path_segment(&value)))
+        })
+        .collect();
+    Ok(pairs.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Decision;
+    use axum::extract::{Path, Query};
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use axum::{Json, Router};
+
+    async fn serve(app: Router) -> RiskrClient {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        RiskrClient::new(&format!("http://{}/", addr)).with_timeout(Duration::from_millis(500))
+    }
+
+    fn request(user_id: &str) -> DecisionRequest {
+        serde_json::from_value(serde_json::json!({
+            "subject": { "user_id": user_id, "account_id": "A1" },
+            "tx": { "type": "withdraw", "asset": "USDC", "usd_value": "100" }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_check_round_trips_server_types() {
+        let app = Router::new().route(
+            "/v1/decision/check",
+            post(
+                |headers: HeaderMap,
+                 Query(query): Query<DecisionQuery>,
+                 Json(req): Json<DecisionRequest>| async move {
+                    assert_eq!(headers[TENANT_HEADER], "payments");
+                    if req.subject.user_id == "BAD" {
+                        return Err((
+                            StatusCode::BAD_REQUEST,
+                            Json(ErrorResponse::bad_request("unknown asset")),
+                        ));
+                    }
+                    let mut response = DecisionResponse::allow("v7".to_string());
+                    response.decision_code = req.subject.user_id;
+                    response.enforced = !query.debug;
+                    Ok(Json(response))
+                },
+            ),
+        );
+        let client = serve(app).await.with_tenant("payments");
+
+        let response = client.check(&request("U1")).await.unwrap();
+        assert_eq!(response.decision, Decision::Allow);
+        assert_eq!(response.policy_version, "v7");
+        assert!(response.enforced);
+
+        let query = DecisionQuery {
+            debug: true,
+            ..Default::default()
+        };
+        let response = client
+            .check_with(&request("U1"), &query, Some("req-1"))
+            .await
+            .unwrap();
+        assert!(!response.enforced);
+
+        let err = client.check(&request("BAD")).await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(err.code(), Some(ErrorCode::ValidationFailed));
+        assert!(!err.is_retryable());
+        let ClientError::Status { error, .. } = err else {
+            panic!("expected a status error");
+        };
+        assert_eq!(error.unwrap().error, "unknown asset");
+
+        let results = client
+            .check_batch(&[request("U1"), request("BAD"), request("U2")])
+            .await;
+        assert_eq!(results[0].as_ref().unwrap().decision_code, "U1");
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap().decision_code, "U2");
+    }
+
+    #[tokio::test]
+    async fn test_admin_calls() {
+        let app = Router::new()
+            .route(
+                "/v1/admin/rules/:rule_id/disable",
+                post(
+                    |Path(rule_id): Path<String>, Json(req): Json<RuleSwitchRequest>| async move {
+                        assert_eq!(req.reason.as_deref(), Some("false positives"));
+                        Json(RuleSwitchResponse {
+                            rule_id,
+                            disabled: true,
+                            persisted: true,
+                        })
+                    },
+                ),
+            )
+            .route(
+                "/v1/admin/usage",
+                axum::routing::get(|Query(query): Query<UsageQuery>| async move {
+                    Json(UsageResponse::new(query.from, query.to, Vec::new()))
+                }),
+            );
+        let client = serve(app).await;
+
+        let switched = client
+            .disable_rule("R 1", Some("false positives".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(switched.rule_id, "R 1");
+        assert!(switched.disabled);
+
+        let day = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let usage = client.usage(day, day).await.unwrap();
+        assert_eq!(usage.from, day);
+        assert!(usage.tenants.is_empty());
+
+        let err = client.enable_rule("R1").await.unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+    }
+}
@@ -1,7 +1,255 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use clap::Parser;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
+use sqlx::postgres::PgConnectOptions;
+
+use crate::api::auth::{AuthError, Scope};
+use crate::api::deadline::RouteTimeouts;
+use crate::api::enrich::SubjectEnrichment;
+use crate::api::oidc::{is_https, OidcConfig, DEFAULT_FETCH_TIMEOUT};
+use crate::api::signing::RequestSigning;
+use crate::export::ExportFormat;
+use crate::lists::{ListFormat, ListKind};
+use crate::loadtest::RuleMix;
+use crate::policy::BakeOptions;
+use crate::retention::{DataClass, EvidenceRetention};
+use crate::rules::RuleSla;
+use crate::storage::{BreakerOptions, RetryPolicy};
+use crate::sweep::SweepOptions;
+
+/// How decisions are made while storage is unavailable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DegradedMode {
+    /// Allow the transaction
+    #[default]
+    FailOpen,
+    /// Ask the caller to retry later (SOFT_DENY_RETRY)
+    FailClosed,
+    /// Decide on inline rules only
+    InlineOnly,
+}
+
+/// Subcommands; the decision server runs when none is given.
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Compliance scenario tooling
+    Scenarios {
+        #[command(subcommand)]
+        command: ScenarioCommand,
+    },
+    /// Rule test vector tooling
+    Vectors {
+        #[command(subcommand)]
+        command: VectorCommand,
+    },
+    /// Sanctions list tooling
+    Sanctions {
+        #[command(subcommand)]
+        command: SanctionsCommand,
+    },
+    /// Audit record export; requires --database-url
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+    /// Bulk import and export of KYC overrides and the internal
+    /// blocklist; requires --database-url
+    Lists {
+        #[command(subcommand)]
+        command: ListCommand,
+    },
+    /// Database maintenance; requires --database-url
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+    /// Configuration tooling
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Continuously send canary requests to an instance and check decisions
+    Probe {
+        /// Base URL of the instance, e.g. http://riskr:8080
+        #[arg(long, env = "RISKR_PROBE_TARGET")]
+        target: String,
+
+        /// Canary YAML file
+        #[arg(long, env = "RISKR_PROBE_CANARIES")]
+        canaries: PathBuf,
+
+        /// Milliseconds between probes
+        #[arg(long, default_value = "1000", env = "RISKR_PROBE_INTERVAL_MS")]
+        interval_ms: u64,
+
+        /// Milliseconds a probe may take before it counts as an error
+        #[arg(long, default_value = "2000", env = "RISKR_PROBE_TIMEOUT_MS")]
+        timeout_ms: u64,
+
+        /// Address serving probe metrics at /metrics
+        #[arg(long, default_value = "0.0.0.0:9091", env = "RISKR_PROBE_METRICS_ADDR")]
+        metrics_addr: String,
+    },
+    /// Drive the configured policy's rules in-process with synthetic
+    /// transactions and report latency
+    Loadtest {
+        /// Target decisions per second; 0 runs as fast as possible
+        #[arg(long, default_value = "0")]
+        tps: u32,
+
+        /// Seconds to run for
+        #[arg(long, default_value = "30")]
+        duration_secs: u64,
+
+        /// Distinct users transactions are spread over
+        #[arg(long, default_value = "10000")]
+        users: u32,
+
+        /// Concurrent workers
+        #[arg(long, default_value = "8")]
+        workers: u32,
+
+        /// Rules each decision runs
+        #[arg(long, value_enum, default_value = "all")]
+        mix: RuleMix,
+
+        /// Write the report to this file instead of stdout
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+}
+
+/// Configuration subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigCommand {
+    /// Validate the server configuration and report every problem found
+    Check,
+}
+
+/// Database subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum DbCommand {
+    /// Partition existing transactions and decisions tables by month. Each
+    /// table is locked while its rows are copied.
+    Partition,
+}
+
+/// Bulk list subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ListCommand {
+    /// Write a list to a file
+    Export {
+        #[arg(value_enum)]
+        list: ListKind,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ListFormat,
+
+        /// Output file
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// Load a list from a file. Nothing is applied if any row is invalid.
+    Import {
+        #[arg(value_enum)]
+        list: ListKind,
+
+        /// Input file
+        #[arg(long)]
+        input: PathBuf,
+
+        /// Input format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ListFormat,
+
+        /// Validate without applying
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Blocklist only: make the file the whole list
+        #[arg(long)]
+        replace: bool,
+
+        /// Recorded as who added blocklist entries
+        #[arg(long, default_value = "cli")]
+        actor: String,
+    },
+}
+
+/// Export subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ExportCommand {
+    /// Export decisions for a range of UTC days, with --export-redact applied
+    Decisions {
+        /// First day, inclusive (YYYY-MM-DD)
+        #[arg(long)]
+        from: NaiveDate,
+
+        /// Last day, inclusive (YYYY-MM-DD)
+        #[arg(long)]
+        to: NaiveDate,
+
+        /// Output format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+
+        /// Output file
+        #[arg(long)]
+        output: PathBuf,
+    },
+}
+
+/// Sanctions subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum SanctionsCommand {
+    /// Compile a text sanctions list into a memory-mappable file
+    Compile {
+        /// Text list, one address per line
+        input: PathBuf,
+
+        /// Compiled output; replaced atomically
+        output: PathBuf,
+    },
+}
+
+/// Scenario subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum ScenarioCommand {
+    /// Run every scenario file in a directory against the configured policy
+    Run {
+        /// Directory of scenario YAML files
+        dir: PathBuf,
+
+        /// Write the report to this file instead of stdout
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        /// Key used to sign the report with HMAC-SHA256
+        #[arg(long, env = "RISKR_SCENARIO_SIGNING_KEY", hide_env_values = true)]
+        signing_key: Option<String>,
+    },
+}
+
+/// Test vector subcommands.
+#[derive(Debug, Clone, Subcommand)]
+pub enum VectorCommand {
+    /// Evaluate the canonical cases and write them with their outcomes
+    Generate {
+        /// Write the vectors to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Evaluate a vector file and report outcomes that differ
+    Verify {
+        /// Vector JSON file
+        input: PathBuf,
+    },
+}
 
 /// Risk engine configuration.
 #[derive(Debug, Clone, Parser)]
@@ -12,6 +260,69 @@ pub struct Config {
     #[arg(long, default_value = "0.0.0.0:8080", env = "RISKR_LISTEN_ADDR")]
     pub listen_addr: String,
 
+    /// Unix domain socket path to listen on in addition to TCP (optional)
+    #[arg(long, env = "RISKR_LISTEN_SOCKET")]
+    pub listen_socket: Option<PathBuf>,
+
+    /// Accept HTTP/2 connections (h2c) alongside HTTP/1.1
+    #[arg(long, default_value = "true", env = "RISKR_HTTP2")]
+    pub http2: bool,
+
+    /// Keep HTTP/1.1 connections alive between requests
+    #[arg(long, default_value = "true", env = "RISKR_HTTP_KEEP_ALIVE")]
+    pub http_keep_alive: bool,
+
+    /// HTTP/2 keep-alive ping interval in seconds (optional)
+    #[arg(long, env = "RISKR_HTTP2_KEEP_ALIVE_SECS")]
+    pub http2_keep_alive_secs: Option<u64>,
+
+    /// Maximum concurrent streams per HTTP/2 connection (optional)
+    #[arg(long, env = "RISKR_HTTP2_MAX_STREAMS")]
+    pub http2_max_concurrent_streams: Option<u32>,
+
+    /// Maximum open connections per listener (optional)
+    #[arg(long, env = "RISKR_MAX_CONNECTIONS")]
+    pub max_connections: Option<usize>,
+
+    /// Maximum request body size in bytes; larger bodies are rejected with
+    /// 413
+    #[arg(long, default_value = "2097152", env = "RISKR_MAX_REQUEST_BODY_BYTES")]
+    pub max_request_body_bytes: usize,
+
+    /// Compress responses with gzip, deflate or brotli when the client
+    /// accepts it
+    #[arg(long, default_value = "true", env = "RISKR_RESPONSE_COMPRESSION")]
+    pub response_compression: bool,
+
+    /// Smallest response body in bytes that is compressed
+    #[arg(long, default_value = "1024", env = "RISKR_COMPRESSION_MIN_BYTES")]
+    pub compression_min_bytes: u16,
+
+    /// Seconds a client has to send a request's headers before the
+    /// connection is closed
+    #[arg(long, default_value = "10", env = "RISKR_HEADER_READ_TIMEOUT_SECS")]
+    pub header_read_timeout_secs: u64,
+
+    /// Seconds a client has to send a request's body before the request
+    /// fails
+    #[arg(long, default_value = "30", env = "RISKR_BODY_READ_TIMEOUT_SECS")]
+    pub body_read_timeout_secs: u64,
+
+    /// Milliseconds a decision request may take before it is answered with
+    /// SOFT_DENY_RETRY and a 504 (0 disables)
+    #[arg(long, default_value = "2000", env = "RISKR_DECISION_TIMEOUT_MS")]
+    pub decision_timeout_ms: u64,
+
+    /// Seconds an admin request may take before it fails with a 504
+    /// (0 disables)
+    #[arg(long, default_value = "30", env = "RISKR_ADMIN_TIMEOUT_SECS")]
+    pub admin_timeout_secs: u64,
+
+    /// Seconds an export or import request may take before it fails with a
+    /// 504 (0 disables)
+    #[arg(long, default_value = "300", env = "RISKR_EXPORT_TIMEOUT_SECS")]
+    pub export_timeout_secs: u64,
+
     /// Path to policy YAML file
     #[arg(long, default_value = "policy.yaml", env = "RISKR_POLICY_PATH")]
     pub policy_path: PathBuf,
@@ -20,6 +331,66 @@ pub struct Config {
     #[arg(long, default_value = "sanctions.txt", env = "RISKR_SANCTIONS_PATH")]
     pub sanctions_path: PathBuf,
 
+    /// Directory caching compiled text sanctions lists by content hash, so
+    /// unchanged lists are not rebuilt on restart or reload (optional)
+    #[arg(long, env = "RISKR_COMPILE_CACHE_DIR")]
+    pub compile_cache_dir: Option<PathBuf>,
+
+    /// Directory where the last policy and sanctions list to load are
+    /// saved, and loaded from if the files fail to load at startup
+    /// (optional, no rules are served until they load if not set)
+    #[arg(long, env = "RISKR_LAST_KNOWN_GOOD_DIR")]
+    pub last_known_good_dir: Option<PathBuf>,
+
+    /// Path to asset registry file (optional, accepts any asset if not set)
+    #[arg(long, env = "RISKR_ASSETS_PATH")]
+    pub assets_path: Option<PathBuf>,
+
+    /// Path to API keys and their scopes for admin endpoints (optional,
+    /// admin endpoints are unauthenticated if not set)
+    #[arg(long, env = "RISKR_API_KEYS_PATH")]
+    pub api_keys_path: Option<PathBuf>,
+
+    /// Scope groups (`policy`, `exports`, ...) whose admin routes accept
+    /// API keys; all if not set
+    #[arg(long, value_delimiter = ',', env = "RISKR_API_KEY_ROUTE_GROUPS")]
+    pub api_key_route_groups: Vec<String>,
+
+    /// Issuer of OIDC tokens accepted on admin routes (optional, disables
+    /// SSO if not set)
+    #[arg(long, env = "RISKR_OIDC_ISSUER")]
+    pub oidc_issuer: Option<String>,
+
+    /// Audience OIDC tokens must be issued for
+    #[arg(long, default_value = "riskr", env = "RISKR_OIDC_AUDIENCE")]
+    pub oidc_audience: String,
+
+    /// JWKS URL of the OIDC provider; discovered from the issuer if not set
+    #[arg(long, env = "RISKR_OIDC_JWKS_URL")]
+    pub oidc_jwks_url: Option<String>,
+
+    /// Token claim holding the caller's groups or scopes
+    #[arg(long, default_value = "scope", env = "RISKR_OIDC_SCOPES_CLAIM")]
+    pub oidc_scopes_claim: String,
+
+    /// Scopes granted by a claim value as `value=scope+scope`; repeatable.
+    /// Claim values that are scope names grant that scope
+    #[arg(
+        long = "oidc-scope-map",
+        value_parser = parse_scope_mapping,
+        value_delimiter = ',',
+        env = "RISKR_OIDC_SCOPE_MAP"
+    )]
+    pub oidc_scope_map: Vec<(String, Vec<Scope>)>,
+
+    /// Scope groups whose admin routes accept OIDC tokens; all if not set
+    #[arg(long, value_delimiter = ',', env = "RISKR_OIDC_ROUTE_GROUPS")]
+    pub oidc_route_groups: Vec<String>,
+
+    /// Seconds the OIDC provider's signing keys are cached
+    #[arg(long, default_value = "300", env = "RISKR_OIDC_JWKS_CACHE_SECS")]
+    pub oidc_jwks_cache_secs: u64,
+
     /// Path to WAL directory (optional, disables WAL if not set)
     #[arg(long, env = "RISKR_WAL_PATH")]
     pub wal_path: Option<PathBuf>,
@@ -32,10 +403,116 @@ pub struct Config {
     #[arg(long, default_value = "30", env = "RISKR_POLICY_RELOAD_SECS")]
     pub policy_reload_secs: u64,
 
+    /// Seconds to watch a newly activated policy before keeping it; 0
+    /// disables automatic rollback
+    #[arg(long, default_value = "0", env = "RISKR_POLICY_BAKE_SECS")]
+    pub policy_bake_secs: u64,
+
+    /// Rise in the non-Allow decision rate over the previous policy that
+    /// rolls a baking policy back (0.05 = five percentage points)
+    #[arg(
+        long,
+        default_value = "0.05",
+        env = "RISKR_POLICY_BAKE_MAX_NON_ALLOW_INCREASE"
+    )]
+    pub policy_bake_max_non_allow_increase: f64,
+
+    /// Decisions needed under each policy before their rates are compared
+    #[arg(long, default_value = "100", env = "RISKR_POLICY_BAKE_MIN_DECISIONS")]
+    pub policy_bake_min_decisions: u64,
+
+    /// Recent requests replayed under each reloaded policy to report the
+    /// decisions it changes; 0 disables
+    #[arg(long, default_value = "500", env = "RISKR_POLICY_REPLAY_SAMPLE")]
+    pub policy_replay_sample: u32,
+
+    /// Seconds between sweeps re-evaluating recently active subjects
+    /// against `sweep` rules; 0 disables
+    #[arg(long, default_value = "0", env = "RISKR_SWEEP_INTERVAL_SECS")]
+    pub sweep_interval_secs: u64,
+
+    /// Subjects with a decision in this many hours are swept
+    #[arg(long, default_value = "168", env = "RISKR_SWEEP_LOOKBACK_HOURS")]
+    pub sweep_lookback_hours: u32,
+
+    /// Most recently active subjects re-evaluated per sweep
+    #[arg(long, default_value = "10000", env = "RISKR_SWEEP_MAX_SUBJECTS")]
+    pub sweep_max_subjects: usize,
+
+    /// Shared secrets POST requests must be signed with; signing is not
+    /// required if none are set
+    #[arg(
+        long,
+        value_delimiter = ',',
+        env = "RISKR_REQUEST_SIGNING_SECRETS",
+        hide_env_values = true
+    )]
+    pub request_signing_secrets: Vec<String>,
+
+    /// Seconds a signed request's timestamp may differ from the server clock
+    #[arg(
+        long,
+        default_value = "300",
+        env = "RISKR_REQUEST_SIGNING_MAX_SKEW_SECS"
+    )]
+    pub request_signing_max_skew_secs: u64,
+
+    /// Rejected policy candidates kept for /v1/admin/policies/failed
+    #[arg(long, default_value = "20", env = "RISKR_FAILED_POLICY_HISTORY")]
+    pub failed_policy_history: usize,
+
+    /// Outbox relay poll interval in milliseconds
+    #[arg(long, default_value = "500", env = "RISKR_OUTBOX_POLL_MS")]
+    pub outbox_poll_ms: u64,
+
+    /// Maximum outbox events delivered per poll
+    #[arg(long, default_value = "100", env = "RISKR_OUTBOX_BATCH_SIZE")]
+    pub outbox_batch_size: u32,
+
+    /// Seconds a relay holds the outbox events it claims; also the delay
+    /// before a failed event is retried
+    #[arg(long, default_value = "30", env = "RISKR_OUTBOX_LEASE_SECS")]
+    pub outbox_lease_secs: u64,
+
+    /// Delivery attempts before an outbox event is dead-lettered (0 retries
+    /// forever)
+    #[arg(long, default_value = "10", env = "RISKR_OUTBOX_MAX_ATTEMPTS")]
+    pub outbox_max_attempts: u32,
+
     /// Latency budget in milliseconds for decision endpoint
     #[arg(long, default_value = "100", env = "RISKR_LATENCY_BUDGET_MS")]
     pub latency_budget_ms: u64,
 
+    /// Fraction of decisions expected within the latency budget
+    #[arg(long, default_value = "0.99", env = "RISKR_SLO_TARGET")]
+    pub slo_target: f64,
+
+    /// p99 latency in milliseconds each streaming rule must stay under;
+    /// rules breaching it (or the error rate) are shadowed (optional)
+    #[arg(long, env = "RISKR_RULE_SLA_P99_MS")]
+    pub rule_sla_p99_ms: Option<u64>,
+
+    /// Fraction of a streaming rule's evaluations allowed to fail
+    #[arg(long, default_value = "0.05", env = "RISKR_RULE_SLA_MAX_ERROR_RATE")]
+    pub rule_sla_max_error_rate: f64,
+
+    /// Seconds a rule must breach its SLA before it is shadowed, and meet
+    /// it before it is restored
+    #[arg(long, default_value = "300", env = "RISKR_RULE_SLA_SUSTAIN_SECS")]
+    pub rule_sla_sustain_secs: u64,
+
+    /// Short-window (5m) SLO burn rate at which an alert event is logged
+    #[arg(long, default_value = "14.4", env = "RISKR_SLO_ALERT_BURN_RATE")]
+    pub slo_alert_burn_rate: f64,
+
+    /// Seconds between liveness self-checks
+    #[arg(long, default_value = "15", env = "RISKR_WATCHDOG_INTERVAL_SECS")]
+    pub watchdog_interval_secs: u64,
+
+    /// Milliseconds each liveness self-check step may take before failing
+    #[arg(long, default_value = "2000", env = "RISKR_WATCHDOG_TIMEOUT_MS")]
+    pub watchdog_timeout_ms: u64,
+
     /// Log level (trace, debug, info, warn, error)
     #[arg(long, default_value = "info", env = "RUST_LOG")]
     pub log_level: String,
@@ -72,9 +549,183 @@ pub struct Config {
     #[arg(long, default_value = "10", env = "RISKR_DB_POOL_MAX")]
     pub db_pool_max: u32,
 
+    /// Serve transaction windows up to this many hours from memory (optional)
+    #[arg(long, env = "RISKR_WINDOW_CACHE_HOURS")]
+    pub window_cache_hours: Option<u64>,
+
+    /// Maximum subjects held in the window cache
+    #[arg(
+        long,
+        default_value = "100000",
+        env = "RISKR_WINDOW_CACHE_MAX_SUBJECTS"
+    )]
+    pub window_cache_max_subjects: usize,
+
+    /// Preload subjects with decisions in this many hours into the window
+    /// cache at startup (optional)
+    #[arg(long, env = "RISKR_WINDOW_CACHE_WARM_HOURS")]
+    pub window_cache_warm_hours: Option<u64>,
+
+    /// Reload cached windows from Postgres every this many seconds, for
+    /// deployments where one subject's writes reach several instances
+    /// (optional)
+    #[arg(long, env = "RISKR_WINDOW_CACHE_RECONCILE_SECS")]
+    pub window_cache_reconcile_secs: Option<u64>,
+
+    /// Attempts per storage call when Postgres errors are transient
+    #[arg(long, default_value = "3", env = "RISKR_DB_RETRY_ATTEMPTS")]
+    pub db_retry_attempts: u32,
+
+    /// Storage error rate that opens the circuit breaker
+    #[arg(long, default_value = "0.5", env = "RISKR_DB_BREAKER_ERROR_RATE")]
+    pub db_breaker_error_rate: f64,
+
+    /// Storage calls per window before the breaker can open
+    #[arg(long, default_value = "20", env = "RISKR_DB_BREAKER_MIN_CALLS")]
+    pub db_breaker_min_calls: u32,
+
+    /// Seconds the breaker stays open before probing storage again
+    #[arg(long, default_value = "10", env = "RISKR_DB_BREAKER_OPEN_SECS")]
+    pub db_breaker_open_secs: u64,
+
+    /// Decision handling while storage is unavailable
+    #[arg(
+        long,
+        value_enum,
+        default_value = "fail-open",
+        env = "RISKR_DEGRADED_MODE"
+    )]
+    pub degraded_mode: DegradedMode,
+
+    /// Use caller-supplied KYC tiers even for subjects already in storage
+    #[arg(long, default_value = "true", env = "RISKR_TRUST_REQUEST_KYC")]
+    pub trust_request_kyc: bool,
+
+    /// Use caller-supplied countries even for subjects already in storage
+    #[arg(long, default_value = "true", env = "RISKR_TRUST_REQUEST_GEO")]
+    pub trust_request_geo: bool,
+
+    /// Base URL of the identity provider that `kyc_verification` rules
+    /// check KYC tiers with (optional)
+    #[arg(long, env = "RISKR_IDENTITY_PROVIDER_URL")]
+    pub identity_provider_url: Option<String>,
+
+    /// Milliseconds an identity provider lookup may take before it fails
+    #[arg(
+        long,
+        default_value = "300",
+        env = "RISKR_IDENTITY_PROVIDER_TIMEOUT_MS"
+    )]
+    pub identity_provider_timeout_ms: u64,
+
+    /// Seconds an identity provider answer is reused for a subject
+    #[arg(
+        long,
+        default_value = "300",
+        env = "RISKR_IDENTITY_PROVIDER_CACHE_SECS"
+    )]
+    pub identity_provider_cache_secs: u64,
+
+    /// Subjects each distinct-count rule keeps a sketch for (memory bound)
+    #[arg(
+        long,
+        default_value = "50000",
+        env = "RISKR_DISTINCT_SKETCH_MAX_SUBJECTS"
+    )]
+    pub distinct_sketch_max_subjects: usize,
+
+    /// MaxMind-format country database for `ip_jurisdiction` rules, e.g.
+    /// GeoLite2-Country.mmdb (optional)
+    #[arg(long, env = "RISKR_GEOIP_DB_PATH")]
+    pub geoip_db_path: Option<PathBuf>,
+
+    /// MaxMind-format ASN database for `ip_jurisdiction` rules, e.g.
+    /// GeoLite2-ASN.mmdb (optional)
+    #[arg(long, env = "RISKR_ASN_DB_PATH")]
+    pub asn_db_path: Option<PathBuf>,
+
+    /// Seconds between checks for replaced GeoIP and ASN database files
+    #[arg(long, default_value = "300", env = "RISKR_GEOIP_RELOAD_SECS")]
+    pub geoip_reload_secs: u64,
+
+    /// Daily decision quota for a tenant as `tenant=count`; repeatable.
+    /// Tenants without a quota are unlimited
+    #[arg(
+        long = "tenant-quota",
+        value_parser = parse_tenant_quota,
+        value_delimiter = ',',
+        env = "RISKR_TENANT_QUOTAS"
+    )]
+    pub tenant_quotas: Vec<(String, u64)>,
+
+    /// Dotted request paths removed from decision exports, such as
+    /// `subject.addresses`
+    #[arg(long, value_delimiter = ',', env = "RISKR_EXPORT_REDACT")]
+    pub export_redact: Vec<String>,
+
+    /// Data class of an evidence key as `key=class`, overriding its
+    /// default; repeatable. Classes are pii, financial and operational
+    #[arg(
+        long = "evidence-class",
+        value_parser = parse_evidence_class,
+        value_delimiter = ',',
+        env = "RISKR_EVIDENCE_CLASSES"
+    )]
+    pub evidence_classes: Vec<(String, DataClass)>,
+
+    /// Days evidence of a data class is kept as `class=days`; repeatable.
+    /// Classes without a retention are kept indefinitely
+    #[arg(
+        long = "evidence-retention",
+        value_parser = parse_evidence_retention,
+        value_delimiter = ',',
+        env = "RISKR_EVIDENCE_RETENTION"
+    )]
+    pub evidence_retention: Vec<(DataClass, u32)>,
+
+    /// Data classes of evidence redacted from decision exports
+    #[arg(long, value_delimiter = ',', env = "RISKR_EXPORT_REDACT_CLASSES")]
+    pub export_redact_classes: Vec<DataClass>,
+
+    /// Delay added to every storage call, in milliseconds
+    #[cfg(feature = "chaos")]
+    #[arg(long, default_value = "0", env = "RISKR_CHAOS_STORAGE_LATENCY_MS")]
+    pub chaos_storage_latency_ms: u64,
+
+    /// Fraction of storage calls that fail, from 0 to 1
+    #[cfg(feature = "chaos")]
+    #[arg(long, default_value = "0", env = "RISKR_CHAOS_STORAGE_ERROR_RATE")]
+    pub chaos_storage_error_rate: f64,
+
+    /// Delay added to streaming rule evaluation, in milliseconds
+    #[cfg(feature = "chaos")]
+    #[arg(long, default_value = "0", env = "RISKR_CHAOS_RULE_DELAY_MS")]
+    pub chaos_rule_delay_ms: u64,
+
+    /// Fail every policy reload
+    #[cfg(feature = "chaos")]
+    #[arg(long, default_value = "false", env = "RISKR_CHAOS_FAIL_POLICY_LOAD")]
+    pub chaos_fail_policy_load: bool,
+
     /// Run database migrations on startup
     #[arg(long, default_value = "false", env = "RISKR_RUN_MIGRATIONS")]
     pub run_migrations: bool,
+
+    /// Partition the transactions and decisions tables by month. Empty
+    /// tables are converted on startup; existing data needs `db partition`
+    #[arg(long, default_value = "false", env = "RISKR_DB_PARTITIONING")]
+    pub db_partitioning: bool,
+
+    /// Months of partitions created ahead of the current one
+    #[arg(long, default_value = "3", env = "RISKR_DB_PARTITION_MONTHS_AHEAD")]
+    pub db_partition_months_ahead: u32,
+
+    /// Evaluate and record decisions but always return Allow (dry run)
+    #[arg(long, default_value = "false", env = "RISKR_MONITOR_ONLY")]
+    pub monitor_only: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
 }
 
 impl Config {
@@ -83,27 +734,421 @@ impl Config {
         Duration::from_secs(self.policy_reload_secs)
     }
 
+    /// Get policy bake options, if a bake period is configured.
+    pub fn policy_bake_options(&self) -> Option<BakeOptions> {
+        (self.policy_bake_secs > 0).then(|| BakeOptions {
+            period: Duration::from_secs(self.policy_bake_secs),
+            max_non_allow_increase: self.policy_bake_max_non_allow_increase,
+            min_decisions: self.policy_bake_min_decisions,
+        })
+    }
+
+    /// Get request signing, if any secrets are configured.
+    pub fn request_signing(&self) -> Option<RequestSigning> {
+        let secrets: Vec<&str> = self
+            .request_signing_secrets
+            .iter()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .collect();
+        (!secrets.is_empty()).then(|| {
+            RequestSigning::new(
+                secrets,
+                chrono::Duration::seconds(self.request_signing_max_skew_secs as i64),
+            )
+        })
+    }
+
+    /// Get the clock skew allowed for signed requests.
+    pub fn request_signing_max_skew(&self) -> Duration {
+        Duration::from_secs(self.request_signing_max_skew_secs.max(1))
+    }
+
+    /// Get sweep options, if sweeping is enabled.
+    pub fn sweep_options(&self) -> Option<SweepOptions> {
+        (self.sweep_interval_secs > 0).then(|| SweepOptions {
+            interval: Duration::from_secs(self.sweep_interval_secs),
+            lookback: chrono::Duration::hours(self.sweep_lookback_hours as i64),
+            max_subjects: self.sweep_max_subjects,
+        })
+    }
+
+    /// Get the streaming rule SLA, if a p99 budget is configured.
+    pub fn rule_sla(&self) -> Option<RuleSla> {
+        self.rule_sla_p99_ms.map(|p99_ms| RuleSla {
+            p99: Duration::from_millis(p99_ms),
+            max_error_rate: self.rule_sla_max_error_rate,
+            sustain_secs: self.rule_sla_sustain_secs,
+        })
+    }
+
+    /// Get the initial failure injection settings.
+    #[cfg(feature = "chaos")]
+    pub fn chaos_settings(&self) -> crate::chaos::ChaosSettings {
+        crate::chaos::ChaosSettings {
+            storage_latency_ms: self.chaos_storage_latency_ms,
+            storage_error_rate: self.chaos_storage_error_rate,
+            rule_delay_ms: self.chaos_rule_delay_ms,
+            fail_policy_load: self.chaos_fail_policy_load,
+        }
+    }
+
+    /// Get outbox relay poll interval as Duration.
+    pub fn outbox_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.outbox_poll_ms)
+    }
+
+    /// Get outbox claim lease as Duration.
+    pub fn outbox_lease(&self) -> Duration {
+        Duration::from_secs(self.outbox_lease_secs)
+    }
+
+    /// Get liveness self-check interval as Duration.
+    pub fn watchdog_interval(&self) -> Duration {
+        Duration::from_secs(self.watchdog_interval_secs)
+    }
+
+    /// Get liveness self-check step timeout as Duration.
+    pub fn watchdog_timeout(&self) -> Duration {
+        Duration::from_millis(self.watchdog_timeout_ms)
+    }
+
+    /// Get identity provider lookup timeout as Duration.
+    pub fn identity_provider_timeout(&self) -> Duration {
+        Duration::from_millis(self.identity_provider_timeout_ms)
+    }
+
+    /// Get identity provider cache lifetime as Duration.
+    pub fn identity_provider_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.identity_provider_cache_secs)
+    }
+
+    /// Get the GeoIP database reload check interval as Duration.
+    pub fn geoip_reload_interval(&self) -> Duration {
+        Duration::from_secs(self.geoip_reload_secs)
+    }
+
     /// Get shutdown timeout as Duration.
     pub fn shutdown_timeout(&self) -> Duration {
         Duration::from_secs(self.shutdown_timeout_secs)
     }
 
+    /// Get the request header read timeout as Duration.
+    pub fn header_read_timeout(&self) -> Duration {
+        Duration::from_secs(self.header_read_timeout_secs)
+    }
+
+    /// Get the request body read timeout as Duration.
+    pub fn body_read_timeout(&self) -> Duration {
+        Duration::from_secs(self.body_read_timeout_secs)
+    }
+
+    /// Get the overall time limit of each route class.
+    pub fn route_timeouts(&self) -> RouteTimeouts {
+        let limit = |d: Duration| (!d.is_zero()).then_some(d);
+        RouteTimeouts {
+            decision: limit(Duration::from_millis(self.decision_timeout_ms)),
+            export: limit(Duration::from_secs(self.export_timeout_secs)),
+            admin: limit(Duration::from_secs(self.admin_timeout_secs)),
+        }
+    }
+
+    /// Get the window cache reconcile interval as Duration.
+    pub fn window_cache_reconcile_interval(&self) -> Option<Duration> {
+        self.window_cache_reconcile_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Get HTTP/2 keep-alive interval as Duration.
+    pub fn http2_keep_alive_interval(&self) -> Option<Duration> {
+        self.http2_keep_alive_secs.map(Duration::from_secs)
+    }
+
+    /// Get storage retry policy.
+    pub fn db_retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.db_retry_attempts.max(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Get storage circuit breaker options.
+    pub fn db_breaker_options(&self) -> BreakerOptions {
+        BreakerOptions {
+            error_rate: self.db_breaker_error_rate,
+            min_calls: self.db_breaker_min_calls,
+            open_for: Duration::from_secs(self.db_breaker_open_secs),
+            ..BreakerOptions::default()
+        }
+    }
+
     /// Get actor idle timeout as Duration.
     pub fn actor_idle_timeout(&self) -> Duration {
         Duration::from_secs(self.actor_idle_secs)
     }
+
+    /// Get the stored subject fields preferred over the caller's.
+    pub fn subject_enrichment(&self) -> SubjectEnrichment {
+        SubjectEnrichment::new(self.trust_request_kyc, self.trust_request_geo)
+    }
+
+    /// Get daily decision quotas keyed by tenant.
+    pub fn tenant_quota_map(&self) -> HashMap<String, u64> {
+        self.tenant_quotas.iter().cloned().collect()
+    }
+
+    /// Get the evidence classification, retention and export redaction.
+    pub fn evidence_retention(&self) -> EvidenceRetention {
+        let mut retention = EvidenceRetention::default();
+        for (key, class) in &self.evidence_classes {
+            retention = retention.with_class(key.clone(), *class);
+        }
+        for (class, days) in &self.evidence_retention {
+            retention = retention.with_retention(*class, chrono::Duration::days(*days as i64));
+        }
+        for class in &self.export_redact_classes {
+            retention = retention.with_export_redact(*class);
+        }
+        retention
+    }
+
+    /// Get the OIDC provider settings, if SSO is enabled.
+    pub fn oidc_config(&self) -> Option<OidcConfig> {
+        let issuer = self.oidc_issuer.clone()?;
+        Some(OidcConfig {
+            issuer,
+            audience: self.oidc_audience.clone(),
+            jwks_url: self.oidc_jwks_url.clone(),
+            scopes_claim: self.oidc_scopes_claim.clone(),
+            scope_map: self.oidc_scope_map.iter().cloned().collect(),
+            jwks_cache_ttl: Duration::from_secs(self.oidc_jwks_cache_secs),
+            timeout: DEFAULT_FETCH_TIMEOUT,
+        })
+    }
+}
+
+/// A problem with the server configuration.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ConfigError {
+    #[error("--listen-addr {addr:?} is not a socket address: {reason}")]
+    InvalidListenAddr { addr: String, reason: String },
+
+    #[error("--listen-addr {addr} cannot be bound: {reason}")]
+    ListenAddrUnavailable { addr: String, reason: String },
+
+    #[error("--latency-budget-ms must be greater than 0")]
+    ZeroLatencyBudget,
+
+    #[error("--stripe-count must be a power of two, got {0}")]
+    StripeCount(usize),
+
+    #[error("--{set} requires --{missing}")]
+    MissingPath {
+        set: &'static str,
+        missing: &'static str,
+    },
+
+    #[error("--database-url is invalid: {0}")]
+    InvalidDatabaseUrl(String),
+
+    #[error("--db-pool-min ({min}) is greater than --db-pool-max ({max})")]
+    PoolBounds { min: u32, max: u32 },
+
+    #[error("--{name} must be between 0 and 1, got {value}")]
+    OutOfRange { name: &'static str, value: f64 },
+
+    #[error("--{name} must be an https URL, got {url}")]
+    InsecureUrl { name: &'static str, url: String },
+}
+
+impl Config {
+    /// Check the server configuration, returning every problem found rather
+    /// than stopping at the first. The listen address is bound and released
+    /// to check that it is free.
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut problems = Vec::new();
+
+        match self.listen_addr.parse::<SocketAddr>() {
+            Ok(addr) => {
+                if let Err(e) = std::net::TcpListener::bind(addr) {
+                    problems.push(ConfigError::ListenAddrUnavailable {
+                        addr: self.listen_addr.clone(),
+                        reason: e.to_string(),
+                    });
+                }
+            }
+            Err(e) => problems.push(ConfigError::InvalidListenAddr {
+                addr: self.listen_addr.clone(),
+                reason: e.to_string(),
+            }),
+        }
+
+        if self.latency_budget_ms == 0 {
+            problems.push(ConfigError::ZeroLatencyBudget);
+        }
+        if !self.stripe_count.is_power_of_two() {
+            problems.push(ConfigError::StripeCount(self.stripe_count));
+        }
+
+        match (&self.wal_path, &self.snapshot_path) {
+            (Some(_), None) => problems.push(ConfigError::MissingPath {
+                set: "wal-path",
+                missing: "snapshot-path",
+            }),
+            (None, Some(_)) => problems.push(ConfigError::MissingPath {
+                set: "snapshot-path",
+                missing: "wal-path",
+            }),
+            _ => {}
+        }
+
+        if let Some(url) = &self.database_url {
+            // The parser doesn't check the scheme
+            let scheme = url.split_once("://").map(|(scheme, _)| scheme);
+            if !matches!(scheme, Some("postgres" | "postgresql")) {
+                problems.push(ConfigError::InvalidDatabaseUrl(
+                    "expected a postgres:// or postgresql:// URL".to_string(),
+                ));
+            } else if let Err(e) = url.parse::<PgConnectOptions>() {
+                problems.push(ConfigError::InvalidDatabaseUrl(e.to_string()));
+            }
+        }
+        if self.db_pool_min > self.db_pool_max {
+            problems.push(ConfigError::PoolBounds {
+                min: self.db_pool_min,
+                max: self.db_pool_max,
+            });
+        }
+
+        for (name, url) in [
+            ("oidc-issuer", &self.oidc_issuer),
+            ("oidc-jwks-url", &self.oidc_jwks_url),
+        ] {
+            if let Some(url) = url.as_ref().filter(|url| !is_https(url)) {
+                problems.push(ConfigError::InsecureUrl {
+                    name,
+                    url: url.clone(),
+                });
+            }
+        }
+
+        for (name, value) in [
+            ("slo-target", self.slo_target),
+            ("db-breaker-error-rate", self.db_breaker_error_rate),
+            ("rule-sla-max-error-rate", self.rule_sla_max_error_rate),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                problems.push(ConfigError::OutOfRange { name, value });
+            }
+        }
+
+        problems
+    }
+}
+
+/// Parse a `value=scope+scope` OIDC claim mapping.
+fn parse_scope_mapping(s: &str) -> Result<(String, Vec<Scope>), String> {
+    let (value, scopes) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected value=scope+scope, got {:?}", s))?;
+    let scopes = scopes
+        .split('+')
+        .map(|scope| scope.trim().parse().map_err(|e: AuthError| e.to_string()))
+        .collect::<Result<_, _>>()?;
+    Ok((value.trim().to_string(), scopes))
+}
+
+/// Parse a `key=class` evidence classification.
+fn parse_evidence_class(s: &str) -> Result<(String, DataClass), String> {
+    let (key, class) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected key=class, got {:?}", s))?;
+    let class = DataClass::from_str(class.trim(), true)?;
+    Ok((key.trim().to_string(), class))
+}
+
+/// Parse a `class=days` evidence retention.
+fn parse_evidence_retention(s: &str) -> Result<(DataClass, u32), String> {
+    let (class, days) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected class=days, got {:?}", s))?;
+    let days = days
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid retention for {}: {:?}", class, days))?;
+    Ok((DataClass::from_str(class.trim(), true)?, days))
+}
+
+/// Parse a `tenant=count` quota.
+fn parse_tenant_quota(s: &str) -> Result<(String, u64), String> {
+    let (tenant, count) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected tenant=count, got {:?}", s))?;
+    let count = count
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid quota for {}: {:?}", tenant, count))?;
+    Ok((tenant.trim().to_string(), count))
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             listen_addr: "0.0.0.0:8080".to_string(),
+            listen_socket: None,
+            http2: true,
+            http_keep_alive: true,
+            http2_keep_alive_secs: None,
+            http2_max_concurrent_streams: None,
+            max_connections: None,
+            max_request_body_bytes: 2 * 1024 * 1024,
+            response_compression: true,
+            compression_min_bytes: 1024,
+            header_read_timeout_secs: 10,
+            body_read_timeout_secs: 30,
+            decision_timeout_ms: 2000,
+            admin_timeout_secs: 30,
+            export_timeout_secs: 300,
             policy_path: PathBuf::from("policy.yaml"),
             sanctions_path: PathBuf::from("sanctions.txt"),
+            compile_cache_dir: None,
+            last_known_good_dir: None,
+            assets_path: None,
+            api_keys_path: None,
+            api_key_route_groups: Vec::new(),
+            oidc_issuer: None,
+            oidc_audience: "riskr".to_string(),
+            oidc_jwks_url: None,
+            oidc_scopes_claim: "scope".to_string(),
+            oidc_scope_map: Vec::new(),
+            oidc_route_groups: Vec::new(),
+            oidc_jwks_cache_secs: 300,
             wal_path: None,
             snapshot_path: None,
             policy_reload_secs: 30,
+            policy_bake_secs: 0,
+            policy_bake_max_non_allow_increase: 0.05,
+            policy_bake_min_decisions: 100,
+            policy_replay_sample: 500,
+            request_signing_secrets: Vec::new(),
+            request_signing_max_skew_secs: 300,
+            sweep_interval_secs: 0,
+            sweep_lookback_hours: 168,
+            sweep_max_subjects: 10_000,
+            failed_policy_history: 20,
+            outbox_poll_ms: 500,
+            outbox_batch_size: 100,
+            outbox_lease_secs: 30,
+            outbox_max_attempts: 10,
             latency_budget_ms: 100,
+            slo_target: 0.99,
+            rule_sla_p99_ms: None,
+            rule_sla_max_error_rate: 0.05,
+            rule_sla_sustain_secs: 300,
+            slo_alert_burn_rate: 14.4,
+            watchdog_interval_secs: 15,
+            watchdog_timeout_ms: 2000,
             log_level: "info".to_string(),
             max_entries_per_user: 1000,
             stripe_count: 64,
@@ -113,7 +1158,42 @@ impl Default for Config {
             database_url: None,
             db_pool_min: 2,
             db_pool_max: 10,
+            window_cache_hours: None,
+            window_cache_max_subjects: 100_000,
+            window_cache_warm_hours: None,
+            window_cache_reconcile_secs: None,
+            db_retry_attempts: 3,
+            db_breaker_error_rate: 0.5,
+            db_breaker_min_calls: 20,
+            db_breaker_open_secs: 10,
+            degraded_mode: DegradedMode::FailOpen,
+            trust_request_kyc: true,
+            trust_request_geo: true,
+            identity_provider_url: None,
+            identity_provider_timeout_ms: 300,
+            identity_provider_cache_secs: 300,
+            distinct_sketch_max_subjects: 50_000,
+            geoip_db_path: None,
+            asn_db_path: None,
+            geoip_reload_secs: 300,
+            tenant_quotas: Vec::new(),
+            export_redact: Vec::new(),
+            evidence_classes: Vec::new(),
+            evidence_retention: Vec::new(),
+            export_redact_classes: Vec::new(),
+            #[cfg(feature = "chaos")]
+            chaos_storage_latency_ms: 0,
+            #[cfg(feature = "chaos")]
+            chaos_storage_error_rate: 0.0,
+            #[cfg(feature = "chaos")]
+            chaos_rule_delay_ms: 0,
+            #[cfg(feature = "chaos")]
+            chaos_fail_policy_load: false,
             run_migrations: false,
+            db_partitioning: false,
+            db_partition_months_ahead: 3,
+            monitor_only: false,
+            command: None,
         }
     }
 }
@@ -144,4 +1224,171 @@ mod tests {
         assert_eq!(config.shutdown_timeout(), Duration::from_secs(15));
         assert_eq!(config.actor_idle_timeout(), Duration::from_secs(1800));
     }
+
+    #[test]
+    fn test_policy_bake_options() {
+        assert!(Config::default().policy_bake_options().is_none());
+
+        let config = Config::parse_from(["riskr", "--policy-bake-secs", "600"]);
+        let bake = config.policy_bake_options().unwrap();
+        assert_eq!(bake.period, Duration::from_secs(600));
+        assert_eq!(bake.max_non_allow_increase, 0.05);
+        assert_eq!(bake.min_decisions, 100);
+    }
+
+    #[test]
+    fn test_scenarios_subcommand() {
+        let config = Config::parse_from([
+            "riskr",
+            "--policy-path",
+            "policy.yaml",
+            "scenarios",
+            "run",
+            "scenarios/",
+        ]);
+
+        match config.command {
+            Some(Command::Scenarios {
+                command: ScenarioCommand::Run { dir, report, .. },
+            }) => {
+                assert_eq!(dir, PathBuf::from("scenarios/"));
+                assert!(report.is_none());
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_db_partition_subcommand() {
+        let config = Config::parse_from([
+            "riskr",
+            "--database-url",
+            "postgres://localhost/riskr",
+            "db",
+            "partition",
+        ]);
+
+        assert!(matches!(
+            config.command,
+            Some(Command::Db {
+                command: DbCommand::Partition
+            })
+        ));
+        assert_eq!(config.db_partition_months_ahead, 3);
+    }
+
+    #[test]
+    fn test_tenant_quotas() {
+        let config = Config::parse_from([
+            "riskr",
+            "--tenant-quota",
+            "payments=1000,lending=50",
+            "--tenant-quota",
+            "ops=5",
+        ]);
+
+        let quotas = config.tenant_quota_map();
+        assert_eq!(quotas.len(), 3);
+        assert_eq!(quotas["lending"], 50);
+
+        assert!(Config::try_parse_from(["riskr", "--tenant-quota", "payments"]).is_err());
+        assert!(Config::try_parse_from(["riskr", "--tenant-quota", "payments=many"]).is_err());
+    }
+
+    #[test]
+    fn test_evidence_retention() {
+        let config = Config::parse_from([
+            "riskr",
+            "--evidence-class",
+            "hour_share=pii",
+            "--evidence-retention",
+            "pii=30,financial=365",
+            "--export-redact-classes",
+            "pii",
+        ]);
+
+        let retention = config.evidence_retention();
+        assert_eq!(retention.class_of("hour_share"), DataClass::Pii);
+        assert_eq!(retention.retentions().count(), 2);
+
+        assert!(Config::try_parse_from(["riskr", "--evidence-retention", "pii"]).is_err());
+        assert!(Config::try_parse_from(["riskr", "--evidence-class", "asn=secret"]).is_err());
+    }
+
+    #[test]
+    fn test_oidc_config() {
+        assert!(Config::default().oidc_config().is_none());
+
+        let config = Config::parse_from([
+            "riskr",
+            "--oidc-issuer",
+            "https://sso.internal",
+            "--oidc-scope-map",
+            "risk-ops=policy:write+decisions:read,auditors=exports:read",
+        ]);
+        let oidc = config.oidc_config().unwrap();
+        assert_eq!(oidc.audience, "riskr");
+        assert_eq!(
+            oidc.scope_map["risk-ops"],
+            vec![Scope::PolicyWrite, Scope::DecisionsRead]
+        );
+        assert_eq!(oidc.scope_map["auditors"], vec![Scope::ExportsRead]);
+
+        assert!(Config::try_parse_from(["riskr", "--oidc-scope-map", "ops=root"]).is_err());
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let config = Config {
+            listen_addr: "127.0.0.1:0".to_string(),
+            database_url: Some("postgres://riskr@localhost/riskr".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(config.validate(), vec![]);
+
+        let config = Config {
+            listen_addr: "localhost".to_string(),
+            latency_budget_ms: 0,
+            stripe_count: 48,
+            wal_path: Some(PathBuf::from("/var/lib/riskr/wal")),
+            database_url: Some("mysql://riskr@localhost/riskr".to_string()),
+            db_pool_min: 20,
+            slo_target: 99.0,
+            oidc_issuer: Some("http://sso.internal".to_string()),
+            ..Default::default()
+        };
+        let problems = config.validate();
+        assert_eq!(problems.len(), 8, "{:?}", problems);
+        assert!(matches!(problems[0], ConfigError::InvalidListenAddr { .. }));
+        assert!(problems.contains(&ConfigError::StripeCount(48)));
+        assert!(problems.contains(&ConfigError::MissingPath {
+            set: "wal-path",
+            missing: "snapshot-path"
+        }));
+        assert!(problems
+            .iter()
+            .any(|p| matches!(p, ConfigError::InvalidDatabaseUrl(_))));
+        assert_eq!(
+            problems[6].to_string(),
+            "--oidc-issuer must be an https URL, got http://sso.internal"
+        );
+        assert_eq!(
+            problems[7].to_string(),
+            "--slo-target must be between 0 and 1, got 99"
+        );
+    }
+
+    #[test]
+    fn test_validate_checks_listen_addr_is_free() {
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let config = Config {
+            listen_addr: taken.local_addr().unwrap().to_string(),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            config.validate()[..],
+            [ConfigError::ListenAddrUnavailable { .. }]
+        ));
+    }
 }
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Risk rating a reviewer gives a subject.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskRating {
+    Low,
+    Medium,
+    High,
+}
+
+impl RiskRating {
+    /// Every rating, lowest first.
+    pub const ALL: [RiskRating; 3] = [RiskRating::Low, RiskRating::Medium, RiskRating::High];
+
+    /// Storage label, the same as the serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskRating::Low => "low",
+            RiskRating::Medium => "medium",
+            RiskRating::High => "high",
+        }
+    }
+
+    /// Parse a storage label.
+    pub fn from_label(s: &str) -> Option<Self> {
+        RiskRating::ALL
+            .into_iter()
+            .find(|rating| rating.as_str() == s)
+    }
+}
+
+impl fmt::Display for RiskRating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Reviewer knowledge about a subject, e.g. "confirmed corporate treasury
+/// flow", kept with the subject instead of in chat history.
+///
+/// Annotations are append-only; the newest rating is the subject's current
+/// one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: Uuid,
+    pub subject_id: Uuid,
+    /// Free-text note
+    pub note: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk_rating: Option<RiskRating>,
+    /// Labels for filtering and reporting, e.g. `treasury`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Who added the annotation
+    pub actor: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Current rating among annotations ordered newest first: the latest one
+/// given.
+pub fn current_rating(annotations: &[Annotation]) -> Option<RiskRating> {
+    annotations.iter().find_map(|a| a.risk_rating)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn annotation(risk_rating: Option<RiskRating>) -> Annotation {
+        Annotation {
+            id: Uuid::new_v4(),
+            subject_id: Uuid::nil(),
+            note: "note".to_string(),
+            risk_rating,
+            tags: Vec::new(),
+            actor: "reviewer".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_rating_labels_round_trip() {
+        for rating in RiskRating::ALL {
+            assert_eq!(RiskRating::from_label(rating.as_str()), Some(rating));
+            assert_eq!(
+                serde_json::to_value(rating).unwrap(),
+                serde_json::json!(rating.as_str())
+            );
+        }
+        assert_eq!(RiskRating::from_label("severe"), None);
+    }
+
+    #[test]
+    fn test_current_rating_is_latest_given() {
+        let annotations = vec![
+            annotation(None),
+            annotation(Some(RiskRating::Low)),
+            annotation(Some(RiskRating::High)),
+        ];
+        assert_eq!(current_rating(&annotations), Some(RiskRating::Low));
+        assert_eq!(current_rating(&annotations[..1]), None);
+    }
+}
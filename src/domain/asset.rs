@@ -0,0 +1,227 @@
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Canonical asset known to the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetInfo {
+    /// Canonical symbol used for rules and aggregations (e.g. "USDC")
+    pub symbol: String,
+    /// Decimal places of the asset's base unit
+    pub decimals: u32,
+    /// Chain implied by the alias, if any (e.g. "polygon" for "usdc-polygon")
+    pub chain: Option<String>,
+}
+
+/// Asset entry in a registry file.
+#[derive(Debug, Deserialize)]
+struct AssetDef {
+    decimals: u32,
+    #[serde(default)]
+    aliases: Vec<String>,
+    /// Aliases that also identify a chain, keyed by alias
+    #[serde(default)]
+    chains: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistryFile {
+    assets: HashMap<String, AssetDef>,
+}
+
+/// Errors loading or applying the asset registry.
+#[derive(Debug, thiserror::Error)]
+pub enum AssetError {
+    #[error("Failed to read asset registry: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse asset registry: {0}")]
+    Parse(#[from] serde_yaml::Error),
+
+    #[error("Alias {alias} maps to both {first} and {second}")]
+    DuplicateAlias {
+        alias: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("Unknown asset: {0}")]
+    Unknown(String),
+
+    #[error("Invalid amount for {asset}: {amount}")]
+    InvalidAmount { asset: String, amount: String },
+}
+
+/// Maps asset spellings to canonical identifiers.
+///
+/// Callers send the same asset under many names ("USDC.e", "usdc-polygon"),
+/// which would otherwise split volume across separate aggregation keys.
+/// Lookups are case-insensitive; canonical symbols always match themselves.
+///
+/// ```yaml
+/// assets:
+///   USDC:
+///     decimals: 6
+///     aliases: ["USDC.e"]
+///     chains: { usdc-polygon: polygon, usdc-arbitrum: arbitrum }
+///   BTC:
+///     decimals: 8
+///     aliases: [XBT]
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AssetRegistry {
+    by_alias: HashMap<String, AssetInfo>,
+}
+
+impl AssetRegistry {
+    /// Load a registry from a YAML file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_yaml(&content)
+    }
+
+    /// Parse a registry from YAML.
+    pub fn from_yaml(content: &str) -> Result<Self, AssetError> {
+        let file: RegistryFile = serde_yaml::from_str(content)?;
+        let mut registry = AssetRegistry::default();
+
+        for (symbol, def) in file.assets {
+            let symbol = symbol.to_uppercase();
+            let info = |chain: Option<&String>| AssetInfo {
+                symbol: symbol.clone(),
+                decimals: def.decimals,
+                chain: chain.cloned(),
+            };
+
+            registry.insert(&symbol, info(None))?;
+            for alias in &def.aliases {
+                registry.insert(alias, info(None))?;
+            }
+            for (alias, chain) in &def.chains {
+                registry.insert(alias, info(Some(chain)))?;
+            }
+        }
+
+        Ok(registry)
+    }
+
+    fn insert(&mut self, alias: &str, info: AssetInfo) -> Result<(), AssetError> {
+        let key = alias.to_lowercase();
+        if let Some(existing) = self.by_alias.get(&key) {
+            if existing.symbol != info.symbol {
+                return Err(AssetError::DuplicateAlias {
+                    alias: alias.to_string(),
+                    first: existing.symbol.clone(),
+                    second: info.symbol,
+                });
+            }
+        }
+        self.by_alias.insert(key, info);
+        Ok(())
+    }
+
+    /// Look up an asset by any of its spellings.
+    pub fn resolve(&self, asset: &str) -> Option<&AssetInfo> {
+        self.by_alias.get(&asset.trim().to_lowercase())
+    }
+
+    /// Resolve an asset and check that an amount fits its precision.
+    ///
+    /// Empty amounts are accepted since the amount is optional on requests.
+    pub fn validate(&self, asset: &str, amount: &str) -> Result<&AssetInfo, AssetError> {
+        let info = self
+            .resolve(asset)
+            .ok_or_else(|| AssetError::Unknown(asset.to_string()))?;
+
+        if !amount.is_empty() {
+            let valid = Decimal::from_str(amount)
+                .is_ok_and(|a| !a.is_sign_negative() && a.scale() <= info.decimals);
+            if !valid {
+                return Err(AssetError::InvalidAmount {
+                    asset: info.symbol.clone(),
+                    amount: amount.to_string(),
+                });
+            }
+        }
+
+        Ok(info)
+    }
+
+    /// Number of known spellings, canonical symbols included.
+    pub fn len(&self) -> usize {
+        self.by_alias.len()
+    }
+
+    /// Returns true if the registry has no assets.
+    pub fn is_empty(&self) -> bool {
+        self.by_alias.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRY: &str = r#"
+assets:
+  USDC:
+    decimals: 6
+    aliases: ["USDC.e"]
+    chains: { usdc-polygon: polygon }
+  btc:
+    decimals: 8
+    aliases: [XBT]
+"#;
+
+    #[test]
+    fn test_resolve_aliases() {
+        let registry = AssetRegistry::from_yaml(REGISTRY).unwrap();
+
+        for alias in ["USDC", "usdc", "USDC.e", "usdc.E", " usdc-polygon "] {
+            assert_eq!(registry.resolve(alias).unwrap().symbol, "USDC");
+        }
+        assert_eq!(
+            registry.resolve("usdc-polygon").unwrap().chain.as_deref(),
+            Some("polygon")
+        );
+        assert_eq!(registry.resolve("xbt").unwrap().symbol, "BTC");
+        assert_eq!(registry.resolve("xbt").unwrap().decimals, 8);
+        assert!(registry.resolve("DOGE").is_none());
+    }
+
+    #[test]
+    fn test_validate_amount_precision() {
+        let registry = AssetRegistry::from_yaml(REGISTRY).unwrap();
+
+        assert!(registry.validate("USDC.e", "").is_ok());
+        assert!(registry.validate("USDC.e", "1000000").is_ok());
+        assert!(registry.validate("USDC.e", "1.000001").is_ok());
+        assert!(matches!(
+            registry.validate("USDC.e", "1.0000001"),
+            Err(AssetError::InvalidAmount { .. })
+        ));
+        assert!(matches!(
+            registry.validate("USDC", "-5"),
+            Err(AssetError::InvalidAmount { .. })
+        ));
+        assert!(matches!(
+            registry.validate("DOGE", "1"),
+            Err(AssetError::Unknown(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_conflicting_aliases() {
+        let yaml = r#"
+assets:
+  USDC: { decimals: 6, aliases: [USD] }
+  USDT: { decimals: 6, aliases: [usd] }
+"#;
+        assert!(matches!(
+            AssetRegistry::from_yaml(yaml),
+            Err(AssetError::DuplicateAlias { .. })
+        ));
+    }
+}
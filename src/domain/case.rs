@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Investigation status of a case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStatus {
+    /// Awaiting an investigator
+    Open,
+    /// Being worked by an investigator
+    Investigating,
+    /// Activity found legitimate
+    ClosedApproved,
+    /// Activity rejected
+    ClosedRejected,
+    /// Suspicious activity report filed
+    SarFiled,
+}
+
+impl CaseStatus {
+    /// Every status, in lifecycle order.
+    pub const ALL: [CaseStatus; 5] = [
+        CaseStatus::Open,
+        CaseStatus::Investigating,
+        CaseStatus::ClosedApproved,
+        CaseStatus::ClosedRejected,
+        CaseStatus::SarFiled,
+    ];
+
+    /// Returns true while the case collects the subject's new decisions.
+    pub fn is_active(&self) -> bool {
+        matches!(self, CaseStatus::Open | CaseStatus::Investigating)
+    }
+
+    /// Returns true if a case may move from this status to `next`.
+    /// Closed cases are final; open cases may be closed without
+    /// investigation.
+    pub fn can_transition_to(&self, next: CaseStatus) -> bool {
+        match self {
+            CaseStatus::Open => next != CaseStatus::Open,
+            CaseStatus::Investigating => {
+                !matches!(next, CaseStatus::Open | CaseStatus::Investigating)
+            }
+            CaseStatus::ClosedApproved | CaseStatus::ClosedRejected | CaseStatus::SarFiled => false,
+        }
+    }
+
+    /// Storage label, the same as the serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaseStatus::Open => "open",
+            CaseStatus::Investigating => "investigating",
+            CaseStatus::ClosedApproved => "closed_approved",
+            CaseStatus::ClosedRejected => "closed_rejected",
+            CaseStatus::SarFiled => "sar_filed",
+        }
+    }
+
+    /// Parse a storage label.
+    pub fn from_label(s: &str) -> Option<Self> {
+        CaseStatus::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+    }
+}
+
+impl fmt::Display for CaseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Investigation of a subject opened by a `REVIEW` decision or by hand.
+///
+/// While active, every new decision for the subject is linked to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Case {
+    pub id: Uuid,
+    pub subject_id: Uuid,
+    pub status: CaseStatus,
+    /// Decision that opened the case, unless opened by hand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opened_by_decision: Option<Uuid>,
+    /// Linked decisions, oldest first
+    pub decision_ids: Vec<Uuid>,
+    /// Latest investigator note
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_lifecycle() {
+        assert!(CaseStatus::Open.can_transition_to(CaseStatus::Investigating));
+        assert!(CaseStatus::Open.can_transition_to(CaseStatus::ClosedApproved));
+        assert!(CaseStatus::Investigating.can_transition_to(CaseStatus::SarFiled));
+        assert!(!CaseStatus::Investigating.can_transition_to(CaseStatus::Open));
+        assert!(!CaseStatus::SarFiled.can_transition_to(CaseStatus::Investigating));
+        assert!(!CaseStatus::ClosedRejected.can_transition_to(CaseStatus::ClosedApproved));
+    }
+
+    #[test]
+    fn test_status_labels_round_trip() {
+        for status in CaseStatus::ALL {
+            assert_eq!(CaseStatus::from_label(status.as_str()), Some(status));
+            assert_eq!(
+                serde_json::to_value(status).unwrap(),
+                serde_json::json!(status.as_str())
+            );
+        }
+        assert_eq!(CaseStatus::from_label("closed"), None);
+    }
+}
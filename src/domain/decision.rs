@@ -1,11 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use utoipa::ToSchema;
 
 /// Risk decision outcome with severity ordering.
 ///
 /// Decisions are ordered by severity from least to most severe.
 /// When multiple rules trigger, the most severe decision wins.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema,
+)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[repr(u8)]
 #[derive(Default)]
@@ -24,6 +27,15 @@ pub enum Decision {
 }
 
 impl Decision {
+    /// All decisions in ascending severity order.
+    pub const ALL: [Decision; 5] = [
+        Decision::Allow,
+        Decision::SoftDenyRetry,
+        Decision::HoldAuto,
+        Decision::Review,
+        Decision::RejectFatal,
+    ];
+
     /// Returns the more severe of two decisions.
     #[inline]
     pub fn max(self, other: Self) -> Self {
@@ -54,6 +66,12 @@ impl Decision {
         *self as u8
     }
 
+    /// Decision with the given severity rank, the inverse of
+    /// [`severity`](Self::severity).
+    pub fn from_severity(severity: u8) -> Option<Self> {
+        Decision::ALL.get(severity as usize).copied()
+    }
+
     /// Parse from string representation.
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_uppercase().as_str() {
@@ -67,7 +85,6 @@ impl Decision {
     }
 }
 
-
 impl fmt::Display for Decision {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -92,6 +109,14 @@ mod tests {
         assert!(Decision::Review < Decision::RejectFatal);
     }
 
+    #[test]
+    fn test_severity_round_trip() {
+        for decision in Decision::ALL {
+            assert_eq!(Decision::from_severity(decision.severity()), Some(decision));
+        }
+        assert_eq!(Decision::from_severity(5), None);
+    }
+
     #[test]
     fn test_decision_max() {
         assert_eq!(Decision::Allow.max(Decision::HoldAuto), Decision::HoldAuto);
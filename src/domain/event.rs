@@ -1,14 +1,17 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use super::evidence::Evidence;
-use super::subject::Subject;
+use super::money::UsdRate;
+use super::subject::{Address, CountryCode, Subject};
 use super::Decision;
 
 /// Unique event identifier.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
 #[serde(transparent)]
 pub struct EventId(pub String);
 
@@ -63,6 +66,25 @@ pub enum Direction {
     Outbound,
 }
 
+/// Destination of an outbound transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Destination {
+    pub address: Address,
+    /// Destination tag or memo identifying the account at a shared address,
+    /// for XRP/XLM/EOS style assets
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl Destination {
+    pub fn new(address: impl Into<String>, tag: Option<String>) -> Self {
+        Destination {
+            address: Address::new(address),
+            tag: tag.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()),
+        }
+    }
+}
+
 /// Schema version for event compatibility.
 pub const SCHEMA_VERSION: &str = "v1";
 
@@ -94,6 +116,11 @@ pub struct TxEvent {
     /// Direction of the transfer
     pub direction: Direction,
 
+    /// Transaction type from the request, lowercase (e.g. `withdraw`,
+    /// `otc_trade`); empty when unknown
+    #[serde(default)]
+    pub tx_type: String,
+
     /// Asset being transferred
     pub asset: Asset,
 
@@ -104,6 +131,10 @@ pub struct TxEvent {
     #[serde(with = "rust_decimal::serde::str")]
     pub usd_value: Decimal,
 
+    /// Caller's quote of the exchange rate `usd_value` was computed at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usd_rate: Option<UsdRate>,
+
     /// Number of confirmations
     #[serde(default)]
     pub confirmations: u32,
@@ -111,6 +142,33 @@ pub struct TxEvent {
     /// Maximum finality depth for the chain
     #[serde(default)]
     pub max_finality_depth: u32,
+
+    /// Country of the counterparty, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty_geo: Option<CountryCode>,
+
+    /// Destination address and tag, when the request names one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<Destination>,
+
+    /// Experimental features the request opted in to, or out of with a
+    /// leading `-`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+
+    /// Whether storage had never seen the subject before this request;
+    /// None when storage was not consulted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_is_new: Option<bool>,
+
+    /// Whether the subject's KYC tier came from neither the request nor
+    /// storage
+    #[serde(skip)]
+    pub kyc_tier_missing: bool,
+
+    /// Address the request came from, from the request context's `ip`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ip: Option<IpAddr>,
 }
 
 impl TxEvent {
@@ -126,17 +184,25 @@ impl TxEvent {
             chain: Chain::inline(),
             tx_hash: String::new(),
             direction,
+            tx_type: String::new(),
             asset,
             amount: String::new(),
             usd_value,
+            usd_rate: None,
             confirmations: 0,
             max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
         }
     }
 }
 
 /// Decision stage in the processing pipeline.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum DecisionStage {
     /// Initial fast-path decision
@@ -148,7 +214,7 @@ pub enum DecisionStage {
 }
 
 /// Decision event recording a risk decision.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DecisionEvent {
     /// Schema version for forward compatibility
     pub schema_version: String,
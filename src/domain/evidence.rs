@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Evidence captured when a rule triggers.
 ///
 /// Provides audit trail information about why a decision was made.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Evidence {
     /// The rule that triggered
     pub rule_id: String,
@@ -17,6 +18,10 @@ pub struct Evidence {
     /// The threshold/limit that was exceeded (if applicable)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<String>,
+
+    /// Structured context for investigators (e.g. list name, contributing transactions)
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub details: serde_json::Value,
 }
 
 impl Evidence {
@@ -31,6 +36,7 @@ impl Evidence {
             key: key.into(),
             value: value.into(),
             limit: None,
+            details: serde_json::Value::Null,
         }
     }
 
@@ -46,8 +52,15 @@ impl Evidence {
             key: key.into(),
             value: value.into(),
             limit: Some(limit.into()),
+            details: serde_json::Value::Null,
         }
     }
+
+    /// Attach structured details.
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = details;
+        self
+    }
 }
 
 /// Result of evaluating a rule.
@@ -119,6 +132,20 @@ mod tests {
         assert_eq!(ev.limit, Some("50000".to_string()));
     }
 
+    #[test]
+    fn test_evidence_details_serialization() {
+        let ev = Evidence::new("R1_OFAC", "address", "0xdead");
+        let json = serde_json::to_string(&ev).unwrap();
+        assert!(!json.contains("details"));
+
+        let ev = ev.with_details(serde_json::json!({ "list": "OFAC" }));
+        let json = serde_json::to_string(&ev).unwrap();
+        assert!(json.contains(r#""details":{"list":"OFAC"}"#));
+
+        let parsed: Evidence = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.details["list"], "OFAC");
+    }
+
     #[test]
     fn test_rule_result_combine() {
         let allow = RuleResult::allow();
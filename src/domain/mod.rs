@@ -1,11 +1,23 @@
+pub mod annotation;
+pub mod asset;
+pub mod case;
 pub mod decision;
 pub mod event;
 pub mod evidence;
+pub mod money;
 pub mod policy;
 pub mod subject;
 
+pub use annotation::{Annotation, RiskRating};
+pub use asset::{AssetError, AssetInfo, AssetRegistry};
+pub use case::{Case, CaseStatus};
 pub use decision::Decision;
-pub use event::{DecisionEvent, TxEvent};
+pub use event::{DecisionEvent, Destination, TxEvent};
 pub use evidence::Evidence;
-pub use policy::{Policy, RuleDef, RuleParams, RuleType};
-pub use subject::{KycTier, Subject};
+pub use money::{Money, UsdRate};
+pub use policy::{
+    ActionAnnotations, ActionPlans, CompositeSignals, MinKycRequirement, MitigationConditions,
+    MitigationDef, PlannedAction, Policy, PriceBand, RuleDef, RuleParams, RuleScope, RuleType,
+    UnknownFieldAction, WindowKey, WindowMode,
+};
+pub use subject::{KycTier, Subject, SubjectAddress, TierRanking};
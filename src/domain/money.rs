@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::event::TxEvent;
+use super::evidence::Evidence;
+
+/// Currency code of USD values.
+pub const USD: &str = "USD";
+
+/// Rule ID of evidence recording the exchange rate behind USD figures.
+pub const USD_RATE_RULE_ID: &str = "USD_RATE";
+
+/// Source of a rate implied by a request's USD value and amount.
+pub const IMPLIED_RATE_SOURCE: &str = "implied";
+
+/// Evidence keys whose values are USD figures.
+pub const USD_EVIDENCE_KEYS: &[&str] = &["usd_value", "daily_usd", "weekly_usd", "monthly_usd"];
+
+/// Monetary amount with its currency.
+///
+/// Serialized as `{"amount": "1000.50", "currency": "USD"}`; the amount is a
+/// decimal string so no consumer parses it into a float.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Money {
+    #[serde(with = "rust_decimal::serde::str")]
+    pub amount: Decimal,
+    /// ISO 4217 code, or the asset symbol for asset amounts
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: impl Into<String>) -> Self {
+        Money {
+            amount,
+            currency: currency.into(),
+        }
+    }
+
+    /// Amount in US dollars.
+    pub fn usd(amount: Decimal) -> Self {
+        Money::new(amount, USD)
+    }
+}
+
+/// Exchange rate a transaction's USD value was computed at, as quoted by
+/// the caller's price source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct UsdRate {
+    /// Price source the rate came from (e.g. `coinbase`, `internal_oracle`)
+    pub source: String,
+    /// USD per unit of the asset
+    pub rate: Decimal,
+    /// When the source quoted the rate
+    pub as_of: DateTime<Utc>,
+}
+
+impl UsdRate {
+    /// Rate the event's USD value was taken at: the caller's quote, or else
+    /// the one implied by its USD value and amount, as of the event. None
+    /// if the event has no quote and no amount.
+    pub fn of(event: &TxEvent) -> Option<UsdRate> {
+        if let Some(rate) = &event.usd_rate {
+            return Some(rate.clone());
+        }
+        let amount: Decimal = event.amount.parse().ok()?;
+        let rate = event.usd_value.checked_div(amount)?;
+        Some(UsdRate {
+            source: IMPLIED_RATE_SOURCE.to_string(),
+            rate: rate.normalize(),
+            as_of: event.occurred_at,
+        })
+    }
+
+    /// Record the rate behind the event's USD value when some of the
+    /// evidence is a USD figure, so a limit decision can be checked against
+    /// the rate at the time.
+    pub fn attach(event: &TxEvent, evidence: &mut Vec<Evidence>) {
+        let priced = evidence
+            .iter()
+            .any(|e| USD_EVIDENCE_KEYS.contains(&e.key.as_str()));
+        if !priced {
+            return;
+        }
+        let Some(rate) = UsdRate::of(event) else {
+            return;
+        };
+        evidence.push(
+            Evidence::new(USD_RATE_RULE_ID, "usd_rate", rate.rate.to_string()).with_details(
+                serde_json::json!({
+                    "source": rate.source,
+                    "as_of": rate.as_of,
+                    "asset": event.asset.0,
+                    "amount": event.amount,
+                    "usd_value": event.usd_value.to_string(),
+                }),
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use smallvec::smallvec;
+
+    fn event(amount: &str, usd_value: i64) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![Address::new("0xabc")],
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        };
+        let mut event = TxEvent::new(
+            subject,
+            Asset::new("BTC"),
+            Decimal::new(usd_value, 0),
+            Direction::Outbound,
+        );
+        event.amount = amount.to_string();
+        event
+    }
+
+    #[test]
+    fn test_rate_attached_to_usd_evidence() {
+        let mut evidence = vec![Evidence::new("R1_OFAC", "address", "0xabc")];
+        UsdRate::attach(&event("0.5", 30000), &mut evidence);
+        assert_eq!(evidence.len(), 1);
+
+        let mut evidence = vec![Evidence::with_limit(
+            "R4_DAILY",
+            "daily_usd",
+            "60000",
+            "50000",
+        )];
+        UsdRate::attach(&event("0.5", 30000), &mut evidence);
+        assert_eq!(evidence[1].rule_id, USD_RATE_RULE_ID);
+        assert_eq!(evidence[1].value, "60000");
+        assert_eq!(evidence[1].details["source"], IMPLIED_RATE_SOURCE);
+        assert_eq!(evidence[1].details["usd_value"], "30000");
+    }
+
+    #[test]
+    fn test_caller_quote_preferred() {
+        let mut tx = event("0.5", 30000);
+        let as_of = Utc::now();
+        tx.usd_rate = Some(UsdRate {
+            source: "coinbase".to_string(),
+            rate: Decimal::new(6000012, 2),
+            as_of,
+        });
+        let rate = UsdRate::of(&tx).unwrap();
+        assert_eq!(rate.source, "coinbase");
+        assert_eq!(rate.rate.to_string(), "60000.12");
+
+        // Nothing to imply a rate from
+        assert!(UsdRate::of(&event("", 30000)).is_none());
+        assert!(UsdRate::of(&event("0", 30000)).is_none());
+    }
+}
@@ -1,8 +1,9 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
 
-use super::Decision;
+use super::{Decision, KycTier, TierRanking};
 
 /// Policy configuration defining rules and their parameters.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,9 +20,21 @@ pub struct Policy {
     #[serde(default)]
     pub rules: Vec<RuleDef>,
 
+    /// Rules capping the aggregated decision under specific conditions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mitigations: Vec<MitigationDef>,
+
+    /// Actions callers carry out for each final decision
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub decision_actions: ActionPlans,
+
     /// Policy signature (for verification)
     #[serde(default)]
     pub signature: String,
+
+    /// Evaluate and record decisions without enforcing them
+    #[serde(default)]
+    pub monitor_only: bool,
 }
 
 impl Policy {
@@ -31,7 +44,10 @@ impl Policy {
             version: "0.0.0".to_string(),
             params: RuleParams::default(),
             rules: Vec::new(),
+            mitigations: Vec::new(),
+            decision_actions: ActionPlans::new(),
             signature: String::new(),
+            monitor_only: false,
         }
     }
 
@@ -46,35 +62,238 @@ impl Policy {
     }
 }
 
+/// How daily volume and count windows are bounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowMode {
+    /// The last 24 hours
+    #[default]
+    Rolling,
+    /// Since midnight in `window_timezone`
+    CalendarDay,
+}
+
+/// Transactions a rule's windows aggregate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowKey {
+    /// All of the user's transactions, across accounts
+    #[default]
+    User,
+    /// Only transactions of the request's `account_id`
+    Account,
+}
+
+impl WindowKey {
+    pub fn is_user(&self) -> bool {
+        *self == WindowKey::User
+    }
+}
+
+/// How a subject with a missing or unknown KYC tier or country is decided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnknownFieldAction {
+    /// Evaluate the tier as the lowest ranked one; a transaction without a
+    /// known country cannot clear jurisdiction checks and is rejected
+    MostRestrictive,
+    /// Send the transaction to manual review
+    Review,
+    /// Reject the transaction
+    Reject,
+}
+
 /// Parameters used by rules.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RuleParams {
+    /// KYC tiers from least to most verified (default: L0, L1, L2)
+    #[serde(default)]
+    pub kyc_tiers: TierRanking,
+
     /// KYC tier transaction caps in USD
     #[serde(default)]
-    pub kyc_tier_caps_usd: HashMap<String, Decimal>,
+    pub kyc_tier_caps_usd: HashMap<KycTier, Decimal>,
+
+    /// Countries grouped for `limit_matrix`, keyed by group name
+    #[serde(default)]
+    pub country_groups: HashMap<String, Vec<String>>,
+
+    /// Multipliers applied to KYC caps and volume limits, keyed by KYC tier
+    /// then country group (e.g. `L1: { high_risk: 0.5 }`)
+    #[serde(default)]
+    pub limit_matrix: HashMap<KycTier, HashMap<String, Decimal>>,
 
     /// Daily volume limit in USD
     #[serde(default)]
     pub daily_volume_limit_usd: Option<Decimal>,
 
+    /// Daily volume limits in native units, keyed by asset symbol
+    /// (e.g. `BTC: 2`); applied alongside the USD limit
+    #[serde(default)]
+    pub daily_volume_limits_native: HashMap<String, Decimal>,
+
+    /// Window used by the daily volume and structuring rules
+    #[serde(default)]
+    pub window_mode: WindowMode,
+
+    /// IANA timezone whose midnight starts a `calendar_day` window
+    /// (default UTC)
+    #[serde(default)]
+    pub window_timezone: Option<String>,
+
+    /// Confirmations a deposit needs before it is final, keyed by chain
+    /// (e.g. `ethereum: 12`), for the pending finality rule
+    #[serde(default)]
+    pub finality_confirmations: HashMap<String, u32>,
+
+    /// Rolling 7-day volume limit in USD
+    #[serde(default)]
+    pub weekly_volume_limit_usd: Option<Decimal>,
+
+    /// Rolling 30-day volume limit in USD
+    #[serde(default)]
+    pub monthly_volume_limit_usd: Option<Decimal>,
+
     /// Small transaction threshold for structuring detection
     #[serde(default)]
     pub structuring_small_usd: Option<Decimal>,
 
+    /// Small transaction thresholds in USD keyed by asset symbol
+    /// (e.g. `USDT: 9500`), since reporting thresholds differ by rail;
+    /// other assets use `structuring_small_usd`
+    #[serde(default)]
+    pub structuring_small_usd_by_asset: HashMap<String, Decimal>,
+
     /// Count threshold for structuring detection
     #[serde(default)]
     pub structuring_small_count: Option<u32>,
+
+    /// Enables adaptive structuring detection: a transaction is small when
+    /// under this multiple of the subject's typical size
+    #[serde(default)]
+    pub structuring_adaptive_multiplier: Option<Decimal>,
+
+    /// Transactions a subject needs before its typical size is used
+    /// (default 20); until then `structuring_small_usd` applies
+    #[serde(default)]
+    pub structuring_adaptive_min_samples: Option<u32>,
+
+    /// Minimum severity of prior decisions counted by the decision rate rule
+    #[serde(default)]
+    pub decision_rate_min_decision: Option<Decision>,
+
+    /// Number of prior flagged decisions tolerated within the window
+    #[serde(default)]
+    pub decision_rate_max_count: Option<u32>,
+
+    /// Lookback window for the decision rate rule in hours (default 24)
+    #[serde(default)]
+    pub decision_rate_window_hours: Option<u32>,
+
+    /// Target false positive rate of the sanctions bloom filter (default 0.01)
+    #[serde(default)]
+    pub sanctions_bloom_fp_rate: Option<f64>,
+
+    /// Minimum capacity the sanctions bloom filter is sized for (default 100)
+    #[serde(default)]
+    pub sanctions_bloom_capacity: Option<usize>,
+
+    /// Name of the sanctions list reported by screening (default "OFAC")
+    #[serde(default)]
+    pub sanctions_list_name: Option<String>,
+
+    /// Decision requests allowed per subject per minute
+    #[serde(default)]
+    pub request_burst_max_per_minute: Option<u32>,
+
+    /// Transactions allowed per window involving each capped country, keyed
+    /// by ISO country code, for the country count rule
+    #[serde(default)]
+    pub country_tx_caps: HashMap<String, u32>,
+
+    /// Window for the country count rule in hours (default 24)
+    #[serde(default)]
+    pub country_tx_window_hours: Option<u32>,
+
+    /// Transactions at or above this USD value are checked against active hours
+    #[serde(default)]
+    pub unusual_hours_min_usd: Option<Decimal>,
+
+    /// Prior transactions required before active hours are trusted (default 20)
+    #[serde(default)]
+    pub unusual_hours_min_history: Option<u32>,
+
+    /// Share of history near the transaction's hour below which it is
+    /// considered out of pattern (default 0.05)
+    #[serde(default)]
+    pub unusual_hours_min_share: Option<f64>,
+
+    /// History used to learn active hours in days (default 30)
+    #[serde(default)]
+    pub unusual_hours_lookback_days: Option<u32>,
+
+    /// USD received in other assets within the window before a withdrawal
+    /// is checked for chain-hopping
+    #[serde(default)]
+    pub chain_hop_min_inbound_usd: Option<Decimal>,
+
+    /// Share of that inbound which, withdrawn in a different asset, flags
+    /// the withdrawal (default 0.8)
+    #[serde(default)]
+    pub chain_hop_ratio: Option<Decimal>,
+
+    /// Window for the chain-hopping rule in minutes (default 60)
+    #[serde(default)]
+    pub chain_hop_window_minutes: Option<u32>,
+
+    /// Transactions at or above this USD value must have the caller's KYC
+    /// tier confirmed by the identity provider
+    #[serde(default)]
+    pub kyc_verification_min_usd: Option<Decimal>,
+
+    /// Distinct destinations a subject may send to per window
+    #[serde(default)]
+    pub distinct_destinations_max: Option<u32>,
+
+    /// Window for the distinct destinations rule in hours (default 24)
+    #[serde(default)]
+    pub distinct_destinations_window_hours: Option<u32>,
+
+    /// Autonomous system numbers whose addresses `ip_jurisdiction` rules
+    /// block
+    #[serde(default)]
+    pub sanctioned_asns: Vec<u32>,
+
+    /// Largest USD value a single transaction can plausibly have, for
+    /// `amount_sanity` rules
+    #[serde(default)]
+    pub amount_sanity_max_usd: Option<Decimal>,
+
+    /// Plausible USD prices per unit keyed by asset symbol (e.g.
+    /// `BTC: { min: 1000, max: 1000000 }`), for `amount_sanity` rules
+    #[serde(default)]
+    pub asset_price_bands_usd: HashMap<String, PriceBand>,
+
+    /// Experimental features enabled for requests that don't opt out
+    #[serde(default)]
+    pub default_features: Vec<String>,
+
+    /// How subjects whose KYC tier is missing or not in `kyc_tiers`, or
+    /// whose country is missing or malformed, are decided; unset evaluates
+    /// them as sent, with a missing tier as L0
+    #[serde(default)]
+    pub unknown_subject_fields: Option<UnknownFieldAction>,
 }
 
 impl RuleParams {
     /// Get KYC cap for a tier, returning None if no limit.
-    pub fn kyc_cap(&self, tier: &str) -> Option<Decimal> {
+    pub fn kyc_cap(&self, tier: &KycTier) -> Option<Decimal> {
         self.kyc_tier_caps_usd.get(tier).copied()
     }
 }
 
 /// Rule type identifier.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RuleType {
     /// OFAC address screening
@@ -85,10 +304,116 @@ pub enum RuleType {
     KycTierTxCap,
     /// Daily USD volume limit
     DailyUsdVolume,
+    /// Rolling 7-day USD volume limit
+    WeeklyUsdVolume,
+    /// Rolling 30-day USD volume limit
+    MonthlyUsdVolume,
     /// Structuring detection (small tx pattern)
     StructuringSmallTx,
+    /// Escalation after repeated holds/reviews for a subject
+    DecisionRateAnomaly,
+    /// Large transaction outside the subject's usual active hours
+    UnusualHours,
+    /// Too many decision requests from one subject per minute
+    RequestBurst,
+    /// Too many transactions involving a capped country per window
+    CountryTxCount,
+    /// Receiving one asset and rapidly withdrawing another
+    ChainHop,
+    /// Minimum KYC tier required per transaction type
+    MinKycTier,
+    /// Several weak risk signals at once, e.g. medium-risk country, low
+    /// KYC tier and a first-ever transaction
+    CompositeRisk,
+    /// Hold deposits until they have enough confirmations
+    PendingFinality,
+    /// Confirm the caller's KYC tier with the external identity provider
+    /// for high-value transactions
+    KycVerification,
+    /// Too many distinct destination addresses from one subject per window
+    DistinctDestinations,
+    /// Client IP located in a blocked country or announced by a sanctioned
+    /// autonomous system
+    IpJurisdiction,
+    /// Implausible transaction values, such as a non-positive or enormous
+    /// USD value or one inconsistent with the asset's price
+    AmountSanity,
+}
+
+/// Range of plausible USD prices per unit of an asset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceBand {
+    pub min: Decimal,
+    pub max: Decimal,
+}
+
+/// Minimum KYC tier required for a transaction type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinKycRequirement {
+    pub tier: KycTier,
+    /// Action when the subject's tier is lower (default: the rule's action)
+    #[serde(default)]
+    pub action: Option<Decision>,
+}
+
+/// Weak signals combined by a composite risk rule.
+///
+/// Each configured signal is one condition; unset signals are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CompositeSignals {
+    /// Subject country is in one of these `country_groups`
+    #[serde(default)]
+    pub country_groups: Vec<String>,
+    /// Subject KYC tier is this tier or lower in `kyc_tiers`
+    #[serde(default)]
+    pub max_kyc_tier: Option<KycTier>,
+    /// Subject has never been seen before this request
+    #[serde(default)]
+    pub new_subject: bool,
+    /// Transaction is at least this many USD
+    #[serde(default)]
+    pub min_usd: Option<Decimal>,
+    /// Signals that must match (default: all configured signals)
+    #[serde(default)]
+    pub min_signals: Option<usize>,
+}
+
+impl CompositeSignals {
+    /// Number of configured signals.
+    pub fn configured(&self) -> usize {
+        [
+            !self.country_groups.is_empty(),
+            self.max_kyc_tier.is_some(),
+            self.new_subject,
+            self.min_usd.is_some(),
+        ]
+        .into_iter()
+        .filter(|&set| set)
+        .count()
+    }
+}
+
+/// Caller-facing actions attached to a rule, such as
+/// `require_step_up_auth: true` or `notify_team: fraud-ops`.
+pub type ActionAnnotations = BTreeMap<String, serde_json::Value>;
+
+/// A consequence callers carry out for a decision, such as freezing
+/// withdrawals for a day or notifying a team.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PlannedAction {
+    /// What to do, e.g. `freeze_withdrawals` or `notify`
+    pub action: String,
+    /// How long the action lasts, for time-bound actions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<u64>,
+    /// Who the action is directed at, e.g. the team to notify
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
 }
 
+/// Actions to carry out for each decision, in order.
+pub type ActionPlans = BTreeMap<Decision, Vec<PlannedAction>>;
+
 /// Definition of a single rule.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleDef {
@@ -105,6 +430,115 @@ pub struct RuleDef {
     /// Blocked countries for jurisdiction rule
     #[serde(default)]
     pub blocked_countries: Vec<String>,
+
+    /// Minimum KYC tiers keyed by transaction type, for the min KYC tier rule
+    #[serde(default)]
+    pub min_kyc_tiers: HashMap<String, MinKycRequirement>,
+
+    /// Signals combined by the composite risk rule
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signals: Option<CompositeSignals>,
+
+    /// Actions returned to the caller when the rule triggers, alongside
+    /// the decision
+    #[serde(default)]
+    pub annotations: ActionAnnotations,
+
+    /// Feature that marks the rule experimental; it only runs for requests
+    /// that opt in to the feature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<String>,
+
+    /// Also re-evaluate the rule against recently active subjects on a
+    /// schedule, between their transactions
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sweep: bool,
+
+    /// Whether the rule's windows aggregate per user or per account
+    #[serde(default, skip_serializing_if = "WindowKey::is_user")]
+    pub window_key: WindowKey,
+
+    /// Transactions the rule applies to; it is not evaluated for others
+    #[serde(default, skip_serializing_if = "RuleScope::is_empty")]
+    pub scope: RuleScope,
+}
+
+/// Branches of traffic a rule applies to, so policies with many country,
+/// asset or tier specific rules only evaluate the relevant ones. Every
+/// configured branch must match; unset branches match everything.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuleScope {
+    /// Subject country is one of these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub countries: Vec<String>,
+    /// Transaction asset is one of these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assets: Vec<String>,
+    /// Subject KYC tier is one of these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kyc_tiers: Vec<KycTier>,
+}
+
+impl RuleScope {
+    /// Whether the rule applies to all transactions.
+    pub fn is_empty(&self) -> bool {
+        self.countries.is_empty() && self.assets.is_empty() && self.kyc_tiers.is_empty()
+    }
+}
+
+/// Conditions a mitigation applies under. Every configured condition must
+/// match; unset conditions are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MitigationConditions {
+    /// Subject user ID is one of these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub user_ids: Vec<String>,
+    /// Subject account ID is one of these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub account_ids: Vec<String>,
+    /// Subject country is one of these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub countries: Vec<String>,
+    /// Subject KYC tier is one of these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kyc_tiers: Vec<KycTier>,
+    /// Transaction is at most this many USD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_usd: Option<Decimal>,
+}
+
+impl MitigationConditions {
+    /// Whether any condition is configured.
+    pub fn is_empty(&self) -> bool {
+        self.user_ids.is_empty()
+            && self.account_ids.is_empty()
+            && self.countries.is_empty()
+            && self.kyc_tiers.is_empty()
+            && self.max_usd.is_none()
+    }
+}
+
+/// Definition of a mitigating rule, which caps the decision the rules
+/// reached, e.g. whitelisted corporate accounts never exceed Review.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MitigationDef {
+    /// Unique identifier, recorded in evidence when the mitigation applies
+    pub id: String,
+
+    /// Order mitigations are considered in, lowest first; only the first
+    /// that matches applies
+    pub precedence: i32,
+
+    /// Most severe decision allowed when the mitigation applies
+    pub cap: Decision,
+
+    /// Conditions on the subject and transaction
+    pub when: MitigationConditions,
+
+    /// Rules the mitigation may override; when set, it only applies if
+    /// every triggered rule is listed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<String>,
 }
 
 impl RuleDef {
@@ -112,7 +546,14 @@ impl RuleDef {
     pub fn is_inline(&self) -> bool {
         matches!(
             self.rule_type,
-            RuleType::OfacAddr | RuleType::JurisdictionBlock | RuleType::KycTierTxCap
+            RuleType::OfacAddr
+                | RuleType::JurisdictionBlock
+                | RuleType::KycTierTxCap
+                | RuleType::MinKycTier
+                | RuleType::CompositeRisk
+                | RuleType::PendingFinality
+                | RuleType::IpJurisdiction
+                | RuleType::AmountSanity
         )
     }
 
@@ -120,7 +561,17 @@ impl RuleDef {
     pub fn is_streaming(&self) -> bool {
         matches!(
             self.rule_type,
-            RuleType::DailyUsdVolume | RuleType::StructuringSmallTx
+            RuleType::DailyUsdVolume
+                | RuleType::WeeklyUsdVolume
+                | RuleType::MonthlyUsdVolume
+                | RuleType::StructuringSmallTx
+                | RuleType::DecisionRateAnomaly
+                | RuleType::UnusualHours
+                | RuleType::RequestBurst
+                | RuleType::CountryTxCount
+                | RuleType::ChainHop
+                | RuleType::KycVerification
+                | RuleType::DistinctDestinations
         )
     }
 }
@@ -149,12 +600,23 @@ rules:
     type: jurisdiction_block
     action: REJECT_FATAL
     blocked_countries: ["IR", "KP", "SY", "RU"]
+  - id: R4_DAILY_USD
+    type: daily_usd_volume
+    action: HOLD_AUTO
+    annotations:
+      require_step_up_auth: true
+      notify_team: fraud-ops
 signature: "UNSIGNED-MVP"
 "#;
 
         let policy: Policy = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(policy.version, "2025-01-01.1");
-        assert_eq!(policy.rules.len(), 2);
+        assert_eq!(policy.rules.len(), 3);
+        assert!(policy.rules[0].annotations.is_empty());
+        assert_eq!(
+            policy.rules[2].annotations["notify_team"],
+            serde_json::json!("fraud-ops")
+        );
         assert_eq!(policy.rules[0].action, Decision::RejectFatal);
         assert_eq!(
             policy.params.kyc_tier_caps_usd.get("L1"),
@@ -169,6 +631,13 @@ signature: "UNSIGNED-MVP"
             rule_type: RuleType::OfacAddr,
             action: Decision::RejectFatal,
             blocked_countries: vec![],
+            min_kyc_tiers: HashMap::new(),
+            signals: None,
+            experimental: None,
+            sweep: false,
+            annotations: ActionAnnotations::new(),
+            window_key: WindowKey::User,
+            scope: RuleScope::default(),
         };
         assert!(inline_rule.is_inline());
         assert!(!inline_rule.is_streaming());
@@ -178,6 +647,13 @@ signature: "UNSIGNED-MVP"
             rule_type: RuleType::DailyUsdVolume,
             action: Decision::HoldAuto,
             blocked_countries: vec![],
+            min_kyc_tiers: HashMap::new(),
+            signals: None,
+            experimental: None,
+            sweep: false,
+            annotations: ActionAnnotations::new(),
+            window_key: WindowKey::User,
+            scope: RuleScope::default(),
         };
         assert!(!streaming_rule.is_inline());
         assert!(streaming_rule.is_streaming());
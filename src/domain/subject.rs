@@ -1,5 +1,7 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
 use smallvec::SmallVec;
+use std::borrow::{Borrow, Cow};
 use std::fmt;
 
 /// Unique user identifier.
@@ -32,6 +34,10 @@ impl AccountId {
     pub fn new(id: impl Into<String>) -> Self {
         AccountId(id.into())
     }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 /// Blockchain address (hex string, case-insensitive).
@@ -84,37 +90,88 @@ impl fmt::Display for CountryCode {
 }
 
 /// KYC verification tier.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
-pub enum KycTier {
+///
+/// Tiers are named by the policy (`L3`, `CORPORATE`, ...) and ordered by
+/// its `kyc_tiers` ranking, so a tier carries no order of its own. Names
+/// are case-insensitive and kept uppercase. `L0`–`L2` are the built-in
+/// tiers and the default ranking.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct KycTier(Cow<'static, str>);
+
+impl KycTier {
     /// Unverified or minimal verification
-    #[default]
-    #[serde(rename = "L0")]
-    L0,
+    pub const L0: KycTier = KycTier(Cow::Borrowed("L0"));
     /// Basic verification (ID check)
-    #[serde(rename = "L1")]
-    L1,
+    pub const L1: KycTier = KycTier(Cow::Borrowed("L1"));
     /// Full verification (ID + address + source of funds)
-    #[serde(rename = "L2")]
-    L2,
-}
+    pub const L2: KycTier = KycTier(Cow::Borrowed("L2"));
 
-impl KycTier {
+    /// Parse a tier name; None if it is blank.
     pub fn from_str(s: &str) -> Option<Self> {
-        match s.to_uppercase().as_str() {
-            "L0" => Some(KycTier::L0),
-            "L1" => Some(KycTier::L1),
-            "L2" => Some(KycTier::L2),
-            _ => None,
+        let s = s.trim();
+        if s.is_empty() {
+            return None;
         }
+        Some(KycTier(Cow::Owned(s.to_uppercase())))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for KycTier {
+    fn borrow(&self) -> &str {
+        &self.0
     }
+}
 
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            KycTier::L0 => "L0",
-            KycTier::L1 => "L1",
-            KycTier::L2 => "L2",
+impl Default for KycTier {
+    fn default() -> Self {
+        KycTier::L0
+    }
+}
+
+impl<'de> Deserialize<'de> for KycTier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        KycTier::from_str(&s).ok_or_else(|| serde::de::Error::custom("empty KYC tier"))
+    }
+}
+
+/// Order of the policy's KYC tiers, from least to most verified.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TierRanking(Vec<KycTier>);
+
+impl TierRanking {
+    pub fn new(tiers: Vec<KycTier>) -> Self {
+        TierRanking(tiers)
+    }
+
+    /// Position of a tier in the ranking, if it is ranked.
+    pub fn rank(&self, tier: &KycTier) -> Option<usize> {
+        self.0.iter().position(|t| t == tier)
+    }
+
+    /// Returns true if `tier` is `required` or above. Unranked tiers meet
+    /// no requirement.
+    pub fn meets(&self, tier: &KycTier, required: &KycTier) -> bool {
+        match (self.rank(tier), self.rank(required)) {
+            (Some(rank), Some(required)) => rank >= required,
+            _ => tier == required,
         }
     }
+
+    pub fn tiers(&self) -> &[KycTier] {
+        &self.0
+    }
+}
+
+impl Default for TierRanking {
+    fn default() -> Self {
+        TierRanking(vec![KycTier::L0, KycTier::L1, KycTier::L2])
+    }
 }
 
 impl fmt::Display for KycTier {
@@ -155,6 +212,19 @@ impl Subject {
     }
 }
 
+/// An address linked to a subject, with what is known about it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubjectAddress {
+    pub address: Address,
+    /// Whether ownership of the address has been verified
+    pub verified: bool,
+    /// Caller's name for the address, e.g. `cold wallet`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// When the address was first linked to the subject
+    pub first_seen: DateTime<Utc>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,5 +249,24 @@ mod tests {
 
         let parsed: KycTier = serde_json::from_str("\"L1\"").unwrap();
         assert_eq!(parsed, KycTier::L1);
+
+        let parsed: KycTier = serde_json::from_str("\"corporate\"").unwrap();
+        assert_eq!(parsed.as_str(), "CORPORATE");
+        assert!(serde_json::from_str::<KycTier>("\" \"").is_err());
+    }
+
+    #[test]
+    fn test_tier_ranking() {
+        let ranking: TierRanking =
+            serde_json::from_str(r#"["L0", "L1", "L2", "L3", "corporate"]"#).unwrap();
+        let tier = |s: &str| KycTier::from_str(s).unwrap();
+
+        assert!(ranking.meets(&tier("L3"), &KycTier::L2));
+        assert!(ranking.meets(&tier("corporate"), &tier("L3")));
+        assert!(!ranking.meets(&KycTier::L1, &tier("L3")));
+        assert!(!ranking.meets(&tier("L9"), &KycTier::L0));
+        assert_eq!(ranking.rank(&tier("CORPORATE")), Some(4));
+
+        assert!(TierRanking::default().meets(&KycTier::L2, &KycTier::L1));
     }
 }
@@ -0,0 +1,388 @@
+//! Decision engine embedded in a host process.
+//!
+//! For hosts that own the network layer, such as a gateway, and want to
+//! hand transactions to the engine in-process. [`EmbeddedEngine::start`]
+//! runs the engine as a task that reads [`TxEvent`]s from one channel and
+//! sends a [`DecisionEvent`] for each on another, in the order received.
+//! Decisions are made as the decision endpoint makes them: inline rules and
+//! the internal blocklist, then streaming rules against storage, with the
+//! outcome recorded. The HTTP server is not needed; a host that also wants
+//! the admin endpoints can serve [`create_router`] over the same storage
+//! and rule set.
+//!
+//! [`create_router`]: crate::api::create_router
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use chrono::Utc;
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::config::DegradedMode;
+use crate::domain::{Decision, DecisionEvent, TxEvent, UsdRate};
+use crate::inline_engine;
+use crate::rules::{
+    evaluate_streaming, Blocklist, RuleSet, RuleSwitches, StreamingRule, FINALITY_EVIDENCE_KEY,
+};
+use crate::storage::{
+    DecisionRecord, PendingDeposit, Storage, StorageRead, TransactionRecord, WindowCache,
+    WindowSpec,
+};
+
+/// Decision engine driven through channels instead of HTTP.
+pub struct EmbeddedEngine {
+    storage: Arc<dyn Storage>,
+    ruleset_rx: watch::Receiver<Arc<RuleSet>>,
+    switches: Option<Arc<RuleSwitches>>,
+    blocklist: Option<Arc<Blocklist>>,
+    monitor_only: bool,
+    degraded_mode: DegradedMode,
+}
+
+impl EmbeddedEngine {
+    /// Create an engine using the latest rule set from `ruleset_rx`.
+    pub fn new(storage: Arc<dyn Storage>, ruleset_rx: watch::Receiver<Arc<RuleSet>>) -> Self {
+        EmbeddedEngine {
+            storage,
+            ruleset_rx,
+            switches: None,
+            blocklist: None,
+            monitor_only: false,
+            degraded_mode: DegradedMode::default(),
+        }
+    }
+
+    /// Skip rules turned off by the kill-switch.
+    pub fn with_rule_switches(mut self, switches: Arc<RuleSwitches>) -> Self {
+        self.switches = Some(switches);
+        self
+    }
+
+    /// Screen addresses against the internal blocklist.
+    pub fn with_blocklist(mut self, blocklist: Arc<Blocklist>) -> Self {
+        self.blocklist = Some(blocklist);
+        self
+    }
+
+    /// Record decisions but allow every transaction.
+    pub fn with_monitor_only(mut self, monitor_only: bool) -> Self {
+        self.monitor_only = monitor_only;
+        self
+    }
+
+    /// Decide this way while storage is unavailable.
+    pub fn with_degraded_mode(mut self, mode: DegradedMode) -> Self {
+        self.degraded_mode = mode;
+        self
+    }
+
+    fn is_disabled(&self, rule_id: &str) -> bool {
+        self.switches
+            .as_ref()
+            .is_some_and(|s| s.is_disabled(rule_id))
+    }
+
+    /// Decide one transaction and record the outcome.
+    ///
+    /// The event ID is recorded as the request ID, so the decision can be
+    /// fetched with `GET /v1/decisions/{event_id}` when the server also runs.
+    pub async fn decide(&self, mut event: TxEvent) -> DecisionEvent {
+        let start = Instant::now();
+        let ruleset = self.ruleset_rx.borrow().clone();
+        let monitor_only = self.monitor_only || ruleset.monitor_only;
+        // Events always carry a tier, so it can be unknown but not missing
+        let (anomaly_decision, anomalies) = ruleset.subject_fields.apply(&mut event, false);
+        let user_id = event.subject.user_id.as_str();
+        let skip = |id: &str| self.is_disabled(id) || !ruleset.features.allows(id, &event.features);
+
+        let verdict = inline_engine::evaluate(&ruleset.inline_for(&event), &event, skip, |_, _| {});
+        let (mut decision, mut evidence) = (verdict.decision, verdict.evidence);
+        decision = decision.max(anomaly_decision);
+        evidence.splice(0..0, anomalies);
+        if let Some(blocklist) = &self.blocklist {
+            let result = blocklist.evaluate(&event);
+            if result.hit {
+                decision = decision.max(result.decision);
+                evidence.extend(result.evidence);
+            }
+        }
+
+        if decision.is_fatal() && !monitor_only {
+            UsdRate::attach(&event, &mut evidence);
+            return DecisionEvent::new(
+                event.event_id.clone(),
+                decision,
+                &ruleset.policy_version,
+                evidence,
+            );
+        }
+
+        let subject_id = if self.storage.is_degraded() {
+            warn!(
+                user_id = user_id,
+                "Storage degraded, skipping stateful rules"
+            );
+            None
+        } else {
+            match self.storage.upsert_subject(&event.subject).await {
+                Ok(id) => Some(id),
+                Err(e) => {
+                    warn!(user_id = user_id, error = %e, "Failed to upsert subject");
+                    None
+                }
+            }
+        };
+        let Some(subject_id) = subject_id else {
+            if monitor_only {
+                return unenforced(&event, &ruleset);
+            }
+            ruleset
+                .mitigations
+                .apply(&event, &mut decision, &mut evidence);
+            let decision = match self.degraded_mode {
+                DegradedMode::FailOpen => Decision::Allow,
+                DegradedMode::FailClosed => decision.max(Decision::SoftDenyRetry),
+                DegradedMode::InlineOnly => decision,
+            };
+            UsdRate::attach(&event, &mut evidence);
+            return DecisionEvent::new(
+                event.event_id.clone(),
+                decision,
+                &ruleset.policy_version,
+                evidence,
+            );
+        };
+
+        let rules: Vec<Arc<dyn StreamingRule>> = ruleset
+            .streaming_for(&event)
+            .iter()
+            .filter(|rule| !skip(rule.id()))
+            .cloned()
+            .collect();
+        let windows = WindowCache::new(self.storage.clone());
+        let specs: Vec<WindowSpec> = rules.iter().flat_map(|rule| rule.windows(&event)).collect();
+        if !specs.is_empty() {
+            // Rules fetch their own windows if this fails
+            if let Err(e) = windows.prefetch(subject_id, &specs).await {
+                warn!(user_id = user_id, error = %e, "Failed to prefetch window aggregates");
+            }
+        }
+        let windows: Arc<dyn StorageRead> = Arc::new(windows);
+        let shared_event = Arc::new(event.clone());
+        let evaluations =
+            evaluate_streaming(&rules, &shared_event, subject_id, &windows, |_| true).await;
+
+        let streaming_from = evidence.len();
+        let mut streaming_decision = Decision::Allow;
+        for evaluation in evaluations {
+            match evaluation.result {
+                Ok(result) if result.hit => {
+                    streaming_decision = streaming_decision.max(result.decision);
+                    decision = decision.max(result.decision);
+                    evidence.extend(result.evidence);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    warn!(user_id = user_id, rule_id = rules[evaluation.index].id(), error = %e, "Failed to evaluate streaming rule");
+                }
+            }
+        }
+        ruleset
+            .mitigations
+            .apply(&event, &mut decision, &mut evidence);
+        UsdRate::attach(&event, &mut evidence);
+
+        let tx_record = TransactionRecord {
+            subject_id,
+            tx_type: format!("{:?}", event.direction),
+            asset: event.asset.0.clone(),
+            amount: event.amount.parse().unwrap_or_default(),
+            usd_value: event.usd_value,
+            dest_address: event
+                .destination
+                .as_ref()
+                .map(|d| d.address.as_str().to_string()),
+            counterparty_geo: event
+                .counterparty_geo
+                .as_ref()
+                .map(|c| c.as_str().to_string()),
+            account_id: Some(event.subject.account_id.as_str().to_string()),
+        };
+        let decision_record = DecisionRecord {
+            subject_id: Some(subject_id),
+            request_id: Some(event.event_id.0.clone()),
+            request: serde_json::to_value(&event).unwrap_or(serde_json::Value::Null),
+            decision,
+            decision_code: evidence
+                .first()
+                .map(|e| e.rule_id.clone())
+                .unwrap_or_else(|| "OK".to_string()),
+            policy_version: ruleset.policy_version.clone(),
+            evidence: evidence.clone(),
+            latency_ms: start.elapsed().as_millis() as u32,
+        };
+        if let Err(e) = self
+            .storage
+            .record_outcome(&tx_record, &decision_record)
+            .await
+        {
+            warn!(user_id = user_id, error = %e, "Failed to record decision");
+        }
+
+        if monitor_only {
+            return unenforced(&event, &ruleset);
+        }
+        // Keep deposits short of finality so confirmation updates can release them
+        if evidence.iter().any(|e| e.key == FINALITY_EVIDENCE_KEY) {
+            let deposit = PendingDeposit {
+                subject_id,
+                event: event.clone(),
+                request_id: decision_record.request_id,
+                request: decision_record.request,
+                streaming_decision,
+                streaming_evidence: evidence[streaming_from..].to_vec(),
+                held_at: Utc::now(),
+            };
+            if let Err(e) = self.storage.save_pending_deposit(&deposit).await {
+                warn!(user_id = user_id, error = %e, "Failed to save pending deposit");
+            }
+        }
+
+        DecisionEvent::new(
+            event.event_id.clone(),
+            decision,
+            &ruleset.policy_version,
+            evidence,
+        )
+    }
+
+    /// Decide each event received on `events` and send the decision on
+    /// `decisions`, one at a time in the order received.
+    ///
+    /// The task ends when `events` is closed or `decisions` is dropped.
+    pub fn start(
+        self,
+        mut events: mpsc::Receiver<TxEvent>,
+        decisions: mpsc::Sender<DecisionEvent>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(event) = events.recv().await {
+                let decision = self.decide(event).await;
+                if decisions.send(decision).await.is_err() {
+                    debug!("Decision receiver dropped, stopping embedded engine");
+                    return;
+                }
+            }
+            debug!("Event channel closed, stopping embedded engine");
+        })
+    }
+}
+
+/// Decision sent in monitor-only mode, where every transaction is allowed.
+fn unenforced(event: &TxEvent, ruleset: &RuleSet) -> DecisionEvent {
+    DecisionEvent::new(
+        event.event_id.clone(),
+        Decision::Allow,
+        &ruleset.policy_version,
+        Vec::new(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::domain::Policy;
+    use crate::storage::{MockStorage, StorageRead};
+    use rust_decimal::Decimal;
+    use std::collections::HashSet;
+
+    const POLICY: &str = r#"
+policy_version: "embedded-1"
+params:
+  daily_volume_limit_usd: 1000
+rules:
+  - { id: R1_OFAC, type: ofac_addr, action: REJECT_FATAL }
+  - { id: R4_DAILY_USD, type: daily_usd_volume, action: HOLD_AUTO }
+"#;
+
+    fn engine(storage: Arc<MockStorage>) -> EmbeddedEngine {
+        let policy: Policy = serde_yaml::from_str(POLICY).unwrap();
+        let ruleset = RuleSet::from_policy(&policy, HashSet::from(["0xdead".to_string()]));
+        let (_tx, rx) = watch::channel(Arc::new(ruleset));
+        EmbeddedEngine::new(storage, rx)
+    }
+
+    fn event(address: &str, usd: i64) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec::smallvec![Address::new(address)],
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        };
+        TxEvent::new(
+            subject,
+            Asset::new("USDC"),
+            Decimal::new(usd, 0),
+            Direction::Outbound,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_decides_events_from_channel_in_order() {
+        let storage = Arc::new(MockStorage::new());
+        let (events_tx, events_rx) = mpsc::channel(8);
+        let (decisions_tx, mut decisions_rx) = mpsc::channel(8);
+        let handle = engine(storage.clone()).start(events_rx, decisions_tx);
+
+        let sent = vec![event("0xabc", 100), event("0xdead", 100)];
+        for event in &sent {
+            events_tx.send(event.clone()).await.unwrap();
+        }
+        drop(events_tx);
+
+        let first = decisions_rx.recv().await.unwrap();
+        assert_eq!(first.event_id, sent[0].event_id);
+        assert_eq!(first.decision, Decision::Allow);
+        assert_eq!(first.policy_version, "embedded-1");
+
+        let second = decisions_rx.recv().await.unwrap();
+        assert_eq!(second.event_id, sent[1].event_id);
+        assert_eq!(second.decision, Decision::RejectFatal);
+        assert_eq!(second.decision_code, "R1_OFAC");
+
+        assert!(decisions_rx.recv().await.is_none());
+        handle.await.unwrap();
+
+        // Only the decision that reached the stateful rules is recorded
+        let recorded = storage.get_recent_decisions(10).await.unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(
+            recorded[0].record.request_id.as_deref(),
+            Some(sent[0].event_id.0.as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_rules_and_monitor_only() {
+        let storage = Arc::new(MockStorage::new());
+        let engine = engine(storage.clone());
+
+        let decided = engine.decide(event("0xabc", 1500)).await;
+        assert_eq!(decided.decision, Decision::HoldAuto);
+        assert_eq!(decided.decision_code, "R4_DAILY_USD");
+
+        let decided = engine
+            .with_monitor_only(true)
+            .decide(event("0xabc", 1500))
+            .await;
+        assert_eq!(decided.decision, Decision::Allow);
+        assert!(decided.evidence.is_empty());
+        let recorded = storage.get_recent_decisions(1).await.unwrap();
+        assert_eq!(recorded[0].record.decision, Decision::HoldAuto);
+    }
+}
@@ -0,0 +1,93 @@
+use arrow_array::{ArrayRef, RecordBatch, StringArray, TimestampMicrosecondArray, UInt32Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use std::io::Write;
+use std::sync::Arc;
+
+use super::ExportRow;
+
+/// Parquet writer for exported decisions; each page becomes a row group.
+pub struct ParquetExport<W: Write + Send> {
+    writer: ArrowWriter<W>,
+    schema: SchemaRef,
+    rows: usize,
+}
+
+impl<W: Write + Send> ParquetExport<W> {
+    pub fn new(out: W) -> anyhow::Result<Self> {
+        let schema = schema();
+        Ok(ParquetExport {
+            writer: ArrowWriter::try_new(out, schema.clone(), None)?,
+            schema,
+            rows: 0,
+        })
+    }
+
+    pub fn write(&mut self, rows: &[ExportRow]) -> anyhow::Result<()> {
+        let strings = |f: fn(&ExportRow) -> &str| -> ArrayRef {
+            Arc::new(StringArray::from_iter_values(rows.iter().map(f)))
+        };
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|r| r.id.to_string()),
+            )),
+            Arc::new(
+                TimestampMicrosecondArray::from_iter_values(
+                    rows.iter().map(|r| r.created_at.timestamp_micros()),
+                )
+                .with_timezone("UTC"),
+            ),
+            Arc::new(StringArray::from(
+                rows.iter()
+                    .map(|r| r.request_id.clone())
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(StringArray::from(
+                rows.iter()
+                    .map(|r| r.subject_id.map(|id| id.to_string()))
+                    .collect::<Vec<_>>(),
+            )),
+            strings(|r| &r.decision),
+            strings(|r| &r.decision_code),
+            strings(|r| &r.policy_version),
+            strings(|r| &r.rules_hit),
+            Arc::new(UInt32Array::from_iter_values(
+                rows.iter().map(|r| r.latency_ms),
+            )),
+            strings(|r| &r.evidence),
+            strings(|r| &r.request),
+        ];
+
+        self.writer
+            .write(&RecordBatch::try_new(self.schema.clone(), columns)?)?;
+        self.rows += rows.len();
+        Ok(())
+    }
+
+    /// Write the footer; returns the number of rows written.
+    pub fn finish(self) -> anyhow::Result<usize> {
+        self.writer.close()?;
+        Ok(self.rows)
+    }
+}
+
+fn schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new(
+            "created_at",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("request_id", DataType::Utf8, true),
+        Field::new("subject_id", DataType::Utf8, true),
+        Field::new("decision", DataType::Utf8, false),
+        Field::new("decision_code", DataType::Utf8, false),
+        Field::new("policy_version", DataType::Utf8, false),
+        Field::new("rules_hit", DataType::Utf8, false),
+        Field::new("latency_ms", DataType::UInt32, false),
+        Field::new("evidence", DataType::Utf8, false),
+        Field::new("request", DataType::Utf8, false),
+    ]))
+}
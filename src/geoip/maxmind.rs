@@ -0,0 +1,183 @@
+//! [`IpIntelligence`] backed by MaxMind-format database files.
+
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use maxminddb::{geoip2, MaxMindDBError, Reader};
+use parking_lot::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use super::{IpInfo, IpIntelligence};
+
+/// Errors opening a GeoIP database.
+#[derive(Debug, thiserror::Error)]
+pub enum GeoIpError {
+    #[error("failed to read {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("invalid MaxMind database {}: {source}", path.display())]
+    Invalid {
+        path: PathBuf,
+        source: MaxMindDBError,
+    },
+}
+
+/// One database file and the modification time it was read at.
+struct Database {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    reader: Arc<Reader<Vec<u8>>>,
+}
+
+impl Database {
+    fn open(path: &Path) -> Result<Self, GeoIpError> {
+        let modified = modified(path)?;
+        let reader = Reader::open_readfile(path).map_err(|source| GeoIpError::Invalid {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(Database {
+            path: path.to_path_buf(),
+            modified,
+            reader: Arc::new(reader),
+        })
+    }
+}
+
+fn modified(path: &Path) -> Result<Option<SystemTime>, GeoIpError> {
+    let metadata = fs::metadata(path).map_err(|source| GeoIpError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(metadata.modified().ok())
+}
+
+/// MaxMind-format country and ASN databases.
+///
+/// Either database may be left out; its fields are then never known.
+/// [`MaxMindDatabase::reload`] reopens a file whose modification time
+/// changed, so updated databases are picked up without a restart. A file
+/// that fails to open keeps the previous copy in use.
+pub struct MaxMindDatabase {
+    country: Option<RwLock<Database>>,
+    asn: Option<RwLock<Database>>,
+}
+
+impl MaxMindDatabase {
+    /// Open the country database, the ASN database, or both.
+    pub fn open(country: Option<&Path>, asn: Option<&Path>) -> Result<Self, GeoIpError> {
+        Ok(MaxMindDatabase {
+            country: country.map(Database::open).transpose()?.map(RwLock::new),
+            asn: asn.map(Database::open).transpose()?.map(RwLock::new),
+        })
+    }
+
+    /// Reopen databases whose files changed, returning how many were
+    /// reopened.
+    pub fn reload(&self) -> Result<usize, GeoIpError> {
+        let mut reloaded = 0;
+        for database in self.country.iter().chain(self.asn.iter()) {
+            let path = database.read().path.clone();
+            if modified(&path)? == database.read().modified {
+                continue;
+            }
+            *database.write() = Database::open(&path)?;
+            reloaded += 1;
+        }
+        Ok(reloaded)
+    }
+
+    /// Check the files for changes every `interval` until aborted.
+    pub fn spawn_reload(self: Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match self.reload() {
+                    Ok(0) => {}
+                    Ok(reloaded) => info!(databases = reloaded, "Reloaded GeoIP databases"),
+                    Err(e) => warn!(error = %e, "Failed to reload GeoIP databases"),
+                }
+            }
+        })
+    }
+}
+
+impl fmt::Debug for MaxMindDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MaxMindDatabase")
+            .field(
+                "country",
+                &self.country.as_ref().map(|d| d.read().path.clone()),
+            )
+            .field("asn", &self.asn.as_ref().map(|d| d.read().path.clone()))
+            .finish()
+    }
+}
+
+impl IpIntelligence for MaxMindDatabase {
+    fn lookup(&self, ip: IpAddr) -> IpInfo {
+        let mut info = IpInfo::default();
+
+        if let Some(database) = &self.country {
+            let reader = database.read().reader.clone();
+            if let Ok(record) = reader.lookup::<geoip2::Country>(ip) {
+                // Fall back to where the network is registered for
+                // addresses without a location, such as anycast ranges
+                info.country = record
+                    .country
+                    .and_then(|c| c.iso_code)
+                    .or_else(|| record.registered_country.and_then(|c| c.iso_code))
+                    .map(|code| code.to_uppercase());
+            }
+        }
+
+        if let Some(database) = &self.asn {
+            let reader = database.read().reader.clone();
+            if let Ok(record) = reader.lookup::<geoip2::Asn>(ip) {
+                info.asn = record.autonomous_system_number;
+                info.as_org = record.autonomous_system_organization.map(str::to_string);
+            }
+        }
+
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_open_rejects_invalid_database() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(file, "not a database").unwrap();
+
+        let err = MaxMindDatabase::open(Some(file.path()), None).unwrap_err();
+        assert!(matches!(err, GeoIpError::Invalid { .. }));
+
+        let err = MaxMindDatabase::open(None, Some(Path::new("/nonexistent.mmdb"))).unwrap_err();
+        assert!(matches!(err, GeoIpError::Io { .. }));
+    }
+
+    #[test]
+    fn test_no_databases_knows_nothing() {
+        let database = MaxMindDatabase::open(None, None).unwrap();
+        assert_eq!(
+            database.lookup("203.0.113.7".parse().unwrap()),
+            IpInfo::default()
+        );
+        assert_eq!(database.reload().unwrap(), 0);
+    }
+}
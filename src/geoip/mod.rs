@@ -0,0 +1,40 @@
+//! Client IP geolocation and network lookups.
+//!
+//! The `ip_jurisdiction` rule screens the address a request came from
+//! rather than the `geo_iso` the caller reports, which is self-reported and
+//! trivially spoofed. Lookups go through [`IpIntelligence`];
+//! [`MaxMindDatabase`] reads MaxMind-format (`.mmdb`) country and ASN
+//! databases, such as GeoLite2-Country and GeoLite2-ASN, and reopens them
+//! when the files are replaced.
+
+#[cfg(feature = "server")]
+mod maxmind;
+
+use std::fmt;
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+pub use maxmind::{GeoIpError, MaxMindDatabase};
+
+/// What is known about an address; fields are None when the databases
+/// don't cover it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpInfo {
+    /// ISO 3166-1 alpha-2 country, uppercase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// Autonomous system number of the network announcing the address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asn: Option<u32>,
+    /// Organization operating the autonomous system
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub as_org: Option<String>,
+}
+
+/// Source of country and network information for addresses.
+pub trait IpIntelligence: Send + Sync + fmt::Debug {
+    /// Look up an address.
+    fn lookup(&self, ip: IpAddr) -> IpInfo;
+}
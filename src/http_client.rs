@@ -0,0 +1,26 @@
+//! HTTP client for calls to external providers.
+//!
+//! Servers are verified against the system's root certificates. Plain http
+//! URLs are still accepted so tests and local development can use them;
+//! configuration validation requires https where it matters.
+
+use axum::body::Body;
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+
+/// Client able to reach https and http URLs.
+pub type HttpsClient = Client<HttpsConnector<HttpConnector>, Body>;
+
+/// Create a client trusting the system's root certificates. Fails if none
+/// can be loaded.
+pub fn https_client() -> std::io::Result<HttpsClient> {
+    let connector = HttpsConnectorBuilder::new()
+        .with_native_roots()?
+        .https_or_http()
+        .enable_http1()
+        .enable_http2()
+        .build();
+    Ok(Client::builder(TokioExecutor::new()).build(connector))
+}
@@ -0,0 +1,136 @@
+//! [`IdentityProvider`] calling a provider's REST API.
+
+use std::fmt;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use serde::Deserialize;
+
+use super::IdentityProvider;
+use crate::domain::KycTier;
+use crate::http_client::{https_client, HttpsClient};
+
+/// Largest provider response body read.
+const MAX_RESPONSE_BYTES: usize = 64 * 1024;
+
+/// Provider answer for a known subject.
+#[derive(Debug, Deserialize)]
+struct KycStatus {
+    kyc_tier: KycTier,
+}
+
+/// Looks tiers up with `GET {base_url}/v1/subjects/{user_id}/kyc`.
+///
+/// The provider answers `{"kyc_tier": "L2"}`, or 404 for unknown subjects.
+/// Lookups taking longer than the timeout fail.
+pub struct HttpIdentityProvider {
+    client: HttpsClient,
+    base_url: String,
+    timeout: Duration,
+}
+
+impl HttpIdentityProvider {
+    /// Provider at `base_url`, e.g. `https://kyc.internal`. Fails if no
+    /// root certificates can be loaded.
+    pub fn new(base_url: &str, timeout: Duration) -> std::io::Result<Self> {
+        Ok(HttpIdentityProvider {
+            client: https_client()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            timeout,
+        })
+    }
+
+    async fn fetch(&self, user_id: &str) -> anyhow::Result<Option<KycTier>> {
+        let url = format!(
+            "{}/v1/subjects/{}/kyc",
+            self.base_url,
+            encode_path_segment(user_id)
+        );
+        let request = Request::get(url)
+            .header(header::ACCEPT, "application/json")
+            .body(Body::empty())?;
+
+        let response = self.client.request(request).await?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if status != StatusCode::OK {
+            anyhow::bail!("identity provider returned status {}", status);
+        }
+        let body =
+            axum::body::to_bytes(Body::new(response.into_body()), MAX_RESPONSE_BYTES).await?;
+        let kyc: KycStatus = serde_json::from_slice(&body)?;
+        Ok(Some(kyc.kyc_tier))
+    }
+}
+
+impl fmt::Debug for HttpIdentityProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpIdentityProvider")
+            .field("base_url", &self.base_url)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for HttpIdentityProvider {
+    async fn kyc_tier(&self, user_id: &str) -> anyhow::Result<Option<KycTier>> {
+        match tokio::time::timeout(self.timeout, self.fetch(user_id)).await {
+            Ok(result) => result,
+            Err(_) => anyhow::bail!("identity provider timed out after {:?}", self.timeout),
+        }
+    }
+}
+
+/// Percent-encode everything but unreserved characters, so user IDs can't
+/// change the request path.
+pub(crate) fn encode_path_segment(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Path;
+    use axum::routing::get;
+    use axum::{Json, Router};
+
+    #[tokio::test]
+    async fn test_http_provider() {
+        let app = Router::new().route(
+            "/v1/subjects/:user_id/kyc",
+            get(|Path(user_id): Path<String>| async move {
+                match user_id.as_str() {
+                    "U 1" => Ok(Json(serde_json::json!({ "kyc_tier": "l2" }))),
+                    "SLOW" => {
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        Err(StatusCode::NOT_FOUND)
+                    }
+                    _ => Err(StatusCode::NOT_FOUND),
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let provider =
+            HttpIdentityProvider::new(&format!("http://{}/", addr), Duration::from_millis(200))
+                .unwrap();
+        assert_eq!(provider.kyc_tier("U 1").await.unwrap(), Some(KycTier::L2));
+        assert_eq!(provider.kyc_tier("U2").await.unwrap(), None);
+        assert!(provider.kyc_tier("SLOW").await.is_err());
+    }
+}
@@ -0,0 +1,138 @@
+//! External identity provider lookups.
+//!
+//! The `kyc_verification` rule asks an external KYC provider for a
+//! subject's verified tier instead of trusting the tier sent by the caller.
+//! Providers implement [`IdentityProvider`]; [`HttpIdentityProvider`]
+//! calls a provider's REST API, and [`CachedIdentityProvider`] keeps
+//! answers for a while so repeat withdrawals don't each pay for a lookup.
+
+#[cfg(feature = "server")]
+mod http;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+
+use crate::domain::KycTier;
+
+#[cfg(feature = "server")]
+pub(crate) use http::encode_path_segment;
+#[cfg(feature = "server")]
+pub use http::HttpIdentityProvider;
+
+/// Most subjects whose answers are cached at once.
+const MAX_CACHED_SUBJECTS: usize = 100_000;
+
+/// Source of subjects' verified KYC tiers.
+#[async_trait]
+pub trait IdentityProvider: Send + Sync + fmt::Debug {
+    /// The subject's verified tier, or None if the provider does not know
+    /// the subject.
+    async fn kyc_tier(&self, user_id: &str) -> anyhow::Result<Option<KycTier>>;
+}
+
+/// Reuses a provider's answers for `ttl`.
+///
+/// Unknown subjects are cached like known ones; failed lookups are not, so
+/// the next request tries the provider again.
+#[derive(Debug)]
+pub struct CachedIdentityProvider<P> {
+    inner: P,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, Option<KycTier>)>>,
+}
+
+impl<P: IdentityProvider> CachedIdentityProvider<P> {
+    pub fn new(inner: P, ttl: Duration) -> Self {
+        CachedIdentityProvider {
+            inner,
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn cached(&self, user_id: &str) -> Option<Option<KycTier>> {
+        let entries = self.entries.lock();
+        let (fetched_at, tier) = entries.get(user_id)?;
+        (fetched_at.elapsed() < self.ttl).then(|| tier.clone())
+    }
+
+    fn store(&self, user_id: &str, tier: Option<KycTier>) {
+        let mut entries = self.entries.lock();
+        if entries.len() >= MAX_CACHED_SUBJECTS && !entries.contains_key(user_id) {
+            entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < self.ttl);
+            if entries.len() >= MAX_CACHED_SUBJECTS {
+                entries.clear();
+            }
+        }
+        entries.insert(user_id.to_string(), (Instant::now(), tier));
+    }
+}
+
+#[async_trait]
+impl<P: IdentityProvider> IdentityProvider for CachedIdentityProvider<P> {
+    async fn kyc_tier(&self, user_id: &str) -> anyhow::Result<Option<KycTier>> {
+        if let Some(tier) = self.cached(user_id) {
+            return Ok(tier);
+        }
+        let tier = self.inner.kyc_tier(user_id).await?;
+        self.store(user_id, tier.clone());
+        Ok(tier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Debug, Default)]
+    struct CountingProvider {
+        calls: AtomicU32,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl IdentityProvider for CountingProvider {
+        async fn kyc_tier(&self, user_id: &str) -> anyhow::Result<Option<KycTier>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                anyhow::bail!("provider down");
+            }
+            Ok((user_id == "U1").then_some(KycTier::L2))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_reuses_answers() {
+        let cached =
+            CachedIdentityProvider::new(CountingProvider::default(), Duration::from_secs(60));
+
+        assert_eq!(cached.kyc_tier("U1").await.unwrap(), Some(KycTier::L2));
+        assert_eq!(cached.kyc_tier("U1").await.unwrap(), Some(KycTier::L2));
+        assert_eq!(cached.kyc_tier("U2").await.unwrap(), None);
+        assert_eq!(cached.kyc_tier("U2").await.unwrap(), None);
+        assert_eq!(cached.inner.calls.load(Ordering::SeqCst), 2);
+
+        let expired = CachedIdentityProvider::new(CountingProvider::default(), Duration::ZERO);
+        expired.kyc_tier("U1").await.unwrap();
+        expired.kyc_tier("U1").await.unwrap();
+        assert_eq!(expired.inner.calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_skips_failures() {
+        let provider = CountingProvider {
+            fail: true,
+            ..Default::default()
+        };
+        let cached = CachedIdentityProvider::new(provider, Duration::from_secs(60));
+
+        assert!(cached.kyc_tier("U1").await.is_err());
+        assert!(cached.kyc_tier("U1").await.is_err());
+        assert_eq!(cached.inner.calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,204 @@
+//! Synchronous, storage-free evaluation of the inline rules.
+//!
+//! The decision endpoint runs its inline phase through [`evaluate`], so an
+//! embedder using [`InlineEngine`] (an edge worker pre-validating requests,
+//! or a client-side build) gets exactly the server's OFAC, jurisdiction and
+//! KYC-cap behaviour for the same policy and sanctions list. Nothing here is
+//! async or touches storage or the runtime, and it builds without the
+//! `server` feature, e.g. for `wasm32-unknown-unknown`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, Policy, TxEvent};
+use crate::rules::{FeatureGates, InlineRule, RuleIndex, RuleSet};
+
+/// Outcome of the inline rules for one transaction.
+#[derive(Debug, Clone)]
+pub struct InlineVerdict {
+    /// Most severe decision of the rules that hit
+    pub decision: Decision,
+    /// Evidence of every hit, in rule order
+    pub evidence: Vec<Evidence>,
+}
+
+/// Evaluate `rules` in order, skipping those for which `skip` returns true.
+///
+/// `observe` sees every rule ID with its result, or `None` when skipped.
+pub fn evaluate(
+    rules: &[Arc<dyn InlineRule>],
+    event: &TxEvent,
+    skip: impl Fn(&str) -> bool,
+    mut observe: impl FnMut(&str, Option<&RuleResult>),
+) -> InlineVerdict {
+    let mut decision = Decision::Allow;
+    let mut evidence = Vec::new();
+
+    for rule in rules {
+        if skip(rule.id()) {
+            observe(rule.id(), None);
+            continue;
+        }
+        let result = rule.evaluate(event);
+        observe(rule.id(), Some(&result));
+        if result.hit {
+            if result.decision > decision {
+                decision = result.decision;
+            }
+            if let Some(ev) = result.evidence {
+                evidence.push(ev);
+            }
+        }
+    }
+
+    InlineVerdict { decision, evidence }
+}
+
+/// Inline rules of a policy, evaluated without a server.
+#[derive(Debug, Clone)]
+pub struct InlineEngine {
+    rules: Vec<Arc<dyn InlineRule>>,
+    index: RuleIndex,
+    policy_version: String,
+    disabled: HashSet<String>,
+    features: FeatureGates,
+}
+
+impl InlineEngine {
+    /// Build the inline rules of `policy` over a sanctions list.
+    pub fn new(policy: &Policy, sanctions: HashSet<String>) -> Self {
+        InlineEngine::from_ruleset(&RuleSet::from_policy(policy, sanctions))
+    }
+
+    /// Share the inline rules of an already built rule set.
+    pub fn from_ruleset(ruleset: &RuleSet) -> Self {
+        InlineEngine {
+            rules: ruleset.inline.clone(),
+            index: ruleset.dispatch.inline.clone(),
+            policy_version: ruleset.policy_version.clone(),
+            disabled: HashSet::new(),
+            features: ruleset.features.clone(),
+        }
+    }
+
+    /// Skip the given rules, mirroring the server's kill-switches.
+    pub fn with_disabled(mut self, rule_ids: impl IntoIterator<Item = String>) -> Self {
+        self.disabled.extend(rule_ids);
+        self
+    }
+
+    /// Version of the policy the rules were built from.
+    pub fn policy_version(&self) -> &str {
+        &self.policy_version
+    }
+
+    /// Evaluate a transaction. Experimental rules run only for the
+    /// features the event enables.
+    pub fn evaluate(&self, event: &TxEvent) -> InlineVerdict {
+        evaluate(
+            &self.index.select(&self.rules, event),
+            event,
+            |id| self.disabled.contains(id) || !self.features.allows(id, &event.features),
+            |_, _| {},
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use rust_decimal::Decimal;
+    use smallvec::smallvec;
+
+    fn test_policy() -> Policy {
+        serde_yaml::from_str(
+            r#"
+policy_version: "edge-1"
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+  - id: R2_JURISDICTION
+    type: jurisdiction_block
+    action: REJECT_FATAL
+    blocked_countries: ["IR"]
+"#,
+        )
+        .unwrap()
+    }
+
+    fn test_event(address: &str, geo: &str) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![Address::new(address)],
+            geo_iso: CountryCode::new(geo),
+            kyc_tier: KycTier::L1,
+        };
+        TxEvent::new(
+            subject,
+            Asset::new("USDC"),
+            Decimal::new(100, 0),
+            Direction::Outbound,
+        )
+    }
+
+    #[test]
+    fn test_engine_evaluates_inline_rules() {
+        let engine = InlineEngine::new(&test_policy(), HashSet::from(["0xdead".to_string()]));
+        assert_eq!(engine.policy_version(), "edge-1");
+
+        let verdict = engine.evaluate(&test_event("0xclean", "US"));
+        assert_eq!(verdict.decision, Decision::Allow);
+        assert!(verdict.evidence.is_empty());
+
+        let verdict = engine.evaluate(&test_event("0xDEAD", "IR"));
+        assert_eq!(verdict.decision, Decision::RejectFatal);
+        let rules: Vec<_> = verdict
+            .evidence
+            .iter()
+            .map(|e| e.rule_id.as_str())
+            .collect();
+        assert_eq!(rules, vec!["R1_OFAC", "R2_JURISDICTION"]);
+    }
+
+    #[test]
+    fn test_engine_gates_experimental_rules() {
+        let mut policy = test_policy();
+        policy.rules[1].experimental = Some("jurisdiction_v2".to_string());
+        let engine = InlineEngine::new(&policy, HashSet::new());
+
+        let mut event = test_event("0xclean", "IR");
+        assert_eq!(engine.evaluate(&event).decision, Decision::Allow);
+
+        event.features = vec!["jurisdiction_v2".to_string()];
+        assert_eq!(engine.evaluate(&event).decision, Decision::RejectFatal);
+    }
+
+    #[test]
+    fn test_engine_skips_disabled_rules() {
+        let engine = InlineEngine::new(&test_policy(), HashSet::from(["0xdead".to_string()]))
+            .with_disabled(["R1_OFAC".to_string()]);
+
+        let verdict = engine.evaluate(&test_event("0xdead", "US"));
+        assert_eq!(verdict.decision, Decision::Allow);
+
+        let mut seen = Vec::new();
+        evaluate(
+            &engine.rules,
+            &test_event("0xdead", "US"),
+            |id| id == "R1_OFAC",
+            |id, result| seen.push((id.to_string(), result.is_some())),
+        );
+        assert_eq!(
+            seen,
+            vec![
+                ("R1_OFAC".to_string(), false),
+                ("R2_JURISDICTION".to_string(), true)
+            ]
+        );
+    }
+}
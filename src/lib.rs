@@ -1,11 +1,43 @@
+#[cfg(feature = "server")]
 pub mod api;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(feature = "server")]
 pub mod config;
 pub mod domain;
+#[cfg(feature = "server")]
+pub mod embedded;
+#[cfg(feature = "server")]
+pub mod export;
+pub mod geoip;
+#[cfg(feature = "server")]
+pub mod http_client;
+pub mod identity;
+pub mod inline_engine;
+#[cfg(feature = "server")]
+pub mod lists;
+#[cfg(feature = "server")]
+pub mod loadtest;
+#[cfg(feature = "server")]
 pub mod observability;
+#[cfg(feature = "server")]
+pub mod outbox;
+#[cfg(feature = "server")]
 pub mod policy;
+#[cfg(feature = "server")]
+pub mod probe;
+#[cfg(feature = "server")]
+pub mod retention;
 pub mod rules;
+#[cfg(feature = "server")]
+pub mod scenarios;
 pub mod storage;
+#[cfg(feature = "server")]
+pub mod sweep;
 
+#[cfg(feature = "server")]
 pub use config::Config;
 pub use domain::{Decision, Evidence, TxEvent};
 pub use rules::{InlineRule, RuleSet, StreamingRule};
@@ -0,0 +1,489 @@
+//! Bulk import and export of subject KYC overrides and the internal
+//! blocklist, for compliance teams managing them in spreadsheets.
+//!
+//! Files are CSV with a header row, or a JSON array of objects with the
+//! same fields:
+//!
+//! ```text
+//! user_id,kyc_tier            address,reason
+//! U123,L2                     0xdead...,fraud ring 2026-114
+//! ```
+//!
+//! Every row is validated before anything is written, and nothing is
+//! written if any row fails, so a file lands whole or not at all. A dry run
+//! stops after validation. Rows are numbered from 1, not counting the CSV
+//! header, to match the spreadsheet they came from.
+
+use chrono::Utc;
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use thiserror::Error;
+
+use crate::domain::subject::{KycTier, TierRanking};
+use crate::export::csv_line;
+use crate::rules::sanctions::normalize_entry;
+use crate::storage::{BlocklistEntry, KycOverride, Storage};
+
+/// Bulk list file format.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ListFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ListFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ListFormat::Csv => "text/csv; charset=utf-8",
+            ListFormat::Json => "application/json",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ListFormat::Csv => "csv",
+            ListFormat::Json => "json",
+        }
+    }
+}
+
+/// Admin-managed list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListKind {
+    /// Subject KYC tiers set out-of-band
+    Overrides,
+    /// Internal address blocklist
+    Blocklist,
+}
+
+/// Errors that can occur during a bulk import.
+#[derive(Error, Debug)]
+pub enum ImportError {
+    /// The file as a whole could not be read
+    #[error("Malformed file: {0}")]
+    Malformed(String),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] anyhow::Error),
+}
+
+/// A row that failed validation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RowError {
+    pub row: usize,
+    pub error: String,
+}
+
+/// Outcome of a bulk import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    /// Rows in the file
+    pub rows: usize,
+    /// Rows written; zero for a dry run or when any row failed
+    pub applied: usize,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportReport {
+    fn new(dry_run: bool, rows: usize) -> Self {
+        ImportReport {
+            dry_run,
+            rows,
+            applied: 0,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Whether nothing can be written because rows failed validation.
+    pub fn is_rejected(&self) -> bool {
+        !self.errors.is_empty()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OverrideRow {
+    user_id: String,
+    kyc_tier: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlocklistRow {
+    address: String,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Set subjects' KYC tiers from a file.
+///
+/// Subjects must already exist, each may appear once, and tiers must be
+/// in `tiers` when given (normally the active policy's ranking).
+pub async fn import_overrides(
+    storage: &dyn Storage,
+    format: ListFormat,
+    body: &str,
+    tiers: Option<&TierRanking>,
+    dry_run: bool,
+) -> Result<ImportReport, ImportError> {
+    let rows = parse_rows::<OverrideRow>(format, body)?;
+    let mut report = ImportReport::new(dry_run, rows.len());
+    let mut seen = HashSet::new();
+    let mut overrides = Vec::with_capacity(rows.len());
+
+    for (row, parsed) in rows.into_iter().enumerate().map(|(i, r)| (i + 1, r)) {
+        let result = match parsed {
+            Ok(r) => validate_override(storage, r, tiers, &mut seen).await?,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(o) => overrides.push(o),
+            Err(error) => report.errors.push(RowError { row, error }),
+        }
+    }
+
+    if dry_run || report.is_rejected() {
+        return Ok(report);
+    }
+
+    for o in &overrides {
+        if !storage.set_kyc_tier(&o.user_id, &o.kyc_tier).await? {
+            return Err(anyhow::anyhow!("Subject removed during import: {}", o.user_id).into());
+        }
+        report.applied += 1;
+    }
+    Ok(report)
+}
+
+async fn validate_override(
+    storage: &dyn Storage,
+    row: OverrideRow,
+    tiers: Option<&TierRanking>,
+    seen: &mut HashSet<String>,
+) -> anyhow::Result<Result<KycOverride, String>> {
+    let user_id = row.user_id.trim().to_string();
+    if user_id.is_empty() {
+        return Ok(Err("user_id is empty".to_string()));
+    }
+    let Some(kyc_tier) = KycTier::from_str(&row.kyc_tier) else {
+        return Ok(Err("kyc_tier is empty".to_string()));
+    };
+    if let Some(tiers) = tiers {
+        if tiers.rank(&kyc_tier).is_none() {
+            return Ok(Err(format!("KYC tier {} is not in the policy", kyc_tier)));
+        }
+    }
+    if !seen.insert(user_id.clone()) {
+        return Ok(Err(format!("Duplicate user_id: {}", user_id)));
+    }
+    if storage.get_subject_by_user_id(&user_id).await?.is_none() {
+        return Ok(Err(format!("Unknown subject: {}", user_id)));
+    }
+    Ok(Ok(KycOverride { user_id, kyc_tier }))
+}
+
+/// Add addresses to the internal blocklist from a file, attributed to
+/// `actor`. With `replace`, the file becomes the whole blocklist.
+pub async fn import_blocklist(
+    storage: &dyn Storage,
+    format: ListFormat,
+    body: &str,
+    actor: &str,
+    replace: bool,
+    dry_run: bool,
+) -> Result<ImportReport, ImportError> {
+    let rows = parse_rows::<BlocklistRow>(format, body)?;
+    let mut report = ImportReport::new(dry_run, rows.len());
+    let mut seen = HashSet::new();
+    let mut entries = Vec::with_capacity(rows.len());
+    let now = Utc::now();
+
+    for (row, parsed) in rows.into_iter().enumerate().map(|(i, r)| (i + 1, r)) {
+        let result = parsed.and_then(|r| {
+            let address = normalize_entry(&r.address);
+            if address.is_empty() {
+                return Err("address is empty".to_string());
+            }
+            if address.contains(' ') {
+                return Err(format!("address contains whitespace: {}", r.address.trim()));
+            }
+            if !seen.insert(address.clone()) {
+                return Err(format!("Duplicate address: {}", address));
+            }
+            Ok(BlocklistEntry {
+                address,
+                reason: r
+                    .reason
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty()),
+                added_at: now,
+                actor: actor.to_string(),
+            })
+        });
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(error) => report.errors.push(RowError { row, error }),
+        }
+    }
+
+    if dry_run || report.is_rejected() {
+        return Ok(report);
+    }
+
+    storage.save_blocklist(&entries, replace).await?;
+    report.applied = entries.len();
+    Ok(report)
+}
+
+/// Subject KYC overrides as a file.
+pub async fn export_overrides(storage: &dyn Storage, format: ListFormat) -> anyhow::Result<String> {
+    let overrides = storage.get_kyc_overrides().await?;
+    Ok(match format {
+        ListFormat::Json => serde_json::to_string_pretty(&overrides)?,
+        ListFormat::Csv => {
+            let mut out = csv_line(["user_id", "kyc_tier"].into_iter());
+            for o in &overrides {
+                out.push_str(&csv_line(
+                    [o.user_id.as_str(), o.kyc_tier.as_str()].into_iter(),
+                ));
+            }
+            out
+        }
+    })
+}
+
+/// The internal blocklist as a file.
+pub async fn export_blocklist(storage: &dyn Storage, format: ListFormat) -> anyhow::Result<String> {
+    let entries = storage.get_blocklist().await?;
+    Ok(match format {
+        ListFormat::Json => serde_json::to_string_pretty(&entries)?,
+        ListFormat::Csv => {
+            let mut out = csv_line(["address", "reason", "added_at", "actor"].into_iter());
+            for e in &entries {
+                let added_at = e.added_at.to_rfc3339();
+                let fields = [
+                    e.address.as_str(),
+                    e.reason.as_deref().unwrap_or(""),
+                    added_at.as_str(),
+                    e.actor.as_str(),
+                ];
+                out.push_str(&csv_line(fields.into_iter()));
+            }
+            out
+        }
+    })
+}
+
+/// Parse a file into rows, each deserialized on its own so one bad row
+/// does not hide the others. CSV cells are strings; blank cells are absent.
+fn parse_rows<T: DeserializeOwned>(
+    format: ListFormat,
+    body: &str,
+) -> Result<Vec<Result<T, String>>, ImportError> {
+    let objects: Vec<Value> = match format {
+        ListFormat::Json => match serde_json::from_str(body) {
+            Ok(Value::Array(items)) => items,
+            Ok(_) => return Err(ImportError::Malformed("expected a JSON array".to_string())),
+            Err(e) => return Err(ImportError::Malformed(e.to_string())),
+        },
+        ListFormat::Csv => {
+            let mut records = parse_csv(body).map_err(ImportError::Malformed)?.into_iter();
+            let Some(header) = records.next() else {
+                return Ok(Vec::new());
+            };
+            let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+            records
+                .map(|record| {
+                    let object: Map<String, Value> = header
+                        .iter()
+                        .zip(record)
+                        .filter(|(_, cell)| !cell.trim().is_empty())
+                        .map(|(name, cell)| (name.clone(), Value::String(cell)))
+                        .collect();
+                    Value::Object(object)
+                })
+                .collect()
+        }
+    };
+
+    Ok(objects
+        .into_iter()
+        .map(|object| serde_json::from_value(object).map_err(|e| e.to_string()))
+        .collect())
+}
+
+/// Split CSV text into records of fields. Quoted fields may hold commas,
+/// line breaks and doubled quotes. Blank lines and a leading byte order
+/// mark, as spreadsheets write, are skipped.
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                line += 1;
+                record.push(std::mem::take(&mut field));
+                let done = std::mem::take(&mut record);
+                if done.iter().any(|f| !f.is_empty()) {
+                    records.push(done);
+                }
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("unterminated quoted field at line {}", line));
+    }
+    record.push(field);
+    if record.iter().any(|f| !f.is_empty()) {
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::subject::{AccountId, CountryCode, UserId};
+    use crate::domain::Subject;
+    use crate::storage::{MockStorage, StorageRead};
+
+    fn subject(user_id: &str) -> Subject {
+        Subject {
+            user_id: UserId::new(user_id),
+            account_id: AccountId::new("A1"),
+            addresses: Default::default(),
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L0,
+        }
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "\u{feff}address,reason\r\n0xabc,\"fraud, ring \"\"A\"\"\"\r\n\r\n0xdef,\"multi\nline\"\n";
+        let records = parse_csv(text).unwrap();
+        assert_eq!(
+            records,
+            vec![
+                vec!["address", "reason"],
+                vec!["0xabc", "fraud, ring \"A\""],
+                vec!["0xdef", "multi\nline"],
+            ]
+        );
+        assert!(parse_csv("a,\"b\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_overrides_reports_bad_rows() {
+        let storage = MockStorage::new();
+        storage.add_subject(subject("U1"));
+        storage.add_subject(subject("U2"));
+        let tiers = TierRanking::default();
+
+        let csv = "user_id,kyc_tier\nU1,l2\nU9,L1\nU2,L7\nU1,L1\nU2,\n";
+        let report = import_overrides(&storage, ListFormat::Csv, csv, Some(&tiers), false)
+            .await
+            .unwrap();
+        assert_eq!(report.rows, 5);
+        assert_eq!(report.applied, 0);
+        let rows: Vec<usize> = report.errors.iter().map(|e| e.row).collect();
+        assert_eq!(rows, vec![2, 3, 4, 5]);
+        assert!(report.errors[0].error.contains("Unknown subject"));
+        assert!(report.errors[3].error.contains("kyc_tier"));
+        assert!(storage.get_kyc_overrides().await.unwrap().is_empty());
+
+        let json = r#"[{"user_id": "U1", "kyc_tier": "L2"}, {"user_id": "U2", "kyc_tier": "L1"}]"#;
+        let report = import_overrides(&storage, ListFormat::Json, json, Some(&tiers), true)
+            .await
+            .unwrap();
+        assert!(report.errors.is_empty());
+        assert_eq!(report.applied, 0);
+
+        let report = import_overrides(&storage, ListFormat::Json, json, Some(&tiers), false)
+            .await
+            .unwrap();
+        assert_eq!(report.applied, 2);
+
+        let exported = export_overrides(&storage, ListFormat::Csv).await.unwrap();
+        assert_eq!(exported, "user_id,kyc_tier\nU1,L2\nU2,L1\n");
+    }
+
+    #[tokio::test]
+    async fn test_import_blocklist() {
+        let storage = MockStorage::new();
+        let csv = "address,reason\n0xAAA,fraud\n0xbbb,\n";
+        let report = import_blocklist(&storage, ListFormat::Csv, csv, "ops", false, false)
+            .await
+            .unwrap();
+        assert_eq!(report.applied, 2);
+
+        let entries = storage.get_blocklist().await.unwrap();
+        assert_eq!(entries[0].address, "0xaaa");
+        assert_eq!(entries[0].reason.as_deref(), Some("fraud"));
+        assert_eq!(entries[1].reason, None);
+        assert_eq!(entries[1].actor, "ops");
+
+        let report = import_blocklist(
+            &storage,
+            ListFormat::Csv,
+            "address\n0xccc\n0xCCC\n0x d\n",
+            "ops",
+            false,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.errors.len(), 2);
+        assert_eq!(storage.get_blocklist().await.unwrap().len(), 2);
+
+        import_blocklist(
+            &storage,
+            ListFormat::Csv,
+            "address\n0xccc\n",
+            "ops",
+            true,
+            false,
+        )
+        .await
+        .unwrap();
+        let exported = export_blocklist(&storage, ListFormat::Json).await.unwrap();
+        let exported: Vec<BlocklistEntry> = serde_json::from_str(&exported).unwrap();
+        assert_eq!(exported.len(), 1);
+        assert_eq!(exported[0].address, "0xccc");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_file() {
+        let storage = MockStorage::new();
+        let result = import_blocklist(&storage, ListFormat::Json, "{}", "ops", false, false).await;
+        assert!(matches!(result, Err(ImportError::Malformed(_))));
+    }
+}
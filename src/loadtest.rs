@@ -0,0 +1,425 @@
+//! In-process load generation.
+//!
+//! `riskr loadtest` drives the decision pipeline directly, without HTTP or
+//! JSON, against in-memory storage, so the numbers measure the engine
+//! rather than the network or a database. Workers generate synthetic
+//! transactions for a fixed population of users at a target rate and
+//! record each decision's latency.
+//!
+//! Allocation counts need [`CountingAllocator`] installed as the global
+//! allocator, which the binary does when built with the `alloc-stats`
+//! feature; otherwise they are left out of the report.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use clap::ValueEnum;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use smallvec::smallvec;
+use tokio::time::MissedTickBehavior;
+
+use crate::domain::event::{Asset, Destination, Direction};
+use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+use crate::domain::{Decision, TxEvent};
+use crate::inline_engine;
+use crate::rules::{evaluate_streaming, RuleSet};
+use crate::storage::{
+    DecisionRecord, Storage, StorageRead, TransactionRecord, WindowCache, WindowSpec,
+};
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+static ALLOCATED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// System allocator that counts allocations for load test reports.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size() as u64, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(new_size as u64, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+/// Which of the policy's rules each decision runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleMix {
+    /// Stateless rules only
+    Inline,
+    /// Stateful rules only, with the subject upserted and the outcome
+    /// recorded
+    Streaming,
+    /// Every rule, as the decision endpoint runs them
+    #[default]
+    All,
+}
+
+impl RuleMix {
+    fn inline(self) -> bool {
+        self != RuleMix::Streaming
+    }
+
+    fn streaming(self) -> bool {
+        self != RuleMix::Inline
+    }
+}
+
+/// Shape of the generated load.
+#[derive(Debug, Clone)]
+pub struct LoadTestOptions {
+    /// Target decisions per second across all workers; 0 runs as fast as
+    /// possible
+    pub tps: u32,
+    pub duration: Duration,
+    /// Distinct users transactions are spread over
+    pub users: u32,
+    /// Concurrent workers
+    pub workers: u32,
+    pub mix: RuleMix,
+}
+
+/// Decision latency distribution in microseconds.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencyDistribution {
+    pub mean_us: f64,
+    pub p50_us: u64,
+    pub p90_us: u64,
+    pub p99_us: u64,
+    pub p999_us: u64,
+    pub max_us: u64,
+}
+
+impl LatencyDistribution {
+    fn from_samples(mut samples: Vec<u64>) -> Self {
+        if samples.is_empty() {
+            return LatencyDistribution::default();
+        }
+        samples.sort_unstable();
+        let rank = |p: f64| samples[((samples.len() as f64 * p).ceil() as usize).max(1) - 1];
+        LatencyDistribution {
+            mean_us: samples.iter().sum::<u64>() as f64 / samples.len() as f64,
+            p50_us: rank(0.5),
+            p90_us: rank(0.9),
+            p99_us: rank(0.99),
+            p999_us: rank(0.999),
+            max_us: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Heap allocations made while the load ran, across every thread.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AllocationStats {
+    pub allocations: u64,
+    pub bytes: u64,
+    pub allocations_per_decision: f64,
+    pub bytes_per_decision: f64,
+}
+
+/// Outcome of a load test.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadTestReport {
+    pub policy_version: String,
+    pub mix: RuleMix,
+    pub workers: u32,
+    pub users: u32,
+    /// Zero when unthrottled
+    pub target_tps: u32,
+    pub achieved_tps: f64,
+    pub elapsed_secs: f64,
+    pub decisions: u64,
+    pub outcomes: BTreeMap<Decision, u64>,
+    pub latency: LatencyDistribution,
+    /// Unset unless built with the `alloc-stats` feature
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<AllocationStats>,
+}
+
+/// Small deterministic generator, so runs with the same options send the
+/// same transactions.
+struct Xorshift(u64);
+
+impl Xorshift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n.max(1)
+    }
+}
+
+const COUNTRIES: [&str; 6] = ["US", "GB", "DE", "SG", "BR", "IN"];
+const TIERS: [KycTier; 3] = [KycTier::L0, KycTier::L1, KycTier::L2];
+const ASSETS: [&str; 3] = ["USDC", "ETH", "BTC"];
+
+/// A synthetic transaction: mostly small withdrawals, with a tail of large
+/// ones so volume and structuring rules have something to find.
+fn synthetic_event(rng: &mut Xorshift, users: u32) -> TxEvent {
+    let user = rng.below(users as u64);
+    let subject = Subject {
+        user_id: UserId::new(format!("LT-U{user}")),
+        account_id: AccountId::new(format!("LT-A{user}")),
+        addresses: smallvec![Address::new(format!("0x{user:040x}"))],
+        geo_iso: CountryCode::new(COUNTRIES[(user % COUNTRIES.len() as u64) as usize]),
+        kyc_tier: TIERS[(user % TIERS.len() as u64) as usize].clone(),
+    };
+
+    let usd_cents = match rng.below(100) {
+        0..=79 => 1_000 + rng.below(200_000),
+        80..=97 => 200_000 + rng.below(2_000_000),
+        _ => 2_000_000 + rng.below(10_000_000),
+    };
+    let direction = if rng.below(10) < 7 {
+        Direction::Outbound
+    } else {
+        Direction::Inbound
+    };
+
+    let mut event = TxEvent::new(
+        subject,
+        Asset::new(ASSETS[rng.below(ASSETS.len() as u64) as usize]),
+        Decimal::new(usd_cents as i64, 2),
+        direction,
+    );
+    event.tx_type = match direction {
+        Direction::Outbound => "withdraw".to_string(),
+        Direction::Inbound => "deposit".to_string(),
+    };
+    if direction == Direction::Outbound {
+        let dest = rng.below(1000);
+        event.destination = Some(Destination::new(format!("0x{dest:040x}"), None));
+    }
+    event
+}
+
+/// Decide one transaction the way the decision endpoint does, without
+/// kill-switches, experimental gating or the blocklist.
+pub async fn decide(
+    ruleset: &RuleSet,
+    storage: &Arc<dyn Storage>,
+    event: &TxEvent,
+    mix: RuleMix,
+) -> Decision {
+    let (mut decision, mut evidence) = if mix.inline() {
+        let verdict =
+            inline_engine::evaluate(&ruleset.inline_for(event), event, |_| false, |_, _| {});
+        (verdict.decision, verdict.evidence)
+    } else {
+        (Decision::Allow, Vec::new())
+    };
+    if decision.is_fatal() || !mix.streaming() {
+        return decision;
+    }
+
+    let Ok(subject_id) = storage.upsert_subject(&event.subject).await else {
+        return decision;
+    };
+    let windows = WindowCache::new(storage.clone());
+    let rules = ruleset.streaming_for(event);
+    let specs: Vec<WindowSpec> = rules.iter().flat_map(|rule| rule.windows(event)).collect();
+    if !specs.is_empty() {
+        let _ = windows.prefetch(subject_id, &specs).await;
+    }
+    let windows: Arc<dyn StorageRead> = Arc::new(windows);
+    let evaluations = evaluate_streaming(
+        &rules,
+        &Arc::new(event.clone()),
+        subject_id,
+        &windows,
+        |_| true,
+    )
+    .await;
+    for result in evaluations.into_iter().filter_map(|e| e.result.ok()) {
+        if result.hit {
+            decision = decision.max(result.decision);
+            evidence.extend(result.evidence);
+        }
+    }
+    ruleset
+        .mitigations
+        .apply(event, &mut decision, &mut evidence);
+
+    let tx = TransactionRecord {
+        subject_id,
+        tx_type: format!("{:?}", event.direction),
+        asset: event.asset.0.clone(),
+        amount: Decimal::ZERO,
+        usd_value: event.usd_value,
+        dest_address: event
+            .destination
+            .as_ref()
+            .map(|d| d.address.as_str().to_string()),
+        counterparty_geo: None,
+        account_id: Some(event.subject.account_id.as_str().to_string()),
+    };
+    let record = DecisionRecord {
+        subject_id: Some(subject_id),
+        request_id: None,
+        request: serde_json::Value::Null,
+        decision,
+        decision_code: evidence
+            .first()
+            .map(|e| e.rule_id.clone())
+            .unwrap_or_else(|| "OK".to_string()),
+        policy_version: ruleset.policy_version.clone(),
+        evidence,
+        latency_ms: 0,
+    };
+    let _ = storage.record_outcome(&tx, &record).await;
+
+    decision
+}
+
+/// Run the load and report what it measured.
+pub async fn run(
+    ruleset: Arc<RuleSet>,
+    storage: Arc<dyn Storage>,
+    options: &LoadTestOptions,
+) -> LoadTestReport {
+    let workers = options.workers.max(1);
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes_before = ALLOCATED_BYTES.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let deadline = start + options.duration;
+
+    let mut handles = Vec::new();
+    for worker in 0..workers {
+        let ruleset = ruleset.clone();
+        let storage = storage.clone();
+        let options = options.clone();
+        handles.push(tokio::spawn(async move {
+            let mut rng = Xorshift(0x9E37_79B9_7F4A_7C15 ^ (worker as u64 + 1));
+            let mut ticker = (options.tps > 0).then(|| {
+                let period = Duration::from_secs_f64(workers as f64 / options.tps as f64);
+                let mut ticker = tokio::time::interval(period);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Burst);
+                ticker
+            });
+            let mut latencies = Vec::new();
+            let mut outcomes = [0u64; Decision::ALL.len()];
+
+            while Instant::now() < deadline {
+                if let Some(ticker) = ticker.as_mut() {
+                    ticker.tick().await;
+                }
+                let event = synthetic_event(&mut rng, options.users);
+                let decided = Instant::now();
+                let decision = decide(&ruleset, &storage, &event, options.mix).await;
+                latencies.push(decided.elapsed().as_micros() as u64);
+                outcomes[decision as usize] += 1;
+                // Unthrottled workers still let others run on a shared thread
+                if ticker.is_none() {
+                    tokio::task::yield_now().await;
+                }
+            }
+            (latencies, outcomes)
+        }));
+    }
+
+    let mut latencies = Vec::new();
+    let mut outcomes = [0u64; Decision::ALL.len()];
+    for handle in handles {
+        if let Ok((worker_latencies, worker_outcomes)) = handle.await {
+            latencies.extend(worker_latencies);
+            for (total, count) in outcomes.iter_mut().zip(worker_outcomes) {
+                *total += count;
+            }
+        }
+    }
+    let elapsed = start.elapsed().as_secs_f64();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    let bytes = ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes_before;
+
+    let decisions = latencies.len() as u64;
+    LoadTestReport {
+        policy_version: ruleset.policy_version.clone(),
+        mix: options.mix,
+        workers,
+        users: options.users,
+        target_tps: options.tps,
+        achieved_tps: decisions as f64 / elapsed,
+        elapsed_secs: elapsed,
+        decisions,
+        outcomes: Decision::ALL.into_iter().zip(outcomes).collect(),
+        latency: LatencyDistribution::from_samples(latencies),
+        allocations: (allocations > 0 && decisions > 0).then(|| AllocationStats {
+            allocations,
+            bytes,
+            allocations_per_decision: allocations as f64 / decisions as f64,
+            bytes_per_decision: bytes as f64 / decisions as f64,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Policy;
+    use crate::storage::MockStorage;
+
+    #[test]
+    fn test_latency_distribution() {
+        let dist = LatencyDistribution::from_samples((1..=1000).rev().collect());
+        assert_eq!(dist.p50_us, 500);
+        assert_eq!(dist.p99_us, 990);
+        assert_eq!(dist.p999_us, 999);
+        assert_eq!(dist.max_us, 1000);
+        assert_eq!(dist.mean_us, 500.5);
+        assert_eq!(
+            LatencyDistribution::from_samples(Vec::new()),
+            LatencyDistribution::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_decisions() {
+        let policy: Policy = serde_yaml::from_str(
+            r#"
+policy_version: "lt-1"
+params:
+  daily_volume_limit_usd: 50000
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+  - id: R4_DAILY_USD
+    type: daily_usd_volume
+    action: HOLD_AUTO
+"#,
+        )
+        .unwrap();
+        let ruleset = Arc::new(RuleSet::from_policy(&policy, Default::default()));
+        let options = LoadTestOptions {
+            tps: 0,
+            duration: Duration::from_millis(50),
+            users: 10,
+            workers: 2,
+            mix: RuleMix::All,
+        };
+
+        let report = run(ruleset, Arc::new(MockStorage::new()), &options).await;
+        assert!(report.decisions > 0);
+        assert_eq!(report.outcomes.values().sum::<u64>(), report.decisions);
+        assert_eq!(report.policy_version, "lt-1");
+        assert!(report.latency.max_us >= report.latency.p50_us);
+    }
+}
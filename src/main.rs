@@ -1,16 +1,44 @@
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
 
 use clap::Parser;
 use tokio::signal;
-use tracing::info;
+use tokio::sync::watch;
+use tracing::{info, warn};
 
+use riskr::api::auth::{AdminAuth, ApiKeys};
+use riskr::api::oidc::OidcVerifier;
 use riskr::api::routes::{create_router, AppState};
-use riskr::config::Config;
-use riskr::observability::init_tracing;
-use riskr::policy::{PolicyLoader, PolicyWatcher};
-use riskr::storage::{MockStorage, PostgresStorage, Storage};
+#[cfg(unix)]
+use riskr::api::server::bind_unix;
+use riskr::api::server::{serve, ServerOptions};
+use riskr::config::{
+    Command, Config, ConfigCommand, ConfigError, DbCommand, ExportCommand, ListCommand,
+    SanctionsCommand, ScenarioCommand, VectorCommand,
+};
+use riskr::domain::AssetRegistry;
+use riskr::export::{export_decisions, ExportFormat, Redactor};
+use riskr::geoip::MaxMindDatabase;
+use riskr::identity::{CachedIdentityProvider, HttpIdentityProvider};
+use riskr::lists::{self, ListKind};
+use riskr::loadtest::{self, LoadTestOptions};
+use riskr::observability::{init_tracing, MetricsRegistry, Watchdog, WatchdogStatus};
+use riskr::outbox::{LifecycleEvent, LifecycleEvents, LogSink, OutboxRelay, OutboxSink};
+use riskr::policy::{
+    load_sanctions, FailedPolicyLog, LastKnownGood, PolicyLoader, PolicyWatcher, RulesetFanout,
+};
+use riskr::probe;
+use riskr::retention::EvidenceRetention;
+use riskr::rules::{Blocklist, DistinctSketches, RuleSlaMonitor, RuleSwitches, SanctionsList};
+use riskr::scenarios;
+use riskr::storage::{MockStorage, PostgresStorage, ResilientStorage, Storage, TieredStorage};
+use riskr::sweep::Sweeper;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: loadtest::CountingAllocator = loadtest::CountingAllocator;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -20,22 +48,102 @@ async fn main() -> anyhow::Result<()> {
     // Initialize tracing
     init_tracing(&config.log_level);
 
+    match config.command {
+        Some(Command::Scenarios {
+            command:
+                ScenarioCommand::Run {
+                    ref dir,
+                    ref report,
+                    ref signing_key,
+                },
+        }) => {
+            return run_scenarios(&config, dir, report.as_deref(), signing_key.as_deref()).await;
+        }
+        Some(Command::Vectors { ref command }) => return run_vectors(command).await,
+        Some(Command::Sanctions {
+            command:
+                SanctionsCommand::Compile {
+                    ref input,
+                    ref output,
+                },
+        }) => return compile_sanctions(input, output),
+        Some(Command::Export {
+            command:
+                ExportCommand::Decisions {
+                    from,
+                    to,
+                    format,
+                    ref output,
+                },
+        }) => return export(&config, from, to, format, output).await,
+        Some(Command::Lists { ref command }) => return run_list_command(&config, command).await,
+        Some(Command::Db {
+            command: DbCommand::Partition,
+        }) => return partition_tables(&config).await,
+        Some(Command::Config {
+            command: ConfigCommand::Check,
+        }) => return check_config(&config),
+        Some(Command::Probe {
+            ref target,
+            ref canaries,
+            interval_ms,
+            timeout_ms,
+            ref metrics_addr,
+        }) => {
+            return run_probe(
+                target,
+                canaries,
+                std::time::Duration::from_millis(interval_ms),
+                std::time::Duration::from_millis(timeout_ms),
+                metrics_addr,
+            )
+            .await;
+        }
+        Some(Command::Loadtest {
+            tps,
+            duration_secs,
+            users,
+            workers,
+            mix,
+            ref report,
+        }) => {
+            let options = LoadTestOptions {
+                tps,
+                duration: std::time::Duration::from_secs(duration_secs),
+                users,
+                workers,
+                mix,
+            };
+            return run_loadtest(&config, &options, report.as_deref()).await;
+        }
+        None => {}
+    }
+
+    let problems = config.validate();
+    if !problems.is_empty() {
+        report_config_problems(&problems);
+        anyhow::bail!("invalid configuration ({} problems)", problems.len());
+    }
+
     info!(
         version = env!("CARGO_PKG_VERSION"),
         "Starting riskr decision engine"
     );
 
-    // Load initial policy
-    let loader = PolicyLoader::new(
-        config.policy_path.to_string_lossy(),
-        config.sanctions_path.to_string_lossy(),
-    );
+    #[cfg(feature = "chaos")]
+    let chaos = {
+        warn!("Chaos failure injection is compiled in; do not run this build in production");
+        Arc::new(riskr::chaos::Chaos::new(config.chaos_settings()))
+    };
 
-    // Start policy watcher
-    let watcher = PolicyWatcher::new(loader, config.policy_reload_interval());
-    let (ruleset_rx, policy_handle) = watcher.start();
+    // Lifecycle events skip the storage outbox so they still go out while
+    // storage is degraded
+    let sink: Arc<dyn OutboxSink> = Arc::new(LogSink);
+    let (lifecycle, lifecycle_handle) = LifecycleEvents::start(sink.clone());
 
     // Create storage backend
+    let mut rule_switch_changes = None;
+    let mut blocklist_changes = None;
     let storage: Arc<dyn Storage> = if let Some(ref database_url) = config.database_url {
         info!("Connecting to PostgreSQL...");
         let pg_storage =
@@ -46,24 +154,282 @@ async fn main() -> anyhow::Result<()> {
             pg_storage.run_migrations().await?;
         }
 
+        if config.db_partitioning {
+            let skipped = pg_storage
+                .partition_tables(false, config.db_partition_months_ahead)
+                .await?;
+            if !skipped.is_empty() {
+                warn!(
+                    tables = ?skipped,
+                    "Tables hold data and were left unpartitioned; run `riskr db partition` to convert them"
+                );
+            }
+        }
+
+        pg_storage.warm_up().await?;
+
+        // Follow kill-switch toggles made on other replicas
+        match pg_storage.rule_switch_changes().await {
+            Ok(changes) => rule_switch_changes = Some(changes),
+            Err(e) => warn!(error = %e, "Failed to listen for rule switch changes"),
+        }
+        match pg_storage.blocklist_changes().await {
+            Ok(changes) => blocklist_changes = Some(changes),
+            Err(e) => warn!(error = %e, "Failed to listen for blocklist changes"),
+        }
+
         info!("PostgreSQL storage initialized");
-        Arc::new(pg_storage)
+        let pg_storage = Arc::new(pg_storage);
+        if config.db_partitioning {
+            spawn_partition_maintenance(pg_storage.clone(), config.db_partition_months_ahead);
+        }
+        if config.request_signing().is_some() {
+            spawn_nonce_pruning(pg_storage.clone(), config.request_signing_max_skew());
+        }
+        let retention = config.evidence_retention();
+        if retention.retentions().next().is_some() {
+            spawn_evidence_expiry(pg_storage.clone(), retention);
+        }
+        let pg_storage: Arc<dyn Storage> = pg_storage;
+        #[cfg(feature = "chaos")]
+        let pg_storage: Arc<dyn Storage> =
+            Arc::new(riskr::chaos::ChaosStorage::new(pg_storage, chaos.clone()));
+        let pg_storage: Arc<dyn Storage> = Arc::new(
+            ResilientStorage::new(
+                pg_storage,
+                config.db_retry_policy(),
+                config.db_breaker_options(),
+            )
+            .with_lifecycle(lifecycle.clone()),
+        );
+
+        match config.window_cache_hours {
+            Some(hours) => {
+                info!(hours = hours, "Window cache enabled");
+                let tiered = Arc::new(TieredStorage::new(
+                    pg_storage,
+                    chrono::Duration::hours(hours as i64),
+                    config.window_cache_max_subjects,
+                ));
+                // Subjects not yet warmed are still loaded on first touch
+                if let Some(warm_hours) = config.window_cache_warm_hours {
+                    let tiered = tiered.clone();
+                    let lifecycle = lifecycle.clone();
+                    tokio::spawn(async move {
+                        let lookback = chrono::Duration::hours(warm_hours as i64);
+                        match tiered.warm_up(lookback).await {
+                            Ok(subjects) => {
+                                info!(subjects = subjects, "Window cache warmed up");
+                                lifecycle.emit(LifecycleEvent::RecoveryCompleted {
+                                    component: "window_cache",
+                                    subjects,
+                                });
+                            }
+                            Err(e) => warn!(error = %e, "Window cache warm-up failed"),
+                        }
+                    });
+                }
+                if let Some(interval) = config.window_cache_reconcile_interval() {
+                    spawn_window_cache_reconcile(tiered.clone(), interval);
+                }
+                tiered
+            }
+            None => pg_storage,
+        }
     } else {
         info!("No database configured, using in-memory mock storage");
-        Arc::new(MockStorage::new())
+        let mock: Arc<dyn Storage> = Arc::new(MockStorage::new());
+        #[cfg(feature = "chaos")]
+        let mock: Arc<dyn Storage> = Arc::new(riskr::chaos::ChaosStorage::new(mock, chaos.clone()));
+        mock
     };
 
+    // Load initial policy
+    let mut loader = policy_loader(&config)?;
+    let distinct_sketches = loader.distinct_sketches().clone();
+    let geoip_handle = match open_ip_databases(&config)? {
+        Some(databases) => {
+            loader = loader.with_ip_intelligence(databases.clone());
+            Some(databases.spawn_reload(config.geoip_reload_interval()))
+        }
+        None => None,
+    };
+
+    let metrics = Arc::new(MetricsRegistry::with_slo(
+        config.slo_target,
+        config.slo_alert_burn_rate,
+    ));
+
+    // Restore rules switched off before this start
+    let rule_switches = Arc::new(RuleSwitches::default());
+    if let Err(e) = rule_switches.load(storage.as_ref()).await {
+        warn!(error = %e, "Failed to load rule switches");
+    }
+    for switch in rule_switches.list() {
+        warn!(rule_id = %switch.rule_id, actor = %switch.actor, "Rule disabled by kill-switch");
+    }
+    let switches_handle =
+        rule_switch_changes.map(|changes| rule_switches.clone().follow(storage.clone(), changes));
+
+    // Restore the internal blocklist
+    let blocklist = Arc::new(Blocklist::default());
+    match blocklist.load(storage.as_ref()).await {
+        Ok(()) => info!(entries = blocklist.len(), "Loaded internal blocklist"),
+        Err(e) => warn!(error = %e, "Failed to load internal blocklist"),
+    }
+    let blocklist_handle =
+        blocklist_changes.map(|changes| blocklist.clone().follow(storage.clone(), changes));
+
+    // Start policy watcher
+    let failed_policies = Arc::new(FailedPolicyLog::new(config.failed_policy_history));
+    let mut watcher = PolicyWatcher::new(loader, config.policy_reload_interval())
+        .with_storage(storage.clone())
+        .with_failure_log(failed_policies.clone())
+        .with_lifecycle(lifecycle);
+    if let Some(dir) = &config.last_known_good_dir {
+        watcher = watcher.with_last_known_good(LastKnownGood::new(dir));
+    }
+    if let Some(bake) = config.policy_bake_options() {
+        info!(
+            bake_secs = bake.period.as_secs(),
+            "Policy bake guardrail enabled"
+        );
+        watcher = watcher.with_bake(bake, metrics.clone());
+    }
+    if config.policy_replay_sample > 0 {
+        watcher = watcher.with_replay(config.policy_replay_sample, metrics.clone());
+    }
+    #[cfg(feature = "chaos")]
+    {
+        watcher = watcher.with_chaos(chaos.clone());
+    }
+    let (ruleset_rx, policy_handle) = watcher.start();
+    #[cfg(feature = "chaos")]
+    let (ruleset_rx, chaos_handle) = chaos.inject_slow_rule(ruleset_rx);
+
+    // Start outbox relay
+    let outbox_handle = OutboxRelay::new(
+        storage.clone(),
+        sink,
+        config.outbox_poll_interval(),
+        config.outbox_batch_size,
+    )
+    .with_lease(config.outbox_lease())
+    .with_max_attempts((config.outbox_max_attempts > 0).then_some(config.outbox_max_attempts))
+    .start();
+
+    // Start scheduled sweeps
+    let sweep_handle = config.sweep_options().map(|options| {
+        info!(
+            interval_secs = options.interval.as_secs(),
+            "Scheduled sweep enabled"
+        );
+        Sweeper::new(storage.clone(), ruleset_rx.clone(), options)
+            .with_rule_switches(rule_switches.clone())
+            .start()
+    });
+
+    // Start liveness self-checks
+    let watchdog = Arc::new(WatchdogStatus::new());
+    let watchdog_handle = Watchdog::new(
+        ruleset_rx.clone(),
+        storage.clone(),
+        watchdog.clone(),
+        config.watchdog_interval(),
+        config.watchdog_timeout(),
+    )
+    .start();
+
+    // Tell subsystems with rule-derived state about rule set changes
+    let rule_sla = config.rule_sla().map(|sla| {
+        info!(
+            p99_ms = sla.p99.as_millis() as u64,
+            max_error_rate = sla.max_error_rate,
+            "Rule SLA enforcement enabled"
+        );
+        Arc::new(RuleSlaMonitor::new(sla))
+    });
+    let mut fanout = RulesetFanout::new()
+        .with_listener(metrics.clone())
+        .with_listener(distinct_sketches);
+    if let Some(monitor) = &rule_sla {
+        fanout = fanout.with_listener(monitor.clone());
+    }
+    let fanout_handle = fanout.start(ruleset_rx.clone());
+
+    // Load asset registry
+    let assets = match &config.assets_path {
+        Some(path) => {
+            let registry = AssetRegistry::load(path)?;
+            info!(path = %path.display(), aliases = registry.len(), "Loaded asset registry");
+            Some(Arc::new(registry))
+        }
+        None => None,
+    };
+
+    // Load admin authentication
+    let mut admin_auth = AdminAuth {
+        api_key_groups: config.api_key_route_groups.clone(),
+        oidc_groups: config.oidc_route_groups.clone(),
+        ..AdminAuth::default()
+    };
+    if let Some(path) = &config.api_keys_path {
+        let keys = ApiKeys::load(path)?;
+        info!(path = %path.display(), keys = keys.len(), "Loaded admin API keys");
+        admin_auth.api_keys = Some(Arc::new(keys));
+    }
+    if let Some(oidc) = config.oidc_config() {
+        info!(issuer = %oidc.issuer, audience = %oidc.audience, "OIDC admin authentication enabled");
+        admin_auth.oidc = Some(Arc::new(OidcVerifier::new(oidc)?));
+    }
+    if admin_auth.is_open() {
+        warn!("No API keys or OIDC issuer configured; admin endpoints are unauthenticated");
+    }
+
     // Create application state
+    let options = ServerOptions::from(&config);
     let state = Arc::new(AppState {
         storage,
         ruleset_rx,
         start_time: Instant::now(),
         version: env!("CARGO_PKG_VERSION").to_string(),
         latency_budget_ms: config.latency_budget_ms,
+        monitor_only: config.monitor_only,
+        degraded_mode: config.degraded_mode,
+        subject_enrichment: config.subject_enrichment(),
+        metrics,
+        assets,
+        tenant_quotas: config.tenant_quota_map(),
+        watchdog,
+        export_redactor: Redactor::new(&config.export_redact)
+            .with_evidence_retention(config.evidence_retention()),
+        failed_policies,
+        rule_switches,
+        blocklist,
+        admin_auth,
+        request_signing: config.request_signing().map(|signing| {
+            info!("Request signing required for POST requests");
+            // Verification reads the body itself, so it must honour the
+            // same limit as the handlers
+            Arc::new(signing.with_max_body_bytes(options.max_request_body_bytes))
+        }),
+        rule_sla,
+        route_timeouts: config.route_timeouts(),
     });
 
     // Create router
     let app = create_router(state);
+    #[cfg(feature = "chaos")]
+    let app = app.merge(riskr::chaos::router(chaos.clone()));
+
+    // Shutdown is broadcast to every listener
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    if config.graceful_shutdown {
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            let _ = shutdown_tx.send(true);
+        });
+    }
 
     // Parse listen address
     let addr: SocketAddr = config.listen_addr.parse()?;
@@ -72,24 +438,436 @@ async fn main() -> anyhow::Result<()> {
 
     // Create TCP listener
     let listener = tokio::net::TcpListener::bind(addr).await?;
+    let tcp_server = serve(listener, app.clone(), options.clone(), shutdown_rx.clone());
 
-    // Run server with graceful shutdown
-    if config.graceful_shutdown {
-        axum::serve(listener, app)
-            .with_graceful_shutdown(shutdown_signal())
-            .await?;
-    } else {
-        axum::serve(listener, app).await?;
+    // Optionally also serve on a Unix domain socket
+    match config.listen_socket {
+        #[cfg(unix)]
+        Some(ref path) => {
+            info!(path = %path.display(), "Starting Unix socket server");
+            let unix_listener = bind_unix(path)?;
+            tokio::try_join!(tcp_server, serve(unix_listener, app, options, shutdown_rx))?;
+        }
+        _ => tcp_server.await?,
     }
 
     // Cleanup
     info!("Shutting down...");
     policy_handle.abort();
+    outbox_handle.abort();
+    lifecycle_handle.abort();
+    watchdog_handle.abort();
+    fanout_handle.abort();
+    if let Some(handle) = sweep_handle {
+        handle.abort();
+    }
+    if let Some(handle) = switches_handle {
+        handle.abort();
+    }
+    if let Some(handle) = blocklist_handle {
+        handle.abort();
+    }
+    if let Some(handle) = geoip_handle {
+        handle.abort();
+    }
+    #[cfg(feature = "chaos")]
+    chaos_handle.abort();
 
     info!("Shutdown complete");
     Ok(())
 }
 
+/// Policy loader for the configured policy and sanctions files.
+fn policy_loader(config: &Config) -> anyhow::Result<PolicyLoader> {
+    let loader = PolicyLoader::new(
+        config.policy_path.to_string_lossy(),
+        config.sanctions_path.to_string_lossy(),
+    )
+    .with_distinct_sketches(Arc::new(DistinctSketches::new(
+        config.distinct_sketch_max_subjects,
+    )));
+    let loader = match &config.compile_cache_dir {
+        Some(dir) => loader.with_compile_cache(dir),
+        None => loader,
+    };
+    Ok(match &config.identity_provider_url {
+        Some(url) => loader.with_identity_provider(Arc::new(CachedIdentityProvider::new(
+            HttpIdentityProvider::new(url, config.identity_provider_timeout())?,
+            config.identity_provider_cache_ttl(),
+        ))),
+        None => loader,
+    })
+}
+
+/// Open the configured GeoIP and ASN databases, if any.
+fn open_ip_databases(config: &Config) -> anyhow::Result<Option<Arc<MaxMindDatabase>>> {
+    if config.geoip_db_path.is_none() && config.asn_db_path.is_none() {
+        return Ok(None);
+    }
+    let databases = MaxMindDatabase::open(
+        config.geoip_db_path.as_deref(),
+        config.asn_db_path.as_deref(),
+    )?;
+    info!(databases = ?databases, "Loaded GeoIP databases");
+    Ok(Some(Arc::new(databases)))
+}
+
+/// Compile a text sanctions list for memory-mapped loading.
+fn compile_sanctions(input: &Path, output: &Path) -> anyhow::Result<()> {
+    let sanctions = load_sanctions(input)?;
+    SanctionsList::compile(&sanctions, output)?;
+
+    info!(
+        entries = sanctions.len(),
+        output = %output.display(),
+        "Compiled sanctions list"
+    );
+    Ok(())
+}
+
+/// Export decisions from PostgreSQL and exit.
+async fn export(
+    config: &Config,
+    from: chrono::NaiveDate,
+    to: chrono::NaiveDate,
+    format: ExportFormat,
+    output: &Path,
+) -> anyhow::Result<()> {
+    if from > to {
+        anyhow::bail!("--from must not be after --to");
+    }
+    let Some(ref database_url) = config.database_url else {
+        anyhow::bail!("Exporting decisions requires --database-url");
+    };
+
+    let storage =
+        PostgresStorage::connect(database_url, config.db_pool_min, config.db_pool_max).await?;
+    let redactor =
+        Redactor::new(&config.export_redact).with_evidence_retention(config.evidence_retention());
+
+    let file = std::io::BufWriter::new(std::fs::File::create(output)?);
+    let count = export_decisions(Arc::new(storage), from, to, format, &redactor, file).await?;
+
+    info!(
+        decisions = count,
+        output = %output.display(),
+        "Exported decisions"
+    );
+    Ok(())
+}
+
+/// Import or export a bulk list and exit.
+async fn run_list_command(config: &Config, command: &ListCommand) -> anyhow::Result<()> {
+    let Some(ref database_url) = config.database_url else {
+        anyhow::bail!("Importing and exporting lists requires --database-url");
+    };
+    let storage =
+        PostgresStorage::connect(database_url, config.db_pool_min, config.db_pool_max).await?;
+
+    match command {
+        ListCommand::Export {
+            list,
+            format,
+            output,
+        } => {
+            let body = match list {
+                ListKind::Overrides => lists::export_overrides(&storage, *format).await?,
+                ListKind::Blocklist => lists::export_blocklist(&storage, *format).await?,
+            };
+            std::fs::write(output, body)?;
+            info!(list = ?list, output = %output.display(), "Exported list");
+        }
+        ListCommand::Import {
+            list,
+            input,
+            format,
+            dry_run,
+            replace,
+            actor,
+        } => {
+            let body = std::fs::read_to_string(input)?;
+            let report = match list {
+                ListKind::Overrides => {
+                    // Tiers are checked against the configured policy
+                    let policy = policy_loader(config)?.load_policy()?;
+                    lists::import_overrides(
+                        &storage,
+                        *format,
+                        &body,
+                        Some(&policy.params.kyc_tiers),
+                        *dry_run,
+                    )
+                    .await?
+                }
+                ListKind::Blocklist => {
+                    lists::import_blocklist(&storage, *format, &body, actor, *replace, *dry_run)
+                        .await?
+                }
+            };
+            for error in &report.errors {
+                warn!(row = error.row, error = %error.error, "Invalid row");
+            }
+            if report.is_rejected() {
+                anyhow::bail!(
+                    "{} of {} rows are invalid; nothing was imported",
+                    report.errors.len(),
+                    report.rows
+                );
+            }
+            info!(
+                list = ?list,
+                rows = report.rows,
+                applied = report.applied,
+                dry_run = report.dry_run,
+                "Imported list"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Partition existing tables by month and exit.
+async fn partition_tables(config: &Config) -> anyhow::Result<()> {
+    let Some(ref database_url) = config.database_url else {
+        anyhow::bail!("Partitioning tables requires --database-url");
+    };
+
+    let storage = PostgresStorage::connect(database_url, 1, 1).await?;
+    storage.run_migrations().await?;
+    storage
+        .partition_tables(true, config.db_partition_months_ahead)
+        .await?;
+
+    info!("Tables partitioned");
+    Ok(())
+}
+
+/// Probe an instance with canary requests until shut down.
+async fn run_probe(
+    target: &str,
+    canaries: &Path,
+    interval: std::time::Duration,
+    timeout: std::time::Duration,
+    metrics_addr: &str,
+) -> anyhow::Result<()> {
+    let canaries = probe::load_canaries(canaries)?;
+    let metrics = Arc::new(probe::ProbeMetrics::new(&canaries));
+
+    let listener = tokio::net::TcpListener::bind(metrics_addr).await?;
+    let router = axum::Router::new().route(
+        "/metrics",
+        axum::routing::get({
+            let metrics = metrics.clone();
+            move || std::future::ready(metrics.to_prometheus())
+        }),
+    );
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            warn!(error = %e, "Probe metrics server failed");
+        }
+    });
+
+    info!(
+        target = target,
+        canaries = canaries.len(),
+        metrics_addr = metrics_addr,
+        "Probing"
+    );
+    let prober = probe::Prober::new(target, timeout);
+    tokio::select! {
+        _ = prober.run(&canaries, &metrics, interval) => {}
+        _ = shutdown_signal() => {}
+    }
+    Ok(())
+}
+
+/// Keep monthly partitions created ahead of time, checking daily.
+fn spawn_partition_maintenance(storage: Arc<PostgresStorage>, months_ahead: u32) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(24 * 3600));
+        loop {
+            interval.tick().await;
+            match storage.ensure_partitions(months_ahead).await {
+                Ok(0) => {}
+                Ok(created) => info!(created = created, "Created table partitions"),
+                Err(e) => warn!(error = %e, "Failed to create table partitions"),
+            }
+        }
+    });
+}
+
+/// Redact decision evidence past its data class's retention, checking daily.
+fn spawn_evidence_expiry(storage: Arc<PostgresStorage>, retention: EvidenceRetention) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(24 * 3600));
+        loop {
+            interval.tick().await;
+            match storage.expire_evidence(&retention).await {
+                Ok(0) => {}
+                Ok(redacted) => info!(decisions = redacted, "Redacted expired evidence"),
+                Err(e) => warn!(error = %e, "Failed to redact expired evidence"),
+            }
+        }
+    });
+}
+
+/// Delete expired request nonces every `max_skew`, since each lives that long.
+fn spawn_nonce_pruning(storage: Arc<PostgresStorage>, max_skew: std::time::Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(max_skew);
+        loop {
+            interval.tick().await;
+            if let Err(e) = storage.prune_nonces().await {
+                warn!(error = %e, "Failed to prune request nonces");
+            }
+        }
+    });
+}
+
+fn spawn_window_cache_reconcile(storage: Arc<TieredStorage>, every: std::time::Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        // The first tick completes immediately; the cache is still cold
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match storage.reconcile().await {
+                Ok(0) => {}
+                Ok(drifted) => info!(subjects = drifted, "Repaired drifted window cache"),
+                Err(e) => warn!(error = %e, "Failed to reconcile window cache"),
+            }
+        }
+    });
+}
+
+/// Run compliance scenarios against the configured policy and exit.
+async fn run_scenarios(
+    config: &Config,
+    dir: &Path,
+    report_path: Option<&Path>,
+    signing_key: Option<&str>,
+) -> anyhow::Result<()> {
+    let mut loader = policy_loader(config)?;
+    if let Some(databases) = open_ip_databases(config)? {
+        loader = loader.with_ip_intelligence(databases);
+    }
+    let (policy, ruleset) = loader.load()?;
+    let files = scenarios::load_dir(dir)?;
+
+    let mut report = scenarios::run(&ruleset, &files).await;
+    match signing_key {
+        Some(key) => report.sign(key.as_bytes()),
+        None => warn!("No signing key configured, report is unsigned"),
+    }
+
+    info!(
+        policy_version = %policy.version,
+        passed = report.passed,
+        failed = report.failed,
+        "Scenarios complete"
+    );
+
+    let json = serde_json::to_string_pretty(&report)?;
+    match report_path {
+        Some(path) => std::fs::write(path, json)?,
+        None => println!("{}", json),
+    }
+
+    if !report.all_passed() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Run an in-process load test against the configured policy and exit.
+async fn run_loadtest(
+    config: &Config,
+    options: &LoadTestOptions,
+    report_path: Option<&Path>,
+) -> anyhow::Result<()> {
+    let mut loader = policy_loader(config)?;
+    if let Some(databases) = open_ip_databases(config)? {
+        loader = loader.with_ip_intelligence(databases);
+    }
+    let (policy, ruleset) = loader.load()?;
+
+    info!(
+        policy_version = %policy.version,
+        tps = options.tps,
+        duration_secs = options.duration.as_secs(),
+        workers = options.workers,
+        "Starting load test"
+    );
+    let report = loadtest::run(Arc::new(ruleset), Arc::new(MockStorage::new()), options).await;
+    info!(
+        decisions = report.decisions,
+        achieved_tps = report.achieved_tps,
+        p99_us = report.latency.p99_us,
+        "Load test complete"
+    );
+
+    let json = serde_json::to_string_pretty(&report)?;
+    match report_path {
+        Some(path) => std::fs::write(path, json)?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Validate the configuration, print every problem and exit.
+fn check_config(config: &Config) -> anyhow::Result<()> {
+    let problems = config.validate();
+    if problems.is_empty() {
+        println!("Configuration OK");
+        return Ok(());
+    }
+    report_config_problems(&problems);
+    std::process::exit(1);
+}
+
+fn report_config_problems(problems: &[ConfigError]) {
+    for problem in problems {
+        eprintln!("config error: {}", problem);
+    }
+}
+
+/// Generate or verify rule test vectors and exit.
+async fn run_vectors(command: &VectorCommand) -> anyhow::Result<()> {
+    match command {
+        VectorCommand::Generate { output } => {
+            let file = scenarios::vectors::generate().await?;
+            let json = file.to_json()?;
+            match output {
+                Some(path) => std::fs::write(path, json)?,
+                None => println!("{}", json),
+            }
+            info!(vectors = file.vectors.len(), "Test vectors generated");
+        }
+        VectorCommand::Verify { input } => {
+            let file: scenarios::vectors::VectorFile =
+                serde_json::from_str(&std::fs::read_to_string(input)?)?;
+            let mismatches = scenarios::vectors::verify(&file).await?;
+            for mismatch in &mismatches {
+                warn!(
+                    vector = %mismatch.name,
+                    expected = ?mismatch.expected,
+                    actual = ?mismatch.actual,
+                    "Test vector mismatch"
+                );
+            }
+            info!(
+                vectors = file.vectors.len(),
+                mismatched = mismatches.len(),
+                "Test vectors verified"
+            );
+            if !mismatches.is_empty() {
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
 async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
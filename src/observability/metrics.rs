@@ -1,6 +1,33 @@
+use parking_lot::Mutex;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
+use super::phases::PhaseMetrics;
+use super::slo::{SloTracker, LONG_WINDOW_SECS, SHORT_WINDOW_SECS};
+use super::stats::DecisionStats;
+
+/// Decision counts at a point in time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DecisionMix {
+    pub total: u64,
+    pub non_allow: u64,
+}
+
+impl DecisionMix {
+    /// Decisions made between `earlier` and this snapshot.
+    pub fn since(&self, earlier: DecisionMix) -> DecisionMix {
+        DecisionMix {
+            total: self.total.saturating_sub(earlier.total),
+            non_allow: self.non_allow.saturating_sub(earlier.non_allow),
+        }
+    }
+
+    /// Fraction of decisions that were not Allow, if any were made.
+    pub fn non_allow_rate(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.non_allow as f64 / self.total as f64)
+    }
+}
+
 /// Metrics registry for the application.
 #[derive(Debug, Default)]
 pub struct MetricsRegistry {
@@ -22,6 +49,9 @@ pub struct MetricsRegistry {
     pub latency_50_100ms: AtomicU64,
     pub latency_over_100ms: AtomicU64,
 
+    /// Decision latency by pipeline phase
+    pub phases: PhaseMetrics,
+
     /// Rule evaluation counts
     pub rules_evaluated_total: AtomicU64,
     pub rules_triggered_total: AtomicU64,
@@ -33,6 +63,25 @@ pub struct MetricsRegistry {
     /// Policy reloads
     pub policy_reloads_total: AtomicU64,
     pub policy_reload_errors: AtomicU64,
+    pub policy_rollbacks_total: AtomicU64,
+
+    /// Recent requests replayed after the last policy activation, and how
+    /// many of their decisions it made more or less severe
+    pub policy_replay_requests: AtomicU64,
+    pub policy_replay_escalated: AtomicU64,
+    pub policy_replay_relaxed: AtomicU64,
+
+    /// Version of the active policy, once a rule set has been activated
+    pub active_policy_version: Mutex<Option<String>>,
+    /// Rules in the active rule set
+    pub active_inline_rules: AtomicU64,
+    pub active_streaming_rules: AtomicU64,
+
+    /// Decision latency SLO
+    pub slo: SloTracker,
+
+    /// Windowed decision summaries for the stats endpoint
+    pub stats: DecisionStats,
 }
 
 impl MetricsRegistry {
@@ -41,6 +90,15 @@ impl MetricsRegistry {
         MetricsRegistry::default()
     }
 
+    /// Create a registry with a decision latency SLO target and the
+    /// short-window burn rate at which an alert event is logged.
+    pub fn with_slo(target: f64, alert_burn_rate: f64) -> Self {
+        MetricsRegistry {
+            slo: SloTracker::new(target, alert_burn_rate),
+            ..MetricsRegistry::default()
+        }
+    }
+
     /// Record a decision outcome.
     pub fn record_decision(&self, decision: &crate::domain::Decision) {
         self.decisions_total.fetch_add(1, Ordering::Relaxed);
@@ -107,8 +165,110 @@ impl MetricsRegistry {
         }
     }
 
+    /// Record a policy rolled back after its bake period.
+    pub fn record_policy_rollback(&self) {
+        self.policy_rollbacks_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record the outcome of replaying recent requests under a newly
+    /// activated policy.
+    pub fn record_policy_replay(&self, replayed: u32, escalated: u32, relaxed: u32) {
+        self.policy_replay_requests
+            .store(replayed as u64, Ordering::Relaxed);
+        self.policy_replay_escalated
+            .store(escalated as u64, Ordering::Relaxed);
+        self.policy_replay_relaxed
+            .store(relaxed as u64, Ordering::Relaxed);
+    }
+
+    /// Record the rule set now in force.
+    pub fn record_active_ruleset(&self, policy_version: &str, inline: usize, streaming: usize) {
+        *self.active_policy_version.lock() = Some(policy_version.to_string());
+        self.active_inline_rules
+            .store(inline as u64, Ordering::Relaxed);
+        self.active_streaming_rules
+            .store(streaming as u64, Ordering::Relaxed);
+    }
+
+    /// Snapshot of decisions made so far.
+    pub fn decision_mix(&self) -> DecisionMix {
+        let total = self.decisions_total.load(Ordering::Relaxed);
+        let allow = self.decisions_allow.load(Ordering::Relaxed);
+        DecisionMix {
+            total,
+            non_allow: total.saturating_sub(allow),
+        }
+    }
+
     /// Export metrics in Prometheus format.
     pub fn to_prometheus(&self) -> String {
+        let mut output = self.counters_prometheus();
+        output.push_str(&self.phases.to_prometheus());
+        output.push_str(&self.slo_prometheus());
+        output.push_str(&self.ruleset_prometheus());
+        output
+    }
+
+    fn ruleset_prometheus(&self) -> String {
+        let Some(version) = self.active_policy_version.lock().clone() else {
+            return String::new();
+        };
+        let version = version.replace('\\', "\\\\").replace('"', "\\\"");
+
+        format!(
+            r#"
+# HELP riskr_policy_info Version of the active policy
+# TYPE riskr_policy_info gauge
+riskr_policy_info{{version="{}"}} 1
+
+# HELP riskr_active_rules Rules in the active rule set
+# TYPE riskr_active_rules gauge
+riskr_active_rules{{kind="inline"}} {}
+riskr_active_rules{{kind="streaming"}} {}
+"#,
+            version,
+            self.active_inline_rules.load(Ordering::Relaxed),
+            self.active_streaming_rules.load(Ordering::Relaxed),
+        )
+    }
+
+    fn slo_prometheus(&self) -> String {
+        let target = self.slo.target();
+        let short = self.slo.window(SHORT_WINDOW_SECS);
+        let long = self.slo.window(LONG_WINDOW_SECS);
+
+        format!(
+            r#"
+# HELP riskr_slo_target Fraction of decisions expected within the latency budget
+# TYPE riskr_slo_target gauge
+riskr_slo_target {}
+
+# HELP riskr_slo_requests Decision requests in the window
+# TYPE riskr_slo_requests gauge
+riskr_slo_requests{{window="5m"}} {}
+riskr_slo_requests{{window="1h"}} {}
+
+# HELP riskr_slo_bad_ratio Fraction of decisions over budget or failed in the window
+# TYPE riskr_slo_bad_ratio gauge
+riskr_slo_bad_ratio{{window="5m"}} {}
+riskr_slo_bad_ratio{{window="1h"}} {}
+
+# HELP riskr_slo_burn_rate Error budget burn rate in the window
+# TYPE riskr_slo_burn_rate gauge
+riskr_slo_burn_rate{{window="5m"}} {}
+riskr_slo_burn_rate{{window="1h"}} {}
+"#,
+            target,
+            short.total,
+            long.total,
+            short.bad_ratio(),
+            long.bad_ratio(),
+            short.burn_rate(target),
+            long.burn_rate(target),
+        )
+    }
+
+    fn counters_prometheus(&self) -> String {
         format!(
             r#"# HELP riskr_decisions_total Total number of decision requests
 # TYPE riskr_decisions_total counter
@@ -154,6 +314,19 @@ riskr_policy_reloads_total {}
 # HELP riskr_policy_reload_errors_total Policy reload errors
 # TYPE riskr_policy_reload_errors_total counter
 riskr_policy_reload_errors_total {}
+
+# HELP riskr_policy_rollbacks_total Policies rolled back during their bake period
+# TYPE riskr_policy_rollbacks_total counter
+riskr_policy_rollbacks_total {}
+
+# HELP riskr_policy_replay_requests Recent requests replayed after the last policy activation
+# TYPE riskr_policy_replay_requests gauge
+riskr_policy_replay_requests {}
+
+# HELP riskr_policy_replay_changed Replayed requests whose decision the last policy activation changed
+# TYPE riskr_policy_replay_changed gauge
+riskr_policy_replay_changed{{direction="escalated"}} {}
+riskr_policy_replay_changed{{direction="relaxed"}} {}
 "#,
             self.decisions_total.load(Ordering::Relaxed),
             self.decisions_allow.load(Ordering::Relaxed),
@@ -173,6 +346,10 @@ riskr_policy_reload_errors_total {}
             self.wal_write_errors.load(Ordering::Relaxed),
             self.policy_reloads_total.load(Ordering::Relaxed),
             self.policy_reload_errors.load(Ordering::Relaxed),
+            self.policy_rollbacks_total.load(Ordering::Relaxed),
+            self.policy_replay_requests.load(Ordering::Relaxed),
+            self.policy_replay_escalated.load(Ordering::Relaxed),
+            self.policy_replay_relaxed.load(Ordering::Relaxed),
         )
     }
 }
@@ -214,6 +391,24 @@ mod tests {
         assert_eq!(metrics.decisions_total.load(Ordering::Relaxed), 3);
         assert_eq!(metrics.decisions_allow.load(Ordering::Relaxed), 2);
         assert_eq!(metrics.decisions_reject.load(Ordering::Relaxed), 1);
+
+        let mix = metrics.decision_mix();
+        assert_eq!(
+            mix,
+            DecisionMix {
+                total: 3,
+                non_allow: 1
+            }
+        );
+        assert_eq!(
+            mix.since(DecisionMix {
+                total: 1,
+                non_allow: 0
+            })
+            .non_allow_rate(),
+            Some(0.5)
+        );
+        assert_eq!(DecisionMix::default().non_allow_rate(), None);
     }
 
     #[test]
@@ -237,4 +432,19 @@ mod tests {
         assert!(output.contains("riskr_decisions_total 1"));
         assert!(output.contains("riskr_decisions{outcome=\"allow\"} 1"));
     }
+
+    #[test]
+    fn test_slo_metrics() {
+        let metrics = MetricsRegistry::with_slo(0.9, 14.4);
+        for i in 0..10 {
+            metrics.slo.record(i == 0);
+        }
+
+        let output = metrics.to_prometheus();
+
+        assert!(output.contains("riskr_slo_target 0.9"));
+        assert!(output.contains("riskr_slo_requests{window=\"5m\"} 10"));
+        assert!(output.contains("riskr_slo_bad_ratio{window=\"1h\"} 0.1"));
+        assert!(output.contains("riskr_slo_burn_rate{window=\"5m\"} 1"));
+    }
 }
@@ -1,5 +1,13 @@
 pub mod metrics;
+pub mod phases;
+pub mod slo;
+pub mod stats;
 pub mod tracing;
+pub mod watchdog;
 
-pub use metrics::MetricsRegistry;
+pub use metrics::{DecisionMix, MetricsRegistry};
+pub use phases::{Phase, PhaseMetrics, PhaseTimings};
+pub use slo::{SloTracker, SloWindow};
+pub use stats::{DecisionStats, DecisionSummary, StatsWindow};
 pub use tracing::init_tracing;
+pub use watchdog::{LivenessCheck, Watchdog, WatchdogStatus};
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+/// Stage of the decision pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading and parsing the request body
+    Deserialize,
+    /// Stateless rules
+    InlineRules,
+    /// Subject lookup, enrichment and upsert
+    SubjectUpsert,
+    /// Stateful rules
+    StreamingRules,
+    /// Recording the transaction, decision and outbox event
+    Persistence,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [
+        Phase::Deserialize,
+        Phase::InlineRules,
+        Phase::SubjectUpsert,
+        Phase::StreamingRules,
+        Phase::Persistence,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Deserialize => "deserialize",
+            Phase::InlineRules => "inline_rules",
+            Phase::SubjectUpsert => "subject_upsert",
+            Phase::StreamingRules => "streaming_rules",
+            Phase::Persistence => "persistence",
+        }
+    }
+}
+
+/// Time spent in each phase of one decision, in microseconds. Phases that
+/// did not run are omitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PhaseTimings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deserialize_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inline_rules_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_upsert_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub streaming_rules_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persistence_us: Option<u64>,
+}
+
+impl PhaseTimings {
+    /// Add the time since `start` to `phase`.
+    pub fn record(&mut self, phase: Phase, start: Instant) {
+        self.add(phase, start.elapsed());
+    }
+
+    /// Add `elapsed` to `phase`; a phase may run in more than one step.
+    pub fn add(&mut self, phase: Phase, elapsed: Duration) {
+        let micros = Some(self.get(phase).unwrap_or(0) + elapsed.as_micros() as u64);
+        match phase {
+            Phase::Deserialize => self.deserialize_us = micros,
+            Phase::InlineRules => self.inline_rules_us = micros,
+            Phase::SubjectUpsert => self.subject_upsert_us = micros,
+            Phase::StreamingRules => self.streaming_rules_us = micros,
+            Phase::Persistence => self.persistence_us = micros,
+        }
+    }
+
+    /// Time recorded for `phase`, if it ran.
+    pub fn get(&self, phase: Phase) -> Option<u64> {
+        match phase {
+            Phase::Deserialize => self.deserialize_us,
+            Phase::InlineRules => self.inline_rules_us,
+            Phase::SubjectUpsert => self.subject_upsert_us,
+            Phase::StreamingRules => self.streaming_rules_us,
+            Phase::Persistence => self.persistence_us,
+        }
+    }
+}
+
+/// Upper bounds of the phase histogram buckets in microseconds.
+const BUCKETS_US: [u64; 7] = [100, 500, 1_000, 5_000, 10_000, 50_000, 100_000];
+
+/// Cumulative latency histogram for one phase.
+#[derive(Debug, Default)]
+struct PhaseHistogram {
+    /// Observations at or under each bound in `BUCKETS_US`
+    buckets: [AtomicU64; BUCKETS_US.len()],
+    count: AtomicU64,
+    sum_us: AtomicU64,
+}
+
+impl PhaseHistogram {
+    fn observe(&self, micros: u64) {
+        for (bound, bucket) in BUCKETS_US.iter().zip(&self.buckets) {
+            if micros <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(micros, Ordering::Relaxed);
+    }
+}
+
+/// Latency histograms for each decision phase.
+#[derive(Debug, Default)]
+pub struct PhaseMetrics {
+    histograms: [PhaseHistogram; Phase::ALL.len()],
+}
+
+impl PhaseMetrics {
+    /// Add the phases that ran in one decision.
+    pub fn record(&self, timings: &PhaseTimings) {
+        for (phase, histogram) in Phase::ALL.iter().zip(&self.histograms) {
+            if let Some(micros) = timings.get(*phase) {
+                histogram.observe(micros);
+            }
+        }
+    }
+
+    /// Number of decisions that ran `phase`.
+    pub fn count(&self, phase: Phase) -> u64 {
+        let index = Phase::ALL.iter().position(|p| *p == phase).unwrap_or(0);
+        self.histograms[index].count.load(Ordering::Relaxed)
+    }
+
+    /// Export the histograms in Prometheus format.
+    pub fn to_prometheus(&self) -> String {
+        let mut output = String::from(
+            "\n# HELP riskr_decision_phase_seconds Time spent in each decision phase\n\
+             # TYPE riskr_decision_phase_seconds histogram\n",
+        );
+
+        for (phase, histogram) in Phase::ALL.iter().zip(&self.histograms) {
+            let name = phase.as_str();
+            for (bound, bucket) in BUCKETS_US.iter().zip(&histogram.buckets) {
+                let _ = writeln!(
+                    output,
+                    "riskr_decision_phase_seconds_bucket{{phase=\"{}\",le=\"{}\"}} {}",
+                    name,
+                    *bound as f64 / 1_000_000.0,
+                    bucket.load(Ordering::Relaxed)
+                );
+            }
+            let count = histogram.count.load(Ordering::Relaxed);
+            let _ = writeln!(
+                output,
+                "riskr_decision_phase_seconds_bucket{{phase=\"{}\",le=\"+Inf\"}} {}",
+                name, count
+            );
+            let _ = writeln!(
+                output,
+                "riskr_decision_phase_seconds_sum{{phase=\"{}\"}} {}",
+                name,
+                histogram.sum_us.load(Ordering::Relaxed) as f64 / 1_000_000.0
+            );
+            let _ = writeln!(
+                output,
+                "riskr_decision_phase_seconds_count{{phase=\"{}\"}} {}",
+                name, count
+            );
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timings_omit_phases_that_did_not_run() {
+        let mut timings = PhaseTimings::default();
+        timings.add(Phase::InlineRules, Duration::from_micros(30));
+        timings.add(Phase::InlineRules, Duration::from_micros(10));
+
+        let json = serde_json::to_value(timings).unwrap();
+        assert_eq!(json, serde_json::json!({ "inline_rules_us": 40 }));
+    }
+
+    #[test]
+    fn test_histogram_prometheus_format() {
+        let metrics = PhaseMetrics::default();
+        let mut timings = PhaseTimings::default();
+        timings.add(Phase::InlineRules, Duration::from_micros(80));
+        timings.add(Phase::Persistence, Duration::from_micros(2_000));
+        metrics.record(&timings);
+
+        assert_eq!(metrics.count(Phase::InlineRules), 1);
+        assert_eq!(metrics.count(Phase::StreamingRules), 0);
+
+        let output = metrics.to_prometheus();
+        assert!(output.contains(
+            "riskr_decision_phase_seconds_bucket{phase=\"inline_rules\",le=\"0.0001\"} 1"
+        ));
+        assert!(output
+            .contains("riskr_decision_phase_seconds_bucket{phase=\"persistence\",le=\"0.001\"} 0"));
+        assert!(output
+            .contains("riskr_decision_phase_seconds_bucket{phase=\"persistence\",le=\"0.005\"} 1"));
+        assert!(output.contains("riskr_decision_phase_seconds_sum{phase=\"persistence\"} 0.002"));
+        assert!(output.contains("riskr_decision_phase_seconds_count{phase=\"deserialize\"} 0"));
+    }
+}
@@ -0,0 +1,242 @@
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::Instant;
+use tracing::warn;
+
+/// Width of a time bucket in seconds.
+const BUCKET_SECS: u64 = 10;
+
+/// Short and long burn-rate windows in seconds.
+pub const SHORT_WINDOW_SECS: u64 = 5 * 60;
+pub const LONG_WINDOW_SECS: u64 = 60 * 60;
+
+/// Minimum requests in the short window before alerting.
+const MIN_ALERT_REQUESTS: u64 = 20;
+
+/// Minimum seconds between burn-rate alert events.
+const ALERT_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    index: u64,
+    total: u64,
+    bad: u64,
+}
+
+/// Request counts and burn rate over one window.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SloWindow {
+    pub total: u64,
+    /// Requests over the latency budget or failed
+    pub bad: u64,
+}
+
+impl SloWindow {
+    /// Fraction of requests that were bad.
+    pub fn bad_ratio(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.bad as f64 / self.total as f64
+    }
+
+    /// Rate at which the error budget is being spent; 1.0 spends exactly
+    /// the budget over the SLO period.
+    pub fn burn_rate(&self, target: f64) -> f64 {
+        let budget = 1.0 - target;
+        if budget <= 0.0 {
+            return 0.0;
+        }
+        self.bad_ratio() / budget
+    }
+}
+
+/// Decision latency SLO tracked over sliding windows.
+///
+/// Requests are counted in 10-second buckets covering the long window,
+/// so multi-window burn-rate alerts can be built directly on the exported
+/// gauges. A structured warning is logged when the short-window burn rate
+/// exceeds the alert threshold.
+#[derive(Debug)]
+pub struct SloTracker {
+    /// Fraction of requests expected within budget (e.g. 0.99)
+    target: f64,
+    /// Short-window burn rate that triggers an alert event
+    alert_burn_rate: f64,
+    start: Instant,
+    buckets: Mutex<VecDeque<Bucket>>,
+    last_alert: Mutex<Option<u64>>,
+}
+
+impl Default for SloTracker {
+    fn default() -> Self {
+        SloTracker::new(0.99, 14.4)
+    }
+}
+
+impl SloTracker {
+    /// Create a tracker for the given target and alert threshold.
+    pub fn new(target: f64, alert_burn_rate: f64) -> Self {
+        SloTracker {
+            target,
+            alert_burn_rate,
+            start: Instant::now(),
+            buckets: Mutex::new(VecDeque::new()),
+            last_alert: Mutex::new(None),
+        }
+    }
+
+    /// SLO target.
+    pub fn target(&self) -> f64 {
+        self.target
+    }
+
+    /// Record a request; `bad` if it was over budget or failed.
+    pub fn record(&self, bad: bool) {
+        self.record_at(self.start.elapsed().as_secs(), bad);
+    }
+
+    /// Counts over the last `secs` seconds.
+    pub fn window(&self, secs: u64) -> SloWindow {
+        self.window_at(self.start.elapsed().as_secs(), secs)
+    }
+
+    fn record_at(&self, now: u64, bad: bool) {
+        let index = now / BUCKET_SECS;
+        {
+            let mut buckets = self.buckets.lock();
+            match buckets.back_mut() {
+                Some(b) if b.index == index => {
+                    b.total += 1;
+                    b.bad += bad as u64;
+                }
+                _ => buckets.push_back(Bucket {
+                    index,
+                    total: 1,
+                    bad: bad as u64,
+                }),
+            }
+
+            let oldest = index.saturating_sub(LONG_WINDOW_SECS / BUCKET_SECS);
+            while buckets.front().is_some_and(|b| b.index < oldest) {
+                buckets.pop_front();
+            }
+        }
+
+        if bad {
+            self.check_alert(now);
+        }
+    }
+
+    fn window_at(&self, now: u64, secs: u64) -> SloWindow {
+        let oldest = (now / BUCKET_SECS + 1).saturating_sub(secs / BUCKET_SECS);
+
+        self.buckets
+            .lock()
+            .iter()
+            .filter(|b| b.index >= oldest)
+            .fold(SloWindow::default(), |acc, b| SloWindow {
+                total: acc.total + b.total,
+                bad: acc.bad + b.bad,
+            })
+    }
+
+    fn check_alert(&self, now: u64) {
+        let short = self.window_at(now, SHORT_WINDOW_SECS);
+        if short.total < MIN_ALERT_REQUESTS {
+            return;
+        }
+
+        let burn_rate = short.burn_rate(self.target);
+        if burn_rate < self.alert_burn_rate {
+            return;
+        }
+
+        let mut last_alert = self.last_alert.lock();
+        if last_alert.is_some_and(|at| now < at + ALERT_INTERVAL_SECS) {
+            return;
+        }
+        *last_alert = Some(now);
+        drop(last_alert);
+
+        let long = self.window_at(now, LONG_WINDOW_SECS);
+        warn!(
+            target: "riskr::slo",
+            burn_rate_5m = burn_rate,
+            burn_rate_1h = long.burn_rate(self.target),
+            bad_ratio_5m = short.bad_ratio(),
+            requests_5m = short.total,
+            slo_target = self.target,
+            "Decision latency SLO burn rate exceeded"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_and_burn_rate() {
+        let slo = SloTracker::new(0.99, 14.4);
+
+        // 100 requests 50 minutes ago, all good
+        for _ in 0..100 {
+            slo.record_at(600, false);
+        }
+        // 100 requests in the last minute, 10 bad
+        for i in 0..100 {
+            slo.record_at(3600, i < 10);
+        }
+
+        let short = slo.window_at(3600, SHORT_WINDOW_SECS);
+        assert_eq!(
+            short,
+            SloWindow {
+                total: 100,
+                bad: 10
+            }
+        );
+        assert!((short.burn_rate(0.99) - 10.0).abs() < 1e-9);
+
+        let long = slo.window_at(3600, LONG_WINDOW_SECS);
+        assert_eq!(
+            long,
+            SloWindow {
+                total: 200,
+                bad: 10
+            }
+        );
+        assert!((long.burn_rate(0.99) - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_old_buckets_are_dropped() {
+        let slo = SloTracker::new(0.99, 14.4);
+
+        slo.record_at(0, true);
+        slo.record_at(LONG_WINDOW_SECS + 2 * BUCKET_SECS, false);
+
+        assert_eq!(slo.buckets.lock().len(), 1);
+        assert_eq!(
+            slo.window_at(LONG_WINDOW_SECS + 2 * BUCKET_SECS, LONG_WINDOW_SECS),
+            SloWindow { total: 1, bad: 0 }
+        );
+    }
+
+    #[test]
+    fn test_alert_is_rate_limited() {
+        let slo = SloTracker::new(0.99, 14.4);
+
+        for _ in 0..MIN_ALERT_REQUESTS {
+            slo.record_at(100, true);
+        }
+        assert_eq!(*slo.last_alert.lock(), Some(100));
+
+        slo.record_at(130, true);
+        assert_eq!(*slo.last_alert.lock(), Some(100));
+
+        slo.record_at(170, true);
+        assert_eq!(*slo.last_alert.lock(), Some(170));
+    }
+}
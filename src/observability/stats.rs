@@ -0,0 +1,356 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::domain::Decision;
+
+/// Width of a time bucket in seconds.
+const BUCKET_SECS: u64 = 60;
+
+/// Longest window kept.
+const RETAINED_SECS: u64 = 24 * 60 * 60;
+
+/// Triggering rules listed in a summary.
+const TOP_RULES: usize = 10;
+
+/// Upper bounds of the latency histogram buckets in microseconds; a final
+/// bucket holds everything slower.
+const LATENCY_BOUNDS_US: [u64; 14] = [
+    100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000, 500_000,
+    1_000_000, 2_500_000,
+];
+
+/// Window a decision summary covers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum StatsWindow {
+    #[default]
+    #[serde(rename = "1h")]
+    Hour,
+    #[serde(rename = "24h")]
+    Day,
+}
+
+impl StatsWindow {
+    /// Window length in seconds.
+    pub fn secs(&self) -> u64 {
+        match self {
+            StatsWindow::Hour => 60 * 60,
+            StatsWindow::Day => RETAINED_SECS,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    index: u64,
+    outcomes: [u64; Decision::ALL.len()],
+    rule_hits: HashMap<String, u64>,
+    latency: [u64; LATENCY_BOUNDS_US.len() + 1],
+    max_latency_us: u64,
+    /// Bit `n` is set if a decision was made degraded in second `n` of the
+    /// bucket
+    degraded: u64,
+}
+
+impl Bucket {
+    fn new(index: u64) -> Self {
+        Bucket {
+            index,
+            outcomes: [0; Decision::ALL.len()],
+            rule_hits: HashMap::new(),
+            latency: [0; LATENCY_BOUNDS_US.len() + 1],
+            max_latency_us: 0,
+            degraded: 0,
+        }
+    }
+}
+
+/// Number of decisions that triggered a rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RuleHits {
+    pub rule_id: String,
+    pub hits: u64,
+}
+
+/// Decision latency percentiles in milliseconds, as the upper bound of
+/// the histogram bucket holding the percentile.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LatencyPercentiles {
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// Decisions over one window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct DecisionSummary {
+    pub window: StatsWindow,
+    /// Start of the counted period; later than a full window back when the
+    /// process started within the window
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub decisions: u64,
+    /// Decisions by outcome, including outcomes with none
+    #[schema(value_type = BTreeMap<String, u64>)]
+    pub outcomes: BTreeMap<Decision, u64>,
+    /// Most frequently triggered rules, most hits first
+    pub top_rules: Vec<RuleHits>,
+    /// Unset when no decisions were made
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyPercentiles>,
+    /// Seconds in which decisions were made with storage degraded
+    pub degraded_secs: u64,
+}
+
+/// Decision outcomes, triggered rules, latency and degraded time over the
+/// last 24 hours, for summaries that don't need a Prometheus query layer.
+///
+/// Decisions are counted in one-minute buckets, so a window's edge is
+/// accurate to a minute. Counts are per process and start empty on
+/// restart.
+#[derive(Debug)]
+pub struct DecisionStats {
+    start: Instant,
+    started_at: DateTime<Utc>,
+    buckets: Mutex<VecDeque<Bucket>>,
+}
+
+impl Default for DecisionStats {
+    fn default() -> Self {
+        DecisionStats::new()
+    }
+}
+
+impl DecisionStats {
+    /// Create an empty tracker.
+    pub fn new() -> Self {
+        DecisionStats {
+            start: Instant::now(),
+            started_at: Utc::now(),
+            buckets: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Record a completed decision, the rules it triggered, how long it took
+    /// and whether storage was degraded.
+    pub fn record(
+        &self,
+        decision: Decision,
+        rules_hit: &[String],
+        elapsed: Duration,
+        degraded: bool,
+    ) {
+        self.record_at(
+            self.start.elapsed().as_secs(),
+            decision,
+            rules_hit,
+            elapsed,
+            degraded,
+        );
+    }
+
+    /// Summary of the window ending now.
+    pub fn summary(&self, window: StatsWindow) -> DecisionSummary {
+        self.summary_at(self.start.elapsed().as_secs(), window)
+    }
+
+    fn record_at(
+        &self,
+        now: u64,
+        decision: Decision,
+        rules_hit: &[String],
+        elapsed: Duration,
+        degraded: bool,
+    ) {
+        let index = now / BUCKET_SECS;
+        let micros = elapsed.as_micros() as u64;
+        let slot = LATENCY_BOUNDS_US
+            .iter()
+            .position(|bound| micros < *bound)
+            .unwrap_or(LATENCY_BOUNDS_US.len());
+
+        let mut buckets = self.buckets.lock();
+        if buckets.back().is_none_or(|b| b.index != index) {
+            buckets.push_back(Bucket::new(index));
+        }
+        let bucket = buckets.back_mut().expect("bucket was just pushed");
+
+        bucket.outcomes[decision as usize] += 1;
+        for rule_id in rules_hit {
+            *bucket.rule_hits.entry(rule_id.clone()).or_default() += 1;
+        }
+        bucket.latency[slot] += 1;
+        bucket.max_latency_us = bucket.max_latency_us.max(micros);
+        if degraded {
+            bucket.degraded |= 1 << (now % BUCKET_SECS);
+        }
+
+        let oldest = index.saturating_sub(RETAINED_SECS / BUCKET_SECS);
+        while buckets.front().is_some_and(|b| b.index < oldest) {
+            buckets.pop_front();
+        }
+    }
+
+    fn summary_at(&self, now: u64, window: StatsWindow) -> DecisionSummary {
+        let oldest = (now / BUCKET_SECS + 1).saturating_sub(window.secs() / BUCKET_SECS);
+
+        let mut outcomes = [0u64; Decision::ALL.len()];
+        let mut rule_hits: HashMap<&str, u64> = HashMap::new();
+        let mut latency = [0u64; LATENCY_BOUNDS_US.len() + 1];
+        let mut max_latency_us = 0;
+        let mut degraded_secs = 0;
+
+        let buckets = self.buckets.lock();
+        for bucket in buckets.iter().filter(|b| b.index >= oldest) {
+            for (total, count) in outcomes.iter_mut().zip(bucket.outcomes) {
+                *total += count;
+            }
+            for (rule_id, hits) in &bucket.rule_hits {
+                *rule_hits.entry(rule_id).or_default() += hits;
+            }
+            for (total, count) in latency.iter_mut().zip(bucket.latency) {
+                *total += count;
+            }
+            max_latency_us = max_latency_us.max(bucket.max_latency_us);
+            degraded_secs += bucket.degraded.count_ones() as u64;
+        }
+
+        let mut top_rules: Vec<RuleHits> = rule_hits
+            .into_iter()
+            .map(|(rule_id, hits)| RuleHits {
+                rule_id: rule_id.to_string(),
+                hits,
+            })
+            .collect();
+        drop(buckets);
+        top_rules.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.rule_id.cmp(&b.rule_id)));
+        top_rules.truncate(TOP_RULES);
+
+        let decisions = outcomes.iter().sum();
+        let percentile = |p: f64| {
+            let rank = ((decisions as f64 * p).ceil() as u64).max(1);
+            let mut seen = 0;
+            for (slot, count) in latency.iter().enumerate() {
+                seen += count;
+                if seen >= rank {
+                    let us = LATENCY_BOUNDS_US
+                        .get(slot)
+                        .copied()
+                        .unwrap_or(max_latency_us);
+                    return us as f64 / 1000.0;
+                }
+            }
+            max_latency_us as f64 / 1000.0
+        };
+
+        let to = self.started_at + chrono::Duration::seconds(now as i64);
+        let from = (to - chrono::Duration::seconds(window.secs() as i64)).max(self.started_at);
+
+        DecisionSummary {
+            window,
+            from,
+            to,
+            decisions,
+            outcomes: Decision::ALL.into_iter().zip(outcomes).collect(),
+            top_rules,
+            latency: (decisions > 0).then(|| LatencyPercentiles {
+                p50_ms: percentile(0.5),
+                p99_ms: percentile(0.99),
+            }),
+            degraded_secs,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_summary_counts_window() {
+        let stats = DecisionStats::new();
+        let r1 = vec!["R1".to_string()];
+        let r2 = vec!["R2".to_string(), "R1".to_string()];
+
+        // Two hours ago, only in the 24h window
+        stats.record_at(0, Decision::RejectFatal, &r1, ms(1), true);
+        // Within the last hour
+        for i in 0..98 {
+            stats.record_at(7200, Decision::Allow, &[], ms(2), i < 2);
+        }
+        stats.record_at(7201, Decision::Review, &r2, ms(40), false);
+        stats.record_at(7230, Decision::HoldAuto, &r2, ms(3000), true);
+
+        let hour = stats.summary_at(7230, StatsWindow::Hour);
+        assert_eq!(hour.decisions, 100);
+        assert_eq!(hour.outcomes[&Decision::Allow], 98);
+        assert_eq!(hour.outcomes[&Decision::RejectFatal], 0);
+        assert_eq!(
+            hour.top_rules,
+            vec![
+                RuleHits {
+                    rule_id: "R1".to_string(),
+                    hits: 2
+                },
+                RuleHits {
+                    rule_id: "R2".to_string(),
+                    hits: 2
+                },
+            ]
+        );
+        assert_eq!(
+            hour.latency,
+            Some(LatencyPercentiles {
+                p50_ms: 2.5,
+                p99_ms: 50.0
+            })
+        );
+        assert_eq!(hour.degraded_secs, 2);
+        assert_eq!(
+            hour.from,
+            stats.started_at + chrono::Duration::seconds(3630)
+        );
+
+        let day = stats.summary_at(7230, StatsWindow::Day);
+        assert_eq!(day.decisions, 101);
+        assert_eq!(day.top_rules[0].hits, 3);
+        assert_eq!(day.degraded_secs, 3);
+        assert_eq!(day.from, stats.started_at);
+    }
+
+    #[test]
+    fn test_slowest_bucket_reports_max() {
+        let stats = DecisionStats::new();
+        stats.record_at(0, Decision::Allow, &[], ms(4000), false);
+
+        let summary = stats.summary_at(0, StatsWindow::Hour);
+        assert_eq!(summary.latency.unwrap().p99_ms, 4000.0);
+        assert_eq!(stats.summary_at(0, StatsWindow::Day).degraded_secs, 0);
+        assert!(DecisionStats::new()
+            .summary_at(0, StatsWindow::Hour)
+            .latency
+            .is_none());
+    }
+
+    #[test]
+    fn test_old_buckets_are_dropped() {
+        let stats = DecisionStats::new();
+
+        stats.record_at(0, Decision::Allow, &[], ms(1), false);
+        stats.record_at(
+            RETAINED_SECS + 2 * BUCKET_SECS,
+            Decision::Allow,
+            &[],
+            ms(1),
+            false,
+        );
+
+        assert_eq!(stats.buckets.lock().len(), 1);
+    }
+}
@@ -0,0 +1,303 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use smallvec::SmallVec;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
+use tokio::time::{interval, timeout};
+use tracing::{debug, error};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::event::{Asset, Direction};
+use crate::domain::subject::{AccountId, CountryCode, KycTier, UserId};
+use crate::domain::{Subject, TxEvent};
+use crate::rules::RuleSet;
+use crate::storage::Storage;
+
+/// User ID of the synthetic subject used by liveness checks.
+pub const CANARY_USER_ID: &str = "__riskr_canary__";
+
+/// Checks missed before the last result is considered stale.
+const STALE_AFTER_CHECKS: u32 = 3;
+
+/// Outcome of one liveness check.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LivenessCheck {
+    pub healthy: bool,
+    pub checked_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Components that did not respond in time
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<String>,
+}
+
+/// Latest watchdog result, shared with the health endpoint.
+#[derive(Debug, Default)]
+pub struct WatchdogStatus {
+    last: Mutex<Option<(Instant, LivenessCheck)>>,
+    /// Age after which the last result no longer counts as healthy
+    stale_after: Mutex<Option<Duration>>,
+    pub checks_total: AtomicU64,
+    pub failures_total: AtomicU64,
+}
+
+impl WatchdogStatus {
+    /// Create an empty status.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Most recent check, if any has completed.
+    pub fn last(&self) -> Option<LivenessCheck> {
+        self.last.lock().as_ref().map(|(_, check)| check.clone())
+    }
+
+    /// Returns false if the last check failed, or if checks have stopped
+    /// completing, which is itself a sign of a stalled runtime. Healthy
+    /// until the first check completes.
+    pub fn is_healthy(&self) -> bool {
+        let stale_after = *self.stale_after.lock();
+        match self.last.lock().as_ref() {
+            None => true,
+            Some((at, check)) => check.healthy && stale_after.is_none_or(|max| at.elapsed() <= max),
+        }
+    }
+
+    fn record(&self, check: LivenessCheck) {
+        self.checks_total.fetch_add(1, Ordering::Relaxed);
+        if !check.healthy {
+            self.failures_total.fetch_add(1, Ordering::Relaxed);
+        }
+        *self.last.lock() = Some((Instant::now(), check));
+    }
+
+    /// Export watchdog metrics in Prometheus format.
+    pub fn to_prometheus(&self) -> String {
+        format!(
+            r#"
+# HELP riskr_watchdog_healthy Whether the last liveness self-check passed
+# TYPE riskr_watchdog_healthy gauge
+riskr_watchdog_healthy {}
+
+# HELP riskr_watchdog_checks_total Liveness self-checks run
+# TYPE riskr_watchdog_checks_total counter
+riskr_watchdog_checks_total {}
+
+# HELP riskr_watchdog_failures_total Liveness self-checks that failed
+# TYPE riskr_watchdog_failures_total counter
+riskr_watchdog_failures_total {}
+"#,
+            self.is_healthy() as u8,
+            self.checks_total.load(Ordering::Relaxed),
+            self.failures_total.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// Periodic liveness self-check.
+///
+/// Each check reads the current rule set from the policy watch channel,
+/// reads the canary subject from storage, and evaluates every rule against
+/// a synthetic transaction for the canary. Nothing is written. Each step
+/// runs in its own task under a timeout, so a deadlocked lock or a hung
+/// connection is reported instead of hanging the check. Storage errors
+/// count as responses; only steps that do not finish in time fail.
+pub struct Watchdog {
+    ruleset_rx: watch::Receiver<Arc<RuleSet>>,
+    storage: Arc<dyn Storage>,
+    status: Arc<WatchdogStatus>,
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl Watchdog {
+    /// Create a watchdog reporting into `status`.
+    pub fn new(
+        ruleset_rx: watch::Receiver<Arc<RuleSet>>,
+        storage: Arc<dyn Storage>,
+        status: Arc<WatchdogStatus>,
+        interval: Duration,
+        timeout: Duration,
+    ) -> Self {
+        // A check runs up to three timed steps
+        *status.stale_after.lock() = Some((interval + timeout * 3) * STALE_AFTER_CHECKS);
+        Watchdog {
+            ruleset_rx,
+            storage,
+            status,
+            interval,
+            timeout,
+        }
+    }
+
+    /// Run one check and record the result.
+    pub async fn check_once(&self) -> LivenessCheck {
+        let start = Instant::now();
+        let mut failures = Vec::new();
+
+        let rx = self.ruleset_rx.clone();
+        let ruleset = self
+            .probe("policy", &mut failures, async move { rx.borrow().clone() })
+            .await;
+
+        if !self.storage.is_degraded() {
+            let storage = self.storage.clone();
+            self.probe("storage", &mut failures, async move {
+                if let Err(e) = storage.get_subject_by_user_id(CANARY_USER_ID).await {
+                    debug!(error = %e, "Watchdog storage read failed");
+                }
+            })
+            .await;
+        }
+
+        if let Some(ruleset) = ruleset {
+            let storage = self.storage.clone();
+            self.probe("decision", &mut failures, async move {
+                synthetic_decision(&ruleset, storage.as_ref()).await
+            })
+            .await;
+        }
+
+        let check = LivenessCheck {
+            healthy: failures.is_empty(),
+            checked_at: Utc::now(),
+            duration_ms: start.elapsed().as_millis() as u64,
+            failures,
+        };
+        if !check.healthy {
+            error!(failures = ?check.failures, "Liveness self-check failed");
+        }
+
+        self.status.record(check.clone());
+        check
+    }
+
+    /// Run a step in its own task, recording a failure if it does not
+    /// finish within the timeout.
+    async fn probe<T: Send + 'static>(
+        &self,
+        name: &str,
+        failures: &mut Vec<String>,
+        step: impl Future<Output = T> + Send + 'static,
+    ) -> Option<T> {
+        let handle = tokio::spawn(step);
+        let abort = handle.abort_handle();
+
+        match timeout(self.timeout, handle).await {
+            Ok(Ok(value)) => Some(value),
+            Ok(Err(e)) => {
+                failures.push(format!("{}: {}", name, e));
+                None
+            }
+            Err(_) => {
+                abort.abort();
+                failures.push(format!(
+                    "{}: no response within {}ms",
+                    name,
+                    self.timeout.as_millis()
+                ));
+                None
+            }
+        }
+    }
+
+    /// Start checking in the background.
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = interval(self.interval);
+
+            loop {
+                interval.tick().await;
+                self.check_once().await;
+            }
+        })
+    }
+}
+
+/// Evaluate every rule for the canary without recording anything.
+async fn synthetic_decision(ruleset: &RuleSet, storage: &dyn Storage) {
+    let event = TxEvent::new(
+        Subject {
+            user_id: UserId::new(CANARY_USER_ID),
+            account_id: AccountId::new(CANARY_USER_ID),
+            addresses: SmallVec::new(),
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L0,
+        },
+        Asset::new("USD"),
+        Decimal::ONE,
+        Direction::Outbound,
+    );
+
+    for rule in &ruleset.inline {
+        rule.evaluate(&event);
+    }
+    if storage.is_degraded() {
+        return;
+    }
+    for rule in &ruleset.streaming {
+        // Errors are responses; only hangs matter here
+        let _ = rule.evaluate(&event, Uuid::nil(), storage).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MockStorage;
+
+    fn watchdog(ruleset_rx: watch::Receiver<Arc<RuleSet>>) -> (Watchdog, Arc<WatchdogStatus>) {
+        let status = Arc::new(WatchdogStatus::new());
+        let watchdog = Watchdog::new(
+            ruleset_rx,
+            Arc::new(MockStorage::new()),
+            status.clone(),
+            Duration::from_secs(10),
+            Duration::from_millis(200),
+        );
+        (watchdog, status)
+    }
+
+    #[tokio::test]
+    async fn test_healthy_check() {
+        let (_tx, rx) = watch::channel(Arc::new(RuleSet::empty()));
+        let (watchdog, status) = watchdog(rx);
+        assert!(status.is_healthy());
+
+        let check = watchdog.check_once().await;
+        assert!(check.healthy);
+        assert!(status.is_healthy());
+        assert_eq!(status.checks_total.load(Ordering::Relaxed), 1);
+        assert_eq!(status.failures_total.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_stuck_policy_channel() {
+        let (tx, rx) = watch::channel(Arc::new(RuleSet::empty()));
+        let (watchdog, status) = watchdog(rx);
+
+        // Hold the channel's write lock past the timeout, as a deadlocked
+        // writer would
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let writer = std::thread::spawn(move || {
+            tx.send_modify(|_| {
+                locked_tx.send(()).unwrap();
+                std::thread::sleep(Duration::from_millis(500));
+            });
+        });
+        locked_rx.recv().unwrap();
+
+        let check = watchdog.check_once().await;
+        assert!(!check.healthy);
+        assert!(check.failures[0].starts_with("policy"));
+        assert!(!status.is_healthy());
+        assert!(status.to_prometheus().contains("riskr_watchdog_healthy 0"));
+
+        writer.join().unwrap();
+    }
+}
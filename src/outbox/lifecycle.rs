@@ -0,0 +1,231 @@
+use chrono::Utc;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use super::OutboxSink;
+use crate::storage::OutboxEvent;
+
+/// Events queued for delivery before new ones are dropped
+const CAPACITY: usize = 1024;
+/// Delivery attempts per event, including the first
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Operational event from the engine itself, as opposed to a decision.
+///
+/// Delivered to the outbox sink with kind `lifecycle.<event>`, so ops
+/// automation can react to them without scraping logs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    PolicyActivated {
+        version: String,
+        previous_version: Option<String>,
+    },
+    PolicyRejected {
+        version: Option<String>,
+        active_version: Option<String>,
+        errors: Vec<String>,
+    },
+    /// A policy was rolled back at the end of a failed bake
+    PolicyRolledBack {
+        version: String,
+        restored_version: String,
+        non_allow_rate: f64,
+        baseline_non_allow_rate: f64,
+    },
+    SanctionsUpdated {
+        list: String,
+        version: String,
+        previous_version: String,
+        entries: usize,
+    },
+    /// State lost on restart was rebuilt
+    RecoveryCompleted {
+        component: &'static str,
+        subjects: usize,
+    },
+    DegradedEntered {
+        component: &'static str,
+        error_rate: f64,
+    },
+    DegradedExited {
+        component: &'static str,
+    },
+}
+
+impl LifecycleEvent {
+    /// Snake-case name of the event.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LifecycleEvent::PolicyActivated { .. } => "policy_activated",
+            LifecycleEvent::PolicyRejected { .. } => "policy_rejected",
+            LifecycleEvent::PolicyRolledBack { .. } => "policy_rolled_back",
+            LifecycleEvent::SanctionsUpdated { .. } => "sanctions_updated",
+            LifecycleEvent::RecoveryCompleted { .. } => "recovery_completed",
+            LifecycleEvent::DegradedEntered { .. } => "degraded_entered",
+            LifecycleEvent::DegradedExited { .. } => "degraded_exited",
+        }
+    }
+
+    /// Outbox event carrying this event and when it happened.
+    pub fn to_outbox_event(&self) -> OutboxEvent {
+        let mut payload = serde_json::to_value(self).unwrap_or_default();
+        if let Some(fields) = payload.as_object_mut() {
+            fields.insert("at".to_string(), serde_json::json!(Utc::now()));
+        }
+        OutboxEvent {
+            id: Uuid::new_v4(),
+            kind: format!("lifecycle.{}", self.name()),
+            payload,
+            attempts: 0,
+        }
+    }
+}
+
+/// Handle for emitting lifecycle events.
+///
+/// Events go straight to the sink rather than through the storage outbox,
+/// since several of them are about storage being unavailable. Emitting
+/// never blocks: events are queued for a background task and dropped with a
+/// warning if the queue is full. The default handle discards events.
+#[derive(Debug, Clone, Default)]
+pub struct LifecycleEvents {
+    tx: Option<mpsc::Sender<OutboxEvent>>,
+}
+
+impl LifecycleEvents {
+    /// Create a handle and the receiving end of its queue.
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<OutboxEvent>) {
+        let (tx, rx) = mpsc::channel(capacity);
+        (LifecycleEvents { tx: Some(tx) }, rx)
+    }
+
+    /// Create a handle delivering to `sink` in the background.
+    ///
+    /// Failed deliveries are retried with backoff, then dropped.
+    pub fn start(sink: Arc<dyn OutboxSink>) -> (Self, tokio::task::JoinHandle<()>) {
+        let (events, mut rx) = Self::channel(CAPACITY);
+        let handle = tokio::spawn(async move {
+            while let Some(mut event) = rx.recv().await {
+                deliver(sink.as_ref(), &mut event, Duration::from_millis(100)).await;
+            }
+        });
+        (events, handle)
+    }
+
+    /// Queue an event for delivery.
+    pub fn emit(&self, event: LifecycleEvent) {
+        let Some(ref tx) = self.tx else {
+            return;
+        };
+        if let Err(e) = tx.try_send(event.to_outbox_event()) {
+            warn!(event = event.name(), error = %e, "Dropped lifecycle event");
+        }
+    }
+}
+
+/// Deliver one event, retrying with exponential backoff.
+async fn deliver(sink: &dyn OutboxSink, event: &mut OutboxEvent, base_delay: Duration) {
+    loop {
+        match sink.deliver(event).await {
+            Ok(()) => return,
+            Err(e) if event.attempts + 1 < MAX_ATTEMPTS => {
+                warn!(id = %event.id, kind = %event.kind, error = %e, "Lifecycle event delivery failed");
+                tokio::time::sleep(base_delay * 2u32.pow(event.attempts)).await;
+                event.attempts += 1;
+            }
+            Err(e) => {
+                error!(
+                    id = %event.id,
+                    kind = %event.kind,
+                    payload = %event.payload,
+                    error = %e,
+                    "Giving up on lifecycle event"
+                );
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    /// Sink failing its first `failures` deliveries.
+    #[derive(Default)]
+    struct FlakySink {
+        failures: Mutex<u32>,
+        delivered: Mutex<Vec<OutboxEvent>>,
+    }
+
+    #[async_trait]
+    impl OutboxSink for FlakySink {
+        async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()> {
+            let mut failures = self.failures.lock();
+            if *failures > 0 {
+                *failures -= 1;
+                anyhow::bail!("sink unavailable");
+            }
+            self.delivered.lock().push(event.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_emit_builds_outbox_event() {
+        let (events, mut rx) = LifecycleEvents::channel(4);
+        events.emit(LifecycleEvent::DegradedEntered {
+            component: "storage",
+            error_rate: 0.75,
+        });
+
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.kind, "lifecycle.degraded_entered");
+        assert_eq!(event.payload["event"], "degraded_entered");
+        assert_eq!(event.payload["component"], "storage");
+        assert_eq!(event.payload["error_rate"], 0.75);
+        assert!(event.payload["at"].is_string());
+
+        // Disabled handles and full queues drop events
+        LifecycleEvents::default().emit(LifecycleEvent::DegradedExited {
+            component: "storage",
+        });
+        let (events, _rx) = LifecycleEvents::channel(1);
+        for _ in 0..2 {
+            events.emit(LifecycleEvent::DegradedExited {
+                component: "storage",
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries_then_gives_up() {
+        let sink = FlakySink {
+            failures: Mutex::new(2),
+            ..Default::default()
+        };
+        let mut event = LifecycleEvent::DegradedExited {
+            component: "storage",
+        }
+        .to_outbox_event();
+        deliver(&sink, &mut event, Duration::ZERO).await;
+        assert_eq!(event.attempts, 2);
+        assert_eq!(sink.delivered.lock().len(), 1);
+
+        *sink.failures.lock() = MAX_ATTEMPTS;
+        let mut event = LifecycleEvent::DegradedExited {
+            component: "storage",
+        }
+        .to_outbox_event();
+        deliver(&sink, &mut event, Duration::ZERO).await;
+        assert_eq!(event.attempts, MAX_ATTEMPTS - 1);
+        assert_eq!(sink.delivered.lock().len(), 1);
+    }
+}
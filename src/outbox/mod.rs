@@ -0,0 +1,309 @@
+pub mod lifecycle;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info, warn};
+
+use crate::storage::{OutboxEvent, Storage};
+
+pub use lifecycle::{LifecycleEvent, LifecycleEvents};
+
+/// Destination for outbox events.
+///
+/// Delivery is at-least-once: an event whose delivery succeeded may be
+/// handed to the sink again if marking it delivered fails, or if its lease
+/// ran out first, so sinks should be idempotent on `OutboxEvent::id`.
+#[async_trait]
+pub trait OutboxSink: Send + Sync {
+    async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()>;
+}
+
+/// Sink that writes events to the log.
+pub struct LogSink;
+
+#[async_trait]
+impl OutboxSink for LogSink {
+    async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()> {
+        info!(
+            target: "riskr::outbox",
+            id = %event.id,
+            kind = %event.kind,
+            payload = %event.payload,
+            "Outbox event"
+        );
+        Ok(())
+    }
+}
+
+/// Default time a relay holds the events it claims.
+pub const DEFAULT_OUTBOX_LEASE: Duration = Duration::from_secs(30);
+
+/// Deliver committed outbox events to a sink.
+///
+/// Events are delivered roughly oldest first, but not strictly in commit
+/// order, and relays on several replicas each deliver the batches they
+/// claim. After a failure the rest of the batch waits out its lease, which
+/// backs the relay off a failing sink, and the failed event is retried
+/// once its own lease runs out. An event that fails `max_attempts` times is
+/// dead-lettered.
+pub struct OutboxRelay {
+    storage: Arc<dyn Storage>,
+    sink: Arc<dyn OutboxSink>,
+    poll_interval: Duration,
+    batch_size: u32,
+    lease: Duration,
+    max_attempts: Option<u32>,
+}
+
+impl OutboxRelay {
+    /// Create a new relay.
+    pub fn new(
+        storage: Arc<dyn Storage>,
+        sink: Arc<dyn OutboxSink>,
+        poll_interval: Duration,
+        batch_size: u32,
+    ) -> Self {
+        OutboxRelay {
+            storage,
+            sink,
+            poll_interval,
+            batch_size,
+            lease: DEFAULT_OUTBOX_LEASE,
+            max_attempts: None,
+        }
+    }
+
+    /// Hold claimed events for `lease`.
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Dead-letter events after `max_attempts` failed deliveries; None
+    /// retries forever.
+    pub fn with_max_attempts(mut self, max_attempts: Option<u32>) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Claim and deliver one batch of pending events.
+    ///
+    /// Returns the number of events delivered.
+    pub async fn relay_once(&self) -> anyhow::Result<usize> {
+        let lease = chrono::Duration::from_std(self.lease)?;
+        let events = self.storage.claim_outbox(self.batch_size, lease).await?;
+        let mut delivered = 0;
+
+        for event in &events {
+            if let Err(e) = self.sink.deliver(event).await {
+                let attempts = event.attempts + 1;
+                if self.max_attempts.is_some_and(|max| attempts >= max) {
+                    error!(
+                        id = %event.id,
+                        kind = %event.kind,
+                        attempts = attempts,
+                        error = %e,
+                        "Outbox event dead-lettered"
+                    );
+                    self.storage
+                        .mark_outbox_dead(event.id, &e.to_string())
+                        .await?;
+                    continue;
+                }
+
+                warn!(
+                    id = %event.id,
+                    attempts = attempts,
+                    error = %e,
+                    "Outbox delivery failed"
+                );
+                self.storage
+                    .mark_outbox_failed(event.id, &e.to_string())
+                    .await?;
+                break;
+            }
+
+            self.storage.mark_outbox_delivered(event.id).await?;
+            delivered += 1;
+        }
+
+        Ok(delivered)
+    }
+
+    /// Start relaying in the background.
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = interval(self.poll_interval);
+
+            loop {
+                interval.tick().await;
+
+                if self.storage.is_degraded() {
+                    continue;
+                }
+
+                // Drain backlogs without waiting a full interval per batch
+                loop {
+                    match self.relay_once().await {
+                        Ok(n) if n == self.batch_size as usize => continue,
+                        Ok(_) => break,
+                        Err(e) => {
+                            warn!(error = %e, "Outbox relay failed");
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Decision;
+    use crate::storage::{
+        DecisionRecord, MockStorage, StorageRead, StorageWrite, TransactionRecord,
+    };
+    use parking_lot::Mutex;
+    use rust_decimal::Decimal;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use uuid::Uuid;
+
+    /// Sink that collects events and can be made to fail.
+    #[derive(Default)]
+    struct TestSink {
+        events: Mutex<Vec<OutboxEvent>>,
+        failing: AtomicBool,
+    }
+
+    #[async_trait]
+    impl OutboxSink for TestSink {
+        async fn deliver(&self, event: &OutboxEvent) -> anyhow::Result<()> {
+            if self.failing.load(Ordering::Relaxed) {
+                anyhow::bail!("sink unavailable");
+            }
+            self.events.lock().push(event.clone());
+            Ok(())
+        }
+    }
+
+    async fn record(storage: &MockStorage, decision: Decision) -> Uuid {
+        let subject_id = Uuid::new_v4();
+        storage
+            .record_outcome(
+                &TransactionRecord {
+                    subject_id,
+                    tx_type: "withdraw".to_string(),
+                    asset: "USDC".to_string(),
+                    amount: Decimal::new(100, 0),
+                    usd_value: Decimal::new(100, 0),
+                    dest_address: None,
+                    counterparty_geo: None,
+                    account_id: None,
+                },
+                &DecisionRecord {
+                    subject_id: Some(subject_id),
+                    request_id: Some("req-1".to_string()),
+                    request: serde_json::Value::Null,
+                    decision,
+                    decision_code: "TEST".to_string(),
+                    policy_version: "v1".to_string(),
+                    evidence: vec![],
+                    latency_ms: 1,
+                },
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_relay_delivers_in_order() {
+        let storage = Arc::new(MockStorage::new());
+        let sink = Arc::new(TestSink::default());
+        let relay = OutboxRelay::new(storage.clone(), sink.clone(), Duration::from_secs(1), 10);
+
+        let first = record(&storage, Decision::Allow).await;
+        let second = record(&storage, Decision::Review).await;
+
+        assert_eq!(relay.relay_once().await.unwrap(), 2);
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+
+        let events = sink.events.lock();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, "decision");
+        assert_eq!(events[0].payload["decision_id"], first.to_string());
+        assert_eq!(events[1].payload["decision_id"], second.to_string());
+        assert_eq!(events[1].payload["decision"], "REVIEW");
+        assert_eq!(storage.get_delivered_outbox().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery_is_retried() {
+        let storage = Arc::new(MockStorage::new());
+        let sink = Arc::new(TestSink::default());
+        let relay = OutboxRelay::new(storage.clone(), sink.clone(), Duration::from_secs(1), 10)
+            .with_lease(Duration::ZERO);
+
+        record(&storage, Decision::Allow).await;
+        record(&storage, Decision::Allow).await;
+
+        sink.failing.store(true, Ordering::Relaxed);
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+
+        let pending = storage.pending_outbox(10).await.unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].attempts, 1);
+        assert_eq!(pending[1].attempts, 0);
+
+        sink.failing.store(false, Ordering::Relaxed);
+        assert_eq!(relay.relay_once().await.unwrap(), 2);
+        assert!(storage.pending_outbox(10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_claimed_events_are_not_redelivered() {
+        let storage = Arc::new(MockStorage::new());
+        let sink = Arc::new(TestSink::default());
+        let relay = OutboxRelay::new(storage.clone(), sink.clone(), Duration::from_secs(1), 10);
+        let replica = OutboxRelay::new(storage.clone(), sink.clone(), Duration::from_secs(1), 10);
+
+        record(&storage, Decision::Allow).await;
+        record(&storage, Decision::Allow).await;
+
+        // A failure leaves the batch claimed, so another replica skips it
+        sink.failing.store(true, Ordering::Relaxed);
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+        sink.failing.store(false, Ordering::Relaxed);
+        assert_eq!(replica.relay_once().await.unwrap(), 0);
+        assert!(sink.events.lock().is_empty());
+        assert_eq!(storage.pending_outbox(10).await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failing_event_is_dead_lettered() {
+        let storage = Arc::new(MockStorage::new());
+        let sink = Arc::new(TestSink::default());
+        let relay = OutboxRelay::new(storage.clone(), sink.clone(), Duration::from_secs(1), 10)
+            .with_lease(Duration::ZERO)
+            .with_max_attempts(Some(2));
+
+        record(&storage, Decision::Allow).await;
+
+        sink.failing.store(true, Ordering::Relaxed);
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+        assert_eq!(storage.pending_outbox(10).await.unwrap()[0].attempts, 1);
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+
+        assert!(storage.pending_outbox(10).await.unwrap().is_empty());
+        let dead = storage.get_dead_outbox();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].attempts, 2);
+
+        sink.failing.store(false, Ordering::Relaxed);
+        assert_eq!(relay.relay_once().await.unwrap(), 0);
+        assert!(sink.events.lock().is_empty());
+    }
+}
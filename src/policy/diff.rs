@@ -0,0 +1,261 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::domain::Policy;
+
+/// Structured difference between two policies.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PolicyDiff {
+    pub from_version: String,
+    pub to_version: String,
+    /// IDs of rules only in the new policy
+    pub rules_added: Vec<String>,
+    /// IDs of rules only in the old policy
+    pub rules_removed: Vec<String>,
+    /// Rules present in both whose definition changed
+    pub rules_changed: Vec<FieldChange>,
+    /// Parameters whose value changed (including set/unset)
+    pub params_changed: Vec<FieldChange>,
+    /// Changes to top-level settings such as `monitor_only`,
+    /// `mitigations` and `decision_actions`
+    pub settings_changed: Vec<FieldChange>,
+}
+
+/// Old and new value of a single rule, parameter or setting.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub name: String,
+    pub from: serde_json::Value,
+    pub to: serde_json::Value,
+}
+
+impl PolicyDiff {
+    /// Compute the difference from `from` to `to`.
+    pub fn between(from: &Policy, to: &Policy) -> Self {
+        let from_rules: BTreeMap<&str, serde_json::Value> = from
+            .rules
+            .iter()
+            .map(|r| (r.id.as_str(), to_json(r)))
+            .collect();
+        let to_rules: BTreeMap<&str, serde_json::Value> = to
+            .rules
+            .iter()
+            .map(|r| (r.id.as_str(), to_json(r)))
+            .collect();
+
+        let rules_added = to_rules
+            .keys()
+            .filter(|id| !from_rules.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect();
+        let rules_removed = from_rules
+            .keys()
+            .filter(|id| !to_rules.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect();
+        let rules_changed = from_rules
+            .iter()
+            .filter_map(|(id, old)| {
+                let new = to_rules.get(id)?;
+                (old != new).then(|| FieldChange {
+                    name: id.to_string(),
+                    from: old.clone(),
+                    to: new.clone(),
+                })
+            })
+            .collect();
+
+        let mut settings_changed = Vec::new();
+        if from.monitor_only != to.monitor_only {
+            settings_changed.push(FieldChange {
+                name: "monitor_only".to_string(),
+                from: from.monitor_only.into(),
+                to: to.monitor_only.into(),
+            });
+        }
+        if from.mitigations != to.mitigations {
+            settings_changed.push(FieldChange {
+                name: "mitigations".to_string(),
+                from: to_json(&from.mitigations),
+                to: to_json(&to.mitigations),
+            });
+        }
+        if from.decision_actions != to.decision_actions {
+            settings_changed.push(FieldChange {
+                name: "decision_actions".to_string(),
+                from: to_json(&from.decision_actions),
+                to: to_json(&to.decision_actions),
+            });
+        }
+
+        PolicyDiff {
+            from_version: from.version.clone(),
+            to_version: to.version.clone(),
+            rules_added,
+            rules_removed,
+            rules_changed,
+            params_changed: object_changes(&to_json(&from.params), &to_json(&to.params)),
+            settings_changed,
+        }
+    }
+
+    /// Returns true if nothing but the version changed.
+    pub fn is_empty(&self) -> bool {
+        self.rules_added.is_empty()
+            && self.rules_removed.is_empty()
+            && self.rules_changed.is_empty()
+            && self.params_changed.is_empty()
+            && self.settings_changed.is_empty()
+    }
+
+    /// One-line description of what changed, for logs.
+    pub fn summary(&self) -> String {
+        let names = |changes: &[FieldChange]| {
+            changes
+                .iter()
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        let mut parts = Vec::new();
+        if !self.rules_added.is_empty() {
+            parts.push(format!("rules added: {}", self.rules_added.join(", ")));
+        }
+        if !self.rules_removed.is_empty() {
+            parts.push(format!("rules removed: {}", self.rules_removed.join(", ")));
+        }
+        if !self.rules_changed.is_empty() {
+            parts.push(format!("rules changed: {}", names(&self.rules_changed)));
+        }
+        if !self.params_changed.is_empty() {
+            parts.push(format!("params changed: {}", names(&self.params_changed)));
+        }
+        if !self.settings_changed.is_empty() {
+            parts.push(format!(
+                "settings changed: {}",
+                names(&self.settings_changed)
+            ));
+        }
+
+        if parts.is_empty() {
+            "no changes".to_string()
+        } else {
+            parts.join("; ")
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+/// Compare the top-level fields of two JSON objects.
+fn object_changes(from: &serde_json::Value, to: &serde_json::Value) -> Vec<FieldChange> {
+    let empty = serde_json::Map::new();
+    let from = from.as_object().unwrap_or(&empty);
+    let to = to.as_object().unwrap_or(&empty);
+
+    let mut names: Vec<&String> = from.keys().chain(to.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let old = from.get(name).cloned().unwrap_or_default();
+            let new = to.get(name).cloned().unwrap_or_default();
+            (old != new).then(|| FieldChange {
+                name: name.clone(),
+                from: old,
+                to: new,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(yaml: &str) -> Policy {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_policy_diff() {
+        let from = policy(
+            r#"
+policy_version: "v1"
+params:
+  daily_volume_limit_usd: 50000
+  structuring_small_count: 5
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+  - id: R2_JURISDICTION
+    type: jurisdiction_block
+    action: REJECT_FATAL
+    blocked_countries: ["IR"]
+  - id: R5_STRUCTURING
+    type: structuring_small_tx
+    action: REVIEW
+"#,
+        );
+        let to = policy(
+            r#"
+policy_version: "v2"
+monitor_only: true
+params:
+  daily_volume_limit_usd: 100000
+  structuring_small_count: 5
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+  - id: R2_JURISDICTION
+    type: jurisdiction_block
+    action: REJECT_FATAL
+    blocked_countries: ["IR", "KP"]
+  - id: R4_DAILY
+    type: daily_usd_volume
+    action: HOLD_AUTO
+"#,
+        );
+
+        let diff = PolicyDiff::between(&from, &to);
+
+        assert_eq!(diff.from_version, "v1");
+        assert_eq!(diff.to_version, "v2");
+        assert_eq!(diff.rules_added, vec!["R4_DAILY"]);
+        assert_eq!(diff.rules_removed, vec!["R5_STRUCTURING"]);
+        assert_eq!(diff.rules_changed.len(), 1);
+        assert_eq!(diff.rules_changed[0].name, "R2_JURISDICTION");
+        assert_eq!(diff.params_changed.len(), 1);
+        assert_eq!(diff.params_changed[0].name, "daily_volume_limit_usd");
+        assert_eq!(diff.settings_changed[0].name, "monitor_only");
+        assert!(!diff.is_empty());
+        assert_eq!(
+            diff.summary(),
+            "rules added: R4_DAILY; rules removed: R5_STRUCTURING; \
+             rules changed: R2_JURISDICTION; params changed: daily_volume_limit_usd; \
+             settings changed: monitor_only"
+        );
+    }
+
+    #[test]
+    fn test_identical_policies() {
+        let yaml = r#"
+policy_version: "v1"
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+"#;
+        let diff = PolicyDiff::between(&policy(yaml), &policy(yaml));
+
+        assert!(diff.is_empty());
+        assert_eq!(diff.summary(), "no changes");
+    }
+}
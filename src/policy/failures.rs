@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+use super::diff::PolicyDiff;
+
+/// Policy candidate rejected by a load or reload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FailedPolicy {
+    /// Version of the candidate, if the file could be parsed
+    pub version: Option<String>,
+    /// Version that stayed active, if any
+    pub active_version: Option<String>,
+    pub first_failed_at: DateTime<Utc>,
+    pub last_failed_at: DateTime<Utc>,
+    /// Consecutive checks that rejected this candidate
+    pub attempts: u32,
+    /// Every validation error, or the load error if it could not be validated
+    pub errors: Vec<String>,
+    /// Changes from the active policy, if the candidate could be parsed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<PolicyDiff>,
+}
+
+/// The most recent rejected policy candidates, newest first.
+///
+/// A candidate rejected on every reload check is kept as one entry with a
+/// growing attempt count.
+#[derive(Debug)]
+pub struct FailedPolicyLog {
+    capacity: usize,
+    entries: Mutex<VecDeque<FailedPolicy>>,
+}
+
+impl FailedPolicyLog {
+    /// Keep up to `capacity` rejected candidates.
+    pub fn new(capacity: usize) -> Self {
+        FailedPolicyLog {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Record a rejected candidate. Returns false if it repeats the most
+    /// recent failure.
+    pub fn record(
+        &self,
+        version: Option<String>,
+        active_version: Option<String>,
+        errors: Vec<String>,
+        diff: Option<PolicyDiff>,
+    ) -> bool {
+        let now = Utc::now();
+        let mut entries = self.entries.lock();
+
+        if let Some(latest) = entries.front_mut() {
+            if latest.version == version
+                && latest.active_version == active_version
+                && latest.errors == errors
+            {
+                latest.last_failed_at = now;
+                latest.attempts += 1;
+                return false;
+            }
+        }
+
+        if self.capacity == 0 {
+            return true;
+        }
+        entries.truncate(self.capacity - 1);
+        entries.push_front(FailedPolicy {
+            version,
+            active_version,
+            first_failed_at: now,
+            last_failed_at: now,
+            attempts: 1,
+            errors,
+            diff,
+        });
+        true
+    }
+
+    /// Retained failures, newest first.
+    pub fn list(&self) -> Vec<FailedPolicy> {
+        self.entries.lock().iter().cloned().collect()
+    }
+}
+
+impl Default for FailedPolicyLog {
+    fn default() -> Self {
+        FailedPolicyLog::new(20)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(message: &str) -> Vec<String> {
+        vec![message.to_string()]
+    }
+
+    #[test]
+    fn test_repeated_failure_kept_once() {
+        let log = FailedPolicyLog::new(5);
+        let active = Some("v1".to_string());
+
+        assert!(log.record(Some("v2".to_string()), active.clone(), errors("bad"), None));
+        assert!(!log.record(Some("v2".to_string()), active.clone(), errors("bad"), None));
+
+        let failed = log.list();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].attempts, 2);
+        assert_eq!(failed[0].version.as_deref(), Some("v2"));
+
+        // A different error for the same version is a new candidate
+        assert!(log.record(Some("v2".to_string()), active, errors("worse"), None));
+        assert_eq!(log.list().len(), 2);
+    }
+
+    #[test]
+    fn test_oldest_failures_dropped() {
+        let log = FailedPolicyLog::new(2);
+        for version in ["v2", "v3", "v4"] {
+            log.record(Some(version.to_string()), None, errors("bad"), None);
+        }
+
+        let versions: Vec<_> = log.list().into_iter().map(|f| f.version.unwrap()).collect();
+        assert_eq!(versions, vec!["v4", "v3"]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Fan-out of rule set changes to subsystems that keep state derived from
+//! the rules.
+//!
+//! Request handlers, the sweeper and the watchdog read the latest rule set
+//! from the policy watch channel each time they use it, so they never see a
+//! stale one. Subsystems that hold per-rule or per-policy state, such as the
+//! rule SLA monitor's windows, the distinct-count sketches and the active
+//! policy metrics, are told about each change instead, so that state follows
+//! the policy rather than lingering for rules that were removed.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::debug;
+
+use crate::observability::MetricsRegistry;
+use crate::rules::{DistinctSketches, RuleSet, RuleSlaMonitor};
+
+/// A subsystem told when the active rule set changes.
+pub trait RulesetListener: Send + Sync {
+    /// Called with the rule set in force when the fan-out starts, then with
+    /// each one activated after it.
+    fn ruleset_changed(&self, ruleset: &RuleSet);
+}
+
+/// Forwards rule set changes from the policy watch channel to listeners.
+#[derive(Default)]
+pub struct RulesetFanout {
+    listeners: Vec<Arc<dyn RulesetListener>>,
+}
+
+impl RulesetFanout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tell `listener` about rule set changes.
+    pub fn with_listener(mut self, listener: Arc<dyn RulesetListener>) -> Self {
+        self.listeners.push(listener);
+        self
+    }
+
+    /// Notify listeners of the rule set in `rx`, then of every change until
+    /// the sender is dropped.
+    pub fn start(self, mut rx: watch::Receiver<Arc<RuleSet>>) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let ruleset = rx.borrow_and_update().clone();
+                self.notify(&ruleset);
+                if rx.changed().await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    fn notify(&self, ruleset: &RuleSet) {
+        debug!(
+            policy_version = %ruleset.policy_version,
+            listeners = self.listeners.len(),
+            "Notifying rule set listeners"
+        );
+        for listener in &self.listeners {
+            listener.ruleset_changed(ruleset);
+        }
+    }
+}
+
+impl RulesetListener for RuleSlaMonitor {
+    fn ruleset_changed(&self, ruleset: &RuleSet) {
+        let rule_ids: HashSet<&str> = ruleset.streaming.iter().map(|r| r.id()).collect();
+        self.retain_rules(|rule_id| rule_ids.contains(rule_id));
+    }
+}
+
+impl RulesetListener for DistinctSketches {
+    fn ruleset_changed(&self, ruleset: &RuleSet) {
+        let rule_ids: HashSet<&str> = ruleset.streaming.iter().map(|r| r.id()).collect();
+        self.retain_rules(|rule_id| rule_ids.contains(rule_id));
+    }
+}
+
+impl RulesetListener for MetricsRegistry {
+    fn ruleset_changed(&self, ruleset: &RuleSet) {
+        self.record_active_ruleset(
+            &ruleset.policy_version,
+            ruleset.inline.len(),
+            ruleset.streaming.len(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    #[derive(Default)]
+    struct Versions(Mutex<Vec<String>>);
+
+    impl RulesetListener for Versions {
+        fn ruleset_changed(&self, ruleset: &RuleSet) {
+            self.0.lock().push(ruleset.policy_version.clone());
+        }
+    }
+
+    fn ruleset(version: &str) -> Arc<RuleSet> {
+        Arc::new(RuleSet {
+            policy_version: version.to_string(),
+            ..RuleSet::empty()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_listeners_follow_changes() {
+        let (tx, rx) = watch::channel(ruleset("v1"));
+        let versions = Arc::new(Versions::default());
+        let metrics = Arc::new(MetricsRegistry::new());
+        let handle = RulesetFanout::new()
+            .with_listener(versions.clone())
+            .with_listener(metrics.clone())
+            .start(rx);
+
+        while versions.0.lock().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        tx.send(ruleset("v2")).unwrap();
+        drop(tx);
+        handle.await.unwrap();
+
+        assert_eq!(*versions.0.lock(), vec!["v1", "v2"]);
+        assert!(metrics
+            .to_prometheus()
+            .contains("riskr_policy_info{version=\"v2\"} 1"));
+    }
+}
@@ -1,18 +1,111 @@
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::watch;
 use tokio::time::interval;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
+use crate::domain::Policy;
+use crate::observability::{DecisionMix, MetricsRegistry};
+use crate::outbox::{LifecycleEvent, LifecycleEvents};
 use crate::rules::RuleSet;
+use crate::storage::Storage;
+
+use super::diff::PolicyDiff;
+use super::failures::FailedPolicyLog;
+use super::last_good::LastKnownGood;
+use super::loader::{validation_errors, PolicyError, PolicyLoader};
+use super::replay::{replay_recent, ReplaySummary};
+
+/// Guardrail applied to newly activated policies.
+#[derive(Debug, Clone, Copy)]
+pub struct BakeOptions {
+    /// How long a new policy is watched before it is kept
+    pub period: Duration,
+    /// Rise in the non-Allow rate over the previous policy that triggers
+    /// a rollback
+    pub max_non_allow_increase: f64,
+    /// Decisions needed under each policy before their rates are compared
+    pub min_decisions: u64,
+}
 
-use super::loader::PolicyLoader;
+/// A newly activated policy under observation.
+struct Bake {
+    version: String,
+    /// Policy and rule set restored on rollback
+    previous: (Policy, Arc<RuleSet>),
+    started: Instant,
+    at_cutover: DecisionMix,
+    /// Non-Allow rate under the previous policy, if enough decisions were made
+    baseline: Option<f64>,
+}
+
+#[derive(Debug, PartialEq)]
+enum BakeVerdict {
+    Pending,
+    Passed,
+    RollBack { rate: f64, baseline: f64 },
+}
+
+impl Bake {
+    fn verdict(&self, now: DecisionMix, options: &BakeOptions) -> BakeVerdict {
+        let mix = now.since(self.at_cutover);
+        if let Some(baseline) = self.baseline {
+            if mix.total >= options.min_decisions {
+                let rate = mix.non_allow_rate().unwrap_or(0.0);
+                if rate > baseline + options.max_non_allow_increase {
+                    return BakeVerdict::RollBack { rate, baseline };
+                }
+            }
+        }
+
+        if self.started.elapsed() >= options.period {
+            BakeVerdict::Passed
+        } else {
+            BakeVerdict::Pending
+        }
+    }
+}
 
 /// Watch for policy changes and broadcast updates.
+///
+/// New rule sets are built off the async workers while the current one keeps
+/// serving, then swapped in through the watch channel in one step. With
+/// [`with_bake`](Self::with_bake), a new policy is rolled back to the
+/// previous one if its non-Allow rate spikes during the bake period; the
+/// rejected version is not reloaded until the policy file changes version.
+/// Candidates that fail to load are logged once with their errors and a diff
+/// against the active policy, and kept in a [`FailedPolicyLog`].
+/// Activations, rejections, rollbacks and sanctions list changes are also
+/// emitted as lifecycle events. With [`with_replay`](Self::with_replay),
+/// recent requests are replayed under each new policy in the background to
+/// report how many decisions it changes. With
+/// [`with_last_known_good`](Self::with_last_known_good), each activated
+/// policy is saved, and the saved copy is served if the files cannot be
+/// loaded at startup.
 pub struct PolicyWatcher {
     loader: PolicyLoader,
     check_interval: Duration,
-    last_version: Option<String>,
+    last_policy: Option<Policy>,
+    /// Where activated policies are recorded (optional)
+    storage: Option<Arc<dyn Storage>>,
+    /// Bake guardrail and the decision counters it watches (optional)
+    bake: Option<(BakeOptions, Arc<MetricsRegistry>)>,
+    baking: Option<Bake>,
+    /// Recent requests replayed after each reload, and where the outcome is
+    /// reported (optional)
+    replay: Option<(u32, Arc<MetricsRegistry>)>,
+    /// Decision counts when the current policy was activated
+    activated_at: DecisionMix,
+    /// Version rolled back during its bake period
+    rejected_version: Option<String>,
+    /// Candidates that failed to load
+    failures: Arc<FailedPolicyLog>,
+    /// Where activated policies are saved for a failed startup (optional)
+    last_good: Option<LastKnownGood>,
+    events: LifecycleEvents,
+    /// Injects policy load failures
+    #[cfg(feature = "chaos")]
+    chaos: Option<Arc<crate::chaos::Chaos>>,
 }
 
 impl PolicyWatcher {
@@ -21,10 +114,69 @@ impl PolicyWatcher {
         PolicyWatcher {
             loader,
             check_interval,
-            last_version: None,
+            last_policy: None,
+            storage: None,
+            bake: None,
+            baking: None,
+            replay: None,
+            activated_at: DecisionMix::default(),
+            rejected_version: None,
+            failures: Arc::new(FailedPolicyLog::default()),
+            last_good: None,
+            events: LifecycleEvents::default(),
+            #[cfg(feature = "chaos")]
+            chaos: None,
         }
     }
 
+    /// Fail reloads while the chaos settings ask for it.
+    #[cfg(feature = "chaos")]
+    pub fn with_chaos(mut self, chaos: Arc<crate::chaos::Chaos>) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Watch each new policy for a bake period, rolling back if the
+    /// non-Allow rate rises too far above the previous policy's. Checks run
+    /// on the reload interval.
+    pub fn with_bake(mut self, options: BakeOptions, metrics: Arc<MetricsRegistry>) -> Self {
+        self.bake = Some((options, metrics));
+        self
+    }
+
+    /// After each reload, replay the `sample` most recently recorded
+    /// requests under the old and new policy and report the decisions that
+    /// changed. Needs [`with_storage`](Self::with_storage).
+    pub fn with_replay(mut self, sample: u32, metrics: Arc<MetricsRegistry>) -> Self {
+        self.replay = Some((sample, metrics));
+        self
+    }
+
+    /// Keep rejected candidates in `failures`, so they can be queried.
+    pub fn with_failure_log(mut self, failures: Arc<FailedPolicyLog>) -> Self {
+        self.failures = failures;
+        self
+    }
+
+    /// Save each activated policy and its sanctions list to `last_good`,
+    /// and serve the saved copy if the files fail to load at startup.
+    pub fn with_last_known_good(mut self, last_good: LastKnownGood) -> Self {
+        self.last_good = Some(last_good);
+        self
+    }
+
+    /// Emit policy and sanctions changes to `events`.
+    pub fn with_lifecycle(mut self, events: LifecycleEvents) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Record each activated policy in storage, keeping a history to diff against.
+    pub fn with_storage(mut self, storage: Arc<dyn Storage>) -> Self {
+        self.storage = Some(storage);
+        self
+    }
+
     /// Start watching for policy changes.
     ///
     /// Returns a receiver that will receive new RuleSet instances when
@@ -33,66 +185,391 @@ impl PolicyWatcher {
         // Load initial policy
         let initial_ruleset = match self.loader.load() {
             Ok((policy, ruleset)) => {
-                self.last_version = Some(policy.version.clone());
                 info!("Loaded initial policy version: {}", policy.version);
+                self.events.emit(LifecycleEvent::PolicyActivated {
+                    version: policy.version.clone(),
+                    previous_version: None,
+                });
+                self.save_last_good(&policy);
+                self.last_policy = Some(policy);
                 Arc::new(ruleset)
             }
             Err(e) => {
                 error!("Failed to load initial policy: {}", e);
-                Arc::new(RuleSet::empty())
+                self.record_failure(&e, self.loader.load_candidate().ok());
+                match self.load_last_good() {
+                    Some((policy, ruleset)) => {
+                        self.events.emit(LifecycleEvent::PolicyActivated {
+                            version: policy.version.clone(),
+                            previous_version: None,
+                        });
+                        self.last_policy = Some(policy);
+                        Arc::new(ruleset)
+                    }
+                    None => Arc::new(RuleSet::empty()),
+                }
             }
         };
 
         let (tx, rx) = watch::channel(initial_ruleset);
 
         let handle = tokio::spawn(async move {
+            if let Some(ref policy) = self.last_policy {
+                self.record_activation(policy).await;
+            }
+
             let mut interval = interval(self.check_interval);
 
             loop {
                 interval.tick().await;
 
-                match self.check_for_updates(&tx) {
-                    Ok(true) => info!("Policy reloaded successfully"),
-                    Ok(false) => {} // No changes
-                    Err(e) => warn!("Error checking for policy updates: {}", e),
+                if let Some(bake) = self.baking.take() {
+                    self.check_bake(bake, &tx).await;
+                }
+
+                // Rebuilding the sanctions bloom filter is CPU-bound and
+                // scales with list size, so keep it off the async workers
+                let loader = self.loader.clone();
+                let last_version = self.last_policy.as_ref().map(|p| p.version.clone());
+                let rejected_version = self.rejected_version.clone();
+                #[cfg(feature = "chaos")]
+                if self.chaos.as_ref().is_some_and(|c| c.policy_load_fault()) {
+                    warn!("Error checking for policy updates: chaos: injected policy load failure");
+                    continue;
+                }
+                let result = tokio::task::spawn_blocking(move || {
+                    check_for_updates(
+                        &loader,
+                        last_version.as_deref(),
+                        rejected_version.as_deref(),
+                    )
+                    .map_err(|e| (e, loader.load_candidate().ok().map(Box::new)))
+                })
+                .await;
+
+                match result {
+                    Ok(Ok(Some((policy, ruleset)))) => {
+                        match self.last_policy {
+                            Some(ref previous) => log_diff(&PolicyDiff::between(previous, &policy)),
+                            None => info!("Policy version changed: None -> {}", policy.version),
+                        }
+                        self.record_activation(&policy).await;
+                        self.save_last_good(&policy);
+                        let version = policy.version.clone();
+                        let previous_policy = self.last_policy.replace(policy);
+                        let ruleset = Arc::new(ruleset);
+                        let previous_ruleset = tx.send_replace(ruleset.clone());
+                        info!("Policy reloaded successfully");
+                        self.emit_activation(
+                            &version,
+                            previous_policy.as_ref(),
+                            &previous_ruleset,
+                            &ruleset,
+                        );
+                        self.start_replay(previous_ruleset.clone(), ruleset);
+                        self.start_bake(version, previous_policy, previous_ruleset);
+                    }
+                    Ok(Ok(None)) => {} // No changes
+                    Ok(Err((e, candidate))) => self.record_failure(&e, candidate.map(|c| *c)),
+                    Err(e) => error!("Policy reload task failed: {}", e),
                 }
             }
         });
 
         (rx, handle)
     }
+}
 
-    /// Check for policy updates and broadcast if changed.
-    fn check_for_updates(
+impl PolicyWatcher {
+    /// Begin baking a policy that was just activated.
+    fn start_bake(
         &mut self,
-        tx: &watch::Sender<Arc<RuleSet>>,
-    ) -> Result<bool, super::loader::PolicyError> {
-        let policy = self.loader.load_policy()?;
+        version: String,
+        previous_policy: Option<Policy>,
+        previous_ruleset: Arc<RuleSet>,
+    ) {
+        let Some((options, ref metrics)) = self.bake else {
+            return;
+        };
+        let now = metrics.decision_mix();
+        let under_previous = now.since(self.activated_at);
+        self.activated_at = now;
+
+        // Replacing a policy that is still baking keeps the last proven one
+        // as the rollback target
+        let (previous, baseline) = match self.baking.take() {
+            Some(bake) => (bake.previous, bake.baseline),
+            None => {
+                let Some(policy) = previous_policy else {
+                    return;
+                };
+                let baseline = if under_previous.total >= options.min_decisions {
+                    under_previous.non_allow_rate()
+                } else {
+                    None
+                };
+                ((policy, previous_ruleset), baseline)
+            }
+        };
+
+        match baseline {
+            Some(baseline) => info!(
+                version = %version,
+                bake_secs = options.period.as_secs(),
+                baseline_non_allow_rate = baseline,
+                "Baking new policy"
+            ),
+            None => warn!(
+                version = %version,
+                "Too few decisions under the previous policy; baking without rollback"
+            ),
+        }
+
+        self.baking = Some(Bake {
+            version,
+            previous,
+            started: Instant::now(),
+            at_cutover: now,
+            baseline,
+        });
+    }
+
+    /// Replay recent requests under a newly activated policy in the
+    /// background, if configured.
+    fn start_replay(&self, previous: Arc<RuleSet>, current: Arc<RuleSet>) {
+        let (Some((sample, metrics)), Some(storage)) = (self.replay.clone(), self.storage.clone())
+        else {
+            return;
+        };
+
+        tokio::spawn(async move {
+            match replay_recent(storage.as_ref(), &previous, &current, sample).await {
+                Ok(summary) => {
+                    metrics.record_policy_replay(
+                        summary.replayed,
+                        summary.escalated,
+                        summary.relaxed,
+                    );
+                    log_replay(&summary);
+                }
+                Err(e) => warn!(
+                    version = %current.policy_version,
+                    error = %e,
+                    "Failed to replay recent requests under new policy"
+                ),
+            }
+        });
+    }
+
+    /// Keep, pass or roll back the policy being baked.
+    async fn check_bake(&mut self, bake: Bake, tx: &watch::Sender<Arc<RuleSet>>) {
+        let Some((options, metrics)) = self.bake.clone() else {
+            return;
+        };
+
+        match bake.verdict(metrics.decision_mix(), &options) {
+            BakeVerdict::Pending => self.baking = Some(bake),
+            BakeVerdict::Passed => info!(version = %bake.version, "Policy passed bake period"),
+            BakeVerdict::RollBack { rate, baseline } => {
+                let (policy, ruleset) = bake.previous;
+                error!(
+                    version = %bake.version,
+                    restored = %policy.version,
+                    non_allow_rate = rate,
+                    baseline_non_allow_rate = baseline,
+                    "Non-Allow rate spiked during bake; rolling back policy"
+                );
+                metrics.record_policy_rollback();
+                self.events.emit(LifecycleEvent::PolicyRolledBack {
+                    version: bake.version.clone(),
+                    restored_version: policy.version.clone(),
+                    non_allow_rate: rate,
+                    baseline_non_allow_rate: baseline,
+                });
+
+                self.record_activation(&policy).await;
+                self.save_last_good(&policy);
+                let _ = tx.send_replace(ruleset);
+                self.last_policy = Some(policy);
+                self.rejected_version = Some(bake.version);
+                self.activated_at = metrics.decision_mix();
+            }
+        }
+    }
+
+    /// Log a candidate that failed to load and keep it in the failure log.
+    ///
+    /// `candidate` is the policy file parsed without validation, if it
+    /// parses. A candidate failing the same way as the last one is only
+    /// logged at debug level.
+    fn record_failure(&self, err: &PolicyError, candidate: Option<Policy>) {
+        let active = self.last_policy.as_ref();
+        let errors = match candidate.as_ref().map(validation_errors) {
+            Some(errors) if !errors.is_empty() => errors,
+            _ => vec![err.to_string()],
+        };
+        let version = candidate.as_ref().map(|p| p.version.clone());
+        let diff = candidate
+            .as_ref()
+            .zip(active)
+            .map(|(candidate, active)| PolicyDiff::between(active, candidate));
+        let diff_summary = diff.as_ref().map(PolicyDiff::summary);
+        let active_version = active.map(|p| p.version.clone());
+
+        if self.failures.record(
+            version.clone(),
+            active_version.clone(),
+            errors.clone(),
+            diff,
+        ) {
+            error!(
+                version = version.as_deref().unwrap_or("unknown"),
+                active_version = active_version.as_deref().unwrap_or("none"),
+                errors = ?errors,
+                diff = diff_summary.as_deref().unwrap_or("unavailable"),
+                "Rejected policy candidate"
+            );
+            self.events.emit(LifecycleEvent::PolicyRejected {
+                version,
+                active_version,
+                errors,
+            });
+        } else {
+            debug!(
+                version = version.as_deref().unwrap_or("unknown"),
+                "Policy candidate still rejected"
+            );
+        }
+    }
+
+    /// Emit the lifecycle events for a reload.
+    fn emit_activation(
+        &self,
+        version: &str,
+        previous_policy: Option<&Policy>,
+        previous: &RuleSet,
+        current: &RuleSet,
+    ) {
+        self.events.emit(LifecycleEvent::PolicyActivated {
+            version: version.to_string(),
+            previous_version: previous_policy.map(|p| p.version.clone()),
+        });
+        if previous.sanctions.version() != current.sanctions.version() {
+            self.events.emit(LifecycleEvent::SanctionsUpdated {
+                list: current.sanctions.name().to_string(),
+                version: current.sanctions.version().to_string(),
+                previous_version: previous.sanctions.version().to_string(),
+                entries: current.sanctions.len(),
+            });
+        }
+    }
+
+    /// Save a newly active policy and the current sanctions file as the
+    /// last known good, if configured.
+    fn save_last_good(&self, policy: &Policy) {
+        let Some(ref last_good) = self.last_good else {
+            return;
+        };
+        if let Err(e) = last_good.save(policy, self.loader.sanctions_path()) {
+            warn!(version = %policy.version, error = %e, "Failed to save last-known-good policy");
+        }
+    }
 
-        // Check if version changed
-        if self.last_version.as_ref() == Some(&policy.version) {
-            return Ok(false);
+    /// Load the last-known-good policy, if configured and saved.
+    fn load_last_good(&self) -> Option<(Policy, RuleSet)> {
+        let last_good = self.last_good.as_ref()?;
+        match last_good.load(&self.loader) {
+            Ok((policy, ruleset)) => {
+                warn!(
+                    version = %policy.version,
+                    "Serving last-known-good policy until the policy files load"
+                );
+                Some((policy, ruleset))
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to load last-known-good policy; serving no rules");
+                None
+            }
         }
+    }
 
-        // Reload full policy and sanctions
-        let (policy, ruleset) = self.loader.load()?;
+    /// Record a newly active policy in storage, if configured.
+    async fn record_activation(&self, policy: &Policy) {
+        if let Some(ref storage) = self.storage {
+            if let Err(e) = storage.set_active_policy(policy).await {
+                warn!(version = %policy.version, error = %e, "Failed to record active policy");
+            }
+        }
+    }
+}
+
+/// Log the change-log entry for a policy reload.
+fn log_diff(diff: &PolicyDiff) {
+    info!(
+        from = %diff.from_version,
+        to = %diff.to_version,
+        rules_added = ?diff.rules_added,
+        rules_removed = ?diff.rules_removed,
+        rules_changed = ?diff.rules_changed.iter().map(|c| &c.name).collect::<Vec<_>>(),
+        params_changed = ?diff.params_changed.iter().map(|c| &c.name).collect::<Vec<_>>(),
+        settings_changed = ?diff.settings_changed.iter().map(|c| &c.name).collect::<Vec<_>>(),
+        "Policy version changed"
+    );
+}
 
+/// Log how a new policy changed the decisions of replayed requests; any
+/// escalation is a warning.
+fn log_replay(summary: &ReplaySummary) {
+    if summary.escalated > 0 {
+        warn!(
+            from = %summary.from_version,
+            to = %summary.to_version,
+            replayed = summary.replayed,
+            escalated = summary.escalated,
+            relaxed = summary.relaxed,
+            escalation_rate = summary.escalation_rate().unwrap_or(0.0),
+            transitions = ?summary.transitions,
+            "New policy escalates recent decisions"
+        );
+    } else {
         info!(
-            "Policy version changed: {:?} -> {}",
-            self.last_version, policy.version
+            from = %summary.from_version,
+            to = %summary.to_version,
+            replayed = summary.replayed,
+            changed = summary.changed(),
+            transitions = ?summary.transitions,
+            "Replayed recent requests under new policy"
         );
+    }
+}
 
-        self.last_version = Some(policy.version);
-        let _ = tx.send(Arc::new(ruleset));
-
-        Ok(true)
+/// Load the policy and, if its version changed, rebuild the full rule set.
+///
+/// A version that was rolled back is ignored.
+fn check_for_updates(
+    loader: &PolicyLoader,
+    last_version: Option<&str>,
+    rejected_version: Option<&str>,
+) -> Result<Option<(Policy, RuleSet)>, PolicyError> {
+    let policy = loader.load_policy()?;
+
+    // Check if version changed
+    let version = Some(policy.version.as_str());
+    if last_version == version || rejected_version == version {
+        return Ok(None);
     }
+
+    // Reload full policy and sanctions
+    loader.load().map(Some)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::Decision;
+    use crate::storage::{MockStorage, StorageRead, StorageWrite};
     use std::io::Write;
+    use std::sync::atomic::Ordering;
     use tempfile::NamedTempFile;
 
     fn create_test_files() -> (NamedTempFile, NamedTempFile) {
@@ -136,6 +613,32 @@ rules:
         handle.abort();
     }
 
+    #[tokio::test]
+    async fn test_corrupt_policy_serves_last_known_good() {
+        let (policy_file, sanctions_file) = create_test_files();
+        let dir = tempfile::tempdir().unwrap();
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+
+        let watcher = PolicyWatcher::new(loader.clone(), Duration::from_secs(60))
+            .with_last_known_good(LastKnownGood::new(dir.path()));
+        let (_rx, handle) = watcher.start();
+        handle.abort();
+
+        std::fs::write(policy_file.path(), "policy_version: [").unwrap();
+        let watcher = PolicyWatcher::new(loader, Duration::from_secs(60))
+            .with_last_known_good(LastKnownGood::new(dir.path()));
+        let (rx, handle) = watcher.start();
+
+        let ruleset = rx.borrow();
+        assert_eq!(ruleset.policy_version, "v1");
+        assert_eq!(ruleset.inline.len(), 1);
+
+        handle.abort();
+    }
+
     #[tokio::test]
     async fn test_policy_watcher_detects_changes() {
         let (policy_file, sanctions_file) = create_test_files();
@@ -183,4 +686,295 @@ rules:
 
         handle.abort();
     }
+
+    #[tokio::test]
+    async fn test_policy_watcher_emits_lifecycle_events() {
+        let (policy_file, sanctions_file) = create_test_files();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+
+        let (events, mut events_rx) = LifecycleEvents::channel(16);
+        let watcher = PolicyWatcher::new(loader, Duration::from_millis(50)).with_lifecycle(events);
+        let (_rx, handle) = watcher.start();
+
+        let event = events_rx.recv().await.unwrap();
+        assert_eq!(event.kind, "lifecycle.policy_activated");
+        assert_eq!(event.payload["version"], "v1");
+
+        std::fs::write(sanctions_file.path(), "0xdead\n0xbeef\n").unwrap();
+        std::fs::write(
+            policy_file.path(),
+            r#"
+policy_version: "v2"
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+"#,
+        )
+        .unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(1), events_rx.recv())
+            .await
+            .expect("Timeout waiting for activation")
+            .unwrap();
+        assert_eq!(event.kind, "lifecycle.policy_activated");
+        assert_eq!(event.payload["version"], "v2");
+        assert_eq!(event.payload["previous_version"], "v1");
+
+        let event = events_rx.recv().await.unwrap();
+        assert_eq!(event.kind, "lifecycle.sanctions_updated");
+        assert_eq!(event.payload["entries"], 2);
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_policy_watcher_logs_rejected_candidate() {
+        let (policy_file, sanctions_file) = create_test_files();
+        let policy_path = policy_file.path().to_path_buf();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+
+        let failures = Arc::new(FailedPolicyLog::new(5));
+        let watcher = PolicyWatcher::new(loader, Duration::from_millis(20))
+            .with_failure_log(failures.clone());
+        let (rx, handle) = watcher.start();
+
+        std::fs::write(
+            &policy_path,
+            r#"
+policy_version: "v2"
+params:
+  window_timezone: "Mars/Olympus"
+  unusual_hours_min_share: 2.0
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+  - id: R4_DAILY
+    type: daily_usd_volume
+    action: HOLD_AUTO
+"#,
+        )
+        .unwrap();
+
+        // Let several reload checks reject the same candidate
+        tokio::time::sleep(Duration::from_millis(150)).await;
+
+        let failed = failures.list();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].attempts > 1);
+        assert_eq!(failed[0].version.as_deref(), Some("v2"));
+        assert_eq!(failed[0].active_version.as_deref(), Some("v1"));
+        assert_eq!(failed[0].errors.len(), 2);
+        let diff = failed[0].diff.as_ref().unwrap();
+        assert_eq!(diff.rules_added, vec!["R4_DAILY"]);
+
+        // The active policy keeps serving
+        assert_eq!(rx.borrow().policy_version, "v1");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_policy_watcher_records_activation() {
+        let (policy_file, sanctions_file) = create_test_files();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+
+        let storage = Arc::new(MockStorage::new());
+        let watcher =
+            PolicyWatcher::new(loader, Duration::from_secs(60)).with_storage(storage.clone());
+        let (_rx, handle) = watcher.start();
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while storage.get_policy("v1").await.unwrap().is_none() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timeout waiting for policy to be recorded");
+
+        let active = storage.get_active_policy().await.unwrap().unwrap();
+        assert_eq!(active.version, "v1");
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_policy_watcher_replays_recent_requests() {
+        let (policy_file, sanctions_file) = create_test_files();
+        let policy_path = policy_file.path().to_path_buf();
+
+        let storage = Arc::new(MockStorage::new());
+        storage
+            .record_decision(&crate::storage::DecisionRecord {
+                subject_id: None,
+                request_id: None,
+                request: serde_json::json!({
+                    "subject": { "user_id": "U1", "account_id": "A1", "geo_iso": "IR", "kyc_level": "L1" },
+                    "tx": { "type": "withdraw", "asset": "USDC", "usd_value": 100 }
+                }),
+                decision: Decision::Allow,
+                decision_code: "OK".to_string(),
+                policy_version: "v1".to_string(),
+                evidence: vec![],
+                latency_ms: 0,
+            })
+            .await
+            .unwrap();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+        let metrics = Arc::new(MetricsRegistry::new());
+        let watcher = PolicyWatcher::new(loader, Duration::from_millis(50))
+            .with_storage(storage.clone())
+            .with_replay(100, metrics.clone());
+        let (mut rx, handle) = watcher.start();
+
+        std::fs::write(
+            &policy_path,
+            r#"
+policy_version: "v2"
+rules:
+  - id: R2_JURISDICTION
+    type: jurisdiction_block
+    action: REJECT_FATAL
+    blocked_countries: ["IR"]
+"#,
+        )
+        .unwrap();
+        tokio::time::timeout(Duration::from_secs(1), rx.changed())
+            .await
+            .expect("Timeout waiting for policy change")
+            .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while metrics.policy_replay_requests.load(Ordering::Relaxed) == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Timeout waiting for replay");
+
+        assert_eq!(metrics.policy_replay_escalated.load(Ordering::Relaxed), 1);
+        assert_eq!(metrics.policy_replay_relaxed.load(Ordering::Relaxed), 0);
+
+        handle.abort();
+    }
+
+    fn bake_options(period: Duration) -> BakeOptions {
+        BakeOptions {
+            period,
+            max_non_allow_increase: 0.05,
+            min_decisions: 10,
+        }
+    }
+
+    #[test]
+    fn test_bake_verdict() {
+        let previous: Policy = serde_yaml::from_str("policy_version: v1\nrules: []").unwrap();
+        let at_cutover = DecisionMix {
+            total: 100,
+            non_allow: 10,
+        };
+        let bake = Bake {
+            version: "v2".to_string(),
+            previous: (previous, Arc::new(RuleSet::empty())),
+            started: Instant::now(),
+            at_cutover,
+            baseline: Some(0.1),
+        };
+        let options = bake_options(Duration::from_secs(60));
+        let after = |total, non_allow| DecisionMix {
+            total: at_cutover.total + total,
+            non_allow: at_cutover.non_allow + non_allow,
+        };
+
+        // Too few decisions to judge
+        assert_eq!(bake.verdict(after(5, 5), &options), BakeVerdict::Pending);
+        // Within the guardrail
+        assert_eq!(bake.verdict(after(20, 3), &options), BakeVerdict::Pending);
+        assert_eq!(
+            bake.verdict(after(20, 4), &options),
+            BakeVerdict::RollBack {
+                rate: 0.2,
+                baseline: 0.1
+            }
+        );
+        assert_eq!(
+            bake.verdict(after(20, 3), &bake_options(Duration::ZERO)),
+            BakeVerdict::Passed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_policy_watcher_rolls_back_on_spike() {
+        let (policy_file, sanctions_file) = create_test_files();
+        let policy_path = policy_file.path().to_path_buf();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+
+        let metrics = Arc::new(MetricsRegistry::new());
+        let watcher = PolicyWatcher::new(loader, Duration::from_millis(50))
+            .with_bake(bake_options(Duration::from_secs(60)), metrics.clone());
+        let (mut rx, handle) = watcher.start();
+
+        // Baseline under v1: nothing blocked
+        for _ in 0..10 {
+            metrics.record_decision(&Decision::Allow);
+        }
+
+        std::fs::write(
+            &policy_path,
+            r#"
+policy_version: "v2"
+rules:
+  - id: R2_JURISDICTION
+    type: jurisdiction_block
+    action: REJECT_FATAL
+    blocked_countries: ["US"]
+"#,
+        )
+        .unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), rx.changed())
+            .await
+            .expect("Timeout waiting for cutover")
+            .unwrap();
+        assert_eq!(rx.borrow_and_update().policy_version, "v2");
+
+        // v2 rejects everything
+        for _ in 0..10 {
+            metrics.record_decision(&Decision::RejectFatal);
+        }
+
+        tokio::time::timeout(Duration::from_secs(1), rx.changed())
+            .await
+            .expect("Timeout waiting for rollback")
+            .unwrap();
+        assert_eq!(rx.borrow_and_update().policy_version, "v1");
+        assert_eq!(metrics.policy_rollbacks_total.load(Ordering::Relaxed), 1);
+
+        // The rejected version is not reloaded
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!rx.has_changed().unwrap());
+
+        handle.abort();
+    }
 }
@@ -0,0 +1,192 @@
+//! Last-known-good copy of the active policy and sanctions list.
+//!
+//! Each policy that activates is written to a directory together with a
+//! copy of the sanctions file it was built with and the copy's SHA-256. If
+//! the policy or sanctions file cannot be loaded at startup, the copy is
+//! loaded instead, so a corrupt deploy keeps the previous rules in force
+//! rather than starting with none.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::domain::Policy;
+use crate::rules::RuleSet;
+
+use super::loader::{PolicyError, PolicyLoader};
+
+const POLICY_FILE: &str = "policy.yaml";
+const SANCTIONS_FILE: &str = "sanctions";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// What a saved copy holds, written last so a partial save is not loaded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LastKnownGoodManifest {
+    pub policy_version: String,
+    /// Hex SHA-256 of the sanctions copy
+    pub sanctions_sha256: String,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// Directory holding the last policy and sanctions list that loaded.
+#[derive(Debug, Clone)]
+pub struct LastKnownGood {
+    dir: PathBuf,
+}
+
+impl LastKnownGood {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        LastKnownGood { dir: dir.into() }
+    }
+
+    /// Save `policy` and the sanctions file at `sanctions_path`, replacing
+    /// the previous copy.
+    pub fn save(&self, policy: &Policy, sanctions_path: &str) -> Result<(), PolicyError> {
+        fs::create_dir_all(&self.dir)?;
+        let sanctions = fs::read(sanctions_path)?;
+        let manifest = LastKnownGoodManifest {
+            policy_version: policy.version.clone(),
+            sanctions_sha256: sha256_hex(&sanctions),
+            saved_at: Utc::now(),
+        };
+
+        // The old manifest is removed first, so a copy half replaced by a
+        // failed save is never loaded
+        match fs::remove_file(self.dir.join(MANIFEST_FILE)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        write_atomic(&self.dir.join(SANCTIONS_FILE), &sanctions)?;
+        write_atomic(
+            &self.dir.join(POLICY_FILE),
+            serde_yaml::to_string(policy)?.as_bytes(),
+        )?;
+        let manifest = serde_json::to_vec_pretty(&manifest)
+            .map_err(|e| PolicyError::Validation(format!("manifest: {}", e)))?;
+        write_atomic(&self.dir.join(MANIFEST_FILE), &manifest)
+    }
+
+    /// Manifest of the saved copy.
+    pub fn manifest(&self) -> Result<LastKnownGoodManifest, PolicyError> {
+        let content = fs::read(self.dir.join(MANIFEST_FILE))?;
+        serde_json::from_slice(&content)
+            .map_err(|e| PolicyError::Validation(format!("manifest: {}", e)))
+    }
+
+    /// Load the saved copy with `loader`'s options, after checking the
+    /// sanctions copy against its recorded hash.
+    pub fn load(&self, loader: &PolicyLoader) -> Result<(Policy, RuleSet), PolicyError> {
+        let manifest = self.manifest()?;
+        let sanctions_path = self.dir.join(SANCTIONS_FILE);
+        let sanctions_sha256 = sha256_hex(&fs::read(&sanctions_path)?);
+        if sanctions_sha256 != manifest.sanctions_sha256 {
+            return Err(PolicyError::Validation(format!(
+                "sanctions copy has SHA-256 {}, manifest records {}",
+                sanctions_sha256, manifest.sanctions_sha256
+            )));
+        }
+
+        let (policy, ruleset) = loader
+            .at(
+                self.dir.join(POLICY_FILE).to_string_lossy(),
+                sanctions_path.to_string_lossy(),
+            )
+            .load()?;
+        if policy.version != manifest.policy_version {
+            return Err(PolicyError::Validation(format!(
+                "policy copy has version {}, manifest records {}",
+                policy.version, manifest.policy_version
+            )));
+        }
+        Ok((policy, ruleset))
+    }
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Write `content` to a temporary file beside `path` and rename it into
+/// place.
+fn write_atomic(path: &Path, content: &[u8]) -> Result<(), PolicyError> {
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICY: &str = r#"
+policy_version: "v1"
+params:
+  daily_volume_limit_usd: 50000
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+"#;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy_path = dir.path().join("policy.yaml");
+        let sanctions_path = dir.path().join("sanctions.txt");
+        fs::write(&policy_path, POLICY).unwrap();
+        fs::write(&sanctions_path, "0xdead\n").unwrap();
+        let loader = PolicyLoader::new(
+            policy_path.to_string_lossy(),
+            sanctions_path.to_string_lossy(),
+        );
+        let (policy, _) = loader.load().unwrap();
+
+        let last_good = LastKnownGood::new(dir.path().join("last-good"));
+        last_good
+            .save(&policy, &sanctions_path.to_string_lossy())
+            .unwrap();
+        assert_eq!(last_good.manifest().unwrap().policy_version, "v1");
+
+        // The originals can be corrupted without affecting the copy
+        fs::write(&policy_path, "policy_version: [").unwrap();
+        fs::remove_file(&sanctions_path).unwrap();
+        assert!(loader.load().is_err());
+
+        let (policy, ruleset) = last_good.load(&loader).unwrap();
+        assert_eq!(policy.version, "v1");
+        assert_eq!(ruleset.inline.len(), 1);
+        assert!(ruleset.sanctions.contains("0xdead"));
+    }
+
+    #[test]
+    fn test_tampered_copy_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let sanctions_path = dir.path().join("sanctions.txt");
+        fs::write(&sanctions_path, "0xdead\n").unwrap();
+        let policy: Policy = serde_yaml::from_str(POLICY).unwrap();
+
+        let last_good = LastKnownGood::new(dir.path().join("last-good"));
+        last_good
+            .save(&policy, &sanctions_path.to_string_lossy())
+            .unwrap();
+        fs::write(
+            dir.path().join("last-good").join(SANCTIONS_FILE),
+            "0xbeef\n",
+        )
+        .unwrap();
+
+        let loader = PolicyLoader::new("unused", "unused");
+        let err = last_good
+            .load(&loader)
+            .err()
+            .expect("tampered sanctions should be rejected");
+        assert!(err.to_string().contains("SHA-256"));
+    }
+}
@@ -1,10 +1,17 @@
+use sha2::{Digest, Sha256};
 use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use thiserror::Error;
+use tracing::{info, warn};
 
-use crate::domain::Policy;
-use crate::rules::RuleSet;
+use crate::domain::{KycTier, Policy, RuleType, WindowKey};
+use crate::geoip::IpIntelligence;
+use crate::identity::IdentityProvider;
+use crate::rules::compiled_sanctions::CompiledSanctions;
+use crate::rules::sanctions::normalize_entry;
+use crate::rules::{DistinctSketches, RuleSet, SanctionsList};
 
 /// Errors that can occur during policy loading.
 #[derive(Error, Debug)]
@@ -21,19 +28,27 @@ pub enum PolicyError {
 
 /// Load a policy from a YAML file.
 pub fn load_policy(path: impl AsRef<Path>) -> Result<Policy, PolicyError> {
-    let content = fs::read_to_string(path)?;
-    let policy: Policy = serde_yaml::from_str(&content)?;
+    let policy = parse_policy(path)?;
 
     validate_policy(&policy)?;
 
     Ok(policy)
 }
 
+/// Parse a policy YAML file without validating it.
+fn parse_policy(path: impl AsRef<Path>) -> Result<Policy, PolicyError> {
+    let content = fs::read_to_string(path)?;
+    Ok(serde_yaml::from_str(&content)?)
+}
+
 /// Load sanctions list from a text file.
 ///
 /// Expected format: one address per line, # for comments.
 pub fn load_sanctions(path: impl AsRef<Path>) -> Result<HashSet<String>, PolicyError> {
-    let content = fs::read_to_string(path)?;
+    Ok(parse_sanctions(&fs::read_to_string(path)?))
+}
+
+fn parse_sanctions(content: &str) -> HashSet<String> {
     let mut sanctions = HashSet::new();
 
     for line in content.lines() {
@@ -44,39 +59,378 @@ pub fn load_sanctions(path: impl AsRef<Path>) -> Result<HashSet<String>, PolicyE
             continue;
         }
 
-        // Normalize to lowercase
-        sanctions.insert(line.to_lowercase());
+        // Normalize to lowercase; tagged entries are "<address> <tag>"
+        sanctions.insert(normalize_entry(line));
+    }
+
+    sanctions
+}
+
+/// Open the compiled form of a text sanctions list from the cache `dir`,
+/// compiling it there first if this content has not been seen before.
+///
+/// Entries are keyed by a hash of the raw file, so an unchanged list is
+/// mapped in milliseconds on restart or reload instead of being parsed and
+/// indexed again. The compiled form does not depend on the policy.
+/// Entries for other contents are removed once a new one is written.
+fn open_cached_sanctions(
+    path: impl AsRef<Path>,
+    dir: impl AsRef<Path>,
+) -> Result<SanctionsList, PolicyError> {
+    let dir = dir.as_ref();
+    let content = fs::read(path)?;
+    let key: String = Sha256::digest(&content)[..16]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let cached = dir.join(format!("sanctions-{}.bin", key));
+
+    if !CompiledSanctions::is_compiled(&cached) {
+        fs::create_dir_all(dir)?;
+        let sanctions = parse_sanctions(&String::from_utf8_lossy(&content));
+        SanctionsList::compile(&sanctions, &cached)?;
+        info!(
+            entries = sanctions.len(),
+            path = %cached.display(),
+            "Compiled sanctions list into cache"
+        );
+        prune_sanctions_cache(dir, &cached);
     }
 
-    Ok(sanctions)
+    Ok(SanctionsList::open_compiled(&cached)?)
+}
+
+/// Remove cached sanctions lists other than `keep`. Lists still mapped by a
+/// running rule set stay readable until they are dropped.
+fn prune_sanctions_cache(dir: &Path, keep: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path != keep && name.starts_with("sanctions-") && name.ends_with(".bin") {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!(error = %e, path = %path.display(), "Failed to prune sanctions cache");
+            }
+        }
+    }
 }
 
 /// Validate policy configuration.
 fn validate_policy(policy: &Policy) -> Result<(), PolicyError> {
+    let errors = validation_errors(policy);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(PolicyError::Validation(errors.join("; ")))
+    }
+}
+
+/// Every validation problem with a policy, in check order.
+pub fn validation_errors(policy: &Policy) -> Vec<String> {
+    let mut errors = Vec::new();
+
     if policy.version.is_empty() {
-        return Err(PolicyError::Validation(
-            "Policy version cannot be empty".to_string(),
-        ));
+        errors.push("Policy version cannot be empty".to_string());
+    }
+
+    let ranking = &policy.params.kyc_tiers;
+    if ranking.tiers().is_empty() {
+        errors.push("kyc_tiers cannot be empty".to_string());
+    }
+    let mut seen_tiers = HashSet::new();
+    for tier in ranking.tiers() {
+        if !seen_tiers.insert(tier) {
+            errors.push(format!("Duplicate KYC tier in kyc_tiers: {}", tier));
+        }
+    }
+    let unranked = |tier: &KycTier| ranking.rank(tier).is_none();
+    for tier in policy.params.kyc_tier_caps_usd.keys() {
+        if unranked(tier) {
+            errors.push(format!(
+                "kyc_tier_caps_usd references a tier missing from kyc_tiers: {}",
+                tier
+            ));
+        }
+    }
+
+    for (tier, by_group) in &policy.params.limit_matrix {
+        if unranked(tier) {
+            errors.push(format!(
+                "limit_matrix references a tier missing from kyc_tiers: {}",
+                tier
+            ));
+        }
+        for (group, factor) in by_group {
+            if !policy.params.country_groups.contains_key(group) {
+                errors.push(format!(
+                    "limit_matrix.{} references unknown country group: {}",
+                    tier, group
+                ));
+            }
+            if *factor <= rust_decimal::Decimal::ZERO {
+                errors.push(format!(
+                    "limit_matrix.{}.{} must be positive, got {}",
+                    tier, group, factor
+                ));
+            }
+        }
+    }
+
+    if let Some(ref tz) = policy.params.window_timezone {
+        if tz.parse::<chrono_tz::Tz>().is_err() {
+            errors.push(format!(
+                "window_timezone is not a known IANA timezone: {}",
+                tz
+            ));
+        }
+    }
+
+    if let Some(fp_rate) = policy.params.sanctions_bloom_fp_rate {
+        if !(fp_rate > 0.0 && fp_rate < 1.0) {
+            errors.push(format!(
+                "sanctions_bloom_fp_rate must be between 0 and 1, got {}",
+                fp_rate
+            ));
+        }
+    }
+
+    for (asset, threshold) in &policy.params.structuring_small_usd_by_asset {
+        if *threshold <= rust_decimal::Decimal::ZERO {
+            errors.push(format!(
+                "structuring_small_usd_by_asset for {} must be positive, got {}",
+                asset, threshold
+            ));
+        }
+    }
+
+    if let Some(max) = policy.params.amount_sanity_max_usd {
+        if max <= rust_decimal::Decimal::ZERO {
+            errors.push(format!(
+                "amount_sanity_max_usd must be positive, got {}",
+                max
+            ));
+        }
+    }
+
+    for (asset, band) in &policy.params.asset_price_bands_usd {
+        if band.min <= rust_decimal::Decimal::ZERO || band.min > band.max {
+            errors.push(format!(
+                "asset_price_bands_usd for {} must have 0 < min <= max, got {} to {}",
+                asset, band.min, band.max
+            ));
+        }
+    }
+
+    if let Some(multiplier) = policy.params.structuring_adaptive_multiplier {
+        if multiplier <= rust_decimal::Decimal::ZERO {
+            errors.push(format!(
+                "structuring_adaptive_multiplier must be positive, got {}",
+                multiplier
+            ));
+        }
+    }
+
+    if policy.params.country_tx_window_hours == Some(0) {
+        errors.push("country_tx_window_hours must be positive".to_string());
+    }
+
+    if let Some(ratio) = policy.params.chain_hop_ratio {
+        if ratio <= rust_decimal::Decimal::ZERO {
+            errors.push(format!("chain_hop_ratio must be positive, got {}", ratio));
+        }
+    }
+
+    if policy.params.chain_hop_window_minutes == Some(0) {
+        errors.push("chain_hop_window_minutes must be positive".to_string());
+    }
+
+    if let Some(share) = policy.params.unusual_hours_min_share {
+        if !(0.0..=1.0).contains(&share) {
+            errors.push(format!(
+                "unusual_hours_min_share must be between 0 and 1, got {}",
+                share
+            ));
+        }
+    }
+
+    for rule in &policy.rules {
+        for (tx_type, requirement) in &rule.min_kyc_tiers {
+            if unranked(&requirement.tier) {
+                errors.push(format!(
+                    "Rule {} requires a tier missing from kyc_tiers for {}: {}",
+                    rule.id, tx_type, requirement.tier
+                ));
+            }
+        }
+        if let Some(ref feature) = rule.experimental {
+            if feature.is_empty() || feature.starts_with('-') {
+                errors.push(format!(
+                    "Rule {} has an invalid experimental feature name: {:?}",
+                    rule.id, feature
+                ));
+            }
+        }
+        if rule.window_key == WindowKey::Account
+            && !matches!(
+                rule.rule_type,
+                RuleType::DailyUsdVolume | RuleType::WeeklyUsdVolume | RuleType::MonthlyUsdVolume
+            )
+        {
+            errors.push(format!(
+                "Rule {} cannot use account windows; only USD volume rules can",
+                rule.id
+            ));
+        }
+        if rule.rule_type == RuleType::AmountSanity && rule.sweep {
+            errors.push(format!(
+                "Rule {} cannot sweep; sweeps evaluate without the transaction's value",
+                rule.id
+            ));
+        }
+        if rule.rule_type == RuleType::KycVerification
+            && policy.params.kyc_verification_min_usd.is_none()
+        {
+            errors.push(format!(
+                "Rule {} requires kyc_verification_min_usd",
+                rule.id
+            ));
+        }
+        if rule.rule_type == RuleType::DistinctDestinations
+            && policy.params.distinct_destinations_max.is_none()
+        {
+            errors.push(format!(
+                "Rule {} requires distinct_destinations_max",
+                rule.id
+            ));
+        }
+        if rule.rule_type == RuleType::IpJurisdiction
+            && rule.blocked_countries.is_empty()
+            && policy.params.sanctioned_asns.is_empty()
+        {
+            errors.push(format!(
+                "Rule {} requires blocked_countries or sanctioned_asns",
+                rule.id
+            ));
+        }
+        if rule.rule_type == RuleType::CompositeRisk {
+            match rule.signals {
+                None => errors.push(format!("Rule {} requires signals", rule.id)),
+                Some(ref signals) => {
+                    for group in &signals.country_groups {
+                        if !policy.params.country_groups.contains_key(group) {
+                            errors.push(format!(
+                                "Rule {} references unknown country group: {}",
+                                rule.id, group
+                            ));
+                        }
+                    }
+                    if let Some(ref tier) = signals.max_kyc_tier {
+                        if unranked(tier) {
+                            errors.push(format!(
+                                "Rule {} caps a tier missing from kyc_tiers: {}",
+                                rule.id, tier
+                            ));
+                        }
+                    }
+                    let configured = signals.configured();
+                    if configured == 0 {
+                        errors.push(format!("Rule {} has no signals configured", rule.id));
+                    } else if let Some(min) = signals.min_signals {
+                        if min == 0 || min > configured {
+                            errors.push(format!(
+                                "Rule {} min_signals must be between 1 and {}, got {}",
+                                rule.id, configured, min
+                            ));
+                        }
+                    }
+                }
+            }
+        }
     }
 
     // Check for duplicate rule IDs
     let mut seen_ids = HashSet::new();
     for rule in &policy.rules {
         if !seen_ids.insert(&rule.id) {
-            return Err(PolicyError::Validation(format!(
-                "Duplicate rule ID: {}",
-                rule.id
-            )));
+            errors.push(format!("Duplicate rule ID: {}", rule.id));
         }
     }
 
-    Ok(())
+    let rule_ids: HashSet<&String> = policy.rules.iter().map(|r| &r.id).collect();
+    let mut seen_precedence = HashSet::new();
+    for mitigation in &policy.mitigations {
+        // Mitigation IDs share the evidence namespace with rule IDs
+        if !seen_ids.insert(&mitigation.id) {
+            errors.push(format!("Duplicate rule ID: {}", mitigation.id));
+        }
+        if !seen_precedence.insert(mitigation.precedence) {
+            errors.push(format!(
+                "Mitigation {} shares precedence {} with another mitigation",
+                mitigation.id, mitigation.precedence
+            ));
+        }
+        if mitigation.when.is_empty() {
+            errors.push(format!(
+                "Mitigation {} has no conditions configured",
+                mitigation.id
+            ));
+        }
+        if mitigation.cap.is_fatal() {
+            errors.push(format!(
+                "Mitigation {} cannot cap at {}",
+                mitigation.id, mitigation.cap
+            ));
+        }
+        for tier in &mitigation.when.kyc_tiers {
+            if unranked(tier) {
+                errors.push(format!(
+                    "Mitigation {} references a tier missing from kyc_tiers: {}",
+                    mitigation.id, tier
+                ));
+            }
+        }
+        for rule_id in &mitigation.rules {
+            if !rule_ids.contains(rule_id) {
+                errors.push(format!(
+                    "Mitigation {} references unknown rule: {}",
+                    mitigation.id, rule_id
+                ));
+            }
+        }
+    }
+
+    for (decision, plan) in &policy.decision_actions {
+        for action in plan {
+            if action.action.trim().is_empty() {
+                errors.push(format!(
+                    "Decision action for {} has no action name",
+                    decision
+                ));
+            }
+            if action.duration_secs == Some(0) {
+                errors.push(format!(
+                    "Decision action {} for {} has a zero duration",
+                    action.action, decision
+                ));
+            }
+        }
+    }
+
+    errors
 }
 
 /// Policy loader that manages policy and sanctions loading.
+#[derive(Debug, Clone)]
 pub struct PolicyLoader {
     policy_path: String,
     sanctions_path: String,
+    compile_cache: Option<PathBuf>,
+    identity: Option<Arc<dyn IdentityProvider>>,
+    sketches: Arc<DistinctSketches>,
+    ip_intel: Option<Arc<dyn IpIntelligence>>,
 }
 
 impl PolicyLoader {
@@ -85,17 +439,105 @@ impl PolicyLoader {
         PolicyLoader {
             policy_path: policy_path.into(),
             sanctions_path: sanctions_path.into(),
+            compile_cache: None,
+            identity: None,
+            sketches: Arc::new(DistinctSketches::default()),
+            ip_intel: None,
+        }
+    }
+
+    /// A loader with the same options reading other policy and sanctions
+    /// files.
+    pub fn at(&self, policy_path: impl Into<String>, sanctions_path: impl Into<String>) -> Self {
+        PolicyLoader {
+            policy_path: policy_path.into(),
+            sanctions_path: sanctions_path.into(),
+            ..self.clone()
         }
     }
 
+    /// Serve text sanctions lists from compiled copies kept in `dir`.
+    pub fn with_compile_cache(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.compile_cache = Some(dir.into());
+        self
+    }
+
+    /// Check tiers for `kyc_verification` rules with `provider`.
+    pub fn with_identity_provider(mut self, provider: Arc<dyn IdentityProvider>) -> Self {
+        self.identity = Some(provider);
+        self
+    }
+
+    /// Count distinct values for rules such as `distinct_destinations` in
+    /// `sketches`. Every rule set this loader builds shares them.
+    pub fn with_distinct_sketches(mut self, sketches: Arc<DistinctSketches>) -> Self {
+        self.sketches = sketches;
+        self
+    }
+
+    /// Look client IPs up for `ip_jurisdiction` rules with `intel`.
+    pub fn with_ip_intelligence(mut self, intel: Arc<dyn IpIntelligence>) -> Self {
+        self.ip_intel = Some(intel);
+        self
+    }
+
     /// Load policy and sanctions, returning a RuleSet.
+    ///
+    /// A sanctions file produced by `riskr sanctions compile` is memory
+    /// mapped rather than read into memory. With a compile cache, text
+    /// lists are compiled once per content and mapped from the cache.
     pub fn load(&self) -> Result<(Policy, RuleSet), PolicyError> {
         let policy = load_policy(&self.policy_path)?;
-        let sanctions = load_sanctions(&self.sanctions_path)?;
 
+        if CompiledSanctions::is_compiled(&self.sanctions_path) {
+            let sanctions = SanctionsList::open_compiled(&self.sanctions_path)?;
+            let ruleset = RuleSet::from_policy_with_list(&policy, sanctions);
+            return Ok(self.finish(policy, ruleset));
+        }
+
+        if let Some(dir) = &self.compile_cache {
+            match open_cached_sanctions(&self.sanctions_path, dir) {
+                Ok(sanctions) => {
+                    let ruleset = RuleSet::from_policy_with_list(&policy, sanctions);
+                    return Ok(self.finish(policy, ruleset));
+                }
+                // Fall back to building in memory; only the startup cost is lost
+                Err(e) => warn!(error = %e, "Sanctions compile cache unavailable"),
+            }
+        }
+
+        let sanctions = load_sanctions(&self.sanctions_path)?;
         let ruleset = RuleSet::from_policy(&policy, sanctions);
+        Ok(self.finish(policy, ruleset))
+    }
 
-        Ok((policy, ruleset))
+    /// Add the rules that need the identity provider, shared sketches or IP
+    /// databases.
+    fn finish(&self, policy: Policy, ruleset: RuleSet) -> (Policy, RuleSet) {
+        let ruleset = ruleset.with_distinct_sketches(&policy, self.sketches.clone());
+        let ruleset = match &self.identity {
+            Some(provider) => ruleset.with_identity_provider(&policy, provider.clone()),
+            None => {
+                for rule in &policy.rules {
+                    if rule.rule_type == RuleType::KycVerification {
+                        warn!(rule_id = %rule.id, "No identity provider configured; rule skipped");
+                    }
+                }
+                ruleset
+            }
+        };
+        let ruleset = match &self.ip_intel {
+            Some(intel) => ruleset.with_ip_intelligence(&policy, intel.clone()),
+            None => {
+                for rule in &policy.rules {
+                    if rule.rule_type == RuleType::IpJurisdiction {
+                        warn!(rule_id = %rule.id, "No GeoIP database configured; rule skipped");
+                    }
+                }
+                ruleset
+            }
+        };
+        (policy, ruleset)
     }
 
     /// Load only the policy (without rebuilding rules).
@@ -103,6 +545,12 @@ impl PolicyLoader {
         load_policy(&self.policy_path)
     }
 
+    /// Parse the policy file without validating it, to describe a
+    /// candidate that failed to load.
+    pub fn load_candidate(&self) -> Result<Policy, PolicyError> {
+        parse_policy(&self.policy_path)
+    }
+
     /// Load only the sanctions list.
     pub fn load_sanctions(&self) -> Result<HashSet<String>, PolicyError> {
         load_sanctions(&self.sanctions_path)
@@ -117,6 +565,11 @@ impl PolicyLoader {
     pub fn sanctions_path(&self) -> &str {
         &self.sanctions_path
     }
+
+    /// Get the distinct-count sketches shared by the rule sets built.
+    pub fn distinct_sketches(&self) -> &Arc<DistinctSketches> {
+        &self.sketches
+    }
 }
 
 #[cfg(test)]
@@ -201,6 +654,31 @@ rules: []
         assert!(result.unwrap_err().to_string().contains("version"));
     }
 
+    #[test]
+    fn test_policy_validation_reports_every_error() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: ""
+params:
+  window_timezone: "Mars/Olympus"
+rules: []
+"#
+        )
+        .unwrap();
+
+        let loader = PolicyLoader::new(file.path().to_string_lossy(), "unused");
+        let candidate = loader.load_candidate().unwrap();
+        let errors = validation_errors(&candidate);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("version"));
+        assert!(errors[1].contains("window_timezone"));
+
+        let message = load_policy(file.path()).unwrap_err().to_string();
+        assert!(message.contains("version") && message.contains("window_timezone"));
+    }
+
     #[test]
     fn test_policy_validation_duplicate_ids() {
         let mut file = NamedTempFile::new().unwrap();
@@ -224,6 +702,261 @@ rules:
         assert!(result.unwrap_err().to_string().contains("Duplicate"));
     }
 
+    #[test]
+    fn test_policy_validation_bloom_fp_rate() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+params:
+  sanctions_bloom_fp_rate: 1.5
+rules: []
+"#
+        )
+        .unwrap();
+
+        let result = load_policy(file.path());
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("sanctions_bloom_fp_rate"));
+    }
+
+    #[test]
+    fn test_policy_validation_limit_matrix() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+params:
+  country_groups:
+    high_risk: [NG]
+  limit_matrix:
+    L1: {{ high_rsk: 0.5 }}
+rules: []
+"#
+        )
+        .unwrap();
+
+        let result = load_policy(file.path());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("unknown country group: high_rsk"));
+    }
+
+    #[test]
+    fn test_policy_validation_kyc_tiers() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+params:
+  kyc_tiers: [L0, L1, L2, L3, L4, CORPORATE]
+  kyc_tier_caps_usd:
+    l3: 250000
+    corporate: 1000000
+rules:
+  - id: R6_MIN_KYC
+    type: min_kyc_tier
+    action: HOLD_AUTO
+    min_kyc_tiers:
+      otc_trade: {{ tier: L4 }}
+"#
+        )
+        .unwrap();
+        let policy = load_policy(file.path()).unwrap();
+        assert_eq!(
+            policy.params.kyc_tier_caps_usd.get("CORPORATE"),
+            Some(&rust_decimal::Decimal::new(1000000, 0))
+        );
+
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+params:
+  kyc_tier_caps_usd:
+    L3: 250000
+rules: []
+"#
+        )
+        .unwrap();
+        let result = load_policy(file.path());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("kyc_tier_caps_usd references a tier missing from kyc_tiers: L3"));
+    }
+
+    #[test]
+    fn test_policy_validation_window_timezone() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+params:
+  window_mode: calendar_day
+  window_timezone: America/Nowhere
+rules: []
+"#
+        )
+        .unwrap();
+
+        let result = load_policy(file.path());
+        assert!(result.unwrap_err().to_string().contains("window_timezone"));
+    }
+
+    #[test]
+    fn test_policy_validation_window_key() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+params:
+  daily_volume_limit_usd: 50000
+  structuring_small_usd: 1000
+  structuring_small_count: 5
+rules:
+  - id: R4_DAILY_ACCOUNT
+    type: daily_usd_volume
+    action: HOLD_AUTO
+    window_key: account
+  - id: R5_STRUCTURING
+    type: structuring_small_tx
+    action: REVIEW
+    window_key: account
+"#
+        )
+        .unwrap();
+
+        let err = load_policy(file.path()).unwrap_err().to_string();
+        assert!(err.contains("Rule R5_STRUCTURING cannot use account windows"));
+        assert!(!err.contains("R4_DAILY_ACCOUNT"));
+    }
+
+    #[test]
+    fn test_policy_validation_composite_risk() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+params:
+  country_groups:
+    medium_risk: [NG, PK]
+rules:
+  - id: R9_WEAK_SIGNALS
+    type: composite_risk
+    action: REVIEW
+    signals:
+      country_groups: [medium_risk, elevated]
+      max_kyc_tier: L3
+      new_subject: true
+      min_signals: 4
+  - id: R10_NO_SIGNALS
+    type: composite_risk
+    action: REVIEW
+"#
+        )
+        .unwrap();
+
+        let err = load_policy(file.path()).unwrap_err().to_string();
+        assert!(err.contains("R9_WEAK_SIGNALS references unknown country group: elevated"));
+        assert!(err.contains("R9_WEAK_SIGNALS caps a tier missing from kyc_tiers: L3"));
+        assert!(err.contains("R9_WEAK_SIGNALS min_signals must be between 1 and 3, got 4"));
+        assert!(err.contains("Rule R10_NO_SIGNALS requires signals"));
+    }
+
+    #[test]
+    fn test_policy_validation_mitigations() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+rules:
+  - id: R4_DAILY_USD
+    type: daily_usd_volume
+    action: HOLD_AUTO
+mitigations:
+  - id: M1_CORPORATE
+    precedence: 10
+    cap: REVIEW
+    when:
+      account_ids: [CORP-1]
+    rules: [R4_DAILY_USD, R5_STRUCTURING]
+  - id: M2_EMPTY
+    precedence: 10
+    cap: REJECT_FATAL
+    when: {{}}
+  - id: R4_DAILY_USD
+    precedence: 20
+    cap: REVIEW
+    when:
+      kyc_tiers: [L3]
+"#
+        )
+        .unwrap();
+
+        let err = load_policy(file.path()).unwrap_err().to_string();
+        assert!(err.contains("Mitigation M1_CORPORATE references unknown rule: R5_STRUCTURING"));
+        assert!(err.contains("Mitigation M2_EMPTY shares precedence 10"));
+        assert!(err.contains("Mitigation M2_EMPTY has no conditions configured"));
+        assert!(err.contains("Mitigation M2_EMPTY cannot cap at REJECT_FATAL"));
+        assert!(err.contains("Duplicate rule ID: R4_DAILY_USD"));
+        assert!(
+            err.contains("Mitigation R4_DAILY_USD references a tier missing from kyc_tiers: L3")
+        );
+    }
+
+    #[test]
+    fn test_policy_decision_actions() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+decision_actions:
+  HOLD_AUTO:
+    - action: freeze_withdrawals
+      duration_secs: 86400
+    - action: notify
+      target: ops
+"#
+        )
+        .unwrap();
+        let policy = load_policy(file.path()).unwrap();
+        let plan = &policy.decision_actions[&crate::domain::Decision::HoldAuto];
+        assert_eq!(plan[0].action, "freeze_withdrawals");
+        assert_eq!(plan[0].duration_secs, Some(86400));
+        assert_eq!(plan[1].target.as_deref(), Some("ops"));
+
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+decision_actions:
+  REVIEW:
+    - action: " "
+    - action: freeze_withdrawals
+      duration_secs: 0
+"#
+        )
+        .unwrap();
+        let err = load_policy(file.path()).unwrap_err().to_string();
+        assert!(err.contains("Decision action for REVIEW has no action name"));
+        assert!(err.contains("Decision action freeze_withdrawals for REVIEW has a zero duration"));
+    }
+
     #[test]
     fn test_policy_loader() {
         let mut policy_file = NamedTempFile::new().unwrap();
@@ -255,4 +988,267 @@ rules:
         assert_eq!(ruleset.inline.len(), 1);
         assert_eq!(ruleset.policy_version, "test-1.0");
     }
+
+    #[test]
+    fn test_policy_loader_identity_provider() {
+        #[derive(Debug)]
+        struct NoProvider;
+
+        #[async_trait::async_trait]
+        impl IdentityProvider for NoProvider {
+            async fn kyc_tier(&self, _user_id: &str) -> anyhow::Result<Option<KycTier>> {
+                Ok(None)
+            }
+        }
+
+        let mut policy_file = NamedTempFile::new().unwrap();
+        writeln!(
+            policy_file,
+            r#"
+policy_version: "test-1.0"
+params:
+  kyc_verification_min_usd: 10000
+rules:
+  - id: R11_KYC_VERIFY
+    type: kyc_verification
+    action: REVIEW
+"#
+        )
+        .unwrap();
+        let mut sanctions_file = NamedTempFile::new().unwrap();
+        writeln!(sanctions_file, "0xdead").unwrap();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+        let (_, ruleset) = loader.load().unwrap();
+        assert!(ruleset.streaming.is_empty());
+
+        let (_, ruleset) = loader
+            .with_identity_provider(Arc::new(NoProvider))
+            .load()
+            .unwrap();
+        assert_eq!(ruleset.streaming.len(), 1);
+        assert_eq!(ruleset.streaming[0].id(), "R11_KYC_VERIFY");
+
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+rules:
+  - id: R11_KYC_VERIFY
+    type: kyc_verification
+    action: REVIEW
+"#
+        )
+        .unwrap();
+        let err = load_policy(file.path()).unwrap_err().to_string();
+        assert!(err.contains("Rule R11_KYC_VERIFY requires kyc_verification_min_usd"));
+    }
+
+    #[test]
+    fn test_policy_loader_distinct_sketches() {
+        let mut policy_file = NamedTempFile::new().unwrap();
+        writeln!(
+            policy_file,
+            r#"
+policy_version: "test-1.0"
+params:
+  distinct_destinations_max: 5
+rules:
+  - id: R12_DISTINCT_DEST
+    type: distinct_destinations
+    action: REVIEW
+"#
+        )
+        .unwrap();
+        let mut sanctions_file = NamedTempFile::new().unwrap();
+        writeln!(sanctions_file, "0xdead").unwrap();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+        let (_, ruleset) = loader.load().unwrap();
+        assert_eq!(ruleset.streaming.len(), 1);
+        assert_eq!(ruleset.streaming[0].id(), "R12_DISTINCT_DEST");
+
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+rules:
+  - id: R12_DISTINCT_DEST
+    type: distinct_destinations
+    action: REVIEW
+"#
+        )
+        .unwrap();
+        let err = load_policy(file.path()).unwrap_err().to_string();
+        assert!(err.contains("Rule R12_DISTINCT_DEST requires distinct_destinations_max"));
+    }
+
+    #[test]
+    fn test_policy_loader_ip_intelligence() {
+        use crate::geoip::MaxMindDatabase;
+
+        let mut policy_file = NamedTempFile::new().unwrap();
+        writeln!(
+            policy_file,
+            r#"
+policy_version: "test-1.0"
+params:
+  sanctioned_asns: [64666]
+rules:
+  - id: R13_IP_JURISDICTION
+    type: ip_jurisdiction
+    action: REJECT_FATAL
+    blocked_countries: [IR, KP]
+"#
+        )
+        .unwrap();
+        let mut sanctions_file = NamedTempFile::new().unwrap();
+        writeln!(sanctions_file, "0xdead").unwrap();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_file.path().to_string_lossy(),
+        );
+        let (_, ruleset) = loader.load().unwrap();
+        assert!(ruleset.inline.is_empty());
+
+        let (_, ruleset) = loader
+            .with_ip_intelligence(Arc::new(MaxMindDatabase::open(None, None).unwrap()))
+            .load()
+            .unwrap();
+        assert_eq!(ruleset.inline.len(), 1);
+        assert_eq!(ruleset.inline[0].id(), "R13_IP_JURISDICTION");
+
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+rules:
+  - id: R13_IP_JURISDICTION
+    type: ip_jurisdiction
+    action: REJECT_FATAL
+"#
+        )
+        .unwrap();
+        let err = load_policy(file.path()).unwrap_err().to_string();
+        assert!(
+            err.contains("Rule R13_IP_JURISDICTION requires blocked_countries or sanctioned_asns")
+        );
+    }
+
+    #[test]
+    fn test_policy_amount_sanity() {
+        let mut file = NamedTempFile::new().unwrap();
+        writeln!(
+            file,
+            r#"
+policy_version: "test"
+params:
+  amount_sanity_max_usd: 0
+  asset_price_bands_usd:
+    BTC: {{ min: 1000000, max: 1000 }}
+rules:
+  - id: R14_AMOUNT_SANITY
+    type: amount_sanity
+    action: REJECT_FATAL
+    sweep: true
+"#
+        )
+        .unwrap();
+        let err = load_policy(file.path()).unwrap_err().to_string();
+        assert!(err.contains("amount_sanity_max_usd must be positive"));
+        assert!(err.contains("asset_price_bands_usd for BTC must have 0 < min <= max"));
+        assert!(err.contains("Rule R14_AMOUNT_SANITY cannot sweep"));
+    }
+
+    #[test]
+    fn test_policy_loader_compiled_sanctions() {
+        let mut policy_file = NamedTempFile::new().unwrap();
+        writeln!(
+            policy_file,
+            r#"
+policy_version: "test-1.0"
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+"#
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let sanctions_path = dir.path().join("sanctions.bin");
+        SanctionsList::compile(&HashSet::from(["0xdead".to_string()]), &sanctions_path).unwrap();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_path.to_string_lossy(),
+        );
+
+        let (_, ruleset) = loader.load().unwrap();
+
+        assert!(ruleset.sanctions.is_compiled());
+        assert!(ruleset.sanctions.contains("0xDEAD"));
+    }
+
+    #[test]
+    fn test_policy_loader_compile_cache() {
+        let mut policy_file = NamedTempFile::new().unwrap();
+        writeln!(
+            policy_file,
+            r#"
+policy_version: "test-1.0"
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+"#
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("cache");
+        let sanctions_path = dir.path().join("sanctions.txt");
+        fs::write(&sanctions_path, "0xDEAD\n0xbeef\n").unwrap();
+
+        let loader = PolicyLoader::new(
+            policy_file.path().to_string_lossy(),
+            sanctions_path.to_string_lossy(),
+        )
+        .with_compile_cache(&cache);
+        let cached = || fs::read_dir(&cache).unwrap().count();
+
+        let (_, ruleset) = loader.load().unwrap();
+        assert!(ruleset.sanctions.is_compiled());
+        assert!(ruleset.sanctions.contains("0xdead"));
+        assert_eq!(
+            ruleset.sanctions.version(),
+            SanctionsList::new(
+                loader.load_sanctions().unwrap(),
+                crate::rules::BloomOptions::default()
+            )
+            .version()
+        );
+        assert_eq!(cached(), 1);
+
+        // Unchanged contents reuse the entry
+        loader.load().unwrap();
+        assert_eq!(cached(), 1);
+
+        // New contents replace it
+        fs::write(&sanctions_path, "0xbad\n").unwrap();
+        let (_, ruleset) = loader.load().unwrap();
+        assert!(ruleset.sanctions.contains("0xBAD"));
+        assert!(!ruleset.sanctions.contains("0xdead"));
+        assert_eq!(cached(), 1);
+    }
 }
@@ -1,5 +1,15 @@
+mod diff;
+mod failures;
+mod fanout;
 mod hot_reload;
+mod last_good;
 mod loader;
+mod replay;
 
-pub use hot_reload::PolicyWatcher;
-pub use loader::{load_policy, load_sanctions, PolicyLoader};
+pub use diff::{FieldChange, PolicyDiff};
+pub use failures::{FailedPolicy, FailedPolicyLog};
+pub use fanout::{RulesetFanout, RulesetListener};
+pub use hot_reload::{BakeOptions, PolicyWatcher};
+pub use last_good::{LastKnownGood, LastKnownGoodManifest};
+pub use loader::{load_policy, load_sanctions, validation_errors, PolicyLoader};
+pub use replay::{replay_recent, ReplaySummary};
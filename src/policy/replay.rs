@@ -0,0 +1,219 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::api::request::DecisionRequest;
+use crate::domain::{Decision, TxEvent};
+use crate::inline_engine;
+use crate::rules::RuleSet;
+use crate::storage::Storage;
+
+/// Decision changes found by replaying recent requests under a new policy.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ReplaySummary {
+    pub from_version: String,
+    pub to_version: String,
+    /// Recorded requests decided under both policies
+    pub replayed: u32,
+    /// Recorded decisions without a replayable request
+    pub skipped: u32,
+    /// Requests decided more severely under the new policy
+    pub escalated: u32,
+    /// Requests decided more leniently under the new policy
+    pub relaxed: u32,
+    /// Changed outcomes counted by `FROM->TO`
+    pub transitions: BTreeMap<String, u32>,
+}
+
+impl ReplaySummary {
+    /// Requests whose decision changed.
+    pub fn changed(&self) -> u32 {
+        self.escalated + self.relaxed
+    }
+
+    /// Fraction of replayed requests decided more severely, if any were
+    /// replayed.
+    pub fn escalation_rate(&self) -> Option<f64> {
+        (self.replayed > 0).then(|| self.escalated as f64 / self.replayed as f64)
+    }
+
+    fn record(&mut self, before: Decision, after: Decision) {
+        self.replayed += 1;
+        if after == before {
+            return;
+        }
+        if after > before {
+            self.escalated += 1;
+        } else {
+            self.relaxed += 1;
+        }
+        *self
+            .transitions
+            .entry(format!("{}->{}", before, after))
+            .or_default() += 1;
+    }
+}
+
+/// Decide the `sample` most recently recorded requests under both the
+/// previous and the current rule set, counting the decisions that change.
+///
+/// Both rule sets see the same stored history, so differences come from the
+/// policy alone rather than from activity since the request was recorded.
+/// Kill-switches are not applied; storage is only read.
+pub async fn replay_recent(
+    storage: &dyn Storage,
+    previous: &RuleSet,
+    current: &RuleSet,
+    sample: u32,
+) -> anyhow::Result<ReplaySummary> {
+    let mut summary = ReplaySummary {
+        from_version: previous.policy_version.clone(),
+        to_version: current.policy_version.clone(),
+        ..ReplaySummary::default()
+    };
+
+    for stored in storage.get_recent_decisions(sample).await? {
+        let Ok(request) = serde_json::from_value::<DecisionRequest>(stored.record.request) else {
+            summary.skipped += 1;
+            continue;
+        };
+        let event = request.to_tx_event();
+        let subject_id = stored.record.subject_id.unwrap_or_else(Uuid::nil);
+
+        let before = decide(previous, &event, subject_id, storage).await;
+        let after = decide(current, &event, subject_id, storage).await;
+        summary.record(before, after);
+    }
+
+    Ok(summary)
+}
+
+/// Decide an event with a rule set. Like the decision endpoint, fatal
+/// inline results skip the streaming rules and rules that fail are ignored.
+async fn decide(
+    ruleset: &RuleSet,
+    event: &TxEvent,
+    subject_id: Uuid,
+    storage: &dyn Storage,
+) -> Decision {
+    let features = &ruleset.features;
+    let verdict = inline_engine::evaluate(
+        &ruleset.inline_for(event),
+        event,
+        |id| !features.allows(id, &event.features),
+        |_, _| {},
+    );
+    let mut decision = verdict.decision;
+    if decision.is_fatal() {
+        return decision;
+    }
+
+    let mut evidence = verdict.evidence;
+    for rule in ruleset.streaming_for(event).iter() {
+        if !features.allows(rule.id(), &event.features) {
+            continue;
+        }
+        if let Ok(result) = rule.evaluate(event, subject_id, storage).await {
+            if result.hit {
+                decision = decision.max(result.decision);
+                evidence.extend(result.evidence);
+            }
+        }
+    }
+
+    ruleset
+        .mitigations
+        .apply(event, &mut decision, &mut evidence);
+    decision
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Policy;
+    use crate::storage::{DecisionRecord, MockStorage, StorageWrite};
+    use std::collections::HashSet;
+
+    fn ruleset(yaml: &str) -> RuleSet {
+        let policy: Policy = serde_yaml::from_str(yaml).unwrap();
+        RuleSet::from_policy(&policy, HashSet::from(["0xdead".to_string()]))
+    }
+
+    async fn record_request(storage: &MockStorage, geo_iso: &str) {
+        let request = serde_json::json!({
+            "subject": { "user_id": "U1", "account_id": "A1", "geo_iso": geo_iso, "kyc_level": "L1" },
+            "tx": { "type": "withdraw", "asset": "USDC", "usd_value": 100 }
+        });
+        storage
+            .record_decision(&DecisionRecord {
+                subject_id: None,
+                request_id: None,
+                request,
+                decision: Decision::Allow,
+                decision_code: "OK".to_string(),
+                policy_version: "v1".to_string(),
+                evidence: vec![],
+                latency_ms: 0,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_replay_counts_changes() {
+        let previous = ruleset(
+            r#"
+policy_version: "v1"
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+"#,
+        );
+        let current = ruleset(
+            r#"
+policy_version: "v2"
+rules:
+  - id: R2_JURISDICTION
+    type: jurisdiction_block
+    action: REJECT_FATAL
+    blocked_countries: ["NG"]
+"#,
+        );
+
+        let storage = MockStorage::new();
+        record_request(&storage, "US").await;
+        record_request(&storage, "NG").await;
+        record_request(&storage, "NG").await;
+        storage
+            .record_decision(&DecisionRecord {
+                subject_id: None,
+                request_id: None,
+                request: serde_json::Value::Null,
+                decision: Decision::Allow,
+                decision_code: "OK".to_string(),
+                policy_version: "v1".to_string(),
+                evidence: vec![],
+                latency_ms: 0,
+            })
+            .await
+            .unwrap();
+
+        let summary = replay_recent(&storage, &previous, &current, 10)
+            .await
+            .unwrap();
+        assert_eq!(summary.from_version, "v1");
+        assert_eq!(summary.to_version, "v2");
+        assert_eq!(summary.replayed, 3);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.escalated, 2);
+        assert_eq!(summary.relaxed, 0);
+        assert_eq!(summary.transitions["ALLOW->REJECT_FATAL"], 2);
+        assert_eq!(summary.escalation_rate(), Some(2.0 / 3.0));
+
+        let summary = replay_recent(&storage, &current, &previous, 2)
+            .await
+            .unwrap();
+        assert_eq!(summary.replayed + summary.skipped, 2);
+    }
+}
@@ -0,0 +1,402 @@
+//! Synthetic monitoring of a running instance.
+//!
+//! `riskr probe` fires canary decision requests at a target instance and
+//! checks each decision against what the canary expects, so on-call gets a
+//! semantic health signal (sanctioned addresses are still rejected, clean
+//! withdrawals still allowed) rather than just a live process. Canaries are
+//! written in YAML:
+//!
+//! ```yaml
+//! canaries:
+//!   - name: sanctioned withdrawal is rejected
+//!     weight: 1
+//!     request:
+//!       subject: { user_id: CANARY1, account_id: CANARY, geo_iso: US, kyc_level: L2 }
+//!       tx: { type: withdraw, asset: USDC, amount: "1", usd_value: 1, dest_address: "0xdead" }
+//!     expect:
+//!       decision: REJECT_FATAL
+//!       rule: R1_OFAC
+//! ```
+//!
+//! Pass, fail and error counts and latency per canary are served in
+//! Prometheus format.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use hyper_util::client::legacy::connect::HttpConnector;
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::TokioExecutor;
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::api::request::DecisionRequest;
+use crate::api::request_id::REQUEST_ID_HEADER;
+use crate::domain::{Decision, Evidence};
+
+/// Largest decision response body read.
+const MAX_RESPONSE_BYTES: usize = 1024 * 1024;
+
+/// Upper bounds of the latency histogram buckets, in seconds.
+const LATENCY_BUCKETS: [f64; 8] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+
+/// A canary file.
+#[derive(Debug, Deserialize)]
+pub struct CanaryFile {
+    pub canaries: Vec<Canary>,
+}
+
+/// A decision request with a known expected outcome.
+#[derive(Debug, Deserialize)]
+pub struct Canary {
+    pub name: String,
+    /// Share of probes sent as this canary, relative to the others
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+    pub request: DecisionRequest,
+    pub expect: Expectation,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+/// Expected decision for a canary.
+#[derive(Debug, Deserialize)]
+pub struct Expectation {
+    pub decision: Decision,
+    /// Rule that must be among the evidence, if any
+    #[serde(default)]
+    pub rule: Option<String>,
+}
+
+/// Outcome of one probe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeOutcome {
+    /// The decision matched the expectation
+    Pass,
+    /// A decision was returned but did not match
+    Fail(String),
+    /// No decision was returned
+    Error(String),
+}
+
+/// The part of a decision response a canary is checked against.
+#[derive(Debug, Deserialize)]
+struct ProbedDecision {
+    decision: Decision,
+    #[serde(default)]
+    evidence: Vec<Evidence>,
+}
+
+/// Load canaries from a YAML file.
+pub fn load_canaries(path: &Path) -> anyhow::Result<Vec<Canary>> {
+    let file: CanaryFile = serde_yaml::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    if file.canaries.is_empty() {
+        anyhow::bail!("{}: no canaries defined", path.display());
+    }
+    if file.canaries.iter().all(|c| c.weight == 0) {
+        anyhow::bail!("{}: every canary has weight 0", path.display());
+    }
+    let mut names = std::collections::HashSet::new();
+    if let Some(canary) = file.canaries.iter().find(|c| !names.insert(&c.name)) {
+        anyhow::bail!("{}: duplicate canary name: {}", path.display(), canary.name);
+    }
+    Ok(file.canaries)
+}
+
+/// Check a decision response body against a canary's expectation.
+pub fn check(expect: &Expectation, body: &[u8]) -> ProbeOutcome {
+    let response: ProbedDecision = match serde_json::from_slice(body) {
+        Ok(response) => response,
+        Err(e) => return ProbeOutcome::Error(format!("unreadable response: {}", e)),
+    };
+
+    if response.decision != expect.decision {
+        return ProbeOutcome::Fail(format!(
+            "expected {}, got {}",
+            expect.decision, response.decision
+        ));
+    }
+    if let Some(ref rule) = expect.rule {
+        if !response.evidence.iter().any(|e| &e.rule_id == rule) {
+            return ProbeOutcome::Fail(format!("expected evidence from {}", rule));
+        }
+    }
+    ProbeOutcome::Pass
+}
+
+/// Canary indices in sending order, each repeated by its weight and
+/// interleaved so heavy canaries don't run back to back.
+pub fn schedule(canaries: &[Canary]) -> Vec<usize> {
+    let total: u64 = canaries.iter().map(|c| c.weight as u64).sum();
+    let mut current = vec![0i64; canaries.len()];
+    let mut order = Vec::with_capacity(total as usize);
+
+    // Smooth weighted round-robin
+    for _ in 0..total {
+        for (i, canary) in canaries.iter().enumerate() {
+            current[i] += canary.weight as i64;
+        }
+        let (best, _) = current
+            .iter()
+            .enumerate()
+            .max_by_key(|&(i, &w)| (w, std::cmp::Reverse(i)))
+            .expect("at least one canary");
+        current[best] -= total as i64;
+        order.push(best);
+    }
+    order
+}
+
+#[derive(Debug, Default)]
+struct CanaryMetrics {
+    pass: AtomicU64,
+    fail: AtomicU64,
+    error: AtomicU64,
+    /// Cumulative counts per latency bucket, then +Inf
+    buckets: [AtomicU64; LATENCY_BUCKETS.len() + 1],
+    latency_sum_us: AtomicU64,
+}
+
+/// Probe results per canary.
+#[derive(Debug, Default)]
+pub struct ProbeMetrics {
+    canaries: BTreeMap<String, CanaryMetrics>,
+}
+
+impl ProbeMetrics {
+    /// Track the given canaries.
+    pub fn new(canaries: &[Canary]) -> Self {
+        ProbeMetrics {
+            canaries: canaries
+                .iter()
+                .map(|c| (c.name.clone(), CanaryMetrics::default()))
+                .collect(),
+        }
+    }
+
+    /// Record one probe of a canary.
+    pub fn record(&self, canary: &str, outcome: &ProbeOutcome, elapsed: Duration) {
+        let Some(metrics) = self.canaries.get(canary) else {
+            return;
+        };
+        let counter = match outcome {
+            ProbeOutcome::Pass => &metrics.pass,
+            ProbeOutcome::Fail(_) => &metrics.fail,
+            ProbeOutcome::Error(_) => &metrics.error,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        let secs = elapsed.as_secs_f64();
+        for (bucket, le) in metrics.buckets.iter().zip(LATENCY_BUCKETS) {
+            if secs <= le {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        metrics.buckets[LATENCY_BUCKETS.len()].fetch_add(1, Ordering::Relaxed);
+        metrics
+            .latency_sum_us
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Export metrics in Prometheus format.
+    pub fn to_prometheus(&self) -> String {
+        let mut output = String::from(
+            "# HELP riskr_probe_results_total Canary probes by result\n\
+             # TYPE riskr_probe_results_total counter\n",
+        );
+        for (name, metrics) in &self.canaries {
+            for (result, counter) in [
+                ("pass", &metrics.pass),
+                ("fail", &metrics.fail),
+                ("error", &metrics.error),
+            ] {
+                output.push_str(&format!(
+                    "riskr_probe_results_total{{canary=\"{}\",result=\"{}\"}} {}\n",
+                    name,
+                    result,
+                    counter.load(Ordering::Relaxed)
+                ));
+            }
+        }
+
+        output.push_str(
+            "\n# HELP riskr_probe_latency_seconds Canary decision latency\n\
+             # TYPE riskr_probe_latency_seconds histogram\n",
+        );
+        for (name, metrics) in &self.canaries {
+            let les = LATENCY_BUCKETS
+                .iter()
+                .map(|le| le.to_string())
+                .chain(["+Inf".to_string()]);
+            for (le, bucket) in les.zip(&metrics.buckets) {
+                output.push_str(&format!(
+                    "riskr_probe_latency_seconds_bucket{{canary=\"{}\",le=\"{}\"}} {}\n",
+                    name,
+                    le,
+                    bucket.load(Ordering::Relaxed)
+                ));
+            }
+            output.push_str(&format!(
+                "riskr_probe_latency_seconds_sum{{canary=\"{}\"}} {}\n\
+                 riskr_probe_latency_seconds_count{{canary=\"{}\"}} {}\n",
+                name,
+                metrics.latency_sum_us.load(Ordering::Relaxed) as f64 / 1e6,
+                name,
+                metrics.buckets[LATENCY_BUCKETS.len()].load(Ordering::Relaxed)
+            ));
+        }
+        output
+    }
+}
+
+/// Sends canaries to a target instance over plain HTTP.
+pub struct Prober {
+    client: Client<HttpConnector, Body>,
+    /// Decision endpoint URL
+    url: String,
+    timeout: Duration,
+}
+
+impl Prober {
+    /// Probe the instance at `target`, e.g. `http://riskr:8080`.
+    pub fn new(target: &str, timeout: Duration) -> Self {
+        Prober {
+            client: Client::builder(TokioExecutor::new()).build_http(),
+            url: format!("{}/v1/decision/check", target.trim_end_matches('/')),
+            timeout,
+        }
+    }
+
+    /// Send one canary and check the decision.
+    pub async fn probe(&self, canary: &Canary) -> (ProbeOutcome, Duration) {
+        let start = Instant::now();
+        let outcome = match tokio::time::timeout(self.timeout, self.send(canary)).await {
+            Ok(Ok(body)) => check(&canary.expect, &body),
+            Ok(Err(e)) => ProbeOutcome::Error(e.to_string()),
+            Err(_) => ProbeOutcome::Error(format!("timed out after {:?}", self.timeout)),
+        };
+        (outcome, start.elapsed())
+    }
+
+    async fn send(&self, canary: &Canary) -> anyhow::Result<axum::body::Bytes> {
+        let request = Request::post(&self.url)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(REQUEST_ID_HEADER, format!("probe-{}", uuid::Uuid::new_v4()))
+            .body(Body::from(serde_json::to_vec(&canary.request)?))?;
+
+        let response = self.client.request(request).await?;
+        let status = response.status();
+        let body =
+            axum::body::to_bytes(Body::new(response.into_body()), MAX_RESPONSE_BYTES).await?;
+        if status != StatusCode::OK {
+            anyhow::bail!("status {}", status);
+        }
+        Ok(body)
+    }
+
+    /// Probe canaries in weighted order, one every `interval`, forever.
+    pub async fn run(&self, canaries: &[Canary], metrics: &ProbeMetrics, interval: Duration) {
+        let order = schedule(canaries);
+        let mut ticker = tokio::time::interval(interval);
+        for &index in order.iter().cycle() {
+            ticker.tick().await;
+            let canary = &canaries[index];
+            let (outcome, elapsed) = self.probe(canary).await;
+            match &outcome {
+                ProbeOutcome::Pass => {}
+                ProbeOutcome::Fail(reason) => {
+                    warn!(canary = %canary.name, reason = %reason, "Canary failed")
+                }
+                ProbeOutcome::Error(reason) => {
+                    warn!(canary = %canary.name, error = %reason, "Canary probe errored")
+                }
+            }
+            metrics.record(&canary.name, &outcome, elapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANARIES: &str = r#"
+canaries:
+  - name: sanctioned
+    weight: 1
+    request:
+      subject: { user_id: C1, account_id: C, geo_iso: US, kyc_level: L2 }
+      tx: { type: withdraw, asset: USDC, amount: "1", usd_value: 1, dest_address: "0xdead" }
+    expect: { decision: REJECT_FATAL, rule: R1_OFAC }
+  - name: clean
+    weight: 3
+    request:
+      subject: { user_id: C2, account_id: C, geo_iso: US, kyc_level: L2 }
+      tx: { type: withdraw, asset: USDC, amount: "1", usd_value: 1 }
+    expect: { decision: ALLOW }
+"#;
+
+    fn canaries() -> Vec<Canary> {
+        serde_yaml::from_str::<CanaryFile>(CANARIES)
+            .unwrap()
+            .canaries
+    }
+
+    #[test]
+    fn test_check_expectation() {
+        let canaries = canaries();
+        let expect = &canaries[0].expect;
+
+        let body = br#"{"decision":"REJECT_FATAL","evidence":[{"rule_id":"R1_OFAC","key":"dest_address","value":"0xdead"}]}"#;
+        assert_eq!(check(expect, body), ProbeOutcome::Pass);
+
+        let body = br#"{"decision":"REJECT_FATAL","evidence":[]}"#;
+        assert!(matches!(check(expect, body), ProbeOutcome::Fail(_)));
+
+        let body = br#"{"decision":"ALLOW"}"#;
+        assert!(matches!(check(expect, body), ProbeOutcome::Fail(_)));
+
+        assert!(matches!(check(expect, b"oops"), ProbeOutcome::Error(_)));
+    }
+
+    #[test]
+    fn test_schedule_follows_weights() {
+        let order = schedule(&canaries());
+        assert_eq!(order.len(), 4);
+        assert_eq!(order.iter().filter(|&&i| i == 1).count(), 3);
+        // The light canary is not starved to the end
+        assert_eq!(order, vec![1, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_metrics_export() {
+        let canaries = canaries();
+        let metrics = ProbeMetrics::new(&canaries);
+        metrics.record("clean", &ProbeOutcome::Pass, Duration::from_millis(3));
+        metrics.record(
+            "sanctioned",
+            &ProbeOutcome::Fail("expected REJECT_FATAL".to_string()),
+            Duration::from_millis(30),
+        );
+
+        let output = metrics.to_prometheus();
+        assert!(output.contains("riskr_probe_results_total{canary=\"clean\",result=\"pass\"} 1"));
+        assert!(
+            output.contains("riskr_probe_results_total{canary=\"sanctioned\",result=\"fail\"} 1")
+        );
+        assert!(
+            output.contains("riskr_probe_latency_seconds_bucket{canary=\"clean\",le=\"0.005\"} 1")
+        );
+        assert!(output
+            .contains("riskr_probe_latency_seconds_bucket{canary=\"sanctioned\",le=\"0.025\"} 0"));
+        assert!(output.contains("riskr_probe_latency_seconds_count{canary=\"sanctioned\"} 1"));
+    }
+}
@@ -0,0 +1,214 @@
+//! Data classes of evidence, and class-based retention and redaction.
+//!
+//! Each evidence key belongs to a data class: personal data such as
+//! addresses and countries, financial figures such as volumes, or
+//! operational counters. Classes can be kept for different periods and
+//! redacted from exports independently. Expired evidence keeps its rule ID,
+//! key and limit, so the audit log still shows which rule decided; its value
+//! and details are replaced.
+
+use chrono::{DateTime, Duration, Utc};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::domain::Evidence;
+use crate::export::REDACTED;
+
+/// Kind of data an evidence key carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum DataClass {
+    /// Identifies or locates a person: addresses, countries, KYC tiers
+    Pii,
+    /// Amounts and volumes
+    Financial,
+    /// Counters and scores about the engine's own activity
+    Operational,
+}
+
+impl DataClass {
+    /// Class of keys not otherwise classified.
+    pub const DEFAULT: DataClass = DataClass::Operational;
+}
+
+/// Classes of the evidence keys rules produce; other keys are operational.
+pub const KEY_CLASSES: &[(&str, DataClass)] = &[
+    ("address", DataClass::Pii),
+    ("dest_address", DataClass::Pii),
+    ("dest_tag", DataClass::Pii),
+    ("geo_iso", DataClass::Pii),
+    ("ip_country", DataClass::Pii),
+    ("asn", DataClass::Pii),
+    ("kyc_tier", DataClass::Pii),
+    ("verified_kyc_tier", DataClass::Pii),
+    ("usd_value", DataClass::Financial),
+    ("daily_usd", DataClass::Financial),
+    ("daily_native", DataClass::Financial),
+    ("weekly_usd", DataClass::Financial),
+    ("monthly_usd", DataClass::Financial),
+    ("usd_rate", DataClass::Financial),
+    ("small_cnt_24h", DataClass::Financial),
+    ("chain_hop", DataClass::Financial),
+];
+
+/// Evidence keys selected for a class: those listed, or, for the default
+/// class, those not listed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyFilter {
+    pub keys: Vec<String>,
+    pub negated: bool,
+}
+
+impl KeyFilter {
+    pub fn matches(&self, key: &str) -> bool {
+        self.keys.iter().any(|k| k == key) != self.negated
+    }
+}
+
+/// How long each data class is kept, and which are left out of exports.
+#[derive(Debug, Clone, Default)]
+pub struct EvidenceRetention {
+    /// Overrides of [`KEY_CLASSES`]
+    classes: HashMap<String, DataClass>,
+    retention: HashMap<DataClass, Duration>,
+    export_redact: HashSet<DataClass>,
+}
+
+impl EvidenceRetention {
+    /// Classify `key` as `class`, overriding its default.
+    pub fn with_class(mut self, key: impl Into<String>, class: DataClass) -> Self {
+        self.classes.insert(key.into(), class);
+        self
+    }
+
+    /// Keep evidence of `class` for `period` after the decision.
+    pub fn with_retention(mut self, class: DataClass, period: Duration) -> Self {
+        self.retention.insert(class, period);
+        self
+    }
+
+    /// Redact evidence of `class` from exports, whatever its age.
+    pub fn with_export_redact(mut self, class: DataClass) -> Self {
+        self.export_redact.insert(class);
+        self
+    }
+
+    /// Data class of an evidence key.
+    pub fn class_of(&self, key: &str) -> DataClass {
+        if let Some(class) = self.classes.get(key) {
+            return *class;
+        }
+        KEY_CLASSES
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map_or(DataClass::DEFAULT, |(_, class)| *class)
+    }
+
+    /// Classes with a retention period, and the period.
+    pub fn retentions(&self) -> impl Iterator<Item = (DataClass, Duration)> + '_ {
+        self.retention
+            .iter()
+            .map(|(class, period)| (*class, *period))
+    }
+
+    /// Evidence keys of `class`, for selecting them in storage.
+    pub fn keys(&self, class: DataClass) -> KeyFilter {
+        let mut classified: HashMap<&str, DataClass> =
+            KEY_CLASSES.iter().map(|(k, c)| (*k, *c)).collect();
+        classified.extend(self.classes.iter().map(|(k, c)| (k.as_str(), *c)));
+
+        // The default class is everything not classified otherwise
+        let negated = class == DataClass::DEFAULT;
+        let mut keys: Vec<String> = classified
+            .into_iter()
+            .filter(|(_, c)| (*c == class) != negated)
+            .map(|(k, _)| k.to_string())
+            .collect();
+        keys.sort();
+        KeyFilter { keys, negated }
+    }
+
+    /// Whether evidence of `class` recorded at `recorded_at` is past its
+    /// retention.
+    pub fn is_expired(&self, class: DataClass, recorded_at: DateTime<Utc>) -> bool {
+        self.retention
+            .get(&class)
+            .is_some_and(|period| recorded_at + *period <= Utc::now())
+    }
+
+    /// Redact evidence of a decision recorded at `recorded_at` for export:
+    /// classes redacted from exports, and those past their retention.
+    pub fn redact_for_export(&self, evidence: &mut [Evidence], recorded_at: DateTime<Utc>) {
+        for item in evidence {
+            let class = self.class_of(&item.key);
+            if self.export_redact.contains(&class) || self.is_expired(class, recorded_at) {
+                redact(item);
+            }
+        }
+    }
+}
+
+/// Replace an evidence item's value and details, keeping what identifies
+/// the rule and the limit it applied.
+pub fn redact(evidence: &mut Evidence) {
+    evidence.value = REDACTED.to_string();
+    evidence.details = serde_json::Value::Null;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classes_and_overrides() {
+        let retention = EvidenceRetention::default().with_class("hour_share", DataClass::Pii);
+        assert_eq!(retention.class_of("address"), DataClass::Pii);
+        assert_eq!(retention.class_of("daily_usd"), DataClass::Financial);
+        assert_eq!(
+            retention.class_of("requests_per_min"),
+            DataClass::Operational
+        );
+        assert_eq!(retention.class_of("hour_share"), DataClass::Pii);
+
+        let pii = retention.keys(DataClass::Pii);
+        assert!(!pii.negated);
+        assert!(pii.matches("hour_share"));
+        assert!(!pii.matches("daily_usd"));
+
+        let operational = retention.keys(DataClass::Operational);
+        assert!(operational.negated);
+        assert!(operational.matches("requests_per_min"));
+        assert!(!operational.matches("address"));
+        assert!(!operational.matches("hour_share"));
+    }
+
+    #[test]
+    fn test_redact_for_export() {
+        let retention = EvidenceRetention::default()
+            .with_retention(DataClass::Pii, Duration::days(30))
+            .with_export_redact(DataClass::Financial);
+        let evidence = || {
+            vec![
+                Evidence::new("R1_OFAC", "address", "0xdead")
+                    .with_details(serde_json::json!({ "list": "OFAC" })),
+                Evidence::with_limit("R4_DAILY", "daily_usd", "60000", "50000"),
+                Evidence::with_limit("R_BURST", "requests_per_min", "30", "20"),
+            ]
+        };
+
+        let mut recent = evidence();
+        retention.redact_for_export(&mut recent, Utc::now() - Duration::days(1));
+        assert_eq!(recent[0].value, "0xdead");
+        assert_eq!(recent[1].value, REDACTED);
+        assert_eq!(recent[1].limit.as_deref(), Some("50000"));
+        assert_eq!(recent[2].value, "30");
+
+        let mut old = evidence();
+        retention.redact_for_export(&mut old, Utc::now() - Duration::days(31));
+        assert_eq!(old[0].value, REDACTED);
+        assert!(old[0].details.is_null());
+        assert_eq!(old[0].rule_id, "R1_OFAC");
+        assert_eq!(old[2].value, "30");
+    }
+}
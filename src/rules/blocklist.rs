@@ -0,0 +1,197 @@
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, TxEvent};
+use crate::storage::{BlocklistEntry, Storage};
+
+use super::sanctions::normalize_entry;
+
+/// Rule ID on evidence for blocklisted addresses.
+pub const BLOCKLIST_RULE_ID: &str = "INTERNAL_BLOCKLIST";
+
+/// Addresses blocked by compliance in-house.
+///
+/// Screened on every decision alongside the policy's rules, so entries take
+/// effect without a policy change and survive policy reloads. Hits are
+/// rejected outright.
+#[derive(Debug, Default)]
+pub struct Blocklist {
+    entries: RwLock<HashMap<String, BlocklistEntry>>,
+}
+
+impl Blocklist {
+    pub fn contains(&self, addr: &str) -> bool {
+        self.entries.read().contains_key(&normalize_entry(addr))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+
+    /// Screen the subject's addresses and the destination.
+    pub fn evaluate(&self, event: &TxEvent) -> RuleResult {
+        let entries = self.entries.read();
+        if entries.is_empty() {
+            return RuleResult::allow();
+        }
+
+        let subject = event
+            .subject
+            .addresses
+            .iter()
+            .map(|a| ("address", a.as_str()));
+        let dest = event
+            .destination
+            .iter()
+            .map(|d| ("dest_address", d.address.as_str()));
+        for (key, addr) in subject.chain(dest) {
+            if let Some(entry) = entries.get(&normalize_entry(addr)) {
+                let mut evidence = Evidence::new(BLOCKLIST_RULE_ID, key, addr);
+                if let Some(reason) = &entry.reason {
+                    evidence = evidence.with_details(serde_json::json!({ "reason": reason }));
+                }
+                return RuleResult::trigger(Decision::RejectFatal, evidence);
+            }
+        }
+
+        RuleResult::allow()
+    }
+
+    /// Add entries, replacing any for the same address.
+    pub fn extend(&self, entries: Vec<BlocklistEntry>) {
+        let mut current = self.entries.write();
+        for entry in entries {
+            current.insert(normalize_entry(&entry.address), entry);
+        }
+    }
+
+    /// Replace every entry with the given set.
+    pub fn replace(&self, entries: Vec<BlocklistEntry>) {
+        *self.entries.write() = entries
+            .into_iter()
+            .map(|e| (normalize_entry(&e.address), e))
+            .collect();
+    }
+
+    /// Load the persisted blocklist from storage.
+    pub async fn load(&self, storage: &dyn Storage) -> anyhow::Result<()> {
+        self.replace(storage.get_blocklist().await?);
+        Ok(())
+    }
+
+    /// Reload from storage each time `changes` signals an import made
+    /// elsewhere, until the sender is dropped.
+    pub fn follow(
+        self: Arc<Self>,
+        storage: Arc<dyn Storage>,
+        mut changes: mpsc::Receiver<()>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            while changes.recv().await.is_some() {
+                match self.load(storage.as_ref()).await {
+                    Ok(()) => info!(entries = self.len(), "Reloaded internal blocklist"),
+                    Err(e) => warn!(error = %e, "Failed to reload internal blocklist"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Chain, Destination, Direction, EventId, SCHEMA_VERSION};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+
+    fn entry(address: &str) -> BlocklistEntry {
+        BlocklistEntry {
+            address: address.to_string(),
+            reason: Some("fraud ring".to_string()),
+            added_at: Utc::now(),
+            actor: "compliance".to_string(),
+        }
+    }
+
+    fn event(address: &str, dest: &str) -> TxEvent {
+        TxEvent {
+            schema_version: SCHEMA_VERSION.to_string(),
+            event_id: EventId::new(),
+            occurred_at: Utc::now(),
+            observed_at: Utc::now(),
+            subject: Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: smallvec::smallvec![Address::new(address)],
+                geo_iso: CountryCode::new("US"),
+                kyc_tier: KycTier::L1,
+            },
+            chain: Chain::inline(),
+            tx_hash: String::new(),
+            direction: Direction::Outbound,
+            tx_type: String::new(),
+            asset: Asset::new("USDC"),
+            amount: "100".to_string(),
+            usd_value: Decimal::new(100, 0),
+            usd_rate: None,
+            confirmations: 0,
+            max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: Some(Destination::new(dest, None)),
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
+        }
+    }
+
+    #[test]
+    fn test_blocks_subject_and_destination() {
+        let blocklist = Blocklist::default();
+        assert!(!blocklist.evaluate(&event("0xabc", "0xbad")).hit);
+
+        blocklist.extend(vec![entry("0xBAD")]);
+        assert!(blocklist.contains("0xbad"));
+
+        let result = blocklist.evaluate(&event("0xabc", "0xBad"));
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::RejectFatal);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.rule_id, BLOCKLIST_RULE_ID);
+        assert_eq!(evidence.key, "dest_address");
+
+        let result = blocklist.evaluate(&event("0xbad", "0xabc"));
+        assert_eq!(result.evidence.unwrap().key, "address");
+    }
+
+    #[tokio::test]
+    async fn test_follow_reloads_from_storage() {
+        let storage: Arc<dyn Storage> = Arc::new(crate::storage::MockStorage::new());
+        let blocklist = Arc::new(Blocklist::default());
+        blocklist.extend(vec![entry("0xstale")]);
+
+        let (tx, rx) = mpsc::channel(1);
+        let handle = blocklist.clone().follow(storage.clone(), rx);
+
+        storage
+            .save_blocklist(&[entry("0xbad")], true)
+            .await
+            .unwrap();
+        tx.send(()).await.unwrap();
+        drop(tx);
+        handle.await.unwrap();
+
+        assert!(blocklist.contains("0xbad"));
+        assert!(!blocklist.contains("0xstale"));
+    }
+}
@@ -0,0 +1,200 @@
+use memmap2::Mmap;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Magic bytes at the start of a compiled sanctions file.
+pub const MAGIC: &[u8; 8] = b"RSKRSAN1";
+
+/// Header: magic, entry count, fingerprint.
+const HEADER_LEN: usize = 24;
+
+/// Read-only sanctions set backed by a memory-mapped file.
+///
+/// The file holds the lowercase addresses sorted, as an offset table
+/// followed by the concatenated bytes, so lookups are a binary search
+/// directly over the mapping and opening a file does no parsing. Pages
+/// are shared with the OS page cache, so mapping a new file on reload
+/// does not double resident memory.
+///
+/// Files must be replaced by rename (as `compile` does), never rewritten
+/// in place, since existing mappings keep reading the old file.
+#[derive(Debug)]
+pub struct CompiledSanctions {
+    map: Mmap,
+    count: usize,
+    fingerprint: u64,
+}
+
+impl CompiledSanctions {
+    /// Map a compiled sanctions file.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only and compiled files are only ever
+        // replaced by rename, so the mapped bytes are not modified.
+        let map = unsafe { Mmap::map(&file)? };
+
+        if map.len() < HEADER_LEN || &map[..8] != MAGIC {
+            return Err(invalid("not a compiled sanctions file"));
+        }
+        let count = read_u64(&map, 8) as usize;
+        let fingerprint = read_u64(&map, 16);
+
+        // The offset table has count + 1 entries; the last is the blob length
+        let blob_start = count
+            .checked_add(1)
+            .and_then(|n| n.checked_mul(8))
+            .and_then(|n| n.checked_add(HEADER_LEN))
+            .filter(|n| *n <= map.len())
+            .ok_or_else(|| invalid("truncated offset table"))?;
+        let blob_len = read_u64(&map, blob_start - 8) as usize;
+        if blob_start + blob_len != map.len() {
+            return Err(invalid("blob length does not match file size"));
+        }
+
+        Ok(CompiledSanctions {
+            map,
+            count,
+            fingerprint,
+        })
+    }
+
+    /// Returns true if the file at `path` starts with the compiled magic.
+    pub fn is_compiled(path: impl AsRef<Path>) -> bool {
+        let mut magic = [0u8; 8];
+        File::open(path)
+            .and_then(|mut f| io::Read::read_exact(&mut f, &mut magic))
+            .is_ok_and(|_| &magic == MAGIC)
+    }
+
+    /// Write addresses to a compiled file, replacing `path` atomically.
+    pub fn compile(
+        addresses: &HashSet<String>,
+        fingerprint: u64,
+        path: impl AsRef<Path>,
+    ) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut sorted: Vec<String> = addresses.iter().map(|a| a.to_lowercase()).collect();
+        sorted.sort();
+        sorted.dedup();
+
+        let tmp = path.with_extension("tmp");
+        {
+            let mut out = BufWriter::new(File::create(&tmp)?);
+            out.write_all(MAGIC)?;
+            out.write_all(&(sorted.len() as u64).to_le_bytes())?;
+            out.write_all(&fingerprint.to_le_bytes())?;
+
+            let mut offset = 0u64;
+            out.write_all(&offset.to_le_bytes())?;
+            for addr in &sorted {
+                offset += addr.len() as u64;
+                out.write_all(&offset.to_le_bytes())?;
+            }
+            for addr in &sorted {
+                out.write_all(addr.as_bytes())?;
+            }
+            out.flush()?;
+        }
+        fs::rename(tmp, path)
+    }
+
+    /// Check an already-lowercase address.
+    pub fn contains(&self, addr: &str) -> bool {
+        let target = addr.as_bytes();
+        let (mut lo, mut hi) = (0, self.count);
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let Some(entry) = self.entry(mid) else {
+                return false;
+            };
+            match entry.cmp(target) {
+                std::cmp::Ordering::Equal => return true,
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+            }
+        }
+        false
+    }
+
+    /// Number of addresses.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns true if the file has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Fingerprint of the contents recorded at compile time.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Size of the mapping in bytes.
+    pub fn mapped_bytes(&self) -> usize {
+        self.map.len()
+    }
+
+    fn entry(&self, i: usize) -> Option<&[u8]> {
+        let blob = HEADER_LEN + (self.count + 1) * 8;
+        let start = read_u64(&self.map, HEADER_LEN + i * 8) as usize;
+        let end = read_u64(&self.map, HEADER_LEN + (i + 1) * 8) as usize;
+        self.map.get(blob + start..blob + end)
+    }
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_le_bytes(buf)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_and_lookup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sanctions.bin");
+        let addresses = HashSet::from([
+            "0xDEAD".to_string(),
+            "0xbeef".to_string(),
+            "bc1qxyz".to_string(),
+        ]);
+
+        CompiledSanctions::compile(&addresses, 42, &path).unwrap();
+        assert!(CompiledSanctions::is_compiled(&path));
+
+        let compiled = CompiledSanctions::open(&path).unwrap();
+        assert_eq!(compiled.len(), 3);
+        assert_eq!(compiled.fingerprint(), 42);
+        assert!(compiled.contains("0xdead"));
+        assert!(compiled.contains("0xbeef"));
+        assert!(compiled.contains("bc1qxyz"));
+        assert!(!compiled.contains("0xdea"));
+        assert!(!compiled.contains("0xfeed"));
+    }
+
+    #[test]
+    fn test_rejects_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sanctions.txt");
+        fs::write(&path, "0xdead\n0xbeef\n").unwrap();
+
+        assert!(!CompiledSanctions::is_compiled(&path));
+        assert!(CompiledSanctions::open(&path).is_err());
+
+        // Truncated file
+        fs::write(&path, [MAGIC.as_slice(), &[0xff; 16]].concat()).unwrap();
+        assert!(CompiledSanctions::open(&path).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+//! Concurrent evaluation of streaming rules.
+//!
+//! Streaming rules spend their time waiting on storage, so evaluating them
+//! one after another adds their latencies together. Each rule runs on its
+//! own task instead, and the request waits for the slowest.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::TxEvent;
+use crate::storage::StorageRead;
+
+use super::traits::StreamingRule;
+
+/// One streaming rule's evaluation.
+#[derive(Debug)]
+pub struct StreamingEvaluation {
+    /// Position of the rule in the slice evaluated
+    pub index: usize,
+    pub result: anyhow::Result<RuleResult>,
+    pub elapsed: Duration,
+}
+
+/// Evaluate streaming rules concurrently, one task each.
+///
+/// Once a rule for which `decides` holds hits with a fatal decision, rules
+/// still running are cancelled, since nothing they return could change the
+/// outcome; they have no evaluation. Shadowed rules should be excluded from
+/// `decides` so their hits don't cancel anything. Evaluations are returned
+/// in rule order. A rule that panics is reported as an error.
+pub async fn evaluate_streaming(
+    rules: &[Arc<dyn StreamingRule>],
+    event: &Arc<TxEvent>,
+    subject_id: Uuid,
+    storage: &Arc<dyn StorageRead>,
+    decides: impl Fn(usize) -> bool,
+) -> Vec<StreamingEvaluation> {
+    let mut tasks = JoinSet::new();
+    let mut task_ids = Vec::with_capacity(rules.len());
+    for (index, rule) in rules.iter().enumerate() {
+        let rule = rule.clone();
+        let event = event.clone();
+        let storage = storage.clone();
+        let handle = tasks.spawn(async move {
+            let start = Instant::now();
+            let result = rule.evaluate(&event, subject_id, storage.as_ref()).await;
+            StreamingEvaluation {
+                index,
+                result,
+                elapsed: start.elapsed(),
+            }
+        });
+        task_ids.push(handle.id());
+    }
+
+    let mut evaluations = Vec::with_capacity(rules.len());
+    while let Some(joined) = tasks.join_next().await {
+        let evaluation = match joined {
+            Ok(evaluation) => evaluation,
+            Err(e) => {
+                let Some(index) = task_ids.iter().position(|id| *id == e.id()) else {
+                    continue;
+                };
+                StreamingEvaluation {
+                    index,
+                    result: Err(anyhow::anyhow!("rule {} panicked", rules[index].id())),
+                    elapsed: Duration::ZERO,
+                }
+            }
+        };
+        let fatal = evaluation
+            .result
+            .as_ref()
+            .is_ok_and(|r| r.hit && r.decision.is_fatal());
+        let index = evaluation.index;
+        evaluations.push(evaluation);
+        if fatal && decides(index) {
+            tasks.abort_all();
+            break;
+        }
+    }
+
+    evaluations.sort_by_key(|e| e.index);
+    evaluations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, CountryCode, KycTier, Subject, UserId};
+    use crate::domain::{Decision, Evidence, RuleType};
+    use crate::rules::traits::RuleDescription;
+    use crate::storage::MockStorage;
+    use async_trait::async_trait;
+    use rust_decimal::Decimal;
+
+    /// Rule that answers with `decision` after `delay`.
+    #[derive(Debug)]
+    struct Delayed {
+        id: &'static str,
+        delay: Duration,
+        decision: Option<Decision>,
+    }
+
+    #[async_trait]
+    impl StreamingRule for Delayed {
+        fn id(&self) -> &str {
+            self.id
+        }
+
+        fn describe(&self) -> RuleDescription {
+            RuleDescription::new(
+                self.id,
+                RuleType::RequestBurst,
+                self.decision,
+                serde_json::Value::Null,
+            )
+        }
+
+        async fn evaluate(
+            &self,
+            _event: &TxEvent,
+            _subject_id: Uuid,
+            _storage: &dyn StorageRead,
+        ) -> anyhow::Result<RuleResult> {
+            tokio::time::sleep(self.delay).await;
+            Ok(match self.decision {
+                Some(decision) => {
+                    RuleResult::trigger(decision, Evidence::new(self.id, "test", "hit"))
+                }
+                None => RuleResult::allow(),
+            })
+        }
+    }
+
+    fn rule(id: &'static str, delay_ms: u64, decision: Option<Decision>) -> Arc<dyn StreamingRule> {
+        Arc::new(Delayed {
+            id,
+            delay: Duration::from_millis(delay_ms),
+            decision,
+        })
+    }
+
+    fn event() -> Arc<TxEvent> {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec::smallvec![],
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        };
+        Arc::new(TxEvent::new(
+            subject,
+            Asset::new("USDC"),
+            Decimal::new(100, 0),
+            Direction::Outbound,
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_rules_run_concurrently_in_rule_order() {
+        let rules = vec![
+            rule("SLOW", 50, Some(Decision::Review)),
+            rule("FAST", 10, None),
+            rule("MID", 30, Some(Decision::HoldAuto)),
+        ];
+        let storage: Arc<dyn StorageRead> = Arc::new(MockStorage::new());
+
+        let start = Instant::now();
+        let evaluations =
+            evaluate_streaming(&rules, &event(), Uuid::new_v4(), &storage, |_| true).await;
+
+        assert!(start.elapsed() < Duration::from_millis(85));
+        let indexes: Vec<usize> = evaluations.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![0, 1, 2]);
+        assert_eq!(
+            evaluations[0].result.as_ref().unwrap().decision,
+            Decision::Review
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fatal_hit_cancels_remaining_rules() {
+        let rules = vec![
+            rule("SLOW", 2_000, None),
+            rule("SHADOWED", 5, Some(Decision::RejectFatal)),
+            rule("FATAL", 20, Some(Decision::RejectFatal)),
+        ];
+        let storage: Arc<dyn StorageRead> = Arc::new(MockStorage::new());
+
+        let start = Instant::now();
+        let evaluations =
+            evaluate_streaming(&rules, &event(), Uuid::new_v4(), &storage, |i| i != 1).await;
+
+        assert!(start.elapsed() < Duration::from_millis(1_000));
+        // The shadowed rule's hit did not stop the fatal one
+        let indexes: Vec<usize> = evaluations.iter().map(|e| e.index).collect();
+        assert_eq!(indexes, vec![1, 2]);
+    }
+}
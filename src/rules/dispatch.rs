@@ -0,0 +1,237 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::domain::{KycTier, Policy, RuleScope, TxEvent};
+use crate::rules::{InlineRule, StreamingRule};
+
+/// Rules of a list indexed by the branches of traffic they apply to.
+///
+/// A scoped rule is listed under each value of the first branch its scope
+/// sets (country, then asset, then tier), so finding the rules for a
+/// transaction takes three lookups plus a scope check of the candidates
+/// instead of a pass over every rule.
+#[derive(Debug, Clone, Default)]
+pub struct RuleIndex {
+    /// Positions of rules that apply to every transaction
+    unscoped: Vec<usize>,
+    countries: HashMap<String, Vec<usize>>,
+    assets: HashMap<String, Vec<usize>>,
+    tiers: HashMap<KycTier, Vec<usize>>,
+    /// Scope of each scoped rule, keyed by position
+    scopes: HashMap<usize, RuleScope>,
+}
+
+impl RuleIndex {
+    fn build<'a>(ids: impl Iterator<Item = &'a str>, scopes: &HashMap<String, RuleScope>) -> Self {
+        let mut index = RuleIndex::default();
+        for (position, id) in ids.enumerate() {
+            let Some(scope) = scopes.get(id) else {
+                index.unscoped.push(position);
+                continue;
+            };
+            // Every branch must match, so any one finds the rule
+            if !scope.countries.is_empty() {
+                for country in &scope.countries {
+                    index
+                        .countries
+                        .entry(country.clone())
+                        .or_default()
+                        .push(position);
+                }
+            } else if !scope.assets.is_empty() {
+                for asset in &scope.assets {
+                    index
+                        .assets
+                        .entry(asset.clone())
+                        .or_default()
+                        .push(position);
+                }
+            } else {
+                for tier in &scope.kyc_tiers {
+                    index.tiers.entry(tier.clone()).or_default().push(position);
+                }
+            }
+            index.scopes.insert(position, scope.clone());
+        }
+        index
+    }
+
+    /// Returns true if some rule only applies to part of the traffic.
+    pub fn is_scoped(&self) -> bool {
+        !self.scopes.is_empty()
+    }
+
+    /// Rules of `rules`, the list the index was built from, that apply to
+    /// `event`, in their original order.
+    pub fn select<'a, R: ?Sized>(&self, rules: &'a [Arc<R>], event: &TxEvent) -> Cow<'a, [Arc<R>]> {
+        if !self.is_scoped() {
+            return Cow::Borrowed(rules);
+        }
+
+        let subject = &event.subject;
+        let mut positions = self.unscoped.clone();
+        let candidates = [
+            self.countries.get(subject.geo_iso.as_str()),
+            self.assets.get(event.asset.0.as_str()),
+            self.tiers.get(&subject.kyc_tier),
+        ];
+        for scoped in candidates.into_iter().flatten() {
+            positions.extend(
+                scoped
+                    .iter()
+                    .filter(|position| applies(&self.scopes[*position], event)),
+            );
+        }
+        positions.sort_unstable();
+
+        Cow::Owned(positions.into_iter().map(|p| rules[p].clone()).collect())
+    }
+}
+
+/// Indexes of a rule set's inline and streaming rules.
+#[derive(Debug, Clone, Default)]
+pub struct RuleDispatch {
+    /// Scope of each scoped rule, keyed by rule ID
+    scopes: HashMap<String, RuleScope>,
+    pub inline: RuleIndex,
+    pub streaming: RuleIndex,
+}
+
+impl RuleDispatch {
+    /// Collect the policy's rule scopes; see [`RuleDispatch::indexed`].
+    pub fn from_policy(policy: &Policy) -> Self {
+        RuleDispatch {
+            scopes: policy
+                .rules
+                .iter()
+                .filter(|r| !r.scope.is_empty())
+                .map(|r| {
+                    let mut scope = r.scope.clone();
+                    for country in &mut scope.countries {
+                        *country = country.to_uppercase();
+                    }
+                    (r.id.clone(), scope)
+                })
+                .collect(),
+            inline: RuleIndex::default(),
+            streaming: RuleIndex::default(),
+        }
+    }
+
+    /// Index rule lists by their scopes. Lists change as rules are added,
+    /// so this is redone for each.
+    pub fn indexed(
+        &self,
+        inline: &[Arc<dyn InlineRule>],
+        streaming: &[Arc<dyn StreamingRule>],
+    ) -> Self {
+        RuleDispatch {
+            scopes: self.scopes.clone(),
+            inline: RuleIndex::build(inline.iter().map(|r| r.id()), &self.scopes),
+            streaming: RuleIndex::build(streaming.iter().map(|r| r.id()), &self.scopes),
+        }
+    }
+}
+
+fn applies(scope: &RuleScope, event: &TxEvent) -> bool {
+    let subject = &event.subject;
+    (scope.countries.is_empty()
+        || scope
+            .countries
+            .iter()
+            .any(|c| c == subject.geo_iso.as_str()))
+        && (scope.assets.is_empty() || scope.assets.contains(&event.asset.0))
+        && (scope.kyc_tiers.is_empty() || scope.kyc_tiers.contains(&subject.kyc_tier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, Address, CountryCode, Subject, UserId};
+    use crate::rules::RuleSet;
+    use rust_decimal::Decimal;
+    use smallvec::smallvec;
+    use std::collections::HashSet;
+
+    fn event(geo: &str, asset: &str, tier: KycTier) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![Address::new("0xabc")],
+            geo_iso: CountryCode::new(geo),
+            kyc_tier: tier,
+        };
+        TxEvent::new(
+            subject,
+            Asset::new(asset),
+            Decimal::new(100, 0),
+            Direction::Outbound,
+        )
+    }
+
+    fn ids(rules: &[Arc<dyn InlineRule>]) -> Vec<&str> {
+        rules.iter().map(|r| r.id()).collect()
+    }
+
+    #[test]
+    fn test_rules_dispatched_by_scope() {
+        let policy: Policy = serde_yaml::from_str(
+            r#"
+policy_version: "test"
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+  - id: R2_DE
+    type: jurisdiction_block
+    action: REJECT_FATAL
+    blocked_countries: ["IR"]
+    scope: { countries: [de, at] }
+  - id: R2_DE_USDT
+    type: jurisdiction_block
+    action: REVIEW
+    blocked_countries: ["RU"]
+    scope: { countries: [DE], assets: [USDT] }
+  - id: R2_L0
+    type: jurisdiction_block
+    action: REVIEW
+    blocked_countries: ["CU"]
+    scope: { kyc_tiers: [L0] }
+"#,
+        )
+        .unwrap();
+        let ruleset = RuleSet::from_policy(&policy, HashSet::new());
+        assert_eq!(ruleset.inline.len(), 4);
+
+        let rules = ruleset.inline_for(&event("DE", "USDT", KycTier::L1));
+        assert_eq!(ids(&rules), ["R1_OFAC", "R2_DE", "R2_DE_USDT"]);
+
+        let rules = ruleset.inline_for(&event("AT", "USDT", KycTier::L0));
+        assert_eq!(ids(&rules), ["R1_OFAC", "R2_DE", "R2_L0"]);
+
+        let rules = ruleset.inline_for(&event("US", "USDC", KycTier::L2));
+        assert_eq!(ids(&rules), ["R1_OFAC"]);
+    }
+
+    #[test]
+    fn test_unscoped_policy_borrows_rules() {
+        let policy: Policy = serde_yaml::from_str(
+            r#"
+policy_version: "test"
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+"#,
+        )
+        .unwrap();
+        let ruleset = RuleSet::from_policy(&policy, HashSet::new());
+        assert!(!ruleset.dispatch.inline.is_scoped());
+        assert!(matches!(
+            ruleset.inline_for(&event("US", "USDC", KycTier::L1)),
+            Cow::Borrowed(_)
+        ));
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::domain::Policy;
+
+/// Experimental rules and the request features that run them.
+///
+/// A rule marked `experimental: <feature>` in policy only runs for
+/// requests that opt in with `features: ["<feature>"]`, so internal test
+/// traffic can exercise it in production without affecting customer
+/// decisions. Features listed in the policy's `default_features` run for
+/// every request instead, unless it opts out with `"-<feature>"`.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureGates {
+    /// Feature of each experimental rule, keyed by rule ID
+    rules: HashMap<String, String>,
+    defaults: HashSet<String>,
+}
+
+impl FeatureGates {
+    pub fn from_policy(policy: &Policy) -> Self {
+        FeatureGates {
+            rules: policy
+                .rules
+                .iter()
+                .filter_map(|r| Some((r.id.clone(), r.experimental.clone()?)))
+                .collect(),
+            defaults: policy.params.default_features.iter().cloned().collect(),
+        }
+    }
+
+    /// Returns true if the rule runs for a request with these features.
+    pub fn allows(&self, rule_id: &str, features: &[String]) -> bool {
+        let Some(feature) = self.rules.get(rule_id) else {
+            return true;
+        };
+        let mut enabled = self.defaults.contains(feature);
+        for requested in features {
+            match requested.strip_prefix('-') {
+                Some(name) if name == feature => enabled = false,
+                None if requested == feature => enabled = true,
+                _ => {}
+            }
+        }
+        enabled
+    }
+
+    /// Returns true if the rule is experimental.
+    pub fn is_experimental(&self, rule_id: &str) -> bool {
+        self.rules.contains_key(rule_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gates() -> FeatureGates {
+        let policy: Policy = serde_yaml::from_str(
+            r#"
+policy_version: "test"
+params:
+  default_features: ["velocity_v3"]
+rules:
+  - id: R4_DAILY
+    type: daily_usd_volume
+    action: HOLD_AUTO
+  - id: R5_STRUCT_V2
+    type: structuring_small_tx
+    action: REVIEW
+    experimental: new_structuring_v2
+  - id: R8_VELOCITY
+    type: daily_usd_volume
+    action: HOLD_AUTO
+    experimental: velocity_v3
+"#,
+        )
+        .unwrap();
+        FeatureGates::from_policy(&policy)
+    }
+
+    fn features(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_experimental_rules_need_opt_in() {
+        let gates = gates();
+        assert!(gates.allows("R4_DAILY", &[]));
+        assert!(!gates.is_experimental("R4_DAILY"));
+
+        assert!(gates.is_experimental("R5_STRUCT_V2"));
+        assert!(!gates.allows("R5_STRUCT_V2", &[]));
+        assert!(!gates.allows("R5_STRUCT_V2", &features(&["other"])));
+        assert!(gates.allows("R5_STRUCT_V2", &features(&["new_structuring_v2"])));
+    }
+
+    #[test]
+    fn test_default_features_allow_opt_out() {
+        let gates = gates();
+        assert!(gates.allows("R8_VELOCITY", &[]));
+        assert!(!gates.allows("R8_VELOCITY", &features(&["-velocity_v3"])));
+        // Opting out of one feature leaves the others alone
+        assert!(gates.allows(
+            "R5_STRUCT_V2",
+            &features(&["-velocity_v3", "new_structuring_v2"])
+        ));
+    }
+}
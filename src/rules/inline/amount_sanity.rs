@@ -0,0 +1,230 @@
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, Money, PriceBand, RuleType, TxEvent};
+use crate::rules::traits::{InlineRule, RuleDescription};
+
+/// Amount sanity rule.
+///
+/// Guards against integration bugs upstream: a USD value that is zero or
+/// negative, above a global maximum, or that implies a price per unit
+/// outside the asset's plausible band. Caught inline, such values never
+/// reach the rolling windows when the rule rejects them.
+#[derive(Debug)]
+pub struct AmountSanityRule {
+    id: String,
+    action: Decision,
+    /// Largest plausible USD value of one transaction
+    max_usd: Option<Decimal>,
+    /// Plausible USD prices per unit, keyed by uppercase asset symbol
+    price_bands: HashMap<String, PriceBand>,
+}
+
+impl AmountSanityRule {
+    /// Create a new amount sanity rule, checking only that USD values are
+    /// positive.
+    pub fn new(id: String, action: Decision) -> Self {
+        AmountSanityRule {
+            id,
+            action,
+            max_usd: None,
+            price_bands: HashMap::new(),
+        }
+    }
+
+    /// Also flag transactions above `max_usd`.
+    pub fn with_max_usd(mut self, max_usd: Option<Decimal>) -> Self {
+        self.max_usd = max_usd;
+        self
+    }
+
+    /// Also flag transactions whose USD value and amount imply a price
+    /// outside the asset's band.
+    pub fn with_price_bands(mut self, bands: HashMap<String, PriceBand>) -> Self {
+        self.price_bands = bands
+            .into_iter()
+            .map(|(asset, band)| (asset.to_uppercase(), band))
+            .collect();
+        self
+    }
+
+    fn trigger(&self, evidence: Evidence, check: &str) -> RuleResult {
+        RuleResult::trigger(
+            self.action,
+            evidence.with_details(serde_json::json!({ "check": check })),
+        )
+    }
+
+    /// Check the price implied by the event's USD value and amount. Events
+    /// without an amount are not checked.
+    fn check_price(&self, event: &TxEvent) -> Option<RuleResult> {
+        let asset = event.asset.0.to_uppercase();
+        let band = self.price_bands.get(&asset)?;
+        let amount: Decimal = event.amount.parse().ok()?;
+        // A zero amount, or one so small the price overflows, can't be
+        // priced at all
+        let Some(price) = (amount > Decimal::ZERO)
+            .then(|| event.usd_value.checked_div(amount))
+            .flatten()
+        else {
+            return Some(self.trigger(
+                Evidence::new(&self.id, "amount", &event.amount),
+                "price_band",
+            ));
+        };
+
+        let price = price.normalize();
+        let bound = if price < band.min {
+            band.min
+        } else if price > band.max {
+            band.max
+        } else {
+            return None;
+        };
+        Some(RuleResult::trigger(
+            self.action,
+            Evidence::with_limit(&self.id, "usd_price", price.to_string(), bound.to_string())
+                .with_details(serde_json::json!({
+                    "check": "price_band",
+                    "asset": asset,
+                    "amount": event.amount,
+                    "usd_value": event.usd_value.to_string(),
+                    "band": { "min": band.min.to_string(), "max": band.max.to_string() },
+                })),
+        ))
+    }
+}
+
+impl InlineRule for AmountSanityRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        let bands: BTreeMap<&String, &PriceBand> = self.price_bands.iter().collect();
+        RuleDescription::new(
+            &self.id,
+            RuleType::AmountSanity,
+            Some(self.action),
+            serde_json::json!({
+                "max_usd": self.max_usd.map(Money::usd),
+                "price_bands_usd": bands,
+            }),
+        )
+    }
+
+    fn evaluate(&self, event: &TxEvent) -> RuleResult {
+        let usd_value = event.usd_value;
+        if usd_value <= Decimal::ZERO {
+            return self.trigger(
+                Evidence::new(&self.id, "usd_value", usd_value.to_string()),
+                "non_positive",
+            );
+        }
+        if let Some(max) = self.max_usd.filter(|max| usd_value > *max) {
+            return self.trigger(
+                Evidence::with_limit(
+                    &self.id,
+                    "usd_value",
+                    usd_value.to_string(),
+                    max.to_string(),
+                ),
+                "max_usd",
+            );
+        }
+
+        self.check_price(event).unwrap_or_else(RuleResult::allow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use smallvec::smallvec;
+
+    fn event(asset: &str, amount: &str, usd_value: i64) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![Address::new("0xabc")],
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        };
+        let mut event = TxEvent::new(
+            subject,
+            Asset::new(asset),
+            Decimal::new(usd_value, 0),
+            Direction::Outbound,
+        );
+        event.amount = amount.to_string();
+        event
+    }
+
+    fn rule() -> AmountSanityRule {
+        AmountSanityRule::new("R_SANITY".to_string(), Decision::RejectFatal)
+            .with_max_usd(Some(Decimal::new(10_000_000, 0)))
+            .with_price_bands(HashMap::from([(
+                "btc".to_string(),
+                PriceBand {
+                    min: Decimal::new(1_000, 0),
+                    max: Decimal::new(1_000_000, 0),
+                },
+            )]))
+    }
+
+    #[test]
+    fn test_plausible_values_allowed() {
+        let rule = rule();
+        assert!(!rule.evaluate(&event("BTC", "0.5", 30_000)).hit);
+        // Assets without a band and events without an amount skip the
+        // price check
+        assert!(!rule.evaluate(&event("USDC", "1", 5_000)).hit);
+        assert!(!rule.evaluate(&event("BTC", "", 5_000)).hit);
+    }
+
+    #[test]
+    fn test_non_positive_and_max() {
+        let rule = rule();
+
+        let result = rule.evaluate(&event("USDC", "", 0));
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::RejectFatal);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.key, "usd_value");
+        assert_eq!(ev.details["check"], "non_positive");
+
+        let ev = rule
+            .evaluate(&event("USDC", "", 2_000_000_000))
+            .evidence
+            .unwrap();
+        assert_eq!(ev.limit, Some("10000000".to_string()));
+        assert_eq!(ev.details["check"], "max_usd");
+    }
+
+    #[test]
+    fn test_price_band() {
+        let rule = rule();
+
+        // 1 satoshi-sized amount priced as a whole coin
+        let ev = rule
+            .evaluate(&event("BTC", "0.00000001", 60_000))
+            .evidence
+            .unwrap();
+        assert_eq!(ev.key, "usd_price");
+        assert_eq!(ev.value, "6000000000000");
+        assert_eq!(ev.limit, Some("1000000".to_string()));
+
+        // Amount in base units instead of coins
+        let ev = rule
+            .evaluate(&event("BTC", "50000000", 30_000))
+            .evidence
+            .unwrap();
+        assert_eq!(ev.limit, Some("1000".to_string()));
+
+        let ev = rule.evaluate(&event("BTC", "0", 30_000)).evidence.unwrap();
+        assert_eq!(ev.key, "amount");
+    }
+}
@@ -0,0 +1,201 @@
+use rust_decimal::Decimal;
+use std::collections::HashSet;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{
+    CompositeSignals, Decision, Evidence, KycTier, Money, RuleType, TierRanking, TxEvent,
+};
+use crate::rules::traits::{InlineRule, RuleDescription};
+
+/// Composite risk rule.
+///
+/// Triggers when several weak signals co-occur, none of which warrants
+/// action alone: the subject's country is in a watched group, its KYC tier
+/// is at or below a ceiling, it is new to the platform, or the transaction
+/// is large. By default every configured signal must match.
+#[derive(Debug)]
+pub struct CompositeRiskRule {
+    id: String,
+    action: Decision,
+    /// Uppercase countries of the watched groups
+    countries: HashSet<String>,
+    max_kyc_tier: Option<KycTier>,
+    ranking: TierRanking,
+    new_subject: bool,
+    min_usd: Option<Decimal>,
+    min_signals: usize,
+}
+
+impl CompositeRiskRule {
+    /// Create a new rule; `countries` are those of `signals.country_groups`.
+    pub fn new(
+        id: String,
+        action: Decision,
+        signals: &CompositeSignals,
+        countries: HashSet<String>,
+        ranking: TierRanking,
+    ) -> Self {
+        CompositeRiskRule {
+            id,
+            action,
+            countries: countries.into_iter().map(|c| c.to_uppercase()).collect(),
+            max_kyc_tier: signals.max_kyc_tier.clone(),
+            ranking,
+            new_subject: signals.new_subject,
+            min_usd: signals.min_usd,
+            min_signals: signals.min_signals.unwrap_or_else(|| signals.configured()),
+        }
+    }
+
+    /// Names of the configured signals the event matches.
+    fn matched(&self, event: &TxEvent) -> Vec<&'static str> {
+        let subject = &event.subject;
+        let mut matched = Vec::new();
+        if self.countries.contains(subject.geo_iso.as_str()) {
+            matched.push("country_group");
+        }
+        if let Some(ref ceiling) = self.max_kyc_tier {
+            // Unranked tiers count as the lowest
+            if !self.ranking.meets(&subject.kyc_tier, ceiling)
+                || self.ranking.rank(&subject.kyc_tier) == self.ranking.rank(ceiling)
+            {
+                matched.push("kyc_tier");
+            }
+        }
+        if self.new_subject && event.subject_is_new == Some(true) {
+            matched.push("new_subject");
+        }
+        if self.min_usd.is_some_and(|min| event.usd_value >= min) {
+            matched.push("usd_value");
+        }
+        matched
+    }
+}
+
+impl InlineRule for CompositeRiskRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        let mut countries: Vec<&String> = self.countries.iter().collect();
+        countries.sort();
+        RuleDescription::new(
+            &self.id,
+            RuleType::CompositeRisk,
+            Some(self.action),
+            serde_json::json!({
+                "countries": countries,
+                "max_kyc_tier": self.max_kyc_tier.as_ref().map(|t| t.as_str()),
+                "new_subject": self.new_subject,
+                "min_usd": self.min_usd.map(Money::usd),
+                "min_signals": self.min_signals,
+            }),
+        )
+    }
+
+    fn evaluate(&self, event: &TxEvent) -> RuleResult {
+        if self.min_signals == 0 {
+            return RuleResult::allow();
+        }
+        let matched = self.matched(event);
+        if matched.len() < self.min_signals {
+            return RuleResult::allow();
+        }
+
+        RuleResult::trigger(
+            self.action,
+            Evidence::with_limit(
+                &self.id,
+                "composite_risk",
+                matched.len().to_string(),
+                self.min_signals.to_string(),
+            )
+            .with_details(serde_json::json!({
+                "signals": matched,
+                "geo_iso": event.subject.geo_iso.as_str(),
+                "kyc_tier": event.subject.kyc_tier.as_str(),
+            })),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, CountryCode, Subject, UserId};
+    use smallvec::SmallVec;
+
+    fn test_event(geo: &str, tier: KycTier, is_new: Option<bool>) -> TxEvent {
+        let mut event = TxEvent::new(
+            Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: SmallVec::new(),
+                geo_iso: CountryCode::new(geo),
+                kyc_tier: tier,
+            },
+            Asset::new("USDC"),
+            Decimal::new(500, 0),
+            Direction::Outbound,
+        );
+        event.subject_is_new = is_new;
+        event
+    }
+
+    fn rule(min_signals: Option<usize>) -> CompositeRiskRule {
+        let signals = CompositeSignals {
+            country_groups: vec!["medium_risk".to_string()],
+            max_kyc_tier: Some(KycTier::L0),
+            new_subject: true,
+            min_usd: None,
+            min_signals,
+        };
+        CompositeRiskRule::new(
+            "R9_WEAK_SIGNALS".to_string(),
+            Decision::Review,
+            &signals,
+            HashSet::from(["ng".to_string(), "PK".to_string()]),
+            TierRanking::default(),
+        )
+    }
+
+    fn hits(rule: &CompositeRiskRule, geo: &str, tier: KycTier, is_new: Option<bool>) -> bool {
+        rule.evaluate(&test_event(geo, tier, is_new)).hit
+    }
+
+    #[test]
+    fn test_all_signals_required_by_default() {
+        let rule = rule(None);
+
+        let result = rule.evaluate(&test_event("NG", KycTier::L0, Some(true)));
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::Review);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.key, "composite_risk");
+        assert_eq!(ev.value, "3");
+        assert_eq!(
+            ev.details["signals"],
+            serde_json::json!(["country_group", "kyc_tier", "new_subject"])
+        );
+
+        assert!(!hits(&rule, "US", KycTier::L0, Some(true)));
+        assert!(!hits(&rule, "NG", KycTier::L1, Some(true)));
+        assert!(!hits(&rule, "NG", KycTier::L0, Some(false)));
+        // Newness is unknown when storage was not consulted
+        assert!(!hits(&rule, "NG", KycTier::L0, None));
+    }
+
+    #[test]
+    fn test_min_signals() {
+        let rule = rule(Some(2));
+
+        assert!(hits(&rule, "PK", KycTier::L0, None));
+        assert!(hits(&rule, "US", KycTier::L0, Some(true)));
+        assert!(!hits(&rule, "US", KycTier::L2, Some(true)));
+        // Unranked tiers count as the lowest
+        let unranked = KycTier::from_str("PENDING").unwrap();
+        assert!(hits(&rule, "NG", unranked, None));
+    }
+}
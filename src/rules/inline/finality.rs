@@ -0,0 +1,168 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::domain::event::Direction;
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, RuleType, TxEvent};
+use crate::rules::traits::{InlineRule, RuleDescription};
+
+/// Evidence key of a deposit held until it reaches finality.
+pub const FINALITY_EVIDENCE_KEY: &str = "confirmations";
+
+/// Pending finality rule.
+///
+/// Holds inbound transfers that have fewer confirmations than their chain
+/// needs for finality. The required depth is the larger of the policy's
+/// depth for the chain and the `max_finality_depth` sent with the event.
+/// Held deposits are released through confirmation updates.
+#[derive(Debug)]
+pub struct FinalityRule {
+    id: String,
+    action: Decision,
+    /// Confirmations required per chain, keyed by uppercase chain name
+    depths: HashMap<String, u32>,
+}
+
+impl FinalityRule {
+    /// Create a new finality rule.
+    pub fn new(id: String, action: Decision, depths: HashMap<String, u32>) -> Self {
+        let depths = depths
+            .into_iter()
+            .map(|(chain, depth)| (chain.to_uppercase(), depth))
+            .collect();
+
+        FinalityRule { id, action, depths }
+    }
+
+    /// Confirmations needed before `event` is final.
+    pub fn required_depth(&self, event: &TxEvent) -> u32 {
+        let policy_depth = self
+            .depths
+            .get(&event.chain.0.to_uppercase())
+            .copied()
+            .unwrap_or(0);
+        policy_depth.max(event.max_finality_depth)
+    }
+}
+
+impl InlineRule for FinalityRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        let depths: BTreeMap<&String, &u32> = self.depths.iter().collect();
+        RuleDescription::new(
+            &self.id,
+            RuleType::PendingFinality,
+            Some(self.action),
+            serde_json::json!({ "confirmations": depths }),
+        )
+    }
+
+    fn evaluate(&self, event: &TxEvent) -> RuleResult {
+        if event.direction != Direction::Inbound {
+            return RuleResult::allow();
+        }
+
+        let required = self.required_depth(event);
+        if event.confirmations < required {
+            return RuleResult::trigger(
+                self.action,
+                Evidence::with_limit(
+                    &self.id,
+                    FINALITY_EVIDENCE_KEY,
+                    event.confirmations.to_string(),
+                    required.to_string(),
+                )
+                .with_details(serde_json::json!({ "chain": event.chain.0 })),
+            );
+        }
+
+        RuleResult::allow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Chain, EventId, SCHEMA_VERSION};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use smallvec::smallvec;
+
+    fn test_event(direction: Direction, confirmations: u32, depth: u32) -> TxEvent {
+        TxEvent {
+            schema_version: SCHEMA_VERSION.to_string(),
+            event_id: EventId::new(),
+            occurred_at: Utc::now(),
+            observed_at: Utc::now(),
+            subject: Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: smallvec![Address::new("0xabc")],
+                geo_iso: CountryCode::new("US"),
+                kyc_tier: KycTier::L1,
+            },
+            chain: Chain::new("ethereum"),
+            tx_hash: String::new(),
+            direction,
+            tx_type: "deposit".to_string(),
+            asset: Asset::new("ETH"),
+            amount: "1".to_string(),
+            usd_value: Decimal::new(3000, 0),
+            usd_rate: None,
+            confirmations,
+            max_finality_depth: depth,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
+        }
+    }
+
+    fn rule() -> FinalityRule {
+        FinalityRule::new(
+            "R_FINALITY".to_string(),
+            Decision::HoldAuto,
+            HashMap::from([("Ethereum".to_string(), 12)]),
+        )
+    }
+
+    #[test]
+    fn test_holds_unconfirmed_deposit() {
+        let result = rule().evaluate(&test_event(Direction::Inbound, 3, 0));
+
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::HoldAuto);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.key, FINALITY_EVIDENCE_KEY);
+        assert_eq!(ev.value, "3");
+        assert_eq!(ev.limit, Some("12".to_string()));
+    }
+
+    #[test]
+    fn test_final_deposit_allowed() {
+        assert!(!rule().evaluate(&test_event(Direction::Inbound, 12, 0)).hit);
+    }
+
+    #[test]
+    fn test_event_depth_can_only_raise_requirement() {
+        let rule = rule();
+        assert_eq!(
+            rule.required_depth(&test_event(Direction::Inbound, 0, 30)),
+            30
+        );
+        assert_eq!(
+            rule.required_depth(&test_event(Direction::Inbound, 0, 1)),
+            12
+        );
+    }
+
+    #[test]
+    fn test_withdrawals_ignored() {
+        assert!(!rule().evaluate(&test_event(Direction::Outbound, 0, 0)).hit);
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, RuleType, TxEvent};
+use crate::geoip::IpIntelligence;
+use crate::rules::traits::{InlineRule, RuleDescription};
+
+/// Client IP jurisdiction screening rule.
+///
+/// Blocks requests whose client IP is located in a blocked country or
+/// belongs to a sanctioned autonomous system, whatever `geo_iso` the caller
+/// reports. Requests without a client IP, or whose IP the databases don't
+/// cover, pass.
+#[derive(Debug)]
+pub struct IpJurisdictionRule {
+    id: String,
+    action: Decision,
+    /// Set of blocked country codes (uppercase)
+    blocked: HashSet<String>,
+    sanctioned_asns: HashSet<u32>,
+    intel: Arc<dyn IpIntelligence>,
+}
+
+impl IpJurisdictionRule {
+    /// Create a new rule looking addresses up with `intel`.
+    pub fn new(
+        id: String,
+        action: Decision,
+        blocked_countries: HashSet<String>,
+        sanctioned_asns: HashSet<u32>,
+        intel: Arc<dyn IpIntelligence>,
+    ) -> Self {
+        let blocked = blocked_countries
+            .into_iter()
+            .map(|c| c.to_uppercase())
+            .collect();
+
+        IpJurisdictionRule {
+            id,
+            action,
+            blocked,
+            sanctioned_asns,
+            intel,
+        }
+    }
+}
+
+impl InlineRule for IpJurisdictionRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        let mut blocked: Vec<&String> = self.blocked.iter().collect();
+        blocked.sort();
+        let mut asns: Vec<&u32> = self.sanctioned_asns.iter().collect();
+        asns.sort();
+        RuleDescription::new(
+            &self.id,
+            RuleType::IpJurisdiction,
+            Some(self.action),
+            serde_json::json!({ "blocked_countries": blocked, "sanctioned_asns": asns }),
+        )
+    }
+
+    fn evaluate(&self, event: &TxEvent) -> RuleResult {
+        let Some(ip) = event.client_ip else {
+            return RuleResult::allow();
+        };
+        let info = self.intel.lookup(ip);
+
+        let details = serde_json::json!({
+            "ip": ip.to_string(),
+            "ip_country": info.country,
+            "asn": info.asn,
+            "as_org": info.as_org,
+            "claimed_geo_iso": event.subject.geo_iso.as_str(),
+        });
+
+        if let Some(asn) = info.asn.filter(|asn| self.sanctioned_asns.contains(asn)) {
+            return RuleResult::trigger(
+                self.action,
+                Evidence::new(&self.id, "asn", asn.to_string()).with_details(details),
+            );
+        }
+
+        if let Some(country) = info.country.as_ref().filter(|c| self.blocked.contains(*c)) {
+            return RuleResult::trigger(
+                self.action,
+                Evidence::new(&self.id, "ip_country", country.as_str()).with_details(details),
+            );
+        }
+
+        RuleResult::allow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, CountryCode, KycTier, Subject, UserId};
+    use crate::geoip::IpInfo;
+    use rust_decimal::Decimal;
+    use std::collections::HashMap;
+    use std::net::IpAddr;
+
+    #[derive(Debug)]
+    struct FixedIntel(HashMap<IpAddr, IpInfo>);
+
+    impl IpIntelligence for FixedIntel {
+        fn lookup(&self, ip: IpAddr) -> IpInfo {
+            self.0.get(&ip).cloned().unwrap_or_default()
+        }
+    }
+
+    fn rule() -> IpJurisdictionRule {
+        let intel = FixedIntel(HashMap::from([
+            (
+                "198.51.100.1".parse().unwrap(),
+                IpInfo {
+                    country: Some("IR".to_string()),
+                    asn: Some(64500),
+                    as_org: None,
+                },
+            ),
+            (
+                "198.51.100.2".parse().unwrap(),
+                IpInfo {
+                    country: Some("NL".to_string()),
+                    asn: Some(64666),
+                    as_org: Some("Sanctioned Hosting".to_string()),
+                },
+            ),
+            (
+                "203.0.113.7".parse().unwrap(),
+                IpInfo {
+                    country: Some("US".to_string()),
+                    asn: Some(64501),
+                    as_org: None,
+                },
+            ),
+        ]));
+
+        IpJurisdictionRule::new(
+            "R_IP_JURISDICTION".to_string(),
+            Decision::RejectFatal,
+            HashSet::from(["ir".to_string()]),
+            HashSet::from([64666]),
+            Arc::new(intel),
+        )
+    }
+
+    fn event(ip: Option<&str>) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec::smallvec![],
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        };
+        let mut event = TxEvent::new(
+            subject,
+            Asset::new("USDC"),
+            Decimal::new(1000, 0),
+            Direction::Outbound,
+        );
+        event.client_ip = ip.map(|ip| ip.parse().unwrap());
+        event
+    }
+
+    #[test]
+    fn test_blocked_ip_country_despite_claimed_geo() {
+        let result = rule().evaluate(&event(Some("198.51.100.1")));
+
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::RejectFatal);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.key, "ip_country");
+        assert_eq!(evidence.value, "IR");
+        assert_eq!(evidence.details["claimed_geo_iso"], "US");
+    }
+
+    #[test]
+    fn test_sanctioned_asn() {
+        let result = rule().evaluate(&event(Some("198.51.100.2")));
+
+        assert!(result.hit);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.key, "asn");
+        assert_eq!(evidence.value, "64666");
+        assert_eq!(evidence.details["as_org"], "Sanctioned Hosting");
+    }
+
+    #[test]
+    fn test_unknown_or_missing_ip_passes() {
+        let rule = rule();
+
+        assert!(!rule.evaluate(&event(Some("203.0.113.7"))).hit);
+        assert!(!rule.evaluate(&event(Some("192.0.2.1"))).hit);
+        assert!(!rule.evaluate(&event(None)).hit);
+    }
+}
@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
 use crate::domain::evidence::RuleResult;
+use crate::domain::RuleType;
 use crate::domain::{Decision, Evidence, TxEvent};
-use crate::rules::traits::InlineRule;
+use crate::rules::traits::{InlineRule, RuleDescription};
 
 /// Jurisdiction blocking rule.
 ///
@@ -43,6 +44,17 @@ impl InlineRule for JurisdictionRule {
         &self.id
     }
 
+    fn describe(&self) -> RuleDescription {
+        let mut blocked: Vec<&String> = self.blocked.iter().collect();
+        blocked.sort();
+        RuleDescription::new(
+            &self.id,
+            RuleType::JurisdictionBlock,
+            Some(self.action),
+            serde_json::json!({ "blocked_countries": blocked }),
+        )
+    }
+
     fn evaluate(&self, event: &TxEvent) -> RuleResult {
         let country = event.subject.geo_iso.as_str();
 
@@ -79,11 +91,19 @@ mod tests {
             chain: Chain::inline(),
             tx_hash: String::new(),
             direction: Direction::Outbound,
+            tx_type: String::new(),
             asset: Asset::new("USDC"),
             amount: "1000".to_string(),
             usd_value: Decimal::new(1000, 0),
+            usd_rate: None,
             confirmations: 0,
             max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
         }
     }
 
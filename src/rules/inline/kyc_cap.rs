@@ -1,9 +1,11 @@
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 
 use crate::domain::evidence::RuleResult;
-use crate::domain::{Decision, Evidence, TxEvent};
-use crate::rules::traits::InlineRule;
+use crate::domain::{Decision, Evidence, KycTier, Money, RuleType, TxEvent};
+use crate::rules::limit_matrix::LimitMatrix;
+use crate::rules::traits::{InlineRule, RuleDescription};
 
 /// KYC tier transaction cap rule.
 ///
@@ -13,17 +15,30 @@ pub struct KycCapRule {
     id: String,
     action: Decision,
     /// Per-tier caps in USD
-    caps: HashMap<String, Decimal>,
+    caps: HashMap<KycTier, Decimal>,
+    /// Geography adjustments applied to the tier cap
+    matrix: Arc<LimitMatrix>,
 }
 
 impl KycCapRule {
     /// Create a new KYC cap rule with tier limits.
-    pub fn new(id: String, action: Decision, caps: HashMap<String, Decimal>) -> Self {
-        KycCapRule { id, action, caps }
+    pub fn new(id: String, action: Decision, caps: HashMap<KycTier, Decimal>) -> Self {
+        KycCapRule {
+            id,
+            action,
+            caps,
+            matrix: Arc::default(),
+        }
+    }
+
+    /// Scale caps by the subject's KYC tier and country.
+    pub fn with_limit_matrix(mut self, matrix: Arc<LimitMatrix>) -> Self {
+        self.matrix = matrix;
+        self
     }
 
     /// Get the cap for a KYC tier, if any.
-    fn get_cap(&self, tier: &str) -> Option<Decimal> {
+    fn get_cap(&self, tier: &KycTier) -> Option<Decimal> {
         self.caps.get(tier).copied()
     }
 }
@@ -33,13 +48,27 @@ impl InlineRule for KycCapRule {
         &self.id
     }
 
+    fn describe(&self) -> RuleDescription {
+        let caps: BTreeMap<&str, Money> = self
+            .caps
+            .iter()
+            .map(|(tier, cap)| (tier.as_str(), Money::usd(*cap)))
+            .collect();
+        RuleDescription::new(
+            &self.id,
+            RuleType::KycTierTxCap,
+            Some(self.action),
+            serde_json::json!({ "caps_usd": caps }),
+        )
+    }
+
     fn evaluate(&self, event: &TxEvent) -> RuleResult {
-        let tier = event.subject.kyc_tier.as_str();
+        let tier = &event.subject.kyc_tier;
         let usd_value = event.usd_value;
 
         // Get cap for this tier; if no cap defined, allow
         let cap = match self.get_cap(tier) {
-            Some(c) if c > Decimal::ZERO => c,
+            Some(c) if c > Decimal::ZERO => self.matrix.scale(c, &event.subject),
             _ => return RuleResult::allow(),
         };
 
@@ -64,7 +93,7 @@ impl InlineRule for KycCapRule {
 mod tests {
     use super::*;
     use crate::domain::event::{Asset, Chain, Direction, EventId, SCHEMA_VERSION};
-    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::domain::subject::{AccountId, Address, CountryCode, Subject, UserId};
     use chrono::Utc;
     use smallvec::smallvec;
 
@@ -84,19 +113,27 @@ mod tests {
             chain: Chain::inline(),
             tx_hash: String::new(),
             direction: Direction::Outbound,
+            tx_type: String::new(),
             asset: Asset::new("USDC"),
             amount: usd_value.to_string(),
             usd_value: Decimal::new(usd_value, 0),
+            usd_rate: None,
             confirmations: 0,
             max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
         }
     }
 
-    fn test_caps() -> HashMap<String, Decimal> {
+    fn test_caps() -> HashMap<KycTier, Decimal> {
         HashMap::from([
-            ("L0".to_string(), Decimal::new(1000, 0)),   // $1,000
-            ("L1".to_string(), Decimal::new(5000, 0)),   // $5,000
-            ("L2".to_string(), Decimal::new(100000, 0)), // $100,000
+            (KycTier::L0, Decimal::new(1000, 0)),   // $1,000
+            (KycTier::L1, Decimal::new(5000, 0)),   // $5,000
+            (KycTier::L2, Decimal::new(100000, 0)), // $100,000
         ])
     }
 
@@ -154,7 +191,7 @@ mod tests {
     #[test]
     fn test_unknown_tier_no_limit() {
         // If tier not in caps map, no limit applies
-        let caps = HashMap::from([("L0".to_string(), Decimal::new(1000, 0))]);
+        let caps = HashMap::from([(KycTier::L0, Decimal::new(1000, 0))]);
         let rule = KycCapRule::new("R3_KYC".to_string(), Decision::HoldAuto, caps);
 
         // L1 not in caps, so no limit
@@ -162,4 +199,42 @@ mod tests {
         let result = rule.evaluate(&event);
         assert!(!result.hit);
     }
+
+    #[test]
+    fn test_limit_matrix_scales_cap() {
+        let params: crate::domain::RuleParams = serde_yaml::from_str(
+            "country_groups: { domestic: [US] }\nlimit_matrix: { L1: { domestic: 0.5 } }",
+        )
+        .unwrap();
+        let rule = KycCapRule::new("R3_KYC".to_string(), Decision::HoldAuto, test_caps())
+            .with_limit_matrix(Arc::new(LimitMatrix::from_params(&params)));
+
+        // L1 cap of $5,000 is halved for US subjects
+        let result = rule.evaluate(&test_event(KycTier::L1, 3000));
+        assert!(result.hit);
+        assert_eq!(result.evidence.unwrap().limit, Some("2500".to_string()));
+
+        // Other tiers are unaffected
+        let result = rule.evaluate(&test_event(KycTier::L0, 1000));
+        assert!(!result.hit);
+    }
+
+    #[test]
+    fn test_policy_defined_tiers() {
+        let params: crate::domain::RuleParams = serde_yaml::from_str(
+            "kyc_tiers: [L0, L1, L2, L3, corporate]\nkyc_tier_caps_usd: { l3: 250000, Corporate: 1000000 }",
+        )
+        .unwrap();
+        let rule = KycCapRule::new(
+            "R3_KYC".to_string(),
+            Decision::HoldAuto,
+            params.kyc_tier_caps_usd,
+        );
+        let tier = |s: &str| KycTier::from_str(s).unwrap();
+
+        assert!(!rule.evaluate(&test_event(tier("L3"), 200000)).hit);
+        assert!(rule.evaluate(&test_event(tier("L3"), 300000)).hit);
+        let result = rule.evaluate(&test_event(tier("corporate"), 2000000));
+        assert_eq!(result.evidence.unwrap().limit, Some("1000000".to_string()));
+    }
 }
@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{
+    Decision, Evidence, KycTier, MinKycRequirement, RuleType, TierRanking, TxEvent,
+};
+use crate::rules::traits::{InlineRule, RuleDescription};
+
+/// Minimum KYC tier per transaction type.
+///
+/// Requires a minimum verification level for specific transaction types,
+/// such as L2 for OTC trades, each with its own action. Types without a
+/// requirement are allowed. Tiers are compared by the policy's ranking.
+#[derive(Debug)]
+pub struct MinKycTierRule {
+    id: String,
+    /// Required tier and action, keyed by lowercase transaction type
+    requirements: HashMap<String, (KycTier, Decision)>,
+    ranking: TierRanking,
+}
+
+impl MinKycTierRule {
+    /// Create a new rule; requirements without an action use `action`.
+    pub fn new(
+        id: String,
+        action: Decision,
+        requirements: HashMap<String, MinKycRequirement>,
+    ) -> Self {
+        let requirements = requirements
+            .into_iter()
+            .map(|(tx_type, req)| {
+                (
+                    tx_type.to_lowercase(),
+                    (req.tier, req.action.unwrap_or(action)),
+                )
+            })
+            .collect();
+
+        MinKycTierRule {
+            id,
+            requirements,
+            ranking: TierRanking::default(),
+        }
+    }
+
+    /// Order tiers by the policy's ranking instead of L0 < L1 < L2.
+    pub fn with_ranking(mut self, ranking: TierRanking) -> Self {
+        self.ranking = ranking;
+        self
+    }
+}
+
+impl InlineRule for MinKycTierRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        let requirements: BTreeMap<&str, serde_json::Value> = self
+            .requirements
+            .iter()
+            .map(|(tx_type, (tier, action))| {
+                (
+                    tx_type.as_str(),
+                    serde_json::json!({ "tier": tier.as_str(), "action": action }),
+                )
+            })
+            .collect();
+        // Each transaction type carries its own action
+        RuleDescription::new(
+            &self.id,
+            RuleType::MinKycTier,
+            None,
+            serde_json::json!({ "requirements": requirements }),
+        )
+    }
+
+    fn evaluate(&self, event: &TxEvent) -> RuleResult {
+        let Some((required, action)) = self.requirements.get(&event.tx_type) else {
+            return RuleResult::allow();
+        };
+
+        let tier = &event.subject.kyc_tier;
+        if !self.ranking.meets(tier, required) {
+            return RuleResult::trigger(
+                *action,
+                Evidence::with_limit(&self.id, "kyc_tier", tier.as_str(), required.as_str())
+                    .with_details(serde_json::json!({ "tx_type": event.tx_type })),
+            );
+        }
+
+        RuleResult::allow()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, CountryCode, Subject, UserId};
+    use rust_decimal::Decimal;
+    use smallvec::SmallVec;
+
+    fn test_event(tx_type: &str, kyc_tier: KycTier) -> TxEvent {
+        let mut event = TxEvent::new(
+            Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: SmallVec::new(),
+                geo_iso: CountryCode::new("US"),
+                kyc_tier,
+            },
+            Asset::new("USDC"),
+            Decimal::new(100, 0),
+            Direction::Outbound,
+        );
+        event.tx_type = tx_type.to_string();
+        event
+    }
+
+    fn test_rule() -> MinKycTierRule {
+        let requirements: HashMap<String, MinKycRequirement> = serde_yaml::from_str(
+            "OTC_TRADE: { tier: L2, action: REJECT_FATAL }\ncard_purchase: { tier: L1 }",
+        )
+        .unwrap();
+        MinKycTierRule::new("R6_MIN_KYC".to_string(), Decision::HoldAuto, requirements)
+    }
+
+    #[test]
+    fn test_below_required_tier() {
+        let rule = test_rule();
+
+        let result = rule.evaluate(&test_event("otc_trade", KycTier::L1));
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::RejectFatal);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.value, "L1");
+        assert_eq!(ev.limit, Some("L2".to_string()));
+        assert_eq!(ev.details["tx_type"], "otc_trade");
+
+        // Falls back to the rule's action
+        let result = rule.evaluate(&test_event("card_purchase", KycTier::L0));
+        assert_eq!(result.decision, Decision::HoldAuto);
+    }
+
+    #[test]
+    fn test_meets_required_tier() {
+        let rule = test_rule();
+
+        assert!(!rule.evaluate(&test_event("otc_trade", KycTier::L2)).hit);
+        assert!(!rule.evaluate(&test_event("card_purchase", KycTier::L1)).hit);
+        // No requirement for withdrawals
+        assert!(!rule.evaluate(&test_event("withdraw", KycTier::L0)).hit);
+    }
+
+    #[test]
+    fn test_policy_ranking() {
+        let ranking: TierRanking = serde_yaml::from_str("[L0, L1, L2, L3, CORPORATE]").unwrap();
+        let rule = test_rule().with_ranking(ranking);
+        let tier = |s: &str| KycTier::from_str(s).unwrap();
+
+        assert!(!rule.evaluate(&test_event("otc_trade", tier("L3"))).hit);
+        assert!(
+            !rule
+                .evaluate(&test_event("otc_trade", tier("corporate")))
+                .hit
+        );
+        // Tiers missing from the ranking meet no requirement
+        assert!(rule.evaluate(&test_event("card_purchase", tier("L9"))).hit);
+    }
+}
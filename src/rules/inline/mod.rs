@@ -1,7 +1,17 @@
+mod amount_sanity;
+mod composite;
+mod finality;
+mod ip_jurisdiction;
 mod jurisdiction;
 mod kyc_cap;
+mod min_kyc;
 mod ofac;
 
+pub use amount_sanity::AmountSanityRule;
+pub use composite::CompositeRiskRule;
+pub use finality::{FinalityRule, FINALITY_EVIDENCE_KEY};
+pub use ip_jurisdiction::IpJurisdictionRule;
 pub use jurisdiction::JurisdictionRule;
 pub use kyc_cap::KycCapRule;
+pub use min_kyc::MinKycTierRule;
 pub use ofac::OfacRule;
@@ -1,63 +1,64 @@
-use bloomfilter::Bloom;
 use std::collections::HashSet;
+use std::sync::Arc;
 
 use crate::domain::evidence::RuleResult;
+use crate::domain::RuleType;
 use crate::domain::{Decision, Evidence, TxEvent};
-use crate::rules::traits::InlineRule;
+use crate::rules::sanctions::{BloomOptions, SanctionsList};
+use crate::rules::traits::{InlineRule, RuleDescription};
 
 /// OFAC sanctions address screening rule.
 ///
 /// Uses a bloom filter for fast negative checks, with a hash set
 /// for definitive verification. This provides O(1) average case
 /// for clean addresses (the common case).
+///
+/// The destination is screened by address and, when the transfer carries
+/// a destination tag or memo, by the tagged entry for that account, so an
+/// exchange-hosted sanctioned account is caught without listing the
+/// exchange's shared address.
 #[derive(Debug)]
 pub struct OfacRule {
     id: String,
     action: Decision,
-    /// Bloom filter for fast negative check
-    bloom: Bloom<String>,
-    /// Definitive set for positive verification
-    addresses: HashSet<String>,
+    /// Shared sanctions list
+    sanctions: Arc<SanctionsList>,
 }
 
 impl OfacRule {
     /// Create a new OFAC rule with the given sanctions list.
     pub fn new(id: String, action: Decision, sanctions: HashSet<String>) -> Self {
-        // Create bloom filter with expected size and false positive rate
-        let item_count = sanctions.len().max(100);
-        let fp_rate = 0.01; // 1% false positive rate
-        let mut bloom = Bloom::new_for_fp_rate(item_count, fp_rate);
-
-        // Normalize and add all addresses
-        let normalized: HashSet<String> = sanctions
-            .into_iter()
-            .map(|addr| addr.to_lowercase())
-            .collect();
-
-        for addr in &normalized {
-            bloom.set(addr);
-        }
+        let list = SanctionsList::new(sanctions, BloomOptions::default());
+        OfacRule::with_list(id, action, Arc::new(list))
+    }
 
+    /// Create a new OFAC rule over an already built sanctions list.
+    pub fn with_list(id: String, action: Decision, sanctions: Arc<SanctionsList>) -> Self {
         OfacRule {
             id,
             action,
-            bloom,
-            addresses: normalized,
+            sanctions,
         }
     }
 
     /// Check if an address is sanctioned.
     #[inline]
     fn is_sanctioned(&self, addr: &str) -> bool {
-        let normalized = addr.to_lowercase();
+        self.sanctions.contains(addr)
+    }
 
-        // Fast path: bloom filter says definitely not present
-        if !self.bloom.check(&normalized) {
-            return false;
+    fn trigger(&self, key: &str, addr: &str, tag: Option<&str>) -> RuleResult {
+        let mut details = serde_json::json!({
+            "list": self.sanctions.name(),
+            "list_version": self.sanctions.version(),
+        });
+        if let Some(tag) = tag {
+            details["tag"] = serde_json::json!(tag);
         }
-
-        // Slow path: verify in hash set (bloom filter may have false positive)
-        self.addresses.contains(&normalized)
+        RuleResult::trigger(
+            self.action,
+            Evidence::new(&self.id, key, addr).with_details(details),
+        )
     }
 }
 
@@ -66,14 +67,38 @@ impl InlineRule for OfacRule {
         &self.id
     }
 
+    fn describe(&self) -> RuleDescription {
+        // The entries themselves are not disclosed
+        RuleDescription::new(
+            &self.id,
+            RuleType::OfacAddr,
+            Some(self.action),
+            serde_json::json!({
+                "list": self.sanctions.name(),
+                "list_version": self.sanctions.version(),
+                "entries": self.sanctions.len(),
+            }),
+        )
+    }
+
     fn evaluate(&self, event: &TxEvent) -> RuleResult {
         // Check all subject addresses
         for addr in &event.subject.addresses {
             if self.is_sanctioned(addr.as_str()) {
-                return RuleResult::trigger(
-                    self.action,
-                    Evidence::new(&self.id, "address", addr.as_str()),
-                );
+                return self.trigger("address", addr.as_str(), None);
+            }
+        }
+
+        if let Some(dest) = &event.destination {
+            let addr = dest.address.as_str();
+            let tag = dest.tag.as_deref();
+            if let Some(tag) = tag {
+                if self.sanctions.contains_tagged(addr, tag) {
+                    return self.trigger("dest_tag", addr, Some(tag));
+                }
+            }
+            if self.is_sanctioned(addr) {
+                return self.trigger("dest_address", addr, tag);
             }
         }
 
@@ -84,7 +109,7 @@ impl InlineRule for OfacRule {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::event::{Asset, Chain, Direction, EventId, SCHEMA_VERSION};
+    use crate::domain::event::{Asset, Chain, Destination, Direction, EventId, SCHEMA_VERSION};
     use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
     use chrono::Utc;
     use rust_decimal::Decimal;
@@ -105,11 +130,19 @@ mod tests {
             chain: Chain::inline(),
             tx_hash: String::new(),
             direction: Direction::Outbound,
+            tx_type: String::new(),
             asset: Asset::new("USDC"),
             amount: "1000".to_string(),
             usd_value: Decimal::new(1000, 0),
+            usd_rate: None,
             confirmations: 0,
             max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
         }
     }
 
@@ -136,6 +169,7 @@ mod tests {
         assert!(result.hit);
         assert_eq!(result.decision, Decision::RejectFatal);
         assert_eq!(result.evidence.as_ref().unwrap().rule_id, "R1_OFAC");
+        assert_eq!(result.evidence.as_ref().unwrap().details["list"], "OFAC");
     }
 
     #[test]
@@ -160,4 +194,41 @@ mod tests {
 
         assert!(!result.hit);
     }
+
+    #[test]
+    fn test_tagged_destination() {
+        let sanctions = HashSet::from(["rexchange 12345".to_string()]);
+        let rule = OfacRule::new("R1_OFAC".to_string(), Decision::RejectFatal, sanctions);
+
+        let mut event = test_event(vec!["0xclean"]);
+        event.destination = Some(Destination::new("rExchange", Some("12345".to_string())));
+        let result = rule.evaluate(&event);
+
+        assert!(result.hit);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.key, "dest_tag");
+        assert_eq!(evidence.value, "rexchange");
+        assert_eq!(evidence.details["tag"], "12345");
+
+        // Other accounts at the shared address are not sanctioned
+        event.destination = Some(Destination::new("rExchange", Some("777".to_string())));
+        assert!(!rule.evaluate(&event).hit);
+        event.destination = Some(Destination::new("rExchange", None));
+        assert!(!rule.evaluate(&event).hit);
+    }
+
+    #[test]
+    fn test_sanctioned_destination_address() {
+        let sanctions = HashSet::from(["0xdead".to_string()]);
+        let rule = OfacRule::new("R1_OFAC".to_string(), Decision::RejectFatal, sanctions);
+
+        let mut event = test_event(vec!["0xclean"]);
+        event.destination = Some(Destination::new("0xDEAD", Some("1".to_string())));
+        let result = rule.evaluate(&event);
+
+        assert!(result.hit);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.key, "dest_address");
+        assert_eq!(evidence.details["tag"], "1");
+    }
 }
@@ -0,0 +1,129 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::domain::{KycTier, RuleParams, Subject};
+
+/// Limit multipliers by KYC tier and country group.
+///
+/// Built from the policy's `country_groups` and `limit_matrix` params and
+/// shared by the volume and cap rules, so a single matrix entry such as
+/// `L1: { high_risk: 0.5 }` halves every limit for L1 subjects in high-risk
+/// countries. A country in several groups gets the most restrictive factor;
+/// subjects matching no entry keep the base limit.
+#[derive(Debug, Clone, Default)]
+pub struct LimitMatrix {
+    /// Groups each country belongs to, keyed by uppercase country code
+    groups: HashMap<String, Vec<String>>,
+    /// Factors keyed by tier, then group
+    factors: HashMap<KycTier, HashMap<String, Decimal>>,
+}
+
+impl LimitMatrix {
+    /// Build the matrix from policy params.
+    pub fn from_params(params: &RuleParams) -> Self {
+        let mut groups: HashMap<String, Vec<String>> = HashMap::new();
+        for (group, countries) in &params.country_groups {
+            for country in countries {
+                groups
+                    .entry(country.to_uppercase())
+                    .or_default()
+                    .push(group.clone());
+            }
+        }
+
+        LimitMatrix {
+            groups,
+            factors: params.limit_matrix.clone(),
+        }
+    }
+
+    /// Returns true if no limits are adjusted.
+    pub fn is_empty(&self) -> bool {
+        self.factors.is_empty()
+    }
+
+    /// Multiplier for the subject's tier and country.
+    pub fn factor(&self, subject: &Subject) -> Decimal {
+        let (Some(by_group), Some(groups)) = (
+            self.factors.get(&subject.kyc_tier),
+            self.groups.get(subject.geo_iso.as_str()),
+        ) else {
+            return Decimal::ONE;
+        };
+
+        groups
+            .iter()
+            .filter_map(|g| by_group.get(g))
+            .copied()
+            .min()
+            .unwrap_or(Decimal::ONE)
+    }
+
+    /// Scale a base limit for the subject.
+    pub fn scale(&self, limit: Decimal, subject: &Subject) -> Decimal {
+        if self.is_empty() {
+            return limit;
+        }
+        (limit * self.factor(subject)).normalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::subject::{AccountId, CountryCode, KycTier, UserId};
+    use smallvec::smallvec;
+
+    fn subject(kyc_tier: KycTier, geo: &str) -> Subject {
+        Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![],
+            geo_iso: CountryCode::new(geo),
+            kyc_tier,
+        }
+    }
+
+    #[test]
+    fn test_scale_by_tier_and_group() {
+        let params: RuleParams = serde_yaml::from_str(
+            r#"
+country_groups:
+  high_risk: [ng, PK]
+  sanctioned_adjacent: [PK]
+limit_matrix:
+  L1: { high_risk: 0.5, sanctioned_adjacent: 0.25 }
+  L2: { high_risk: 0.8 }
+"#,
+        )
+        .unwrap();
+        let matrix = LimitMatrix::from_params(&params);
+        let limit = Decimal::new(50000, 0);
+
+        assert_eq!(
+            matrix.scale(limit, &subject(KycTier::L1, "NG")),
+            Decimal::new(25000, 0)
+        );
+        // Most restrictive group wins
+        assert_eq!(
+            matrix.scale(limit, &subject(KycTier::L1, "PK")),
+            Decimal::new(12500, 0)
+        );
+        assert_eq!(
+            matrix.scale(limit, &subject(KycTier::L2, "PK")),
+            Decimal::new(40000, 0)
+        );
+        assert_eq!(matrix.scale(limit, &subject(KycTier::L1, "US")), limit);
+        assert_eq!(
+            matrix.scale(limit, &subject(KycTier::from_str("L3").unwrap(), "NG")),
+            limit
+        );
+    }
+
+    #[test]
+    fn test_empty_matrix() {
+        let matrix = LimitMatrix::default();
+        assert!(matrix.is_empty());
+        assert_eq!(matrix.factor(&subject(KycTier::L1, "NG")), Decimal::ONE);
+    }
+}
@@ -0,0 +1,192 @@
+//! Mitigating rules: a secondary decision channel that caps the decision
+//! the rules reached under specific conditions, such as whitelisted
+//! corporate accounts never exceeding Review.
+//!
+//! Mitigations run after aggregation, in precedence order, and only the
+//! first that matches applies. Fatal decisions are never mitigated, so a
+//! sanctions hit cannot be overridden, and neither are deposits held for
+//! finality, which wait on confirmations rather than risk. A mitigation
+//! that lowers a decision adds evidence recording the decision it
+//! replaced, so the downgrade is audited with the hits that caused it.
+
+use super::FINALITY_EVIDENCE_KEY;
+use crate::domain::{Decision, Evidence, MitigationDef, Policy, TxEvent};
+
+/// Evidence key of a mitigation that lowered a decision.
+pub const MITIGATION_EVIDENCE_KEY: &str = "mitigated_decision";
+
+/// A policy's mitigations, ordered by precedence.
+#[derive(Debug, Clone, Default)]
+pub struct Mitigations {
+    defs: Vec<MitigationDef>,
+}
+
+impl Mitigations {
+    /// Collect the policy's mitigations.
+    pub fn from_policy(policy: &Policy) -> Self {
+        let mut defs = policy.mitigations.clone();
+        defs.sort_by_key(|m| m.precedence);
+        Mitigations { defs }
+    }
+
+    /// Cap `decision` by the first matching mitigation, appending evidence
+    /// when it is lowered.
+    pub fn apply(&self, event: &TxEvent, decision: &mut Decision, evidence: &mut Vec<Evidence>) {
+        if *decision == Decision::Allow
+            || decision.is_fatal()
+            || evidence.iter().any(|e| e.key == FINALITY_EVIDENCE_KEY)
+        {
+            return;
+        }
+
+        let Some(mitigation) = self.defs.iter().find(|m| matches(m, event, evidence)) else {
+            return;
+        };
+        if *decision <= mitigation.cap {
+            return;
+        }
+
+        let mitigated_rules: Vec<String> = evidence.iter().map(|e| e.rule_id.clone()).collect();
+        evidence.push(
+            Evidence::with_limit(
+                mitigation.id.clone(),
+                MITIGATION_EVIDENCE_KEY,
+                decision.to_string(),
+                mitigation.cap.to_string(),
+            )
+            .with_details(serde_json::json!({
+                "precedence": mitigation.precedence,
+                "mitigated_rules": mitigated_rules,
+            })),
+        );
+        *decision = mitigation.cap;
+    }
+}
+
+fn matches(mitigation: &MitigationDef, event: &TxEvent, evidence: &[Evidence]) -> bool {
+    let when = &mitigation.when;
+    let subject = &event.subject;
+
+    let listed =
+        |values: &[String], value: &str| values.is_empty() || values.iter().any(|v| v == value);
+    listed(&when.user_ids, subject.user_id.as_str())
+        && listed(&when.account_ids, subject.account_id.as_str())
+        && (when.countries.is_empty()
+            || when
+                .countries
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(subject.geo_iso.as_str())))
+        && (when.kyc_tiers.is_empty() || when.kyc_tiers.contains(&subject.kyc_tier))
+        && when.max_usd.is_none_or(|max| event.usd_value <= max)
+        && (mitigation.rules.is_empty()
+            || evidence
+                .iter()
+                .all(|e| mitigation.rules.contains(&e.rule_id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, CountryCode, UserId};
+    use crate::domain::{KycTier, MitigationConditions, Subject};
+    use rust_decimal::Decimal;
+
+    fn event(account_id: &str, usd: i64) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new(account_id),
+            addresses: smallvec::smallvec![],
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L2,
+        };
+        TxEvent::new(
+            subject,
+            Asset::new("USDC"),
+            Decimal::new(usd, 0),
+            Direction::Outbound,
+        )
+    }
+
+    fn mitigation(id: &str, precedence: i32, cap: Decision) -> MitigationDef {
+        MitigationDef {
+            id: id.to_string(),
+            precedence,
+            cap,
+            when: MitigationConditions {
+                account_ids: vec!["CORP".to_string()],
+                ..Default::default()
+            },
+            rules: Vec::new(),
+        }
+    }
+
+    fn mitigations(defs: Vec<MitigationDef>) -> Mitigations {
+        let mut policy = Policy::empty();
+        policy.mitigations = defs;
+        Mitigations::from_policy(&policy)
+    }
+
+    #[test]
+    fn test_first_matching_mitigation_caps_decision() {
+        let mitigations = mitigations(vec![
+            mitigation("M_LATER", 20, Decision::SoftDenyRetry),
+            mitigation("M_CORP", 10, Decision::HoldAuto),
+        ]);
+
+        let mut decision = Decision::Review;
+        let mut evidence = vec![Evidence::new("R4_DAILY", "daily_usd", "60000")];
+        mitigations.apply(&event("CORP", 100), &mut decision, &mut evidence);
+        assert_eq!(decision, Decision::HoldAuto);
+        assert_eq!(evidence[1].rule_id, "M_CORP");
+
+        // Already within the first match's cap
+        let mut decision = Decision::HoldAuto;
+        let mut evidence = vec![Evidence::new("R4_DAILY", "daily_usd", "60000")];
+        mitigations.apply(&event("CORP", 100), &mut decision, &mut evidence);
+        assert_eq!(decision, Decision::HoldAuto);
+        assert_eq!(evidence.len(), 1);
+
+        let mut decision = Decision::Review;
+        mitigations.apply(&event("RETAIL", 100), &mut decision, &mut vec![]);
+        assert_eq!(decision, Decision::Review);
+
+        // Fatal decisions are never mitigated
+        let mut decision = Decision::RejectFatal;
+        let mut evidence = vec![Evidence::new("R1_OFAC", "address", "0xdead")];
+        mitigations.apply(&event("CORP", 100), &mut decision, &mut evidence);
+        assert_eq!(decision, Decision::RejectFatal);
+        assert_eq!(evidence.len(), 1);
+    }
+
+    #[test]
+    fn test_mitigation_is_recorded_in_evidence() {
+        let mut m = mitigation("M_CORP", 10, Decision::SoftDenyRetry);
+        m.when.max_usd = Some(Decimal::new(1000, 0));
+        m.rules = vec!["R4_DAILY".to_string()];
+        let mitigations = mitigations(vec![m]);
+
+        let mut decision = Decision::Review;
+        let mut evidence = vec![Evidence::new("R4_DAILY", "daily_usd", "60000")];
+        mitigations.apply(&event("CORP", 100), &mut decision, &mut evidence);
+        assert_eq!(decision, Decision::SoftDenyRetry);
+        let recorded = &evidence[1];
+        assert_eq!(recorded.rule_id, "M_CORP");
+        assert_eq!(recorded.key, MITIGATION_EVIDENCE_KEY);
+        assert_eq!(recorded.value, Decision::Review.to_string());
+        assert_eq!(recorded.details["mitigated_rules"][0], "R4_DAILY");
+
+        // Over the amount, or with a rule it may not override
+        let mut decision = Decision::Review;
+        mitigations.apply(&event("CORP", 5000), &mut decision, &mut vec![]);
+        assert_eq!(decision, Decision::Review);
+
+        let mut decision = Decision::Review;
+        let mut evidence = vec![
+            Evidence::new("R4_DAILY", "daily_usd", "60000"),
+            Evidence::new("R5_STRUCT", "small_tx_count", "9"),
+        ];
+        mitigations.apply(&event("CORP", 100), &mut decision, &mut evidence);
+        assert_eq!(decision, Decision::Review);
+    }
+}
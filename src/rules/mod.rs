@@ -1,32 +1,120 @@
+#[cfg(feature = "server")]
+pub mod blocklist;
+pub mod compiled_sanctions;
+#[cfg(feature = "server")]
+pub mod concurrent;
+pub mod dispatch;
+pub mod features;
 pub mod inline;
+pub mod limit_matrix;
+pub mod mitigation;
+pub mod sanctions;
+pub mod sketch;
+#[cfg(feature = "server")]
+pub mod sla;
 pub mod streaming;
+pub mod subject_fields;
+#[cfg(feature = "server")]
+pub mod switches;
 pub mod traits;
+pub mod window;
 
-pub use inline::{JurisdictionRule, KycCapRule, OfacRule};
-pub use streaming::{DailyVolumeRule, StructuringRule};
-pub use traits::{InlineRule, StreamingRule};
+#[cfg(feature = "server")]
+pub use blocklist::{Blocklist, BLOCKLIST_RULE_ID};
+#[cfg(feature = "server")]
+pub use concurrent::{evaluate_streaming, StreamingEvaluation};
+pub use dispatch::{RuleDispatch, RuleIndex};
+pub use features::FeatureGates;
+pub use inline::{
+    AmountSanityRule, CompositeRiskRule, FinalityRule, IpJurisdictionRule, JurisdictionRule,
+    KycCapRule, MinKycTierRule, OfacRule, FINALITY_EVIDENCE_KEY,
+};
+pub use limit_matrix::LimitMatrix;
+pub use mitigation::{Mitigations, MITIGATION_EVIDENCE_KEY};
+pub use sanctions::{BloomOptions, MembershipTrace, SanctionsList, SanctionsStats};
+pub use sketch::DistinctSketches;
+#[cfg(feature = "server")]
+pub use sla::{RuleSla, RuleSlaMonitor, ShadowedRule};
+pub use streaming::{
+    AdaptiveThreshold, ChainHopRule, CountryTxCountRule, DailyVolumeRule, DecisionRateRule,
+    DistinctDestinationsRule, KycVerificationRule, PeriodVolumeRule, RequestBurstRule,
+    StructuringRule, UnusualHoursRule,
+};
+pub use subject_fields::{SubjectFieldCheck, SUBJECT_FIELDS_RULE_ID};
+#[cfg(feature = "server")]
+pub use switches::RuleSwitches;
+pub use traits::{InlineRule, RuleDescription, StreamingRule};
+pub use window::{Clock, DayWindow, FixedClock, SystemClock, WindowStart};
 
-use crate::domain::{Policy, RuleType};
-use std::collections::HashSet;
+use crate::domain::{ActionAnnotations, ActionPlans, Decision, Policy, RuleType, TxEvent};
+use crate::geoip::IpIntelligence;
+use crate::identity::IdentityProvider;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Collection of compiled rules ready for evaluation.
 pub struct RuleSet {
     pub inline: Vec<Arc<dyn InlineRule>>,
     pub streaming: Vec<Arc<dyn StreamingRule>>,
+    /// Sanctions list shared by OFAC rules
+    pub sanctions: Arc<SanctionsList>,
     pub policy_version: String,
+    /// Decisions are recorded but not enforced
+    pub monitor_only: bool,
+    /// Action annotations keyed by rule ID, for rules that have any
+    pub annotations: HashMap<String, ActionAnnotations>,
+    /// Actions callers carry out for each final decision
+    pub action_plans: ActionPlans,
+    /// Experimental rules and the request features that run them
+    pub features: FeatureGates,
+    /// Some rule uses `subject_is_new`, so subjects must be looked up
+    pub needs_subject_lookup: bool,
+    /// Rules also re-evaluated by the scheduled sweep
+    pub sweep: HashSet<String>,
+    /// Caps applied to the aggregated decision
+    pub mitigations: Mitigations,
+    /// Rules indexed by the traffic they apply to
+    pub dispatch: RuleDispatch,
+    /// Handling of subjects with a missing or unknown tier or country
+    pub subject_fields: SubjectFieldCheck,
 }
 
 impl RuleSet {
     /// Build rules from a policy and sanctions list.
     pub fn from_policy(policy: &Policy, sanctions: HashSet<String>) -> Self {
+        let defaults = BloomOptions::default();
+        let bloom_options = BloomOptions {
+            fp_rate: policy
+                .params
+                .sanctions_bloom_fp_rate
+                .unwrap_or(defaults.fp_rate),
+            min_capacity: policy
+                .params
+                .sanctions_bloom_capacity
+                .unwrap_or(defaults.min_capacity),
+        };
+
+        Self::from_policy_with_list(policy, SanctionsList::new(sanctions, bloom_options))
+    }
+
+    /// Build rules from a policy and an already built sanctions list, such
+    /// as a compiled one.
+    pub fn from_policy_with_list(policy: &Policy, mut sanctions: SanctionsList) -> Self {
         let mut inline: Vec<Arc<dyn InlineRule>> = Vec::new();
         let mut streaming: Vec<Arc<dyn StreamingRule>> = Vec::new();
 
+        if let Some(ref name) = policy.params.sanctions_list_name {
+            sanctions = sanctions.with_name(name.clone());
+        }
+        let sanctions = Arc::new(sanctions);
+        let matrix = Arc::new(LimitMatrix::from_params(&policy.params));
+        let window = DayWindow::from_params(&policy.params);
+
         for rule_def in &policy.rules {
             match rule_def.rule_type {
                 RuleType::OfacAddr => {
-                    inline.push(Arc::new(OfacRule::new(
+                    inline.push(Arc::new(OfacRule::with_list(
                         rule_def.id.clone(),
                         rule_def.action,
                         sanctions.clone(),
@@ -44,20 +132,102 @@ impl RuleSet {
                         blocked,
                     )));
                 }
-                RuleType::KycTierTxCap => {
-                    inline.push(Arc::new(KycCapRule::new(
+                RuleType::MinKycTier => {
+                    if !rule_def.min_kyc_tiers.is_empty() {
+                        inline.push(Arc::new(
+                            MinKycTierRule::new(
+                                rule_def.id.clone(),
+                                rule_def.action,
+                                rule_def.min_kyc_tiers.clone(),
+                            )
+                            .with_ranking(policy.params.kyc_tiers.clone()),
+                        ));
+                    }
+                }
+                RuleType::CompositeRisk => {
+                    if let Some(ref signals) = rule_def.signals {
+                        let countries: HashSet<String> = signals
+                            .country_groups
+                            .iter()
+                            .filter_map(|g| policy.params.country_groups.get(g))
+                            .flatten()
+                            .cloned()
+                            .collect();
+                        inline.push(Arc::new(CompositeRiskRule::new(
+                            rule_def.id.clone(),
+                            rule_def.action,
+                            signals,
+                            countries,
+                            policy.params.kyc_tiers.clone(),
+                        )));
+                    }
+                }
+                RuleType::AmountSanity => {
+                    inline.push(Arc::new(
+                        AmountSanityRule::new(rule_def.id.clone(), rule_def.action)
+                            .with_max_usd(policy.params.amount_sanity_max_usd)
+                            .with_price_bands(policy.params.asset_price_bands_usd.clone()),
+                    ));
+                }
+                RuleType::PendingFinality => {
+                    inline.push(Arc::new(FinalityRule::new(
                         rule_def.id.clone(),
                         rule_def.action,
-                        policy.params.kyc_tier_caps_usd.clone(),
+                        policy.params.finality_confirmations.clone(),
                     )));
                 }
-                RuleType::DailyUsdVolume => {
-                    if let Some(limit) = policy.params.daily_volume_limit_usd {
-                        streaming.push(Arc::new(DailyVolumeRule::new(
+                RuleType::KycTierTxCap => {
+                    inline.push(Arc::new(
+                        KycCapRule::new(
                             rule_def.id.clone(),
                             rule_def.action,
-                            limit,
-                        )));
+                            policy.params.kyc_tier_caps_usd.clone(),
+                        )
+                        .with_limit_matrix(matrix.clone()),
+                    ));
+                }
+                RuleType::DailyUsdVolume => {
+                    let native_limits = policy.params.daily_volume_limits_native.clone();
+                    match policy.params.daily_volume_limit_usd {
+                        Some(limit) => {
+                            streaming.push(Arc::new(
+                                DailyVolumeRule::new(rule_def.id.clone(), rule_def.action, limit)
+                                    .with_native_limits(native_limits)
+                                    .with_limit_matrix(matrix.clone())
+                                    .with_window(window.clone())
+                                    .with_window_key(rule_def.window_key),
+                            ));
+                        }
+                        None if !native_limits.is_empty() => {
+                            streaming.push(Arc::new(
+                                DailyVolumeRule::native(
+                                    rule_def.id.clone(),
+                                    rule_def.action,
+                                    native_limits,
+                                )
+                                .with_limit_matrix(matrix.clone())
+                                .with_window(window.clone()),
+                            ));
+                        }
+                        None => {}
+                    }
+                }
+                RuleType::WeeklyUsdVolume => {
+                    if let Some(limit) = policy.params.weekly_volume_limit_usd {
+                        streaming.push(Arc::new(
+                            PeriodVolumeRule::weekly(rule_def.id.clone(), rule_def.action, limit)
+                                .with_limit_matrix(matrix.clone())
+                                .with_window_key(rule_def.window_key),
+                        ));
+                    }
+                }
+                RuleType::MonthlyUsdVolume => {
+                    if let Some(limit) = policy.params.monthly_volume_limit_usd {
+                        streaming.push(Arc::new(
+                            PeriodVolumeRule::monthly(rule_def.id.clone(), rule_def.action, limit)
+                                .with_limit_matrix(matrix.clone())
+                                .with_window_key(rule_def.window_key),
+                        ));
                     }
                 }
                 RuleType::StructuringSmallTx => {
@@ -65,22 +235,210 @@ impl RuleSet {
                         policy.params.structuring_small_usd,
                         policy.params.structuring_small_count,
                     ) {
-                        streaming.push(Arc::new(StructuringRule::new(
+                        let mut rule = StructuringRule::new(
                             rule_def.id.clone(),
                             rule_def.action,
                             threshold,
                             count,
+                        )
+                        .with_asset_thresholds(policy.params.structuring_small_usd_by_asset.clone())
+                        .with_window(window.clone());
+                        if let Some(multiplier) = policy.params.structuring_adaptive_multiplier {
+                            rule = rule.with_adaptive(AdaptiveThreshold {
+                                multiplier,
+                                min_samples: policy
+                                    .params
+                                    .structuring_adaptive_min_samples
+                                    .unwrap_or(20),
+                            });
+                        }
+                        streaming.push(Arc::new(rule));
+                    }
+                }
+                RuleType::DecisionRateAnomaly => {
+                    if let Some(max_count) = policy.params.decision_rate_max_count {
+                        let min_decision = policy
+                            .params
+                            .decision_rate_min_decision
+                            .unwrap_or(Decision::HoldAuto);
+                        let window_hours = policy.params.decision_rate_window_hours.unwrap_or(24);
+                        streaming.push(Arc::new(DecisionRateRule::new(
+                            rule_def.id.clone(),
+                            rule_def.action,
+                            min_decision,
+                            max_count,
+                            chrono::Duration::hours(window_hours as i64),
+                        )));
+                    }
+                }
+                RuleType::RequestBurst => {
+                    if let Some(max) = policy.params.request_burst_max_per_minute {
+                        streaming.push(Arc::new(RequestBurstRule::new(
+                            rule_def.id.clone(),
+                            rule_def.action,
+                            max,
+                        )));
+                    }
+                }
+                RuleType::CountryTxCount => {
+                    if !policy.params.country_tx_caps.is_empty() {
+                        let window_hours = policy.params.country_tx_window_hours.unwrap_or(24);
+                        streaming.push(Arc::new(CountryTxCountRule::new(
+                            rule_def.id.clone(),
+                            rule_def.action,
+                            policy.params.country_tx_caps.clone(),
+                            chrono::Duration::hours(window_hours as i64),
                         )));
                     }
                 }
+                RuleType::ChainHop => {
+                    if let Some(min_usd) = policy.params.chain_hop_min_inbound_usd {
+                        let params = &policy.params;
+                        let window_minutes = params.chain_hop_window_minutes.unwrap_or(60);
+                        streaming.push(Arc::new(ChainHopRule::new(
+                            rule_def.id.clone(),
+                            rule_def.action,
+                            min_usd,
+                            params
+                                .chain_hop_ratio
+                                .unwrap_or(rust_decimal::Decimal::new(8, 1)),
+                            chrono::Duration::minutes(window_minutes as i64),
+                        )));
+                    }
+                }
+                RuleType::UnusualHours => {
+                    if let Some(min_usd) = policy.params.unusual_hours_min_usd {
+                        let params = &policy.params;
+                        let lookback_days = params.unusual_hours_lookback_days.unwrap_or(30);
+                        streaming.push(Arc::new(UnusualHoursRule::new(
+                            rule_def.id.clone(),
+                            rule_def.action,
+                            min_usd,
+                            params.unusual_hours_min_history.unwrap_or(20),
+                            params.unusual_hours_min_share.unwrap_or(0.05),
+                            chrono::Duration::days(lookback_days as i64),
+                        )));
+                    }
+                }
+                // Needs an identity provider; see `with_identity_provider`
+                RuleType::KycVerification => {}
+                // Needs shared sketches; see `with_distinct_sketches`
+                RuleType::DistinctDestinations => {}
+                // Needs IP databases; see `with_ip_intelligence`
+                RuleType::IpJurisdiction => {}
             }
         }
 
+        let dispatch = RuleDispatch::from_policy(policy).indexed(&inline, &streaming);
         RuleSet {
             inline,
             streaming,
+            sanctions,
             policy_version: policy.version.clone(),
+            monitor_only: policy.monitor_only,
+            annotations: policy
+                .rules
+                .iter()
+                .filter(|r| !r.annotations.is_empty())
+                .map(|r| (r.id.clone(), r.annotations.clone()))
+                .collect(),
+            action_plans: policy.decision_actions.clone(),
+            features: FeatureGates::from_policy(policy),
+            needs_subject_lookup: policy.rules.iter().any(|r| {
+                r.rule_type == RuleType::CompositeRisk
+                    && r.signals.as_ref().is_some_and(|s| s.new_subject)
+            }),
+            sweep: policy
+                .rules
+                .iter()
+                .filter(|r| r.sweep)
+                .map(|r| r.id.clone())
+                .collect(),
+            mitigations: Mitigations::from_policy(policy),
+            dispatch,
+            subject_fields: SubjectFieldCheck::from_policy(policy),
+        }
+    }
+
+    /// Inline rules that apply to `event`, in policy order.
+    pub fn inline_for(&self, event: &TxEvent) -> Cow<'_, [Arc<dyn InlineRule>]> {
+        self.dispatch.inline.select(&self.inline, event)
+    }
+
+    /// Streaming rules that apply to `event`, in policy order.
+    pub fn streaming_for(&self, event: &TxEvent) -> Cow<'_, [Arc<dyn StreamingRule>]> {
+        self.dispatch.streaming.select(&self.streaming, event)
+    }
+
+    /// Add the policy's KYC verification rules, checking tiers with
+    /// `provider`.
+    pub fn with_identity_provider(
+        mut self,
+        policy: &Policy,
+        provider: Arc<dyn IdentityProvider>,
+    ) -> Self {
+        if let Some(min_usd) = policy.params.kyc_verification_min_usd {
+            for rule_def in &policy.rules {
+                if rule_def.rule_type == RuleType::KycVerification {
+                    self.streaming.push(Arc::new(KycVerificationRule::new(
+                        rule_def.id.clone(),
+                        rule_def.action,
+                        min_usd,
+                        policy.params.kyc_tiers.clone(),
+                        provider.clone(),
+                    )));
+                }
+            }
         }
+        self.dispatch = self.dispatch.indexed(&self.inline, &self.streaming);
+        self
+    }
+
+    /// Add the policy's distinct destination rules, counting in
+    /// `sketches` so that counts carry over policy reloads.
+    pub fn with_distinct_sketches(
+        mut self,
+        policy: &Policy,
+        sketches: Arc<DistinctSketches>,
+    ) -> Self {
+        if let Some(max) = policy.params.distinct_destinations_max {
+            let window_hours = policy
+                .params
+                .distinct_destinations_window_hours
+                .unwrap_or(24);
+            for rule_def in &policy.rules {
+                if rule_def.rule_type == RuleType::DistinctDestinations {
+                    self.streaming.push(Arc::new(DistinctDestinationsRule::new(
+                        rule_def.id.clone(),
+                        rule_def.action,
+                        max,
+                        chrono::Duration::hours(window_hours as i64),
+                        sketches.clone(),
+                    )));
+                }
+            }
+        }
+        self.dispatch = self.dispatch.indexed(&self.inline, &self.streaming);
+        self
+    }
+
+    /// Add the policy's client IP jurisdiction rules, looking addresses up
+    /// with `intel`.
+    pub fn with_ip_intelligence(mut self, policy: &Policy, intel: Arc<dyn IpIntelligence>) -> Self {
+        let sanctioned_asns: HashSet<u32> = policy.params.sanctioned_asns.iter().copied().collect();
+        for rule_def in &policy.rules {
+            if rule_def.rule_type == RuleType::IpJurisdiction {
+                self.inline.push(Arc::new(IpJurisdictionRule::new(
+                    rule_def.id.clone(),
+                    rule_def.action,
+                    rule_def.blocked_countries.iter().cloned().collect(),
+                    sanctioned_asns.clone(),
+                    intel.clone(),
+                )));
+            }
+        }
+        self.dispatch = self.dispatch.indexed(&self.inline, &self.streaming);
+        self
     }
 
     /// Create an empty rule set.
@@ -88,7 +446,17 @@ impl RuleSet {
         RuleSet {
             inline: Vec::new(),
             streaming: Vec::new(),
+            sanctions: Arc::new(SanctionsList::empty()),
             policy_version: "0.0.0".to_string(),
+            monitor_only: false,
+            annotations: HashMap::new(),
+            action_plans: ActionPlans::new(),
+            features: FeatureGates::default(),
+            needs_subject_lookup: false,
+            sweep: HashSet::new(),
+            mitigations: Mitigations::default(),
+            dispatch: RuleDispatch::default(),
+            subject_fields: SubjectFieldCheck::default(),
         }
     }
 }
@@ -96,22 +464,24 @@ impl RuleSet {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{Decision, Policy, RuleDef, RuleParams, RuleType};
+    use crate::domain::{Decision, KycTier, Policy, RuleDef, RuleParams, RuleType};
     use rust_decimal::Decimal;
     use std::collections::HashMap;
 
     #[test]
     fn test_ruleset_from_policy() {
         let mut kyc_caps = HashMap::new();
-        kyc_caps.insert("L0".to_string(), Decimal::new(1000, 0));
+        kyc_caps.insert(KycTier::L0, Decimal::new(1000, 0));
 
         let policy = Policy {
             version: "test-1".to_string(),
             params: RuleParams {
                 kyc_tier_caps_usd: kyc_caps,
                 daily_volume_limit_usd: Some(Decimal::new(50000, 0)),
+                monthly_volume_limit_usd: Some(Decimal::new(250000, 0)),
                 structuring_small_usd: Some(Decimal::new(10000, 0)),
                 structuring_small_count: Some(5),
+                ..Default::default()
             },
             rules: vec![
                 RuleDef {
@@ -119,22 +489,73 @@ mod tests {
                     rule_type: RuleType::OfacAddr,
                     action: Decision::RejectFatal,
                     blocked_countries: vec![],
+                    min_kyc_tiers: HashMap::new(),
+                    signals: None,
+                    experimental: None,
+                    sweep: false,
+                    window_key: Default::default(),
+                    scope: Default::default(),
+                    annotations: Default::default(),
                 },
                 RuleDef {
                     id: "R4".to_string(),
                     rule_type: RuleType::DailyUsdVolume,
                     action: Decision::HoldAuto,
                     blocked_countries: vec![],
+                    min_kyc_tiers: HashMap::new(),
+                    signals: None,
+                    experimental: None,
+                    sweep: false,
+                    window_key: Default::default(),
+                    scope: Default::default(),
+                    annotations: ActionAnnotations::from([(
+                        "require_step_up_auth".to_string(),
+                        serde_json::json!(true),
+                    )]),
+                },
+                RuleDef {
+                    id: "R4_MONTHLY".to_string(),
+                    rule_type: RuleType::MonthlyUsdVolume,
+                    action: Decision::Review,
+                    blocked_countries: vec![],
+                    min_kyc_tiers: HashMap::new(),
+                    signals: None,
+                    experimental: None,
+                    sweep: false,
+                    window_key: Default::default(),
+                    scope: Default::default(),
+                    annotations: Default::default(),
+                },
+                // No weekly limit set, so this rule is skipped
+                RuleDef {
+                    id: "R4_WEEKLY".to_string(),
+                    rule_type: RuleType::WeeklyUsdVolume,
+                    action: Decision::Review,
+                    blocked_countries: vec![],
+                    min_kyc_tiers: HashMap::new(),
+                    signals: None,
+                    experimental: None,
+                    sweep: false,
+                    window_key: Default::default(),
+                    scope: Default::default(),
+                    annotations: Default::default(),
                 },
             ],
+            mitigations: vec![],
+            decision_actions: Default::default(),
             signature: String::new(),
+            monitor_only: false,
         };
 
         let sanctions = HashSet::from(["0xdead".to_string()]);
         let ruleset = RuleSet::from_policy(&policy, sanctions);
 
         assert_eq!(ruleset.inline.len(), 1);
-        assert_eq!(ruleset.streaming.len(), 1);
+        assert_eq!(ruleset.streaming.len(), 2);
+        assert_eq!(ruleset.streaming[1].id(), "R4_MONTHLY");
+        assert_eq!(ruleset.annotations.len(), 1);
+        assert!(ruleset.annotations.contains_key("R4"));
         assert_eq!(ruleset.policy_version, "test-1");
+        assert_eq!(ruleset.sanctions.len(), 1);
     }
 }
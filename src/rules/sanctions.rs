@@ -0,0 +1,424 @@
+use bloomfilter::Bloom;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::compiled_sanctions::CompiledSanctions;
+
+/// List name used when the policy does not name the sanctions list.
+pub const DEFAULT_LIST_NAME: &str = "OFAC";
+
+/// Normalize a list entry: lowercase, with a tagged entry's address and tag
+/// separated by a single space.
+pub fn normalize_entry(entry: &str) -> String {
+    entry
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// List entry for an account at a shared address, such as an
+/// exchange-hosted account, identified by its destination tag or memo.
+pub fn tagged_entry(addr: &str, tag: &str) -> String {
+    normalize_entry(&format!("{} {}", addr, tag))
+}
+
+/// Bloom filter sizing for a sanctions list.
+#[derive(Debug, Clone, Copy)]
+pub struct BloomOptions {
+    /// Target false positive rate
+    pub fp_rate: f64,
+    /// Minimum number of items the filter is sized for
+    pub min_capacity: usize,
+}
+
+impl Default for BloomOptions {
+    fn default() -> Self {
+        BloomOptions {
+            fp_rate: 0.01,
+            min_capacity: 100,
+        }
+    }
+}
+
+/// Point-in-time statistics for a sanctions list.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct SanctionsStats {
+    /// Number of sanctioned addresses
+    pub entries: usize,
+    /// Bloom filter size in bytes
+    pub bloom_bytes: u64,
+    /// Configured false positive rate
+    pub target_fp_rate: f64,
+    /// Total membership checks
+    pub checks: u64,
+    /// Checks the bloom filter passed to the hash set
+    pub bloom_hits: u64,
+    /// Bloom hits that were not in the hash set
+    pub false_positives: u64,
+}
+
+impl SanctionsStats {
+    /// Observed false positive rate over checks of non-sanctioned addresses.
+    pub fn observed_fp_rate(&self) -> f64 {
+        let true_hits = self.bloom_hits.saturating_sub(self.false_positives);
+        let negatives = self.checks.saturating_sub(true_hits);
+        if negatives == 0 {
+            return 0.0;
+        }
+        self.false_positives as f64 / negatives as f64
+    }
+}
+
+/// How a membership check resolved, step by step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MembershipTrace {
+    /// Address as looked up
+    pub normalized: String,
+    /// Bloom filter result; None for compiled lists, which have no filter
+    pub bloom: Option<bool>,
+    /// Whether the address is in the list
+    pub sanctioned: bool,
+}
+
+/// Sanctioned address set with a bloom filter in front.
+///
+/// The bloom filter answers "definitely not sanctioned" for the common
+/// case; the hash set gives the definitive answer on a bloom hit. Very
+/// large lists can instead be served from a compiled, memory-mapped file.
+#[derive(Debug)]
+pub struct SanctionsList {
+    store: Store,
+    /// Name of the list (e.g. "OFAC")
+    name: String,
+    /// Fingerprint of the list contents
+    version: String,
+    fp_rate: f64,
+    checks: AtomicU64,
+    bloom_hits: AtomicU64,
+    false_positives: AtomicU64,
+}
+
+/// Backing store for a sanctions list.
+#[derive(Debug)]
+enum Store {
+    Memory(Box<MemoryStore>),
+    Compiled(CompiledSanctions),
+}
+
+/// Sanctions list held in memory.
+#[derive(Debug)]
+struct MemoryStore {
+    /// Bloom filter for fast negative check
+    bloom: Bloom<String>,
+    /// Definitive set for positive verification
+    addresses: HashSet<String>,
+}
+
+impl SanctionsList {
+    /// Build a sanctions list, normalizing entries with `normalize_entry`.
+    pub fn new(sanctions: HashSet<String>, options: BloomOptions) -> Self {
+        let item_count = sanctions.len().max(options.min_capacity).max(1);
+        let mut bloom = Bloom::new_for_fp_rate(item_count, options.fp_rate);
+
+        let addresses: HashSet<String> = sanctions
+            .into_iter()
+            .map(|addr| normalize_entry(&addr))
+            .collect();
+
+        for addr in &addresses {
+            bloom.set(addr);
+        }
+
+        let version = format!("{:016x}", fingerprint(&addresses));
+
+        SanctionsList {
+            store: Store::Memory(Box::new(MemoryStore { bloom, addresses })),
+            name: DEFAULT_LIST_NAME.to_string(),
+            version,
+            fp_rate: options.fp_rate,
+            checks: AtomicU64::new(0),
+            bloom_hits: AtomicU64::new(0),
+            false_positives: AtomicU64::new(0),
+        }
+    }
+
+    /// Open a list compiled with `compile`, without loading it into memory.
+    pub fn open_compiled(path: impl AsRef<Path>) -> io::Result<Self> {
+        let compiled = CompiledSanctions::open(path)?;
+
+        Ok(SanctionsList {
+            version: format!("{:016x}", compiled.fingerprint()),
+            store: Store::Compiled(compiled),
+            name: DEFAULT_LIST_NAME.to_string(),
+            fp_rate: 0.0,
+            checks: AtomicU64::new(0),
+            bloom_hits: AtomicU64::new(0),
+            false_positives: AtomicU64::new(0),
+        })
+    }
+
+    /// Compile addresses into a memory-mappable file at `path`.
+    ///
+    /// The compiled list reports the same version as the in-memory list
+    /// built from the same addresses.
+    pub fn compile(sanctions: &HashSet<String>, path: impl AsRef<Path>) -> io::Result<()> {
+        let addresses: HashSet<String> = sanctions.iter().map(|a| normalize_entry(a)).collect();
+        CompiledSanctions::compile(&addresses, fingerprint(&addresses), path)
+    }
+
+    /// Set the list name.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Create an empty sanctions list.
+    pub fn empty() -> Self {
+        SanctionsList::new(HashSet::new(), BloomOptions::default())
+    }
+
+    /// Check if an address is sanctioned.
+    #[inline]
+    pub fn contains(&self, addr: &str) -> bool {
+        let normalized = addr.to_lowercase();
+        self.checks.fetch_add(1, Ordering::Relaxed);
+
+        let (bloom, addresses) = match &self.store {
+            Store::Memory(memory) => (&memory.bloom, &memory.addresses),
+            Store::Compiled(compiled) => {
+                // No bloom filter: every hit is a true hit
+                let found = compiled.contains(&normalized);
+                if found {
+                    self.bloom_hits.fetch_add(1, Ordering::Relaxed);
+                }
+                return found;
+            }
+        };
+
+        // Fast path: bloom filter says definitely not present
+        if !bloom.check(&normalized) {
+            return false;
+        }
+        self.bloom_hits.fetch_add(1, Ordering::Relaxed);
+
+        // Slow path: verify in hash set (bloom filter may have false positive)
+        let found = addresses.contains(&normalized);
+        if !found {
+            self.false_positives.fetch_add(1, Ordering::Relaxed);
+        }
+        found
+    }
+
+    /// Check if the account at `addr` with destination tag `tag` is
+    /// sanctioned by a tagged entry.
+    pub fn contains_tagged(&self, addr: &str, tag: &str) -> bool {
+        self.contains(&tagged_entry(addr, tag))
+    }
+
+    /// Trace a membership check without counting it in the statistics.
+    ///
+    /// The set is consulted even when the bloom filter rules an address
+    /// out, so a filter that disagrees with the set shows up.
+    pub fn trace(&self, addr: &str) -> MembershipTrace {
+        let normalized = addr.to_lowercase();
+        let (bloom, sanctioned) = match &self.store {
+            Store::Memory(memory) => (
+                Some(memory.bloom.check(&normalized)),
+                memory.addresses.contains(&normalized),
+            ),
+            Store::Compiled(compiled) => (None, compiled.contains(&normalized)),
+        };
+
+        MembershipTrace {
+            normalized,
+            bloom,
+            sanctioned,
+        }
+    }
+
+    /// Name of the list.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Version of the list, derived from its contents.
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Number of sanctioned addresses.
+    pub fn len(&self) -> usize {
+        match &self.store {
+            Store::Memory(memory) => memory.addresses.len(),
+            Store::Compiled(compiled) => compiled.len(),
+        }
+    }
+
+    /// Returns true if the list has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the list is served from a compiled file.
+    pub fn is_compiled(&self) -> bool {
+        matches!(self.store, Store::Compiled(_))
+    }
+
+    /// Current statistics.
+    pub fn stats(&self) -> SanctionsStats {
+        let bloom_bytes = match &self.store {
+            Store::Memory(memory) => memory.bloom.number_of_bits().div_ceil(8),
+            Store::Compiled(_) => 0,
+        };
+
+        SanctionsStats {
+            entries: self.len(),
+            bloom_bytes,
+            target_fp_rate: self.fp_rate,
+            checks: self.checks.load(Ordering::Relaxed),
+            bloom_hits: self.bloom_hits.load(Ordering::Relaxed),
+            false_positives: self.false_positives.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Compute an order-independent fingerprint of the list contents.
+fn fingerprint(addresses: &HashSet<String>) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut sorted: Vec<&String> = addresses.iter().collect();
+    sorted.sort();
+
+    let mut hasher = DefaultHasher::new();
+    sorted.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_normalizes() {
+        let list = SanctionsList::new(
+            HashSet::from(["0xDEAD".to_string()]),
+            BloomOptions::default(),
+        );
+
+        assert!(list.contains("0xdead"));
+        assert!(list.contains("0xDeAd"));
+        assert!(!list.contains("0xbeef"));
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn test_tagged_entries() {
+        let list = SanctionsList::new(
+            HashSet::from(["rHost  12345".to_string()]),
+            BloomOptions::default(),
+        );
+
+        assert!(list.contains_tagged("RHOST", " 12345"));
+        assert!(!list.contains_tagged("rhost", "999"));
+        // Only the tagged account is sanctioned, not the shared address
+        assert!(!list.contains("rhost"));
+    }
+
+    #[test]
+    fn test_trace_is_not_counted() {
+        let list = SanctionsList::new(
+            HashSet::from(["0xDEAD".to_string()]),
+            BloomOptions::default(),
+        );
+
+        let trace = list.trace("0xDeAd");
+        assert_eq!(trace.normalized, "0xdead");
+        assert_eq!(trace.bloom, Some(true));
+        assert!(trace.sanctioned);
+        assert!(!list.trace("0xbeef").sanctioned);
+        assert_eq!(list.stats().checks, 0);
+    }
+
+    #[test]
+    fn test_name_and_version() {
+        let a = SanctionsList::new(
+            HashSet::from(["0xdead".to_string(), "0xbeef".to_string()]),
+            BloomOptions::default(),
+        );
+        let b = SanctionsList::new(
+            HashSet::from(["0xBEEF".to_string(), "0xDEAD".to_string()]),
+            BloomOptions::default(),
+        )
+        .with_name("EU");
+
+        assert_eq!(a.name(), DEFAULT_LIST_NAME);
+        assert_eq!(b.name(), "EU");
+        assert_eq!(a.version(), b.version());
+        assert_ne!(a.version(), SanctionsList::empty().version());
+    }
+
+    #[test]
+    fn test_stats_counters() {
+        let list = SanctionsList::new(
+            HashSet::from(["0xdead".to_string()]),
+            BloomOptions::default(),
+        );
+
+        list.contains("0xdead");
+        for i in 0..100 {
+            list.contains(&format!("0x{:040x}", i));
+        }
+
+        let stats = list.stats();
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.checks, 101);
+        assert!(stats.bloom_hits >= 1);
+        assert_eq!(stats.bloom_hits - stats.false_positives, 1);
+        assert!(stats.bloom_bytes > 0);
+        assert!(stats.observed_fp_rate() <= 1.0);
+    }
+
+    #[test]
+    fn test_larger_capacity_means_larger_filter() {
+        let small = SanctionsList::new(HashSet::new(), BloomOptions::default());
+        let large = SanctionsList::new(
+            HashSet::new(),
+            BloomOptions {
+                fp_rate: 0.001,
+                min_capacity: 400_000,
+            },
+        );
+
+        assert!(large.stats().bloom_bytes > small.stats().bloom_bytes);
+    }
+
+    #[test]
+    fn test_compiled_list_matches_memory_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sanctions.bin");
+        let sanctions = HashSet::from(["0xDEAD".to_string(), "0xbeef".to_string()]);
+
+        SanctionsList::compile(&sanctions, &path).unwrap();
+        let compiled = SanctionsList::open_compiled(&path).unwrap().with_name("EU");
+        let memory = SanctionsList::new(sanctions, BloomOptions::default());
+
+        assert!(compiled.is_compiled());
+        assert_eq!(compiled.version(), memory.version());
+        assert_eq!(compiled.name(), "EU");
+        assert_eq!(compiled.len(), 2);
+        assert!(compiled.contains("0xDeAd"));
+        assert!(!compiled.contains("0xfeed"));
+        assert_eq!(compiled.trace("0xDEAD").bloom, None);
+        assert!(compiled.trace("0xDEAD").sanctioned);
+
+        let stats = compiled.stats();
+        assert_eq!(stats.checks, 2);
+        assert_eq!(stats.bloom_bytes, 0);
+        assert_eq!(stats.observed_fp_rate(), 0.0);
+    }
+}
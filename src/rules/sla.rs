@@ -0,0 +1,270 @@
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Width of the window each rule's latency and errors are judged over.
+const WINDOW_SECS: u64 = 60;
+
+/// Evaluations a window needs before it counts towards a mode change.
+const MIN_SAMPLES: usize = 20;
+
+/// Latency and error budget streaming rules are held to.
+#[derive(Debug, Clone, Copy)]
+pub struct RuleSla {
+    /// Highest acceptable p99 evaluation latency
+    pub p99: Duration,
+    /// Highest acceptable fraction of evaluations that fail
+    pub max_error_rate: f64,
+    /// Seconds a rule must breach the SLA before it is demoted, and meet
+    /// it again before it is restored
+    pub sustain_secs: u64,
+}
+
+/// A rule demoted to shadow mode for breaching its SLA.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ShadowedRule {
+    pub rule_id: String,
+    pub since: DateTime<Utc>,
+    pub reason: String,
+}
+
+#[derive(Debug)]
+struct RuleWindow {
+    index: u64,
+    latencies_us: Vec<u64>,
+    errors: u64,
+    /// Window in which the current run of windows wanting a mode change
+    /// started
+    streak_start: Option<u64>,
+    shadowed: Option<ShadowedRule>,
+}
+
+/// Per-rule SLA tracking with automatic demotion to shadow mode.
+///
+/// A shadowed rule is still evaluated, so its latency keeps being measured,
+/// but its result does not affect the decision. One misbehaving rule backed
+/// by a slow or failing intel source then degrades only itself instead of
+/// every decision. Rules are restored once they meet the SLA again for the
+/// sustain period.
+#[derive(Debug)]
+pub struct RuleSlaMonitor {
+    sla: RuleSla,
+    start: Instant,
+    rules: Mutex<HashMap<String, RuleWindow>>,
+}
+
+impl RuleSlaMonitor {
+    pub fn new(sla: RuleSla) -> Self {
+        RuleSlaMonitor {
+            sla,
+            start: Instant::now(),
+            rules: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns true if the rule's result must not affect decisions.
+    pub fn is_shadowed(&self, rule_id: &str) -> bool {
+        self.rules
+            .lock()
+            .get(rule_id)
+            .is_some_and(|w| w.shadowed.is_some())
+    }
+
+    /// Rules in shadow mode, ordered by rule ID.
+    pub fn shadowed(&self) -> Vec<ShadowedRule> {
+        let mut shadowed: Vec<_> = self
+            .rules
+            .lock()
+            .values()
+            .filter_map(|w| w.shadowed.clone())
+            .collect();
+        shadowed.sort_by(|a, b| a.rule_id.cmp(&b.rule_id));
+        shadowed
+    }
+
+    /// Forget the windows, and any shadowing, of rules for which `keep`
+    /// returns false, such as rules no longer in the policy.
+    pub fn retain_rules(&self, keep: impl Fn(&str) -> bool) {
+        self.rules.lock().retain(|rule_id, window| {
+            let kept = keep(rule_id);
+            if !kept && window.shadowed.is_some() {
+                info!(rule_id = %rule_id, "Shadowed rule removed from the policy");
+            }
+            kept
+        });
+    }
+
+    /// Record one evaluation of a rule.
+    pub fn record(&self, rule_id: &str, elapsed: Duration, failed: bool) {
+        self.record_at(self.start.elapsed().as_secs(), rule_id, elapsed, failed);
+    }
+
+    fn record_at(&self, now: u64, rule_id: &str, elapsed: Duration, failed: bool) {
+        let index = now / WINDOW_SECS;
+        let mut rules = self.rules.lock();
+        let window = rules
+            .entry(rule_id.to_string())
+            .or_insert_with(|| RuleWindow {
+                index,
+                latencies_us: Vec::new(),
+                errors: 0,
+                streak_start: None,
+                shadowed: None,
+            });
+
+        if window.index != index {
+            self.close(rule_id, window);
+            window.index = index;
+        }
+        window.latencies_us.push(elapsed.as_micros() as u64);
+        window.errors += failed as u64;
+    }
+
+    /// Judge a finished window and change the rule's mode once it has
+    /// wanted the change for the sustain period.
+    fn close(&self, rule_id: &str, window: &mut RuleWindow) {
+        let judged = window.latencies_us.len() >= MIN_SAMPLES;
+        let breach = self.breach(window);
+        window.latencies_us.clear();
+        window.errors = 0;
+
+        // Quiet windows neither extend nor break a streak
+        if !judged {
+            return;
+        }
+        if breach.is_some() == window.shadowed.is_some() {
+            window.streak_start = None;
+            return;
+        }
+
+        let start = *window.streak_start.get_or_insert(window.index);
+        if (window.index + 1 - start) * WINDOW_SECS < self.sla.sustain_secs {
+            return;
+        }
+        window.streak_start = None;
+
+        match breach {
+            Some(reason) => {
+                warn!(
+                    rule_id = rule_id,
+                    reason = %reason,
+                    "Rule breached its SLA, demoted to shadow mode"
+                );
+                window.shadowed = Some(ShadowedRule {
+                    rule_id: rule_id.to_string(),
+                    since: Utc::now(),
+                    reason,
+                });
+            }
+            None => {
+                info!(rule_id = rule_id, "Rule back within its SLA, restored");
+                window.shadowed = None;
+            }
+        }
+    }
+
+    fn breach(&self, window: &RuleWindow) -> Option<String> {
+        let samples = window.latencies_us.len();
+        if samples == 0 {
+            return None;
+        }
+
+        let error_rate = window.errors as f64 / samples as f64;
+        if error_rate > self.sla.max_error_rate {
+            return Some(format!(
+                "error rate {:.3} over {:.3}",
+                error_rate, self.sla.max_error_rate
+            ));
+        }
+
+        let mut sorted = window.latencies_us.clone();
+        sorted.sort_unstable();
+        let p99 = sorted[(samples * 99).div_ceil(100) - 1];
+        let budget = self.sla.p99.as_micros() as u64;
+        (p99 > budget).then(|| format!("p99 {}us over {}us", p99, budget))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> RuleSlaMonitor {
+        RuleSlaMonitor::new(RuleSla {
+            p99: Duration::from_millis(50),
+            max_error_rate: 0.1,
+            sustain_secs: 120,
+        })
+    }
+
+    fn fill(monitor: &RuleSlaMonitor, window: u64, latency_ms: u64, failed: bool) {
+        for _ in 0..MIN_SAMPLES {
+            monitor.record_at(
+                window * WINDOW_SECS,
+                "R_INTEL",
+                Duration::from_millis(latency_ms),
+                failed,
+            );
+        }
+    }
+
+    #[test]
+    fn test_sustained_breach_demotes_then_restores() {
+        let monitor = monitor();
+
+        fill(&monitor, 0, 200, false);
+        fill(&monitor, 1, 200, false);
+        // One slow window is not enough
+        assert!(!monitor.is_shadowed("R_INTEL"));
+
+        fill(&monitor, 2, 1, false);
+        assert!(monitor.is_shadowed("R_INTEL"));
+        let shadowed = monitor.shadowed();
+        assert_eq!(shadowed.len(), 1);
+        assert!(shadowed[0].reason.starts_with("p99"));
+
+        fill(&monitor, 3, 1, false);
+        fill(&monitor, 4, 1, false);
+        assert!(!monitor.is_shadowed("R_INTEL"));
+    }
+
+    #[test]
+    fn test_recovery_breaks_breach_streak() {
+        let monitor = monitor();
+
+        fill(&monitor, 0, 200, false);
+        fill(&monitor, 1, 1, false);
+        fill(&monitor, 2, 200, false);
+        fill(&monitor, 3, 1, false);
+        assert!(!monitor.is_shadowed("R_INTEL"));
+    }
+
+    #[test]
+    fn test_error_rate_breach() {
+        let monitor = monitor();
+
+        fill(&monitor, 0, 1, true);
+        fill(&monitor, 1, 1, true);
+        fill(&monitor, 2, 1, true);
+        assert!(monitor.is_shadowed("R_INTEL"));
+        assert!(monitor.shadowed()[0].reason.starts_with("error rate"));
+    }
+
+    #[test]
+    fn test_retain_rules_drops_removed() {
+        let monitor = monitor();
+
+        fill(&monitor, 0, 1, true);
+        fill(&monitor, 1, 1, true);
+        fill(&monitor, 2, 1, true);
+        monitor.retain_rules(|rule_id| rule_id == "R_INTEL");
+        assert!(monitor.is_shadowed("R_INTEL"));
+
+        monitor.retain_rules(|rule_id| rule_id == "R_OTHER");
+        assert!(!monitor.is_shadowed("R_INTEL"));
+        assert!(monitor.shadowed().is_empty());
+    }
+}
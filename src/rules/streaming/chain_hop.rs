@@ -0,0 +1,250 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::domain::event::Direction;
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, Money, RuleType, TxEvent};
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::storage::StorageRead;
+
+/// Cross-asset pass-through (chain-hopping) detection.
+///
+/// Flags subjects that receive one asset and rapidly withdraw another, a
+/// common way of breaking the trail between chains. On a withdrawal of
+/// asset B, the USD received in other assets within the window is compared
+/// with what is being sent out in B beyond what was received in B. Triggers
+/// when at least `min_inbound_usd` came in through other assets and the
+/// outflow is at least `ratio` of it.
+#[derive(Debug)]
+pub struct ChainHopRule {
+    id: String,
+    action: Decision,
+    min_inbound_usd: Decimal,
+    /// Share of the other-asset inbound that must leave in the withdrawn asset
+    ratio: Decimal,
+    window: Duration,
+}
+
+impl ChainHopRule {
+    /// Create a new chain-hopping rule.
+    pub fn new(
+        id: String,
+        action: Decision,
+        min_inbound_usd: Decimal,
+        ratio: Decimal,
+        window: Duration,
+    ) -> Self {
+        ChainHopRule {
+            id,
+            action,
+            min_inbound_usd,
+            ratio,
+            window,
+        }
+    }
+}
+
+#[async_trait]
+impl StreamingRule for ChainHopRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        RuleDescription::new(
+            &self.id,
+            RuleType::ChainHop,
+            Some(self.action),
+            serde_json::json!({
+                "min_inbound_usd": Money::usd(self.min_inbound_usd),
+                "ratio": self.ratio.to_string(),
+                "window_minutes": self.window.num_minutes(),
+            }),
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        event: &TxEvent,
+        subject_id: Uuid,
+        storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        if event.direction != Direction::Outbound {
+            return Ok(RuleResult::allow());
+        }
+        let asset = event.asset.0.to_uppercase();
+
+        let flows = storage.get_asset_flows(subject_id, self.window).await?;
+        let mut inbound_other = Decimal::ZERO;
+        let mut assets_in = Vec::new();
+        let mut outflow = event.usd_value;
+        for flow in &flows {
+            if flow.asset == asset {
+                outflow += flow.outbound_usd - flow.inbound_usd;
+            } else if flow.inbound_usd > Decimal::ZERO {
+                inbound_other += flow.inbound_usd;
+                assets_in.push(flow.asset.as_str());
+            }
+        }
+
+        if inbound_other < self.min_inbound_usd || outflow <= Decimal::ZERO {
+            return Ok(RuleResult::allow());
+        }
+        let threshold = inbound_other * self.ratio;
+        if outflow < threshold {
+            return Ok(RuleResult::allow());
+        }
+
+        Ok(RuleResult::trigger(
+            self.action,
+            Evidence::with_limit(
+                &self.id,
+                "chain_hop",
+                outflow.to_string(),
+                threshold.to_string(),
+            )
+            .with_details(serde_json::json!({
+                "asset_out": asset,
+                "assets_in": assets_in,
+                "inbound_usd": Money::usd(inbound_other),
+                "ratio": self.ratio,
+                "window_minutes": self.window.num_minutes(),
+            })),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::Asset;
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::storage::{MockStorage, StorageWrite, TransactionRecord};
+    use smallvec::smallvec;
+
+    fn test_event(asset: &str, usd: i64, direction: Direction) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![Address::new("0xabc")],
+            geo_iso: CountryCode::new("US"),
+            kyc_tier: KycTier::L1,
+        };
+        TxEvent::new(subject, Asset::new(asset), Decimal::new(usd, 0), direction)
+    }
+
+    async fn record(storage: &MockStorage, subject_id: Uuid, asset: &str, usd: i64, inbound: bool) {
+        storage
+            .record_transaction(&TransactionRecord {
+                subject_id,
+                tx_type: if inbound { "Inbound" } else { "Outbound" }.to_string(),
+                asset: asset.to_string(),
+                amount: Decimal::new(usd, 0),
+                usd_value: Decimal::new(usd, 0),
+                dest_address: None,
+                counterparty_geo: None,
+                account_id: None,
+            })
+            .await
+            .unwrap();
+    }
+
+    fn rule() -> ChainHopRule {
+        ChainHopRule::new(
+            "R_CHAIN_HOP".to_string(),
+            Decision::Review,
+            Decimal::new(1000, 0),
+            Decimal::new(8, 1),
+            Duration::minutes(60),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_cross_asset_pass_through() {
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        record(&storage, subject_id, "BTC", 5000, true).await;
+
+        let result = rule()
+            .evaluate(
+                &test_event("XMR", 3000, Direction::Outbound),
+                subject_id,
+                &storage,
+            )
+            .await
+            .unwrap();
+        assert!(!result.hit);
+
+        let result = rule()
+            .evaluate(
+                &test_event("xmr", 4500, Direction::Outbound),
+                subject_id,
+                &storage,
+            )
+            .await
+            .unwrap();
+        assert!(result.hit);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.key, "chain_hop");
+        assert_eq!(evidence.value, "4500");
+        assert_eq!(evidence.details["asset_out"], "XMR");
+        assert_eq!(evidence.details["assets_in"][0], "BTC");
+        assert_eq!(evidence.details["inbound_usd"]["amount"], "5000");
+        assert_eq!(evidence.details["inbound_usd"]["currency"], "USD");
+    }
+
+    #[tokio::test]
+    async fn test_same_asset_and_small_inbound_ignored() {
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        record(&storage, subject_id, "BTC", 5000, true).await;
+
+        // Sending back what came in is not a hop
+        let result = rule()
+            .evaluate(
+                &test_event("BTC", 5000, Direction::Outbound),
+                subject_id,
+                &storage,
+            )
+            .await
+            .unwrap();
+        assert!(!result.hit);
+
+        // Nor are deposits
+        let result = rule()
+            .evaluate(
+                &test_event("ETH", 5000, Direction::Inbound),
+                subject_id,
+                &storage,
+            )
+            .await
+            .unwrap();
+        assert!(!result.hit);
+
+        // Outflow already covered by its own inbound
+        record(&storage, subject_id, "ETH", 5000, true).await;
+        let result = rule()
+            .evaluate(
+                &test_event("ETH", 3000, Direction::Outbound),
+                subject_id,
+                &storage,
+            )
+            .await
+            .unwrap();
+        assert!(!result.hit);
+
+        let other = Uuid::new_v4();
+        record(&storage, other, "BTC", 500, true).await;
+        let result = rule()
+            .evaluate(
+                &test_event("XMR", 500, Direction::Outbound),
+                other,
+                &storage,
+            )
+            .await
+            .unwrap();
+        assert!(!result.hit);
+    }
+}
@@ -0,0 +1,241 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, RuleType, TxEvent};
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::storage::{StorageRead, WindowSpec};
+
+/// Per-country transaction count cap.
+///
+/// Throttles flows involving medium-risk countries that are allowed but
+/// not blocked outright. A subject based in a capped country may make at
+/// most that country's count of transactions within the window; otherwise
+/// transactions with a counterparty in a capped country are counted per
+/// country. Triggers when the current transaction takes a count over its
+/// cap.
+#[derive(Debug)]
+pub struct CountryTxCountRule {
+    id: String,
+    action: Decision,
+    /// Transactions allowed per window, keyed by uppercase country code
+    caps: HashMap<String, u32>,
+    window: Duration,
+}
+
+impl CountryTxCountRule {
+    /// Create a new per-country count rule.
+    pub fn new(id: String, action: Decision, caps: HashMap<String, u32>, window: Duration) -> Self {
+        CountryTxCountRule {
+            id,
+            action,
+            caps: caps
+                .into_iter()
+                .map(|(country, cap)| (country.to_uppercase(), cap))
+                .collect(),
+            window,
+        }
+    }
+
+    fn trigger(&self, party: &str, country: &str, count: u32, cap: u32) -> RuleResult {
+        RuleResult::trigger(
+            self.action,
+            Evidence::with_limit(
+                &self.id,
+                "country_tx_count",
+                count.to_string(),
+                cap.to_string(),
+            )
+            .with_details(serde_json::json!({
+                "country": country,
+                "party": party,
+                "window_hours": self.window.num_hours(),
+            })),
+        )
+    }
+}
+
+#[async_trait]
+impl StreamingRule for CountryTxCountRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        let caps: BTreeMap<&String, &u32> = self.caps.iter().collect();
+        RuleDescription::new(
+            &self.id,
+            RuleType::CountryTxCount,
+            Some(self.action),
+            serde_json::json!({
+                "caps": caps,
+                "window_hours": self.window.num_hours(),
+            }),
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        event: &TxEvent,
+        subject_id: Uuid,
+        storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        let subject_country = event.subject.geo_iso.as_str();
+
+        // Every transaction of such a subject involves its country
+        if let Some(&cap) = self.caps.get(subject_country) {
+            let prior = storage
+                .get_recent_transactions(subject_id, self.window)
+                .await?
+                .len() as u32;
+            // Include the current transaction
+            let count = prior + 1;
+            if count > cap {
+                return Ok(self.trigger("subject", subject_country, count, cap));
+            }
+        }
+
+        if let Some(country) = event.counterparty_geo.as_ref().map(|c| c.as_str()) {
+            if country != subject_country {
+                if let Some(&cap) = self.caps.get(country) {
+                    let prior = storage
+                        .get_counterparty_tx_count(subject_id, country, self.window)
+                        .await?;
+                    let count = prior + 1;
+                    if count > cap {
+                        return Ok(self.trigger("counterparty", country, count, cap));
+                    }
+                }
+            }
+        }
+
+        Ok(RuleResult::allow())
+    }
+
+    fn windows(&self, event: &TxEvent) -> Vec<WindowSpec> {
+        let subject_country = event.subject.geo_iso.as_str();
+        match event.counterparty_geo.as_ref().map(|c| c.as_str()) {
+            Some(country) if country != subject_country && self.caps.contains_key(country) => {
+                vec![WindowSpec::CounterpartyCount {
+                    country: country.to_string(),
+                    window: self.window,
+                }]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::storage::{MockStorage, StorageWrite, TransactionPoint, TransactionRecord};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use smallvec::smallvec;
+
+    fn test_event(geo: &str, counterparty: Option<&str>) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![Address::new("0xabc")],
+            geo_iso: CountryCode::new(geo),
+            kyc_tier: KycTier::L1,
+        };
+        let mut event = TxEvent::new(
+            subject,
+            Asset::new("USDC"),
+            Decimal::new(100, 0),
+            Direction::Outbound,
+        );
+        event.counterparty_geo = counterparty.map(CountryCode::new);
+        event
+    }
+
+    fn rule(cap: u32) -> CountryTxCountRule {
+        CountryTxCountRule::new(
+            "R_COUNTRY".to_string(),
+            Decision::HoldAuto,
+            HashMap::from([("ng".to_string(), cap)]),
+            Duration::hours(24),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_subject_country_capped() {
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        for _ in 0..2 {
+            storage.add_transaction_point(
+                subject_id,
+                TransactionPoint {
+                    at: Utc::now(),
+                    usd_value: Decimal::new(100, 0),
+                },
+            );
+        }
+
+        let result = rule(3)
+            .evaluate(&test_event("NG", None), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(!result.hit);
+
+        let result = rule(2)
+            .evaluate(&test_event("NG", None), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.value, "3");
+        assert_eq!(evidence.details["party"], "subject");
+
+        // Subjects elsewhere are not counted
+        let result = rule(2)
+            .evaluate(&test_event("US", None), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(!result.hit);
+    }
+
+    #[tokio::test]
+    async fn test_counterparty_country_capped() {
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        for counterparty in ["NG", "GB"] {
+            storage
+                .record_transaction(&TransactionRecord {
+                    subject_id,
+                    tx_type: "withdraw".to_string(),
+                    asset: "USDC".to_string(),
+                    amount: Decimal::new(100, 0),
+                    usd_value: Decimal::new(100, 0),
+                    dest_address: None,
+                    counterparty_geo: Some(counterparty.to_string()),
+                    account_id: None,
+                })
+                .await
+                .unwrap();
+        }
+
+        let result = rule(1)
+            .evaluate(&test_event("US", Some("ng")), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.value, "2");
+        assert_eq!(evidence.details["country"], "NG");
+        assert_eq!(evidence.details["party"], "counterparty");
+
+        let result = rule(2)
+            .evaluate(&test_event("US", Some("NG")), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(!result.hit);
+    }
+}
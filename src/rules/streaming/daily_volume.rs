@@ -1,29 +1,145 @@
 use async_trait::async_trait;
-use chrono::Duration;
 use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::domain::evidence::RuleResult;
-use crate::domain::{Decision, Evidence, TxEvent};
-use crate::rules::traits::StreamingRule;
-use crate::storage::Storage;
+use crate::domain::{Decision, Evidence, Money, RuleType, TxEvent, WindowKey};
+use crate::rules::limit_matrix::LimitMatrix;
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::rules::window::{aggregate_at, day_volume, volume_spec, DayWindow, WindowStart};
+use crate::storage::{StorageRead, WindowSpec};
 
-/// Daily USD volume limit rule.
+/// Daily volume limit rule.
 ///
-/// Tracks rolling 24-hour transaction volume per user and triggers
-/// when the cumulative volume exceeds the configured threshold.
+/// Tracks daily transaction volume per user (rolling 24 hours or the
+/// current calendar day, per the policy's window mode) and triggers
+/// when the cumulative volume exceeds the configured threshold. Limits
+/// can be set in USD, in native units per asset, or both.
 #[derive(Debug)]
 pub struct DailyVolumeRule {
     id: String,
     action: Decision,
     /// Daily volume limit in USD
-    limit: Decimal,
+    limit: Option<Decimal>,
+    /// Daily volume limits in native units, keyed by uppercase asset symbol
+    native_limits: HashMap<String, Decimal>,
+    /// Tier and geography adjustments applied to every limit
+    matrix: Arc<LimitMatrix>,
+    /// Rolling or calendar-day window
+    window: DayWindow,
+    /// Whether USD volume is summed per user or per account
+    window_key: WindowKey,
 }
 
 impl DailyVolumeRule {
     /// Create a new daily volume rule.
     pub fn new(id: String, action: Decision, limit: Decimal) -> Self {
-        DailyVolumeRule { id, action, limit }
+        DailyVolumeRule {
+            id,
+            action,
+            limit: Some(limit),
+            native_limits: HashMap::new(),
+            matrix: Arc::default(),
+            window: DayWindow::default(),
+            window_key: WindowKey::User,
+        }
+    }
+
+    /// Create a rule with only native unit limits.
+    pub fn native(id: String, action: Decision, native_limits: HashMap<String, Decimal>) -> Self {
+        DailyVolumeRule {
+            id,
+            action,
+            limit: None,
+            native_limits: HashMap::new(),
+            matrix: Arc::default(),
+            window: DayWindow::default(),
+            window_key: WindowKey::User,
+        }
+        .with_native_limits(native_limits)
+    }
+
+    /// Also limit daily volume in native units for the given assets.
+    pub fn with_native_limits(mut self, native_limits: HashMap<String, Decimal>) -> Self {
+        self.native_limits = native_limits
+            .into_iter()
+            .map(|(asset, limit)| (asset.to_uppercase(), limit))
+            .collect();
+        self
+    }
+
+    /// Scale limits by the subject's KYC tier and country.
+    pub fn with_limit_matrix(mut self, matrix: Arc<LimitMatrix>) -> Self {
+        self.matrix = matrix;
+        self
+    }
+
+    /// Bound the volume by the given window instead of a rolling 24 hours.
+    pub fn with_window(mut self, window: DayWindow) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Sum USD volume per account instead of per user. Native unit limits
+    /// stay per user.
+    pub fn with_window_key(mut self, window_key: WindowKey) -> Self {
+        self.window_key = window_key;
+        self
+    }
+
+    /// Check the native unit limit for the event's asset, if any.
+    async fn evaluate_native(
+        &self,
+        event: &TxEvent,
+        subject_id: Uuid,
+        storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        let asset = event.asset.0.to_uppercase();
+        let Some(limit) = self.native_limits.get(&asset) else {
+            return Ok(RuleResult::allow());
+        };
+        let limit = self.matrix.scale(*limit, &event.subject);
+        let Ok(amount) = event.amount.parse::<Decimal>() else {
+            return Ok(RuleResult::allow());
+        };
+
+        let current = match self.window.start() {
+            WindowStart::Lookback(window) => {
+                storage
+                    .get_rolling_amount(subject_id, &asset, window)
+                    .await?
+            }
+            WindowStart::Since { start, end } => {
+                let spec = WindowSpec::Amount {
+                    asset: asset.clone(),
+                    window: end - start,
+                };
+                aggregate_at(storage, subject_id, spec, end).await?
+            }
+        };
+        let new_amount = current + amount;
+
+        if new_amount > limit {
+            return Ok(RuleResult::trigger(
+                self.action,
+                Evidence::with_limit(
+                    &self.id,
+                    "daily_native",
+                    new_amount.to_string(),
+                    limit.to_string(),
+                )
+                .with_details(serde_json::json!({
+                    "asset": asset,
+                    "window": self.window.label(),
+                    "window_amount": Money::new(current, &asset),
+                    "tx_amount": Money::new(amount, &asset),
+                })),
+            ));
+        }
+
+        Ok(RuleResult::allow())
     }
 }
 
@@ -33,34 +149,90 @@ impl StreamingRule for DailyVolumeRule {
         &self.id
     }
 
+    fn describe(&self) -> RuleDescription {
+        let native_limits: BTreeMap<&str, Money> = self
+            .native_limits
+            .iter()
+            .map(|(asset, limit)| (asset.as_str(), Money::new(*limit, asset)))
+            .collect();
+        RuleDescription::new(
+            &self.id,
+            RuleType::DailyUsdVolume,
+            Some(self.action),
+            serde_json::json!({
+                "limit_usd": self.limit.map(Money::usd),
+                "native_limits": native_limits,
+                "window": self.window.label(),
+                "window_key": self.window_key,
+            }),
+        )
+    }
+
     async fn evaluate(
         &self,
         event: &TxEvent,
         subject_id: Uuid,
-        storage: &dyn Storage,
+        storage: &dyn StorageRead,
     ) -> anyhow::Result<RuleResult> {
-        // Get current rolling 24h volume
-        let current_volume = storage
-            .get_rolling_volume(subject_id, Duration::hours(24))
-            .await?;
+        let Some(limit) = self.limit else {
+            return self.evaluate_native(event, subject_id, storage).await;
+        };
+        let limit = self.matrix.scale(limit, &event.subject);
+
+        // Get current window volume
+        let start = self.window.start();
+        let current_volume = day_volume(storage, subject_id, event, self.window_key, start).await?;
 
         // Calculate new total including this transaction
         let new_volume = current_volume + event.usd_value;
 
         // Check if new volume exceeds limit
-        if new_volume > self.limit {
+        if new_volume > limit {
+            // Attach the transactions making up the window; best effort,
+            // and only for user windows since transactions are listed per
+            // user
+            let contributing: Vec<serde_json::Value> = match self.window_key {
+                WindowKey::User => storage
+                    .get_recent_transactions(subject_id, start.length())
+                    .await
+                    .unwrap_or_default()
+                    .iter()
+                    .map(
+                        |p| serde_json::json!({ "at": p.at, "usd_value": Money::usd(p.usd_value) }),
+                    )
+                    .collect(),
+                WindowKey::Account => Vec::new(),
+            };
+            let mut details = serde_json::json!({
+                "window": self.window.label(),
+                "window_usd": Money::usd(current_volume),
+                "tx_usd": Money::usd(event.usd_value),
+                "transactions": contributing,
+            });
+            if self.window_key == WindowKey::Account {
+                details["account_id"] = event.subject.account_id.as_str().into();
+            }
+
             return Ok(RuleResult::trigger(
                 self.action,
                 Evidence::with_limit(
                     &self.id,
                     "daily_usd",
                     new_volume.to_string(),
-                    self.limit.to_string(),
-                ),
+                    limit.to_string(),
+                )
+                .with_details(details),
             ));
         }
 
-        Ok(RuleResult::allow())
+        self.evaluate_native(event, subject_id, storage).await
+    }
+
+    fn windows(&self, event: &TxEvent) -> Vec<WindowSpec> {
+        match (self.limit, self.window.fixed_lookback()) {
+            (Some(_), Some(lookback)) => vec![volume_spec(self.window_key, event, lookback)],
+            _ => Vec::new(),
+        }
     }
 }
 
@@ -69,11 +241,16 @@ mod tests {
     use super::*;
     use crate::domain::event::{Asset, Chain, Direction, EventId, SCHEMA_VERSION};
     use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
-    use crate::storage::MockStorage;
-    use chrono::Utc;
+    use crate::rules::window::FixedClock;
+    use crate::storage::{MockStorage, StorageWrite, TransactionRecord};
+    use chrono::{Duration, Utc};
     use smallvec::smallvec;
 
     fn test_event(usd_value: i64) -> TxEvent {
+        native_event("USDC", &usd_value.to_string(), usd_value)
+    }
+
+    fn native_event(asset: &str, amount: &str, usd_value: i64) -> TxEvent {
         TxEvent {
             schema_version: SCHEMA_VERSION.to_string(),
             event_id: EventId::new(),
@@ -89,11 +266,19 @@ mod tests {
             chain: Chain::inline(),
             tx_hash: String::new(),
             direction: Direction::Outbound,
-            asset: Asset::new("USDC"),
-            amount: usd_value.to_string(),
+            tx_type: String::new(),
+            asset: Asset::new(asset),
+            amount: amount.to_string(),
             usd_value: Decimal::new(usd_value, 0),
+            usd_rate: None,
             confirmations: 0,
             max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
         }
     }
 
@@ -174,4 +359,105 @@ mod tests {
 
         assert!(!result.hit); // Old tx pruned, only new $20k counted
     }
+
+    #[tokio::test]
+    async fn test_calendar_day_window() {
+        let rule_at = |now: &str| {
+            let window = DayWindow::calendar_day(chrono_tz::Tz::Europe__London)
+                .with_clock(Arc::new(FixedClock(now.parse().unwrap())));
+            DailyVolumeRule::new(
+                "R4_DAILY".to_string(),
+                Decision::HoldAuto,
+                Decimal::new(50000, 0),
+            )
+            .with_window(window)
+        };
+
+        // 23:30 on 15 June in London (BST, UTC+1)
+        let storage = MockStorage::new().with_clock("2026-06-15T22:30:00Z".parse().unwrap());
+        let subject_id = Uuid::new_v4();
+        storage
+            .record_transaction(&TransactionRecord {
+                subject_id,
+                tx_type: "Outbound".to_string(),
+                asset: "USDC".to_string(),
+                amount: Decimal::new(40000, 0),
+                usd_value: Decimal::new(40000, 0),
+                dest_address: None,
+                counterparty_geo: None,
+                account_id: None,
+            })
+            .await
+            .unwrap();
+
+        let result = rule_at("2026-06-15T22:30:00Z")
+            .evaluate(&test_event(20000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        let details = result.evidence.unwrap().details;
+        assert_eq!(details["window"], "calendar_day:Europe/London");
+        assert_eq!(details["transactions"].as_array().unwrap().len(), 1);
+
+        // Past midnight in London, though not in UTC, the limit has reset
+        storage.advance(Duration::hours(1));
+        let result = rule_at("2026-06-15T23:30:00Z")
+            .evaluate(&test_event(20000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(!result.hit);
+    }
+
+    #[tokio::test]
+    async fn test_native_limit() {
+        let rule = DailyVolumeRule::native(
+            "R4_DAILY".to_string(),
+            Decision::HoldAuto,
+            HashMap::from([("btc".to_string(), Decimal::new(2, 0))]),
+        );
+
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        storage.set_rolling_amount(subject_id, "BTC", Decimal::new(15, 1));
+
+        // 1.5 + 0.4 BTC stays under 2 BTC
+        let event = native_event("BTC", "0.4", 40000);
+        let result = rule.evaluate(&event, subject_id, &storage).await.unwrap();
+        assert!(!result.hit);
+
+        // 1.5 + 0.6 BTC exceeds it regardless of USD value
+        let event = native_event("BTC", "0.6", 10);
+        let result = rule.evaluate(&event, subject_id, &storage).await.unwrap();
+        assert!(result.hit);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.value, "2.1");
+        assert_eq!(ev.limit, Some("2".to_string()));
+        assert_eq!(ev.details["asset"], "BTC");
+
+        // Assets without a native limit are not checked
+        let event = native_event("ETH", "1000", 10);
+        let result = rule.evaluate(&event, subject_id, &storage).await.unwrap();
+        assert!(!result.hit);
+    }
+
+    #[tokio::test]
+    async fn test_usd_and_native_limits() {
+        let rule = DailyVolumeRule::new(
+            "R4_DAILY".to_string(),
+            Decision::HoldAuto,
+            Decimal::new(50000, 0),
+        )
+        .with_native_limits(HashMap::from([("BTC".to_string(), Decimal::new(2, 0))]));
+
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        storage.set_rolling_amount(subject_id, "BTC", Decimal::new(19, 1));
+
+        // Under the USD limit but over the native one
+        let event = native_event("BTC", "0.5", 30000);
+        let result = rule.evaluate(&event, subject_id, &storage).await.unwrap();
+
+        assert!(result.hit);
+        assert_eq!(result.evidence.unwrap().limit, Some("2".to_string()));
+    }
 }
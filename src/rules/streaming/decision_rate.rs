@@ -0,0 +1,213 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use uuid::Uuid;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, RuleType, TxEvent};
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::storage::StorageRead;
+
+/// Decision rate anomaly rule.
+///
+/// Looks at the subject's own decision history and escalates once the
+/// number of prior decisions at or above a severity (e.g. holds and
+/// reviews) within the window exceeds the configured count.
+#[derive(Debug)]
+pub struct DecisionRateRule {
+    id: String,
+    action: Decision,
+    /// Minimum severity of a prior decision to be counted
+    min_decision: Decision,
+    /// Number of counted decisions tolerated within the window
+    max_count: u32,
+    /// Lookback window
+    window: Duration,
+}
+
+impl DecisionRateRule {
+    /// Create a new decision rate rule.
+    pub fn new(
+        id: String,
+        action: Decision,
+        min_decision: Decision,
+        max_count: u32,
+        window: Duration,
+    ) -> Self {
+        DecisionRateRule {
+            id,
+            action,
+            min_decision,
+            max_count,
+            window,
+        }
+    }
+}
+
+#[async_trait]
+impl StreamingRule for DecisionRateRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        RuleDescription::new(
+            &self.id,
+            RuleType::DecisionRateAnomaly,
+            Some(self.action),
+            serde_json::json!({
+                "min_decision": self.min_decision,
+                "max_count": self.max_count,
+                "window_hours": self.window.num_hours(),
+            }),
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        _event: &TxEvent,
+        subject_id: Uuid,
+        storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        let count = storage
+            .count_recent_decisions(subject_id, self.min_decision, self.window)
+            .await?;
+
+        // Trigger if count exceeds threshold (not just equals)
+        if count > self.max_count {
+            return Ok(RuleResult::trigger(
+                self.action,
+                Evidence::with_limit(
+                    &self.id,
+                    "flagged_decisions",
+                    count.to_string(),
+                    self.max_count.to_string(),
+                ),
+            ));
+        }
+
+        Ok(RuleResult::allow())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Chain, Direction, EventId, SCHEMA_VERSION};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::storage::{DecisionRecord, MockStorage, StorageWrite};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use smallvec::smallvec;
+
+    fn test_event() -> TxEvent {
+        TxEvent {
+            schema_version: SCHEMA_VERSION.to_string(),
+            event_id: EventId::new(),
+            occurred_at: Utc::now(),
+            observed_at: Utc::now(),
+            subject: Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: smallvec![Address::new("0xabc")],
+                geo_iso: CountryCode::new("US"),
+                kyc_tier: KycTier::L1,
+            },
+            chain: Chain::inline(),
+            tx_hash: String::new(),
+            direction: Direction::Outbound,
+            tx_type: String::new(),
+            asset: Asset::new("USDC"),
+            amount: "100".to_string(),
+            usd_value: Decimal::new(100, 0),
+            usd_rate: None,
+            confirmations: 0,
+            max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
+        }
+    }
+
+    async fn record(storage: &MockStorage, subject_id: Uuid, decision: Decision) {
+        storage
+            .record_decision(&DecisionRecord {
+                subject_id: Some(subject_id),
+                request_id: None,
+                request: serde_json::Value::Null,
+                decision,
+                decision_code: "TEST".to_string(),
+                policy_version: "v1".to_string(),
+                evidence: vec![],
+                latency_ms: 1,
+            })
+            .await
+            .unwrap();
+    }
+
+    fn test_rule() -> DecisionRateRule {
+        DecisionRateRule::new(
+            "R6_DECISION_RATE".to_string(),
+            Decision::Review,
+            Decision::HoldAuto,
+            2,
+            Duration::hours(24),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_at_count_threshold() {
+        let rule = test_rule();
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        record(&storage, subject_id, Decision::HoldAuto).await;
+        record(&storage, subject_id, Decision::Review).await;
+
+        let result = rule
+            .evaluate(&test_event(), subject_id, &storage)
+            .await
+            .unwrap();
+
+        assert!(!result.hit); // 2 == 2, at threshold but not over
+    }
+
+    #[tokio::test]
+    async fn test_over_count_threshold() {
+        let rule = test_rule();
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        record(&storage, subject_id, Decision::HoldAuto).await;
+        record(&storage, subject_id, Decision::HoldAuto).await;
+        record(&storage, subject_id, Decision::Review).await;
+
+        let result = rule
+            .evaluate(&test_event(), subject_id, &storage)
+            .await
+            .unwrap();
+
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::Review);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.value, "3");
+        assert_eq!(ev.limit, Some("2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_lower_severity_not_counted() {
+        let rule = test_rule();
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        record(&storage, subject_id, Decision::Allow).await;
+        record(&storage, subject_id, Decision::SoftDenyRetry).await;
+        record(&storage, subject_id, Decision::HoldAuto).await;
+
+        let result = rule
+            .evaluate(&test_event(), subject_id, &storage)
+            .await
+            .unwrap();
+
+        assert!(!result.hit); // Only one HoldAuto counted
+    }
+}
@@ -0,0 +1,213 @@
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, RuleType, TxEvent};
+use crate::rules::sketch::DistinctSketches;
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::storage::StorageRead;
+
+/// Limits how many distinct destinations a subject sends to per window.
+///
+/// Destinations are counted with an in-memory sketch rather than by
+/// querying history, so memory stays fixed per subject however many
+/// addresses it uses. An address with a different destination tag counts
+/// as a different destination. Transactions without a destination are
+/// not counted.
+#[derive(Debug)]
+pub struct DistinctDestinationsRule {
+    id: String,
+    action: Decision,
+    /// Distinct destinations allowed per window
+    max: u32,
+    window: Duration,
+    sketches: Arc<DistinctSketches>,
+}
+
+impl DistinctDestinationsRule {
+    /// Create a new distinct destinations rule.
+    pub fn new(
+        id: String,
+        action: Decision,
+        max: u32,
+        window: Duration,
+        sketches: Arc<DistinctSketches>,
+    ) -> Self {
+        DistinctDestinationsRule {
+            id,
+            action,
+            max,
+            window,
+            sketches,
+        }
+    }
+}
+
+#[async_trait]
+impl StreamingRule for DistinctDestinationsRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        RuleDescription::new(
+            &self.id,
+            RuleType::DistinctDestinations,
+            Some(self.action),
+            serde_json::json!({
+                "max": self.max,
+                "window_hours": self.window.num_hours(),
+            }),
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        event: &TxEvent,
+        subject_id: Uuid,
+        _storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        let Some(ref destination) = event.destination else {
+            return Ok(RuleResult::allow());
+        };
+
+        let value = (destination.address.as_str(), destination.tag.as_deref());
+        let count = self
+            .sketches
+            .observe(&self.id, subject_id, self.window, &value, Utc::now());
+
+        if count > self.max as u64 {
+            return Ok(RuleResult::trigger(
+                self.action,
+                Evidence::with_limit(
+                    &self.id,
+                    "distinct_destinations",
+                    count.to_string(),
+                    self.max.to_string(),
+                ),
+            ));
+        }
+
+        Ok(RuleResult::allow())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Chain, Destination, Direction, EventId, SCHEMA_VERSION};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::storage::MockStorage;
+    use rust_decimal::Decimal;
+    use smallvec::smallvec;
+
+    fn test_event(destination: Option<Destination>) -> TxEvent {
+        TxEvent {
+            schema_version: SCHEMA_VERSION.to_string(),
+            event_id: EventId::new(),
+            occurred_at: Utc::now(),
+            observed_at: Utc::now(),
+            subject: Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: smallvec![Address::new("0xabc")],
+                geo_iso: CountryCode::new("US"),
+                kyc_tier: KycTier::L1,
+            },
+            chain: Chain::inline(),
+            tx_hash: String::new(),
+            direction: Direction::Outbound,
+            tx_type: String::new(),
+            asset: Asset::new("USDC"),
+            amount: "100".to_string(),
+            usd_value: Decimal::new(100, 0),
+            usd_rate: None,
+            confirmations: 0,
+            max_finality_depth: 0,
+            counterparty_geo: None,
+            destination,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
+        }
+    }
+
+    fn test_rule(sketches: Arc<DistinctSketches>) -> DistinctDestinationsRule {
+        DistinctDestinationsRule::new(
+            "R_DISTINCT_DEST".to_string(),
+            Decision::Review,
+            2,
+            Duration::hours(24),
+            sketches,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_triggers_above_max_distinct() {
+        let rule = test_rule(Arc::new(DistinctSketches::default()));
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+
+        for address in ["0xd1", "0xd2", "0xd1", "0xd2"] {
+            let event = test_event(Some(Destination::new(address, None)));
+            let result = rule.evaluate(&event, subject_id, &storage).await.unwrap();
+            assert!(!result.hit);
+        }
+
+        // Same address, different tag
+        let event = test_event(Some(Destination::new("0xd1", Some("77".to_string()))));
+        let result = rule.evaluate(&event, subject_id, &storage).await.unwrap();
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::Review);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.value, "3");
+        assert_eq!(evidence.limit.as_deref(), Some("2"));
+
+        // Other subjects are counted separately
+        let result = rule
+            .evaluate(&event, Uuid::new_v4(), &storage)
+            .await
+            .unwrap();
+        assert!(!result.hit);
+    }
+
+    #[tokio::test]
+    async fn test_state_survives_rebuilt_rule() {
+        let sketches = Arc::new(DistinctSketches::default());
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+
+        for address in ["0xd1", "0xd2"] {
+            let event = test_event(Some(Destination::new(address, None)));
+            test_rule(sketches.clone())
+                .evaluate(&event, subject_id, &storage)
+                .await
+                .unwrap();
+        }
+
+        let event = test_event(Some(Destination::new("0xd3", None)));
+        let result = test_rule(sketches)
+            .evaluate(&event, subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+    }
+
+    #[tokio::test]
+    async fn test_ignores_missing_destination() {
+        let rule = test_rule(Arc::new(DistinctSketches::default()));
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+
+        for _ in 0..5 {
+            let result = rule
+                .evaluate(&test_event(None), subject_id, &storage)
+                .await
+                .unwrap();
+            assert!(!result.hit);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, Money, RuleType, TierRanking, TxEvent};
+use crate::identity::IdentityProvider;
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::storage::StorageRead;
+
+/// Verifies the caller-supplied KYC tier with an external identity provider.
+///
+/// Transactions at or above the USD threshold trigger unless the provider
+/// confirms the subject at the claimed tier or above. Verification is
+/// mandatory there, so unknown subjects and failed or timed out lookups
+/// trigger too. Smaller transactions are not checked.
+#[derive(Debug)]
+pub struct KycVerificationRule {
+    id: String,
+    action: Decision,
+    min_usd: Decimal,
+    ranking: TierRanking,
+    provider: Arc<dyn IdentityProvider>,
+}
+
+impl KycVerificationRule {
+    /// Create a new KYC verification rule.
+    pub fn new(
+        id: String,
+        action: Decision,
+        min_usd: Decimal,
+        ranking: TierRanking,
+        provider: Arc<dyn IdentityProvider>,
+    ) -> Self {
+        KycVerificationRule {
+            id,
+            action,
+            min_usd,
+            ranking,
+            provider,
+        }
+    }
+
+    fn unverified(&self, reason: &str) -> RuleResult {
+        RuleResult::trigger(
+            self.action,
+            Evidence::new(&self.id, "verified_kyc_tier", reason),
+        )
+    }
+}
+
+#[async_trait]
+impl StreamingRule for KycVerificationRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        // The identity provider and its credentials are not disclosed
+        RuleDescription::new(
+            &self.id,
+            RuleType::KycVerification,
+            Some(self.action),
+            serde_json::json!({ "min_usd": Money::usd(self.min_usd) }),
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        event: &TxEvent,
+        _subject_id: Uuid,
+        _storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        if event.usd_value < self.min_usd {
+            return Ok(RuleResult::allow());
+        }
+
+        let claimed = &event.subject.kyc_tier;
+        let user_id = event.subject.user_id.as_str();
+        match self.provider.kyc_tier(user_id).await {
+            Ok(Some(verified)) if self.ranking.meets(&verified, claimed) => Ok(RuleResult::allow()),
+            Ok(Some(verified)) => Ok(RuleResult::trigger(
+                self.action,
+                Evidence::with_limit(
+                    &self.id,
+                    "verified_kyc_tier",
+                    verified.as_str(),
+                    claimed.as_str(),
+                ),
+            )),
+            Ok(None) => Ok(self.unverified("unknown")),
+            Err(e) => {
+                warn!(user_id = user_id, rule_id = %self.id, error = %e, "KYC verification failed");
+                Ok(self.unverified("unavailable"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Chain, Direction, EventId, SCHEMA_VERSION};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::storage::MockStorage;
+    use chrono::Utc;
+    use smallvec::smallvec;
+    use std::collections::HashMap;
+
+    /// Verified tiers by user ID; `DOWN` fails.
+    #[derive(Debug)]
+    struct FixedProvider(HashMap<&'static str, KycTier>);
+
+    #[async_trait]
+    impl IdentityProvider for FixedProvider {
+        async fn kyc_tier(&self, user_id: &str) -> anyhow::Result<Option<KycTier>> {
+            if user_id == "DOWN" {
+                anyhow::bail!("connection refused");
+            }
+            Ok(self.0.get(user_id).cloned())
+        }
+    }
+
+    fn rule() -> KycVerificationRule {
+        let provider = FixedProvider(HashMap::from([("U1", KycTier::L2), ("U2", KycTier::L1)]));
+        KycVerificationRule::new(
+            "R_KYC_VERIFY".to_string(),
+            Decision::Review,
+            Decimal::new(10000, 0),
+            TierRanking::default(),
+            Arc::new(provider),
+        )
+    }
+
+    fn event(user_id: &str, tier: KycTier, usd_value: i64) -> TxEvent {
+        TxEvent {
+            schema_version: SCHEMA_VERSION.to_string(),
+            event_id: EventId::new(),
+            occurred_at: Utc::now(),
+            observed_at: Utc::now(),
+            subject: Subject {
+                user_id: UserId::new(user_id),
+                account_id: AccountId::new("A1"),
+                addresses: smallvec![Address::new("0xabc")],
+                geo_iso: CountryCode::new("US"),
+                kyc_tier: tier,
+            },
+            chain: Chain::inline(),
+            tx_hash: String::new(),
+            direction: Direction::Outbound,
+            tx_type: String::new(),
+            asset: Asset::new("USDC"),
+            amount: usd_value.to_string(),
+            usd_value: Decimal::new(usd_value, 0),
+            usd_rate: None,
+            confirmations: 0,
+            max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
+        }
+    }
+
+    async fn evaluate(event: &TxEvent) -> RuleResult {
+        rule()
+            .evaluate(event, Uuid::new_v4(), &MockStorage::new())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_confirmed_tier_allows() {
+        assert!(!evaluate(&event("U1", KycTier::L2, 20000)).await.hit);
+        assert!(!evaluate(&event("U1", KycTier::L1, 20000)).await.hit);
+    }
+
+    #[tokio::test]
+    async fn test_overstated_tier_triggers() {
+        let result = evaluate(&event("U2", KycTier::L2, 20000)).await;
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::Review);
+        let evidence = result.evidence.unwrap();
+        assert_eq!(evidence.value, "L1");
+        assert_eq!(evidence.limit.as_deref(), Some("L2"));
+    }
+
+    #[tokio::test]
+    async fn test_unverifiable_above_threshold_triggers() {
+        let result = evaluate(&event("U3", KycTier::L1, 20000)).await;
+        assert_eq!(result.evidence.unwrap().value, "unknown");
+
+        let result = evaluate(&event("DOWN", KycTier::L1, 20000)).await;
+        assert_eq!(result.evidence.unwrap().value, "unavailable");
+    }
+
+    #[tokio::test]
+    async fn test_below_threshold_not_checked() {
+        assert!(!evaluate(&event("DOWN", KycTier::L2, 500)).await.hit);
+    }
+}
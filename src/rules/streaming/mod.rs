@@ -1,5 +1,21 @@
+mod chain_hop;
+mod country_tx_count;
 mod daily_volume;
+mod decision_rate;
+mod distinct_destinations;
+mod kyc_verification;
+mod period_volume;
+mod request_burst;
 mod structuring;
+mod unusual_hours;
 
+pub use chain_hop::ChainHopRule;
+pub use country_tx_count::CountryTxCountRule;
 pub use daily_volume::DailyVolumeRule;
-pub use structuring::StructuringRule;
+pub use decision_rate::DecisionRateRule;
+pub use distinct_destinations::DistinctDestinationsRule;
+pub use kyc_verification::KycVerificationRule;
+pub use period_volume::PeriodVolumeRule;
+pub use request_burst::RequestBurstRule;
+pub use structuring::{AdaptiveThreshold, StructuringRule};
+pub use unusual_hours::UnusualHoursRule;
@@ -0,0 +1,247 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, Money, RuleType, TxEvent, WindowKey};
+use crate::rules::limit_matrix::LimitMatrix;
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::rules::window::{volume_spec, window_volume};
+use crate::storage::{StorageRead, WindowSpec};
+
+/// Long-horizon volume cap rule.
+///
+/// Limits rolling USD volume over a 7-day or 30-day window. Unlike the
+/// daily rule, evidence carries only the window total: listing a month of
+/// contributing transactions would bloat every decision record.
+#[derive(Debug)]
+pub struct PeriodVolumeRule {
+    id: String,
+    action: Decision,
+    /// Volume limit in USD over the window
+    limit: Decimal,
+    window: Duration,
+    /// Evidence key, e.g. "weekly_usd"
+    key: &'static str,
+    /// Tier and geography adjustments applied to the limit
+    matrix: Arc<LimitMatrix>,
+    /// Whether volume is summed per user or per account
+    window_key: WindowKey,
+}
+
+impl PeriodVolumeRule {
+    /// Cap rolling 7-day volume.
+    pub fn weekly(id: String, action: Decision, limit: Decimal) -> Self {
+        PeriodVolumeRule {
+            id,
+            action,
+            limit,
+            window: Duration::days(7),
+            key: "weekly_usd",
+            matrix: Arc::default(),
+            window_key: WindowKey::User,
+        }
+    }
+
+    /// Cap rolling 30-day volume.
+    pub fn monthly(id: String, action: Decision, limit: Decimal) -> Self {
+        PeriodVolumeRule {
+            id,
+            action,
+            limit,
+            window: Duration::days(30),
+            key: "monthly_usd",
+            matrix: Arc::default(),
+            window_key: WindowKey::User,
+        }
+    }
+
+    /// Scale the limit by the subject's KYC tier and country.
+    pub fn with_limit_matrix(mut self, matrix: Arc<LimitMatrix>) -> Self {
+        self.matrix = matrix;
+        self
+    }
+
+    /// Sum volume per account instead of per user.
+    pub fn with_window_key(mut self, window_key: WindowKey) -> Self {
+        self.window_key = window_key;
+        self
+    }
+}
+
+#[async_trait]
+impl StreamingRule for PeriodVolumeRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        let rule_type = if self.key == "weekly_usd" {
+            RuleType::WeeklyUsdVolume
+        } else {
+            RuleType::MonthlyUsdVolume
+        };
+        RuleDescription::new(
+            &self.id,
+            rule_type,
+            Some(self.action),
+            serde_json::json!({
+                "limit_usd": Money::usd(self.limit),
+                "window_days": self.window.num_days(),
+                "window_key": self.window_key,
+            }),
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        event: &TxEvent,
+        subject_id: Uuid,
+        storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        let current_volume =
+            window_volume(storage, subject_id, event, self.window_key, self.window).await?;
+        let new_volume = current_volume + event.usd_value;
+        let limit = self.matrix.scale(self.limit, &event.subject);
+
+        if new_volume > limit {
+            let mut details = serde_json::json!({
+                "window_days": self.window.num_days(),
+                "window_usd": Money::usd(current_volume),
+                "tx_usd": Money::usd(event.usd_value),
+            });
+            if self.window_key == WindowKey::Account {
+                details["account_id"] = event.subject.account_id.as_str().into();
+            }
+            return Ok(RuleResult::trigger(
+                self.action,
+                Evidence::with_limit(
+                    &self.id,
+                    self.key,
+                    new_volume.to_string(),
+                    limit.to_string(),
+                )
+                .with_details(details),
+            ));
+        }
+
+        Ok(RuleResult::allow())
+    }
+
+    fn windows(&self, event: &TxEvent) -> Vec<WindowSpec> {
+        vec![volume_spec(self.window_key, event, self.window)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, CountryCode, KycTier, Subject, UserId};
+    use crate::storage::{MockStorage, StorageWrite, TransactionRecord};
+    use smallvec::smallvec;
+
+    fn test_event(usd_value: i64) -> TxEvent {
+        TxEvent::new(
+            Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: smallvec![],
+                geo_iso: CountryCode::new("US"),
+                kyc_tier: KycTier::L1,
+            },
+            Asset::new("USDC"),
+            Decimal::new(usd_value, 0),
+            Direction::Outbound,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_weekly_cap() {
+        let rule = PeriodVolumeRule::weekly(
+            "R4_WEEKLY".to_string(),
+            Decision::Review,
+            Decimal::new(100000, 0),
+        );
+
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        storage.set_rolling_volume(subject_id, Decimal::new(90000, 0));
+
+        let result = rule
+            .evaluate(&test_event(5000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(!result.hit);
+
+        let result = rule
+            .evaluate(&test_event(20000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::Review);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.key, "weekly_usd");
+        assert_eq!(ev.value, "110000");
+        assert_eq!(ev.details["window_days"], 7);
+    }
+
+    #[tokio::test]
+    async fn test_account_level_volume() {
+        let rule = PeriodVolumeRule::weekly(
+            "R4_WEEKLY_ACCOUNT".to_string(),
+            Decision::Review,
+            Decimal::new(10000, 0),
+        )
+        .with_window_key(WindowKey::Account);
+
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        for (account_id, usd_value) in [("A1", 8000), ("A2", 9000)] {
+            storage
+                .record_transaction(&TransactionRecord {
+                    subject_id,
+                    tx_type: "Outbound".to_string(),
+                    asset: "USDC".to_string(),
+                    amount: Decimal::new(usd_value, 0),
+                    usd_value: Decimal::new(usd_value, 0),
+                    dest_address: None,
+                    counterparty_geo: None,
+                    account_id: Some(account_id.to_string()),
+                })
+                .await
+                .unwrap();
+        }
+
+        // Only A1's 8000 counts toward A1's cap
+        let event = test_event(1000);
+        assert!(matches!(
+            &rule.windows(&event)[..],
+            [WindowSpec::AccountVolume { account_id, .. }] if account_id == "A1"
+        ));
+        let result = rule.evaluate(&event, subject_id, &storage).await.unwrap();
+        assert!(!result.hit);
+
+        let result = rule
+            .evaluate(&test_event(3000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.value, "11000");
+        assert_eq!(ev.details["account_id"], "A1");
+    }
+
+    #[tokio::test]
+    async fn test_monthly_window() {
+        let rule = PeriodVolumeRule::monthly(
+            "R4_MONTHLY".to_string(),
+            Decision::HoldAuto,
+            Decimal::new(250000, 0),
+        );
+        assert_eq!(rule.window, Duration::days(30));
+        assert_eq!(rule.key, "monthly_usd");
+    }
+}
@@ -0,0 +1,181 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use uuid::Uuid;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, RuleType, TxEvent};
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::storage::StorageRead;
+
+/// Per-subject request burst throttle.
+///
+/// Counts the subject's recorded decisions over the last minute,
+/// regardless of outcome or transaction size, and triggers once the
+/// current request takes the count over the limit. Requests cut short by
+/// an inline fatal hit or answered while storage is degraded are not
+/// recorded, so they don't count. Intended to be paired with
+/// `SOFT_DENY_RETRY` so scripted clients back off.
+#[derive(Debug)]
+pub struct RequestBurstRule {
+    id: String,
+    action: Decision,
+    /// Requests allowed per minute
+    max_per_minute: u32,
+}
+
+impl RequestBurstRule {
+    /// Create a new request burst rule.
+    pub fn new(id: String, action: Decision, max_per_minute: u32) -> Self {
+        RequestBurstRule {
+            id,
+            action,
+            max_per_minute,
+        }
+    }
+}
+
+#[async_trait]
+impl StreamingRule for RequestBurstRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        RuleDescription::new(
+            &self.id,
+            RuleType::RequestBurst,
+            Some(self.action),
+            serde_json::json!({ "max_per_minute": self.max_per_minute }),
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        _event: &TxEvent,
+        subject_id: Uuid,
+        storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        // Evaluated requests record a decision whatever its outcome; inline
+        // fatals and degraded-storage responses are never recorded and are
+        // missed here
+        let prior = storage
+            .count_recent_decisions(subject_id, Decision::Allow, Duration::minutes(1))
+            .await?;
+
+        // Include the current request
+        let count = prior + 1;
+
+        if count > self.max_per_minute {
+            return Ok(RuleResult::trigger(
+                self.action,
+                Evidence::with_limit(
+                    &self.id,
+                    "requests_per_min",
+                    count.to_string(),
+                    self.max_per_minute.to_string(),
+                ),
+            ));
+        }
+
+        Ok(RuleResult::allow())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Chain, Direction, EventId, SCHEMA_VERSION};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::storage::{DecisionRecord, MockStorage, StorageWrite};
+    use chrono::Utc;
+    use rust_decimal::Decimal;
+    use smallvec::smallvec;
+
+    fn test_event() -> TxEvent {
+        TxEvent {
+            schema_version: SCHEMA_VERSION.to_string(),
+            event_id: EventId::new(),
+            occurred_at: Utc::now(),
+            observed_at: Utc::now(),
+            subject: Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: smallvec![Address::new("0xabc")],
+                geo_iso: CountryCode::new("US"),
+                kyc_tier: KycTier::L1,
+            },
+            chain: Chain::inline(),
+            tx_hash: String::new(),
+            direction: Direction::Outbound,
+            tx_type: String::new(),
+            asset: Asset::new("USDC"),
+            amount: "100".to_string(),
+            usd_value: Decimal::new(100, 0),
+            usd_rate: None,
+            confirmations: 0,
+            max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
+        }
+    }
+
+    async fn record_requests(storage: &MockStorage, subject_id: Uuid, count: usize) {
+        for _ in 0..count {
+            storage
+                .record_decision(&DecisionRecord {
+                    subject_id: Some(subject_id),
+                    request_id: None,
+                    request: serde_json::Value::Null,
+                    decision: Decision::Allow,
+                    decision_code: "OK".to_string(),
+                    policy_version: "v1".to_string(),
+                    evidence: vec![],
+                    latency_ms: 1,
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    fn test_rule() -> RequestBurstRule {
+        RequestBurstRule::new("R8_BURST".to_string(), Decision::SoftDenyRetry, 5)
+    }
+
+    #[tokio::test]
+    async fn test_at_limit() {
+        let rule = test_rule();
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        record_requests(&storage, subject_id, 4).await;
+
+        let result = rule
+            .evaluate(&test_event(), subject_id, &storage)
+            .await
+            .unwrap();
+
+        assert!(!result.hit); // 4 prior + this one == 5
+    }
+
+    #[tokio::test]
+    async fn test_over_limit() {
+        let rule = test_rule();
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        record_requests(&storage, subject_id, 5).await;
+
+        let result = rule
+            .evaluate(&test_event(), subject_id, &storage)
+            .await
+            .unwrap();
+
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::SoftDenyRetry);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.value, "6");
+        assert_eq!(ev.limit, Some("5".to_string()));
+    }
+}
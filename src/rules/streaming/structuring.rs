@@ -1,25 +1,53 @@
 use async_trait::async_trait;
-use chrono::Duration;
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 use crate::domain::evidence::RuleResult;
-use crate::domain::{Decision, Evidence, TxEvent};
-use crate::rules::traits::StreamingRule;
-use crate::storage::Storage;
+use crate::domain::{Decision, Evidence, Money, RuleType, TxEvent};
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::rules::window::{aggregate_at, DayWindow, WindowStart};
+use crate::storage::{StorageRead, WindowSpec};
+
+/// Scales the structuring "small" threshold with the subject's typical
+/// transaction size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveThreshold {
+    /// Multiple of the typical size below which a transaction is small
+    pub multiplier: Decimal,
+    /// Transactions needed before the subject's own history is used
+    pub min_samples: u32,
+}
 
 /// Structuring detection rule.
 ///
 /// Detects potential structuring behavior by counting small transactions
-/// within a 24-hour window. Triggers when the count exceeds a threshold.
+/// within a day (rolling 24 hours or the current calendar day). Triggers
+/// when the count exceeds a threshold.
+///
+/// The fixed threshold can be set per asset, since reporting thresholds
+/// differ by rail; assets without one use the global threshold. The count
+/// covers all of the subject's transactions under the threshold chosen for
+/// the event's asset.
+///
+/// In adaptive mode, a subject with enough history has its own "small"
+/// threshold: a multiple of its typical transaction size. Subjects without
+/// that history use the fixed threshold.
 #[derive(Debug)]
 pub struct StructuringRule {
     id: String,
     action: Decision,
     /// Threshold below which a transaction is considered "small"
     amount_threshold: Decimal,
+    /// Thresholds keyed by uppercase asset symbol, overriding the global one
+    asset_thresholds: HashMap<String, Decimal>,
     /// Number of small transactions to trigger the rule
     count_threshold: u32,
+    /// Rolling or calendar-day window
+    window: DayWindow,
+    /// Per-subject threshold (optional)
+    adaptive: Option<AdaptiveThreshold>,
 }
 
 impl StructuringRule {
@@ -34,7 +62,61 @@ impl StructuringRule {
             id,
             action,
             amount_threshold,
+            asset_thresholds: HashMap::new(),
             count_threshold,
+            window: DayWindow::default(),
+            adaptive: None,
+        }
+    }
+
+    /// Use a different small threshold for the given assets.
+    pub fn with_asset_thresholds(mut self, thresholds: HashMap<String, Decimal>) -> Self {
+        self.asset_thresholds = thresholds
+            .into_iter()
+            .map(|(asset, threshold)| (asset.to_uppercase(), threshold))
+            .collect();
+        self
+    }
+
+    /// Fixed small threshold for the event's asset.
+    fn fixed_threshold(&self, event: &TxEvent) -> Decimal {
+        self.asset_thresholds
+            .get(&event.asset.0.to_uppercase())
+            .copied()
+            .unwrap_or(self.amount_threshold)
+    }
+
+    /// Count within the given window instead of a rolling 24 hours.
+    pub fn with_window(mut self, window: DayWindow) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Scale the small threshold with each subject's typical size.
+    pub fn with_adaptive(mut self, adaptive: AdaptiveThreshold) -> Self {
+        self.adaptive = Some(adaptive);
+        self
+    }
+
+    /// Small threshold for the subject, and its typical size when the
+    /// threshold was derived from it.
+    async fn threshold_for(
+        &self,
+        event: &TxEvent,
+        subject_id: Uuid,
+        storage: &dyn StorageRead,
+    ) -> anyhow::Result<(Decimal, Option<Decimal>)> {
+        let fixed = self.fixed_threshold(event);
+        let Some(adaptive) = self.adaptive else {
+            return Ok((fixed, None));
+        };
+
+        match storage.get_tx_size_profile(subject_id).await? {
+            Some(profile) if profile.samples >= adaptive.min_samples => Ok((
+                (profile.typical_usd * adaptive.multiplier).round_dp(2),
+                Some(profile.typical_usd),
+            )),
+            _ => Ok((fixed, None)),
         }
     }
 }
@@ -45,19 +127,59 @@ impl StreamingRule for StructuringRule {
         &self.id
     }
 
+    fn describe(&self) -> RuleDescription {
+        let by_asset: BTreeMap<&str, Money> = self
+            .asset_thresholds
+            .iter()
+            .map(|(asset, threshold)| (asset.as_str(), Money::usd(*threshold)))
+            .collect();
+        let adaptive = self.adaptive.map(|a| {
+            serde_json::json!({
+                "multiplier": a.multiplier.to_string(),
+                "min_samples": a.min_samples,
+            })
+        });
+        RuleDescription::new(
+            &self.id,
+            RuleType::StructuringSmallTx,
+            Some(self.action),
+            serde_json::json!({
+                "small_usd": Money::usd(self.amount_threshold),
+                "small_usd_by_asset": by_asset,
+                "small_count": self.count_threshold,
+                "window": self.window.label(),
+                "adaptive": adaptive,
+            }),
+        )
+    }
+
     async fn evaluate(
         &self,
         event: &TxEvent,
         subject_id: Uuid,
-        storage: &dyn Storage,
+        storage: &dyn StorageRead,
     ) -> anyhow::Result<RuleResult> {
+        let (threshold, typical_usd) = self.threshold_for(event, subject_id, storage).await?;
+
         // Count existing small transactions
-        let small_count = storage
-            .get_small_tx_count(subject_id, Duration::hours(24), self.amount_threshold)
-            .await?;
+        let small_count = match self.window.start() {
+            WindowStart::Lookback(window) => {
+                storage
+                    .get_small_tx_count(subject_id, window, threshold)
+                    .await?
+            }
+            WindowStart::Since { start, end } => {
+                let spec = WindowSpec::SmallCount {
+                    window: end - start,
+                    threshold,
+                };
+                let count = aggregate_at(storage, subject_id, spec, end).await?;
+                count.to_u32().unwrap_or(u32::MAX)
+            }
+        };
 
         // Check if current transaction is also small
-        let current_is_small = event.usd_value < self.amount_threshold;
+        let current_is_small = event.usd_value < threshold;
 
         // Calculate total including current transaction
         let total_count = if current_is_small {
@@ -68,19 +190,39 @@ impl StreamingRule for StructuringRule {
 
         // Trigger if count exceeds threshold (not just equals)
         if total_count > self.count_threshold {
-            return Ok(RuleResult::trigger(
-                self.action,
-                Evidence::with_limit(
-                    &self.id,
-                    "small_cnt_24h",
-                    total_count.to_string(),
-                    self.count_threshold.to_string(),
-                ),
-            ));
+            let mut evidence = Evidence::with_limit(
+                &self.id,
+                "small_cnt_24h",
+                total_count.to_string(),
+                self.count_threshold.to_string(),
+            );
+            if let Some(typical_usd) = typical_usd {
+                evidence = evidence.with_details(serde_json::json!({
+                    "small_usd": Money::usd(threshold),
+                    "typical_tx_usd": Money::usd(typical_usd),
+                }));
+            } else if threshold != self.amount_threshold {
+                evidence = evidence.with_details(serde_json::json!({
+                    "small_usd": Money::usd(threshold),
+                    "asset": event.asset.0.to_uppercase(),
+                }));
+            }
+            return Ok(RuleResult::trigger(self.action, evidence));
         }
 
         Ok(RuleResult::allow())
     }
+
+    fn windows(&self, event: &TxEvent) -> Vec<WindowSpec> {
+        // An adaptive threshold isn't known until the profile is read
+        match (self.adaptive, self.window.fixed_lookback()) {
+            (None, Some(window)) => vec![WindowSpec::SmallCount {
+                window,
+                threshold: self.fixed_threshold(event),
+            }],
+            _ => Vec::new(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -88,7 +230,7 @@ mod tests {
     use super::*;
     use crate::domain::event::{Asset, Chain, Direction, EventId, SCHEMA_VERSION};
     use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
-    use crate::storage::MockStorage;
+    use crate::storage::{MockStorage, StorageWrite, TransactionRecord, TxSizeProfile};
     use chrono::Utc;
     use smallvec::smallvec;
 
@@ -108,11 +250,19 @@ mod tests {
             chain: Chain::inline(),
             tx_hash: String::new(),
             direction: Direction::Outbound,
+            tx_type: String::new(),
             asset: Asset::new("USDC"),
             amount: usd_value.to_string(),
             usd_value: Decimal::new(usd_value, 0),
+            usd_rate: None,
             confirmations: 0,
             max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
         }
     }
 
@@ -201,6 +351,212 @@ mod tests {
         assert!(!result.hit); // Large tx not counted, still at 5
     }
 
+    #[tokio::test]
+    async fn test_asset_threshold_overrides_global() {
+        let rule = StructuringRule::new(
+            "R5_STRUCT".to_string(),
+            Decision::Review,
+            Decimal::new(10000, 0),
+            5,
+        )
+        .with_asset_thresholds(HashMap::from([("usdt".to_string(), Decimal::new(3000, 0))]));
+
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+        storage.set_small_tx_count(subject_id, 5);
+
+        // $5k is small against the global bar
+        let result = rule
+            .evaluate(&test_event(5000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        assert!(result.evidence.unwrap().details.is_null());
+
+        // but not against the USDT one
+        let mut event = test_event(5000);
+        event.asset = Asset::new("USDT");
+        assert!(
+            !rule
+                .evaluate(&event, subject_id, &storage)
+                .await
+                .unwrap()
+                .hit
+        );
+        assert_eq!(
+            rule.windows(&event),
+            vec![WindowSpec::SmallCount {
+                window: chrono::Duration::hours(24),
+                threshold: Decimal::new(3000, 0),
+            }]
+        );
+
+        let mut event = test_event(2000);
+        event.asset = Asset::new("USDT");
+        let result = rule.evaluate(&event, subject_id, &storage).await.unwrap();
+        assert!(result.hit);
+        let details = result.evidence.unwrap().details;
+        assert_eq!(details["asset"], "USDT");
+    }
+
+    async fn record_history(storage: &MockStorage, subject_id: Uuid, usd_value: i64, n: u32) {
+        for _ in 0..n {
+            storage
+                .record_transaction(&TransactionRecord {
+                    subject_id,
+                    tx_type: "Outbound".to_string(),
+                    asset: "USDC".to_string(),
+                    amount: Decimal::new(usd_value, 0),
+                    usd_value: Decimal::new(usd_value, 0),
+                    dest_address: None,
+                    counterparty_geo: None,
+                    account_id: None,
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_window_edge() {
+        let rule = StructuringRule::new(
+            "R5_STRUCT".to_string(),
+            Decision::Review,
+            Decimal::new(10000, 0),
+            5,
+        );
+        let storage = MockStorage::new().with_clock(Utc::now());
+        let subject_id = Uuid::new_v4();
+
+        record_history(&storage, subject_id, 5000, 1).await;
+        storage.advance(chrono::Duration::minutes(1));
+        record_history(&storage, subject_id, 5000, 4).await;
+        // $10k is not under the threshold
+        record_history(&storage, subject_id, 10000, 1).await;
+
+        // The first is still inside the window a second before it leaves
+        storage.advance(
+            chrono::Duration::hours(24)
+                - chrono::Duration::minutes(1)
+                - chrono::Duration::seconds(1),
+        );
+        let result = rule
+            .evaluate(&test_event(5000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        assert_eq!(result.evidence.unwrap().value, "6");
+
+        storage.advance(chrono::Duration::seconds(1));
+        let result = rule
+            .evaluate(&test_event(5000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(!result.hit);
+    }
+
+    fn adaptive_rule() -> StructuringRule {
+        StructuringRule::new(
+            "R5_STRUCT".to_string(),
+            Decision::Review,
+            Decimal::new(10000, 0),
+            5,
+        )
+        .with_adaptive(AdaptiveThreshold {
+            multiplier: Decimal::new(5, 1),
+            min_samples: 10,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_threshold_follows_typical_size() {
+        let rule = adaptive_rule();
+        let storage = MockStorage::new();
+
+        // A subject routinely moving $9k: an ordinary $8k transfer is small
+        // against the fixed $10k bar but not against half its typical size
+        let whale = Uuid::new_v4();
+        record_history(&storage, whale, 9000, 10).await;
+        storage.set_small_tx_count(whale, 5);
+        assert!(
+            !rule
+                .evaluate(&test_event(8000), whale, &storage)
+                .await
+                .unwrap()
+                .hit
+        );
+
+        // A micro user moving $200 at a time: $150 is not small for them
+        let micro = Uuid::new_v4();
+        record_history(&storage, micro, 200, 10).await;
+        storage.set_small_tx_count(micro, 5);
+        assert!(
+            !rule
+                .evaluate(&test_event(150), micro, &storage)
+                .await
+                .unwrap()
+                .hit
+        );
+
+        // ...but $50 is, and the evidence shows the subject's threshold
+        let result = rule
+            .evaluate(&test_event(50), micro, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        let details = result.evidence.unwrap().details;
+        let usd = |key: &str| {
+            assert_eq!(details[key]["currency"], "USD");
+            details[key]["amount"]
+                .as_str()
+                .unwrap()
+                .parse::<Decimal>()
+                .unwrap()
+        };
+        assert_eq!(usd("small_usd"), Decimal::new(100, 0));
+        assert_eq!(usd("typical_tx_usd"), Decimal::new(200, 0));
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_needs_history() {
+        let rule = adaptive_rule();
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+
+        // Too little history: the fixed $10k threshold applies
+        record_history(&storage, subject_id, 100_000, 3).await;
+        storage.set_small_tx_count(subject_id, 5);
+        let result = rule
+            .evaluate(&test_event(20000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(!result.hit);
+
+        let result = rule
+            .evaluate(&test_event(5000), subject_id, &storage)
+            .await
+            .unwrap();
+        assert!(result.hit);
+        assert!(result.evidence.unwrap().details.is_null());
+    }
+
+    #[test]
+    fn test_typical_size_resists_outliers() {
+        let mut profile = None;
+        for _ in 0..20 {
+            profile = TxSizeProfile::observe(profile, Decimal::new(100, 0));
+        }
+        profile = TxSizeProfile::observe(profile, Decimal::new(1_000_000, 0));
+        // Zero-value transactions are ignored
+        profile = TxSizeProfile::observe(profile, Decimal::ZERO);
+
+        let profile = profile.unwrap();
+        assert_eq!(profile.samples, 21);
+        // One $1M transfer moves a $100 subject to about $250, not $47k
+        assert!(profile.typical_usd > Decimal::new(200, 0));
+        assert!(profile.typical_usd < Decimal::new(300, 0));
+    }
+
     #[tokio::test]
     async fn test_mixed_transactions() {
         let rule = StructuringRule::new(
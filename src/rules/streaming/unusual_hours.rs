@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use chrono::{Duration, Timelike};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, Money, RuleType, TxEvent};
+use crate::rules::traits::{RuleDescription, StreamingRule};
+use crate::storage::StorageRead;
+
+/// Out-of-pattern hours rule.
+///
+/// Learns the subject's active hours from an hour-of-day histogram of past
+/// transactions and flags large transactions at hours where the subject is
+/// rarely active. The hour either side of the transaction is counted too,
+/// so activity just across an hour boundary is not treated as unusual.
+#[derive(Debug)]
+pub struct UnusualHoursRule {
+    id: String,
+    action: Decision,
+    /// Transactions below this USD value are not checked
+    min_usd: Decimal,
+    /// Prior transactions required before the histogram is trusted
+    min_history: u32,
+    /// Share of history near the transaction hour below which it triggers
+    min_share: f64,
+    /// History used to build the histogram
+    lookback: Duration,
+}
+
+impl UnusualHoursRule {
+    /// Create a new out-of-pattern hours rule.
+    pub fn new(
+        id: String,
+        action: Decision,
+        min_usd: Decimal,
+        min_history: u32,
+        min_share: f64,
+        lookback: Duration,
+    ) -> Self {
+        UnusualHoursRule {
+            id,
+            action,
+            min_usd,
+            min_history,
+            min_share,
+            lookback,
+        }
+    }
+}
+
+#[async_trait]
+impl StreamingRule for UnusualHoursRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn describe(&self) -> RuleDescription {
+        RuleDescription::new(
+            &self.id,
+            RuleType::UnusualHours,
+            Some(self.action),
+            serde_json::json!({
+                "min_usd": Money::usd(self.min_usd),
+                "min_history": self.min_history,
+                "min_share": self.min_share,
+                "lookback_days": self.lookback.num_days(),
+            }),
+        )
+    }
+
+    async fn evaluate(
+        &self,
+        event: &TxEvent,
+        subject_id: Uuid,
+        storage: &dyn StorageRead,
+    ) -> anyhow::Result<RuleResult> {
+        if event.usd_value < self.min_usd {
+            return Ok(RuleResult::allow());
+        }
+
+        let hours = storage
+            .get_hourly_activity(subject_id, self.lookback)
+            .await?;
+
+        // Not enough history to know what is usual
+        let total: u32 = hours.iter().sum();
+        if total < self.min_history {
+            return Ok(RuleResult::allow());
+        }
+
+        let hour = event.occurred_at.hour() as usize;
+        let nearby = hours[(hour + 23) % 24] + hours[hour] + hours[(hour + 1) % 24];
+        let share = nearby as f64 / total as f64;
+
+        if share < self.min_share {
+            return Ok(RuleResult::trigger(
+                self.action,
+                Evidence::with_limit(
+                    &self.id,
+                    "hour_share",
+                    format!("{:.3}", share),
+                    format!("{:.3}", self.min_share),
+                ),
+            ));
+        }
+
+        Ok(RuleResult::allow())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Chain, Direction, EventId, SCHEMA_VERSION};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use crate::storage::MockStorage;
+    use chrono::{TimeZone, Utc};
+    use smallvec::smallvec;
+
+    fn test_event(hour: u32, usd_value: i64) -> TxEvent {
+        let at = Utc.with_ymd_and_hms(2025, 1, 15, hour, 30, 0).unwrap();
+        TxEvent {
+            schema_version: SCHEMA_VERSION.to_string(),
+            event_id: EventId::new(),
+            occurred_at: at,
+            observed_at: at,
+            subject: Subject {
+                user_id: UserId::new("U1"),
+                account_id: AccountId::new("A1"),
+                addresses: smallvec![Address::new("0xabc")],
+                geo_iso: CountryCode::new("US"),
+                kyc_tier: KycTier::L1,
+            },
+            chain: Chain::inline(),
+            tx_hash: String::new(),
+            direction: Direction::Outbound,
+            tx_type: String::new(),
+            asset: Asset::new("USDC"),
+            amount: usd_value.to_string(),
+            usd_value: Decimal::new(usd_value, 0),
+            usd_rate: None,
+            confirmations: 0,
+            max_finality_depth: 0,
+            counterparty_geo: None,
+            destination: None,
+            features: Vec::new(),
+            subject_is_new: None,
+            kyc_tier_missing: false,
+            client_ip: None,
+        }
+    }
+
+    fn test_rule() -> UnusualHoursRule {
+        UnusualHoursRule::new(
+            "R7_UNUSUAL_HOURS".to_string(),
+            Decision::Review,
+            Decimal::new(5000, 0),
+            20,
+            0.05,
+            Duration::days(30),
+        )
+    }
+
+    /// Subject active during office hours only (09:00-17:59 UTC).
+    fn daytime_storage(subject_id: Uuid) -> MockStorage {
+        let storage = MockStorage::new();
+        let mut hours = [0u32; 24];
+        for count in hours.iter_mut().take(18).skip(9) {
+            *count = 5;
+        }
+        storage.set_hourly_activity(subject_id, hours);
+        storage
+    }
+
+    #[tokio::test]
+    async fn test_large_tx_outside_active_hours() {
+        let rule = test_rule();
+        let subject_id = Uuid::new_v4();
+        let storage = daytime_storage(subject_id);
+
+        let result = rule
+            .evaluate(&test_event(3, 10_000), subject_id, &storage)
+            .await
+            .unwrap();
+
+        assert!(result.hit);
+        assert_eq!(result.decision, Decision::Review);
+        let ev = result.evidence.unwrap();
+        assert_eq!(ev.value, "0.000");
+        assert_eq!(ev.limit, Some("0.050".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_within_or_adjacent_to_active_hours() {
+        let rule = test_rule();
+        let subject_id = Uuid::new_v4();
+        let storage = daytime_storage(subject_id);
+
+        for hour in [8, 12, 18] {
+            let result = rule
+                .evaluate(&test_event(hour, 10_000), subject_id, &storage)
+                .await
+                .unwrap();
+            assert!(!result.hit, "hour {} should be usual", hour);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_small_tx_not_checked() {
+        let rule = test_rule();
+        let subject_id = Uuid::new_v4();
+        let storage = daytime_storage(subject_id);
+
+        let result = rule
+            .evaluate(&test_event(3, 100), subject_id, &storage)
+            .await
+            .unwrap();
+
+        assert!(!result.hit);
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_history() {
+        let rule = test_rule();
+        let subject_id = Uuid::new_v4();
+        let storage = MockStorage::new();
+        let mut hours = [0u32; 24];
+        hours[12] = 10;
+        storage.set_hourly_activity(subject_id, hours);
+
+        let result = rule
+            .evaluate(&test_event(3, 10_000), subject_id, &storage)
+            .await
+            .unwrap();
+
+        assert!(!result.hit); // 10 < 20 transactions of history
+    }
+}
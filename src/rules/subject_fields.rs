@@ -0,0 +1,192 @@
+//! Handling of subjects whose KYC tier or country is missing or unknown.
+//!
+//! Requests without a tier are otherwise evaluated as L0, tiers the policy
+//! does not rank meet no tier requirement but pass tier caps they have no
+//! entry in, and a blank country passes every jurisdiction check. With
+//! `unknown_subject_fields` set, such subjects are instead evaluated as the
+//! least verified tier, reviewed or rejected, with evidence of each anomaly.
+
+use crate::domain::{Decision, Evidence, Policy, TierRanking, TxEvent, UnknownFieldAction};
+
+/// Rule ID of evidence about missing or unknown subject fields.
+pub const SUBJECT_FIELDS_RULE_ID: &str = "SUBJECT_FIELDS";
+
+/// A policy's handling of missing or unknown subject fields.
+#[derive(Debug, Clone, Default)]
+pub struct SubjectFieldCheck {
+    action: Option<UnknownFieldAction>,
+    ranking: TierRanking,
+}
+
+impl SubjectFieldCheck {
+    pub fn from_policy(policy: &Policy) -> Self {
+        SubjectFieldCheck {
+            action: policy.params.unknown_subject_fields,
+            ranking: policy.params.kyc_tiers.clone(),
+        }
+    }
+
+    /// Check the event's subject, returning the decision its anomalies
+    /// call for and their evidence. `tier_missing` is set when the tier
+    /// came from neither the request nor storage. With `most_restrictive`,
+    /// the subject's tier is replaced by the lowest ranked one.
+    pub fn apply(&self, event: &mut TxEvent, tier_missing: bool) -> (Decision, Vec<Evidence>) {
+        let Some(action) = self.action else {
+            return (Decision::Allow, Vec::new());
+        };
+        let mut decision = Decision::Allow;
+        let mut evidence = Vec::new();
+        let on_anomaly = match action {
+            UnknownFieldAction::Review => Decision::Review,
+            UnknownFieldAction::MostRestrictive | UnknownFieldAction::Reject => {
+                Decision::RejectFatal
+            }
+        };
+
+        let subject = &mut event.subject;
+        let tier_anomaly = if tier_missing {
+            Some("missing")
+        } else if self.ranking.rank(&subject.kyc_tier).is_none() {
+            Some("unknown")
+        } else {
+            None
+        };
+        if let Some(reason) = tier_anomaly {
+            let sent = if tier_missing {
+                String::new()
+            } else {
+                subject.kyc_tier.to_string()
+            };
+            let lowest = self.ranking.tiers().first().cloned();
+            match (action, lowest) {
+                (UnknownFieldAction::MostRestrictive, Some(lowest)) => {
+                    evidence.push(
+                        Evidence::with_limit(
+                            SUBJECT_FIELDS_RULE_ID,
+                            "kyc_tier",
+                            sent,
+                            lowest.to_string(),
+                        )
+                        .with_details(serde_json::json!({ "reason": reason })),
+                    );
+                    subject.kyc_tier = lowest;
+                }
+                _ => {
+                    decision = decision.max(on_anomaly);
+                    evidence.push(
+                        Evidence::new(SUBJECT_FIELDS_RULE_ID, "kyc_tier", sent)
+                            .with_details(serde_json::json!({ "reason": reason })),
+                    );
+                }
+            }
+        }
+
+        let geo = subject.geo_iso.as_str();
+        let geo_anomaly = if geo.is_empty() {
+            Some("missing")
+        } else if geo.len() != 2 || !geo.chars().all(|c| c.is_ascii_alphabetic()) {
+            Some("unknown")
+        } else {
+            None
+        };
+        if let Some(reason) = geo_anomaly {
+            decision = decision.max(on_anomaly);
+            evidence.push(
+                Evidence::new(SUBJECT_FIELDS_RULE_ID, "geo_iso", geo)
+                    .with_details(serde_json::json!({ "reason": reason })),
+            );
+        }
+
+        (decision, evidence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::event::{Asset, Direction};
+    use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, Subject, UserId};
+    use rust_decimal::Decimal;
+    use smallvec::smallvec;
+
+    fn check(action: &str) -> SubjectFieldCheck {
+        let policy: Policy = serde_yaml::from_str(&format!(
+            r#"
+policy_version: "test"
+params:
+  kyc_tiers: [L0, L1, L2]
+  unknown_subject_fields: {action}
+rules: []
+"#
+        ))
+        .unwrap();
+        SubjectFieldCheck::from_policy(&policy)
+    }
+
+    fn event(geo: &str, tier: &str) -> TxEvent {
+        let subject = Subject {
+            user_id: UserId::new("U1"),
+            account_id: AccountId::new("A1"),
+            addresses: smallvec![Address::new("0xabc")],
+            geo_iso: CountryCode::new(geo),
+            kyc_tier: KycTier::from_str(tier).unwrap_or_default(),
+        };
+        TxEvent::new(
+            subject,
+            Asset::new("USDC"),
+            Decimal::new(100, 0),
+            Direction::Outbound,
+        )
+    }
+
+    #[test]
+    fn test_known_fields_pass() {
+        let mut tx = event("US", "L1");
+        let (decision, evidence) = check("reject").apply(&mut tx, false);
+        assert_eq!(decision, Decision::Allow);
+        assert!(evidence.is_empty());
+
+        let mut tx = event("", "GOLD");
+        let (decision, evidence) = SubjectFieldCheck::default().apply(&mut tx, false);
+        assert_eq!(decision, Decision::Allow);
+        assert!(evidence.is_empty());
+    }
+
+    #[test]
+    fn test_review_and_reject() {
+        let mut tx = event("US", "GOLD");
+        let (decision, evidence) = check("review").apply(&mut tx, false);
+        assert_eq!(decision, Decision::Review);
+        assert_eq!(evidence[0].key, "kyc_tier");
+        assert_eq!(evidence[0].value, "GOLD");
+        assert_eq!(evidence[0].details["reason"], "unknown");
+
+        let mut tx = event("", "L1");
+        let (decision, evidence) = check("reject").apply(&mut tx, false);
+        assert_eq!(decision, Decision::RejectFatal);
+        assert_eq!(evidence[0].key, "geo_iso");
+        assert_eq!(evidence[0].details["reason"], "missing");
+    }
+
+    #[test]
+    fn test_most_restrictive_lowers_tier() {
+        let mut tx = event("US", "");
+        let (decision, evidence) = check("most_restrictive").apply(&mut tx, true);
+        assert_eq!(decision, Decision::Allow);
+        assert_eq!(tx.subject.kyc_tier, KycTier::L0);
+        assert_eq!(evidence[0].value, "");
+        assert_eq!(evidence[0].limit.as_deref(), Some("L0"));
+        assert_eq!(evidence[0].details["reason"], "missing");
+
+        let mut tx = event("US", "bogus");
+        let (decision, evidence) = check("most_restrictive").apply(&mut tx, false);
+        assert_eq!(decision, Decision::Allow);
+        assert_eq!(tx.subject.kyc_tier, KycTier::L0);
+        assert_eq!(evidence[0].value, "BOGUS");
+        assert_eq!(evidence[0].details["reason"], "unknown");
+
+        let mut tx = event("U5", "L2");
+        let (decision, _) = check("most_restrictive").apply(&mut tx, false);
+        assert_eq!(decision, Decision::RejectFatal);
+    }
+}
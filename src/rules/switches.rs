@@ -0,0 +1,124 @@
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+use crate::storage::{RuleSwitch, Storage};
+
+/// Rules turned off through the admin kill-switch.
+///
+/// Checked at evaluation time, so a toggle takes effect on the next request
+/// without rebuilding the rule set, and survives policy reloads.
+#[derive(Debug, Default)]
+pub struct RuleSwitches {
+    disabled: RwLock<HashMap<String, RuleSwitch>>,
+}
+
+impl RuleSwitches {
+    pub fn is_disabled(&self, rule_id: &str) -> bool {
+        self.disabled.read().contains_key(rule_id)
+    }
+
+    /// Disable a rule, replacing any earlier switch for it.
+    pub fn disable(&self, switch: RuleSwitch) {
+        self.disabled.write().insert(switch.rule_id.clone(), switch);
+    }
+
+    /// Re-enable a rule. Returns false if it was not disabled.
+    pub fn enable(&self, rule_id: &str) -> bool {
+        self.disabled.write().remove(rule_id).is_some()
+    }
+
+    /// Disabled rules, ordered by rule ID.
+    pub fn list(&self) -> Vec<RuleSwitch> {
+        let mut switches: Vec<_> = self.disabled.read().values().cloned().collect();
+        switches.sort_by(|a, b| a.rule_id.cmp(&b.rule_id));
+        switches
+    }
+
+    /// Replace every switch with the given set.
+    pub fn replace(&self, switches: Vec<RuleSwitch>) {
+        *self.disabled.write() = switches
+            .into_iter()
+            .map(|s| (s.rule_id.clone(), s))
+            .collect();
+    }
+
+    /// Load the persisted switches from storage.
+    pub async fn load(&self, storage: &dyn Storage) -> anyhow::Result<()> {
+        self.replace(storage.get_disabled_rules().await?);
+        Ok(())
+    }
+
+    /// Reload from storage each time `changes` signals a toggle made
+    /// elsewhere, until the sender is dropped.
+    pub fn follow(
+        self: Arc<Self>,
+        storage: Arc<dyn Storage>,
+        mut changes: mpsc::Receiver<()>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            while changes.recv().await.is_some() {
+                match self.load(storage.as_ref()).await {
+                    Ok(()) => info!(
+                        disabled = self.disabled.read().len(),
+                        "Reloaded rule switches"
+                    ),
+                    Err(e) => warn!(error = %e, "Failed to reload rule switches"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MockStorage;
+    use chrono::Utc;
+
+    fn switch(rule_id: &str) -> RuleSwitch {
+        RuleSwitch {
+            rule_id: rule_id.to_string(),
+            disabled_at: Utc::now(),
+            actor: "ops".to_string(),
+            reason: None,
+        }
+    }
+
+    #[test]
+    fn test_disable_and_enable() {
+        let switches = RuleSwitches::default();
+        switches.disable(switch("R2"));
+        switches.disable(switch("R1"));
+
+        assert!(switches.is_disabled("R1"));
+        assert!(!switches.is_disabled("R3"));
+        let ids: Vec<_> = switches.list().into_iter().map(|s| s.rule_id).collect();
+        assert_eq!(ids, vec!["R1", "R2"]);
+
+        assert!(switches.enable("R1"));
+        assert!(!switches.enable("R1"));
+        assert!(!switches.is_disabled("R1"));
+    }
+
+    #[tokio::test]
+    async fn test_follow_reloads_from_storage() {
+        let storage: Arc<dyn Storage> = Arc::new(MockStorage::new());
+        let switches = Arc::new(RuleSwitches::default());
+        switches.disable(switch("STALE"));
+
+        let (tx, rx) = mpsc::channel(1);
+        let handle = switches.clone().follow(storage.clone(), rx);
+
+        storage.disable_rule(&switch("R1")).await.unwrap();
+        tx.send(()).await.unwrap();
+        drop(tx);
+        handle.await.unwrap();
+
+        assert!(switches.is_disabled("R1"));
+        assert!(!switches.is_disabled("STALE"));
+    }
+}
@@ -1,8 +1,41 @@
 use crate::domain::evidence::RuleResult;
-use crate::domain::TxEvent;
+use crate::domain::{Decision, RuleType, TxEvent};
+use serde::Serialize;
 use std::fmt::Debug;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+/// What a rule checks and how it is configured, for display.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RuleDescription {
+    pub id: String,
+    /// Absent for rules the policy doesn't define, such as injected faults
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_type: Option<RuleType>,
+    /// Decision on a hit; absent when it depends on what was hit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<Decision>,
+    /// Rule parameters, leaving out list contents and credentials
+    #[schema(value_type = Object)]
+    pub params: serde_json::Value,
+}
+
+impl RuleDescription {
+    pub fn new(
+        id: &str,
+        rule_type: RuleType,
+        action: Option<Decision>,
+        params: serde_json::Value,
+    ) -> Self {
+        RuleDescription {
+            id: id.to_string(),
+            rule_type: Some(rule_type),
+            action,
+            params,
+        }
+    }
+}
+
 /// Trait for stateless inline rules.
 ///
 /// Inline rules are evaluated synchronously in the request path
@@ -19,6 +52,9 @@ pub trait InlineRule: Send + Sync + Debug {
     /// Returns a RuleResult indicating whether the rule triggered
     /// and what decision/evidence resulted.
     fn evaluate(&self, event: &TxEvent) -> RuleResult;
+
+    /// Describe the rule and its parameters.
+    fn describe(&self) -> RuleDescription;
 }
 
 /// Trait for stateful streaming rules.
@@ -33,20 +69,30 @@ pub trait StreamingRule: Send + Sync + Debug {
 
     /// Evaluate the rule against a transaction with storage access.
     ///
-    /// The storage provides access to query historical transaction data
-    /// for the subject (user/entity) identified by subject_id.
+    /// The storage provides read-only access to historical transaction
+    /// data for the subject (user/entity) identified by subject_id.
     async fn evaluate(
         &self,
         event: &TxEvent,
         subject_id: Uuid,
-        storage: &dyn crate::storage::Storage,
+        storage: &dyn crate::storage::StorageRead,
     ) -> anyhow::Result<RuleResult>;
+
+    /// Describe the rule and its parameters.
+    fn describe(&self) -> RuleDescription;
+
+    /// Window aggregates `evaluate` will read for this event, so they can
+    /// be fetched for every rule in one query beforehand. Rules reading
+    /// none, or whose windows move with the clock, return none.
+    fn windows(&self, _event: &TxEvent) -> Vec<crate::storage::WindowSpec> {
+        Vec::new()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{Decision, Evidence};
+    use crate::domain::Evidence;
 
     #[derive(Debug)]
     struct TestInlineRule {
@@ -59,6 +105,15 @@ mod tests {
             &self.id
         }
 
+        fn describe(&self) -> RuleDescription {
+            RuleDescription::new(
+                &self.id,
+                RuleType::KycTierTxCap,
+                Some(Decision::HoldAuto),
+                serde_json::Value::Null,
+            )
+        }
+
         fn evaluate(&self, _event: &TxEvent) -> RuleResult {
             if self.should_trigger {
                 RuleResult::trigger(
@@ -79,5 +134,6 @@ mod tests {
         };
 
         assert_eq!(rule.id(), "TEST_RULE");
+        assert_eq!(rule.describe().action, Some(Decision::HoldAuto));
     }
 }
@@ -0,0 +1,297 @@
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::{RuleParams, TxEvent, WindowKey, WindowMode};
+use crate::storage::{StorageRead, WindowSpec};
+
+/// Source of the current time for window boundaries.
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock.
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at a fixed instant.
+#[derive(Debug)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Where a window starts, as storage is queried for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowStart {
+    /// A fixed lookback from now
+    Lookback(Duration),
+    /// From `start` to `end`, both read from our clock, so the boundary
+    /// doesn't depend on storage's clock agreeing with ours
+    Since {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+}
+
+impl WindowStart {
+    /// How far back the window reaches.
+    pub fn length(&self) -> Duration {
+        match self {
+            WindowStart::Lookback(window) => *window,
+            WindowStart::Since { start, end } => *end - *start,
+        }
+    }
+}
+
+/// Daily window used by volume and count rules.
+///
+/// Rolling windows cover the last 24 hours. Calendar-day windows cover
+/// the time since the most recent midnight in the policy's timezone, so
+/// limits reset at the day boundary regulations are written against.
+/// Rolling windows are queried by lookback from now, calendar days from
+/// their start.
+#[derive(Debug, Clone)]
+pub struct DayWindow {
+    mode: WindowMode,
+    tz: Tz,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for DayWindow {
+    fn default() -> Self {
+        DayWindow::rolling()
+    }
+}
+
+impl DayWindow {
+    /// Rolling 24-hour window.
+    pub fn rolling() -> Self {
+        DayWindow {
+            mode: WindowMode::Rolling,
+            tz: Tz::UTC,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Window resetting at midnight in `tz`.
+    pub fn calendar_day(tz: Tz) -> Self {
+        DayWindow {
+            mode: WindowMode::CalendarDay,
+            tz,
+            ..DayWindow::rolling()
+        }
+    }
+
+    /// Build the window from policy params. An unparseable timezone falls
+    /// back to UTC; the policy loader rejects those.
+    pub fn from_params(params: &RuleParams) -> Self {
+        match params.window_mode {
+            WindowMode::Rolling => DayWindow::rolling(),
+            WindowMode::CalendarDay => DayWindow::calendar_day(
+                params
+                    .window_timezone
+                    .as_deref()
+                    .and_then(|tz| tz.parse().ok())
+                    .unwrap_or(Tz::UTC),
+            ),
+        }
+    }
+
+    /// Read the time from `clock` instead of the system clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Start of the current window.
+    pub fn start(&self) -> WindowStart {
+        match self.mode {
+            WindowMode::Rolling => WindowStart::Lookback(Duration::hours(24)),
+            WindowMode::CalendarDay => {
+                let end = self.clock.now();
+                WindowStart::Since {
+                    start: self.day_start(end),
+                    end,
+                }
+            }
+        }
+    }
+
+    /// Lookback from the clock's now covering the current window.
+    pub fn lookback(&self) -> Duration {
+        match self.mode {
+            WindowMode::Rolling => Duration::hours(24),
+            WindowMode::CalendarDay => {
+                let now = self.clock.now();
+                now - self.day_start(now)
+            }
+        }
+    }
+
+    /// Lookback for rolling windows, which doesn't move with the clock.
+    pub fn fixed_lookback(&self) -> Option<Duration> {
+        match self.mode {
+            WindowMode::Rolling => Some(Duration::hours(24)),
+            WindowMode::CalendarDay => None,
+        }
+    }
+
+    /// Start of the local day containing `now`, in UTC.
+    fn day_start(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let local = now.with_timezone(&self.tz);
+        let midnight = local.date_naive().and_time(NaiveTime::MIN);
+
+        // Where a DST change skips midnight, the day starts at the first
+        // instant after the gap
+        match self.tz.from_local_datetime(&midnight).earliest() {
+            Some(start) => start.with_timezone(&Utc),
+            None => (1..=24)
+                .find_map(|hours| {
+                    self.tz
+                        .from_local_datetime(&(midnight + Duration::hours(hours)))
+                        .earliest()
+                })
+                .map(|start| start.with_timezone(&Utc))
+                .unwrap_or(now),
+        }
+    }
+
+    /// Label recorded in evidence, e.g. `rolling_24h` or
+    /// `calendar_day:America/New_York`.
+    pub fn label(&self) -> String {
+        match self.mode {
+            WindowMode::Rolling => "rolling_24h".to_string(),
+            WindowMode::CalendarDay => format!("calendar_day:{}", self.tz.name()),
+        }
+    }
+}
+
+/// Volume window of the event's user or account, per `key`.
+pub fn volume_spec(key: WindowKey, event: &TxEvent, window: Duration) -> WindowSpec {
+    match key {
+        WindowKey::User => WindowSpec::Volume(window),
+        WindowKey::Account => WindowSpec::AccountVolume {
+            account_id: event.subject.account_id.as_str().to_string(),
+            window,
+        },
+    }
+}
+
+/// USD volume over `window` of the event's user or account, per `key`.
+pub async fn window_volume(
+    storage: &dyn StorageRead,
+    subject_id: Uuid,
+    event: &TxEvent,
+    key: WindowKey,
+    window: Duration,
+) -> anyhow::Result<Decimal> {
+    match key {
+        WindowKey::User => storage.get_rolling_volume(subject_id, window).await,
+        WindowKey::Account => {
+            let spec = volume_spec(key, event, window);
+            let values = storage.get_window_aggregates(subject_id, &[spec]).await?;
+            Ok(values.first().copied().unwrap_or_default())
+        }
+    }
+}
+
+/// USD volume of the event's user or account, per `key`, since `start`.
+pub async fn day_volume(
+    storage: &dyn StorageRead,
+    subject_id: Uuid,
+    event: &TxEvent,
+    key: WindowKey,
+    start: WindowStart,
+) -> anyhow::Result<Decimal> {
+    match start {
+        WindowStart::Lookback(window) => {
+            window_volume(storage, subject_id, event, key, window).await
+        }
+        WindowStart::Since { start, end } => {
+            let spec = volume_spec(key, event, end - start);
+            aggregate_at(storage, subject_id, spec, end).await
+        }
+    }
+}
+
+/// One aggregate over the window ending at `end`.
+pub async fn aggregate_at(
+    storage: &dyn StorageRead,
+    subject_id: Uuid,
+    spec: WindowSpec,
+    end: DateTime<Utc>,
+) -> anyhow::Result<Decimal> {
+    let values = storage
+        .get_window_aggregates_at(subject_id, &[spec], end)
+        .await?;
+    Ok(values.first().copied().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> Arc<dyn Clock> {
+        Arc::new(FixedClock(s.parse().unwrap()))
+    }
+
+    #[test]
+    fn test_rolling_lookback() {
+        let window = DayWindow::rolling().with_clock(at("2026-03-10T15:00:00Z"));
+        assert_eq!(window.lookback(), Duration::hours(24));
+        assert_eq!(window.start(), WindowStart::Lookback(Duration::hours(24)));
+        assert_eq!(window.label(), "rolling_24h");
+    }
+
+    #[test]
+    fn test_calendar_day_lookback() {
+        let window = DayWindow::calendar_day(Tz::UTC).with_clock(at("2026-03-10T15:30:00Z"));
+        assert_eq!(window.lookback(), Duration::minutes(15 * 60 + 30));
+
+        // 02:00 UTC is 22:00 the previous day in New York (EDT, UTC-4)
+        let window =
+            DayWindow::calendar_day(Tz::America__New_York).with_clock(at("2026-06-02T02:00:00Z"));
+        assert_eq!(window.lookback(), Duration::hours(22));
+        assert_eq!(
+            window.start(),
+            WindowStart::Since {
+                start: "2026-06-01T04:00:00Z".parse().unwrap(),
+                end: "2026-06-02T02:00:00Z".parse().unwrap(),
+            }
+        );
+        assert_eq!(window.label(), "calendar_day:America/New_York");
+    }
+
+    #[test]
+    fn test_calendar_day_across_dst() {
+        // New York springs forward on 2026-03-08; the day is 23 hours long
+        let window =
+            DayWindow::calendar_day(Tz::America__New_York).with_clock(at("2026-03-09T03:59:00Z"));
+        assert_eq!(window.lookback(), Duration::minutes(22 * 60 + 59));
+    }
+
+    #[test]
+    fn test_from_params() {
+        let params: RuleParams =
+            serde_yaml::from_str("window_mode: calendar_day\nwindow_timezone: Asia/Tokyo").unwrap();
+        let window = DayWindow::from_params(&params);
+        assert_eq!(window.label(), "calendar_day:Asia/Tokyo");
+
+        assert_eq!(
+            DayWindow::from_params(&RuleParams::default()).label(),
+            "rolling_24h"
+        );
+    }
+}
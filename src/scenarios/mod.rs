@@ -0,0 +1,376 @@
+//! Executable compliance scenarios.
+//!
+//! A scenario file describes one feature as a list of given/when/then
+//! cases, written in YAML:
+//!
+//! ```yaml
+//! feature: Daily volume limit
+//! scenarios:
+//!   - name: Withdrawal over the daily limit is held
+//!     given:
+//!       subject: { user_id: U1, account_id: A1, geo_iso: US, kyc_level: L2 }
+//!       history: { rolling_volume_usd: 45000 }
+//!     when:
+//!       tx: { type: withdraw, asset: USDC, usd_value: 10000 }
+//!     then:
+//!       decision: HOLD_AUTO
+//!       rule: R4_DAILY_VOLUME
+//! ```
+//!
+//! Each scenario runs against the real rule implementations with its
+//! history seeded into in-memory storage.
+
+pub mod report;
+pub mod vectors;
+
+pub use report::{ScenarioReport, ScenarioResult};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::api::request::{DecisionRequest, SubjectRequest, TxRequest};
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, Evidence, TxEvent};
+use crate::rules::RuleSet;
+use crate::storage::{DecisionRecord, MockStorage, StorageWrite, TransactionRecord};
+
+/// A scenario file: one feature and its scenarios.
+#[derive(Debug, Deserialize)]
+pub struct ScenarioFile {
+    pub feature: String,
+    pub scenarios: Vec<Scenario>,
+}
+
+/// A single given/when/then case.
+#[derive(Debug, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub given: Given,
+    pub when: When,
+    pub then: Then,
+}
+
+/// Subject and prior activity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Given {
+    pub subject: SubjectRequest,
+    #[serde(default)]
+    pub history: History,
+}
+
+/// Activity seeded into storage before the transaction is evaluated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    /// USD volume over the last 24 hours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rolling_volume_usd: Option<Decimal>,
+
+    /// Native volume over the last 24 hours, keyed by asset symbol
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rolling_amounts: BTreeMap<String, Decimal>,
+
+    /// Small transactions counted by structuring detection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub small_tx_count: Option<u32>,
+
+    /// Transaction counts by UTC hour of day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hourly_activity: Option<[u32; 24]>,
+
+    /// Decisions previously issued to the subject
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prior_decisions: Vec<Decision>,
+
+    /// Transactions made before, oldest first. Each passes through the
+    /// stateful rules, as it would have live, and is then recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transactions: Vec<TxRequest>,
+}
+
+/// The transaction under test.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct When {
+    pub tx: TxRequest,
+    /// Request context, such as the client `ip`
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub context: serde_json::Value,
+    /// When the transaction occurs (default: now)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// Expected outcome.
+#[derive(Debug, Deserialize)]
+pub struct Then {
+    pub decision: Decision,
+    /// Rule expected among those that triggered (optional)
+    #[serde(default)]
+    pub rule: Option<String>,
+}
+
+/// Load every `.yaml`/`.yml` scenario file in a directory, sorted by name.
+pub fn load_dir(dir: impl AsRef<Path>) -> anyhow::Result<Vec<(PathBuf, ScenarioFile)>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext == "yaml" || ext == "yml")
+        })
+        .collect();
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let content = fs::read_to_string(&path)?;
+            let file: ScenarioFile = serde_yaml::from_str(&content)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+            Ok((path, file))
+        })
+        .collect()
+}
+
+/// Run every scenario in the given files against a rule set.
+pub async fn run(ruleset: &RuleSet, files: &[(PathBuf, ScenarioFile)]) -> ScenarioReport {
+    let mut results = Vec::new();
+
+    for (path, file) in files {
+        for scenario in &file.scenarios {
+            let (decision, hits) = evaluate(ruleset, &scenario.given, &scenario.when).await;
+            let rules_hit: Vec<String> = hits.into_iter().map(|(id, _)| id).collect();
+            let rule_hit = match &scenario.then.rule {
+                Some(rule) => rules_hit.contains(rule),
+                None => true,
+            };
+            let passed = decision == scenario.then.decision && rule_hit;
+
+            results.push(ScenarioResult {
+                file: path.display().to_string(),
+                feature: file.feature.clone(),
+                name: scenario.name.clone(),
+                passed,
+                expected_decision: scenario.then.decision,
+                expected_rule: scenario.then.rule.clone(),
+                decision,
+                rules_hit,
+            });
+        }
+    }
+
+    ScenarioReport::new(ruleset.policy_version.clone(), results)
+}
+
+/// Evaluate one case, returning the decision and the results of the rules
+/// that triggered, by rule ID.
+async fn evaluate(
+    ruleset: &RuleSet,
+    given: &Given,
+    when: &When,
+) -> (Decision, Vec<(String, RuleResult)>) {
+    let mut event = tx_event(&given.subject, &when.tx, &when.context);
+    if let Some(at) = when.at {
+        event.occurred_at = at;
+        event.observed_at = at;
+    }
+
+    let storage = MockStorage::new();
+    let subject_id = storage.add_subject(event.subject.clone());
+    seed(ruleset, &storage, subject_id, given).await;
+
+    let mut decision = Decision::Allow;
+    let mut hits = Vec::new();
+
+    for rule in &ruleset.inline {
+        let result = rule.evaluate(&event);
+        if result.hit {
+            decision = decision.max(result.decision);
+            hits.push((rule.id().to_string(), result));
+        }
+    }
+
+    // Match the decision endpoint: fatal inline results skip stateful rules
+    if decision.is_fatal() {
+        return (decision, hits);
+    }
+
+    for rule in &ruleset.streaming {
+        if let Ok(result) = rule.evaluate(&event, subject_id, &storage).await {
+            if result.hit {
+                decision = decision.max(result.decision);
+                hits.push((rule.id().to_string(), result));
+            }
+        }
+    }
+
+    // A mitigation that lowers the decision is reported as a hit of its own
+    let mut evidence: Vec<Evidence> = hits
+        .iter()
+        .filter_map(|(_, result)| result.evidence.clone())
+        .collect();
+    let triggered = evidence.len();
+    ruleset
+        .mitigations
+        .apply(&event, &mut decision, &mut evidence);
+    if evidence.len() > triggered {
+        let mitigation = evidence.swap_remove(triggered);
+        hits.push((
+            mitigation.rule_id.clone(),
+            RuleResult::trigger(decision, mitigation),
+        ));
+    }
+
+    (decision, hits)
+}
+
+fn tx_event(subject: &SubjectRequest, tx: &TxRequest, context: &serde_json::Value) -> TxEvent {
+    DecisionRequest {
+        subject: subject.clone(),
+        tx: tx.clone(),
+        context: context.clone(),
+        features: Vec::new(),
+    }
+    .to_tx_event()
+}
+
+/// Seed a subject's history into storage.
+async fn seed(ruleset: &RuleSet, storage: &MockStorage, subject_id: Uuid, given: &Given) {
+    let history = &given.history;
+    if let Some(volume) = history.rolling_volume_usd {
+        storage.set_rolling_volume(subject_id, volume);
+    }
+    for (asset, amount) in &history.rolling_amounts {
+        storage.set_rolling_amount(subject_id, asset, *amount);
+    }
+    if let Some(count) = history.small_tx_count {
+        storage.set_small_tx_count(subject_id, count);
+    }
+    if let Some(hours) = history.hourly_activity {
+        storage.set_hourly_activity(subject_id, hours);
+    }
+    for decision in &history.prior_decisions {
+        // MockStorage cannot fail
+        let _ = storage
+            .record_decision(&DecisionRecord {
+                subject_id: Some(subject_id),
+                request_id: None,
+                request: serde_json::Value::Null,
+                decision: *decision,
+                decision_code: "SCENARIO".to_string(),
+                policy_version: String::new(),
+                evidence: vec![],
+                latency_ms: 0,
+            })
+            .await;
+    }
+    for tx in &history.transactions {
+        let event = tx_event(&given.subject, tx, &serde_json::Value::Null);
+        for rule in &ruleset.streaming {
+            let _ = rule.evaluate(&event, subject_id, storage).await;
+        }
+        let _ = storage
+            .record_transaction(&TransactionRecord {
+                subject_id,
+                tx_type: format!("{:?}", event.direction),
+                asset: event.asset.0.clone(),
+                amount: event.amount.parse().unwrap_or_default(),
+                usd_value: event.usd_value,
+                dest_address: event
+                    .destination
+                    .as_ref()
+                    .map(|d| d.address.as_str().to_string()),
+                counterparty_geo: event
+                    .counterparty_geo
+                    .as_ref()
+                    .map(|c| c.as_str().to_string()),
+                account_id: Some(event.subject.account_id.as_str().to_string()),
+            })
+            .await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Policy;
+    use std::collections::HashSet;
+
+    const POLICY: &str = r#"
+policy_version: "v1"
+params:
+  daily_volume_limit_usd: 50000
+  decision_rate_max_count: 2
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+  - id: R4_DAILY_VOLUME
+    type: daily_usd_volume
+    action: HOLD_AUTO
+  - id: R6_DECISION_RATE
+    type: decision_rate_anomaly
+    action: REVIEW
+"#;
+
+    const SCENARIOS: &str = r#"
+feature: Baseline
+scenarios:
+  - name: Small withdrawal is allowed
+    given:
+      subject: { user_id: U1, account_id: A1, geo_iso: US, kyc_level: L1 }
+    when:
+      tx: { type: withdraw, asset: USDC, usd_value: 100 }
+    then:
+      decision: ALLOW
+  - name: Sanctioned address is rejected
+    given:
+      subject: { user_id: U1, account_id: A1, geo_iso: US, kyc_level: L1, addresses: ["0xdead"] }
+      history: { rolling_volume_usd: 49950 }
+    when:
+      tx: { type: withdraw, asset: USDC, usd_value: 100 }
+    then:
+      decision: REJECT_FATAL
+      rule: R1_OFAC
+  - name: Repeated holds escalate to review
+    given:
+      subject: { user_id: U1, account_id: A1, geo_iso: US, kyc_level: L1 }
+      history:
+        rolling_volume_usd: 49950
+        prior_decisions: [HOLD_AUTO, HOLD_AUTO, REVIEW]
+    when:
+      tx: { type: withdraw, asset: USDC, usd_value: 100 }
+    then:
+      decision: REVIEW
+      rule: R4_DAILY_VOLUME
+  - name: Wrong expectation fails
+    given:
+      subject: { user_id: U1, account_id: A1, geo_iso: US, kyc_level: L1 }
+    when:
+      tx: { type: withdraw, asset: USDC, usd_value: 100 }
+    then:
+      decision: HOLD_AUTO
+"#;
+
+    #[tokio::test]
+    async fn test_run_scenarios() {
+        let policy: Policy = serde_yaml::from_str(POLICY).unwrap();
+        let ruleset = RuleSet::from_policy(&policy, HashSet::from(["0xdead".to_string()]));
+        let file: ScenarioFile = serde_yaml::from_str(SCENARIOS).unwrap();
+
+        let report = run(&ruleset, &[(PathBuf::from("baseline.yaml"), file)]).await;
+
+        assert_eq!(report.policy_version, "v1");
+        assert_eq!(report.passed, 3);
+        assert_eq!(report.failed, 1);
+        assert_eq!(
+            report.results[2].rules_hit,
+            vec!["R4_DAILY_VOLUME", "R6_DECISION_RATE"]
+        );
+        assert!(!report.results[3].passed);
+        assert_eq!(report.results[3].decision, Decision::Allow);
+    }
+}
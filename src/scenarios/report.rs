@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::domain::Decision;
+
+/// Signature value of a report that has not been signed.
+pub const UNSIGNED: &str = "unsigned";
+
+/// Outcome of a single scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioResult {
+    pub file: String,
+    pub feature: String,
+    pub name: String,
+    pub passed: bool,
+    pub expected_decision: Decision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_rule: Option<String>,
+    pub decision: Decision,
+    pub rules_hit: Vec<String>,
+}
+
+/// Results of a scenario run, signed for compliance sign-off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioReport {
+    pub policy_version: String,
+    pub generated_at: DateTime<Utc>,
+    pub passed: usize,
+    pub failed: usize,
+    pub results: Vec<ScenarioResult>,
+    /// Hex HMAC-SHA256 of the report with this field set to "unsigned"
+    pub signature: String,
+}
+
+impl ScenarioReport {
+    /// Create an unsigned report.
+    pub fn new(policy_version: String, results: Vec<ScenarioResult>) -> Self {
+        let passed = results.iter().filter(|r| r.passed).count();
+        ScenarioReport {
+            policy_version,
+            generated_at: Utc::now(),
+            passed,
+            failed: results.len() - passed,
+            results,
+            signature: UNSIGNED.to_string(),
+        }
+    }
+
+    /// Returns true if every scenario passed.
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0
+    }
+
+    /// Sign the report with a shared key.
+    pub fn sign(&mut self, key: &[u8]) {
+        self.signature = self.compute_signature(key);
+    }
+
+    /// Check the signature against a shared key.
+    pub fn verify(&self, key: &[u8]) -> bool {
+        self.signature != UNSIGNED && self.signature == self.compute_signature(key)
+    }
+
+    fn compute_signature(&self, key: &[u8]) -> String {
+        let unsigned = ScenarioReport {
+            signature: UNSIGNED.to_string(),
+            ..self.clone()
+        };
+        // Serializing plain structs cannot fail
+        let body = serde_json::to_vec(&unsigned).unwrap_or_default();
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(&body);
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> ScenarioReport {
+        ScenarioReport::new(
+            "v1".to_string(),
+            vec![ScenarioResult {
+                file: "baseline.yaml".to_string(),
+                feature: "Baseline".to_string(),
+                name: "Small withdrawal is allowed".to_string(),
+                passed: true,
+                expected_decision: Decision::Allow,
+                expected_rule: None,
+                decision: Decision::Allow,
+                rules_hit: vec![],
+            }],
+        )
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut report = report();
+        assert!(!report.verify(b"secret"));
+
+        report.sign(b"secret");
+        assert_eq!(report.signature.len(), 64);
+        assert!(report.verify(b"secret"));
+        assert!(!report.verify(b"other"));
+
+        // Any change to the results invalidates the signature
+        report.results[0].decision = Decision::Review;
+        assert!(!report.verify(b"secret"));
+    }
+}
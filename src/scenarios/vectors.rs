@@ -0,0 +1,295 @@
+//! Deterministic rule test vectors.
+//!
+//! `riskr vectors generate` evaluates a canonical set of cases, one that
+//! triggers and one that doesn't for every rule type, and writes each case
+//! with its outcome as JSON. `riskr vectors verify` evaluates a vector file
+//! again and reports every case whose outcome differs, so any build, or a
+//! reimplementation of the rules in another language, can check that it
+//! decides the same way.
+//!
+//! Outcomes record each triggered rule's decision and evidence key, value
+//! and limit. Evidence details are left out: they are context for
+//! investigators, not rule semantics.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use super::{evaluate, Given, When};
+use crate::domain::evidence::RuleResult;
+use crate::domain::{Decision, KycTier, Policy};
+use crate::geoip::{IpInfo, IpIntelligence};
+use crate::identity::IdentityProvider;
+use crate::rules::RuleSet;
+
+/// Version of the vector file layout.
+pub const VECTOR_FORMAT: u32 = 1;
+
+/// Canonical cases, compiled in so any build can generate vectors.
+const CASES: &str = include_str!("vectors.yaml");
+
+/// Inputs of one vector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Case {
+    pub name: String,
+    /// Policy holding the rule under test, as written
+    pub policy: serde_json::Value,
+    /// Sanctioned addresses
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sanctions: Vec<String>,
+    /// Tier the identity provider reports for the subject; unknown when
+    /// unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified_kyc_tier: Option<KycTier>,
+    /// What the IP databases report for every client IP; nothing when
+    /// unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_ip_info: Option<IpInfo>,
+    pub given: Given,
+    pub when: When,
+}
+
+/// A case and the outcome it must produce.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vector {
+    #[serde(flatten)]
+    pub case: Case,
+    pub expected: Outcome,
+}
+
+/// Decision for a case and the rules that triggered, in evaluation order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub decision: Decision,
+    pub hits: Vec<Hit>,
+}
+
+/// One triggered rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hit {
+    pub rule_id: String,
+    pub decision: Decision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<String>,
+}
+
+impl Hit {
+    fn new(rule_id: String, result: RuleResult) -> Self {
+        let evidence = result.evidence;
+        Hit {
+            rule_id,
+            decision: result.decision,
+            key: evidence.as_ref().map(|e| e.key.clone()),
+            value: evidence.as_ref().map(|e| e.value.clone()),
+            limit: evidence.and_then(|e| e.limit),
+        }
+    }
+}
+
+/// A vector file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorFile {
+    pub format: u32,
+    pub vectors: Vec<Vector>,
+}
+
+impl VectorFile {
+    /// Pretty JSON with object keys sorted, so generating twice gives the
+    /// same bytes.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(&serde_json::to_value(self)?)?)
+    }
+}
+
+/// A vector whose outcome differs from the expected one.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub name: String,
+    pub expected: Outcome,
+    pub actual: Outcome,
+}
+
+/// Identity provider answering one tier for every subject.
+#[derive(Debug)]
+struct FixedTier(Option<KycTier>);
+
+#[async_trait]
+impl IdentityProvider for FixedTier {
+    async fn kyc_tier(&self, _user_id: &str) -> anyhow::Result<Option<KycTier>> {
+        Ok(self.0.clone())
+    }
+}
+
+/// IP databases answering the same for every address.
+#[derive(Debug)]
+struct FixedIpInfo(Option<IpInfo>);
+
+impl IpIntelligence for FixedIpInfo {
+    fn lookup(&self, _ip: IpAddr) -> IpInfo {
+        self.0.clone().unwrap_or_default()
+    }
+}
+
+/// The canonical cases.
+pub fn cases() -> anyhow::Result<Vec<Case>> {
+    Ok(serde_yaml::from_str(CASES)?)
+}
+
+/// Evaluate a case against fresh rules and storage.
+pub async fn run_case(case: &Case) -> anyhow::Result<Outcome> {
+    let policy: Policy = serde_json::from_value(case.policy.clone())
+        .map_err(|e| anyhow::anyhow!("{}: invalid policy: {}", case.name, e))?;
+    let ruleset = RuleSet::from_policy(&policy, case.sanctions.iter().cloned().collect())
+        .with_identity_provider(&policy, Arc::new(FixedTier(case.verified_kyc_tier.clone())))
+        .with_distinct_sketches(&policy, Arc::default())
+        .with_ip_intelligence(&policy, Arc::new(FixedIpInfo(case.client_ip_info.clone())));
+
+    let (decision, hits) = evaluate(&ruleset, &case.given, &case.when).await;
+    Ok(Outcome {
+        decision,
+        hits: hits
+            .into_iter()
+            .map(|(rule_id, result)| Hit::new(rule_id, result))
+            .collect(),
+    })
+}
+
+/// Evaluate every canonical case.
+pub async fn generate() -> anyhow::Result<VectorFile> {
+    let mut vectors = Vec::new();
+    for case in cases()? {
+        let expected = run_case(&case).await?;
+        vectors.push(Vector { case, expected });
+    }
+    Ok(VectorFile {
+        format: VECTOR_FORMAT,
+        vectors,
+    })
+}
+
+/// Evaluate every vector in a file, returning those whose outcome differs.
+pub async fn verify(file: &VectorFile) -> anyhow::Result<Vec<Mismatch>> {
+    anyhow::ensure!(
+        file.format == VECTOR_FORMAT,
+        "unsupported vector format {} (expected {})",
+        file.format,
+        VECTOR_FORMAT
+    );
+
+    let mut mismatches = Vec::new();
+    for vector in &file.vectors {
+        let actual = run_case(&vector.case).await?;
+        if actual != vector.expected {
+            mismatches.push(Mismatch {
+                name: vector.case.name.clone(),
+                expected: vector.expected.clone(),
+                actual,
+            });
+        }
+    }
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RuleType;
+
+    /// Every rule type. The match stops compiling when a type is added, as
+    /// a reminder to add cases for it.
+    fn rule_types() -> Vec<RuleType> {
+        use RuleType::*;
+        let all = vec![
+            OfacAddr,
+            JurisdictionBlock,
+            KycTierTxCap,
+            DailyUsdVolume,
+            WeeklyUsdVolume,
+            MonthlyUsdVolume,
+            StructuringSmallTx,
+            DecisionRateAnomaly,
+            UnusualHours,
+            RequestBurst,
+            CountryTxCount,
+            ChainHop,
+            MinKycTier,
+            CompositeRisk,
+            PendingFinality,
+            KycVerification,
+            DistinctDestinations,
+            IpJurisdiction,
+            AmountSanity,
+        ];
+        for rule_type in &all {
+            match rule_type {
+                OfacAddr | JurisdictionBlock | KycTierTxCap | DailyUsdVolume | WeeklyUsdVolume
+                | MonthlyUsdVolume | StructuringSmallTx | DecisionRateAnomaly | UnusualHours
+                | RequestBurst | CountryTxCount | ChainHop | MinKycTier | CompositeRisk
+                | PendingFinality | KycVerification | DistinctDestinations | IpJurisdiction
+                | AmountSanity => {}
+            }
+        }
+        all
+    }
+
+    #[tokio::test]
+    async fn test_cases_cover_every_rule_type() {
+        let mut triggered = Vec::new();
+        let mut allowed = Vec::new();
+        for case in cases().unwrap() {
+            let policy: Policy = serde_json::from_value(case.policy.clone()).unwrap();
+            assert_eq!(policy.rules.len(), 1, "{}", case.name);
+            let rule_type = policy.rules[0].rule_type.clone();
+
+            let outcome = run_case(&case).await.unwrap();
+            if outcome.hits.is_empty() {
+                allowed.push(rule_type);
+            } else {
+                assert_eq!(outcome.hits[0].rule_id, policy.rules[0].id, "{}", case.name);
+                triggered.push(rule_type);
+            }
+        }
+
+        for rule_type in rule_types() {
+            assert!(
+                triggered.contains(&rule_type),
+                "{rule_type:?} never triggers"
+            );
+            assert!(
+                allowed.contains(&rule_type),
+                "{rule_type:?} always triggers"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generated_vectors_verify() {
+        let file = generate().await.unwrap();
+        let json = file.to_json().unwrap();
+        assert_eq!(generate().await.unwrap().to_json().unwrap(), json);
+
+        let mut parsed: VectorFile = serde_json::from_str(&json).unwrap();
+        assert!(verify(&parsed).await.unwrap().is_empty());
+
+        let daily = parsed
+            .vectors
+            .iter_mut()
+            .find(|v| v.case.name == "daily_usd_volume over limit")
+            .unwrap();
+        assert_eq!(daily.expected.decision, Decision::HoldAuto);
+        assert_eq!(daily.expected.hits[0].value.as_deref(), Some("50000.01"));
+        daily.expected.decision = Decision::Allow;
+
+        let mismatches = verify(&parsed).await.unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].actual.decision, Decision::HoldAuto);
+
+        parsed.format = VECTOR_FORMAT + 1;
+        assert!(verify(&parsed).await.is_err());
+    }
+}
@@ -1,25 +1,70 @@
 // src/storage/mock.rs
 use async_trait::async_trait;
-use chrono::Duration;
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
 use parking_lot::Mutex;
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use uuid::Uuid;
 
-use crate::domain::{Policy, Subject};
+use crate::domain::subject::{Address, KycTier, SubjectAddress};
+use crate::domain::{Annotation, Case, CaseStatus, Decision, DecisionEvent, Policy, Subject};
 
-use super::traits::{DecisionRecord, Storage, TransactionRecord};
+use super::traits::{
+    AssetFlow, BlocklistEntry, DecisionRecord, KycOverride, OutboxEvent, PendingDeposit,
+    RuleSwitch, StorageRead, StorageWrite, StoredDecision, TransactionPoint, TransactionRecord,
+    TxSizeProfile, UsageRecord, WindowSpec,
+};
 
 /// Mock storage for testing.
+///
+/// By default windowed reads return the counters seeded with `set_*`,
+/// whatever the window. With [`MockStorage::with_clock`] they are computed
+/// from the transactions recorded against a clock the test controls,
+/// applying each window and threshold as Postgres does.
 #[derive(Debug, Default)]
 pub struct MockStorage {
     subjects: Mutex<HashMap<String, (Uuid, Subject)>>,
+    /// Addresses ever linked to each subject and not removed, oldest first
+    subject_addresses: Mutex<HashMap<Uuid, Vec<SubjectAddress>>>,
+    /// Tiers set out-of-band, keyed by user ID
+    kyc_overrides: Mutex<HashMap<String, KycTier>>,
     rolling_volumes: Mutex<HashMap<Uuid, Decimal>>,
+    rolling_amounts: Mutex<HashMap<(Uuid, String), Decimal>>,
     small_tx_counts: Mutex<HashMap<Uuid, u32>>,
+    hourly_activity: Mutex<HashMap<Uuid, [u32; 24]>>,
+    tx_sizes: Mutex<HashMap<Uuid, TxSizeProfile>>,
     sanctions: Mutex<Vec<String>>,
     active_policy: Mutex<Option<Policy>>,
-    recorded_transactions: Mutex<Vec<TransactionRecord>>,
-    recorded_decisions: Mutex<Vec<DecisionRecord>>,
+    policies: Mutex<HashMap<String, Policy>>,
+    /// Recorded transactions and when, oldest first
+    recorded_transactions: Mutex<Vec<(DateTime<Utc>, TransactionRecord)>>,
+    transaction_points: Mutex<HashMap<Uuid, Vec<TransactionPoint>>>,
+    recorded_decisions: Mutex<Vec<StoredDecision>>,
+    /// Undelivered outbox events, oldest first
+    outbox: Mutex<Vec<OutboxEvent>>,
+    /// When each claimed outbox event's lease runs out
+    outbox_claims: Mutex<HashMap<Uuid, DateTime<Utc>>>,
+    delivered_outbox: Mutex<Vec<OutboxEvent>>,
+    dead_outbox: Mutex<Vec<OutboxEvent>>,
+    /// Deposits held for finality, keyed by event ID
+    pending_deposits: Mutex<HashMap<String, PendingDeposit>>,
+    /// Cases in creation order
+    cases: Mutex<Vec<Case>>,
+    /// Subject annotations in creation order
+    annotations: Mutex<Vec<Annotation>>,
+    /// Rules disabled through the kill-switch, keyed by rule ID
+    disabled_rules: Mutex<BTreeMap<String, RuleSwitch>>,
+    /// Internal blocklist keyed by address
+    blocklist: Mutex<BTreeMap<String, BlocklistEntry>>,
+    /// Usage keyed by day then tenant
+    usage: Mutex<BTreeMap<(NaiveDate, String), UsageRecord>>,
+    /// Claimed request nonces and when they expire
+    nonces: Mutex<HashMap<String, DateTime<Utc>>>,
+    degraded: AtomicBool,
+    /// Time set with `with_clock`, or None for the wall clock
+    clock: Mutex<Option<DateTime<Utc>>>,
 }
 
 impl MockStorage {
@@ -27,16 +72,131 @@ impl MockStorage {
         Self::default()
     }
 
+    /// Fix the clock at `now` and compute windowed reads from recorded
+    /// transactions instead of seeded counters.
+    pub fn with_clock(self, now: DateTime<Utc>) -> Self {
+        *self.clock.lock() = Some(now);
+        self
+    }
+
+    /// Current time: the fixed clock, or the wall clock when unset.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.lock().unwrap_or_else(Utc::now)
+    }
+
+    /// Move the fixed clock forward (for testing).
+    ///
+    /// Panics without `with_clock`.
+    pub fn advance(&self, by: Duration) {
+        let mut clock = self.clock.lock();
+        let now = clock.as_mut().expect("advance needs with_clock");
+        *now += by;
+    }
+
+    fn clocked(&self) -> bool {
+        self.clock.lock().is_some()
+    }
+
+    /// A subject's recorded transactions in `(now - window, now]`, as the
+    /// Postgres window predicate.
+    fn windowed(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> Vec<(DateTime<Utc>, TransactionRecord)> {
+        self.windowed_at(subject_id, window, self.now())
+    }
+
+    /// A subject's recorded transactions in `(end - window, end]`.
+    fn windowed_at(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+        end: DateTime<Utc>,
+    ) -> Vec<(DateTime<Utc>, TransactionRecord)> {
+        let since = end - window;
+        self.recorded_transactions
+            .lock()
+            .iter()
+            .filter(|(at, tx)| tx.subject_id == subject_id && *at > since && *at <= end)
+            .cloned()
+            .collect()
+    }
+
+    /// Link a subject's addresses that are not linked yet.
+    fn link_addresses(&self, subject_id: Uuid, subject: &Subject) {
+        let mut linked = self.subject_addresses.lock();
+        let linked = linked.entry(subject_id).or_default();
+        for address in &subject.addresses {
+            if !linked.iter().any(|a| &a.address == address) {
+                linked.push(SubjectAddress {
+                    address: address.clone(),
+                    verified: false,
+                    label: None,
+                    first_seen: Utc::now(),
+                });
+            }
+        }
+    }
+
+    /// Link a recorded decision to its subject's active case, opening one
+    /// with `note` for a review. Returns whether a case was opened.
+    fn link_case(&self, decision: &DecisionRecord, decision_id: Uuid, note: Option<&str>) -> bool {
+        let Some(subject_id) = decision.subject_id else {
+            return false;
+        };
+
+        let mut cases = self.cases.lock();
+        match cases
+            .iter_mut()
+            .find(|c| c.subject_id == subject_id && c.status.is_active())
+        {
+            Some(case) => {
+                case.decision_ids.push(decision_id);
+                case.updated_at = Utc::now();
+                false
+            }
+            None if decision.decision == Decision::Review => {
+                let mut case = new_case(subject_id, Some(decision_id), note);
+                case.decision_ids.push(decision_id);
+                cases.push(case);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Set the rolling volume for a subject (for testing).
     pub fn set_rolling_volume(&self, subject_id: Uuid, volume: Decimal) {
         self.rolling_volumes.lock().insert(subject_id, volume);
     }
 
+    /// Set the rolling native amount of an asset for a subject (for testing).
+    pub fn set_rolling_amount(&self, subject_id: Uuid, asset: &str, amount: Decimal) {
+        self.rolling_amounts
+            .lock()
+            .insert((subject_id, asset.to_uppercase()), amount);
+    }
+
     /// Set the small tx count for a subject (for testing).
     pub fn set_small_tx_count(&self, subject_id: Uuid, count: u32) {
         self.small_tx_counts.lock().insert(subject_id, count);
     }
 
+    /// Set the hourly activity histogram for a subject (for testing).
+    pub fn set_hourly_activity(&self, subject_id: Uuid, hours: [u32; 24]) {
+        self.hourly_activity.lock().insert(subject_id, hours);
+    }
+
+    /// Add a timestamped transaction for a subject (for testing).
+    pub fn add_transaction_point(&self, subject_id: Uuid, point: TransactionPoint) {
+        self.transaction_points
+            .lock()
+            .entry(subject_id)
+            .or_default()
+            .push(point);
+    }
+
     /// Add a sanctioned address (for testing).
     pub fn add_sanction(&self, address: String) {
         self.sanctions.lock().push(address.to_lowercase());
@@ -51,23 +211,62 @@ impl MockStorage {
     pub fn add_subject(&self, subject: Subject) -> Uuid {
         let id = Uuid::new_v4();
         let user_id = subject.user_id.as_str().to_string();
+        self.link_addresses(id, &subject);
         self.subjects.lock().insert(user_id, (id, subject));
         id
     }
 
+    /// Mark the storage as degraded (for testing).
+    pub fn set_degraded(&self, degraded: bool) {
+        self.degraded.store(degraded, Ordering::Relaxed);
+    }
+
     /// Get recorded transactions (for assertions).
     pub fn get_recorded_transactions(&self) -> Vec<TransactionRecord> {
-        self.recorded_transactions.lock().clone()
+        self.recorded_transactions
+            .lock()
+            .iter()
+            .map(|(_, tx)| tx.clone())
+            .collect()
     }
 
     /// Get recorded decisions (for assertions).
     pub fn get_recorded_decisions(&self) -> Vec<DecisionRecord> {
-        self.recorded_decisions.lock().clone()
+        self.recorded_decisions
+            .lock()
+            .iter()
+            .map(|d| d.record.clone())
+            .collect()
+    }
+
+    /// Get delivered outbox events (for assertions).
+    pub fn get_delivered_outbox(&self) -> Vec<OutboxEvent> {
+        self.delivered_outbox.lock().clone()
+    }
+
+    /// Get dead-lettered outbox events (for assertions).
+    pub fn get_dead_outbox(&self) -> Vec<OutboxEvent> {
+        self.dead_outbox.lock().clone()
+    }
+}
+
+/// Open case with no linked decisions.
+fn new_case(subject_id: Uuid, opened_by_decision: Option<Uuid>, note: Option<&str>) -> Case {
+    let now = Utc::now();
+    Case {
+        id: Uuid::new_v4(),
+        subject_id,
+        status: CaseStatus::Open,
+        opened_by_decision,
+        decision_ids: Vec::new(),
+        note: note.map(str::to_string),
+        created_at: now,
+        updated_at: now,
     }
 }
 
 #[async_trait]
-impl Storage for MockStorage {
+impl StorageRead for MockStorage {
     async fn get_subject_by_user_id(
         &self,
         user_id: &str,
@@ -75,31 +274,41 @@ impl Storage for MockStorage {
         Ok(self.subjects.lock().get(user_id).cloned())
     }
 
-    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid> {
-        let user_id = subject.user_id.as_str().to_string();
-        let mut subjects = self.subjects.lock();
-
-        if let Some((id, _)) = subjects.get(&user_id) {
-            let id = *id;
-            subjects.insert(user_id, (id, subject.clone()));
-            Ok(id)
-        } else {
-            let id = Uuid::new_v4();
-            subjects.insert(user_id, (id, subject.clone()));
-            Ok(id)
-        }
+    async fn get_subject_addresses(&self, subject_id: Uuid) -> anyhow::Result<Vec<SubjectAddress>> {
+        Ok(self
+            .subject_addresses
+            .lock()
+            .get(&subject_id)
+            .cloned()
+            .unwrap_or_default())
     }
 
-    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid> {
-        self.recorded_transactions.lock().push(tx.clone());
-        Ok(Uuid::new_v4())
+    async fn get_kyc_overrides(&self) -> anyhow::Result<Vec<KycOverride>> {
+        let mut overrides: Vec<KycOverride> = self
+            .kyc_overrides
+            .lock()
+            .iter()
+            .map(|(user_id, tier)| KycOverride {
+                user_id: user_id.clone(),
+                kyc_tier: tier.clone(),
+            })
+            .collect();
+        overrides.sort_by(|a, b| a.user_id.cmp(&b.user_id));
+        Ok(overrides)
     }
 
     async fn get_rolling_volume(
         &self,
         subject_id: Uuid,
-        _window: Duration,
+        window: Duration,
     ) -> anyhow::Result<Decimal> {
+        if self.clocked() {
+            return Ok(self
+                .windowed(subject_id, window)
+                .iter()
+                .map(|(_, tx)| tx.usd_value)
+                .sum());
+        }
         Ok(self
             .rolling_volumes
             .lock()
@@ -108,12 +317,62 @@ impl Storage for MockStorage {
             .unwrap_or(Decimal::ZERO))
     }
 
+    async fn get_rolling_volume_at(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Decimal> {
+        let since = at - window;
+        Ok(self
+            .transaction_points
+            .lock()
+            .get(&subject_id)
+            .map(|points| {
+                points
+                    .iter()
+                    .filter(|p| p.at > since && p.at <= at)
+                    .map(|p| p.usd_value)
+                    .sum()
+            })
+            .unwrap_or(Decimal::ZERO))
+    }
+
+    async fn get_rolling_amount(
+        &self,
+        subject_id: Uuid,
+        asset: &str,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        if self.clocked() {
+            return Ok(self
+                .windowed(subject_id, window)
+                .iter()
+                .filter(|(_, tx)| tx.asset.eq_ignore_ascii_case(asset))
+                .map(|(_, tx)| tx.amount)
+                .sum());
+        }
+        Ok(self
+            .rolling_amounts
+            .lock()
+            .get(&(subject_id, asset.to_uppercase()))
+            .copied()
+            .unwrap_or(Decimal::ZERO))
+    }
+
     async fn get_small_tx_count(
         &self,
         subject_id: Uuid,
-        _window: Duration,
-        _threshold: Decimal,
+        window: Duration,
+        threshold: Decimal,
     ) -> anyhow::Result<u32> {
+        if self.clocked() {
+            return Ok(self
+                .windowed(subject_id, window)
+                .iter()
+                .filter(|(_, tx)| tx.usd_value < threshold)
+                .count() as u32);
+        }
         Ok(self
             .small_tx_counts
             .lock()
@@ -122,6 +381,170 @@ impl Storage for MockStorage {
             .unwrap_or(0))
     }
 
+    async fn get_counterparty_tx_count(
+        &self,
+        subject_id: Uuid,
+        country: &str,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        Ok(self
+            .windowed(subject_id, window)
+            .iter()
+            .filter(|(_, tx)| tx.counterparty_geo.as_deref() == Some(country))
+            .count() as u32)
+    }
+
+    async fn get_asset_flows(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<AssetFlow>> {
+        let mut flows: BTreeMap<String, AssetFlow> = BTreeMap::new();
+        for (_, tx) in self.windowed(subject_id, window) {
+            let asset = tx.asset.to_uppercase();
+            let flow = flows.entry(asset.clone()).or_insert_with(|| AssetFlow {
+                asset,
+                inbound_usd: Decimal::ZERO,
+                outbound_usd: Decimal::ZERO,
+            });
+            if tx.tx_type == "Inbound" {
+                flow.inbound_usd += tx.usd_value;
+            } else {
+                flow.outbound_usd += tx.usd_value;
+            }
+        }
+        Ok(flows.into_values().collect())
+    }
+
+    async fn get_recent_transactions(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<TransactionPoint>> {
+        let now = self.now();
+        let since = now - window;
+        let mut points: Vec<TransactionPoint> = self
+            .transaction_points
+            .lock()
+            .get(&subject_id)
+            .map(|points| {
+                points
+                    .iter()
+                    .filter(|p| p.at > since && p.at <= now)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+        points.sort_by_key(|p| p.at);
+        Ok(points)
+    }
+
+    async fn get_hourly_activity(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<[u32; 24]> {
+        if self.clocked() {
+            let mut hours = [0u32; 24];
+            for (at, _) in self.windowed(subject_id, window) {
+                hours[at.hour() as usize] += 1;
+            }
+            return Ok(hours);
+        }
+        Ok(self
+            .hourly_activity
+            .lock()
+            .get(&subject_id)
+            .copied()
+            .unwrap_or([0; 24]))
+    }
+
+    async fn get_window_aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+    ) -> anyhow::Result<Vec<Decimal>> {
+        let mut values = Vec::with_capacity(specs.len());
+        for spec in specs {
+            let value = match spec {
+                WindowSpec::Volume(window) => self.get_rolling_volume(subject_id, *window).await?,
+                WindowSpec::SmallCount { window, threshold } => self
+                    .get_small_tx_count(subject_id, *window, *threshold)
+                    .await?
+                    .into(),
+                WindowSpec::CounterpartyCount { country, window } => self
+                    .get_counterparty_tx_count(subject_id, country, *window)
+                    .await?
+                    .into(),
+                WindowSpec::DistinctDestinations(window) => {
+                    let recorded = self.windowed(subject_id, *window);
+                    let destinations: HashSet<&str> = recorded
+                        .iter()
+                        .filter_map(|(_, tx)| tx.dest_address.as_deref())
+                        .collect();
+                    Decimal::from(destinations.len())
+                }
+                WindowSpec::AccountVolume { account_id, window } => self
+                    .windowed(subject_id, *window)
+                    .iter()
+                    .filter(|(_, tx)| tx.account_id.as_ref() == Some(account_id))
+                    .map(|(_, tx)| tx.usd_value)
+                    .sum(),
+                WindowSpec::Amount { asset, window } => {
+                    self.get_rolling_amount(subject_id, asset, *window).await?
+                }
+            };
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Without a clock, the seeded counters, as `get_window_aggregates`.
+    async fn get_window_aggregates_at(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Decimal>> {
+        if !self.clocked() {
+            return self.get_window_aggregates(subject_id, specs).await;
+        }
+        Ok(specs
+            .iter()
+            .map(|spec| {
+                let recorded = self.windowed_at(subject_id, spec.window(), at);
+                let txs = recorded.iter().map(|(_, tx)| tx);
+                match spec {
+                    WindowSpec::Volume(_) => txs.map(|tx| tx.usd_value).sum(),
+                    WindowSpec::SmallCount { threshold, .. } => {
+                        Decimal::from(txs.filter(|tx| tx.usd_value < *threshold).count())
+                    }
+                    WindowSpec::CounterpartyCount { country, .. } => Decimal::from(
+                        txs.filter(|tx| tx.counterparty_geo.as_ref() == Some(country))
+                            .count(),
+                    ),
+                    WindowSpec::DistinctDestinations(_) => Decimal::from(
+                        txs.filter_map(|tx| tx.dest_address.as_deref())
+                            .collect::<HashSet<_>>()
+                            .len(),
+                    ),
+                    WindowSpec::AccountVolume { account_id, .. } => txs
+                        .filter(|tx| tx.account_id.as_ref() == Some(account_id))
+                        .map(|tx| tx.usd_value)
+                        .sum(),
+                    WindowSpec::Amount { asset, .. } => txs
+                        .filter(|tx| tx.asset.eq_ignore_ascii_case(asset))
+                        .map(|tx| tx.amount)
+                        .sum(),
+                }
+            })
+            .collect())
+    }
+
+    async fn get_tx_size_profile(&self, subject_id: Uuid) -> anyhow::Result<Option<TxSizeProfile>> {
+        Ok(self.tx_sizes.lock().get(&subject_id).copied())
+    }
+
     async fn get_all_sanctions(&self) -> anyhow::Result<Vec<String>> {
         Ok(self.sanctions.lock().clone())
     }
@@ -135,14 +558,518 @@ impl Storage for MockStorage {
         Ok(self.active_policy.lock().clone())
     }
 
+    async fn get_policy(&self, version: &str) -> anyhow::Result<Option<Policy>> {
+        Ok(self.policies.lock().get(version).cloned())
+    }
+
+    async fn count_recent_decisions(
+        &self,
+        subject_id: Uuid,
+        min_decision: Decision,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        // Like the seeded counters, unclocked reads ignore the window
+        let now = self.now();
+        let clocked = self.clocked();
+        Ok(self
+            .recorded_decisions
+            .lock()
+            .iter()
+            .filter(|d| !clocked || (d.created_at > now - window && d.created_at <= now))
+            .map(|d| &d.record)
+            .filter(|d| d.subject_id == Some(subject_id) && d.decision >= min_decision)
+            .count() as u32)
+    }
+
+    async fn get_decisions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        let mut decisions: Vec<StoredDecision> = self
+            .recorded_decisions
+            .lock()
+            .iter()
+            .filter(|d| d.created_at >= from && d.created_at < to)
+            .filter(|d| after.is_none_or(|cursor| (d.created_at, d.id) > cursor))
+            .cloned()
+            .collect();
+        decisions.sort_by_key(|d| (d.created_at, d.id));
+        decisions.truncate(limit as usize);
+        Ok(decisions)
+    }
+
+    async fn get_decision_by_request_id(
+        &self,
+        request_id: &str,
+    ) -> anyhow::Result<Option<StoredDecision>> {
+        Ok(self
+            .recorded_decisions
+            .lock()
+            .iter()
+            .rev()
+            .find(|d| d.record.request_id.as_deref() == Some(request_id))
+            .cloned())
+    }
+
+    async fn get_subject_decisions(
+        &self,
+        subject_id: Uuid,
+        at: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        let mut decisions: Vec<StoredDecision> = self
+            .recorded_decisions
+            .lock()
+            .iter()
+            .filter(|d| d.record.subject_id == Some(subject_id) && d.created_at <= at)
+            .cloned()
+            .collect();
+        decisions.sort_by_key(|d| std::cmp::Reverse((d.created_at, d.id)));
+        decisions.truncate(limit as usize);
+        Ok(decisions)
+    }
+
+    async fn get_recent_decisions(&self, limit: u32) -> anyhow::Result<Vec<StoredDecision>> {
+        let mut decisions = self.recorded_decisions.lock().clone();
+        decisions.sort_by_key(|d| std::cmp::Reverse((d.created_at, d.id)));
+        decisions.truncate(limit as usize);
+        Ok(decisions)
+    }
+
+    async fn pending_outbox(&self, limit: u32) -> anyhow::Result<Vec<OutboxEvent>> {
+        Ok(self
+            .outbox
+            .lock()
+            .iter()
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_pending_deposit(&self, event_id: &str) -> anyhow::Result<Option<PendingDeposit>> {
+        Ok(self.pending_deposits.lock().get(event_id).cloned())
+    }
+
+    async fn get_case(&self, case_id: Uuid) -> anyhow::Result<Option<Case>> {
+        Ok(self.cases.lock().iter().find(|c| c.id == case_id).cloned())
+    }
+
+    async fn list_cases(
+        &self,
+        status: Option<CaseStatus>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Case>> {
+        let mut cases: Vec<Case> = self
+            .cases
+            .lock()
+            .iter()
+            .filter(|c| status.is_none() || status == Some(c.status))
+            .cloned()
+            .collect();
+        cases.sort_by_key(|c| Reverse(c.updated_at));
+        cases.truncate(limit as usize);
+        Ok(cases)
+    }
+
+    async fn get_annotations(
+        &self,
+        subject_id: Uuid,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Annotation>> {
+        Ok(self
+            .annotations
+            .lock()
+            .iter()
+            .rev()
+            .filter(|a| a.subject_id == subject_id)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_disabled_rules(&self) -> anyhow::Result<Vec<RuleSwitch>> {
+        Ok(self.disabled_rules.lock().values().cloned().collect())
+    }
+
+    async fn get_blocklist(&self) -> anyhow::Result<Vec<BlocklistEntry>> {
+        Ok(self.blocklist.lock().values().cloned().collect())
+    }
+
+    async fn get_daily_usage(&self, tenant: &str) -> anyhow::Result<u64> {
+        let key = (Utc::now().date_naive(), tenant.to_string());
+        Ok(self.usage.lock().get(&key).map_or(0, |r| r.decisions))
+    }
+
+    async fn get_usage(&self, from: NaiveDate, to: NaiveDate) -> anyhow::Result<Vec<UsageRecord>> {
+        Ok(self
+            .usage
+            .lock()
+            .values()
+            .filter(|r| r.day >= from && r.day <= to)
+            .cloned()
+            .collect())
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.degraded.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl StorageWrite for MockStorage {
+    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid> {
+        let user_id = subject.user_id.as_str().to_string();
+        let mut subject = subject.clone();
+        if let Some(tier) = self.kyc_overrides.lock().get(&user_id) {
+            subject.kyc_tier = tier.clone();
+        }
+        let mut subjects = self.subjects.lock();
+
+        let id = match subjects.get(&user_id) {
+            Some((id, _)) => *id,
+            None => Uuid::new_v4(),
+        };
+        self.link_addresses(id, &subject);
+        subjects.insert(user_id, (id, subject));
+        Ok(id)
+    }
+
+    async fn set_kyc_tier(&self, user_id: &str, tier: &KycTier) -> anyhow::Result<bool> {
+        let mut subjects = self.subjects.lock();
+        let Some((_, subject)) = subjects.get_mut(user_id) else {
+            return Ok(false);
+        };
+
+        subject.kyc_tier = tier.clone();
+        self.kyc_overrides
+            .lock()
+            .insert(user_id.to_string(), tier.clone());
+        Ok(true)
+    }
+
+    async fn save_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+        verified: Option<bool>,
+        label: Option<&str>,
+    ) -> anyhow::Result<SubjectAddress> {
+        let address = Address::new(address);
+        let (saved, added) = {
+            let mut linked = self.subject_addresses.lock();
+            let linked = linked.entry(subject_id).or_default();
+            let added = !linked.iter().any(|a| a.address == address);
+            if added {
+                linked.push(SubjectAddress {
+                    address: address.clone(),
+                    verified: false,
+                    label: None,
+                    first_seen: Utc::now(),
+                });
+            }
+            let saved = linked
+                .iter_mut()
+                .find(|a| a.address == address)
+                .expect("address was just linked");
+            if let Some(verified) = verified {
+                saved.verified = verified;
+            }
+            if let Some(label) = label {
+                saved.label = Some(label.to_string()).filter(|l| !l.is_empty());
+            }
+            (saved.clone(), added)
+        };
+
+        if added {
+            if let Some((_, subject)) = self
+                .subjects
+                .lock()
+                .values_mut()
+                .find(|(id, _)| *id == subject_id)
+            {
+                subject.addresses.push(address);
+            }
+        }
+        Ok(saved)
+    }
+
+    async fn remove_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+    ) -> anyhow::Result<bool> {
+        let address = Address::new(address);
+        let removed = match self.subject_addresses.lock().get_mut(&subject_id) {
+            Some(linked) => {
+                let before = linked.len();
+                linked.retain(|a| a.address != address);
+                linked.len() < before
+            }
+            None => false,
+        };
+
+        if let Some((_, subject)) = self
+            .subjects
+            .lock()
+            .values_mut()
+            .find(|(id, _)| *id == subject_id)
+        {
+            subject.addresses.retain(|a| *a != address);
+        }
+        Ok(removed)
+    }
+
+    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid> {
+        let at = self.now();
+        self.recorded_transactions.lock().push((at, tx.clone()));
+        let mut tx_sizes = self.tx_sizes.lock();
+        if let Some(profile) =
+            TxSizeProfile::observe(tx_sizes.get(&tx.subject_id).copied(), tx.usd_value)
+        {
+            tx_sizes.insert(tx.subject_id, profile);
+        }
+        drop(tx_sizes);
+        self.add_transaction_point(
+            tx.subject_id,
+            TransactionPoint {
+                at,
+                usd_value: tx.usd_value,
+            },
+        );
+        Ok(Uuid::new_v4())
+    }
+
     async fn set_active_policy(&self, policy: &Policy) -> anyhow::Result<()> {
         *self.active_policy.lock() = Some(policy.clone());
+        self.policies
+            .lock()
+            .insert(policy.version.clone(), policy.clone());
         Ok(())
     }
 
     async fn record_decision(&self, decision: &DecisionRecord) -> anyhow::Result<Uuid> {
-        self.recorded_decisions.lock().push(decision.clone());
-        Ok(Uuid::new_v4())
+        let id = Uuid::new_v4();
+        self.recorded_decisions.lock().push(StoredDecision {
+            id,
+            created_at: self.now(),
+            record: decision.clone(),
+        });
+        Ok(id)
+    }
+
+    async fn record_outcome(
+        &self,
+        tx: &TransactionRecord,
+        decision: &DecisionRecord,
+    ) -> anyhow::Result<Uuid> {
+        self.record_transaction(tx).await?;
+        let decision_id = self.record_decision(decision).await?;
+        self.link_case(decision, decision_id, None);
+
+        self.outbox.lock().push(OutboxEvent {
+            id: Uuid::new_v4(),
+            kind: "decision".to_string(),
+            payload: decision.outbox_payload(decision_id),
+            attempts: 0,
+        });
+
+        Ok(decision_id)
+    }
+
+    async fn record_decision_outcome(
+        &self,
+        decision: &DecisionRecord,
+        note: Option<&str>,
+    ) -> anyhow::Result<(Uuid, bool)> {
+        let decision_id = self.record_decision(decision).await?;
+        let opened = self.link_case(decision, decision_id, note);
+
+        self.outbox.lock().push(OutboxEvent {
+            id: Uuid::new_v4(),
+            kind: "decision".to_string(),
+            payload: decision.outbox_payload(decision_id),
+            attempts: 0,
+        });
+
+        Ok((decision_id, opened))
+    }
+
+    async fn claim_outbox(&self, limit: u32, lease: Duration) -> anyhow::Result<Vec<OutboxEvent>> {
+        let now = self.now();
+        let mut claims = self.outbox_claims.lock();
+        let claimed: Vec<OutboxEvent> = self
+            .outbox
+            .lock()
+            .iter()
+            .filter(|e| claims.get(&e.id).is_none_or(|until| *until <= now))
+            .take(limit as usize)
+            .cloned()
+            .collect();
+        for event in &claimed {
+            claims.insert(event.id, now + lease);
+        }
+        Ok(claimed)
+    }
+
+    async fn mark_outbox_delivered(&self, id: Uuid) -> anyhow::Result<()> {
+        let mut outbox = self.outbox.lock();
+        if let Some(pos) = outbox.iter().position(|e| e.id == id) {
+            let event = outbox.remove(pos);
+            self.delivered_outbox.lock().push(event);
+        }
+        Ok(())
+    }
+
+    async fn mark_outbox_failed(&self, id: Uuid, _error: &str) -> anyhow::Result<()> {
+        if let Some(event) = self.outbox.lock().iter_mut().find(|e| e.id == id) {
+            event.attempts += 1;
+        }
+        Ok(())
+    }
+
+    async fn mark_outbox_dead(&self, id: Uuid, _error: &str) -> anyhow::Result<()> {
+        let mut outbox = self.outbox.lock();
+        if let Some(pos) = outbox.iter().position(|e| e.id == id) {
+            let mut event = outbox.remove(pos);
+            event.attempts += 1;
+            self.dead_outbox.lock().push(event);
+        }
+        Ok(())
+    }
+
+    async fn save_pending_deposit(&self, deposit: &PendingDeposit) -> anyhow::Result<()> {
+        self.pending_deposits
+            .lock()
+            .insert(deposit.event.event_id.0.clone(), deposit.clone());
+        Ok(())
+    }
+
+    async fn resolve_pending_deposit(
+        &self,
+        event_id: &str,
+        decision: &DecisionRecord,
+        event: &DecisionEvent,
+    ) -> anyhow::Result<Option<Uuid>> {
+        if self.pending_deposits.lock().remove(event_id).is_none() {
+            return Ok(None);
+        }
+
+        let decision_id = self.record_decision(decision).await?;
+        self.link_case(decision, decision_id, None);
+        self.outbox.lock().push(OutboxEvent {
+            id: Uuid::new_v4(),
+            kind: "decision_event".to_string(),
+            payload: serde_json::to_value(event)?,
+            attempts: 0,
+        });
+
+        Ok(Some(decision_id))
+    }
+
+    async fn create_case(
+        &self,
+        subject_id: Uuid,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        let mut cases = self.cases.lock();
+        if cases
+            .iter()
+            .any(|c| c.subject_id == subject_id && c.status.is_active())
+        {
+            return Ok(None);
+        }
+
+        let case = new_case(subject_id, None, note);
+        cases.push(case.clone());
+        Ok(Some(case))
+    }
+
+    async fn update_case_status(
+        &self,
+        case_id: Uuid,
+        from: CaseStatus,
+        to: CaseStatus,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        let mut cases = self.cases.lock();
+        let Some(case) = cases
+            .iter_mut()
+            .find(|c| c.id == case_id && c.status == from)
+        else {
+            return Ok(None);
+        };
+
+        case.status = to;
+        if let Some(note) = note {
+            case.note = Some(note.to_string());
+        }
+        case.updated_at = Utc::now();
+        Ok(Some(case.clone()))
+    }
+
+    async fn add_annotation(&self, annotation: &Annotation) -> anyhow::Result<()> {
+        self.annotations.lock().push(annotation.clone());
+        Ok(())
+    }
+
+    async fn disable_rule(&self, switch: &RuleSwitch) -> anyhow::Result<()> {
+        self.disabled_rules
+            .lock()
+            .insert(switch.rule_id.clone(), switch.clone());
+        Ok(())
+    }
+
+    async fn enable_rule(&self, rule_id: &str, _actor: &str) -> anyhow::Result<()> {
+        self.disabled_rules.lock().remove(rule_id);
+        Ok(())
+    }
+
+    async fn save_blocklist(
+        &self,
+        entries: &[BlocklistEntry],
+        replace: bool,
+    ) -> anyhow::Result<()> {
+        let mut blocklist = self.blocklist.lock();
+        if replace {
+            blocklist.clear();
+        }
+        for entry in entries {
+            blocklist.insert(entry.address.clone(), entry.clone());
+        }
+        Ok(())
+    }
+
+    async fn record_usage(&self, tenant: &str, rules_hit: &[String]) -> anyhow::Result<()> {
+        let day = Utc::now().date_naive();
+        let mut usage = self.usage.lock();
+        let record = usage
+            .entry((day, tenant.to_string()))
+            .or_insert_with(|| UsageRecord {
+                tenant: tenant.to_string(),
+                day,
+                decisions: 0,
+                rule_hits: BTreeMap::new(),
+            });
+
+        record.decisions += 1;
+        for rule_id in rules_hit {
+            *record.rule_hits.entry(rule_id.clone()).or_default() += 1;
+        }
+        Ok(())
+    }
+
+    async fn claim_nonce(&self, nonce: &str, expires_at: DateTime<Utc>) -> anyhow::Result<bool> {
+        let now = Utc::now();
+        let mut nonces = self.nonces.lock();
+        nonces.retain(|_, expiry| *expiry > now);
+        if nonces.contains_key(nonce) {
+            return Ok(false);
+        }
+        nonces.insert(nonce.to_string(), expires_at);
+        Ok(true)
     }
 }
 
@@ -175,6 +1102,20 @@ mod tests {
         assert_eq!(retrieved.user_id.as_str(), "U1");
     }
 
+    #[tokio::test]
+    async fn test_kyc_tier_survives_upsert() {
+        let storage = MockStorage::new();
+
+        assert!(!storage.set_kyc_tier("U1", &KycTier::L2).await.unwrap());
+
+        storage.upsert_subject(&test_subject()).await.unwrap();
+        assert!(storage.set_kyc_tier("U1", &KycTier::L2).await.unwrap());
+        storage.upsert_subject(&test_subject()).await.unwrap();
+
+        let (_, retrieved) = storage.get_subject_by_user_id("U1").await.unwrap().unwrap();
+        assert_eq!(retrieved.kyc_tier, KycTier::L2);
+    }
+
     #[tokio::test]
     async fn test_sanctions_check() {
         let storage = MockStorage::new();
@@ -198,4 +1139,150 @@ mod tests {
             .unwrap();
         assert_eq!(volume, Decimal::new(45000, 0));
     }
+
+    #[tokio::test]
+    async fn test_clocked_windows() {
+        let start: DateTime<Utc> = "2024-01-15T10:00:00Z".parse().unwrap();
+        let storage = MockStorage::new().with_clock(start);
+        let subject_id = Uuid::new_v4();
+        let tx = |usd_value: i64| TransactionRecord {
+            subject_id,
+            tx_type: "Outbound".to_string(),
+            asset: "USDC".to_string(),
+            amount: Decimal::new(usd_value, 0),
+            usd_value: Decimal::new(usd_value, 0),
+            dest_address: None,
+            counterparty_geo: None,
+            account_id: None,
+        };
+
+        storage.record_transaction(&tx(100)).await.unwrap();
+        storage.advance(Duration::hours(1));
+        storage.record_transaction(&tx(5000)).await.unwrap();
+        storage.set_rolling_volume(subject_id, Decimal::new(1, 0));
+
+        // Seeded counters are ignored once the clock is fixed
+        let volume = storage
+            .get_rolling_volume(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(volume, Decimal::new(5100, 0));
+        let small = storage
+            .get_small_tx_count(subject_id, Duration::hours(24), Decimal::new(5000, 0))
+            .await
+            .unwrap();
+        assert_eq!(small, 1);
+
+        // A transaction exactly one window old has left it
+        storage.advance(Duration::hours(23));
+        let volume = storage
+            .get_rolling_volume(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(volume, Decimal::new(5000, 0));
+        let hours = storage
+            .get_hourly_activity(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(hours[10], 0);
+        assert_eq!(hours[11], 1);
+
+        storage.advance(Duration::hours(1));
+        let volume = storage
+            .get_rolling_volume(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(volume, Decimal::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_usage() {
+        let storage = MockStorage::new();
+        let today = Utc::now().date_naive();
+
+        storage.record_usage("payments", &[]).await.unwrap();
+        storage
+            .record_usage("payments", &["R1_OFAC".to_string()])
+            .await
+            .unwrap();
+        storage.record_usage("lending", &[]).await.unwrap();
+
+        assert_eq!(storage.get_daily_usage("payments").await.unwrap(), 2);
+        assert_eq!(storage.get_daily_usage("unknown").await.unwrap(), 0);
+
+        let usage = storage.get_usage(today, today).await.unwrap();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[1].tenant, "payments");
+        assert_eq!(usage[1].rule_hits["R1_OFAC"], 1);
+
+        let yesterday = today.pred_opt().unwrap();
+        assert!(storage
+            .get_usage(yesterday, yesterday)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_count_recent_decisions() {
+        let storage = MockStorage::new();
+        let subject_id = Uuid::new_v4();
+
+        for decision in [Decision::Allow, Decision::HoldAuto, Decision::Review] {
+            storage
+                .record_decision(&DecisionRecord {
+                    subject_id: Some(subject_id),
+                    request_id: None,
+                    request: serde_json::Value::Null,
+                    decision,
+                    decision_code: "TEST".to_string(),
+                    policy_version: "v1".to_string(),
+                    evidence: vec![],
+                    latency_ms: 1,
+                })
+                .await
+                .unwrap();
+        }
+
+        let count = storage
+            .count_recent_decisions(subject_id, Decision::HoldAuto, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let other = storage
+            .count_recent_decisions(Uuid::new_v4(), Decision::Allow, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(other, 0);
+    }
+
+    #[tokio::test]
+    async fn test_count_recent_decisions_clocked() {
+        let storage = MockStorage::new().with_clock(Utc::now());
+        let subject_id = Uuid::new_v4();
+        let decision = DecisionRecord {
+            subject_id: Some(subject_id),
+            request_id: None,
+            request: serde_json::Value::Null,
+            decision: Decision::Review,
+            decision_code: "TEST".to_string(),
+            policy_version: "v1".to_string(),
+            evidence: vec![],
+            latency_ms: 1,
+        };
+
+        storage.record_decision(&decision).await.unwrap();
+        storage.advance(Duration::minutes(30));
+        storage.record_decision(&decision).await.unwrap();
+
+        let storage = &storage;
+        let count =
+            move |window| storage.count_recent_decisions(subject_id, Decision::Allow, window);
+        assert_eq!(count(Duration::hours(1)).await.unwrap(), 2);
+        // Only the decision inside the window counts
+        assert_eq!(count(Duration::minutes(10)).await.unwrap(), 1);
+        storage.advance(Duration::minutes(30));
+        assert_eq!(count(Duration::minutes(10)).await.unwrap(), 0);
+    }
 }
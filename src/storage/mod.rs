@@ -1,8 +1,26 @@
 // src/storage/mod.rs
 pub mod mock;
+#[cfg(feature = "server")]
 pub mod postgres;
+#[cfg(feature = "server")]
+pub mod resilient;
+#[cfg(feature = "server")]
+pub mod tiered;
 pub mod traits;
+#[cfg(feature = "server")]
+pub mod window_cache;
 
 pub use mock::MockStorage;
+#[cfg(feature = "server")]
 pub use postgres::PostgresStorage;
-pub use traits::{DecisionRecord, Storage, TransactionRecord};
+#[cfg(feature = "server")]
+pub use resilient::{BreakerOptions, ResilientStorage, RetryPolicy};
+#[cfg(feature = "server")]
+pub use tiered::TieredStorage;
+pub use traits::{
+    AssetFlow, BlocklistEntry, DecisionRecord, KycOverride, OutboxEvent, PendingDeposit,
+    RuleSwitch, Storage, StorageRead, StorageWrite, StoredDecision, TransactionPoint,
+    TransactionRecord, TxSizeProfile, UsageRecord, WindowSpec, TX_SIZE_EWMA_ALPHA,
+};
+#[cfg(feature = "server")]
+pub use window_cache::WindowCache;
@@ -1,21 +1,42 @@
 // src/storage/postgres.rs
 use async_trait::async_trait;
-use chrono::Duration;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use rust_decimal::Decimal;
-use sqlx::postgres::PgPoolOptions;
-use sqlx::{PgPool, Row};
+use sqlx::postgres::{PgListener, PgPoolOptions, PgRow};
+use sqlx::{PgConnection, PgPool, Row};
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tracing::{info, warn};
 use uuid::Uuid;
 
-use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, UserId};
-use crate::domain::{Policy, Subject};
+use crate::domain::subject::{AccountId, Address, CountryCode, KycTier, SubjectAddress, UserId};
+use crate::domain::{
+    Annotation, Case, CaseStatus, Decision, DecisionEvent, Policy, RiskRating, Subject,
+};
+use crate::export::REDACTED;
+use crate::retention::EvidenceRetention;
 
-use super::traits::{DecisionRecord, Storage, TransactionRecord};
+use super::traits::{
+    AssetFlow, BlocklistEntry, DecisionRecord, KycOverride, OutboxEvent, PendingDeposit,
+    RuleSwitch, StorageRead, StorageWrite, StoredDecision, TransactionPoint, TransactionRecord,
+    TxSizeProfile, UsageRecord, WindowSpec, TX_SIZE_EWMA_ALPHA,
+};
 
 /// PostgreSQL implementation of the Storage trait.
 pub struct PostgresStorage {
     pool: PgPool,
 }
 
+/// Notification channel for rule kill-switch changes.
+const RULE_SWITCH_CHANNEL: &str = "riskr_rule_switches";
+
+/// Notification channel for internal blocklist changes.
+const BLOCKLIST_CHANNEL: &str = "riskr_blocklist";
+
+/// Tables partitioned by month of `created_at` when partitioning is enabled.
+pub const PARTITIONED_TABLES: [&str; 2] = ["transactions", "decisions"];
+
 impl PostgresStorage {
     /// Create a new PostgresStorage instance with a connection pool.
     pub async fn connect(
@@ -38,14 +59,264 @@ impl PostgresStorage {
         Ok(())
     }
 
+    /// Whether `table` is partitioned.
+    pub async fn is_partitioned(&self, table: &str) -> anyhow::Result<bool> {
+        let partitioned: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM pg_partitioned_table WHERE partrelid = $1::regclass)",
+        )
+        .bind(table)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(partitioned)
+    }
+
+    /// Partition the transactions and decisions tables by month.
+    ///
+    /// Empty tables, as on a new install, are always converted. Tables with
+    /// rows are only converted when `convert_existing` is set, since the
+    /// copy locks the table for its duration; otherwise they are left as
+    /// they are and returned. Tables already partitioned are skipped.
+    pub async fn partition_tables(
+        &self,
+        convert_existing: bool,
+        months_ahead: u32,
+    ) -> anyhow::Result<Vec<&'static str>> {
+        let mut skipped = Vec::new();
+        for table in PARTITIONED_TABLES {
+            if self.is_partitioned(table).await? {
+                continue;
+            }
+            if !convert_existing {
+                let has_rows: bool =
+                    sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {})", table))
+                        .fetch_one(&self.pool)
+                        .await?;
+                if has_rows {
+                    skipped.push(table);
+                    continue;
+                }
+            }
+
+            sqlx::query("SELECT riskr_partition_table($1, $2)")
+                .bind(table)
+                .bind(months_ahead as i32)
+                .execute(&self.pool)
+                .await?;
+            info!(table = table, "Partitioned table by month");
+        }
+        Ok(skipped)
+    }
+
+    /// Create monthly partitions through `months_ahead` months from now
+    /// for every partitioned table, returning how many were created.
+    pub async fn ensure_partitions(&self, months_ahead: u32) -> anyhow::Result<u32> {
+        let mut created = 0;
+        for table in PARTITIONED_TABLES {
+            if !self.is_partitioned(table).await? {
+                continue;
+            }
+            let count: i32 = sqlx::query_scalar(
+                "SELECT riskr_ensure_month_partitions($1, (now() AT TIME ZONE 'UTC')::date, $2)",
+            )
+            .bind(table)
+            .bind(months_ahead as i32)
+            .fetch_one(&self.pool)
+            .await?;
+            created += count as u32;
+        }
+        Ok(created)
+    }
+
+    /// Redact decision evidence past its class's retention, returning how
+    /// many decisions were changed. Already redacted items are left alone.
+    pub async fn expire_evidence(&self, retention: &EvidenceRetention) -> anyhow::Result<u64> {
+        let mut changed = 0;
+        for (class, period) in retention.retentions() {
+            let filter = retention.keys(class);
+            let result = sqlx::query(
+                r#"
+                UPDATE decisions d
+                SET evidence = (
+                    SELECT jsonb_agg(
+                        CASE WHEN (e->>'key' = ANY($1)) <> $2 AND e->>'value' <> $4
+                            THEN (e - 'details') || jsonb_build_object('value', $4::text)
+                            ELSE e
+                        END
+                        ORDER BY ord
+                    )
+                    FROM jsonb_array_elements(d.evidence) WITH ORDINALITY AS x(e, ord)
+                )
+                WHERE d.created_at < $3
+                  AND jsonb_typeof(d.evidence) = 'array'
+                  AND EXISTS (
+                      SELECT 1 FROM jsonb_array_elements(d.evidence) e
+                      WHERE (e->>'key' = ANY($1)) <> $2 AND e->>'value' <> $4
+                  )
+                "#,
+            )
+            .bind(&filter.keys)
+            .bind(filter.negated)
+            .bind(Utc::now() - period)
+            .bind(REDACTED)
+            .execute(&self.pool)
+            .await?;
+            changed += result.rows_affected();
+        }
+        Ok(changed)
+    }
+
+    /// Delete expired request nonces, returning how many were removed.
+    pub async fn prune_nonces(&self) -> anyhow::Result<u64> {
+        let result = sqlx::query("DELETE FROM request_nonces WHERE expires_at <= now()")
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Signal every rule kill-switch change made by any replica.
+    ///
+    /// A signal is also sent whenever the listening connection is
+    /// re-established, since changes may have been missed while it was down.
+    pub async fn rule_switch_changes(&self) -> anyhow::Result<mpsc::Receiver<()>> {
+        self.changes(RULE_SWITCH_CHANNEL).await
+    }
+
+    /// Signal every internal blocklist change made by any replica, as
+    /// [`rule_switch_changes`](Self::rule_switch_changes) does.
+    pub async fn blocklist_changes(&self) -> anyhow::Result<mpsc::Receiver<()>> {
+        self.changes(BLOCKLIST_CHANNEL).await
+    }
+
+    /// Signal each notification on `channel`, and each reconnect.
+    async fn changes(&self, channel: &'static str) -> anyhow::Result<mpsc::Receiver<()>> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(channel).await?;
+
+        let (tx, rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            loop {
+                // Ok(None) means the connection dropped and will reconnect
+                if let Err(e) = listener.try_recv().await {
+                    warn!(channel = channel, error = %e, "Change listener failed");
+                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                }
+                // A full channel already has a reload pending
+                if let Err(TrySendError::Closed(_)) = tx.try_send(()) {
+                    break;
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+
+    /// Open the pool's minimum connections up front so the first requests
+    /// don't pay connect latency.
+    pub async fn warm_up(&self) -> anyhow::Result<()> {
+        let target = self.pool.options().get_min_connections().max(1);
+
+        let mut conns = Vec::with_capacity(target as usize);
+        for _ in 0..target {
+            let mut conn = self.pool.acquire().await?;
+            sqlx::query("SELECT 1").execute(&mut *conn).await?;
+            conns.push(conn);
+        }
+
+        // Connections return to the pool when dropped
+        Ok(())
+    }
+
     /// Get a reference to the connection pool.
     pub fn pool(&self) -> &PgPool {
         &self.pool
     }
+
+    /// Window aggregates in one round trip, over windows ending at `at`
+    /// or the database's now.
+    async fn window_aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Decimal>> {
+        let Some(longest) = specs.iter().map(WindowSpec::window).max() else {
+            return Ok(Vec::new());
+        };
+
+        // One filtered aggregate per spec over the longest window; $1 is
+        // the subject, $2 the end of the windows and $3 the longest window
+        let end = "COALESCE($2::timestamptz, now())";
+        let mut columns = Vec::with_capacity(specs.len());
+        let mut param = 3;
+        for spec in specs {
+            param += 1;
+            let since = format!("created_at > {end} - (${param} || ' seconds')::interval");
+            columns.push(match spec {
+                WindowSpec::Volume(_) => {
+                    format!("COALESCE(SUM(usd_value) FILTER (WHERE {since}), 0)")
+                }
+                WindowSpec::SmallCount { .. } => {
+                    param += 1;
+                    format!("(COUNT(*) FILTER (WHERE {since} AND usd_value < ${param}))::numeric")
+                }
+                WindowSpec::CounterpartyCount { .. } => {
+                    param += 1;
+                    format!(
+                        "(COUNT(*) FILTER (WHERE {since} AND counterparty_geo = ${param}))::numeric"
+                    )
+                }
+                WindowSpec::DistinctDestinations(_) => {
+                    format!("(COUNT(DISTINCT dest_address) FILTER (WHERE {since}))::numeric")
+                }
+                WindowSpec::AccountVolume { .. } => {
+                    param += 1;
+                    format!(
+                        "COALESCE(SUM(usd_value) FILTER (WHERE {since} AND account_id = ${param}), 0)"
+                    )
+                }
+                WindowSpec::Amount { .. } => {
+                    param += 1;
+                    format!(
+                        "COALESCE(SUM(amount) FILTER (WHERE {since} AND UPPER(asset) = UPPER(${param})), 0)"
+                    )
+                }
+            });
+        }
+        let sql = format!(
+            r#"
+            SELECT {}
+            FROM transactions
+            WHERE subject_id = $1
+              AND created_at > {end} - ($3 || ' seconds')::interval
+              AND created_at <= {end}
+            "#,
+            columns.join(", ")
+        );
+
+        let mut query = sqlx::query(&sql)
+            .bind(subject_id)
+            .bind(at)
+            .bind(longest.num_seconds().to_string());
+        for spec in specs {
+            query = query.bind(spec.window().num_seconds().to_string());
+            match spec {
+                WindowSpec::SmallCount { threshold, .. } => query = query.bind(*threshold),
+                WindowSpec::CounterpartyCount { country, .. } => query = query.bind(country),
+                WindowSpec::AccountVolume { account_id, .. } => query = query.bind(account_id),
+                WindowSpec::Amount { asset, .. } => query = query.bind(asset),
+                WindowSpec::Volume(_) | WindowSpec::DistinctDestinations(_) => {}
+            }
+        }
+        let row = query.fetch_one(&self.pool).await?;
+
+        (0..specs.len())
+            .map(|i| Ok(row.try_get::<Decimal, _>(i)?))
+            .collect()
+    }
 }
 
 #[async_trait]
-impl Storage for PostgresStorage {
+impl StorageRead for PostgresStorage {
     async fn get_subject_by_user_id(
         &self,
         user_id: &str,
@@ -100,64 +371,41 @@ impl Storage for PostgresStorage {
         Ok(Some((subject_id, subject)))
     }
 
-    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid> {
-        // Upsert the subject record
-        let subject_id: Uuid = sqlx::query_scalar(
+    async fn get_subject_addresses(&self, subject_id: Uuid) -> anyhow::Result<Vec<SubjectAddress>> {
+        let rows = sqlx::query(
             r#"
-            INSERT INTO subjects (user_id, account_id, kyc_level, geo_iso, updated_at)
-            VALUES ($1, $2, $3, $4, now())
-            ON CONFLICT (user_id)
-            DO UPDATE SET
-                account_id = EXCLUDED.account_id,
-                kyc_level = EXCLUDED.kyc_level,
-                geo_iso = EXCLUDED.geo_iso,
-                updated_at = now()
-            RETURNING id
+            SELECT address, verified, label, created_at
+            FROM subject_addresses
+            WHERE subject_id = $1
+            ORDER BY created_at, address
             "#,
         )
-        .bind(subject.user_id.as_str())
-        .bind(&subject.account_id.0)
-        .bind(subject.kyc_tier.as_str())
-        .bind(subject.geo_iso.as_str())
-        .fetch_one(&self.pool)
+        .bind(subject_id)
+        .fetch_all(&self.pool)
         .await?;
 
-        // Upsert addresses
-        for address in &subject.addresses {
-            sqlx::query(
-                r#"
-                INSERT INTO subject_addresses (subject_id, address)
-                VALUES ($1, $2)
-                ON CONFLICT (subject_id, address) DO NOTHING
-                "#,
-            )
-            .bind(subject_id)
-            .bind(address.as_str())
-            .execute(&self.pool)
-            .await?;
-        }
-
-        Ok(subject_id)
+        Ok(rows.iter().map(subject_address_from_row).collect())
     }
 
-    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid> {
-        let tx_id: Uuid = sqlx::query_scalar(
+    async fn get_kyc_overrides(&self) -> anyhow::Result<Vec<KycOverride>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
             r#"
-            INSERT INTO transactions (subject_id, tx_type, asset, amount, usd_value, dest_address)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id
+            SELECT user_id, kyc_level
+            FROM subjects
+            WHERE kyc_verified_at IS NOT NULL
+            ORDER BY user_id
             "#,
         )
-        .bind(tx.subject_id)
-        .bind(&tx.tx_type)
-        .bind(&tx.asset)
-        .bind(tx.amount)
-        .bind(tx.usd_value)
-        .bind(&tx.dest_address)
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(tx_id)
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, kyc_level)| KycOverride {
+                user_id,
+                kyc_tier: KycTier::from_str(&kyc_level).unwrap_or_default(),
+            })
+            .collect())
     }
 
     async fn get_rolling_volume(
@@ -183,6 +431,32 @@ impl Storage for PostgresStorage {
         Ok(volume.unwrap_or(Decimal::ZERO))
     }
 
+    async fn get_rolling_amount(
+        &self,
+        subject_id: Uuid,
+        asset: &str,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        let window_secs = window.num_seconds();
+
+        let amount: Option<Decimal> = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)
+            FROM transactions
+            WHERE subject_id = $1
+              AND UPPER(asset) = UPPER($2)
+              AND created_at > now() - ($3 || ' seconds')::interval
+            "#,
+        )
+        .bind(subject_id)
+        .bind(asset)
+        .bind(window_secs.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(amount.unwrap_or(Decimal::ZERO))
+    }
+
     async fn get_small_tx_count(
         &self,
         subject_id: Uuid,
@@ -209,6 +483,162 @@ impl Storage for PostgresStorage {
         Ok(count as u32)
     }
 
+    async fn get_counterparty_tx_count(
+        &self,
+        subject_id: Uuid,
+        country: &str,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        let window_secs = window.num_seconds();
+
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM transactions
+            WHERE subject_id = $1
+              AND created_at > now() - ($2 || ' seconds')::interval
+              AND counterparty_geo = $3
+            "#,
+        )
+        .bind(subject_id)
+        .bind(window_secs.to_string())
+        .bind(country)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count as u32)
+    }
+
+    async fn get_asset_flows(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<AssetFlow>> {
+        let window_secs = window.num_seconds();
+
+        let rows: Vec<(String, Decimal, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT UPPER(asset),
+                   COALESCE(SUM(usd_value) FILTER (WHERE tx_type = 'Inbound'), 0),
+                   COALESCE(SUM(usd_value) FILTER (WHERE tx_type <> 'Inbound'), 0)
+            FROM transactions
+            WHERE subject_id = $1
+              AND created_at > now() - ($2 || ' seconds')::interval
+            GROUP BY UPPER(asset)
+            ORDER BY UPPER(asset)
+            "#,
+        )
+        .bind(subject_id)
+        .bind(window_secs.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(asset, inbound_usd, outbound_usd)| AssetFlow {
+                asset,
+                inbound_usd,
+                outbound_usd,
+            })
+            .collect())
+    }
+
+    async fn get_recent_transactions(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<TransactionPoint>> {
+        let window_secs = window.num_seconds();
+
+        let rows: Vec<(chrono::DateTime<chrono::Utc>, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT created_at, usd_value
+            FROM transactions
+            WHERE subject_id = $1
+              AND created_at > now() - ($2 || ' seconds')::interval
+            ORDER BY created_at
+            "#,
+        )
+        .bind(subject_id)
+        .bind(window_secs.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(at, usd_value)| TransactionPoint { at, usd_value })
+            .collect())
+    }
+
+    async fn get_hourly_activity(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<[u32; 24]> {
+        let window_secs = window.num_seconds();
+
+        let rows: Vec<(i32, i64)> = sqlx::query_as(
+            r#"
+            SELECT EXTRACT(HOUR FROM created_at AT TIME ZONE 'UTC')::int AS hour,
+                   COUNT(*)
+            FROM transactions
+            WHERE subject_id = $1
+              AND created_at > now() - ($2 || ' seconds')::interval
+            GROUP BY hour
+            "#,
+        )
+        .bind(subject_id)
+        .bind(window_secs.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut hours = [0u32; 24];
+        for (hour, count) in rows {
+            if let Some(slot) = hours.get_mut(hour as usize) {
+                *slot = count as u32;
+            }
+        }
+
+        Ok(hours)
+    }
+
+    async fn get_window_aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.window_aggregates(subject_id, specs, None).await
+    }
+
+    async fn get_window_aggregates_at(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.window_aggregates(subject_id, specs, Some(at)).await
+    }
+
+    async fn get_tx_size_profile(&self, subject_id: Uuid) -> anyhow::Result<Option<TxSizeProfile>> {
+        let row: Option<(Option<Decimal>, i32)> = sqlx::query_as(
+            r#"
+            SELECT typical_tx_usd, tx_size_samples
+            FROM subjects
+            WHERE id = $1
+            "#,
+        )
+        .bind(subject_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|(typical_usd, samples)| {
+            typical_usd.map(|typical_usd| TxSizeProfile {
+                typical_usd,
+                samples: samples as u32,
+            })
+        }))
+    }
+
     async fn get_all_sanctions(&self) -> anyhow::Result<Vec<String>> {
         let addresses = sqlx::query_scalar(
             r#"
@@ -261,73 +691,1161 @@ impl Storage for PostgresStorage {
         Ok(Some(policy))
     }
 
-    async fn set_active_policy(&self, policy: &Policy) -> anyhow::Result<()> {
-        // Start a transaction
-        let mut tx = self.pool.begin().await?;
-
-        // Deactivate all existing policies
-        sqlx::query(
+    async fn get_policy(&self, version: &str) -> anyhow::Result<Option<Policy>> {
+        let row = sqlx::query(
             r#"
-            UPDATE policies
-            SET active = false
-            WHERE active = true
+            SELECT config
+            FROM policies
+            WHERE version = $1
             "#,
         )
-        .execute(&mut *tx)
+        .bind(version)
+        .fetch_optional(&self.pool)
         .await?;
 
-        // Insert or update the new policy
-        let config = serde_json::to_value(policy)?;
-
-        sqlx::query(
-            r#"
-            INSERT INTO policies (version, config, active)
-            VALUES ($1, $2, true)
-            ON CONFLICT (version)
-            DO UPDATE SET
-                config = EXCLUDED.config,
-                active = true
-            "#,
-        )
-        .bind(&policy.version)
-        .bind(config)
-        .execute(&mut *tx)
-        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
 
-        // Commit the transaction
-        tx.commit().await?;
+        let config: serde_json::Value = row.get("config");
+        let policy: Policy = serde_json::from_value(config)?;
 
-        Ok(())
+        Ok(Some(policy))
     }
 
-    async fn record_decision(&self, decision: &DecisionRecord) -> anyhow::Result<Uuid> {
-        let evidence = serde_json::to_value(&decision.evidence)?;
-
-        let decision_id: Uuid = sqlx::query_scalar(
-            r#"
-            INSERT INTO decisions (
-                subject_id,
-                request,
-                decision,
-                decision_code,
-                policy_version,
-                evidence,
-                latency_ms
-            )
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+    async fn count_recent_decisions(
+        &self,
+        subject_id: Uuid,
+        min_decision: Decision,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        let window_secs = window.num_seconds();
+
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM decisions
+            WHERE subject_id = $1
+              AND created_at > now() - ($2 || ' seconds')::interval
+              AND decision >= $3
+            "#,
+        )
+        .bind(subject_id)
+        .bind(window_secs.to_string())
+        .bind(min_decision.severity() as i16)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count as u32)
+    }
+
+    async fn get_decisions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        let (after_at, after_id) = after.unzip();
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, created_at, subject_id, request_id, request, decision,
+                   decision_code, policy_version, evidence, latency_ms
+            FROM decisions
+            WHERE created_at >= $1 AND created_at < $2
+              AND ($3::timestamptz IS NULL OR (created_at, id) > ($3, $4))
+            ORDER BY created_at, id
+            LIMIT $5
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(after_at)
+        .bind(after_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(stored_decision_from_row).collect()
+    }
+
+    async fn get_decision_by_request_id(
+        &self,
+        request_id: &str,
+    ) -> anyhow::Result<Option<StoredDecision>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, created_at, subject_id, request_id, request, decision,
+                   decision_code, policy_version, evidence, latency_ms
+            FROM decisions
+            WHERE request_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(request_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(stored_decision_from_row).transpose()
+    }
+
+    async fn get_subject_decisions(
+        &self,
+        subject_id: Uuid,
+        at: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, created_at, subject_id, request_id, request, decision,
+                   decision_code, policy_version, evidence, latency_ms
+            FROM decisions
+            WHERE subject_id = $1 AND created_at <= $2
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(subject_id)
+        .bind(at)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(stored_decision_from_row).collect()
+    }
+
+    async fn get_recent_decisions(&self, limit: u32) -> anyhow::Result<Vec<StoredDecision>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, created_at, subject_id, request_id, request, decision,
+                   decision_code, policy_version, evidence, latency_ms
+            FROM decisions
+            ORDER BY created_at DESC, id DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(stored_decision_from_row).collect()
+    }
+
+    async fn pending_outbox(&self, limit: u32) -> anyhow::Result<Vec<OutboxEvent>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, kind, payload, attempts
+            FROM outbox
+            WHERE delivered_at IS NULL AND dead_lettered_at IS NULL
+            ORDER BY created_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(outbox_event_from_row).collect())
+    }
+
+    async fn get_pending_deposit(&self, event_id: &str) -> anyhow::Result<Option<PendingDeposit>> {
+        let deposit: Option<serde_json::Value> =
+            sqlx::query_scalar("SELECT deposit FROM pending_deposits WHERE event_id = $1")
+                .bind(event_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(deposit.map(serde_json::from_value).transpose()?)
+    }
+
+    async fn get_case(&self, case_id: Uuid) -> anyhow::Result<Option<Case>> {
+        let row = sqlx::query(
+            r#"
+            SELECT c.id, c.subject_id, c.status, c.opened_by_decision, c.note,
+                   c.created_at, c.updated_at,
+                   ARRAY(
+                       SELECT d.id FROM decisions d
+                       WHERE d.case_id = c.id
+                       ORDER BY d.created_at, d.id
+                   ) AS decision_ids
+            FROM cases c
+            WHERE c.id = $1
+            "#,
+        )
+        .bind(case_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(case_from_row).transpose()
+    }
+
+    async fn list_cases(
+        &self,
+        status: Option<CaseStatus>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Case>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT c.id, c.subject_id, c.status, c.opened_by_decision, c.note,
+                   c.created_at, c.updated_at,
+                   ARRAY(
+                       SELECT d.id FROM decisions d
+                       WHERE d.case_id = c.id
+                       ORDER BY d.created_at, d.id
+                   ) AS decision_ids
+            FROM cases c
+            WHERE $1::text IS NULL OR c.status = $1
+            ORDER BY c.updated_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(status.map(|s| s.as_str()))
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(case_from_row).collect()
+    }
+
+    async fn get_annotations(
+        &self,
+        subject_id: Uuid,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Annotation>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, subject_id, note, risk_rating, tags, actor, created_at
+            FROM subject_annotations
+            WHERE subject_id = $1
+            ORDER BY created_at DESC, id
+            LIMIT $2
+            "#,
+        )
+        .bind(subject_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(annotation_from_row).collect()
+    }
+
+    async fn get_disabled_rules(&self) -> anyhow::Result<Vec<RuleSwitch>> {
+        let rows: Vec<(String, DateTime<Utc>, String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT rule_id, disabled_at, actor, reason
+            FROM rule_switches
+            ORDER BY rule_id
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(rule_id, disabled_at, actor, reason)| RuleSwitch {
+                rule_id,
+                disabled_at,
+                actor,
+                reason,
+            })
+            .collect())
+    }
+
+    async fn get_blocklist(&self) -> anyhow::Result<Vec<BlocklistEntry>> {
+        let rows: Vec<(String, Option<String>, DateTime<Utc>, String)> = sqlx::query_as(
+            r#"
+            SELECT address, reason, added_at, actor
+            FROM internal_blocklist
+            ORDER BY address
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(address, reason, added_at, actor)| BlocklistEntry {
+                address,
+                reason,
+                added_at,
+                actor,
+            })
+            .collect())
+    }
+
+    async fn get_daily_usage(&self, tenant: &str) -> anyhow::Result<u64> {
+        let count: Option<i64> = sqlx::query_scalar(
+            r#"
+            SELECT decisions
+            FROM tenant_usage
+            WHERE tenant = $1 AND day = (now() AT TIME ZONE 'UTC')::date
+            "#,
+        )
+        .bind(tenant)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(count.unwrap_or(0) as u64)
+    }
+
+    async fn get_usage(&self, from: NaiveDate, to: NaiveDate) -> anyhow::Result<Vec<UsageRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT tenant, day, decisions
+            FROM tenant_usage
+            WHERE day BETWEEN $1 AND $2
+            ORDER BY day, tenant
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let hit_rows = sqlx::query(
+            r#"
+            SELECT tenant, day, rule_id, hits
+            FROM tenant_rule_hits
+            WHERE day BETWEEN $1 AND $2
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut rule_hits: HashMap<(String, NaiveDate), BTreeMap<String, u64>> = HashMap::new();
+        for row in hit_rows {
+            let hits: i64 = row.get("hits");
+            rule_hits
+                .entry((row.get("tenant"), row.get("day")))
+                .or_default()
+                .insert(row.get("rule_id"), hits as u64);
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let tenant: String = row.get("tenant");
+                let day: NaiveDate = row.get("day");
+                let decisions: i64 = row.get("decisions");
+                UsageRecord {
+                    rule_hits: rule_hits.remove(&(tenant.clone(), day)).unwrap_or_default(),
+                    tenant,
+                    day,
+                    decisions: decisions as u64,
+                }
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl StorageWrite for PostgresStorage {
+    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid> {
+        // Upsert the subject record
+        let subject_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO subjects (user_id, account_id, kyc_level, geo_iso, updated_at)
+            VALUES ($1, $2, $3, $4, now())
+            ON CONFLICT (user_id)
+            DO UPDATE SET
+                account_id = EXCLUDED.account_id,
+                kyc_level = CASE
+                    WHEN subjects.kyc_verified_at IS NULL THEN EXCLUDED.kyc_level
+                    ELSE subjects.kyc_level
+                END,
+                geo_iso = EXCLUDED.geo_iso,
+                updated_at = now()
             RETURNING id
             "#,
         )
-        .bind(decision.subject_id)
-        .bind(&decision.request)
-        .bind(format!("{:?}", decision.decision))
-        .bind(&decision.decision_code)
-        .bind(&decision.policy_version)
-        .bind(evidence)
-        .bind(decision.latency_ms as i32)
+        .bind(subject.user_id.as_str())
+        .bind(&subject.account_id.0)
+        .bind(subject.kyc_tier.as_str())
+        .bind(subject.geo_iso.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+
+        // Upsert addresses
+        for address in &subject.addresses {
+            sqlx::query(
+                r#"
+                INSERT INTO subject_addresses (subject_id, address)
+                VALUES ($1, $2)
+                ON CONFLICT (subject_id, address) DO NOTHING
+                "#,
+            )
+            .bind(subject_id)
+            .bind(address.as_str())
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(subject_id)
+    }
+
+    async fn set_kyc_tier(&self, user_id: &str, tier: &KycTier) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE subjects
+            SET kyc_level = $2,
+                kyc_verified_at = now(),
+                updated_at = now()
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(tier.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn save_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+        verified: Option<bool>,
+        label: Option<&str>,
+    ) -> anyhow::Result<SubjectAddress> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO subject_addresses (subject_id, address, verified, label)
+            VALUES ($1, $2, COALESCE($3, false), NULLIF($4::text, ''))
+            ON CONFLICT (subject_id, address)
+            DO UPDATE SET
+                verified = COALESCE($3, subject_addresses.verified),
+                label = CASE
+                    WHEN $4::text IS NULL THEN subject_addresses.label
+                    ELSE NULLIF($4::text, '')
+                END
+            RETURNING address, verified, label, created_at
+            "#,
+        )
+        .bind(subject_id)
+        .bind(Address::new(address).as_str())
+        .bind(verified)
+        .bind(label)
         .fetch_one(&self.pool)
         .await?;
 
+        Ok(subject_address_from_row(&row))
+    }
+
+    async fn remove_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+    ) -> anyhow::Result<bool> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM subject_addresses
+            WHERE subject_id = $1 AND address = $2
+            "#,
+        )
+        .bind(subject_id)
+        .bind(Address::new(address).as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid> {
+        let mut db_tx = self.pool.begin().await?;
+        let tx_id = insert_transaction(&mut db_tx, tx).await?;
+        db_tx.commit().await?;
+        Ok(tx_id)
+    }
+
+    async fn set_active_policy(&self, policy: &Policy) -> anyhow::Result<()> {
+        // Start a transaction
+        let mut tx = self.pool.begin().await?;
+
+        // Deactivate all existing policies
+        sqlx::query(
+            r#"
+            UPDATE policies
+            SET active = false
+            WHERE active = true
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // Insert or update the new policy
+        let config = serde_json::to_value(policy)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO policies (version, config, active)
+            VALUES ($1, $2, true)
+            ON CONFLICT (version)
+            DO UPDATE SET
+                config = EXCLUDED.config,
+                active = true
+            "#,
+        )
+        .bind(&policy.version)
+        .bind(config)
+        .execute(&mut *tx)
+        .await?;
+
+        // Commit the transaction
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn record_decision(&self, decision: &DecisionRecord) -> anyhow::Result<Uuid> {
+        let mut conn = self.pool.acquire().await?;
+        Ok(insert_decision(&mut conn, decision).await?.0)
+    }
+
+    async fn record_outcome(
+        &self,
+        tx: &TransactionRecord,
+        decision: &DecisionRecord,
+    ) -> anyhow::Result<Uuid> {
+        let mut db_tx = self.pool.begin().await?;
+
+        insert_transaction(&mut db_tx, tx).await?;
+        let (decision_id, created_at) = insert_decision(&mut db_tx, decision).await?;
+        link_case(&mut db_tx, decision, decision_id, created_at, None).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO outbox (kind, payload)
+            VALUES ('decision', $1)
+            "#,
+        )
+        .bind(decision.outbox_payload(decision_id))
+        .execute(&mut *db_tx)
+        .await?;
+
+        db_tx.commit().await?;
         Ok(decision_id)
     }
+
+    async fn record_decision_outcome(
+        &self,
+        decision: &DecisionRecord,
+        note: Option<&str>,
+    ) -> anyhow::Result<(Uuid, bool)> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let (decision_id, created_at) = insert_decision(&mut db_tx, decision).await?;
+        let opened = link_case(&mut db_tx, decision, decision_id, created_at, note).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO outbox (kind, payload)
+            VALUES ('decision', $1)
+            "#,
+        )
+        .bind(decision.outbox_payload(decision_id))
+        .execute(&mut *db_tx)
+        .await?;
+
+        db_tx.commit().await?;
+        Ok((decision_id, opened))
+    }
+
+    async fn claim_outbox(&self, limit: u32, lease: Duration) -> anyhow::Result<Vec<OutboxEvent>> {
+        // SKIP LOCKED keeps concurrent claims from waiting on, or taking,
+        // each other's rows; the lease keeps them apart after commit
+        let rows = sqlx::query(
+            r#"
+            UPDATE outbox
+            SET claimed_until = now() + ($2 || ' seconds')::interval
+            WHERE id IN (
+                SELECT id FROM outbox
+                WHERE delivered_at IS NULL AND dead_lettered_at IS NULL
+                  AND (claimed_until IS NULL OR claimed_until <= now())
+                ORDER BY created_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, kind, payload, attempts, created_at
+            "#,
+        )
+        .bind(limit as i64)
+        .bind(lease.num_seconds().to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut rows: Vec<(DateTime<Utc>, OutboxEvent)> = rows
+            .iter()
+            .map(|row| (row.get("created_at"), outbox_event_from_row(row)))
+            .collect();
+        rows.sort_by_key(|(created_at, _)| *created_at);
+        Ok(rows.into_iter().map(|(_, event)| event).collect())
+    }
+
+    async fn mark_outbox_delivered(&self, id: Uuid) -> anyhow::Result<()> {
+        sqlx::query("UPDATE outbox SET delivered_at = now() WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn mark_outbox_failed(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        sqlx::query("UPDATE outbox SET attempts = attempts + 1, last_error = $2 WHERE id = $1")
+            .bind(id)
+            .bind(error)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn mark_outbox_dead(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE outbox
+            SET attempts = attempts + 1, last_error = $2, dead_lettered_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn save_pending_deposit(&self, deposit: &PendingDeposit) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO pending_deposits (event_id, subject_id, deposit)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (event_id) DO UPDATE
+            SET deposit = EXCLUDED.deposit, updated_at = now()
+            "#,
+        )
+        .bind(&deposit.event.event_id.0)
+        .bind(deposit.subject_id)
+        .bind(serde_json::to_value(deposit)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn resolve_pending_deposit(
+        &self,
+        event_id: &str,
+        decision: &DecisionRecord,
+        event: &DecisionEvent,
+    ) -> anyhow::Result<Option<Uuid>> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let deleted = sqlx::query("DELETE FROM pending_deposits WHERE event_id = $1")
+            .bind(event_id)
+            .execute(&mut *db_tx)
+            .await?;
+        if deleted.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let (decision_id, created_at) = insert_decision(&mut db_tx, decision).await?;
+        link_case(&mut db_tx, decision, decision_id, created_at, None).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO outbox (kind, payload)
+            VALUES ('decision_event', $1)
+            "#,
+        )
+        .bind(serde_json::to_value(event)?)
+        .execute(&mut *db_tx)
+        .await?;
+
+        db_tx.commit().await?;
+        Ok(Some(decision_id))
+    }
+
+    async fn create_case(
+        &self,
+        subject_id: Uuid,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        let case_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO cases (subject_id, note)
+            VALUES ($1, $2)
+            ON CONFLICT (subject_id) WHERE status IN ('open', 'investigating') DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(subject_id)
+        .bind(note)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match case_id {
+            Some(id) => self.get_case(id).await,
+            None => Ok(None),
+        }
+    }
+
+    async fn update_case_status(
+        &self,
+        case_id: Uuid,
+        from: CaseStatus,
+        to: CaseStatus,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        let updated = sqlx::query(
+            r#"
+            UPDATE cases
+            SET status = $3, note = COALESCE($4, note), updated_at = now()
+            WHERE id = $1 AND status = $2
+            "#,
+        )
+        .bind(case_id)
+        .bind(from.as_str())
+        .bind(to.as_str())
+        .bind(note)
+        .execute(&self.pool)
+        .await?;
+
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get_case(case_id).await
+    }
+
+    async fn add_annotation(&self, annotation: &Annotation) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO subject_annotations
+                (id, subject_id, note, risk_rating, tags, actor, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(annotation.id)
+        .bind(annotation.subject_id)
+        .bind(&annotation.note)
+        .bind(annotation.risk_rating.map(|r| r.as_str()))
+        .bind(&annotation.tags)
+        .bind(&annotation.actor)
+        .bind(annotation.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn disable_rule(&self, switch: &RuleSwitch) -> anyhow::Result<()> {
+        let mut db_tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO rule_switches (rule_id, disabled_at, actor, reason)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (rule_id) DO UPDATE
+            SET disabled_at = EXCLUDED.disabled_at,
+                actor = EXCLUDED.actor,
+                reason = EXCLUDED.reason
+            "#,
+        )
+        .bind(&switch.rule_id)
+        .bind(switch.disabled_at)
+        .bind(&switch.actor)
+        .bind(&switch.reason)
+        .execute(&mut *db_tx)
+        .await?;
+
+        log_rule_switch(
+            &mut db_tx,
+            &switch.rule_id,
+            true,
+            &switch.actor,
+            switch.reason.as_deref(),
+        )
+        .await?;
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    async fn enable_rule(&self, rule_id: &str, actor: &str) -> anyhow::Result<()> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let deleted = sqlx::query("DELETE FROM rule_switches WHERE rule_id = $1")
+            .bind(rule_id)
+            .execute(&mut *db_tx)
+            .await?;
+        if deleted.rows_affected() > 0 {
+            log_rule_switch(&mut db_tx, rule_id, false, actor, None).await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    async fn save_blocklist(
+        &self,
+        entries: &[BlocklistEntry],
+        replace: bool,
+    ) -> anyhow::Result<()> {
+        let addresses: Vec<&str> = entries.iter().map(|e| e.address.as_str()).collect();
+        let reasons: Vec<Option<&str>> = entries.iter().map(|e| e.reason.as_deref()).collect();
+        let added_at: Vec<DateTime<Utc>> = entries.iter().map(|e| e.added_at).collect();
+        let actors: Vec<&str> = entries.iter().map(|e| e.actor.as_str()).collect();
+
+        let mut db_tx = self.pool.begin().await?;
+
+        if replace {
+            sqlx::query("DELETE FROM internal_blocklist WHERE address <> ALL($1)")
+                .bind(&addresses)
+                .execute(&mut *db_tx)
+                .await?;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO internal_blocklist (address, reason, added_at, actor)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::timestamptz[], $4::text[])
+            ON CONFLICT (address) DO UPDATE
+            SET reason = EXCLUDED.reason,
+                added_at = EXCLUDED.added_at,
+                actor = EXCLUDED.actor
+            "#,
+        )
+        .bind(&addresses)
+        .bind(&reasons)
+        .bind(&added_at)
+        .bind(&actors)
+        .execute(&mut *db_tx)
+        .await?;
+
+        sqlx::query("SELECT pg_notify($1, '')")
+            .bind(BLOCKLIST_CHANNEL)
+            .execute(&mut *db_tx)
+            .await?;
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    async fn record_usage(&self, tenant: &str, rules_hit: &[String]) -> anyhow::Result<()> {
+        let mut db_tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_usage (tenant, day, decisions)
+            VALUES ($1, (now() AT TIME ZONE 'UTC')::date, 1)
+            ON CONFLICT (tenant, day) DO UPDATE SET decisions = tenant_usage.decisions + 1
+            "#,
+        )
+        .bind(tenant)
+        .execute(&mut *db_tx)
+        .await?;
+
+        if !rules_hit.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO tenant_rule_hits (tenant, day, rule_id, hits)
+                SELECT $1, (now() AT TIME ZONE 'UTC')::date, rule_id, 1
+                FROM UNNEST($2::text[]) AS rule_id
+                ON CONFLICT (tenant, day, rule_id) DO UPDATE SET hits = tenant_rule_hits.hits + 1
+                "#,
+            )
+            .bind(tenant)
+            .bind(rules_hit)
+            .execute(&mut *db_tx)
+            .await?;
+        }
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    async fn claim_nonce(&self, nonce: &str, expires_at: DateTime<Utc>) -> anyhow::Result<bool> {
+        // An expired claim is taken over rather than rejected
+        let claimed = sqlx::query(
+            r#"
+            INSERT INTO request_nonces (nonce, expires_at)
+            VALUES ($1, $2)
+            ON CONFLICT (nonce) DO UPDATE SET expires_at = EXCLUDED.expires_at
+            WHERE request_nonces.expires_at <= now()
+            RETURNING nonce
+            "#,
+        )
+        .bind(nonce)
+        .bind(expires_at)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(claimed.is_some())
+    }
+}
+
+/// Append a kill-switch toggle to the audit log and notify other replicas
+/// when the transaction commits.
+async fn log_rule_switch(
+    conn: &mut PgConnection,
+    rule_id: &str,
+    disabled: bool,
+    actor: &str,
+    reason: Option<&str>,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO rule_switch_log (rule_id, disabled, actor, reason)
+        VALUES ($1, $2, $3, $4)
+        "#,
+    )
+    .bind(rule_id)
+    .bind(disabled)
+    .bind(actor)
+    .bind(reason)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(RULE_SWITCH_CHANNEL)
+        .bind(rule_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// Link a decision to its subject's active case, opening one with `note`
+/// for a review, on the given transaction. Returns whether a case was
+/// opened.
+async fn link_case(
+    conn: &mut PgConnection,
+    decision: &DecisionRecord,
+    decision_id: Uuid,
+    created_at: DateTime<Utc>,
+    note: Option<&str>,
+) -> anyhow::Result<bool> {
+    let Some(subject_id) = decision.subject_id else {
+        return Ok(false);
+    };
+
+    let case: Option<(Uuid, bool)> = if decision.decision == Decision::Review {
+        // Upsert against the one-active-case-per-subject index so
+        // concurrent reviews share a case; xmax is 0 for inserted rows
+        sqlx::query_as(
+            r#"
+            INSERT INTO cases (subject_id, opened_by_decision, note)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (subject_id) WHERE status IN ('open', 'investigating')
+            DO UPDATE SET updated_at = now()
+            RETURNING id, (xmax = 0) AS opened
+            "#,
+        )
+        .bind(subject_id)
+        .bind(decision_id)
+        .bind(note)
+        .fetch_optional(&mut *conn)
+        .await?
+    } else {
+        sqlx::query_as(
+            r#"
+            UPDATE cases SET updated_at = now()
+            WHERE subject_id = $1 AND status IN ('open', 'investigating')
+            RETURNING id, false AS opened
+            "#,
+        )
+        .bind(subject_id)
+        .fetch_optional(&mut *conn)
+        .await?
+    };
+
+    let Some((case_id, opened)) = case else {
+        return Ok(false);
+    };
+    // created_at confines the update to one partition
+    sqlx::query("UPDATE decisions SET case_id = $1 WHERE id = $2 AND created_at = $3")
+        .bind(case_id)
+        .bind(decision_id)
+        .bind(created_at)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(opened)
+}
+
+/// Build a case from a row of the case queries.
+fn case_from_row(row: &PgRow) -> anyhow::Result<Case> {
+    let status: String = row.get("status");
+    Ok(Case {
+        id: row.get("id"),
+        subject_id: row.get("subject_id"),
+        status: CaseStatus::from_label(&status)
+            .ok_or_else(|| anyhow::anyhow!("Unknown stored case status: {}", status))?,
+        opened_by_decision: row.get("opened_by_decision"),
+        decision_ids: row.get("decision_ids"),
+        note: row.get("note"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+fn subject_address_from_row(row: &PgRow) -> SubjectAddress {
+    SubjectAddress {
+        address: Address::new(row.get::<String, _>("address")),
+        verified: row.get("verified"),
+        label: row.get("label"),
+        first_seen: row.get("created_at"),
+    }
+}
+
+fn annotation_from_row(row: &PgRow) -> anyhow::Result<Annotation> {
+    let risk_rating: Option<String> = row.get("risk_rating");
+    Ok(Annotation {
+        id: row.get("id"),
+        subject_id: row.get("subject_id"),
+        note: row.get("note"),
+        risk_rating: risk_rating
+            .map(|r| {
+                RiskRating::from_label(&r)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown stored risk rating: {}", r))
+            })
+            .transpose()?,
+        tags: row.get("tags"),
+        actor: row.get("actor"),
+        created_at: row.get("created_at"),
+    })
+}
+
+/// Build an outbox event from a row of the outbox queries.
+fn outbox_event_from_row(row: &PgRow) -> OutboxEvent {
+    OutboxEvent {
+        id: row.get("id"),
+        kind: row.get("kind"),
+        payload: row.get("payload"),
+        attempts: row.get::<i32, _>("attempts") as u32,
+    }
+}
+
+/// Build a stored decision from a row of the decision queries.
+fn stored_decision_from_row(row: &PgRow) -> anyhow::Result<StoredDecision> {
+    let decision: i16 = row.get("decision");
+    let evidence: Option<serde_json::Value> = row.get("evidence");
+    let latency_ms: Option<i32> = row.get("latency_ms");
+
+    Ok(StoredDecision {
+        id: row.get("id"),
+        created_at: row.get("created_at"),
+        record: DecisionRecord {
+            subject_id: row.get("subject_id"),
+            request_id: row.get("request_id"),
+            request: row.get("request"),
+            decision: parse_stored_decision(decision)?,
+            decision_code: row.get("decision_code"),
+            policy_version: row.get("policy_version"),
+            evidence: match evidence {
+                Some(value) => serde_json::from_value(value)?,
+                None => Vec::new(),
+            },
+            latency_ms: latency_ms.unwrap_or(0) as u32,
+        },
+    })
+}
+
+/// Parse a decision stored by severity rank.
+fn parse_stored_decision(severity: i16) -> anyhow::Result<Decision> {
+    u8::try_from(severity)
+        .ok()
+        .and_then(Decision::from_severity)
+        .ok_or_else(|| anyhow::anyhow!("Unknown stored decision severity: {}", severity))
+}
+
+/// Insert a transaction and update the subject's typical size, on the
+/// given transaction.
+async fn insert_transaction(
+    conn: &mut PgConnection,
+    tx: &TransactionRecord,
+) -> anyhow::Result<Uuid> {
+    let tx_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO transactions
+            (subject_id, tx_type, asset, amount, usd_value, dest_address, counterparty_geo,
+             account_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id
+        "#,
+    )
+    .bind(tx.subject_id)
+    .bind(&tx.tx_type)
+    .bind(&tx.asset)
+    .bind(tx.amount)
+    .bind(tx.usd_value)
+    .bind(&tx.dest_address)
+    .bind(&tx.counterparty_geo)
+    .bind(&tx.account_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Same log-space EWMA as TxSizeProfile::observe
+    sqlx::query(
+        r#"
+        UPDATE subjects
+        SET typical_tx_usd = CASE
+                WHEN typical_tx_usd IS NULL THEN $2
+                ELSE round(exp((1 - $3::numeric) * ln(typical_tx_usd) + $3::numeric * ln($2)), 2)
+            END,
+            tx_size_samples = tx_size_samples + 1
+        WHERE id = $1 AND $2 > 0
+        "#,
+    )
+    .bind(tx.subject_id)
+    .bind(tx.usd_value)
+    .bind(TX_SIZE_EWMA_ALPHA)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(tx_id)
+}
+
+/// Insert a decision on the given connection or transaction, returning
+/// its ID and creation time.
+async fn insert_decision(
+    conn: &mut PgConnection,
+    decision: &DecisionRecord,
+) -> anyhow::Result<(Uuid, DateTime<Utc>)> {
+    let evidence = serde_json::to_value(&decision.evidence)?;
+
+    let row: (Uuid, DateTime<Utc>) = sqlx::query_as(
+        r#"
+        INSERT INTO decisions (
+            subject_id,
+            request,
+            decision,
+            decision_code,
+            policy_version,
+            evidence,
+            latency_ms,
+            request_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING id, created_at
+        "#,
+    )
+    .bind(decision.subject_id)
+    .bind(&decision.request)
+    .bind(decision.decision.severity() as i16)
+    .bind(&decision.decision_code)
+    .bind(&decision.policy_version)
+    .bind(evidence)
+    .bind(decision.latency_ms as i32)
+    .bind(&decision.request_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(row)
 }
@@ -0,0 +1,785 @@
+// src/storage/resilient.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::domain::subject::{KycTier, SubjectAddress};
+use crate::domain::{Annotation, Case, CaseStatus, Decision, DecisionEvent, Policy, Subject};
+use crate::outbox::{LifecycleEvent, LifecycleEvents};
+
+use super::traits::{
+    AssetFlow, BlocklistEntry, DecisionRecord, KycOverride, OutboxEvent, PendingDeposit,
+    RuleSwitch, Storage, StorageRead, StorageWrite, StoredDecision, TransactionPoint,
+    TransactionRecord, TxSizeProfile, UsageRecord, WindowSpec,
+};
+
+/// Retry settings for transient storage errors.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Backoff before the first retry; doubled on each further retry
+    pub base_delay: std::time::Duration,
+    /// Upper bound on a single backoff
+    pub max_delay: std::time::Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: std::time::Duration::from_millis(10),
+            max_delay: std::time::Duration::from_millis(200),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff before the given retry (1-based), with full jitter.
+    fn backoff(&self, retry: u32) -> std::time::Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(1u32 << retry.saturating_sub(1).min(16));
+        let cap = exp.min(self.max_delay).as_micros() as u64;
+        if cap == 0 {
+            return std::time::Duration::ZERO;
+        }
+        let random = std::collections::hash_map::RandomState::new().hash_one(retry);
+        std::time::Duration::from_micros(random % (cap + 1))
+    }
+}
+
+/// Circuit breaker settings.
+#[derive(Debug, Clone, Copy)]
+pub struct BreakerOptions {
+    /// Error rate at or above which the circuit opens
+    pub error_rate: f64,
+    /// Calls needed in a window before the error rate is trusted
+    pub min_calls: u32,
+    /// Length of the window the error rate is measured over
+    pub window: std::time::Duration,
+    /// How long the circuit stays open before letting a probe through
+    pub open_for: std::time::Duration,
+}
+
+impl Default for BreakerOptions {
+    fn default() -> Self {
+        BreakerOptions {
+            error_rate: 0.5,
+            min_calls: 20,
+            window: std::time::Duration::from_secs(10),
+            open_for: std::time::Duration::from_secs(10),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum BreakerState {
+    Closed {
+        window_start: Instant,
+        calls: u32,
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A single probe call is in flight
+    HalfOpen {
+        since: Instant,
+    },
+}
+
+/// Error-rate circuit breaker.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    options: BreakerOptions,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(options: BreakerOptions) -> Self {
+        CircuitBreaker {
+            options,
+            state: Mutex::new(BreakerState::Closed {
+                window_start: Instant::now(),
+                calls: 0,
+                failures: 0,
+            }),
+        }
+    }
+
+    /// Returns true while calls are being rejected.
+    pub fn is_open(&self) -> bool {
+        match *self.state.lock() {
+            BreakerState::Open { until } => Instant::now() < until,
+            _ => false,
+        }
+    }
+
+    /// Ask to make a call; false if the circuit is open.
+    fn try_acquire(&self) -> bool {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+        match *state {
+            BreakerState::Closed { .. } => true,
+            BreakerState::Open { until } if now >= until => {
+                *state = BreakerState::HalfOpen { since: now };
+                true
+            }
+            // Let another probe through if the last one never reported back
+            BreakerState::HalfOpen { since }
+                if now.duration_since(since) >= self.options.open_for =>
+            {
+                *state = BreakerState::HalfOpen { since: now };
+                true
+            }
+            BreakerState::Open { .. } | BreakerState::HalfOpen { .. } => false,
+        }
+    }
+
+    /// Record the outcome of a call.
+    ///
+    /// Returns the lifecycle event if this opened or closed the circuit.
+    fn record(&self, success: bool) -> Option<LifecycleEvent> {
+        let now = Instant::now();
+        let mut state = self.state.lock();
+
+        match *state {
+            BreakerState::HalfOpen { .. } => {
+                if success {
+                    debug!("Storage circuit closed");
+                    *state = BreakerState::Closed {
+                        window_start: now,
+                        calls: 0,
+                        failures: 0,
+                    };
+                    return Some(LifecycleEvent::DegradedExited {
+                        component: "storage",
+                    });
+                }
+                *state = BreakerState::Open {
+                    until: now + self.options.open_for,
+                };
+            }
+            BreakerState::Closed {
+                ref mut window_start,
+                ref mut calls,
+                ref mut failures,
+            } => {
+                if now.duration_since(*window_start) > self.options.window {
+                    *window_start = now;
+                    *calls = 0;
+                    *failures = 0;
+                }
+                *calls += 1;
+                if !success {
+                    *failures += 1;
+                }
+
+                let rate = *failures as f64 / *calls as f64;
+                if *calls >= self.options.min_calls && rate >= self.options.error_rate {
+                    warn!(
+                        error_rate = rate,
+                        calls = *calls,
+                        "Storage error rate over threshold, opening circuit"
+                    );
+                    *state = BreakerState::Open {
+                        until: now + self.options.open_for,
+                    };
+                    return Some(LifecycleEvent::DegradedEntered {
+                        component: "storage",
+                        error_rate: rate,
+                    });
+                }
+            }
+            // Calls that started before the circuit opened
+            BreakerState::Open { .. } => {}
+        }
+        None
+    }
+}
+
+/// Whether a failed call may be retried.
+///
+/// Reads are retried on any transient error. Writes are only retried when
+/// the statement is known not to have been applied: the connection was
+/// never acquired, or the database rolled the transaction back.
+fn is_retryable(err: &anyhow::Error, write: bool) -> bool {
+    let Some(err) = err.downcast_ref::<sqlx::Error>() else {
+        return false;
+    };
+
+    match err {
+        sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Io(_) => !write,
+        sqlx::Error::Database(db) => match db.code().as_deref() {
+            // serialization_failure, deadlock_detected
+            Some("40001") | Some("40P01") => true,
+            // connection_exception class, admin_shutdown
+            Some(code) if code.starts_with("08") || code == "57P01" => !write,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Storage wrapper adding retries and a circuit breaker.
+///
+/// Transient errors are retried with jittered exponential backoff. When
+/// the error rate crosses the breaker threshold, calls fail fast and
+/// `is_degraded` reports true until a probe call succeeds. Opening and
+/// closing the circuit are emitted as lifecycle events.
+pub struct ResilientStorage {
+    inner: Arc<dyn Storage>,
+    retry: RetryPolicy,
+    breaker: CircuitBreaker,
+    events: LifecycleEvents,
+}
+
+impl ResilientStorage {
+    pub fn new(inner: Arc<dyn Storage>, retry: RetryPolicy, breaker: BreakerOptions) -> Self {
+        ResilientStorage {
+            inner,
+            retry,
+            breaker: CircuitBreaker::new(breaker),
+            events: LifecycleEvents::default(),
+        }
+    }
+
+    /// Emit degraded-mode transitions to `events`.
+    pub fn with_lifecycle(mut self, events: LifecycleEvents) -> Self {
+        self.events = events;
+        self
+    }
+
+    fn record(&self, success: bool) {
+        if let Some(event) = self.breaker.record(success) {
+            self.events.emit(event);
+        }
+    }
+
+    async fn call<T, F, Fut>(&self, write: bool, f: F) -> anyhow::Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        if !self.breaker.try_acquire() {
+            anyhow::bail!("storage circuit open");
+        }
+
+        let mut attempt = 1;
+        loop {
+            match f().await {
+                Ok(value) => {
+                    self.record(true);
+                    return Ok(value);
+                }
+                Err(e) if attempt < self.retry.max_attempts && is_retryable(&e, write) => {
+                    debug!(attempt = attempt, error = %e, "Retrying storage call");
+                    tokio::time::sleep(self.retry.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    self.record(false);
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl StorageRead for ResilientStorage {
+    async fn get_subject_by_user_id(
+        &self,
+        user_id: &str,
+    ) -> anyhow::Result<Option<(Uuid, Subject)>> {
+        self.call(false, || self.inner.get_subject_by_user_id(user_id))
+            .await
+    }
+
+    async fn get_subject_addresses(&self, subject_id: Uuid) -> anyhow::Result<Vec<SubjectAddress>> {
+        self.call(false, || self.inner.get_subject_addresses(subject_id))
+            .await
+    }
+
+    async fn get_kyc_overrides(&self) -> anyhow::Result<Vec<KycOverride>> {
+        self.call(false, || self.inner.get_kyc_overrides()).await
+    }
+
+    async fn get_rolling_volume(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        self.call(false, || self.inner.get_rolling_volume(subject_id, window))
+            .await
+    }
+
+    async fn get_rolling_amount(
+        &self,
+        subject_id: Uuid,
+        asset: &str,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        self.call(false, || {
+            self.inner.get_rolling_amount(subject_id, asset, window)
+        })
+        .await
+    }
+
+    async fn get_small_tx_count(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+        threshold: Decimal,
+    ) -> anyhow::Result<u32> {
+        self.call(false, || {
+            self.inner.get_small_tx_count(subject_id, window, threshold)
+        })
+        .await
+    }
+
+    async fn get_counterparty_tx_count(
+        &self,
+        subject_id: Uuid,
+        country: &str,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        self.call(false, || {
+            self.inner
+                .get_counterparty_tx_count(subject_id, country, window)
+        })
+        .await
+    }
+
+    async fn get_asset_flows(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<AssetFlow>> {
+        self.call(false, || self.inner.get_asset_flows(subject_id, window))
+            .await
+    }
+
+    async fn get_recent_transactions(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<TransactionPoint>> {
+        self.call(false, || {
+            self.inner.get_recent_transactions(subject_id, window)
+        })
+        .await
+    }
+
+    async fn get_hourly_activity(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<[u32; 24]> {
+        self.call(false, || self.inner.get_hourly_activity(subject_id, window))
+            .await
+    }
+
+    async fn get_window_aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.call(false, || {
+            self.inner.get_window_aggregates(subject_id, specs)
+        })
+        .await
+    }
+
+    async fn get_window_aggregates_at(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.call(false, || {
+            self.inner.get_window_aggregates_at(subject_id, specs, at)
+        })
+        .await
+    }
+
+    async fn get_tx_size_profile(&self, subject_id: Uuid) -> anyhow::Result<Option<TxSizeProfile>> {
+        self.call(false, || self.inner.get_tx_size_profile(subject_id))
+            .await
+    }
+
+    async fn get_all_sanctions(&self) -> anyhow::Result<Vec<String>> {
+        self.call(false, || self.inner.get_all_sanctions()).await
+    }
+
+    async fn is_sanctioned(&self, address: &str) -> anyhow::Result<bool> {
+        self.call(false, || self.inner.is_sanctioned(address)).await
+    }
+
+    async fn get_active_policy(&self) -> anyhow::Result<Option<Policy>> {
+        self.call(false, || self.inner.get_active_policy()).await
+    }
+
+    async fn get_policy(&self, version: &str) -> anyhow::Result<Option<Policy>> {
+        self.call(false, || self.inner.get_policy(version)).await
+    }
+
+    async fn count_recent_decisions(
+        &self,
+        subject_id: Uuid,
+        min_decision: Decision,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        self.call(false, || {
+            self.inner
+                .count_recent_decisions(subject_id, min_decision, window)
+        })
+        .await
+    }
+
+    async fn get_decisions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        self.call(false, || self.inner.get_decisions(from, to, after, limit))
+            .await
+    }
+
+    async fn get_decision_by_request_id(
+        &self,
+        request_id: &str,
+    ) -> anyhow::Result<Option<StoredDecision>> {
+        self.call(false, || self.inner.get_decision_by_request_id(request_id))
+            .await
+    }
+
+    async fn get_subject_decisions(
+        &self,
+        subject_id: Uuid,
+        at: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        self.call(false, || {
+            self.inner.get_subject_decisions(subject_id, at, limit)
+        })
+        .await
+    }
+
+    async fn get_recent_decisions(&self, limit: u32) -> anyhow::Result<Vec<StoredDecision>> {
+        self.call(false, || self.inner.get_recent_decisions(limit))
+            .await
+    }
+
+    async fn pending_outbox(&self, limit: u32) -> anyhow::Result<Vec<OutboxEvent>> {
+        self.call(false, || self.inner.pending_outbox(limit)).await
+    }
+
+    async fn get_pending_deposit(&self, event_id: &str) -> anyhow::Result<Option<PendingDeposit>> {
+        self.call(false, || self.inner.get_pending_deposit(event_id))
+            .await
+    }
+
+    async fn get_case(&self, case_id: Uuid) -> anyhow::Result<Option<Case>> {
+        self.call(false, || self.inner.get_case(case_id)).await
+    }
+
+    async fn list_cases(
+        &self,
+        status: Option<CaseStatus>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Case>> {
+        self.call(false, || self.inner.list_cases(status, limit))
+            .await
+    }
+
+    async fn get_annotations(
+        &self,
+        subject_id: Uuid,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Annotation>> {
+        self.call(false, || self.inner.get_annotations(subject_id, limit))
+            .await
+    }
+
+    async fn get_disabled_rules(&self) -> anyhow::Result<Vec<RuleSwitch>> {
+        self.call(false, || self.inner.get_disabled_rules()).await
+    }
+
+    async fn get_blocklist(&self) -> anyhow::Result<Vec<BlocklistEntry>> {
+        self.call(false, || self.inner.get_blocklist()).await
+    }
+
+    async fn get_daily_usage(&self, tenant: &str) -> anyhow::Result<u64> {
+        self.call(false, || self.inner.get_daily_usage(tenant))
+            .await
+    }
+
+    async fn get_usage(&self, from: NaiveDate, to: NaiveDate) -> anyhow::Result<Vec<UsageRecord>> {
+        self.call(false, || self.inner.get_usage(from, to)).await
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.breaker.is_open() || self.inner.is_degraded()
+    }
+}
+
+#[async_trait]
+impl StorageWrite for ResilientStorage {
+    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid> {
+        // Idempotent upsert, safe to retry like a read
+        self.call(false, || self.inner.upsert_subject(subject))
+            .await
+    }
+
+    async fn set_kyc_tier(&self, user_id: &str, tier: &KycTier) -> anyhow::Result<bool> {
+        // Sets an absolute value, safe to retry like a read
+        self.call(false, || self.inner.set_kyc_tier(user_id, tier))
+            .await
+    }
+
+    async fn save_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+        verified: Option<bool>,
+        label: Option<&str>,
+    ) -> anyhow::Result<SubjectAddress> {
+        // An upsert to absolute values, safe to retry like a read
+        self.call(false, || {
+            self.inner
+                .save_subject_address(subject_id, address, verified, label)
+        })
+        .await
+    }
+
+    async fn remove_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+    ) -> anyhow::Result<bool> {
+        self.call(true, || {
+            self.inner.remove_subject_address(subject_id, address)
+        })
+        .await
+    }
+
+    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid> {
+        self.call(true, || self.inner.record_transaction(tx)).await
+    }
+
+    async fn set_active_policy(&self, policy: &Policy) -> anyhow::Result<()> {
+        // Runs in a single transaction, so a failed attempt left nothing behind
+        self.call(false, || self.inner.set_active_policy(policy))
+            .await
+    }
+
+    async fn record_decision(&self, decision: &DecisionRecord) -> anyhow::Result<Uuid> {
+        self.call(true, || self.inner.record_decision(decision))
+            .await
+    }
+
+    async fn record_outcome(
+        &self,
+        tx: &TransactionRecord,
+        decision: &DecisionRecord,
+    ) -> anyhow::Result<Uuid> {
+        self.call(true, || self.inner.record_outcome(tx, decision))
+            .await
+    }
+
+    async fn record_decision_outcome(
+        &self,
+        decision: &DecisionRecord,
+        note: Option<&str>,
+    ) -> anyhow::Result<(Uuid, bool)> {
+        self.call(true, || self.inner.record_decision_outcome(decision, note))
+            .await
+    }
+
+    async fn claim_outbox(&self, limit: u32, lease: Duration) -> anyhow::Result<Vec<OutboxEvent>> {
+        self.call(true, || self.inner.claim_outbox(limit, lease))
+            .await
+    }
+
+    async fn mark_outbox_delivered(&self, id: Uuid) -> anyhow::Result<()> {
+        self.call(false, || self.inner.mark_outbox_delivered(id))
+            .await
+    }
+
+    async fn mark_outbox_failed(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        self.call(true, || self.inner.mark_outbox_failed(id, error))
+            .await
+    }
+
+    async fn mark_outbox_dead(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        self.call(true, || self.inner.mark_outbox_dead(id, error))
+            .await
+    }
+
+    async fn save_pending_deposit(&self, deposit: &PendingDeposit) -> anyhow::Result<()> {
+        self.call(true, || self.inner.save_pending_deposit(deposit))
+            .await
+    }
+
+    async fn resolve_pending_deposit(
+        &self,
+        event_id: &str,
+        decision: &DecisionRecord,
+        event: &DecisionEvent,
+    ) -> anyhow::Result<Option<Uuid>> {
+        self.call(true, || {
+            self.inner
+                .resolve_pending_deposit(event_id, decision, event)
+        })
+        .await
+    }
+
+    async fn create_case(
+        &self,
+        subject_id: Uuid,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        self.call(true, || self.inner.create_case(subject_id, note))
+            .await
+    }
+
+    async fn update_case_status(
+        &self,
+        case_id: Uuid,
+        from: CaseStatus,
+        to: CaseStatus,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        self.call(true, || {
+            self.inner.update_case_status(case_id, from, to, note)
+        })
+        .await
+    }
+
+    async fn add_annotation(&self, annotation: &Annotation) -> anyhow::Result<()> {
+        self.call(true, || self.inner.add_annotation(annotation))
+            .await
+    }
+
+    async fn disable_rule(&self, switch: &RuleSwitch) -> anyhow::Result<()> {
+        self.call(true, || self.inner.disable_rule(switch)).await
+    }
+
+    async fn enable_rule(&self, rule_id: &str, actor: &str) -> anyhow::Result<()> {
+        self.call(true, || self.inner.enable_rule(rule_id, actor))
+            .await
+    }
+
+    async fn save_blocklist(
+        &self,
+        entries: &[BlocklistEntry],
+        replace: bool,
+    ) -> anyhow::Result<()> {
+        // Upserts keyed by address, safe to retry
+        self.call(false, || self.inner.save_blocklist(entries, replace))
+            .await
+    }
+
+    async fn record_usage(&self, tenant: &str, rules_hit: &[String]) -> anyhow::Result<()> {
+        self.call(true, || self.inner.record_usage(tenant, rules_hit))
+            .await
+    }
+
+    async fn claim_nonce(&self, nonce: &str, expires_at: DateTime<Utc>) -> anyhow::Result<bool> {
+        // A retried claim that had landed would reject its own request
+        self.call(true, || self.inner.claim_nonce(nonce, expires_at))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(BreakerOptions {
+            error_rate: 0.5,
+            min_calls: 4,
+            window: std::time::Duration::from_secs(60),
+            open_for: std::time::Duration::from_millis(20),
+        })
+    }
+
+    #[test]
+    fn test_breaker_opens_on_error_rate() {
+        let breaker = breaker();
+
+        breaker.record(true);
+        breaker.record(false);
+        breaker.record(true);
+        assert!(!breaker.is_open()); // Below min_calls
+
+        assert_eq!(
+            breaker.record(false),
+            Some(LifecycleEvent::DegradedEntered {
+                component: "storage",
+                error_rate: 0.5,
+            })
+        );
+        assert!(breaker.is_open()); // 2/4 failures
+        assert!(!breaker.try_acquire());
+    }
+
+    #[test]
+    fn test_breaker_half_open_probe() {
+        let breaker = breaker();
+        for _ in 0..4 {
+            breaker.record(false);
+        }
+        assert!(breaker.is_open());
+
+        std::thread::sleep(std::time::Duration::from_millis(30));
+        assert!(breaker.try_acquire()); // Probe allowed
+        assert!(!breaker.try_acquire()); // Only one probe
+
+        assert_eq!(
+            breaker.record(true),
+            Some(LifecycleEvent::DegradedExited {
+                component: "storage"
+            })
+        );
+        assert!(!breaker.is_open());
+        assert!(breaker.try_acquire());
+    }
+
+    #[test]
+    fn test_backoff_bounded() {
+        let retry = RetryPolicy::default();
+        for attempt in 1..10 {
+            assert!(retry.backoff(attempt) <= retry.max_delay);
+        }
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        let timeout = anyhow::Error::from(sqlx::Error::PoolTimedOut);
+        assert!(is_retryable(&timeout, false));
+        assert!(is_retryable(&timeout, true));
+
+        let io = anyhow::Error::from(sqlx::Error::Io(std::io::Error::from(
+            std::io::ErrorKind::ConnectionReset,
+        )));
+        assert!(is_retryable(&io, false));
+        assert!(!is_retryable(&io, true)); // May have been applied
+
+        let other = anyhow::anyhow!("not a database error");
+        assert!(!is_retryable(&other, false));
+    }
+}
@@ -0,0 +1,861 @@
+// src/storage/tiered.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::domain::subject::{KycTier, SubjectAddress};
+use crate::domain::{Annotation, Case, CaseStatus, Decision, DecisionEvent, Policy, Subject};
+
+use super::traits::{
+    AssetFlow, BlocklistEntry, DecisionRecord, KycOverride, OutboxEvent, PendingDeposit,
+    RuleSwitch, Storage, StorageRead, StorageWrite, StoredDecision, TransactionPoint,
+    TransactionRecord, TxSizeProfile, UsageRecord, WindowSpec,
+};
+
+/// Storage that serves transaction window queries from memory.
+///
+/// Subjects are loaded from the backing store the first time one of their
+/// windows is queried and kept up to date as transactions are written
+/// through. The cache starts empty, so a restarted process is consistent
+/// with the backing store by construction; `warm_up` preloads recently
+/// active subjects so their first requests after a deploy are not cold.
+/// Everything else, and windows longer than the retention, goes straight
+/// to the backing store.
+///
+/// Writes for a subject should go through a single instance. Where they
+/// don't, such as behind a load balancer without subject affinity, run
+/// `reconcile` periodically to repair the drift.
+pub struct TieredStorage {
+    cold: Arc<dyn Storage>,
+    /// Longest window served from memory
+    retention: Duration,
+    /// Maximum number of subjects held in memory
+    max_subjects: usize,
+    cache: Mutex<HotWindows>,
+}
+
+#[derive(Default)]
+struct HotWindows {
+    /// Transactions within the retention, oldest first
+    subjects: HashMap<Uuid, VecDeque<TransactionPoint>>,
+    /// Subjects being loaded; true if a write arrived during the load
+    loading: HashMap<Uuid, bool>,
+}
+
+impl TieredStorage {
+    /// Wrap a backing store with an in-memory window cache.
+    pub fn new(cold: Arc<dyn Storage>, retention: Duration, max_subjects: usize) -> Self {
+        TieredStorage {
+            cold,
+            retention,
+            max_subjects,
+            cache: Mutex::new(HotWindows::default()),
+        }
+    }
+
+    /// Number of subjects currently held in memory.
+    pub fn cached_subjects(&self) -> usize {
+        self.cache.lock().subjects.len()
+    }
+
+    /// Reload every cached subject from the backing store.
+    ///
+    /// Subjects written through during their reload are left for the next
+    /// run. Returns the number of subjects whose cached window had drifted.
+    pub async fn reconcile(&self) -> anyhow::Result<usize> {
+        let subject_ids: Vec<Uuid> = self.cache.lock().subjects.keys().copied().collect();
+        let mut drifted = 0;
+
+        for subject_id in subject_ids {
+            {
+                let mut cache = self.cache.lock();
+                if cache.loading.contains_key(&subject_id) {
+                    continue;
+                }
+                cache.loading.insert(subject_id, false);
+            }
+            let loaded = self
+                .cold
+                .get_recent_transactions(subject_id, self.retention)
+                .await;
+
+            let mut cache = self.cache.lock();
+            let dirty = cache.loading.remove(&subject_id);
+            let points = loaded?;
+            if dirty != Some(false) {
+                continue;
+            }
+            if let Some(cached) = cache.subjects.get_mut(&subject_id) {
+                // Timestamps differ between tiers, so compare values only
+                let same = cached.len() == points.len()
+                    && cached
+                        .iter()
+                        .zip(&points)
+                        .all(|(a, b)| a.usd_value == b.usd_value);
+                if !same {
+                    drifted += 1;
+                    *cached = points.into();
+                }
+            }
+        }
+
+        debug!(drifted = drifted, "Reconciled window cache");
+        Ok(drifted)
+    }
+
+    /// Load the windows of subjects with decisions in the last `lookback`,
+    /// most recently active first, up to the subject limit.
+    ///
+    /// Returns the number of subjects loaded.
+    pub async fn warm_up(&self, lookback: Duration) -> anyhow::Result<usize> {
+        const PAGE: u32 = 1000;
+        let to = Utc::now();
+        let from = to - lookback;
+
+        // Latest decision time per subject
+        let mut last_seen: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+        let mut after = None;
+        loop {
+            let page = self.cold.get_decisions(from, to, after, PAGE).await?;
+            for decision in &page {
+                if let Some(subject_id) = decision.record.subject_id {
+                    last_seen.insert(subject_id, decision.created_at);
+                }
+            }
+            match page.last() {
+                Some(last) if page.len() == PAGE as usize => {
+                    after = Some((last.created_at, last.id))
+                }
+                _ => break,
+            }
+        }
+
+        let mut subjects: Vec<(Uuid, DateTime<Utc>)> = last_seen.into_iter().collect();
+        subjects.sort_by_key(|(_, at)| Reverse(*at));
+        subjects.truncate(self.max_subjects);
+
+        let mut loaded = 0;
+        for (subject_id, _) in subjects {
+            {
+                let mut cache = self.cache.lock();
+                if cache.subjects.contains_key(&subject_id)
+                    || cache.loading.contains_key(&subject_id)
+                {
+                    continue;
+                }
+                cache.loading.insert(subject_id, false);
+            }
+            self.load(subject_id).await?;
+            loaded += 1;
+        }
+
+        debug!(subjects = loaded, "Warmed up window cache");
+        Ok(loaded)
+    }
+
+    /// Transactions within the window, loading the subject if needed.
+    ///
+    /// Returns None if the window is longer than the retention.
+    async fn window(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Option<Vec<TransactionPoint>>> {
+        if window > self.retention {
+            return Ok(None);
+        }
+        self.points_after(subject_id, Utc::now() - window)
+            .await
+            .map(Some)
+    }
+
+    /// Transactions after `since`, loading the subject if needed.
+    ///
+    /// Returns None if `since` is further back than the retention.
+    async fn since(
+        &self,
+        subject_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Option<Vec<TransactionPoint>>> {
+        if Utc::now() - since > self.retention {
+            return Ok(None);
+        }
+        self.points_after(subject_id, since).await.map(Some)
+    }
+
+    /// Window aggregates ending at `at`, or now, from memory when it holds
+    /// them all.
+    async fn aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<Decimal>> {
+        // Memory holds only USD values, so anything else goes to the
+        // backing store in one call
+        let hot = specs
+            .iter()
+            .all(|spec| matches!(spec, WindowSpec::Volume(_) | WindowSpec::SmallCount { .. }));
+        let longest = specs.iter().map(WindowSpec::window).max();
+        let points = match (longest, at) {
+            (Some(longest), None) if hot => self.window(subject_id, longest).await?,
+            (Some(longest), Some(at)) if hot => self.since(subject_id, at - longest).await?,
+            _ => None,
+        };
+        let Some(points) = points else {
+            return match at {
+                Some(at) => {
+                    self.cold
+                        .get_window_aggregates_at(subject_id, specs, at)
+                        .await
+                }
+                None => self.cold.get_window_aggregates(subject_id, specs).await,
+            };
+        };
+
+        let end = at.unwrap_or_else(Utc::now);
+        Ok(specs
+            .iter()
+            .map(|spec| {
+                let since = end - spec.window();
+                let in_window = points.iter().filter(|p| p.at > since && p.at <= end);
+                match spec {
+                    WindowSpec::SmallCount { threshold, .. } => {
+                        Decimal::from(in_window.filter(|p| p.usd_value < *threshold).count())
+                    }
+                    _ => in_window.map(|p| p.usd_value).sum(),
+                }
+            })
+            .collect())
+    }
+
+    /// Transactions after `since` within the retention.
+    async fn points_after(
+        &self,
+        subject_id: Uuid,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<TransactionPoint>> {
+        let in_window = |points: &VecDeque<TransactionPoint>| -> Vec<TransactionPoint> {
+            points.iter().filter(|p| p.at > since).copied().collect()
+        };
+
+        {
+            let mut cache = self.cache.lock();
+            if let Some(points) = cache.subjects.get_mut(&subject_id) {
+                prune(points, self.retention);
+                return Ok(in_window(points));
+            }
+            cache.loading.entry(subject_id).or_insert(false);
+        }
+
+        let points = self.load(subject_id).await?;
+        Ok(in_window(&points))
+    }
+
+    /// Load a subject marked as loading from the backing store, caching its
+    /// window unless a write raced with the load.
+    async fn load(&self, subject_id: Uuid) -> anyhow::Result<VecDeque<TransactionPoint>> {
+        let loaded = self
+            .cold
+            .get_recent_transactions(subject_id, self.retention)
+            .await;
+
+        let mut cache = self.cache.lock();
+        let dirty = cache.loading.remove(&subject_id);
+        let points: VecDeque<TransactionPoint> = loaded?.into();
+
+        // Only cache if no write raced with the load
+        if dirty == Some(false) {
+            if cache.subjects.len() >= self.max_subjects {
+                if let Some(evict) = cache.subjects.keys().next().copied() {
+                    cache.subjects.remove(&evict);
+                }
+            }
+            cache.subjects.insert(subject_id, points.clone());
+        }
+
+        Ok(points)
+    }
+
+    /// Add a written-through transaction to the subject's cached window.
+    fn push_transaction(&self, tx: &TransactionRecord) {
+        let mut cache = self.cache.lock();
+        if let Some(dirty) = cache.loading.get_mut(&tx.subject_id) {
+            *dirty = true;
+        }
+        if let Some(points) = cache.subjects.get_mut(&tx.subject_id) {
+            points.push_back(TransactionPoint {
+                at: Utc::now(),
+                usd_value: tx.usd_value,
+            });
+        }
+    }
+}
+
+/// Drop transactions older than the retention.
+fn prune(points: &mut VecDeque<TransactionPoint>, retention: Duration) {
+    let cutoff = Utc::now() - retention;
+    while points.front().is_some_and(|p| p.at <= cutoff) {
+        points.pop_front();
+    }
+}
+
+#[async_trait]
+impl StorageRead for TieredStorage {
+    async fn get_subject_by_user_id(
+        &self,
+        user_id: &str,
+    ) -> anyhow::Result<Option<(Uuid, Subject)>> {
+        self.cold.get_subject_by_user_id(user_id).await
+    }
+
+    async fn get_subject_addresses(&self, subject_id: Uuid) -> anyhow::Result<Vec<SubjectAddress>> {
+        self.cold.get_subject_addresses(subject_id).await
+    }
+
+    async fn get_kyc_overrides(&self) -> anyhow::Result<Vec<KycOverride>> {
+        self.cold.get_kyc_overrides().await
+    }
+
+    async fn get_rolling_volume(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        match self.window(subject_id, window).await? {
+            Some(points) => Ok(points.iter().map(|p| p.usd_value).sum()),
+            None => self.cold.get_rolling_volume(subject_id, window).await,
+        }
+    }
+
+    async fn get_rolling_amount(
+        &self,
+        subject_id: Uuid,
+        asset: &str,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        // Cached windows only hold USD values
+        self.cold
+            .get_rolling_amount(subject_id, asset, window)
+            .await
+    }
+
+    async fn get_small_tx_count(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+        threshold: Decimal,
+    ) -> anyhow::Result<u32> {
+        match self.window(subject_id, window).await? {
+            Some(points) => Ok(points.iter().filter(|p| p.usd_value < threshold).count() as u32),
+            None => {
+                self.cold
+                    .get_small_tx_count(subject_id, window, threshold)
+                    .await
+            }
+        }
+    }
+
+    async fn get_counterparty_tx_count(
+        &self,
+        subject_id: Uuid,
+        country: &str,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        self.cold
+            .get_counterparty_tx_count(subject_id, country, window)
+            .await
+    }
+
+    async fn get_asset_flows(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<AssetFlow>> {
+        self.cold.get_asset_flows(subject_id, window).await
+    }
+
+    async fn get_recent_transactions(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<TransactionPoint>> {
+        match self.window(subject_id, window).await? {
+            Some(points) => Ok(points),
+            None => self.cold.get_recent_transactions(subject_id, window).await,
+        }
+    }
+
+    async fn get_hourly_activity(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<[u32; 24]> {
+        match self.window(subject_id, window).await? {
+            Some(points) => {
+                let mut hours = [0u32; 24];
+                for p in &points {
+                    hours[p.at.hour() as usize] += 1;
+                }
+                Ok(hours)
+            }
+            None => self.cold.get_hourly_activity(subject_id, window).await,
+        }
+    }
+
+    async fn get_window_aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.aggregates(subject_id, specs, None).await
+    }
+
+    async fn get_window_aggregates_at(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.aggregates(subject_id, specs, Some(at)).await
+    }
+
+    async fn get_tx_size_profile(&self, subject_id: Uuid) -> anyhow::Result<Option<TxSizeProfile>> {
+        self.cold.get_tx_size_profile(subject_id).await
+    }
+
+    async fn get_all_sanctions(&self) -> anyhow::Result<Vec<String>> {
+        self.cold.get_all_sanctions().await
+    }
+
+    async fn is_sanctioned(&self, address: &str) -> anyhow::Result<bool> {
+        self.cold.is_sanctioned(address).await
+    }
+
+    async fn get_active_policy(&self) -> anyhow::Result<Option<Policy>> {
+        self.cold.get_active_policy().await
+    }
+
+    async fn get_policy(&self, version: &str) -> anyhow::Result<Option<Policy>> {
+        self.cold.get_policy(version).await
+    }
+
+    async fn count_recent_decisions(
+        &self,
+        subject_id: Uuid,
+        min_decision: Decision,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        self.cold
+            .count_recent_decisions(subject_id, min_decision, window)
+            .await
+    }
+
+    async fn get_decisions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        self.cold.get_decisions(from, to, after, limit).await
+    }
+
+    async fn get_decision_by_request_id(
+        &self,
+        request_id: &str,
+    ) -> anyhow::Result<Option<StoredDecision>> {
+        self.cold.get_decision_by_request_id(request_id).await
+    }
+
+    async fn get_subject_decisions(
+        &self,
+        subject_id: Uuid,
+        at: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        self.cold.get_subject_decisions(subject_id, at, limit).await
+    }
+
+    async fn get_recent_decisions(&self, limit: u32) -> anyhow::Result<Vec<StoredDecision>> {
+        self.cold.get_recent_decisions(limit).await
+    }
+
+    async fn pending_outbox(&self, limit: u32) -> anyhow::Result<Vec<OutboxEvent>> {
+        self.cold.pending_outbox(limit).await
+    }
+
+    async fn get_pending_deposit(&self, event_id: &str) -> anyhow::Result<Option<PendingDeposit>> {
+        self.cold.get_pending_deposit(event_id).await
+    }
+
+    async fn get_case(&self, case_id: Uuid) -> anyhow::Result<Option<Case>> {
+        self.cold.get_case(case_id).await
+    }
+
+    async fn list_cases(
+        &self,
+        status: Option<CaseStatus>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Case>> {
+        self.cold.list_cases(status, limit).await
+    }
+
+    async fn get_annotations(
+        &self,
+        subject_id: Uuid,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Annotation>> {
+        self.cold.get_annotations(subject_id, limit).await
+    }
+
+    async fn get_disabled_rules(&self) -> anyhow::Result<Vec<RuleSwitch>> {
+        self.cold.get_disabled_rules().await
+    }
+
+    async fn get_blocklist(&self) -> anyhow::Result<Vec<BlocklistEntry>> {
+        self.cold.get_blocklist().await
+    }
+
+    async fn get_daily_usage(&self, tenant: &str) -> anyhow::Result<u64> {
+        self.cold.get_daily_usage(tenant).await
+    }
+
+    async fn get_usage(&self, from: NaiveDate, to: NaiveDate) -> anyhow::Result<Vec<UsageRecord>> {
+        self.cold.get_usage(from, to).await
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.cold.is_degraded()
+    }
+}
+
+#[async_trait]
+impl StorageWrite for TieredStorage {
+    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid> {
+        self.cold.upsert_subject(subject).await
+    }
+
+    async fn set_kyc_tier(&self, user_id: &str, tier: &KycTier) -> anyhow::Result<bool> {
+        self.cold.set_kyc_tier(user_id, tier).await
+    }
+
+    async fn save_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+        verified: Option<bool>,
+        label: Option<&str>,
+    ) -> anyhow::Result<SubjectAddress> {
+        self.cold
+            .save_subject_address(subject_id, address, verified, label)
+            .await
+    }
+
+    async fn remove_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+    ) -> anyhow::Result<bool> {
+        self.cold.remove_subject_address(subject_id, address).await
+    }
+
+    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid> {
+        let tx_id = self.cold.record_transaction(tx).await?;
+        self.push_transaction(tx);
+        Ok(tx_id)
+    }
+
+    async fn set_active_policy(&self, policy: &Policy) -> anyhow::Result<()> {
+        self.cold.set_active_policy(policy).await
+    }
+
+    async fn record_decision(&self, decision: &DecisionRecord) -> anyhow::Result<Uuid> {
+        self.cold.record_decision(decision).await
+    }
+
+    async fn record_outcome(
+        &self,
+        tx: &TransactionRecord,
+        decision: &DecisionRecord,
+    ) -> anyhow::Result<Uuid> {
+        let decision_id = self.cold.record_outcome(tx, decision).await?;
+        self.push_transaction(tx);
+        Ok(decision_id)
+    }
+
+    async fn record_decision_outcome(
+        &self,
+        decision: &DecisionRecord,
+        note: Option<&str>,
+    ) -> anyhow::Result<(Uuid, bool)> {
+        self.cold.record_decision_outcome(decision, note).await
+    }
+
+    async fn claim_outbox(&self, limit: u32, lease: Duration) -> anyhow::Result<Vec<OutboxEvent>> {
+        self.cold.claim_outbox(limit, lease).await
+    }
+
+    async fn mark_outbox_delivered(&self, id: Uuid) -> anyhow::Result<()> {
+        self.cold.mark_outbox_delivered(id).await
+    }
+
+    async fn mark_outbox_failed(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        self.cold.mark_outbox_failed(id, error).await
+    }
+
+    async fn mark_outbox_dead(&self, id: Uuid, error: &str) -> anyhow::Result<()> {
+        self.cold.mark_outbox_dead(id, error).await
+    }
+
+    async fn save_pending_deposit(&self, deposit: &PendingDeposit) -> anyhow::Result<()> {
+        self.cold.save_pending_deposit(deposit).await
+    }
+
+    async fn resolve_pending_deposit(
+        &self,
+        event_id: &str,
+        decision: &DecisionRecord,
+        event: &DecisionEvent,
+    ) -> anyhow::Result<Option<Uuid>> {
+        self.cold
+            .resolve_pending_deposit(event_id, decision, event)
+            .await
+    }
+
+    async fn create_case(
+        &self,
+        subject_id: Uuid,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        self.cold.create_case(subject_id, note).await
+    }
+
+    async fn update_case_status(
+        &self,
+        case_id: Uuid,
+        from: CaseStatus,
+        to: CaseStatus,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>> {
+        self.cold.update_case_status(case_id, from, to, note).await
+    }
+
+    async fn add_annotation(&self, annotation: &Annotation) -> anyhow::Result<()> {
+        self.cold.add_annotation(annotation).await
+    }
+
+    async fn disable_rule(&self, switch: &RuleSwitch) -> anyhow::Result<()> {
+        self.cold.disable_rule(switch).await
+    }
+
+    async fn enable_rule(&self, rule_id: &str, actor: &str) -> anyhow::Result<()> {
+        self.cold.enable_rule(rule_id, actor).await
+    }
+
+    async fn save_blocklist(
+        &self,
+        entries: &[BlocklistEntry],
+        replace: bool,
+    ) -> anyhow::Result<()> {
+        self.cold.save_blocklist(entries, replace).await
+    }
+
+    async fn record_usage(&self, tenant: &str, rules_hit: &[String]) -> anyhow::Result<()> {
+        self.cold.record_usage(tenant, rules_hit).await
+    }
+
+    async fn claim_nonce(&self, nonce: &str, expires_at: DateTime<Utc>) -> anyhow::Result<bool> {
+        self.cold.claim_nonce(nonce, expires_at).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MockStorage;
+
+    fn tx(subject_id: Uuid, usd_value: i64) -> TransactionRecord {
+        TransactionRecord {
+            subject_id,
+            tx_type: "withdraw".to_string(),
+            asset: "USDC".to_string(),
+            amount: Decimal::new(usd_value, 0),
+            usd_value: Decimal::new(usd_value, 0),
+            dest_address: None,
+            counterparty_geo: None,
+            account_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_window_aggregates_from_memory() {
+        let cold = Arc::new(MockStorage::new());
+        let subject_id = Uuid::new_v4();
+        for (hours_ago, usd_value) in [(30, 500), (2, 40), (1, 1000)] {
+            cold.add_transaction_point(
+                subject_id,
+                TransactionPoint {
+                    at: Utc::now() - Duration::hours(hours_ago),
+                    usd_value: Decimal::new(usd_value, 0),
+                },
+            );
+        }
+        let storage = TieredStorage::new(cold.clone(), Duration::hours(48), 100);
+
+        let values = storage
+            .get_window_aggregates(
+                subject_id,
+                &[
+                    WindowSpec::Volume(Duration::hours(24)),
+                    WindowSpec::Volume(Duration::hours(48)),
+                    WindowSpec::SmallCount {
+                        window: Duration::hours(48),
+                        threshold: Decimal::new(600, 0),
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            values,
+            vec![
+                Decimal::new(1040, 0),
+                Decimal::new(1540, 0),
+                Decimal::new(2, 0)
+            ]
+        );
+        assert_eq!(storage.cached_subjects(), 1);
+
+        // A window ending 90 minutes ago leaves out the latest transaction
+        let values = storage
+            .get_window_aggregates_at(
+                subject_id,
+                &[WindowSpec::Volume(Duration::hours(24))],
+                Utc::now() - Duration::minutes(90),
+            )
+            .await
+            .unwrap();
+        assert_eq!(values, vec![Decimal::new(40, 0)]);
+    }
+
+    #[tokio::test]
+    async fn test_loads_from_backing_store_and_writes_through() {
+        let cold = Arc::new(MockStorage::new());
+        let subject_id = Uuid::new_v4();
+        cold.add_transaction_point(
+            subject_id,
+            TransactionPoint {
+                at: Utc::now() - Duration::hours(2),
+                usd_value: Decimal::new(1000, 0),
+            },
+        );
+        let storage = TieredStorage::new(cold.clone(), Duration::hours(48), 100);
+
+        let volume = storage
+            .get_rolling_volume(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(volume, Decimal::new(1000, 0));
+        assert_eq!(storage.cached_subjects(), 1);
+
+        storage
+            .record_transaction(&tx(subject_id, 50))
+            .await
+            .unwrap();
+
+        let volume = storage
+            .get_rolling_volume(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(volume, Decimal::new(1050, 0));
+        assert_eq!(cold.get_recorded_transactions().len(), 1);
+
+        let small = storage
+            .get_small_tx_count(subject_id, Duration::hours(24), Decimal::new(100, 0))
+            .await
+            .unwrap();
+        assert_eq!(small, 1);
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_loads_recent_subjects() {
+        let cold = Arc::new(MockStorage::new());
+        let subject_id = Uuid::new_v4();
+        cold.add_transaction_point(
+            subject_id,
+            TransactionPoint {
+                at: Utc::now() - Duration::hours(2),
+                usd_value: Decimal::new(1000, 0),
+            },
+        );
+        for subject in [Some(subject_id), Some(Uuid::new_v4()), None] {
+            cold.record_decision(&DecisionRecord {
+                subject_id: subject,
+                request_id: None,
+                request: serde_json::Value::Null,
+                decision: Decision::Allow,
+                decision_code: "OK".to_string(),
+                policy_version: "v1".to_string(),
+                evidence: vec![],
+                latency_ms: 1,
+            })
+            .await
+            .unwrap();
+        }
+        let storage = TieredStorage::new(cold, Duration::hours(48), 100);
+
+        assert_eq!(storage.warm_up(Duration::hours(1)).await.unwrap(), 2);
+        assert_eq!(storage.cached_subjects(), 2);
+        assert_eq!(storage.warm_up(Duration::hours(1)).await.unwrap(), 0);
+
+        let volume = storage
+            .get_rolling_volume(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(volume, Decimal::new(1000, 0));
+    }
+
+    #[tokio::test]
+    async fn test_long_windows_use_backing_store() {
+        let cold = Arc::new(MockStorage::new());
+        let subject_id = Uuid::new_v4();
+        cold.set_rolling_volume(subject_id, Decimal::new(7000, 0));
+        let storage = TieredStorage::new(cold, Duration::hours(48), 100);
+
+        let volume = storage
+            .get_rolling_volume(subject_id, Duration::days(7))
+            .await
+            .unwrap();
+        assert_eq!(volume, Decimal::new(7000, 0));
+        assert_eq!(storage.cached_subjects(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_repairs_drift() {
+        let cold = Arc::new(MockStorage::new());
+        let subject_id = Uuid::new_v4();
+        let storage = TieredStorage::new(cold.clone(), Duration::hours(48), 100);
+
+        storage
+            .get_rolling_volume(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+
+        // Written directly to the backing store, bypassing the cache
+        cold.record_transaction(&tx(subject_id, 200)).await.unwrap();
+
+        assert_eq!(storage.reconcile().await.unwrap(), 1);
+        let volume = storage
+            .get_rolling_volume(subject_id, Duration::hours(24))
+            .await
+            .unwrap();
+        assert_eq!(volume, Decimal::new(200, 0));
+    }
+}
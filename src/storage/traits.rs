@@ -1,10 +1,17 @@
 // src/storage/traits.rs
 use async_trait::async_trait;
-use chrono::Duration;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::domain::{Decision, Evidence, Policy, Subject};
+use crate::domain::subject::{KycTier, SubjectAddress};
+use crate::domain::{
+    Annotation, Case, CaseStatus, Decision, DecisionEvent, Evidence, Policy, Subject, TxEvent,
+};
 
 /// Record of a transaction for storage.
 #[derive(Debug, Clone)]
@@ -15,12 +22,74 @@ pub struct TransactionRecord {
     pub amount: Decimal,
     pub usd_value: Decimal,
     pub dest_address: Option<String>,
+    /// ISO country code of the counterparty, when known
+    pub counterparty_geo: Option<String>,
+    /// Account of the subject the transaction was made from, for
+    /// account-level windows
+    pub account_id: Option<String>,
+}
+
+/// Timestamped USD value of a stored transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransactionPoint {
+    pub at: DateTime<Utc>,
+    pub usd_value: Decimal,
+}
+
+/// USD moved in and out of one asset by a subject over a window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetFlow {
+    /// Uppercase asset symbol
+    pub asset: String,
+    pub inbound_usd: Decimal,
+    pub outbound_usd: Decimal,
+}
+
+/// One aggregate of a subject's transactions over a window, fetched with
+/// others by [`StorageRead::get_window_aggregates`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum WindowSpec {
+    /// USD volume, as [`StorageRead::get_rolling_volume`]
+    Volume(Duration),
+    /// Transactions under a USD threshold, as
+    /// [`StorageRead::get_small_tx_count`]
+    SmallCount {
+        window: Duration,
+        threshold: Decimal,
+    },
+    /// Transactions with a counterparty in a country, as
+    /// [`StorageRead::get_counterparty_tx_count`]
+    CounterpartyCount { country: String, window: Duration },
+    /// Distinct destination addresses
+    DistinctDestinations(Duration),
+    /// USD volume of one of the subject's accounts
+    AccountVolume {
+        account_id: String,
+        window: Duration,
+    },
+    /// Native amount of one asset, as [`StorageRead::get_rolling_amount`]
+    Amount { asset: String, window: Duration },
+}
+
+impl WindowSpec {
+    /// Window the aggregate covers.
+    pub fn window(&self) -> Duration {
+        match self {
+            WindowSpec::Volume(window)
+            | WindowSpec::SmallCount { window, .. }
+            | WindowSpec::CounterpartyCount { window, .. }
+            | WindowSpec::DistinctDestinations(window)
+            | WindowSpec::AccountVolume { window, .. }
+            | WindowSpec::Amount { window, .. } => *window,
+        }
+    }
 }
 
 /// Record of a decision for audit logging.
 #[derive(Debug, Clone)]
 pub struct DecisionRecord {
     pub subject_id: Option<Uuid>,
+    pub request_id: Option<String>,
     pub request: serde_json::Value,
     pub decision: Decision,
     pub decision_code: String,
@@ -29,29 +98,233 @@ pub struct DecisionRecord {
     pub latency_ms: u32,
 }
 
-/// Storage trait for persistence operations.
+impl DecisionRecord {
+    /// Payload of the `decision` outbox event for this record.
+    pub fn outbox_payload(&self, decision_id: Uuid) -> serde_json::Value {
+        serde_json::json!({
+            "decision_id": decision_id,
+            "request_id": self.request_id,
+            "subject_id": self.subject_id,
+            "decision": self.decision,
+            "decision_code": self.decision_code,
+            "policy_version": self.policy_version,
+            "evidence": self.evidence,
+        })
+    }
+}
+
+/// Deposit held until it has enough confirmations for finality.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingDeposit {
+    pub subject_id: Uuid,
+    /// The held event; `confirmations` is updated as they are reported
+    pub event: TxEvent,
+    pub request_id: Option<String>,
+    pub request: serde_json::Value,
+    /// Most severe streaming rule outcome when the deposit was held.
+    /// Streaming rules are not rerun on release, since the transaction is
+    /// already counted in its windows.
+    pub streaming_decision: Decision,
+    pub streaming_evidence: Vec<Evidence>,
+    pub held_at: DateTime<Utc>,
+}
+
+/// Rule turned off through the admin kill-switch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleSwitch {
+    pub rule_id: String,
+    pub disabled_at: DateTime<Utc>,
+    /// Who disabled it
+    pub actor: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Subject whose KYC tier was set out-of-band.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KycOverride {
+    pub user_id: String,
+    pub kyc_tier: KycTier,
+}
+
+/// Address on the internal blocklist, screened alongside sanctions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlocklistEntry {
+    /// Address, lowercase
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub added_at: DateTime<Utc>,
+    /// Who added it
+    pub actor: String,
+}
+
+/// Decision read back from the audit log.
+#[derive(Debug, Clone)]
+pub struct StoredDecision {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub record: DecisionRecord,
+}
+
+/// Side-effect event awaiting delivery by the outbox relay.
+#[derive(Debug, Clone)]
+pub struct OutboxEvent {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    /// Failed delivery attempts so far
+    pub attempts: u32,
+}
+
+/// Weight of the newest transaction in a subject's typical size.
+pub const TX_SIZE_EWMA_ALPHA: f64 = 0.1;
+
+/// Typical transaction size of a subject.
+///
+/// An exponentially weighted moving average of log USD values, i.e. a
+/// geometric mean. For the skewed amounts seen in practice it follows the
+/// median, and a single large transfer barely moves it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TxSizeProfile {
+    pub typical_usd: Decimal,
+    /// Transactions folded in so far
+    pub samples: u32,
+}
+
+impl TxSizeProfile {
+    /// Fold a transaction into `profile`. Transactions without a positive
+    /// USD value are ignored.
+    pub fn observe(profile: Option<TxSizeProfile>, usd_value: Decimal) -> Option<TxSizeProfile> {
+        let Some(value) = usd_value.to_f64().filter(|v| *v > 0.0) else {
+            return profile;
+        };
+
+        Some(match profile {
+            None => TxSizeProfile {
+                typical_usd: usd_value,
+                samples: 1,
+            },
+            Some(p) => {
+                let previous = p.typical_usd.to_f64().unwrap_or(value);
+                let typical = ((1.0 - TX_SIZE_EWMA_ALPHA) * previous.ln()
+                    + TX_SIZE_EWMA_ALPHA * value.ln())
+                .exp();
+                TxSizeProfile {
+                    typical_usd: Decimal::from_f64_retain(typical)
+                        .map(|d| d.round_dp(2))
+                        .unwrap_or(p.typical_usd),
+                    samples: p.samples.saturating_add(1),
+                }
+            }
+        })
+    }
+}
+
+/// Decision and rule-trigger counts for one tenant on one UTC day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct UsageRecord {
+    pub tenant: String,
+    pub day: NaiveDate,
+    pub decisions: u64,
+    /// Trigger counts by rule ID
+    pub rule_hits: BTreeMap<String, u64>,
+}
+
+/// Read half of [`Storage`]: lookups and window aggregates.
+///
+/// Streaming rules are handed only this half, so evaluating a rule can
+/// never write.
 #[async_trait]
-pub trait Storage: Send + Sync {
+pub trait StorageRead: Send + Sync {
     // Subjects
     async fn get_subject_by_user_id(
         &self,
         user_id: &str,
     ) -> anyhow::Result<Option<(Uuid, Subject)>>;
-    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid>;
+    /// Addresses linked to a subject, oldest first.
+    async fn get_subject_addresses(&self, subject_id: Uuid) -> anyhow::Result<Vec<SubjectAddress>>;
+    /// Subjects whose tier was set by `set_kyc_tier`, ordered by user ID.
+    async fn get_kyc_overrides(&self) -> anyhow::Result<Vec<KycOverride>>;
 
     // Transactions (for streaming rules)
-    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid>;
     async fn get_rolling_volume(
         &self,
         subject_id: Uuid,
         window: Duration,
     ) -> anyhow::Result<Decimal>;
+    /// USD volume of the window ending at `at`, rebuilt from stored
+    /// transactions, for investigating past decisions.
+    async fn get_rolling_volume_at(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Decimal> {
+        let values = self
+            .get_window_aggregates_at(subject_id, &[WindowSpec::Volume(window)], at)
+            .await?;
+        Ok(values.first().copied().unwrap_or_default())
+    }
+    /// Sum of native `amount` for one asset within the window; the asset
+    /// symbol is matched case-insensitively.
+    async fn get_rolling_amount(
+        &self,
+        subject_id: Uuid,
+        asset: &str,
+        window: Duration,
+    ) -> anyhow::Result<Decimal>;
     async fn get_small_tx_count(
         &self,
         subject_id: Uuid,
         window: Duration,
         threshold: Decimal,
     ) -> anyhow::Result<u32>;
+    /// Transactions within the window, oldest first.
+    async fn get_recent_transactions(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<TransactionPoint>>;
+    /// Transaction counts by UTC hour of day over the window.
+    async fn get_hourly_activity(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<[u32; 24]>;
+    /// Transactions within the window whose counterparty is in `country`.
+    async fn get_counterparty_tx_count(
+        &self,
+        subject_id: Uuid,
+        country: &str,
+        window: Duration,
+    ) -> anyhow::Result<u32>;
+    /// Inbound and outbound USD per asset within the window, ordered by
+    /// asset.
+    async fn get_asset_flows(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<AssetFlow>>;
+    /// Several window aggregates in one round trip, in the order of
+    /// `specs`. Counts are returned as whole numbers.
+    async fn get_window_aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+    ) -> anyhow::Result<Vec<Decimal>>;
+    /// As [`StorageRead::get_window_aggregates`], over windows ending at
+    /// `at` instead of storage's now. Windows with a fixed start, such as
+    /// calendar days, are read this way so the boundary doesn't depend on
+    /// the database's clock.
+    async fn get_window_aggregates_at(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Decimal>>;
+    /// Typical transaction size, or None before the first transaction.
+    async fn get_tx_size_profile(&self, subject_id: Uuid) -> anyhow::Result<Option<TxSizeProfile>>;
 
     // Sanctions
     async fn get_all_sanctions(&self) -> anyhow::Result<Vec<String>>;
@@ -59,8 +332,213 @@ pub trait Storage: Send + Sync {
 
     // Policies
     async fn get_active_policy(&self) -> anyhow::Result<Option<Policy>>;
+    async fn get_policy(&self, version: &str) -> anyhow::Result<Option<Policy>>;
+
+    // Decisions (audit log)
+    async fn count_recent_decisions(
+        &self,
+        subject_id: Uuid,
+        min_decision: Decision,
+        window: Duration,
+    ) -> anyhow::Result<u32>;
+    /// Decisions created in `[from, to)`, ordered by creation time then ID,
+    /// starting after the `(created_at, id)` cursor if given.
+    async fn get_decisions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>>;
+    /// Most recent decision recorded for a request ID.
+    async fn get_decision_by_request_id(
+        &self,
+        request_id: &str,
+    ) -> anyhow::Result<Option<StoredDecision>>;
+    /// A subject's decisions created at or before `at`, newest first.
+    async fn get_subject_decisions(
+        &self,
+        subject_id: Uuid,
+        at: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>>;
+    /// The most recent decisions across all subjects, newest first.
+    async fn get_recent_decisions(&self, limit: u32) -> anyhow::Result<Vec<StoredDecision>>;
+
+    // Outbox
+    /// Undelivered outbox events that aren't dead-lettered, oldest first.
+    async fn pending_outbox(&self, limit: u32) -> anyhow::Result<Vec<OutboxEvent>>;
+
+    // Deposits held for finality
+    async fn get_pending_deposit(&self, event_id: &str) -> anyhow::Result<Option<PendingDeposit>>;
+
+    // Cases
+    async fn get_case(&self, case_id: Uuid) -> anyhow::Result<Option<Case>>;
+    /// Cases, most recently updated first, optionally only those with
+    /// the given status.
+    async fn list_cases(&self, status: Option<CaseStatus>, limit: u32)
+        -> anyhow::Result<Vec<Case>>;
+
+    // Subject annotations
+    /// A subject's annotations, newest first.
+    async fn get_annotations(
+        &self,
+        subject_id: Uuid,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Annotation>>;
+
+    // Rule kill-switch
+    /// Currently disabled rules, ordered by rule ID.
+    async fn get_disabled_rules(&self) -> anyhow::Result<Vec<RuleSwitch>>;
+
+    // Internal blocklist
+    /// Blocklisted addresses, ordered by address.
+    async fn get_blocklist(&self) -> anyhow::Result<Vec<BlocklistEntry>>;
+
+    // Tenant usage
+    /// Decisions counted for the tenant on the current UTC day.
+    async fn get_daily_usage(&self, tenant: &str) -> anyhow::Result<u64>;
+    /// Usage for every tenant and day from `from` to `to` inclusive,
+    /// ordered by day then tenant.
+    async fn get_usage(&self, from: NaiveDate, to: NaiveDate) -> anyhow::Result<Vec<UsageRecord>>;
+
+    /// Returns true while the backend is known to be unavailable, so callers
+    /// can switch to degraded handling without waiting on a failed call.
+    fn is_degraded(&self) -> bool {
+        false
+    }
+}
+
+/// Write half of [`Storage`]: everything that records or changes state.
+#[async_trait]
+pub trait StorageWrite: Send + Sync {
+    // Subjects
+    async fn upsert_subject(&self, subject: &Subject) -> anyhow::Result<Uuid>;
+    /// Set a subject's KYC tier out-of-band. Later upserts keep this tier
+    /// rather than the request-supplied one. Returns false if the subject
+    /// is unknown.
+    async fn set_kyc_tier(&self, user_id: &str, tier: &KycTier) -> anyhow::Result<bool>;
+
+    /// Link an address to a subject, or update a linked one. `verified` and
+    /// `label` are left unchanged when None (false and unset for a new
+    /// address); an empty label clears it. Returns the stored address.
+    async fn save_subject_address(
+        &self,
+        subject_id: Uuid,
+        address: &str,
+        verified: Option<bool>,
+        label: Option<&str>,
+    ) -> anyhow::Result<SubjectAddress>;
+
+    /// Unlink an address from a subject. Returns false if it was not
+    /// linked.
+    async fn remove_subject_address(&self, subject_id: Uuid, address: &str)
+        -> anyhow::Result<bool>;
+
+    // Transactions (for streaming rules)
+    /// Record a transaction and fold it into the subject's typical size.
+    async fn record_transaction(&self, tx: &TransactionRecord) -> anyhow::Result<Uuid>;
+
+    // Policies
     async fn set_active_policy(&self, policy: &Policy) -> anyhow::Result<()>;
 
     // Decisions (audit log)
     async fn record_decision(&self, decision: &DecisionRecord) -> anyhow::Result<Uuid>;
+
+    // Outbox
+    /// Record the transaction, the decision and its `decision` outbox event
+    /// atomically, linking the decision to the subject's active case and
+    /// opening one for a `REVIEW`. Returns the decision ID.
+    async fn record_outcome(
+        &self,
+        tx: &TransactionRecord,
+        decision: &DecisionRecord,
+    ) -> anyhow::Result<Uuid>;
+    /// Record a decision made without a transaction, such as a sweep's,
+    /// with its `decision` outbox event atomically. It is linked to cases
+    /// as in `record_outcome`, and a case it opens gets `note`. Returns the
+    /// decision ID and whether a case was opened.
+    async fn record_decision_outcome(
+        &self,
+        decision: &DecisionRecord,
+        note: Option<&str>,
+    ) -> anyhow::Result<(Uuid, bool)>;
+    /// Claim up to `limit` pending events no other relay holds, oldest
+    /// first, for `lease`. A claimed event is skipped by other claims
+    /// until it is delivered, dead-lettered or its lease runs out.
+    async fn claim_outbox(&self, limit: u32, lease: Duration) -> anyhow::Result<Vec<OutboxEvent>>;
+    async fn mark_outbox_delivered(&self, id: Uuid) -> anyhow::Result<()>;
+    /// Count a failed delivery. The event stays claimed until its lease
+    /// runs out, which spaces out retries.
+    async fn mark_outbox_failed(&self, id: Uuid, error: &str) -> anyhow::Result<()>;
+    /// Count a failed delivery and give up on the event.
+    async fn mark_outbox_dead(&self, id: Uuid, error: &str) -> anyhow::Result<()>;
+
+    // Deposits held for finality
+    /// Store a held deposit, replacing any with the same event ID.
+    async fn save_pending_deposit(&self, deposit: &PendingDeposit) -> anyhow::Result<()>;
+    /// Record the final decision for a held deposit with a `decision_event`
+    /// outbox event, and stop holding it, atomically. The decision is linked
+    /// to cases as in `record_outcome`. Returns the decision ID, or None if
+    /// the deposit was not held.
+    async fn resolve_pending_deposit(
+        &self,
+        event_id: &str,
+        decision: &DecisionRecord,
+        event: &DecisionEvent,
+    ) -> anyhow::Result<Option<Uuid>>;
+
+    // Cases
+    /// Open a case for a subject by hand. Returns None if the subject
+    /// already has an active case.
+    async fn create_case(
+        &self,
+        subject_id: Uuid,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>>;
+    /// Move a case from `from` to `to`, replacing its note if one is given.
+    /// Returns None if the case is unknown or no longer in `from`.
+    async fn update_case_status(
+        &self,
+        case_id: Uuid,
+        from: CaseStatus,
+        to: CaseStatus,
+        note: Option<&str>,
+    ) -> anyhow::Result<Option<Case>>;
+
+    // Subject annotations
+    async fn add_annotation(&self, annotation: &Annotation) -> anyhow::Result<()>;
+
+    // Rule kill-switch
+    /// Disable a rule until it is enabled again. Every toggle is kept in
+    /// an audit log, and other replicas are notified where supported.
+    async fn disable_rule(&self, switch: &RuleSwitch) -> anyhow::Result<()>;
+    /// Re-enable a disabled rule; enabling an active rule is a no-op.
+    async fn enable_rule(&self, rule_id: &str, actor: &str) -> anyhow::Result<()>;
+
+    // Internal blocklist
+    /// Add entries, replacing any for the same address. With `replace`,
+    /// addresses not in `entries` are removed. Other replicas are notified
+    /// where supported.
+    async fn save_blocklist(&self, entries: &[BlocklistEntry], replace: bool)
+        -> anyhow::Result<()>;
+
+    // Tenant usage
+    /// Count one decision, and the rules it triggered, against the tenant's
+    /// usage for the current UTC day.
+    async fn record_usage(&self, tenant: &str, rules_hit: &[String]) -> anyhow::Result<()>;
+
+    // Request nonces
+    /// Claim a signed request's nonce until `expires_at`. Returns false if
+    /// it is already claimed and hasn't expired, meaning a replay.
+    async fn claim_nonce(&self, nonce: &str, expires_at: DateTime<Utc>) -> anyhow::Result<bool>;
 }
+
+/// Storage trait for persistence operations.
+///
+/// Implemented for every type with both halves. A read-only backend, such
+/// as a replica serving investigation queries, implements only
+/// [`StorageRead`].
+pub trait Storage: StorageRead + StorageWrite {}
+
+impl<T: StorageRead + StorageWrite + ?Sized> Storage for T {}
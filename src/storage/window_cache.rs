@@ -0,0 +1,548 @@
+// src/storage/window_cache.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use parking_lot::Mutex;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::domain::{Annotation, Case, CaseStatus, Decision, Policy, Subject, SubjectAddress};
+
+use super::traits::{
+    AssetFlow, BlocklistEntry, KycOverride, OutboxEvent, PendingDeposit, RuleSwitch, StorageRead,
+    StoredDecision, TransactionPoint, TxSizeProfile, UsageRecord, WindowSpec,
+};
+
+/// Memoized results of one kind of query, by its arguments.
+type Memo<K, V> = Mutex<HashMap<K, V>>;
+
+/// Per-request memo of window aggregates.
+///
+/// Streaming rules evaluated for one request often need the same window,
+/// such as the daily volume. Passing this to each rule instead of the
+/// shared storage fetches every distinct window query once per request.
+/// Errors are not cached. The cache only reads; record the outcome
+/// against the storage itself, then drop the cache at the end of the
+/// request.
+///
+/// `prefetch` fills the memo for several aggregates with one query, so
+/// rules reading them afterwards don't each make a round trip.
+pub struct WindowCache {
+    inner: Arc<dyn StorageRead>,
+    volumes: Memo<(Uuid, Duration), Decimal>,
+    amounts: Memo<(Uuid, String, Duration), Decimal>,
+    small_counts: Memo<(Uuid, Duration, Decimal), u32>,
+    counterparty_counts: Memo<(Uuid, String, Duration), u32>,
+    asset_flows: Memo<(Uuid, Duration), Vec<AssetFlow>>,
+    transactions: Memo<(Uuid, Duration), Vec<TransactionPoint>>,
+    hourly: Memo<(Uuid, Duration), [u32; 24]>,
+    decision_counts: Memo<(Uuid, Decision, Duration), u32>,
+    distinct_destinations: Memo<(Uuid, Duration), Decimal>,
+    account_volumes: Memo<(Uuid, String, Duration), Decimal>,
+}
+
+impl WindowCache {
+    /// Memoize window queries against `inner`.
+    pub fn new(inner: Arc<dyn StorageRead>) -> Self {
+        WindowCache {
+            inner,
+            volumes: Mutex::default(),
+            amounts: Mutex::default(),
+            small_counts: Mutex::default(),
+            counterparty_counts: Mutex::default(),
+            asset_flows: Mutex::default(),
+            transactions: Mutex::default(),
+            hourly: Mutex::default(),
+            decision_counts: Mutex::default(),
+            distinct_destinations: Mutex::default(),
+            account_volumes: Mutex::default(),
+        }
+    }
+
+    /// Fetch the aggregates not yet memoized in one call.
+    pub async fn prefetch(&self, subject_id: Uuid, specs: &[WindowSpec]) -> anyhow::Result<()> {
+        self.get_window_aggregates(subject_id, specs)
+            .await
+            .map(|_| ())
+    }
+
+    fn cached(&self, subject_id: Uuid, spec: &WindowSpec) -> Option<Decimal> {
+        match spec {
+            WindowSpec::Volume(window) => self.volumes.lock().get(&(subject_id, *window)).copied(),
+            WindowSpec::SmallCount { window, threshold } => self
+                .small_counts
+                .lock()
+                .get(&(subject_id, *window, *threshold))
+                .map(|&count| count.into()),
+            WindowSpec::CounterpartyCount { country, window } => self
+                .counterparty_counts
+                .lock()
+                .get(&(subject_id, country.clone(), *window))
+                .map(|&count| count.into()),
+            WindowSpec::DistinctDestinations(window) => self
+                .distinct_destinations
+                .lock()
+                .get(&(subject_id, *window))
+                .copied(),
+            WindowSpec::AccountVolume { account_id, window } => self
+                .account_volumes
+                .lock()
+                .get(&(subject_id, account_id.clone(), *window))
+                .copied(),
+            WindowSpec::Amount { asset, window } => self
+                .amounts
+                .lock()
+                .get(&(subject_id, asset.to_uppercase(), *window))
+                .copied(),
+        }
+    }
+
+    fn remember(&self, subject_id: Uuid, spec: &WindowSpec, value: Decimal) {
+        let count = || value.to_u32().unwrap_or(u32::MAX);
+        match spec {
+            WindowSpec::Volume(window) => {
+                self.volumes.lock().insert((subject_id, *window), value);
+            }
+            WindowSpec::SmallCount { window, threshold } => {
+                self.small_counts
+                    .lock()
+                    .insert((subject_id, *window, *threshold), count());
+            }
+            WindowSpec::CounterpartyCount { country, window } => {
+                self.counterparty_counts
+                    .lock()
+                    .insert((subject_id, country.clone(), *window), count());
+            }
+            WindowSpec::DistinctDestinations(window) => {
+                self.distinct_destinations
+                    .lock()
+                    .insert((subject_id, *window), value);
+            }
+            WindowSpec::AccountVolume { account_id, window } => {
+                self.account_volumes
+                    .lock()
+                    .insert((subject_id, account_id.clone(), *window), value);
+            }
+            WindowSpec::Amount { asset, window } => {
+                self.amounts
+                    .lock()
+                    .insert((subject_id, asset.to_uppercase(), *window), value);
+            }
+        }
+    }
+
+    /// Forget everything fetched so far.
+    pub fn clear(&self) {
+        self.volumes.lock().clear();
+        self.amounts.lock().clear();
+        self.small_counts.lock().clear();
+        self.counterparty_counts.lock().clear();
+        self.asset_flows.lock().clear();
+        self.transactions.lock().clear();
+        self.hourly.lock().clear();
+        self.decision_counts.lock().clear();
+        self.distinct_destinations.lock().clear();
+        self.account_volumes.lock().clear();
+    }
+}
+
+/// Return the memoized value for `key`, fetching it on a miss.
+async fn memo<K, V, F>(map: &Memo<K, V>, key: K, fetch: F) -> anyhow::Result<V>
+where
+    K: Eq + Hash,
+    V: Clone,
+    F: Future<Output = anyhow::Result<V>>,
+{
+    if let Some(value) = map.lock().get(&key) {
+        return Ok(value.clone());
+    }
+    let value = fetch.await?;
+    map.lock().insert(key, value.clone());
+    Ok(value)
+}
+
+#[async_trait]
+impl StorageRead for WindowCache {
+    async fn get_subject_by_user_id(
+        &self,
+        user_id: &str,
+    ) -> anyhow::Result<Option<(Uuid, Subject)>> {
+        self.inner.get_subject_by_user_id(user_id).await
+    }
+
+    async fn get_subject_addresses(&self, subject_id: Uuid) -> anyhow::Result<Vec<SubjectAddress>> {
+        self.inner.get_subject_addresses(subject_id).await
+    }
+
+    async fn get_kyc_overrides(&self) -> anyhow::Result<Vec<KycOverride>> {
+        self.inner.get_kyc_overrides().await
+    }
+
+    async fn get_rolling_volume(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        memo(
+            &self.volumes,
+            (subject_id, window),
+            self.inner.get_rolling_volume(subject_id, window),
+        )
+        .await
+    }
+
+    async fn get_rolling_amount(
+        &self,
+        subject_id: Uuid,
+        asset: &str,
+        window: Duration,
+    ) -> anyhow::Result<Decimal> {
+        memo(
+            &self.amounts,
+            (subject_id, asset.to_uppercase(), window),
+            self.inner.get_rolling_amount(subject_id, asset, window),
+        )
+        .await
+    }
+
+    async fn get_small_tx_count(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+        threshold: Decimal,
+    ) -> anyhow::Result<u32> {
+        memo(
+            &self.small_counts,
+            (subject_id, window, threshold),
+            self.inner.get_small_tx_count(subject_id, window, threshold),
+        )
+        .await
+    }
+
+    async fn get_counterparty_tx_count(
+        &self,
+        subject_id: Uuid,
+        country: &str,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        memo(
+            &self.counterparty_counts,
+            (subject_id, country.to_string(), window),
+            self.inner
+                .get_counterparty_tx_count(subject_id, country, window),
+        )
+        .await
+    }
+
+    async fn get_asset_flows(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<AssetFlow>> {
+        memo(
+            &self.asset_flows,
+            (subject_id, window),
+            self.inner.get_asset_flows(subject_id, window),
+        )
+        .await
+    }
+
+    async fn get_recent_transactions(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<Vec<TransactionPoint>> {
+        memo(
+            &self.transactions,
+            (subject_id, window),
+            self.inner.get_recent_transactions(subject_id, window),
+        )
+        .await
+    }
+
+    async fn get_hourly_activity(
+        &self,
+        subject_id: Uuid,
+        window: Duration,
+    ) -> anyhow::Result<[u32; 24]> {
+        memo(
+            &self.hourly,
+            (subject_id, window),
+            self.inner.get_hourly_activity(subject_id, window),
+        )
+        .await
+    }
+
+    async fn get_window_aggregates(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+    ) -> anyhow::Result<Vec<Decimal>> {
+        let cached: Vec<Option<Decimal>> = specs
+            .iter()
+            .map(|spec| self.cached(subject_id, spec))
+            .collect();
+        let mut missing: Vec<WindowSpec> = Vec::new();
+        for (spec, value) in specs.iter().zip(&cached) {
+            if value.is_none() && !missing.contains(spec) {
+                missing.push(spec.clone());
+            }
+        }
+
+        let mut fetched: HashMap<&WindowSpec, Decimal> = HashMap::new();
+        if !missing.is_empty() {
+            let values = self
+                .inner
+                .get_window_aggregates(subject_id, &missing)
+                .await?;
+            anyhow::ensure!(
+                values.len() == missing.len(),
+                "expected {} window aggregates, got {}",
+                missing.len(),
+                values.len()
+            );
+            for (spec, value) in missing.iter().zip(values) {
+                self.remember(subject_id, spec, value);
+                fetched.insert(spec, value);
+            }
+        }
+
+        Ok(specs
+            .iter()
+            .zip(cached)
+            .map(|(spec, value)| {
+                value
+                    .or_else(|| fetched.get(spec).copied())
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    /// Not memoized, since the end of the windows moves with each call.
+    async fn get_window_aggregates_at(
+        &self,
+        subject_id: Uuid,
+        specs: &[WindowSpec],
+        at: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<Decimal>> {
+        self.inner
+            .get_window_aggregates_at(subject_id, specs, at)
+            .await
+    }
+
+    async fn get_tx_size_profile(&self, subject_id: Uuid) -> anyhow::Result<Option<TxSizeProfile>> {
+        self.inner.get_tx_size_profile(subject_id).await
+    }
+
+    async fn get_all_sanctions(&self) -> anyhow::Result<Vec<String>> {
+        self.inner.get_all_sanctions().await
+    }
+
+    async fn is_sanctioned(&self, address: &str) -> anyhow::Result<bool> {
+        self.inner.is_sanctioned(address).await
+    }
+
+    async fn get_active_policy(&self) -> anyhow::Result<Option<Policy>> {
+        self.inner.get_active_policy().await
+    }
+
+    async fn get_policy(&self, version: &str) -> anyhow::Result<Option<Policy>> {
+        self.inner.get_policy(version).await
+    }
+
+    async fn count_recent_decisions(
+        &self,
+        subject_id: Uuid,
+        min_decision: Decision,
+        window: Duration,
+    ) -> anyhow::Result<u32> {
+        memo(
+            &self.decision_counts,
+            (subject_id, min_decision, window),
+            self.inner
+                .count_recent_decisions(subject_id, min_decision, window),
+        )
+        .await
+    }
+
+    async fn get_decisions(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        after: Option<(DateTime<Utc>, Uuid)>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        self.inner.get_decisions(from, to, after, limit).await
+    }
+
+    async fn get_decision_by_request_id(
+        &self,
+        request_id: &str,
+    ) -> anyhow::Result<Option<StoredDecision>> {
+        self.inner.get_decision_by_request_id(request_id).await
+    }
+
+    async fn get_subject_decisions(
+        &self,
+        subject_id: Uuid,
+        at: DateTime<Utc>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<StoredDecision>> {
+        self.inner
+            .get_subject_decisions(subject_id, at, limit)
+            .await
+    }
+
+    async fn get_recent_decisions(&self, limit: u32) -> anyhow::Result<Vec<StoredDecision>> {
+        self.inner.get_recent_decisions(limit).await
+    }
+
+    async fn pending_outbox(&self, limit: u32) -> anyhow::Result<Vec<OutboxEvent>> {
+        self.inner.pending_outbox(limit).await
+    }
+
+    async fn get_pending_deposit(&self, event_id: &str) -> anyhow::Result<Option<PendingDeposit>> {
+        self.inner.get_pending_deposit(event_id).await
+    }
+
+    async fn get_case(&self, case_id: Uuid) -> anyhow::Result<Option<Case>> {
+        self.inner.get_case(case_id).await
+    }
+
+    async fn list_cases(
+        &self,
+        status: Option<CaseStatus>,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Case>> {
+        self.inner.list_cases(status, limit).await
+    }
+
+    async fn get_annotations(
+        &self,
+        subject_id: Uuid,
+        limit: u32,
+    ) -> anyhow::Result<Vec<Annotation>> {
+        self.inner.get_annotations(subject_id, limit).await
+    }
+
+    async fn get_disabled_rules(&self) -> anyhow::Result<Vec<RuleSwitch>> {
+        self.inner.get_disabled_rules().await
+    }
+
+    async fn get_blocklist(&self) -> anyhow::Result<Vec<BlocklistEntry>> {
+        self.inner.get_blocklist().await
+    }
+
+    async fn get_daily_usage(&self, tenant: &str) -> anyhow::Result<u64> {
+        self.inner.get_daily_usage(tenant).await
+    }
+
+    async fn get_usage(&self, from: NaiveDate, to: NaiveDate) -> anyhow::Result<Vec<UsageRecord>> {
+        self.inner.get_usage(from, to).await
+    }
+
+    fn is_degraded(&self) -> bool {
+        self.inner.is_degraded()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MockStorage;
+
+    #[tokio::test]
+    async fn test_window_fetched_once_per_request() {
+        let storage = Arc::new(MockStorage::new());
+        let subject_id = Uuid::new_v4();
+        storage.set_rolling_volume(subject_id, Decimal::new(100, 0));
+
+        let cache = WindowCache::new(storage.clone());
+        let day = Duration::hours(24);
+        assert_eq!(
+            cache.get_rolling_volume(subject_id, day).await.unwrap(),
+            Decimal::new(100, 0)
+        );
+
+        // The second rule asking for the same window sees the memoized value
+        storage.set_rolling_volume(subject_id, Decimal::new(500, 0));
+        assert_eq!(
+            cache.get_rolling_volume(subject_id, day).await.unwrap(),
+            Decimal::new(100, 0)
+        );
+
+        // A different window is a different query
+        assert_eq!(
+            cache
+                .get_rolling_volume(subject_id, Duration::days(7))
+                .await
+                .unwrap(),
+            Decimal::new(500, 0)
+        );
+
+        // Clearing forgets the memoized window
+        cache.clear();
+        assert_eq!(
+            cache.get_rolling_volume(subject_id, day).await.unwrap(),
+            Decimal::new(500, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prefetch_fills_memo() {
+        let storage = Arc::new(MockStorage::new());
+        let subject_id = Uuid::new_v4();
+        storage.set_rolling_volume(subject_id, Decimal::new(100, 0));
+        storage.set_small_tx_count(subject_id, 3);
+
+        let cache = WindowCache::new(storage.clone());
+        let day = Duration::hours(24);
+        let threshold = Decimal::new(1000, 0);
+        cache
+            .prefetch(
+                subject_id,
+                &[
+                    WindowSpec::Volume(day),
+                    WindowSpec::SmallCount {
+                        window: day,
+                        threshold,
+                    },
+                    WindowSpec::Volume(day),
+                ],
+            )
+            .await
+            .unwrap();
+
+        // Rules reading the prefetched windows see the memoized values
+        storage.set_rolling_volume(subject_id, Decimal::new(500, 0));
+        storage.set_small_tx_count(subject_id, 9);
+        assert_eq!(
+            cache.get_rolling_volume(subject_id, day).await.unwrap(),
+            Decimal::new(100, 0)
+        );
+        assert_eq!(
+            cache
+                .get_small_tx_count(subject_id, day, threshold)
+                .await
+                .unwrap(),
+            3
+        );
+
+        // Only the windows not yet memoized are fetched
+        let values = cache
+            .get_window_aggregates(
+                subject_id,
+                &[
+                    WindowSpec::Volume(day),
+                    WindowSpec::Volume(Duration::days(7)),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_eq!(values, vec![Decimal::new(100, 0), Decimal::new(500, 0)]);
+    }
+}
@@ -0,0 +1,454 @@
+//! Scheduled re-evaluation of recently active subjects.
+//!
+//! Some risk only becomes visible between transactions: a counterparty is
+//! sanctioned after the fact, or weekly aggregates cross a limit lowered by
+//! a policy update. On each sweep, every subject with a decision in the
+//! lookback window has its latest recorded request re-evaluated against the
+//! rules marked `sweep: true`. A hit records a `REVIEW` decision and opens a
+//! case for the subject.
+//!
+//! The request is re-evaluated with a zero amount, since its transaction
+//! is already part of the subject's history; rules judge the subject's
+//! accumulated activity alone. A subject is flagged at most once per
+//! recorded request.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::interval;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::api::request::DecisionRequest;
+use crate::domain::{Decision, Evidence};
+use crate::inline_engine;
+use crate::rules::{RuleSet, RuleSwitches};
+use crate::storage::{DecisionRecord, Storage, StoredDecision};
+
+/// Request ID prefix of decisions recorded by the sweep, followed by the ID
+/// of the decision that was re-evaluated.
+pub const SWEEP_REQUEST_PREFIX: &str = "sweep:";
+
+/// Decisions read from storage per page.
+const PAGE_SIZE: u32 = 500;
+
+/// When and how widely to sweep.
+#[derive(Debug, Clone)]
+pub struct SweepOptions {
+    /// Time between sweeps
+    pub interval: Duration,
+    /// Subjects with a decision this recent are swept
+    pub lookback: chrono::Duration,
+    /// Most recently active subjects swept per run
+    pub max_subjects: usize,
+}
+
+/// Outcome of one sweep.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SweepSummary {
+    /// Subjects re-evaluated
+    pub swept: u32,
+    /// Subjects whose latest request could not be re-evaluated
+    pub skipped: u32,
+    /// Subjects flagged for review
+    pub flagged: u32,
+    /// Cases opened; flagged subjects may already have an active case
+    pub cases_opened: u32,
+}
+
+/// Periodically re-evaluates recently active subjects.
+pub struct Sweeper {
+    storage: Arc<dyn Storage>,
+    ruleset_rx: watch::Receiver<Arc<RuleSet>>,
+    options: SweepOptions,
+    switches: Option<Arc<RuleSwitches>>,
+}
+
+impl Sweeper {
+    /// Create a sweeper using the latest rule set from `ruleset_rx`.
+    pub fn new(
+        storage: Arc<dyn Storage>,
+        ruleset_rx: watch::Receiver<Arc<RuleSet>>,
+        options: SweepOptions,
+    ) -> Self {
+        Sweeper {
+            storage,
+            ruleset_rx,
+            options,
+            switches: None,
+        }
+    }
+
+    /// Skip rules turned off by the kill-switch.
+    pub fn with_rule_switches(mut self, switches: Arc<RuleSwitches>) -> Self {
+        self.switches = Some(switches);
+        self
+    }
+
+    /// Run one sweep.
+    pub async fn sweep_once(&self) -> anyhow::Result<SweepSummary> {
+        let ruleset = self.ruleset_rx.borrow().clone();
+        let mut summary = SweepSummary::default();
+        if ruleset.sweep.is_empty() {
+            return Ok(summary);
+        }
+
+        for stored in self.latest_requests().await? {
+            let Some(subject_id) = stored.record.subject_id else {
+                continue;
+            };
+            let Ok(request) =
+                serde_json::from_value::<DecisionRequest>(stored.record.request.clone())
+            else {
+                summary.skipped += 1;
+                continue;
+            };
+            summary.swept += 1;
+
+            let evidence = self.evaluate(&ruleset, &request, subject_id).await;
+            if evidence.is_empty() {
+                continue;
+            }
+            summary.flagged += 1;
+            if self.flag(&ruleset, &stored, subject_id, evidence).await? {
+                summary.cases_opened += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// The latest request of each subject active in the lookback window
+    /// that the sweep hasn't flagged since, most recent first.
+    async fn latest_requests(&self) -> anyhow::Result<Vec<StoredDecision>> {
+        let now = Utc::now();
+        let mut latest: HashMap<Uuid, StoredDecision> = HashMap::new();
+        let mut flagged: HashMap<Uuid, DateTime<Utc>> = HashMap::new();
+        let mut after = None;
+
+        loop {
+            let page = self
+                .storage
+                .get_decisions(now - self.options.lookback, now, after, PAGE_SIZE)
+                .await?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            after = Some((last.created_at, last.id));
+            let full = page.len() == PAGE_SIZE as usize;
+
+            for stored in page {
+                let Some(subject_id) = stored.record.subject_id else {
+                    continue;
+                };
+                let swept = stored
+                    .record
+                    .request_id
+                    .as_deref()
+                    .is_some_and(|id| id.starts_with(SWEEP_REQUEST_PREFIX));
+                if swept {
+                    flagged.insert(subject_id, stored.created_at);
+                } else {
+                    // Pages are oldest first, so later requests replace earlier ones
+                    latest.insert(subject_id, stored);
+                }
+            }
+
+            if !full {
+                break;
+            }
+        }
+
+        let mut requests: Vec<StoredDecision> = latest
+            .into_values()
+            .filter(|stored| {
+                let subject_id = stored.record.subject_id.unwrap_or_else(Uuid::nil);
+                flagged
+                    .get(&subject_id)
+                    .is_none_or(|at| *at < stored.created_at)
+            })
+            .collect();
+        requests.sort_by_key(|stored| Reverse(stored.created_at));
+        requests.truncate(self.options.max_subjects);
+        Ok(requests)
+    }
+
+    /// Evidence of the sweep rules that hit for the subject's request.
+    async fn evaluate(
+        &self,
+        ruleset: &RuleSet,
+        request: &DecisionRequest,
+        subject_id: Uuid,
+    ) -> Vec<Evidence> {
+        let mut event = request.to_tx_event();
+        // The transaction is already in the subject's history
+        event.usd_value = Decimal::ZERO;
+        event.amount = "0".to_string();
+
+        let skip = |id: &str| {
+            !ruleset.sweep.contains(id)
+                || !ruleset.features.allows(id, &event.features)
+                || self.switches.as_ref().is_some_and(|s| s.is_disabled(id))
+        };
+
+        let mut evidence =
+            inline_engine::evaluate(&ruleset.inline_for(&event), &event, skip, |_, _| {}).evidence;
+        for rule in ruleset.streaming_for(&event).iter() {
+            if skip(rule.id()) {
+                continue;
+            }
+            match rule
+                .evaluate(&event, subject_id, self.storage.as_ref())
+                .await
+            {
+                Ok(result) if result.hit => evidence.extend(result.evidence),
+                Ok(_) => {}
+                Err(e) => warn!(rule_id = rule.id(), error = %e, "Sweep rule failed"),
+            }
+        }
+        evidence
+    }
+
+    /// Record a review decision for the subject, linked to its active case
+    /// or a new one, with its outbox event. Returns whether a case was
+    /// opened.
+    async fn flag(
+        &self,
+        ruleset: &RuleSet,
+        stored: &StoredDecision,
+        subject_id: Uuid,
+        evidence: Vec<Evidence>,
+    ) -> anyhow::Result<bool> {
+        let rule_ids: Vec<&str> = evidence.iter().map(|e| e.rule_id.as_str()).collect();
+        let note = format!("Scheduled sweep: {}", rule_ids.join(", "));
+        info!(
+            subject_id = %subject_id,
+            decision_id = %stored.id,
+            rules = %rule_ids.join(","),
+            "Sweep flagged subject for review"
+        );
+
+        let decision = DecisionRecord {
+            subject_id: Some(subject_id),
+            request_id: Some(format!("{}{}", SWEEP_REQUEST_PREFIX, stored.id)),
+            request: stored.record.request.clone(),
+            decision: Decision::Review,
+            decision_code: rule_ids[0].to_string(),
+            policy_version: ruleset.policy_version.clone(),
+            evidence,
+            latency_ms: 0,
+        };
+        let (_, opened) = self
+            .storage
+            .record_decision_outcome(&decision, Some(&note))
+            .await?;
+        Ok(opened)
+    }
+
+    /// Start sweeping in the background.
+    pub fn start(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = interval(self.options.interval);
+            // The first tick completes immediately; sweep after a full interval
+            interval.tick().await;
+
+            loop {
+                interval.tick().await;
+
+                if self.storage.is_degraded() {
+                    continue;
+                }
+
+                match self.sweep_once().await {
+                    Ok(summary) if summary.flagged > 0 => info!(
+                        swept = summary.swept,
+                        flagged = summary.flagged,
+                        cases_opened = summary.cases_opened,
+                        "Sweep complete"
+                    ),
+                    Ok(summary) => debug!(swept = summary.swept, "Sweep complete"),
+                    Err(e) => warn!(error = %e, "Sweep failed"),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CaseStatus, Policy};
+    use crate::storage::{MockStorage, StorageRead, StorageWrite, TransactionRecord};
+    use std::collections::HashSet;
+
+    const POLICY: &str = r#"
+policy_version: "sweep-1"
+params:
+  weekly_volume_limit_usd: 1000
+rules:
+  - id: R1_OFAC
+    type: ofac_addr
+    action: REJECT_FATAL
+    sweep: true
+  - id: R4_WEEKLY
+    type: weekly_usd_volume
+    action: REVIEW
+    sweep: true
+  - id: R_JURIS
+    type: jurisdiction_block
+    action: REJECT_FATAL
+    blocked_countries: [US]
+"#;
+
+    fn sweeper(storage: Arc<MockStorage>, sanctions: &[&str]) -> Sweeper {
+        let policy: Policy = serde_yaml::from_str(POLICY).unwrap();
+        let sanctions: HashSet<String> = sanctions.iter().map(|s| s.to_string()).collect();
+        let (_tx, rx) = watch::channel(Arc::new(RuleSet::from_policy(&policy, sanctions)));
+        Sweeper::new(
+            storage,
+            rx,
+            SweepOptions {
+                interval: Duration::from_secs(60),
+                lookback: chrono::Duration::days(7),
+                max_subjects: 100,
+            },
+        )
+    }
+
+    async fn record_request(storage: &MockStorage, subject_id: Uuid, dest: &str, usd: i64) {
+        let request = serde_json::json!({
+            "subject": {"user_id": "U1", "account_id": "A1", "addresses": ["0xabc"],
+                        "geo_iso": "US", "kyc_level": "L2"},
+            "tx": {"type": "withdraw", "asset": "USDC", "amount": usd.to_string(),
+                   "usd_value": usd, "dest_address": dest}
+        });
+        storage
+            .record_outcome(
+                &TransactionRecord {
+                    subject_id,
+                    tx_type: "Outbound".to_string(),
+                    asset: "USDC".to_string(),
+                    amount: Decimal::new(usd, 0),
+                    usd_value: Decimal::new(usd, 0),
+                    dest_address: Some(dest.to_string()),
+                    counterparty_geo: None,
+                    account_id: None,
+                },
+                &DecisionRecord {
+                    subject_id: Some(subject_id),
+                    request_id: Some(Uuid::new_v4().to_string()),
+                    request,
+                    decision: Decision::Allow,
+                    decision_code: "OK".to_string(),
+                    policy_version: "sweep-1".to_string(),
+                    evidence: vec![],
+                    latency_ms: 1,
+                },
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sweep_flags_destination_sanctioned_later() {
+        let storage = Arc::new(MockStorage::new());
+        let subject_id = Uuid::new_v4();
+        record_request(&storage, subject_id, "0xbad", 10).await;
+
+        // Nothing to find before the address is listed; the non-sweep
+        // jurisdiction rule would have hit
+        let summary = sweeper(storage.clone(), &[]).sweep_once().await.unwrap();
+        assert_eq!(summary.swept, 1);
+        assert_eq!(summary.flagged, 0);
+
+        let queued = storage.pending_outbox(100).await.unwrap().len();
+        let sweeper = sweeper(storage.clone(), &["0xbad"]);
+        let summary = sweeper.sweep_once().await.unwrap();
+        assert_eq!(summary.flagged, 1);
+        assert_eq!(summary.cases_opened, 1);
+
+        let cases = storage
+            .list_cases(Some(CaseStatus::Open), 10)
+            .await
+            .unwrap();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].subject_id, subject_id);
+        assert_eq!(cases[0].note.as_deref(), Some("Scheduled sweep: R1_OFAC"));
+
+        let recorded = storage.get_recent_decisions(1).await.unwrap();
+        assert_eq!(recorded[0].record.decision, Decision::Review);
+        assert!(recorded[0]
+            .record
+            .request_id
+            .as_deref()
+            .unwrap()
+            .starts_with(SWEEP_REQUEST_PREFIX));
+        // Linked to the case it opened and queued for delivery
+        assert_eq!(cases[0].opened_by_decision, Some(recorded[0].id));
+        assert_eq!(cases[0].decision_ids, vec![recorded[0].id]);
+        let outbox = storage.pending_outbox(100).await.unwrap();
+        assert_eq!(outbox.len(), queued + 1);
+        assert_eq!(outbox[queued].kind, "decision");
+
+        // Flagged once per request
+        let summary = sweeper.sweep_once().await.unwrap();
+        assert_eq!(summary.swept, 0);
+
+        // A new request makes the subject eligible again
+        record_request(&storage, subject_id, "0xbad", 10).await;
+        let summary = sweeper.sweep_once().await.unwrap();
+        assert_eq!(summary.flagged, 1);
+        assert_eq!(summary.cases_opened, 0);
+        // The new request and its review both join the open case, the
+        // review last
+        let review = storage.get_recent_decisions(1).await.unwrap();
+        assert_eq!(review[0].record.decision, Decision::Review);
+        let case = storage.get_case(cases[0].id).await.unwrap().unwrap();
+        assert_eq!(case.decision_ids.len(), 3);
+        assert_eq!(case.decision_ids[0], recorded[0].id);
+        assert_eq!(case.decision_ids.last(), Some(&review[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_sweep_checks_accumulated_volume() {
+        let storage = Arc::new(MockStorage::new());
+        let under = Uuid::new_v4();
+        let over = Uuid::new_v4();
+        record_request(&storage, under, "0x1", 1000).await;
+        storage.set_rolling_volume(under, Decimal::new(1000, 0));
+        record_request(&storage, over, "0x1", 600).await;
+        record_request(&storage, over, "0x2", 600).await;
+        storage.set_rolling_volume(over, Decimal::new(1200, 0));
+
+        let summary = sweeper(storage.clone(), &[]).sweep_once().await.unwrap();
+        assert_eq!(summary.swept, 2);
+        assert_eq!(summary.flagged, 1);
+
+        let cases = storage.list_cases(None, 10).await.unwrap();
+        assert_eq!(cases.len(), 1);
+        assert_eq!(cases[0].subject_id, over);
+    }
+
+    #[tokio::test]
+    async fn test_sweep_without_sweep_rules_is_noop() {
+        let storage = Arc::new(MockStorage::new());
+        record_request(&storage, Uuid::new_v4(), "0xbad", 10).await;
+
+        let (_tx, rx) = watch::channel(Arc::new(RuleSet::empty()));
+        let sweeper = Sweeper::new(
+            storage,
+            rx,
+            SweepOptions {
+                interval: Duration::from_secs(60),
+                lookback: chrono::Duration::days(7),
+                max_subjects: 100,
+            },
+        );
+        assert_eq!(sweeper.sweep_once().await.unwrap(), SweepSummary::default());
+    }
+}